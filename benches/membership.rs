@@ -0,0 +1,73 @@
+//! Membership queries over a real dictionary, to measure the effect of the node layout (breadth-first ids, see
+//! `Dawg::visit_nodes`) on query latency.
+//!
+//! The dictionary is the file named by `DAWG_BENCH_WORDS` (one word per line, `/usr/share/dict/words` for instance), or
+//! the word list of the test fixtures. Every word is queried once along with a near miss (its last letter changed), in a
+//! shuffled order, against the compiled layout and the finished dawg. Run with `cargo bench --bench membership`
+
+use std::{hint::black_box, time::Instant};
+
+use dawging::{dawg::{compiled::CompiledDawg, lexicon::{Lexicon, MatchMode}}, UnsyncDawg};
+
+const ROUNDS: usize = 7;
+
+/// xorshift, to shuffle the queries the same way on every run
+fn next(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+fn words() -> Vec<String> {
+    let path = std::env::var("DAWG_BENCH_WORDS").unwrap_or_else(|_| concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/words.txt").to_owned());
+    let text = std::fs::read_to_string(&path).unwrap_or_else(|err| panic!("can't read {path}: {err}"));
+    let mut words = text.lines().map(str::trim).filter(|word| !word.is_empty()).map(String::from).collect::<Vec<_>>();
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Every word and a near miss of it, shuffled
+fn queries(words: &[String]) -> Vec<String> {
+    let mut queries = words.to_vec();
+    queries.extend(words.iter().map(|word| {
+        let mut letters = word.chars().collect::<Vec<_>>();
+        let last = letters.pop().unwrap_or('a');
+        letters.push(if last == 'z' { 'a' } else { 'z' });
+        letters.into_iter().collect()
+    }));
+
+    let mut state = 0x9E37_79B9_7F4A_7C15;
+    for index in (1..queries.len()).rev() {
+        queries.swap(index, (next(&mut state) % (index as u64 + 1)) as usize);
+    }
+    queries
+}
+
+/// Best time per query over `ROUNDS` runs, in nanoseconds, and the number of hits
+fn measure(queries: &[String], contains: impl Fn(&str) -> bool) -> (f64, usize) {
+    let mut best = f64::MAX;
+    let mut hits = 0;
+    for _ in 0..ROUNDS {
+        let start = Instant::now();
+        hits = queries.iter().filter(|query| contains(black_box(query))).count();
+        best = best.min(start.elapsed().as_nanos() as f64 / queries.len() as f64);
+    }
+    (best, hits)
+}
+
+fn main() {
+    let words = words();
+    let mut dawg = UnsyncDawg::new();
+    dawg.add_all(&words).unwrap();
+    dawg.finish();
+    let compiled: CompiledDawg = dawg.compile();
+    let queries = queries(&words);
+
+    println!("{} words, {} nodes, {} queries, best of {ROUNDS} runs", words.len(), compiled.node_count(), queries.len());
+    let (time, hits) = measure(&queries, |query| Lexicon::contains(&compiled, query, MatchMode::Exact));
+    println!("  CompiledDawg: {time:.1} ns/query ({hits} hits)");
+    let (time, hits) = measure(&queries, |query| dawg.contains(query));
+    println!("  UnsyncDawg:   {time:.1} ns/query ({hits} hits)");
+}
@@ -0,0 +1,64 @@
+//! Builds a dawg from an arbitrary word list and checks every query against the naive model.
+//!
+//! The input is read as (lossy) UTF-8 lines: a line starting with `?` is a query, any other line is a word
+//! (so empty words, duplicates and unsorted words are all reachable). Run with `cargo fuzz run build_query`
+#![no_main]
+
+use dawging::{dawg::lexicon::{FoldProfile, Lexicon, MatchMode}, naive::{self, check_equivalence, Query}, UnsyncDawg};
+use libfuzzer_sys::fuzz_target;
+
+const MODES: [MatchMode; 4] = [
+    MatchMode::Exact,
+    MatchMode::AsciiInsensitive,
+    MatchMode::UnicodeInsensitive(FoldProfile::Default),
+    MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+];
+
+fuzz_target!(|data: &[u8]| {
+    let text = String::from_utf8_lossy(data);
+    let (queries, words): (Vec<&str>, Vec<&str>) = text.split('\n').partition(|line| line.starts_with('?'));
+
+    let mut dawg = UnsyncDawg::new();
+    let added = dawg.add_all(&words).unwrap();
+    let model = naive::Lexicon::new(words.iter().map(|word| word.to_string()));
+    assert_eq!(added, model.word_count());
+
+    let queries = queries.iter().map(|query| &query[1..]).chain(words.iter().copied()).collect::<Vec<_>>();
+    let queries = queries.iter().flat_map(|query| MODES.iter().flat_map(move |mode| [
+        Query::Contains(query.to_string(), *mode),
+        Query::ContainsPrefix(query.to_string(), *mode),
+        Query::Completions(query.to_string(), *mode),
+    ])).collect::<Vec<_>>();
+
+    // queries must agree while building as well as once finished
+    check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("{divergence}"));
+    dawg.assert_invariants().unwrap();
+    dawg.finish();
+    check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("{divergence}"));
+    dawg.assert_minimal().unwrap();
+
+    for (index, word) in model.words().enumerate() {
+        assert_eq!(dawg.rank(word), Some(index));
+        assert_eq!(dawg.select(index).as_deref(), Some(word));
+    }
+    assert!(dawg.words().rev().eq(model.words().collect::<Vec<_>>().into_iter().rev()));
+
+    let bytes = dawg.to_bytes().unwrap();
+    let loaded = UnsyncDawg::<char>::load(&bytes[..]).unwrap();
+    assert_eq!(loaded.to_bytes().unwrap(), bytes);
+    check_equivalence(&loaded, &model, &queries).unwrap_or_else(|divergence| panic!("{divergence}"));
+    loaded.assert_minimal().unwrap();
+
+    // edits copy paths instead of minimizing again, so only the structure is checked
+    let mut edited = dawg.fork();
+    for query in &queries[..queries.len().min(8)] {
+        if let Query::Contains(word, _) = query {
+            edited.insert(word).unwrap();
+            edited.assert_invariants().unwrap();
+            edited.remove(word).unwrap();
+            edited.assert_invariants().unwrap();
+        }
+    }
+    edited.retain(|word| word.len() % 2 == 0);
+    edited.assert_minimal().unwrap();
+});
@@ -0,0 +1,149 @@
+//! Building dictionaries ahead of time, from a build script, so that the program never parses a word list at runtime.
+//!
+//! In `build.rs` (with dawging in `[build-dependencies]`):
+//!
+//! ```ignore
+//! use std::{env, path::Path};
+//! use dawging::{codegen::compile_word_list, dawg::options::DawgOptions};
+//!
+//! fn main() {
+//!     println!("cargo:rerun-if-changed=data/words.txt");
+//!     let output = Path::new(&env::var("OUT_DIR").unwrap()).join("words.dawg");
+//!     let report = compile_word_list(Path::new("data/words.txt"), &output, &DawgOptions::new()).unwrap();
+//!     if report.skipped > 0 {
+//!         println!("cargo:warning=skipped {} lines of data/words.txt", report.skipped);
+//!     }
+//! }
+//! ```
+//!
+//! Then in the crate, the saved dawg is baked into the executable:
+//!
+//! ```ignore
+//! static WORDS: &[u8] = include_bytes!(concat!(env!("OUT_DIR"), "/words.dawg"));
+//!
+//! let dawg = UnsyncDawg::load(WORDS)?;
+//! ```
+//!
+//! With `Artifact::Compiled` the file holds a CompiledDawg instead, which `EmbeddedDawg` queries in place without loading it.
+//!
+//! The same word list and options always give the same bytes, and an output file already holding them is left untouched,
+//! so rebuilds don't change the artifact nor its modification time
+
+use std::{fs::{self, File}, io::BufReader, path::Path};
+
+#[cfg(feature = "unsync")]
+use crate::UnsyncDawg as BuildDawg;
+#[cfg(all(feature = "sync", not(feature = "unsync")))]
+use crate::SyncDawg as BuildDawg;
+use crate::{dawg::{external::ExternalSortOptions, lexicon::Lexicon, options::DawgOptions, wordlist::Utf8Policy}, error::DawgError};
+
+/// What `compile_word_list_with` writes
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Artifact {
+    /// a Dawg, as written by `Dawg::save`
+    #[default]
+    Dawg,
+    /// a CompiledDawg, as written by `CompiledDawg::save`
+    Compiled,
+}
+
+/// Settings of `compile_word_list_with`
+#[derive(Debug, Clone, Default)]
+pub struct CodegenOptions {
+    options: DawgOptions,
+    sort: bool,
+    utf8: Utf8Policy,
+    artifact: Artifact,
+}
+
+impl CodegenOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options of the dawg being built
+    pub fn dawg_options(mut self, options: DawgOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Whether the word list is sorted (with an external merge sort, see `Dawg::from_unsorted_reader`) before being added.
+    /// Otherwise the words must already be in the sort order of the options
+    pub fn sort(mut self, sort: bool) -> Self {
+        self.sort = sort;
+        self
+    }
+
+    /// What happens to lines that aren't valid utf-8, when the words aren't sorted here
+    pub fn utf8_policy(mut self, policy: Utf8Policy) -> Self {
+        self.utf8 = policy;
+        self
+    }
+
+    pub fn artifact(mut self, artifact: Artifact) -> Self {
+        self.artifact = artifact;
+        self
+    }
+}
+
+/// What `compile_word_list` read and wrote, for the build script to report
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct CompileReport {
+    /// lines of the word list, 0 when it was sorted here (the sort doesn't count them)
+    pub lines: usize,
+    /// distinct words in the dawg
+    pub words: usize,
+    /// lines that weren't added as words (see `LoadReport::skipped`), 0 when the word list was sorted here
+    pub skipped: usize,
+    pub nodes: usize,
+    pub edges: usize,
+    /// size in bytes of the output
+    pub bytes: usize,
+    /// value is false if the output already held the same bytes and was left as it was
+    pub written: bool,
+}
+
+/// Builds a dawg configured by `options` from the sorted word list at `input` (one word per line, see `Dawg::read_words`)
+/// and saves it at `output`
+pub fn compile_word_list(input: &Path, output: &Path, options: &DawgOptions) -> Result<CompileReport, DawgError> {
+    compile_word_list_with(input, output, &CodegenOptions::new().dawg_options(options.clone()))
+}
+
+/// Same as `compile_word_list`, configured by `options`
+pub fn compile_word_list_with(input: &Path, output: &Path, options: &CodegenOptions) -> Result<CompileReport, DawgError> {
+    let mut report = CompileReport::default();
+
+    let dawg = match options.sort {
+        true => {
+            let sort = ExternalSortOptions::new().dawg_options(options.options.clone());
+            BuildDawg::from_unsorted_file(input, &sort)?
+        }
+        false => {
+            let (dawg, load) = BuildDawg::from_reader(BufReader::new(File::open(input)?), options.options.clone(), options.utf8)?;
+            report.lines = load.lines;
+            report.skipped = load.skipped();
+            dawg
+        }
+    };
+
+    let bytes = match options.artifact {
+        Artifact::Dawg => {
+            let stats = dawg.compression_stats();
+            (report.nodes, report.edges) = (stats.dawg_nodes, stats.dawg_edges);
+            dawg.to_bytes()?
+        }
+        Artifact::Compiled => {
+            let compiled = dawg.compile();
+            (report.nodes, report.edges) = (compiled.node_count(), compiled.edge_count());
+            compiled.to_bytes()
+        }
+    };
+
+    report.words = dawg.word_count();
+    report.bytes = bytes.len();
+    report.written = fs::read(output).map_or(true, |existing| existing != bytes);
+    if report.written {
+        fs::write(output, &bytes)?;
+    }
+    Ok(report)
+}
@@ -1,3 +1,71 @@
+pub mod anagram;
+pub mod bidawg;
+pub mod budget;
+pub mod cache;
+pub mod checkpoint;
 pub mod common;
+pub mod compiled;
+pub mod complete;
+#[cfg(all(feature = "sync", feature = "unsync"))]
+pub mod convert;
+pub mod crosscheck;
+pub mod depth;
+pub mod diff;
+pub mod edit;
+pub mod embedded;
+pub mod entropy;
+pub mod external;
+pub mod filter;
+pub mod filtered;
+pub mod flags;
+pub mod forest;
+pub mod frontcoded;
+pub mod fuzzy;
+pub mod gaddag;
+pub mod grid;
+pub mod hangman;
+pub mod hooks;
+pub mod infix;
+pub mod integrity;
+pub mod invariants;
+pub mod iter;
+pub mod lexicon;
+pub(crate) mod lru;
+pub mod map;
+pub mod metadata;
+pub mod migrate;
+pub mod multi;
+pub mod noalloc;
+pub mod options;
+pub mod page;
+pub mod paged;
+#[cfg(feature = "rayon")]
+pub mod par_words;
+#[cfg(feature = "rayon")]
+pub mod parallel;
+pub mod phrase;
+pub mod query;
+pub mod rank;
+pub mod register;
+pub mod sample;
+pub mod searcher;
+pub mod serialize;
+pub mod setops;
+pub mod spell;
+pub mod stats;
+pub mod streaming;
+pub mod subset;
+pub mod suffix;
+#[cfg(feature = "sync")]
 pub mod sync;
-pub mod unsync;
\ No newline at end of file
+pub mod table;
+pub(crate) mod trace;
+pub mod transform;
+pub mod tsv;
+pub(crate) mod unicode;
+#[cfg(feature = "unsync")]
+pub mod unsync;
+pub mod view;
+pub mod visit;
+pub mod wordle;
+pub mod wordlist;
@@ -0,0 +1,46 @@
+use std::collections::HashMap;
+
+use crate::dawg::common::{Dawg, Wrapper};
+
+/// The letters of `word` in sorted order, shared by all of its anagrams
+fn anagram_key(word: &str) -> Box<[char]> {
+    let mut letters = word.chars().collect::<Vec<_>>();
+    letters.sort_unstable();
+    letters.into_boxed_slice()
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the groups of at least `min_size` words made of the same letters, each group in sorted order and the groups
+    /// in the order of their first word. Words are compared letter by letter as the dawg stores them, so once folded by its
+    /// options.
+    ///
+    /// A first pass over the words counts the members of every group, keeping a key per group rather than the words, and a
+    /// second pass collects the words of the groups large enough
+    pub fn anagram_groups(&self, min_size: usize) -> impl Iterator<Item = Vec<String>> {
+        let mut sizes = HashMap::<Box<[char]>, usize>::new();
+        for word in self.words() {
+            *sizes.entry(anagram_key(&word)).or_default() += 1;
+        }
+        sizes.retain(|_, size| *size >= min_size.max(1));
+
+        // the groups are numbered as their first word comes up
+        let mut slots = HashMap::<Box<[char]>, usize>::with_capacity(sizes.len());
+        let mut groups = Vec::<Vec<String>>::with_capacity(sizes.len());
+        for word in self.words() {
+            let key = anagram_key(&word);
+            let Some(size) = sizes.remove(&key) else {
+                if let Some(slot) = slots.get(&key) {
+                    groups[*slot].push(word);
+                }
+                continue;
+            };
+
+            slots.insert(key, groups.len());
+            let mut group = Vec::with_capacity(size);
+            group.push(word);
+            groups.push(group);
+        }
+
+        groups.into_iter()
+    }
+}
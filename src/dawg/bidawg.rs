@@ -0,0 +1,186 @@
+//! A dictionary along with the dawg of its reversed words, for suffix queries.
+//!
+//! A saved BiDawg is made of the magic bytes `DAWB`, the version of its layout (u16), a flag (u8, 1 if the reversed dawg is
+//! saved as well), then the length (u64) and the bytes of the forward dawg as written by `Dawg::save`, followed by those of
+//! the reversed dawg if the flag is set. Numbers are little-endian
+
+use std::{io::{Read, Write}, sync::OnceLock};
+
+use crate::{dawg::{common::{Dawg, NodeOps, Wrapper}, hooks::Hooks, options::DawgOptions, serialize::{write_u64, Cursor}}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWB";
+const VERSION: u16 = 1;
+
+/// The words of a dictionary in two dawgs, the second one holding them reversed: suffixes of words are prefixes of the
+/// reversed ones, so `words_with_suffix` and `front_hooks` walk the reversed dawg the way `words_with_prefix` and
+/// `back_hooks` walk the forward one.
+///
+/// Both dawgs are built from the same words, and `insert` and `remove` change both. A BiDawg loaded from a file saved without
+/// the reversed dawg rebuilds it from the forward one the first time a query needs it
+pub struct BiDawg<T: Wrapper> {
+    forward: Dawg<T>,
+    reverse: OnceLock<Dawg<T>>,
+}
+
+impl<T> BiDawg<T> where T: Wrapper {
+    /// Builds both dawgs of `words`, in any order, folded by the options
+    pub fn from_words<I, S>(words: I, options: DawgOptions) -> Result<Self, DawgError> where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let words = words.into_iter().map(|word| options.fold(word.as_ref())).collect::<Vec<_>>();
+
+        let mut forward = Dawg::with_options(options.clone())?;
+        let mut reverse = Dawg::with_options(options)?;
+        forward.add_all(&words)?;
+        reverse.add_all(words.iter().map(|word| reversed(word)))?;
+        forward.finish();
+        reverse.finish();
+        Ok(Self { forward, reverse: OnceLock::from(reverse) })
+    }
+
+    /// the dawg of the words
+    pub fn forward(&self) -> &Dawg<T> {
+        &self.forward
+    }
+
+    /// the dawg of the reversed words, built from the forward one if it wasn't loaded along with it
+    pub fn reverse(&self) -> &Dawg<T> {
+        self.reverse.get_or_init(|| rebuild(&self.forward))
+    }
+
+    fn reverse_mut(&mut self) -> &mut Dawg<T> {
+        self.reverse();
+        self.reverse.get_mut().unwrap()
+    }
+
+    /// value is true if `word` is in the dictionary
+    pub fn contains(&self, word: &str) -> bool {
+        self.forward.contains(word)
+    }
+
+    /// Returns every word starting with `prefix` (matched exactly) in sorted order
+    pub fn words_with_prefix(&self, prefix: &str) -> Vec<String> {
+        self.forward.words_with_prefix(prefix).collect()
+    }
+
+    /// Returns every word ending with `suffix` (matched exactly) in sorted order, read from the reversed dawg
+    pub fn words_with_suffix(&self, suffix: &str) -> Vec<String> {
+        let mut words = self.reverse().words_with_prefix(&reversed(suffix)).map(|word| reversed(&word)).collect::<Vec<_>>();
+        words.sort_unstable();
+        words
+    }
+
+    /// Returns every word starting with `prefix` and ending with `suffix` (matched exactly, they may overlap) in sorted order.
+    /// The words are listed from whichever dawg has the fewest of them below the affix, then filtered on the other one
+    pub fn words_with_affixes(&self, prefix: &str, suffix: &str) -> Vec<String> {
+        let below = |dawg: &Dawg<T>, affix: &str| dawg.find_sequence(&affix.chars().collect::<Vec<_>>()).map_or(0, |node| node.reachable());
+        if below(&self.forward, prefix) <= below(self.reverse(), &reversed(suffix)) {
+            return self.forward.words_with_prefix(prefix).filter(|word| word.ends_with(suffix)).collect();
+        }
+
+        let mut words = self.words_with_suffix(suffix);
+        words.retain(|word| word.starts_with(prefix));
+        words
+    }
+
+    /// Returns the letters that make a word when put before `word` (folded by the options): the back hooks of the reversed
+    /// word in the reversed dawg
+    pub fn front_hooks(&self, word: &str) -> Vec<char> {
+        self.reverse().back_hooks(&reversed(&self.forward.options.fold(word)))
+    }
+
+    /// Returns the letters that make a word when put after `word` (folded by the options)
+    pub fn back_hooks(&self, word: &str) -> Vec<char> {
+        self.forward.back_hooks(word)
+    }
+
+    /// Returns both the front and the back hooks of `word`
+    pub fn hooks(&self, word: &str) -> Hooks {
+        Hooks { front: self.front_hooks(word), back: self.back_hooks(word) }
+    }
+
+    /// Adds `word` (folded by the options) to both dawgs, see `Dawg::insert`. Value is false if it was already there
+    pub fn insert(&mut self, word: &str) -> Result<bool, DawgError> {
+        let word = self.forward.options.fold(word);
+        let inserted = self.forward.insert(&word)?;
+        if inserted {
+            self.reverse_mut().insert(&reversed(&word))?;
+        }
+        Ok(inserted)
+    }
+
+    /// Removes `word` (folded by the options) from both dawgs, see `Dawg::remove`. Value is false if it wasn't there
+    pub fn remove(&mut self, word: &str) -> Result<bool, DawgError> {
+        let word = self.forward.options.fold(word);
+        let removed = self.forward.remove(&word)?;
+        if removed {
+            self.reverse_mut().remove(&reversed(&word))?;
+        }
+        Ok(removed)
+    }
+
+    /// Encodes both dawgs, or only the forward one unless `with_reverse` (the reversed one is then rebuilt once loaded,
+    /// when a query first needs it), see the `bidawg` module
+    pub fn to_bytes(&self, with_reverse: bool) -> Vec<u8> {
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.push(with_reverse as u8);
+
+        let reverse = with_reverse.then(|| self.reverse());
+        for dawg in std::iter::once(&self.forward).chain(reverse) {
+            let bytes = dawg.to_bytes().expect("both dawgs of a BiDawg are finished");
+            write_u64(&mut out, bytes.len() as u64);
+            out.extend_from_slice(&bytes);
+        }
+        out
+    }
+
+    /// Writes the BiDawg to `writer`, see `to_bytes`
+    pub fn save<W: Write>(&self, mut writer: W, with_reverse: bool) -> Result<(), DawgError> {
+        writer.write_all(&self.to_bytes(with_reverse))?;
+        Ok(writer.flush()?)
+    }
+
+    /// Rebuilds a BiDawg from the bytes written by `save`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(DawgError::InvalidFormat(String::from("missing BiDawg magic bytes")));
+        }
+
+        let version = reader.u16()?;
+        if version > VERSION {
+            return Err(DawgError::NewerVersion { version, supported: VERSION });
+        }
+
+        let with_reverse = reader.u8()? == 1;
+        let mut dawg = || {
+            let len = reader.u64()? as usize;
+            Dawg::from_bytes(reader.bytes(len)?)
+        };
+
+        let forward = dawg()?;
+        let reverse = match with_reverse {
+            true => OnceLock::from(dawg()?),
+            false => OnceLock::new(),
+        };
+        Ok(Self { forward, reverse })
+    }
+
+    /// Reads a BiDawg written by `save` from `reader`
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+fn reversed(word: &str) -> String {
+    word.chars().rev().collect()
+}
+
+/// Builds the dawg of the reversed words of `forward`, with the same options
+fn rebuild<T: Wrapper>(forward: &Dawg<T>) -> Dawg<T> {
+    let mut reverse = Dawg::with_options(forward.options.clone()).expect("the options of the forward dawg were validated");
+    reverse.add_all(forward.words().map(|word| reversed(&word))).expect("the reversed words fit the options of their dawg");
+    reverse.finish();
+    reverse
+}
@@ -0,0 +1,132 @@
+use std::time::Instant;
+
+use crate::dawg::trace::Visits;
+
+/// Number of nodes visited between two looks at the clock, reading it being far slower than visiting a node
+const DEADLINE_CHECK_INTERVAL: usize = 256;
+
+/// Bounds on the work of a search: the nodes it visits, the results it returns and the time it runs until.
+/// The default budget has no bound, the search runs to the end
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct SearchBudget {
+    max_nodes: Option<usize>,
+    max_results: Option<usize>,
+    deadline: Option<Instant>,
+}
+
+impl SearchBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Stops the search once it visited `max_nodes` nodes
+    pub fn max_nodes(mut self, max_nodes: usize) -> Self {
+        self.max_nodes = Some(max_nodes);
+        self
+    }
+
+    /// Stops the search once it found `max_results` results
+    pub fn max_results(mut self, max_results: usize) -> Self {
+        self.max_results = Some(max_results);
+        self
+    }
+
+    /// Stops the search once `deadline` is past, looking at the clock every few hundred nodes
+    pub fn deadline(mut self, deadline: Instant) -> Self {
+        self.deadline = Some(deadline);
+        self
+    }
+
+    pub fn get_max_nodes(&self) -> Option<usize> {
+        self.max_nodes
+    }
+
+    pub fn get_max_results(&self) -> Option<usize> {
+        self.max_results
+    }
+
+    pub fn get_deadline(&self) -> Option<Instant> {
+        self.deadline
+    }
+}
+
+/// Bound of a `SearchBudget` that stopped a search
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Truncation {
+    Nodes,
+    /// the search had more results than it could return
+    Results,
+    Deadline,
+}
+
+/// Results of a search run under a `SearchBudget`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budgeted<T> {
+    /// results found before the search stopped, in the order the search without a budget returns them
+    pub results: Vec<T>,
+    /// the bound that stopped the search before it was over, `None` if it ran to the end
+    pub truncated: Option<Truncation>,
+}
+
+impl<T> Budgeted<T> {
+    /// value is true if the search stopped before it was over, so some results may be missing
+    pub fn is_truncated(&self) -> bool {
+        self.truncated.is_some()
+    }
+}
+
+/// Work done so far by a search under a `SearchBudget`
+#[derive(Debug, Clone, Default)]
+pub(crate) struct BudgetMeter {
+    budget: SearchBudget,
+    nodes: usize,
+    results: usize,
+    truncated: Option<Truncation>,
+}
+
+impl BudgetMeter {
+    pub(crate) fn new(budget: SearchBudget) -> Self {
+        Self { budget, ..Self::default() }
+    }
+
+    /// Counts a node about to be visited, value is false if the search has to stop instead
+    pub(crate) fn visit(&mut self) -> bool {
+        if self.truncated.is_some() {
+            return false;
+        }
+        if self.budget.max_nodes.is_some_and(|max_nodes| self.nodes >= max_nodes) {
+            self.truncated = Some(Truncation::Nodes);
+            return false;
+        }
+        if self.nodes % DEADLINE_CHECK_INTERVAL == 0 && self.budget.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            self.truncated = Some(Truncation::Deadline);
+            return false;
+        }
+
+        self.nodes += 1;
+        true
+    }
+
+    /// Counts a result about to be returned, value is false if it is one too many
+    pub(crate) fn accept(&mut self) -> bool {
+        if self.truncated.is_some() {
+            return false;
+        }
+        if self.budget.max_results.is_some_and(|max_results| self.results >= max_results) {
+            self.truncated = Some(Truncation::Results);
+            return false;
+        }
+
+        self.results += 1;
+        true
+    }
+
+    /// Nodes visited so far, for the debug event of the search
+    pub(crate) fn visits(&self) -> Visits {
+        Visits::of(self.nodes)
+    }
+
+    pub(crate) fn finish<T>(self, results: Vec<T>) -> Budgeted<T> {
+        Budgeted { results, truncated: self.truncated }
+    }
+}
@@ -0,0 +1,166 @@
+use std::{borrow::Borrow, fmt::Debug, sync::Mutex};
+
+use crate::dawg::{common::{Dawg, Wrapper}, lexicon::{Lexicon, MatchMode}, lru::Lru};
+
+/// How the cache of a `CachedDawg` was used, see `CachedDawg::stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// queries answered from memory
+    pub hits: usize,
+    /// queries answered by the lexicon, their result then being kept
+    pub misses: usize,
+    /// results dropped from memory to make room for others
+    pub evictions: usize,
+    /// results currently held in memory
+    pub resident: usize,
+}
+
+/// A cached query, along with its arguments
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+enum Query {
+    Completions { prefix: String, mode: MatchMode, limit: Option<usize> },
+    CountCompletions { prefix: String, mode: MatchMode },
+    Suggest { word: String, mode: MatchMode, limit: usize, max_distance: u8 },
+}
+
+#[derive(Debug, Clone)]
+enum Answer {
+    Words(Vec<String>),
+    Count(usize),
+}
+
+/// Results held in memory by their query
+#[derive(Debug, Default)]
+struct ResultCache {
+    results: Lru<Query, Answer>,
+    stats: CacheStats,
+}
+
+/// Lexicon answering the completions of the prefixes queried again and again from memory: the results of `completions`,
+/// `completions_limit` and `count_completions`, and of `suggest` over a dawg, are kept for their arguments, at most
+/// `capacity` of them, the least recently used one being dropped to make room for another. `contains` and
+/// `contains_prefix` go straight to the lexicon.
+///
+/// The cache owns the lexicon and only lends it out immutably, so the lexicon can't change under the cached results: `replace`
+/// swaps it for another (a rebuilt dawg, ...) and drops every result. A lexicon changing behind a shared reference (layers
+/// added to a MultiDawg borrowed elsewhere, ...) calls for `invalidate_all`.
+///
+/// The cache is behind a lock, so a CachedDawg of a lexicon that can be shared between threads can be shared as well
+pub struct CachedDawg<Q: Lexicon> {
+    lexicon: Q,
+    capacity: usize,
+    cache: Mutex<ResultCache>,
+}
+
+impl<Q> Debug for CachedDawg<Q> where Q: Lexicon {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CachedDawg").field("capacity", &self.capacity).field("stats", &self.stats()).finish()
+    }
+}
+
+impl<Q> CachedDawg<Q> where Q: Lexicon {
+    /// Caches the results of at most `capacity` (at least 1) queries on `lexicon`
+    pub fn new(lexicon: Q, capacity: usize) -> Self {
+        Self { lexicon, capacity: capacity.max(1), cache: Mutex::new(ResultCache::default()) }
+    }
+
+    pub fn get_ref(&self) -> &Q {
+        &self.lexicon
+    }
+
+    pub fn into_inner(self) -> Q {
+        self.lexicon
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        self.cache.lock().unwrap().stats
+    }
+
+    /// Swaps the lexicon for `lexicon`, dropping every cached result. Returns the previous lexicon
+    pub fn replace(&mut self, lexicon: Q) -> Q {
+        self.invalidate_all();
+        std::mem::replace(&mut self.lexicon, lexicon)
+    }
+
+    /// Drops every cached result, the counters of `stats` are kept
+    pub fn invalidate_all(&self) {
+        let mut cache = self.cache.lock().unwrap();
+        cache.results.clear();
+        cache.stats.resident = 0;
+    }
+
+    /// Returns up to `limit` words within `max_distance` edits of `word`, closest first, see `Dawg::suggest`
+    pub fn suggest<T>(&self, word: &str, mode: MatchMode, limit: usize, max_distance: u8) -> Vec<String> where Q: Borrow<Dawg<T>>, T: Wrapper {
+        let query = Query::Suggest { word: word.to_owned(), mode, limit, max_distance };
+        match self.cached(query, || Answer::Words(self.lexicon.borrow().suggest(word, mode, limit, max_distance))) {
+            Answer::Words(words) => words,
+            Answer::Count(_) => unreachable!(),
+        }
+    }
+
+    /// Returns the result of `query` from memory, or computes it with `answer` and keeps it. The lock isn't held while
+    /// `answer` runs, so two threads missing the same query both compute it
+    fn cached(&self, query: Query, answer: impl FnOnce() -> Answer) -> Answer {
+        {
+            let mut cache = self.cache.lock().unwrap();
+            if let Some(answer) = cache.results.get(&query).cloned() {
+                cache.stats.hits += 1;
+                return answer;
+            }
+            cache.stats.misses += 1;
+        }
+
+        let answer = answer();
+        let mut cache = self.cache.lock().unwrap();
+        cache.results.remove(&query);
+        while cache.results.len() >= self.capacity && cache.results.pop_oldest().is_some() {
+            cache.stats.evictions += 1;
+        }
+
+        cache.results.insert(query, answer.clone());
+        cache.stats.resident = cache.results.len();
+        answer
+    }
+}
+
+impl<Q> Lexicon for CachedDawg<Q> where Q: Lexicon {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        self.lexicon.contains(word, mode)
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        self.lexicon.contains_prefix(prefix, mode)
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        let query = Query::Completions { prefix: prefix.to_owned(), mode, limit: None };
+        match self.cached(query, || Answer::Words(self.lexicon.completions(prefix, mode))) {
+            Answer::Words(words) => words,
+            Answer::Count(_) => unreachable!(),
+        }
+    }
+
+    fn word_count(&self) -> usize {
+        self.lexicon.word_count()
+    }
+
+    fn completions_limit(&self, prefix: &str, mode: MatchMode, limit: usize) -> Vec<String> {
+        let query = Query::Completions { prefix: prefix.to_owned(), mode, limit: Some(limit) };
+        match self.cached(query, || Answer::Words(self.lexicon.completions_limit(prefix, mode, limit))) {
+            Answer::Words(words) => words,
+            Answer::Count(_) => unreachable!(),
+        }
+    }
+
+    fn count_completions(&self, prefix: &str, mode: MatchMode) -> usize {
+        let query = Query::CountCompletions { prefix: prefix.to_owned(), mode };
+        match self.cached(query, || Answer::Count(self.lexicon.count_completions(prefix, mode))) {
+            Answer::Count(count) => count,
+            Answer::Words(_) => unreachable!(),
+        }
+    }
+}
@@ -0,0 +1,93 @@
+use std::collections::BTreeMap;
+
+use crate::{dawg::{common::{Dawg, Label, NodeRef, TriDawg, Wrapper}, options::SortDirection}, error::DawgError};
+
+/// Identifies a checkpoint taken with `Dawg::checkpoint`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct CheckpointId {
+    index: usize,
+    serial: usize,
+}
+
+/// State of a dawg being built, enough to bring it back to the moment the checkpoint was taken.
+/// Nodes that are minimized never change again, so only the root and the nodes waiting to be minimized are saved.
+/// A finished dawg copies the nodes it edits (see `Dawg::insert_sequence`), so its old root still holds its words
+#[derive(Debug)]
+pub(crate) struct Checkpoint<N: NodeRef> {
+    serial: usize,
+    root: N,
+    edited: bool,
+    previous_word: Vec<N::Label>,
+    /// number of staged words when the checkpoint was taken
+    staged: usize,
+    direction: Option<SortDirection>,
+    unchecked_nodes: Vec<TriDawg<N>>,
+    /// (node, terminal, edges) of the root and of every node waiting to be minimized
+    nodes: Vec<(N, bool, BTreeMap<N::Label, N>)>,
+    /// number of signatures in the register log when the checkpoint was taken
+    registered: usize,
+    trie_nodes: usize,
+    words_added: usize,
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Remembers the current state of the dawg, so the words added (or, once it is finished, inserted and removed) from now
+    /// on can be taken back with `rollback`
+    pub fn checkpoint(&mut self) -> CheckpointId {
+        let nodes = std::iter::once(&self.root).chain(self.unchecked_nodes.iter().map(|tri| &tri.child))
+            .map(|node| node.read(|inner| (node.clone(), inner.terminal, inner.edges.clone())))
+            .collect();
+
+        self.checkpoint_serial += 1;
+        self.checkpoints.push(Checkpoint {
+            serial: self.checkpoint_serial,
+            root: self.root.clone(),
+            edited: self.edited,
+            previous_word: self.previous_word.clone(),
+            staged: self.staged.len(),
+            direction: self.direction,
+            unchecked_nodes: self.unchecked_nodes.clone(),
+            nodes,
+            registered: self.register_log.len(),
+            trie_nodes: self.trie_nodes,
+            words_added: self.words_added,
+        });
+
+        CheckpointId { index: self.checkpoints.len() - 1, serial: self.checkpoint_serial }
+    }
+
+    /// Brings the dawg back to the state it was in when `id` was taken, forgetting every word added since.
+    /// Checkpoints taken after `id` can no longer be rolled back to, while `id` itself remains valid.
+    /// `finish` drops every checkpoint, so this fails for a checkpoint taken before the last `finish`
+    pub fn rollback(&mut self, id: CheckpointId) -> Result<(), DawgError> {
+        if self.checkpoints.get(id.index).is_none_or(|checkpoint| checkpoint.serial != id.serial) {
+            return Err(DawgError::InvalidCheckpoint);
+        }
+
+        self.checkpoints.truncate(id.index + 1);
+        let checkpoint = &self.checkpoints[id.index];
+
+        for signature in self.register_log.drain(checkpoint.registered..) {
+            self.minimized_nodes.remove(&signature);
+        }
+
+        for (node, terminal, edges) in &checkpoint.nodes {
+            node.write(|node| {
+                node.terminal = *terminal;
+                node.edges = edges.clone();
+            });
+        }
+
+        self.root = checkpoint.root.clone();
+        self.edited = checkpoint.edited;
+        self.infix_index = None;
+        self.reverse_index = None;
+        self.previous_word = checkpoint.previous_word.clone();
+        self.staged.truncate(checkpoint.staged);
+        self.direction = checkpoint.direction;
+        self.unchecked_nodes = checkpoint.unchecked_nodes.clone();
+        self.trie_nodes = checkpoint.trie_nodes;
+        self.words_added = checkpoint.words_added;
+        Ok(())
+    }
+}
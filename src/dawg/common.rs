@@ -1,45 +1,140 @@
-// use std::collections::HashMap;
-use std::{collections::HashMap, rc::Rc, cell::RefCell, fmt::Display, cmp, sync::{Arc, Mutex}};
+use std::{collections::{BTreeMap, HashMap}, fmt::Display, hash::Hash, sync::{atomic::{AtomicUsize, Ordering}, Arc}};
 
-#[derive(Debug, Clone)]
-pub enum NodeType {
-    Sync(Arc<Mutex<DawgNode>>),
-    Unsync(Rc<RefCell<DawgNode>>),
+use crate::{dawg::{checkpoint::Checkpoint, infix::InfixIndex, lexicon::MatchMode, options::{DawgOptions, SortDirection}, register::Register, suffix::ReverseIndex, trace}, error::DawgError, node::{completion_depths, count_reachable}};
+
+/// Anything that can label the edges of a Dawg: `char` for words, but also token ids, syllables, enum variants, ...
+pub trait Label: Ord + Hash + Clone {}
+
+impl<T> Label for T where T: Ord + Hash + Clone {}
+
+/// Shared pointer to a DawgNode, implemented by `Rc<RefCell<_>>` in the unsync flavour and `Arc<Mutex<_>>` in the sync one.
+/// Each flavour only ever points at its own kind of node, so a sync Dawg is `Send + Sync`
+pub trait NodeRef: Clone + Sized {
+    type Label: Label;
+
+    /// Moves `node` behind a new pointer
+    fn new(node: DawgNode<Self>) -> Self;
+
+    /// Runs `f` with shared access to the node behind this pointer
+    fn read<R>(&self, f: impl FnOnce(&DawgNode<Self>) -> R) -> R;
+
+    /// Runs `f` with exclusive access to the node behind this pointer
+    fn write<R>(&self, f: impl FnOnce(&mut DawgNode<Self>) -> R) -> R;
 }
 
-impl NodeType {
-    pub fn get_unsync(&self) -> Option<&Rc<RefCell<DawgNode>>> {
-        match self {
-            NodeType::Unsync(node) => {Some(node)}
-            NodeType::Sync(_) => {None}
+/// Helpers shared by every NodeRef, kept out of the public trait
+pub(crate) trait NodeOps: NodeRef {
+    /// id of the node behind this pointer
+    fn id(&self) -> usize {
+        self.read(|node| node.id)
+    }
+
+    /// value is true if the node behind this pointer is the end of a word
+    fn is_terminal(&self) -> bool {
+        self.read(|node| node.terminal)
+    }
+
+    fn set_terminal(&self, terminal: bool) {
+        self.write(|node| node.terminal = terminal)
+    }
+
+    /// number of words reachable from the node behind this pointer (valid once the dawg is finished)
+    fn count(&self) -> usize {
+        self.read(|node| node.count)
+    }
+
+    /// number of words below this node, recounted from the edges if `finish` hasn't computed it yet
+    fn reachable(&self) -> usize {
+        match self.count() {
+            0 => count_reachable(self, &mut HashMap::new()),
+            count => count,
+        }
+    }
+
+    fn num_reachable(&self) -> usize {
+        self.write(|node| node.num_reachable())
+    }
+
+    /// (shortest, longest) number of edges from the node behind this pointer down to the end of a word, `None` if no word
+    /// goes through it. Cached by `finish`, worked out from the nodes below otherwise
+    fn completion_depths(&self) -> Option<(usize, usize)> {
+        match self.read(|node| node.depths) {
+            Some(depths) => Some(depths),
+            None => completion_depths(self, &mut HashMap::new()),
         }
     }
 
-    pub fn get_sync(&self) -> Option<&Arc<Mutex<DawgNode>>> {
-        match self {
-            NodeType::Unsync(_) => {None}
-            NodeType::Sync(node) => {Some(node)}
+    fn num_depths(&self) -> Option<(usize, usize)> {
+        self.write(|node| node.num_depths())
+    }
+
+    /// Returns the child reached by following `letter` from this node
+    fn child(&self, letter: &Self::Label) -> Option<Self> {
+        self.read(|node| node.edges.get(letter).cloned())
+    }
+
+    /// Points the edge labelled `letter` at `child`, replacing whatever it pointed at before
+    fn insert_edge(&self, letter: Self::Label, child: Self) {
+        self.write(|node| node.edges.insert(letter, child));
+    }
+
+    fn remove_edge(&self, letter: &Self::Label) {
+        self.write(|node| node.edges.remove(letter));
+    }
+
+    /// Returns the (letter, child) pairs leaving this node in sorted letter order
+    fn children(&self) -> Vec<(Self::Label, Self)> {
+        self.read(|node| node.edges.iter().map(|(k, v)| (k.clone(), v.clone())).collect())
+    }
+
+    fn signature(&self) -> Signature<Self::Label> {
+        self.read(|node| node.signature())
+    }
+
+    /// Copies the node behind this pointer and everything below it into fresh nodes, copying each node once
+    /// (looked up by id in `visited`) so nodes shared by several parents stay shared in the copy
+    fn deep_clone(&self, visited: &mut HashMap<usize, Self>) -> Self {
+        if let Some(copy) = visited.get(&self.id()) {
+            return copy.clone();
         }
+
+        let (id, terminal, count, depths) = (self.id(), self.is_terminal(), self.count(), self.read(|node| node.depths));
+        let edges = self.children().into_iter().map(|(letter, child)| (letter, child.deep_clone(visited))).collect();
+        let copy = Self::new(DawgNode { id, terminal, edges, count, depths });
+
+        visited.insert(id, copy.clone());
+        copy
     }
 }
 
+impl<N> NodeOps for N where N: NodeRef {}
+
+
+/// Identifies a node by its right language: two nodes with the same signature can be merged during minimization
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub(crate) struct Signature<L> {
+    pub(crate) terminal: bool,
+    pub(crate) edges: Vec<(L, usize)>,
+}
 
 
 #[derive(Debug)]
-pub struct DawgNode {
+pub struct DawgNode<N: NodeRef> {
     /// id of the node
     pub(crate) id: usize,
     /// value is true if this node is the end of a word
-    pub terminal: bool,
+    pub(crate) terminal: bool,
     /// Returns all the other nodes (e.g, letters) extending from this node (letter)
-    pub edges: HashMap<String, NodeType>,
+    pub(crate) edges: BTreeMap<N::Label, N>,
     /// returns the number of words so far that have been formed from the root of the dawg up to this node
     pub(crate) count: usize,
+    /// shortest and longest distance to the end of a word below this node, once worked out (see `num_depths`)
+    pub(crate) depths: Option<(usize, usize)>,
 }
 
-impl DawgNode {
+impl<N> DawgNode<N> where N: NodeRef {
     pub fn new(id: usize) -> Self {
-        Self { id, terminal: false, edges: HashMap::new(), count: 0 }
+        Self { id, terminal: false, edges: BTreeMap::new(), count: 0, depths: None }
     }
 
     pub(crate) fn num_reachable(&mut self) -> usize {
@@ -53,28 +148,38 @@ impl DawgNode {
             count += 1;
         }
 
-        for (_, value) in &mut self.edges {
-            match value {
-                NodeType::Unsync(node) => {
-                    if let Some(pre_value) = Rc::get_mut(node) {
-                        count += pre_value.get_mut().num_reachable();
-                    }
-                }
-                NodeType::Sync(node) => {
-                    if let Some(pre_value) = Arc::get_mut(node) {
-                        // count += pre_value.lock().unwrap().num_reachable();
-                        count += pre_value.get_mut().unwrap().num_reachable();
-                    }
-                }
-            }
+        for value in self.edges.values() {
+            // children are shared between parents once minimized, so they have to be reached through the pointer rather than `get_mut`
+            count += value.num_reachable();
         }
 
         self.count = count;
-        return count;
+        count
+    }
+
+    pub(crate) fn num_depths(&mut self) -> Option<(usize, usize)> {
+        if self.depths.is_some() {
+            return self.depths;
+        }
+
+        let mut depths = self.terminal.then_some((0, 0));
+        for value in self.edges.values() {
+            let Some((min, max)) = value.num_depths() else { continue };
+            depths = Some(depths.map_or((min + 1, max + 1), |(shortest, longest)| (shortest.min(min + 1), longest.max(max + 1))));
+        }
+
+        self.depths = depths;
+        depths
+    }
+
+    /// the terminal flag and the (letter, child id) pairs of this node
+    pub(crate) fn signature(&self) -> Signature<N::Label> {
+        let edges = self.edges.iter().map(|(letter, child)| (letter.clone(), child.id())).collect();
+        Signature { terminal: self.terminal, edges }
     }
 }
 
-impl Display for DawgNode {
+impl<N> Display for DawgNode<N> where N: NodeRef, N::Label: Display {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         let mut arr = vec![];
         if self.terminal {
@@ -84,14 +189,8 @@ impl Display for DawgNode {
         }
 
         for (key, value) in &self.edges {
-
-            let id = match value {
-                NodeType::Sync(node) => node.lock().unwrap().id.to_string(),
-                NodeType::Unsync(node) => node.try_borrow().unwrap().id.to_string(),
-            };
-            
-            arr.push(id);
-            arr.push(key.to_owned());
+            arr.push(value.id().to_string());
+            arr.push(key.to_string());
         }
 
         let name = arr.join("_");
@@ -99,52 +198,47 @@ impl Display for DawgNode {
     }
 }
 
-impl Ord for DawgNode {
+impl<N> Ord for DawgNode<N> where N: NodeRef {
     fn cmp(&self, other: &Self) -> std::cmp::Ordering {
-        self.to_string().cmp(&other.to_string())
+        self.signature().cmp(&other.signature())
     }
 }
 
-impl PartialOrd for DawgNode {
+impl<N> PartialOrd for DawgNode<N> where N: NodeRef {
     fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
-        Some(self.cmp(&other))       
+        Some(self.cmp(other))
     }
 }
 
-impl PartialEq for DawgNode {
+impl<N> PartialEq for DawgNode<N> where N: NodeRef {
     fn eq(&self, other: &Self) -> bool {
-        self.to_string() == other.to_string()
+        self.signature() == other.signature()
     }
 }
 
-impl Eq for DawgNode {}
+impl<N> Eq for DawgNode<N> where N: NodeRef {}
 
 
 
 
 #[derive(Debug, Clone)]
-pub(crate) struct TriDawg {
-    pub(crate) parent: NodeType,
-    pub(crate) letter: String,
-    pub(crate) child: NodeType,
+pub(crate) struct TriDawg<N: NodeRef> {
+    pub(crate) parent: N,
+    pub(crate) letter: N::Label,
+    pub(crate) child: N,
 }
 
-impl TriDawg {
-    pub fn new(parent: NodeType, letter: String, child: NodeType) -> Self {
+impl<N> TriDawg<N> where N: NodeRef {
+    pub fn new(parent: N, letter: N::Label, child: N) -> Self {
         Self { parent, letter, child, }
     }
 }
 
 
-pub(crate) enum SearchReq {
-    Vertex,
-    Word,
-}
-
-
 #[derive(Debug)]
 pub(crate) struct SearchRes<T> {
     pub(crate) node: T,
+    /// the word as spelt in the dawg (may differ in case from the searched word)
     pub(crate) word: String
 }
 
@@ -154,18 +248,466 @@ impl<T> SearchRes<T> {
     }
 }
 
-pub trait Wrapper {
+/// Allocator of the nodes of a Dawg, `DawgWrapper` in the `sync` and `unsync` modules are the default ones.
+///
+/// A custom wrapper can place nodes wherever it likes (keeping them alive in an arena, counting them, ...) as long as:
+/// - `create` returns a node built with `DawgNode::new(id)`, i.e. not terminal and without edges
+/// - the ids handed to `DawgNode::new` are unique and increasing within a wrapper, the Dawg relies on them to tell nodes apart
+/// - `new` returns a wrapper that hasn't created any node yet, the first node it creates becomes the root
+///
+/// ```
+/// # #[cfg(feature = "unsync")] {
+/// use dawging::{Dawg, dawg::common::{DawgNode, NodeRef, Wrapper}, dawg::unsync::UnsyncNode};
+///
+/// #[derive(Default)]
+/// struct Counting {
+///     created: usize,
+/// }
+///
+/// impl Wrapper for Counting {
+///     type Node = UnsyncNode<char>;
+///
+///     fn new() -> Self {
+///         Self::default()
+///     }
+///
+///     fn create(&mut self) -> Self::Node {
+///         self.created += 1;
+///         UnsyncNode::new(DawgNode::new(self.created - 1))
+///     }
+/// }
+///
+/// let mut dawg = Dawg::with_wrapper(Counting::new());
+/// dawg.try_add("tap").unwrap();
+/// dawg.try_add("top").unwrap();
+/// dawg.finish();
+///
+/// assert!(dawg.contains("top"));
+/// assert_eq!(dawg.wrapper().created, 5);
+/// # }
+/// ```
+pub trait Wrapper<L: Label = char> {
+    /// pointer to the nodes this wrapper creates
+    type Node: NodeRef<Label = L>;
+
+    /// Creates a wrapper that hasn't allocated any node yet
     fn new() -> Self;
 
-    fn create(&mut self) -> NodeType;
+    /// Allocates a new, empty node with the next id
+    fn create(&mut self) -> Self::Node;
 }
 
 
+/// Source of the ids telling Dawgs (and the node handles they give out) apart
+pub(crate) static NEXT_DAWG_ID: AtomicUsize = AtomicUsize::new(0);
+
 #[derive(Debug)]
-pub struct Dawg<T: Wrapper> {
+pub struct Dawg<T: Wrapper<L>, L: Label = char> {
+    /// unique id of this dawg, a clone gets its own
+    pub(crate) dawg_id: usize,
     pub(crate) node: T,
-    pub(crate) minimized_nodes: HashMap<String, NodeType>,
-    pub(crate) root: NodeType,
-    pub(crate) unchecked_nodes: Vec<TriDawg>,
-    pub(crate) previous_word: String,
+    pub(crate) minimized_nodes: Register<T::Node>,
+    pub(crate) root: T::Node,
+    pub(crate) unchecked_nodes: Vec<TriDawg<T::Node>>,
+    /// terminal node without edges shared by every word while building, instead of each word ending in a leaf of its own
+    pub(crate) leaf: Option<T::Node>,
+    pub(crate) previous_word: Vec<L>,
+    /// words added ahead of their place in the order of the letters (see `SortOrder`), merged in by `finish`
+    pub(crate) staged: Vec<Vec<L>>,
+    /// direction of the first two distinct words added, under `SortDirection::Auto`
+    pub(crate) direction: Option<SortDirection>,
+    /// number of nodes an un-minimized trie of the words added so far would contain
+    pub(crate) trie_nodes: usize,
+    pub(crate) options: DawgOptions,
+    /// number of distinct words added so far
+    pub(crate) words_added: usize,
+    /// number of words dropped by `ValidationPolicy::Skip`
+    pub(crate) words_skipped: usize,
+    /// value is true once `finish` was called, words added afterwards are inserted by copying their path
+    pub(crate) finished: bool,
+    /// value is true if words were inserted or removed since the dawg was finished, so `finish` minimizes it again
+    pub(crate) edited: bool,
+    /// checkpoints of the dawg being built, from the oldest to the latest
+    pub(crate) checkpoints: Vec<Checkpoint<T::Node>>,
+    /// signatures registered since the oldest checkpoint, in order
+    pub(crate) register_log: Vec<Signature<L>>,
+    pub(crate) checkpoint_serial: usize,
+    /// index of the substrings of the words built by `build_infix_index`, dropped once the words change
+    pub(crate) infix_index: Option<Arc<InfixIndex>>,
+    /// incoming edges of the nodes built by `build_reverse_index`, dropped once the words change
+    pub(crate) reverse_index: Option<Arc<ReverseIndex<L>>>,
+}
+
+impl<T, L> Clone for Dawg<T, L> where T: Wrapper<L> + Clone, L: Label {
+    /// Deep copy of the dawg: every node is duplicated exactly once, so the copy is as small as the original
+    /// and mutating one of them never affects the other
+    fn clone(&self) -> Self {
+        let mut visited = HashMap::new();
+        let root = self.root.deep_clone(&mut visited);
+
+        let minimized_nodes = self.minimized_nodes.deep_clone(|node| node.deep_clone(&mut visited));
+        let unchecked_nodes = self.unchecked_nodes.iter()
+            .map(|TriDawg { parent, letter, child }| TriDawg::new(parent.deep_clone(&mut visited), letter.clone(), child.deep_clone(&mut visited)))
+            .collect();
+        let leaf = self.leaf.as_ref().map(|leaf| leaf.deep_clone(&mut visited));
+
+        Self {
+            dawg_id: NEXT_DAWG_ID.fetch_add(1, Ordering::Relaxed),
+            node: self.node.clone(),
+            minimized_nodes,
+            root,
+            unchecked_nodes,
+            leaf,
+            previous_word: self.previous_word.clone(),
+            staged: self.staged.clone(),
+            direction: self.direction,
+            trie_nodes: self.trie_nodes,
+            options: self.options.clone(),
+            words_added: self.words_added,
+            words_skipped: self.words_skipped,
+            finished: self.finished,
+            edited: self.edited,
+            checkpoints: vec![],
+            register_log: vec![],
+            checkpoint_serial: self.checkpoint_serial,
+            infix_index: self.infix_index.clone(),
+            reverse_index: self.reverse_index.clone(),
+        }
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Creates an empty dawg whose nodes are allocated by `node`, see `Wrapper` for what it must uphold
+    pub fn with_wrapper(mut node: T) -> Self {
+        Dawg {
+            dawg_id: NEXT_DAWG_ID.fetch_add(1, Ordering::Relaxed),
+            root: node.create(),
+            node,
+            minimized_nodes: Register::default(),
+            unchecked_nodes: vec![],
+            leaf: None,
+            previous_word: vec![],
+            staged: vec![],
+            direction: None,
+            trie_nodes: 1,
+            options: DawgOptions::default(),
+            words_added: 0,
+            words_skipped: 0,
+            finished: false,
+            edited: false,
+            checkpoints: vec![],
+            register_log: vec![],
+            checkpoint_serial: 0,
+            infix_index: None,
+            reverse_index: None,
+        }
+    }
+
+    /// the wrapper allocating the nodes of this dawg
+    pub fn wrapper(&self) -> &T {
+        &self.node
+    }
+
+    pub(crate) fn minimize(&mut self, down_to: usize) {
+        while self.unchecked_nodes.len() > down_to {
+            let TriDawg { parent, letter, child } = self.unchecked_nodes.pop().unwrap();
+            let signature = child.signature();
+
+            match self.minimized_nodes.get(&signature, &self.root) {
+                Some(minimized_reference) => parent.insert_edge(letter, minimized_reference),
+                None => {
+                    let pinned = !self.checkpoints.is_empty();
+                    if pinned {
+                        self.register_log.push(signature.clone());
+                    }
+                    // the path is only asked for when the register spills, the pending nodes above spell it
+                    let path = || self.unchecked_nodes.iter().map(|tri| tri.letter.clone()).chain([letter]).collect();
+                    self.minimized_nodes.insert(signature, child, path, pinned);
+                }
+            }
+        }
+    }
+
+    /// Adds a sequence of labels to the dawg, sequences must be added in sorted order: one sorting before the previous one
+    /// fails with `DawgError::Unsorted`. Once the dawg is finished, the nodes are no longer changed in place and the
+    /// sequence goes wherever it belongs through `insert_sequence`
+    pub fn add_sequence(&mut self, word: &[L]) -> Result<(), DawgError> {
+        if !self.finished && self.previous_word.as_slice() > word {
+            // labels can't be spelled out in general, so the sequences are named by their rank
+            let (previous, word) = (format!("sequence {}", self.words_added), format!("sequence {}", self.words_added + 1));
+            return Err(DawgError::Unsorted { previous, word, order: "labels" });
+        }
+        self.push_sequence(word);
+        self.minimized_nodes.check()
+    }
+
+    /// `add_sequence` for the callers that already checked the order of the sequences
+    pub(crate) fn push_sequence(&mut self, word: &[L]) {
+        if self.finished {
+            self.insert_sequence(word);
+            return;
+        }
+        debug_assert!(self.previous_word.as_slice() <= word, "sequences must be added in sorted order");
+
+        if self.words_added == 0 || self.previous_word != word {
+            self.words_added += 1;
+        }
+
+        // the pending nodes spell the last word built, which is the previous word unless words were staged since
+        let common_prefix = word.iter().zip(&self.unchecked_nodes).take_while(|(letter, tri)| **letter == tri.letter).count();
+
+        self.minimize(common_prefix);
+
+        if common_prefix < word.len() {
+            self.detach_leaf();
+        }
+
+        for (depth, letter) in word.iter().enumerate().skip(common_prefix) {
+            let node = match self.unchecked_nodes.last() {
+                Some(last) => last.child.clone(),
+                None => self.root.clone(),
+            };
+
+            let next_node = match depth + 1 == word.len() {
+                true => self.leaf(),
+                false => self.node.create(),
+            };
+            node.insert_edge(letter.clone(), next_node.clone());
+
+            self.unchecked_nodes.push(TriDawg::new(node, letter.clone(), next_node));
+            self.trie_nodes += 1;
+        }
+
+        // the empty sequence (which can only come first) ends on the root itself
+        match self.unchecked_nodes.last() {
+            Some(last) => last.child.set_terminal(true),
+            None => self.root.set_terminal(true),
+        }
+        self.previous_word = word.to_vec();
+    }
+
+    /// Returns the leaf shared by the words being added, creating it the first time
+    fn leaf(&mut self) -> T::Node {
+        match &self.leaf {
+            Some(leaf) => leaf.clone(),
+            None => {
+                let leaf = self.node.create();
+                leaf.set_terminal(true);
+                self.leaf.insert(leaf).clone()
+            }
+        }
+    }
+
+    /// Replaces the shared leaf ending the last word with a node of its own, before the next word extends it.
+    /// Only the last pending node can be the leaf, since it is only ever used for the last letter of a word
+    fn detach_leaf(&mut self) {
+        let (Some(last), Some(leaf)) = (self.unchecked_nodes.last_mut(), &self.leaf) else { return };
+        if last.child.id() != leaf.id() {
+            return;
+        }
+
+        let node = self.node.create();
+        node.set_terminal(true);
+        last.parent.insert_edge(last.letter.clone(), node.clone());
+        last.child = node;
+    }
+
+    /// Minimizes the words still pending and gives the nodes their canonical ids: dense, as `visit_nodes` numbers them
+    /// and the same for every dawg holding the same words. Adding words afterwards may renumber the nodes, and a dawg
+    /// edited since it was finished is minimized again (see `insert_sequence`)
+    pub fn finish(&mut self) {
+        let span = trace::timed!("finish", words, nodes);
+        self.reminimize();
+        self.merge_staged();
+        self.minimize(0);
+        let words = self.root.num_reachable();
+        self.root.num_depths();
+        self.minimized_nodes.release();
+        self.unchecked_nodes = vec![];
+        self.leaf = None;
+        self.unshare();
+        let nodes = self.canonicalize_ids();
+        self.checkpoints.clear();
+        self.register_log.clear();
+        self.finished = true;
+        span.record("words", words);
+        span.record("nodes", nodes);
+    }
+
+    /// Same as `finish`, failing if reading or writing the entries spilled by a register budget failed while building (see
+    /// `with_register_budget`): the dawg then holds the right words but may not be minimal
+    pub fn try_finish(&mut self) -> Result<(), DawgError> {
+        self.finish();
+        self.minimized_nodes.check()
+    }
+
+    /// value is true if `word` sorts before the last word built, in the order of the letters
+    pub(crate) fn goes_back(&self, word: &[L]) -> bool {
+        self.unchecked_nodes.iter().map(|tri| &tri.letter).gt(word.iter())
+    }
+
+    /// Holds `word` aside until `finish`, for a word that can't be built yet since it `goes_back`
+    pub(crate) fn stage(&mut self, word: Vec<L>) {
+        if self.words_added == 0 || self.previous_word != word {
+            self.words_added += 1;
+        }
+
+        self.previous_word = word.clone();
+        self.staged.push(word);
+    }
+
+    /// Rebuilds the dawg with the staged words merged in with the others, leaving it unfinished
+    pub(crate) fn merge_staged(&mut self) {
+        if self.staged.is_empty() {
+            return;
+        }
+
+        self.minimize(0);
+        let staged = std::mem::take(&mut self.staged);
+        let mut sequences = self.sequences().chain(staged).collect::<Vec<_>>();
+        sequences.sort();
+        sequences.dedup();
+
+        let (previous_word, direction, words_skipped) = (std::mem::take(&mut self.previous_word), self.direction, self.words_skipped);
+        self.clear();
+        for sequence in &sequences {
+            self.push_sequence(sequence);
+        }
+        (self.previous_word, self.direction, self.words_skipped) = (previous_word, direction, words_skipped);
+    }
+
+    /// Removes every word, leaving an empty dawg with the same options and wrapper
+    pub fn clear(&mut self) {
+        self.root = self.node.create();
+        self.minimized_nodes.release();
+        self.unchecked_nodes = vec![];
+        self.leaf = None;
+        self.previous_word = vec![];
+        self.staged = vec![];
+        self.direction = None;
+        self.trie_nodes = 1;
+        self.words_added = 0;
+        self.words_skipped = 0;
+        self.finished = false;
+        self.edited = false;
+        self.checkpoints.clear();
+        self.register_log.clear();
+        self.infix_index = None;
+        self.reverse_index = None;
+    }
+
+    /// value is true if `finish` was called (and the dawg wasn't cleared since)
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Number of distinct words added since the dawg was created (or cleared), duplicates aren't counted
+    pub fn words_added(&self) -> usize {
+        self.words_added
+    }
+
+    /// Number of words dropped since the dawg was created (or cleared) under `ValidationPolicy::Skip`
+    pub fn words_skipped(&self) -> usize {
+        self.words_skipped
+    }
+
+    /// Length of the path of the last word that is still waiting to be minimized, 0 once the dawg is finished
+    pub fn pending_depth(&self) -> usize {
+        self.unchecked_nodes.len()
+    }
+
+    /// Number of nodes in the minimization register, which `finish` releases (so it is then 0)
+    pub fn minimized_states(&self) -> usize {
+        self.minimized_nodes.len()
+    }
+
+    /// The last word added while building, kept across `finish`. Words added once the dawg is finished are inserted
+    /// wherever they go, so they don't change it
+    pub fn last_word(&self) -> Option<&[L]> {
+        match self.words_added {
+            0 => None,
+            _ => Some(&self.previous_word),
+        }
+    }
+
+    /// Follows `word` from the root exactly, returning the node it leads to
+    pub(crate) fn find_sequence(&self, word: &[L]) -> Option<T::Node> {
+        let mut node = self.root.clone();
+
+        for letter in word {
+            node = node.child(letter)?;
+        }
+
+        Some(node)
+    }
+
+    /// value is true if `word` was added to the dawg
+    pub fn contains_sequence(&self, word: &[L]) -> bool {
+        self.find_sequence(word).is_some_and(|node| node.is_terminal())
+    }
+
+    /// value is true if at least one sequence in the dawg starts with `prefix`
+    pub fn contains_prefix_sequence(&self, prefix: &[L]) -> bool {
+        self.find_sequence(prefix).is_some_and(|node| node.is_terminal() || !node.children().is_empty())
+    }
+
+    /// Number of words in the dawg, recounted from the edges until it is finished (only then are the counts of the nodes set)
+    pub(crate) fn count_words(&self) -> usize {
+        if self.finished {
+            return self.root.count();
+        }
+
+        count_reachable(&self.root, &mut HashMap::new())
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Follows `word` from the root, returning every node it leads to along with the spelling of the path taken.
+    /// An exact search leads to at most one node, while the other modes may match several spellings
+    pub(crate) fn find(&self, word: &str, mode: MatchMode) -> Vec<SearchRes<T::Node>> {
+        self.find_chars(word.chars(), mode, true)
+    }
+
+    /// Same as `find` but reading the letters off `chars`, the spellings are only built if `spell` is true
+    pub(crate) fn find_chars(&self, chars: impl IntoIterator<Item = char>, mode: MatchMode, spell: bool) -> Vec<SearchRes<T::Node>> {
+        self.find_chars_from(self.root.clone(), chars, mode, spell)
+    }
+
+    /// Same as `find_chars`, starting from `start` instead of the root
+    pub(crate) fn find_chars_from(&self, start: T::Node, chars: impl IntoIterator<Item = char>, mode: MatchMode, spell: bool) -> Vec<SearchRes<T::Node>> {
+        let mut frontier = vec![SearchRes::new(start, String::new())];
+
+        for letter in self.options.fold_chars(chars) {
+            let mut next = vec![];
+
+            for SearchRes { node, mut word } in frontier {
+                match mode {
+                    MatchMode::Exact => {
+                        if let Some(child) = node.child(&letter) {
+                            if spell {
+                                word.push(letter);
+                            }
+                            next.push(SearchRes::new(child, word));
+                        }
+                    }
+                    _ => node.read(|node| {
+                        for (label, child) in &node.edges {
+                            if mode.matches(*label, letter) {
+                                let word = if spell { format!("{word}{label}") } else { String::new() };
+                                next.push(SearchRes::new(child.clone(), word));
+                            }
+                        }
+                    }),
+                }
+            }
+
+            frontier = next;
+
+            if frontier.is_empty() {
+                break;
+            }
+        }
+
+        frontier
+    }
 }
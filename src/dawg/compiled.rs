@@ -0,0 +1,488 @@
+use std::{convert::Infallible, io::{Read, Write}, ops::ControlFlow};
+
+use crate::{dawg::{common::{Dawg, Wrapper}, integrity::{check, read_section, write_section, Crc32, Section}, lexicon::{Lexicon, MatchMode}, noalloc::{self, CompiledNodes, Listing}, options::DawgOptions, serialize::{check_word_counts, topological_order, write_u32, write_u64, Cursor}, trace, visit::NodeView}, error::DawgError};
+
+/// Magic bytes every saved CompiledDawg starts with
+pub(crate) const MAGIC: &[u8; 4] = b"DAWC";
+/// Version of the layout written by `CompiledDawg::save`, version 2 follows every section with its checksum
+pub(crate) const VERSION: u16 = 2;
+/// Fanout from which `compile` gives a node a dispatch table
+pub const DISPATCH_FANOUT: usize = 16;
+/// Widest range of labels (last label - first label + 1) a dispatch table may cover
+pub const DISPATCH_SPAN: u32 = 256;
+/// Marks a node without a dispatch table, and the empty slots of a table
+pub(crate) const NONE: u32 = u32::MAX;
+/// Size in bytes of a saved node: terminal flag, reachable count, first edge, number of edges, dispatch table
+pub(crate) const NODE_SIZE: usize = 1 + 8 + 4 + 4 + 4;
+/// Size in bytes of a saved edge: label and child
+pub(crate) const EDGE_SIZE: usize = 4 + 4;
+/// Size in bytes of a saved slot of a dispatch table
+pub(crate) const SLOT_SIZE: usize = 4;
+
+/// A node of a CompiledDawg, its edges being `first_edge..first_edge + edges` of the edge arrays
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct CompiledNode {
+    pub(crate) terminal: bool,
+    pub(crate) count: u64,
+    pub(crate) first_edge: u32,
+    pub(crate) edges: u32,
+    /// start of the dispatch table of the node in the slots, `NONE` if its edges are binary searched
+    pub(crate) table: u32,
+}
+
+impl CompiledNode {
+    pub(crate) fn decode(reader: &mut Cursor) -> Result<Self, DawgError> {
+        Ok(Self { terminal: reader.u8()? == 1, count: reader.u64()?, first_edge: reader.u32()?, edges: reader.u32()?, table: reader.u32()? })
+    }
+}
+
+/// Reads a saved edge, failing if its label isn't a char or its child isn't one of the `node_count` nodes
+pub(crate) fn decode_edge(reader: &mut Cursor, edge: u32, node_count: u32) -> Result<(char, u32), DawgError> {
+    let label = char::from_u32(reader.u32()?).ok_or_else(|| DawgError::InvalidFormat(format!("edge {edge} has an invalid label")))?;
+    let child = reader.u32()?;
+    match child < node_count {
+        true => Ok((label, child)),
+        false => Err(DawgError::InvalidFormat(format!("edge {edge} points at a node out of bounds"))),
+    }
+}
+
+/// What a saved CompiledDawg starts with, followed by its nodes, edges and slots
+#[derive(Debug)]
+pub(crate) struct Header {
+    pub(crate) options: DawgOptions,
+    pub(crate) node_count: u32,
+    pub(crate) edge_count: u32,
+    pub(crate) slot_count: u32,
+    /// value is true if every section is followed by its checksum
+    pub(crate) checksummed: bool,
+    /// size in bytes of the header
+    pub(crate) len: usize,
+}
+
+impl Header {
+    /// Number of bytes to read before the options, whose length they give
+    pub(crate) const PREFIX: usize = 4 + 2 + 4;
+
+    /// Number of bytes of the header following its first `Header::PREFIX` bytes, `prefix`
+    pub(crate) fn rest_len(prefix: &[u8]) -> Result<usize, DawgError> {
+        let mut reader = Cursor::new(prefix);
+        reader.bytes(4).map_err(|_| DawgError::Truncated { section: Section::Header })?;
+        let version = reader.u16().map_err(|_| DawgError::Truncated { section: Section::Header })?;
+        let options_len = reader.u32().map_err(|_| DawgError::Truncated { section: Section::Header })? as usize;
+        Ok(options_len + 3 * 4 + if version >= 2 { 4 } else { 0 })
+    }
+
+    /// Reads the header at the start of `bytes`, checking it against its checksum
+    pub(crate) fn parse(bytes: &[u8]) -> Result<Self, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        let truncated = |_| DawgError::Truncated { section: Section::Header };
+
+        if reader.bytes(4).map_err(truncated)? != MAGIC {
+            return Err(DawgError::InvalidFormat(String::from("missing DAWC magic bytes")));
+        }
+
+        let version = reader.u16().map_err(truncated)?;
+        if version > VERSION {
+            return Err(DawgError::NewerVersion { version, supported: VERSION });
+        }
+        if version == 0 {
+            return Err(DawgError::InvalidFormat(format!("unsupported version {version}")));
+        }
+
+        let checksummed = version >= 2;
+        let options_len = reader.u32().map_err(truncated)? as usize;
+        let options = reader.bytes(options_len).map_err(truncated)?;
+        let mut counts = Cursor::new(reader.bytes(3 * 4).map_err(truncated)?);
+        let (node_count, edge_count, slot_count) = (counts.u32()?, counts.u32()?, counts.u32()?);
+
+        if checksummed {
+            let header = &bytes[..reader.position];
+            check(Section::Header, reader.u32().map_err(truncated)?, Crc32::of(header))?;
+        }
+
+        Ok(Self { options: DawgOptions::decode(options)?, node_count, edge_count, slot_count, checksummed, len: reader.position })
+    }
+
+    /// size in bytes of the checksum following each section
+    fn checksum_len(&self) -> u64 {
+        if self.checksummed { 4 } else { 0 }
+    }
+
+    /// position of the first node in the file
+    pub(crate) fn nodes_at(&self) -> u64 {
+        self.len as u64
+    }
+
+    pub(crate) fn edges_at(&self) -> u64 {
+        self.nodes_at() + self.node_count as u64 * NODE_SIZE as u64 + self.checksum_len()
+    }
+
+    pub(crate) fn slots_at(&self) -> u64 {
+        self.edges_at() + self.edge_count as u64 * EDGE_SIZE as u64 + self.checksum_len()
+    }
+
+    /// size in bytes of the whole saved CompiledDawg
+    pub(crate) fn file_len(&self) -> u64 {
+        self.slots_at() + self.slot_count as u64 * SLOT_SIZE as u64 + self.checksum_len()
+    }
+}
+
+/// Frozen, read-only form of a finished char dawg, made by `Dawg::compile`.
+///
+/// Nodes live in one array (numbered like `visit_nodes`, the root being 0) and their edges in two more, sorted by label.
+/// Following an edge binary searches the labels of the node, except on nodes with many edges (the root and the first
+/// levels of a natural-language dictionary) which get a dispatch table: one slot per label between their first and last
+/// label, holding the child of that label, so the transition costs a single lookup whatever the fanout
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompiledDawg {
+    options: DawgOptions,
+    nodes: Vec<CompiledNode>,
+    labels: Vec<char>,
+    children: Vec<u32>,
+    /// dispatch tables of every node having one, back to back
+    slots: Vec<u32>,
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Compiles the finished dawg into its frozen form, nodes with at least `DISPATCH_FANOUT` edges getting a dispatch table
+    pub fn compile(&self) -> CompiledDawg {
+        self.compile_with(DISPATCH_FANOUT)
+    }
+
+    /// Same as `compile`, giving a dispatch table to the nodes with at least `min_fanout` edges (`usize::MAX` for none).
+    /// A node whose labels span more than `DISPATCH_SPAN` letters keeps the binary search whatever its fanout
+    pub fn compile_with(&self, min_fanout: usize) -> CompiledDawg {
+        let mut compiled = CompiledDawg::empty(self.options.clone());
+        for view in self.visit_nodes_bfs() {
+            compiled.push_node(&view, min_fanout);
+        }
+
+        compiled
+    }
+}
+
+impl CompiledDawg {
+    pub(crate) fn empty(options: DawgOptions) -> Self {
+        Self { options, nodes: vec![], labels: vec![], children: vec![], slots: vec![] }
+    }
+
+    /// Appends the node `view` with its edges, giving it a dispatch table if it has at least `min_fanout` of them
+    pub(crate) fn push_node(&mut self, view: &NodeView<char>, min_fanout: usize) {
+        let first_edge = self.labels.len() as u32;
+        for (label, child) in view.edges() {
+            self.labels.push(*label);
+            self.children.push(child as u32);
+        }
+
+        let mut node = CompiledNode { terminal: view.is_terminal(), count: view.count() as u64, first_edge, edges: self.labels.len() as u32 - first_edge, table: NONE };
+        if node.edges as usize >= min_fanout.max(1) && self.span(&node) <= DISPATCH_SPAN {
+            node.table = self.slots.len() as u32;
+            self.slots.resize(self.slots.len() + self.span(&node) as usize, NONE);
+
+            let low = self.labels[first_edge as usize] as u32;
+            for edge in self.edges(&node) {
+                self.slots[(node.table + self.labels[edge] as u32 - low) as usize] = self.children[edge];
+            }
+        }
+        self.nodes.push(node);
+    }
+
+    /// Appends the nodes of `other`, compiled on their own, after the ones already here. Children keep their ids, which
+    /// count from the root of the whole dawg, while edges and tables are shifted past the ones already here
+    #[cfg(feature = "rayon")]
+    pub(crate) fn append(&mut self, other: CompiledDawg) {
+        let (edges, slots) = (self.labels.len() as u32, self.slots.len() as u32);
+        self.nodes.extend(other.nodes.into_iter().map(|node| CompiledNode {
+            first_edge: node.first_edge + edges,
+            table: if node.table == NONE { NONE } else { node.table + slots },
+            ..node
+        }));
+        self.labels.extend(other.labels);
+        self.children.extend(other.children);
+        self.slots.extend(other.slots);
+    }
+
+    pub fn options(&self) -> &DawgOptions {
+        &self.options
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.labels.len()
+    }
+
+    /// number of nodes given a dispatch table
+    pub fn dispatch_tables(&self) -> usize {
+        self.nodes.iter().filter(|node| node.table != NONE).count()
+    }
+
+    fn edges(&self, node: &CompiledNode) -> std::ops::Range<usize> {
+        node.first_edge as usize..(node.first_edge + node.edges) as usize
+    }
+
+    /// number of slots the dispatch table of `node` needs (its labels are sorted, so they span from the first to the last)
+    fn span(&self, node: &CompiledNode) -> u32 {
+        match self.labels[self.edges(node)] {
+            [] => 0,
+            [first, .., last] => last as u32 - first as u32 + 1,
+            [_] => 1,
+        }
+    }
+
+    /// Number of label comparisons an exact lookup of `word` makes, a table lookup counting as one
+    pub fn probes(&self, word: &str) -> usize {
+        let mut probes = 0;
+        let mut node = self.nodes.first().copied();
+
+        for letter in self.options.fold_chars(word.chars()) {
+            let Some(current) = node else { break };
+            let Ok(next) = self.step_counting(&current, letter, &mut probes);
+            node = next.map(|child| self.nodes[child as usize]);
+        }
+
+        probes
+    }
+
+    /// Pushes the words below `node` onto `out` in sorted order, `word` being the spelling of the path to `node`, stopping
+    /// once `out` holds `limit` words
+    fn push_words(&self, node: CompiledNode, word: &mut String, out: &mut Vec<String>, limit: usize) {
+        let limit = out.len().saturating_add(limit);
+        let mut stack = vec![(node, 0)];
+        if node.terminal {
+            out.push(word.clone());
+        }
+
+        while let Some((node, next_edge)) = stack.last_mut() {
+            if out.len() >= limit {
+                out.truncate(limit);
+                break;
+            }
+            let edges = self.edges(node);
+            let edge = edges.start + *next_edge;
+            *next_edge += 1;
+
+            match edge < edges.end {
+                true => {
+                    let child = self.nodes[self.children[edge] as usize];
+                    word.push(self.labels[edge]);
+                    if child.terminal {
+                        out.push(word.clone());
+                    }
+                    stack.push((child, 0));
+                }
+                false => {
+                    stack.pop();
+                    if !stack.is_empty() {
+                        word.pop();
+                    }
+                }
+            }
+        }
+    }
+
+    /// Returns every word in sorted order
+    pub fn words(&self) -> Vec<String> {
+        self.completions("", MatchMode::Exact)
+    }
+
+    /// Writes the words starting with `prefix` (folded by the options, matched exactly) to `out` in sorted order without
+    /// allocating, see the `noalloc` module. The words are written in utf-8 one after the other, the i-th one ending at
+    /// `ends[i]`, the first at 0.
+    ///
+    /// Only whole words are written: the listing stops once `ends` is full, and skips the words that no longer fit in `out`.
+    /// `Listing::total` tells how many words there were to write
+    pub fn completions_into(&self, prefix: &str, out: &mut [u8], ends: &mut [usize]) -> Listing {
+        noalloc::completions_into(self, prefix, out, ends)
+    }
+
+    /// Hands the words starting with `prefix` (folded by the options, matched exactly) to `visit` in sorted order without
+    /// allocating, until it breaks. Each word is spelled in `path`, so the words longer than it are skipped
+    pub fn for_each_completion(&self, prefix: &str, path: &mut [u8], visit: impl FnMut(&str) -> ControlFlow<()>) -> Listing {
+        noalloc::for_each_completion(self, prefix, path, visit)
+    }
+
+    /// Encodes the compiled dawg, dispatch tables included
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut options = vec![];
+        self.options.encode(&mut options);
+
+        let mut out = vec![];
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        write_u32(&mut out, options.len() as u32);
+        out.extend_from_slice(&options);
+        write_u32(&mut out, self.nodes.len() as u32);
+        write_u32(&mut out, self.labels.len() as u32);
+        write_u32(&mut out, self.slots.len() as u32);
+        let header = Crc32::of(&out);
+        write_u32(&mut out, header);
+
+        let mut nodes = Vec::with_capacity(self.nodes.len() * NODE_SIZE);
+        for node in &self.nodes {
+            nodes.push(node.terminal as u8);
+            write_u64(&mut nodes, node.count);
+            write_u32(&mut nodes, node.first_edge);
+            write_u32(&mut nodes, node.edges);
+            write_u32(&mut nodes, node.table);
+        }
+        let mut edges = Vec::with_capacity(self.labels.len() * EDGE_SIZE);
+        for (label, child) in self.labels.iter().zip(&self.children) {
+            write_u32(&mut edges, *label as u32);
+            write_u32(&mut edges, *child);
+        }
+        let mut slots = Vec::with_capacity(self.slots.len() * SLOT_SIZE);
+        for slot in &self.slots {
+            write_u32(&mut slots, *slot);
+        }
+
+        for section in [&nodes, &edges, &slots] {
+            write_section(&mut out, section);
+        }
+        out
+    }
+
+    /// Writes the compiled dawg to `writer`, see `to_bytes`
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        let span = trace::timed!("save", bytes);
+        let bytes = self.to_bytes();
+        span.record("bytes", bytes.len());
+        writer.write_all(&bytes)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Reads back the bytes written by `save`. Every section must match its checksum, the edges must point at existing nodes without forming a cycle, the labels
+    /// of each node must be sorted, every dispatch table must agree with the edges of its node, and every saved word count
+    /// must match the words below its node
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DawgError> {
+        let span = trace::timed!("load", bytes, nodes);
+        span.record("bytes", bytes.len());
+        let invalid = |reason: String| DawgError::InvalidFormat(reason);
+        let header = Header::parse(bytes)?;
+        span.record("nodes", header.node_count as usize);
+        let mut reader = Cursor::new(bytes);
+        reader.position = header.len;
+        let Header { options, node_count, edge_count, slot_count, checksummed, .. } = header;
+
+        let mut compiled = CompiledDawg { options, nodes: vec![], labels: vec![], children: vec![], slots: vec![] };
+        let mut section = Cursor::new(read_section(&mut reader, Section::Nodes, node_count as usize * NODE_SIZE, checksummed)?);
+        for _ in 0..node_count {
+            let node = CompiledNode::decode(&mut section)?;
+            if node.first_edge as u64 + node.edges as u64 > edge_count as u64 {
+                return Err(invalid(format!("node {} has edges out of bounds", compiled.nodes.len())));
+            }
+            compiled.nodes.push(node);
+        }
+
+        let mut section = Cursor::new(read_section(&mut reader, Section::Edges, edge_count as usize * EDGE_SIZE, checksummed)?);
+        for edge in 0..edge_count {
+            let (label, child) = decode_edge(&mut section, edge, node_count)?;
+            compiled.labels.push(label);
+            compiled.children.push(child);
+        }
+
+        let mut section = Cursor::new(read_section(&mut reader, Section::Slots, slot_count as usize * SLOT_SIZE, checksummed)?);
+        for _ in 0..slot_count {
+            compiled.slots.push(section.u32()?);
+        }
+
+        for (id, node) in compiled.nodes.iter().enumerate() {
+            if compiled.labels[compiled.edges(node)].windows(2).any(|pair| pair[0] >= pair[1]) {
+                return Err(invalid(format!("the labels of node {id} aren't sorted")));
+            }
+            if node.table != NONE && compiled.table_matches(node) != Ok(true) {
+                return Err(invalid(format!("the dispatch table of node {id} doesn't match its edges")));
+            }
+        }
+
+        let children_of = |id: usize| compiled.edges(&compiled.nodes[id]).map(|edge| compiled.children[edge]);
+        let topological = topological_order(compiled.nodes.len(), children_of)?;
+        check_word_counts(&topological, |id| (compiled.nodes[id].terminal, compiled.nodes[id].count), children_of)?;
+        Ok(compiled)
+    }
+
+    /// Reads a compiled dawg written by `save` from `reader`
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+impl CompiledNodes for CompiledDawg {
+    type Error = Infallible;
+
+    fn options(&self) -> &DawgOptions {
+        &self.options
+    }
+
+    fn root(&self) -> Option<CompiledNode> {
+        self.nodes.first().copied()
+    }
+
+    fn node(&self, id: u32) -> Result<CompiledNode, Infallible> {
+        Ok(self.nodes[id as usize])
+    }
+
+    fn edge(&self, edge: u32) -> Result<(char, u32), Infallible> {
+        Ok((self.labels[edge as usize], self.children[edge as usize]))
+    }
+
+    fn slot(&self, slot: u32) -> Result<u32, Infallible> {
+        Ok(self.slots[slot as usize])
+    }
+
+    fn slot_len(&self) -> u32 {
+        self.slots.len() as u32
+    }
+}
+
+/// Queries under `MatchMode::Exact` don't allocate but for `completions`, see the `noalloc` module
+impl Lexicon for CompiledDawg {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        if mode == MatchMode::Exact {
+            return self.contains_exact(word);
+        }
+        let Ok(found) = self.find(word, mode, false);
+        found.iter().any(|(node, _)| node.terminal)
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        if mode == MatchMode::Exact {
+            return self.contains_prefix_exact(prefix);
+        }
+        let Ok(found) = self.find(prefix, mode, false);
+        found.iter().any(|(node, _)| node.terminal || node.edges > 0)
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        self.completions_limit(prefix, mode, usize::MAX)
+    }
+
+    /// Stops listing the words below each node the prefix leads to once `limit` of them are listed
+    fn completions_limit(&self, prefix: &str, mode: MatchMode, limit: usize) -> Vec<String> {
+        let mut words = vec![];
+
+        let Ok(found) = self.find(prefix, mode, true);
+        for (node, mut spelling) in found {
+            self.push_words(node, &mut spelling, &mut words, limit);
+        }
+
+        words.sort();
+        words.dedup();
+        words.truncate(limit);
+        words
+    }
+
+    /// Read from the counts of the nodes the prefix leads to, without listing the words
+    fn count_completions(&self, prefix: &str, mode: MatchMode) -> usize {
+        let Ok(found) = self.find(prefix, mode, false);
+        found.iter().map(|(node, _)| node.count as usize).sum()
+    }
+
+    fn word_count(&self) -> usize {
+        self.nodes.first().map_or(0, |root| root.count as usize)
+    }
+}
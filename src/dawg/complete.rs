@@ -0,0 +1,44 @@
+use crate::dawg::common::{Dawg, NodeOps, Wrapper};
+
+/// How far a prefix can be completed without choosing between words, see `Dawg::complete_unique`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Completion {
+    /// the only word starting with the prefix
+    Unique(String),
+    /// the prefix extended as long as every word starting with it goes on the same way: up to the first letter where they
+    /// part, or the first of them ending. It is the prefix itself if they part right away
+    CommonPrefix(String),
+    /// no word starts with the prefix
+    None,
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Completes `prefix` (matched exactly, as in `words_with_prefix`) as far as it is unambiguous, the way shells complete
+    /// on tab. The word counts of the nodes tell a unique completion apart, otherwise the prefix is extended along the
+    /// nodes with a single child, without listing the completions
+    pub fn complete_unique(&self, prefix: &str) -> Completion {
+        let mut word = prefix.chars().collect::<Vec<_>>();
+        let Some(mut node) = self.find_sequence(&word) else { return Completion::None };
+        let unique = match node.reachable() {
+            0 => return Completion::None,
+            count => count == 1,
+        };
+
+        // a unique completion is the single path down to its terminal node
+        while !node.is_terminal() {
+            let mut children = node.children();
+            if children.len() != 1 {
+                break;
+            }
+
+            let (label, child) = children.pop().unwrap();
+            word.push(label);
+            node = child;
+        }
+
+        match unique {
+            true => Completion::Unique(word.into_iter().collect()),
+            false => Completion::CommonPrefix(word.into_iter().collect()),
+        }
+    }
+}
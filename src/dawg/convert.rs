@@ -0,0 +1,57 @@
+use std::collections::HashMap;
+
+use crate::{dawg::{common::{Dawg, DawgNode, Label, NodeOps, NodeRef, Wrapper}, register::Register, sync::{self, SyncDawg}, unsync::{self, UnsyncDawg}}, error::DawgError};
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Moves the finished dawg over to the nodes of `wrapper`, which must hand out ids past those of the dawg.
+    /// Each node is recreated once (looked up by id), with its id, terminal flag, counts and edges, so shared nodes stay shared
+    fn rewrap<U: Wrapper<L>>(self, wrapper: U) -> Result<Dawg<U, L>, DawgError> {
+        if !self.finished {
+            return Err(DawgError::NotFinished);
+        }
+
+        let mut dawg = Dawg::with_wrapper(wrapper);
+        dawg.root = recreate(&self.root, &mut HashMap::new());
+        dawg.previous_word = self.previous_word;
+        dawg.direction = self.direction;
+        dawg.trie_nodes = self.trie_nodes;
+        dawg.minimized_nodes = Register::with_hasher(self.options.hasher);
+        dawg.options = self.options;
+        dawg.words_added = self.words_added;
+        dawg.finished = true;
+        dawg.edited = self.edited;
+        dawg.checkpoint_serial = self.checkpoint_serial;
+        Ok(dawg)
+    }
+}
+
+fn recreate<N: NodeRef, M: NodeRef<Label = N::Label>>(node: &N, created: &mut HashMap<usize, M>) -> M {
+    if let Some(copy) = created.get(&node.id()) {
+        return copy.clone();
+    }
+
+    let (id, terminal, count, depths) = node.read(|node| (node.id, node.terminal, node.count, node.depths));
+    let edges = node.children().into_iter().map(|(label, child)| (label, recreate(&child, created))).collect();
+    let copy = M::new(DawgNode { id, terminal, edges, count, depths });
+
+    created.insert(id, copy.clone());
+    copy
+}
+
+impl<L> UnsyncDawg<L> where L: Label {
+    /// Turns the finished dawg into a `SyncDawg` holding the same nodes, to share it between threads without building it
+    /// again. Fails with `DawgError::NotFinished` if the dawg isn't finished
+    pub fn to_sync(self) -> Result<SyncDawg<L>, DawgError> {
+        let next_id = self.node.next_id;
+        self.rewrap(sync::DawgWrapper { next_id })
+    }
+}
+
+impl<L> SyncDawg<L> where L: Label {
+    /// Turns the finished dawg into an `UnsyncDawg` holding the same nodes. Fails with `DawgError::NotFinished` if the dawg
+    /// isn't finished
+    pub fn to_unsync(self) -> Result<UnsyncDawg<L>, DawgError> {
+        let next_id = self.node.next_id;
+        self.rewrap(unsync::DawgWrapper { next_id })
+    }
+}
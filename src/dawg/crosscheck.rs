@@ -0,0 +1,149 @@
+use std::borrow::Cow;
+
+use crate::{dawg::common::{Dawg, NodeOps, NodeRef, Wrapper}, error::DawgError};
+
+/// Most letters a LetterSet can hold
+pub const MAX_LETTERS: usize = 64;
+
+/// Letters a LetterSet is taken over, at most `MAX_LETTERS` of them
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct LetterAlphabet {
+    /// sorted and without duplicates
+    letters: Cow<'static, [char]>,
+}
+
+impl LetterAlphabet {
+    /// the lowercase letters a to z, used by `Dawg::cross_checks`
+    pub const LATIN: LetterAlphabet = LetterAlphabet { letters: Cow::Borrowed(&[
+        'a', 'b', 'c', 'd', 'e', 'f', 'g', 'h', 'i', 'j', 'k', 'l', 'm', 'n', 'o', 'p', 'q', 'r', 's', 't', 'u', 'v', 'w', 'x', 'y', 'z',
+    ]) };
+
+    /// Alphabet of the distinct letters of `letters`
+    pub fn new(letters: &str) -> Result<Self, DawgError> {
+        let mut letters = letters.chars().collect::<Vec<_>>();
+        letters.sort();
+        letters.dedup();
+
+        match letters.len() <= MAX_LETTERS {
+            true => Ok(Self { letters: letters.into() }),
+            false => Err(DawgError::InvalidValue(format!("a letter set holds at most {MAX_LETTERS} letters, not {}", letters.len()))),
+        }
+    }
+
+    pub fn letters(&self) -> &[char] {
+        &self.letters
+    }
+
+    /// position of `letter` in the alphabet, which is its bit in a LetterSet
+    pub fn index(&self, letter: char) -> Option<usize> {
+        self.letters.binary_search(&letter).ok()
+    }
+}
+
+impl Default for LetterAlphabet {
+    fn default() -> Self {
+        Self::LATIN
+    }
+}
+
+/// Set of letters of a LetterAlphabet, one bit per letter
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LetterSet(u64);
+
+impl LetterSet {
+    /// Set holding every letter of `alphabet`
+    pub fn full(alphabet: &LetterAlphabet) -> Self {
+        match alphabet.letters.len() {
+            0 => Self(0),
+            len => Self(u64::MAX >> (MAX_LETTERS - len)),
+        }
+    }
+
+    pub fn bits(self) -> u64 {
+        self.0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn len(self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    /// value is true if the set holds `letter`, a letter of `alphabet`
+    pub fn contains(self, letter: char, alphabet: &LetterAlphabet) -> bool {
+        alphabet.index(letter).is_some_and(|index| self.0 >> index & 1 == 1)
+    }
+
+    /// Adds `letter` to the set, returning false if it isn't in `alphabet`
+    pub fn insert(&mut self, letter: char, alphabet: &LetterAlphabet) -> bool {
+        alphabet.index(letter).map(|index| self.0 |= 1 << index).is_some()
+    }
+
+    pub fn intersection(self, other: Self) -> Self {
+        Self(self.0 & other.0)
+    }
+
+    pub fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Returns the letters of the set in the order of `alphabet`
+    pub fn letters(self, alphabet: &LetterAlphabet) -> impl Iterator<Item = char> + '_ {
+        alphabet.letters.iter().enumerate().filter(move |(index, _)| self.0 >> index & 1 == 1).map(|(_, letter)| *letter)
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the letters `L` of a to z for which `before`, `L` then `after` spell a word (see `cross_checks_in`)
+    pub fn cross_checks(&self, before: &str, after: &str) -> LetterSet {
+        self.cross_checks_in(before, after, &LetterAlphabet::LATIN)
+    }
+
+    /// Returns the letters `L` of `alphabet` for which `before`, `L` then `after` spell a word, folded by the options of
+    /// the dawg and matched exactly. Nothing is allocated: `before` is followed from the root, then `after` from the child
+    /// of every edge in `alphabet`, once per distinct child since minimized edges often share their child
+    pub fn cross_checks_in(&self, before: &str, after: &str, alphabet: &LetterAlphabet) -> LetterSet {
+        let Some(node) = self.follow(self.root.clone(), before) else {
+            return LetterSet::default();
+        };
+
+        // children already walked, with whether `after` leads to a word from them
+        let mut walked = [(0, false); MAX_LETTERS];
+        let mut walked_len = 0;
+        let mut set = LetterSet::default();
+
+        node.read(|node| {
+            for (label, child) in &node.edges {
+                let Some(index) = alphabet.index(*label) else {
+                    continue;
+                };
+
+                let id = child.id();
+                let accepts = match walked[..walked_len].iter().find(|(walked, _)| *walked == id) {
+                    Some((_, accepts)) => *accepts,
+                    None => {
+                        let accepts = self.follow(child.clone(), after).is_some_and(|end| end.is_terminal());
+                        if walked_len < MAX_LETTERS {
+                            walked[walked_len] = (id, accepts);
+                            walked_len += 1;
+                        }
+                        accepts
+                    }
+                };
+
+                if accepts {
+                    set.0 |= 1 << index;
+                }
+            }
+        });
+
+        set
+    }
+
+    /// Follows the letters of `word`, folded by the options, from `node`
+    fn follow(&self, node: T::Node, word: &str) -> Option<T::Node> {
+        self.options.fold_chars(word.chars()).try_fold(node, |node, letter| node.child(&letter))
+    }
+}
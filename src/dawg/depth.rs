@@ -0,0 +1,34 @@
+use crate::dawg::common::{Dawg, NodeOps, Wrapper};
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the shortest word starting with `prefix` (matched exactly, as in `words_with_prefix`), the smallest of them in
+    /// sorted order if several are as short. The prefix itself is its shortest completion when it is a word.
+    /// The walk follows the completion depths `finish` keeps on the nodes, one edge per letter
+    pub fn shortest_completion(&self, prefix: &str) -> Option<String> {
+        self.guided_completion(prefix, |(shortest, _)| shortest)
+    }
+
+    /// Returns the longest word starting with `prefix` (matched exactly), the smallest of them in sorted order if several are
+    /// as long, following the completion depths like `shortest_completion`
+    pub fn longest_completion(&self, prefix: &str) -> Option<String> {
+        self.guided_completion(prefix, |(_, longest)| longest)
+    }
+
+    /// Descends from `prefix` through the first edge keeping the depth picked by `depth` of the completion, until the node
+    /// ends a word at that depth
+    fn guided_completion(&self, prefix: &str, depth: impl Fn((usize, usize)) -> usize) -> Option<String> {
+        let mut word = prefix.chars().collect::<Vec<_>>();
+        let mut node = self.find_sequence(&word)?;
+        let mut left = depth(node.completion_depths()?);
+
+        while left > 0 {
+            let (label, child) = node.children().into_iter()
+                .find(|(_, child)| child.completion_depths().is_some_and(|depths| depth(depths) + 1 == left))?;
+            word.push(label);
+            node = child;
+            left -= 1;
+        }
+
+        Some(word.into_iter().collect())
+    }
+}
@@ -0,0 +1,52 @@
+use std::cmp::Ordering;
+
+use crate::{dawg::{common::{Dawg, Wrapper}, options::SortOrder}, error::DawgError};
+
+/// Words that differ between a dawg and a new list of words, as returned by `Dawg::diff_with_sorted`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DiffReport {
+    /// words of the dawg missing from the new list, in sorted order
+    pub removed: Vec<String>,
+    /// words of the new list missing from the dawg, in sorted order
+    pub added: Vec<String>,
+}
+
+impl DiffReport {
+    /// value is true if the dawg and the new list hold the same words
+    pub fn is_empty(&self) -> bool {
+        self.removed.is_empty() && self.added.is_empty()
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Compares the words of the dawg with `new_words`, which must be sorted (repeated words are only counted once).
+    /// Both are walked once side by side, so nothing but the differences is kept in memory.
+    /// The new words go through the case folding of the options, like the words added with `try_add`
+    pub fn diff_with_sorted<I: Iterator<Item = String>>(&self, new_words: I) -> Result<DiffReport, DawgError> {
+        let mut report = DiffReport::default();
+        let mut words = self.words().peekable();
+        let mut previous: Option<String> = None;
+
+        for word in new_words {
+            let word = self.options.fold(&word);
+
+            match previous.as_ref().map(|previous| previous.cmp(&word)) {
+                Some(Ordering::Greater) => return Err(DawgError::Unsorted { previous: previous.unwrap(), word, order: SortOrder::Bytes.name() }),
+                Some(Ordering::Equal) => continue,
+                _ => {}
+            }
+
+            while let Some(removed) = words.next_if(|current| *current < word) {
+                report.removed.push(removed);
+            }
+
+            if words.next_if_eq(&word).is_none() {
+                report.added.push(word.clone());
+            }
+            previous = Some(word);
+        }
+
+        report.removed.extend(words);
+        Ok(report)
+    }
+}
@@ -0,0 +1,238 @@
+use std::{collections::HashMap, sync::atomic::Ordering};
+
+use crate::{dawg::{common::{Dawg, Label, NodeOps, NodeRef, Wrapper, NEXT_DAWG_ID}, invariants::InvariantViolation, register::Register}, error::DawgError, node::count_reachable};
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Copies `node` into a fresh node from the wrapper, with `count` words below it
+    fn copy_node(&mut self, node: &T::Node, count: usize) -> T::Node {
+        let copy = self.node.create();
+        let (terminal, edges) = node.read(|node| (node.terminal, node.edges.clone()));
+
+        copy.write(|copy| {
+            copy.terminal = terminal;
+            copy.edges = edges;
+            copy.count = count;
+        });
+        copy
+    }
+
+    /// Checks the nodes the edit copied in debug builds, panicking at the first whose count, kept up to date by the edit
+    /// rather than recounted, isn't the words ending there plus the counts of its children. `path` holds them root first,
+    /// and is checked beforehand for an edge leading back up the path. The nodes below the path are trusted, unless the
+    /// `testutil` feature is enabled: every node is then recounted from the root, which costs the whole dawg per edit.
+    /// Release builds skip it
+    fn check_counts(&self, path: &[T::Node], edit: &str) {
+        if !cfg!(debug_assertions) {
+            return;
+        }
+
+        for (depth, node) in path.iter().enumerate() {
+            if let Some((_, child)) = node.children().iter().find(|(_, child)| path[..=depth].iter().any(|above| above.id() == child.id())) {
+                panic!("{} after {edit}", InvariantViolation::Cycle { node: child.id() });
+            }
+            let below = node.children().iter().map(|(_, child)| child.count()).sum::<usize>();
+            assert_eq!(node.count(), node.is_terminal() as usize + below, "{edit} left a wrong word count on node {}", node.id());
+        }
+
+        if cfg!(feature = "testutil") {
+            let mut recounted = HashMap::new();
+            for node in self.breadth_first() {
+                assert_eq!(node.count(), count_reachable(&node, &mut recounted), "{edit} left a wrong word count on node {}", node.id());
+            }
+        }
+    }
+
+    /// Copies the nodes of a finished dawg into fresh ones before `finish` renumbers them: a `fork` shares its nodes with
+    /// the dawg it was taken from, and writing new ids into them would renumber the other side as well.
+    /// Nothing is copied while the ids are still the ones the last `finish` gave them
+    pub(crate) fn unshare(&mut self) {
+        if !self.finished || self.breadth_first().iter().enumerate().all(|(id, node)| node.id() == id) {
+            return;
+        }
+
+        self.root = self.root.deep_clone(&mut HashMap::new());
+    }
+
+    /// Rebuilds a dawg edited since it was finished from its sequences, so `finish` gives it the automaton (and the ids)
+    /// of a fresh build of the same words. The last word built is kept
+    pub(crate) fn reminimize(&mut self) {
+        if !self.edited {
+            return;
+        }
+
+        let previous_word = std::mem::take(&mut self.previous_word);
+        self.rebuild(|_| true);
+        self.previous_word = previous_word;
+    }
+
+    /// Adds `sequence` to a dawg whatever its order, returning false if it was already there.
+    ///
+    /// Nodes are shared by many paths, so none is changed in place: the nodes along the path are copied and the copies edited,
+    /// leaving every other path (and any `fork` of the dawg) untouched. The copies get new ids and may duplicate nodes
+    /// found elsewhere, until `finish` minimizes the dawg again. Words still pending in a dawg being built are minimized first.
+    ///
+    /// Each copy counts one word more than the node it replaces, and the nodes below the path are unchanged, so the word
+    /// counts `rank`, `select`, `keys_at` and paging go through are right after every edit without being recounted. Debug
+    /// builds check the copied nodes (see `check_counts`)
+    pub fn insert_sequence(&mut self, sequence: &[L]) -> bool {
+        if !self.finished {
+            self.finish();
+        }
+
+        if self.contains_sequence(sequence) {
+            return false;
+        }
+
+        self.infix_index = None;
+        self.reverse_index = None;
+        let root = self.root.clone();
+        let mut parent = self.copy_node(&root, root.reachable() + 1);
+        self.root = parent.clone();
+        let mut path = vec![parent.clone()];
+
+        for label in sequence {
+            let child = match parent.child(label) {
+                Some(child) => self.copy_node(&child, child.reachable() + 1),
+                None => {
+                    self.trie_nodes += 1;
+                    let child = self.node.create();
+                    child.write(|child| child.count = 1);
+                    child
+                }
+            };
+
+            parent.insert_edge(label.clone(), child.clone());
+            path.push(child.clone());
+            parent = child;
+        }
+
+        parent.set_terminal(true);
+        self.words_added += 1;
+        self.edited = true;
+        self.check_counts(&path, "an insert");
+        true
+    }
+
+    /// Removes `sequence` from the dawg, returning false if it wasn't there.
+    /// Like `insert_sequence`, the path is copied rather than edited (each copy counting one word less) and nodes left
+    /// without words are dropped
+    pub fn remove_sequence(&mut self, sequence: &[L]) -> bool {
+        if !self.finished {
+            self.finish();
+        }
+
+        if !self.contains_sequence(sequence) {
+            return false;
+        }
+
+        self.infix_index = None;
+        self.reverse_index = None;
+        let root = self.root.clone();
+        let mut path = vec![self.copy_node(&root, root.reachable() - 1)];
+        self.root = path[0].clone();
+
+        for label in sequence {
+            let child = path.last().unwrap().child(label).unwrap();
+            let copy = self.copy_node(&child, child.reachable() - 1);
+            path.last().unwrap().insert_edge(label.clone(), copy.clone());
+            path.push(copy);
+        }
+
+        path.last().unwrap().set_terminal(false);
+
+        for (depth, label) in sequence.iter().enumerate().rev() {
+            if path[depth + 1].count() != 0 {
+                break;
+            }
+            path[depth].remove_edge(label);
+            self.trie_nodes -= 1;
+        }
+
+        self.words_added = self.words_added.saturating_sub(1);
+        self.edited = true;
+        self.check_counts(&path, "a remove");
+        true
+    }
+
+    /// Returns a dawg holding the same words that shares every node with this one, unlike `clone` which copies them all.
+    /// Both sides can then be edited with `insert_sequence`/`remove_sequence` (or `insert`/`remove`), which copy
+    /// the nodes they change, so neither ever sees the edits of the other.
+    /// Everything but the nodes is copied as `clone` copies it, the register and the checkpoints excepted: a finished dawg
+    /// needs neither. A dawg that isn't finished has nodes that are still changed in place, so it is cloned instead
+    pub fn fork(&self) -> Self where T: Clone {
+        if !self.finished {
+            return self.clone();
+        }
+
+        Self {
+            dawg_id: NEXT_DAWG_ID.fetch_add(1, Ordering::Relaxed),
+            node: self.node.clone(),
+            minimized_nodes: Register::with_hasher(self.options.hasher),
+            root: self.root.clone(),
+            unchecked_nodes: self.unchecked_nodes.clone(),
+            leaf: self.leaf.clone(),
+            previous_word: self.previous_word.clone(),
+            staged: self.staged.clone(),
+            direction: self.direction,
+            trie_nodes: self.trie_nodes,
+            options: self.options.clone(),
+            words_added: self.words_added,
+            words_skipped: self.words_skipped,
+            finished: self.finished,
+            edited: self.edited,
+            checkpoints: vec![],
+            register_log: vec![],
+            checkpoint_serial: self.checkpoint_serial,
+            infix_index: self.infix_index.clone(),
+            reverse_index: self.reverse_index.clone(),
+        }
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Adds `word` in any order, after applying the options of the dawg (see `insert_sequence`).
+    /// Value is false if the word was already there
+    pub fn insert(&mut self, word: &str) -> Result<bool, DawgError> {
+        let letters = self.text_labels(word)?;
+        Ok(self.insert_sequence(&letters))
+    }
+
+    /// Removes `word` after applying the options of the dawg (see `remove_sequence`), value is false if it wasn't there
+    pub fn remove(&mut self, word: &str) -> Result<bool, DawgError> {
+        let letters = self.text_labels(word)?;
+        Ok(self.remove_sequence(&letters))
+    }
+}
+
+#[cfg(all(test, debug_assertions, feature = "unsync"))]
+mod tests {
+    use crate::{dawg::common::NodeRef, UnsyncDawg};
+
+    #[test]
+    #[should_panic(expected = "an insert left a wrong word count")]
+    fn a_count_on_the_path_is_caught_by_the_insert() {
+        let mut dawg = UnsyncDawg::new();
+        dawg.add_all(["ab", "b"]).unwrap();
+        dawg.finish();
+
+        // the root is copied by any insert, counting one word more than the wrong count it had
+        dawg.root.write(|node| node.count = 7);
+        dawg.insert("ac").unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "testutil")]
+    #[should_panic(expected = "an insert left a wrong word count")]
+    fn a_count_off_the_path_is_caught_by_the_next_insert() {
+        use crate::dawg::common::NodeOps;
+
+        let mut dawg = UnsyncDawg::new();
+        dawg.add_all(["abcd"]).unwrap();
+        dawg.finish();
+
+        // inserting "ae" copies the root and "a", checking their children but not "c" below them
+        let c = dawg.root.child(&'a').unwrap().child(&'b').unwrap().child(&'c').unwrap();
+        c.write(|node| node.count = 7);
+        dawg.insert("ae").unwrap();
+    }
+}
@@ -0,0 +1,242 @@
+//! Querying a CompiledDawg straight from its saved bytes, such as a dictionary baked into the executable:
+//!
+//! ```ignore
+//! static DICT: EmbeddedDawg = EmbeddedDawg::new(include_bytes!("english.dawc"));
+//!
+//! let dict = DICT.view()?;
+//! assert!(dict.contains("apple", MatchMode::Exact));
+//! ```
+//!
+//! Nothing is copied out of the bytes: nodes, edges and dispatch tables are decoded where they lie whenever a query reaches
+//! them. Every number is read with `from_le_bytes` out of a byte array, so the bytes need no alignment (`include_bytes!`
+//! doesn't give any)
+
+use std::{convert::Infallible, ops::ControlFlow, sync::OnceLock};
+
+use crate::{dawg::{compiled::{decode_edge, CompiledNode, Header, EDGE_SIZE, NODE_SIZE, NONE, SLOT_SIZE}, integrity::{read_section, Section}, lexicon::{Lexicon, MatchMode}, noalloc::{self, CompiledNodes, Listing}, options::DawgOptions, serialize::{check_word_counts, topological_order, Cursor}}, error::DawgError};
+
+/// Read-only CompiledDawg borrowing the bytes written by `CompiledDawg::save`, made by `CompiledView::from_bytes`
+#[derive(Debug)]
+pub struct CompiledView<'a> {
+    header: Header,
+    nodes: &'a [u8],
+    edges: &'a [u8],
+    slots: &'a [u8],
+}
+
+impl<'a> CompiledView<'a> {
+    /// Checks `bytes` the way `CompiledDawg::from_bytes` does (checksums, bounds, sorted labels, dispatch tables, no cycle,
+    /// word counts), then answers queries from them without copying them
+    pub fn from_bytes(bytes: &'a [u8]) -> Result<Self, DawgError> {
+        let header = Header::parse(bytes)?;
+        let mut reader = Cursor::new(bytes);
+        reader.position = header.len;
+
+        let nodes = read_section(&mut reader, Section::Nodes, header.node_count as usize * NODE_SIZE, header.checksummed)?;
+        let edges = read_section(&mut reader, Section::Edges, header.edge_count as usize * EDGE_SIZE, header.checksummed)?;
+        let slots = read_section(&mut reader, Section::Slots, header.slot_count as usize * SLOT_SIZE, header.checksummed)?;
+        let view = Self { header, nodes, edges, slots };
+
+        view.validate()?;
+        Ok(view)
+    }
+
+    fn validate(&self) -> Result<(), DawgError> {
+        let invalid = |reason: String| DawgError::InvalidFormat(reason);
+        let mut edges = Cursor::new(self.edges);
+        for edge in 0..self.header.edge_count {
+            decode_edge(&mut edges, edge, self.header.node_count)?;
+        }
+
+        for id in 0..self.header.node_count {
+            let node = CompiledNode::decode(&mut Cursor::new(&self.nodes[id as usize * NODE_SIZE..]))?;
+            if node.first_edge as u64 + node.edges as u64 > self.header.edge_count as u64 {
+                return Err(invalid(format!("node {id} has edges out of bounds")));
+            }
+            if (node.first_edge..node.first_edge + node.edges).skip(1).any(|edge| self.label(edge - 1) >= self.label(edge)) {
+                return Err(invalid(format!("the labels of node {id} aren't sorted")));
+            }
+            if node.table != NONE && self.table_matches(&node) != Ok(true) {
+                return Err(invalid(format!("the dispatch table of node {id} doesn't match its edges")));
+            }
+        }
+
+        let children_of = |id: usize| {
+            let node = self.node(id as u32);
+            (node.first_edge..node.first_edge + node.edges).map(|edge| self.child(edge))
+        };
+        let topological = topological_order(self.header.node_count as usize, children_of)?;
+        let saved = |id: usize| {
+            let node = self.node(id as u32);
+            (node.terminal, node.count)
+        };
+        check_word_counts(&topological, saved, children_of)
+    }
+
+    pub fn options(&self) -> &DawgOptions {
+        &self.header.options
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.header.node_count as usize
+    }
+
+    pub fn edge_count(&self) -> usize {
+        self.header.edge_count as usize
+    }
+
+    fn u32_at(bytes: &[u8], at: usize) -> u32 {
+        u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap())
+    }
+
+    fn node(&self, id: u32) -> CompiledNode {
+        // in bounds and well formed since `validate`
+        CompiledNode::decode(&mut Cursor::new(&self.nodes[id as usize * NODE_SIZE..])).unwrap()
+    }
+
+    fn label(&self, edge: u32) -> char {
+        char::from_u32(Self::u32_at(self.edges, edge as usize * EDGE_SIZE)).unwrap_or(char::REPLACEMENT_CHARACTER)
+    }
+
+    fn child(&self, edge: u32) -> u32 {
+        Self::u32_at(self.edges, edge as usize * EDGE_SIZE + 4)
+    }
+
+    fn slot(&self, slot: u32) -> u32 {
+        Self::u32_at(self.slots, slot as usize * SLOT_SIZE)
+    }
+
+    /// Returns every word in sorted order
+    pub fn words(&self) -> Vec<String> {
+        self.completions("", MatchMode::Exact)
+    }
+
+    /// Writes the words starting with `prefix` to `out` without allocating, see `CompiledDawg::completions_into`
+    pub fn completions_into(&self, prefix: &str, out: &mut [u8], ends: &mut [usize]) -> Listing {
+        noalloc::completions_into(self, prefix, out, ends)
+    }
+
+    /// Hands the words starting with `prefix` to `visit` without allocating, see `CompiledDawg::for_each_completion`
+    pub fn for_each_completion(&self, prefix: &str, path: &mut [u8], visit: impl FnMut(&str) -> ControlFlow<()>) -> Listing {
+        noalloc::for_each_completion(self, prefix, path, visit)
+    }
+}
+
+impl CompiledNodes for CompiledView<'_> {
+    type Error = Infallible;
+
+    fn options(&self) -> &DawgOptions {
+        &self.header.options
+    }
+
+    fn root(&self) -> Option<CompiledNode> {
+        (self.header.node_count > 0).then(|| self.node(0))
+    }
+
+    fn node(&self, id: u32) -> Result<CompiledNode, Infallible> {
+        Ok(CompiledView::node(self, id))
+    }
+
+    fn edge(&self, edge: u32) -> Result<(char, u32), Infallible> {
+        Ok((self.label(edge), self.child(edge)))
+    }
+
+    fn slot(&self, slot: u32) -> Result<u32, Infallible> {
+        Ok(CompiledView::slot(self, slot))
+    }
+
+    fn slot_len(&self) -> u32 {
+        self.header.slot_count
+    }
+}
+
+/// Queries under `MatchMode::Exact` don't allocate but for `completions`, see the `noalloc` module
+impl Lexicon for CompiledView<'_> {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        if mode == MatchMode::Exact {
+            return self.contains_exact(word);
+        }
+        let Ok(found) = self.find(word, mode, false);
+        found.iter().any(|(node, _)| node.terminal)
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        if mode == MatchMode::Exact {
+            return self.contains_prefix_exact(prefix);
+        }
+        let Ok(found) = self.find(prefix, mode, false);
+        found.iter().any(|(node, _)| node.terminal || node.edges > 0)
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        let mut words = vec![];
+
+        let Ok(found) = self.find(prefix, mode, true);
+        for (node, mut word) in found {
+            if node.terminal {
+                words.push(word.clone());
+            }
+            let mut stack = vec![(node, 0)];
+
+            while let Some((node, next_edge)) = stack.last_mut() {
+                if *next_edge == node.edges {
+                    stack.pop();
+                    if !stack.is_empty() {
+                        word.pop();
+                    }
+                    continue;
+                }
+
+                let edge = node.first_edge + *next_edge;
+                *next_edge += 1;
+                let child = self.node(self.child(edge));
+
+                word.push(self.label(edge));
+                if child.terminal {
+                    words.push(word.clone());
+                }
+                stack.push((child, 0));
+            }
+        }
+
+        words.sort();
+        words.dedup();
+        words
+    }
+
+    fn word_count(&self) -> usize {
+        match self.header.node_count {
+            0 => 0,
+            _ => self.node(0).count as usize,
+        }
+    }
+}
+
+/// Handle on a CompiledDawg saved in a `'static` byte slice, which can be made in a `const` or a `static`: checking the
+/// bytes is deferred to the first call to `view`
+#[derive(Debug)]
+pub struct EmbeddedDawg {
+    bytes: &'static [u8],
+    view: OnceLock<CompiledView<'static>>,
+}
+
+impl EmbeddedDawg {
+    pub const fn new(bytes: &'static [u8]) -> Self {
+        Self { bytes, view: OnceLock::new() }
+    }
+
+    pub const fn bytes(&self) -> &'static [u8] {
+        self.bytes
+    }
+
+    /// Returns the view over the bytes, checking them the first time (see `CompiledView::from_bytes`). A failed check isn't
+    /// remembered, every call checks again until one succeeds
+    pub fn view(&self) -> Result<&CompiledView<'static>, DawgError> {
+        if let Some(view) = self.view.get() {
+            return Ok(view);
+        }
+
+        let view = CompiledView::from_bytes(self.bytes)?;
+        Ok(self.view.get_or_init(|| view))
+    }
+}
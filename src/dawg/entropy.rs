@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use crate::dawg::common::{Dawg, NodeOps, Wrapper};
+
+/// Outcome of `Dawg::letter_entropy_at` and `letter_entropy` standing for the word ending where the next letter would be
+pub const END_OF_WORD: char = '\u{0}';
+
+/// Returns each letter (or `END_OF_WORD`) found at `position` (counted in chars) in `candidates`, with its share of the
+/// Shannon entropy (in bits) of that letter: `-p * log2(p)`, `p` being the proportion of the candidates it stands for.
+/// A candidate shorter than `position` ends before it. Works on the words of any search, such as `Dawg::hangman` or
+/// `Dawg::wordle_filter`, sorted like `Dawg::letter_entropy_at`
+pub fn letter_entropy<I, S>(candidates: I, position: usize) -> Vec<(char, f64)> where I: IntoIterator<Item = S>, S: AsRef<str> {
+    let mut counts = BTreeMap::<char, usize>::new();
+    for candidate in candidates {
+        *counts.entry(candidate.as_ref().chars().nth(position).unwrap_or(END_OF_WORD)).or_default() += 1;
+    }
+    entropies(counts.into_iter().collect())
+}
+
+/// `-p * log2(p)` of every outcome, from the outcome splitting the words most to the least, the smaller letter first on a tie
+fn entropies(counts: Vec<(char, usize)>) -> Vec<(char, f64)> {
+    let total = counts.iter().map(|(_, count)| count).sum::<usize>() as f64;
+    let mut entropies = counts.into_iter()
+        .filter(|(_, count)| *count > 0)
+        .map(|(letter, count)| {
+            let p = count as f64 / total;
+            (letter, -p * p.log2())
+        })
+        .collect::<Vec<_>>();
+
+    entropies.sort_by(|(a, x), (b, y)| y.total_cmp(x).then(a.cmp(b)));
+    entropies
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the letters that can follow `prefix` (matched exactly), along with `END_OF_WORD` if the prefix is a word,
+    /// each with its share of the entropy of the next letter among the completions of the prefix (see `letter_entropy`).
+    /// The proportions are read from the reachable counts of the children of the prefix, without listing the completions.
+    /// Their sum is the information the next letter gives, and the letter with the largest share is the one splitting the
+    /// completions most evenly
+    pub fn letter_entropy_at(&self, prefix: &str) -> Vec<(char, f64)> {
+        let Some(node) = self.find_sequence(&prefix.chars().collect::<Vec<_>>()) else {
+            return vec![];
+        };
+
+        let mut counts = node.children().into_iter().map(|(label, child)| (label, child.reachable())).collect::<Vec<_>>();
+        if node.is_terminal() {
+            counts.push((END_OF_WORD, 1));
+        }
+        entropies(counts)
+    }
+}
@@ -0,0 +1,190 @@
+use std::{cell::RefCell, fs::{self, File}, io::{self, BufRead, BufReader, BufWriter, Write}, path::{Path, PathBuf}, rc::Rc, sync::atomic::{AtomicUsize, Ordering}};
+
+use crate::{dawg::{common::{Dawg, Wrapper}, options::DawgOptions, setops::KMerge, wordlist::{snippet, trim_line}}, error::DawgError};
+
+/// Source of the names of the spill directories created by this process
+static NEXT_SPILL_DIR: AtomicUsize = AtomicUsize::new(0);
+
+/// Settings of `Dawg::from_unsorted_file`
+#[derive(Debug, Clone)]
+pub struct ExternalSortOptions {
+    options: DawgOptions,
+    chunk_size: usize,
+    temp_dir: PathBuf,
+}
+
+impl Default for ExternalSortOptions {
+    /// Chunks of 64MiB spilled to the temporary directory of the system
+    fn default() -> Self {
+        Self { options: DawgOptions::default(), chunk_size: 64 << 20, temp_dir: std::env::temp_dir() }
+    }
+}
+
+impl ExternalSortOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options of the dawg being built, only their sort order doesn't matter since the words are sorted here
+    pub fn dawg_options(mut self, options: DawgOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Number of bytes of words held in memory before they are sorted and spilled to disk
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        self.chunk_size = chunk_size.max(1);
+        self
+    }
+
+    /// Directory in which the sorted chunks are written, each build using a directory of its own inside it
+    pub fn temp_dir(mut self, temp_dir: impl Into<PathBuf>) -> Self {
+        self.temp_dir = temp_dir.into();
+        self
+    }
+}
+
+/// Directory holding the files spilled by a build (the sorted chunks here, or the register entries evicted from memory),
+/// removed along with them once dropped (even while unwinding)
+#[derive(Debug)]
+pub(crate) struct SpillDir {
+    pub(crate) path: PathBuf,
+    runs: usize,
+}
+
+impl SpillDir {
+    /// Creates a directory of its own inside `temp_dir`, named after `purpose`
+    pub(crate) fn create(temp_dir: &Path, purpose: &str) -> io::Result<Self> {
+        loop {
+            let name = format!("dawging-{purpose}-{}-{}", std::process::id(), NEXT_SPILL_DIR.fetch_add(1, Ordering::Relaxed));
+            let path = temp_dir.join(name);
+
+            match fs::create_dir(&path) {
+                Ok(()) => return Ok(Self { path, runs: 0 }),
+                Err(err) if err.kind() == io::ErrorKind::AlreadyExists => continue,
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
+    /// Writes `words` (sorted and deduplicated) to a new run file
+    fn spill(&mut self, words: &[String]) -> io::Result<()> {
+        let mut run = BufWriter::new(File::create(self.path.join(self.runs.to_string()))?);
+        for word in words {
+            run.write_all(word.as_bytes())?;
+            run.write_all(b"\n")?;
+        }
+        run.flush()?;
+        self.runs += 1;
+        Ok(())
+    }
+
+    /// Reads the words of every run back, the first read error being kept in `error` (which also ends that run)
+    fn runs(&self, error: &Rc<RefCell<Option<io::Error>>>) -> io::Result<Vec<impl Iterator<Item = String>>> {
+        (0..self.runs).map(|run| {
+            let error = Rc::clone(error);
+            let lines = BufReader::new(File::open(self.path.join(run.to_string()))?).lines();
+            Ok(lines.map_while(move |line| line.map_err(|err| *error.borrow_mut() = Some(err)).ok()))
+        }).collect()
+    }
+}
+
+impl Drop for SpillDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
+
+/// Sorts and deduplicates `chunk`
+fn sort_chunk(chunk: &mut Vec<String>) {
+    chunk.sort_unstable();
+    chunk.dedup();
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Builds a finished dawg from a file of words in any order, one per line (see `from_unsorted_reader`)
+    pub fn from_unsorted_file(path: impl AsRef<Path>, options: &ExternalSortOptions) -> Result<Self, DawgError> {
+        Self::from_unsorted_reader(BufReader::new(File::open(path)?), options)
+    }
+
+    /// Builds a finished dawg from words in any order, one per line, with an external merge sort:
+    /// words are read in chunks of `chunk_size` bytes, each chunk sorted and written to disk, and the sorted chunks
+    /// merged straight into the dawg, so at most one chunk is held in memory besides the dawg.
+    ///
+    /// Blank lines are skipped and repeated words added once. The words are folded and checked against the alphabet
+    /// of the options, invalid utf-8 is reported as a `DawgError::Line`. The spilled chunks are removed whatever happens
+    pub fn from_unsorted_reader<R: BufRead>(mut reader: R, options: &ExternalSortOptions) -> Result<Self, DawgError> {
+        let mut dawg = Self::with_options(options.options.clone())?;
+        let mut spill: Option<SpillDir> = None;
+        let mut chunk = vec![];
+        let mut chunk_bytes = 0;
+        let mut bytes = vec![];
+        let mut line_number = 0;
+
+        loop {
+            bytes.clear();
+            if reader.read_until(b'\n', &mut bytes)? == 0 {
+                break;
+            }
+            line_number += 1;
+
+            let line = trim_line(&bytes, line_number == 1);
+            let word = std::str::from_utf8(line).map_err(|_| DawgError::Line {
+                line: line_number,
+                snippet: snippet(&String::from_utf8_lossy(line)),
+                error: Box::new(DawgError::InvalidUtf8),
+            })?;
+
+            if word.trim().is_empty() {
+                continue;
+            }
+
+            let word = dawg.options.fold(word);
+            chunk_bytes += word.len();
+            chunk.push(word);
+
+            if chunk_bytes >= options.chunk_size {
+                sort_chunk(&mut chunk);
+                let spill = match &mut spill {
+                    Some(spill) => spill,
+                    None => spill.insert(SpillDir::create(&options.temp_dir, "sort")?),
+                };
+                spill.spill(&chunk)?;
+                chunk.clear();
+                chunk_bytes = 0;
+            }
+        }
+
+        sort_chunk(&mut chunk);
+        match spill {
+            // everything fit in a single chunk, which needn't go through the disk
+            None => {
+                for word in &chunk {
+                    dawg.add_sequence(&dawg.text_labels(word)?)?;
+                }
+            }
+            Some(mut spill) => {
+                if !chunk.is_empty() {
+                    spill.spill(&chunk)?;
+                }
+                drop(chunk);
+
+                let error = Rc::new(RefCell::new(None));
+                for word in KMerge::new(spill.runs(&error)?) {
+                    if let Some(err) = error.borrow_mut().take() {
+                        return Err(err.into());
+                    }
+                    dawg.add_sequence(&dawg.text_labels(&word)?)?;
+                }
+
+                let failed = error.borrow_mut().take();
+                if let Some(err) = failed {
+                    return Err(err.into());
+                }
+            }
+        }
+
+        dawg.finish();
+        Ok(dawg)
+    }
+}
@@ -0,0 +1,272 @@
+//! Probabilistic filters over the words of a dawg, answering "is this probably a word" in a fraction of its size.
+//!
+//! A filter never rejects a word of the dawg, and wrongly accepts other strings at about the rate it was built for. Saved
+//! filters are read back by `WordFilter`, which needs nothing else from this crate, and are simple enough to be read by
+//! other languages. All numbers are little-endian:
+//!
+//! | offset | size | content                                                                                   |
+//! |--------|------|-------------------------------------------------------------------------------------------|
+//! | 0      | 4    | magic bytes `DAWF`                                                                        |
+//! | 4      | 1    | version, 1                                                                                |
+//! | 5      | 1    | kind: 0 for a Bloom filter, 1 for an XOR filter                                           |
+//! | 6      | 1    | flags: bit 0 set if words are lowercased (as `str::to_lowercase` or JS `toLowerCase`) first |
+//! | 7      | 1    | 0                                                                                         |
+//!
+//! A Bloom filter follows with `m` (u32, number of bits), `k` (u32, number of hashes) and `ceil(m / 8)` bytes of bits, bit
+//! `i` being `(bytes[i >> 3] >> (i & 7)) & 1`. A word may be present if bits `(h1 + i * h2) % m` are all set for `i` in
+//! `0..k`, with `h1 = hash(word, 0)`, `h2 = hash(word, 0x9E3779B9)` and the sum and product wrapping at 32 bits.
+//!
+//! An XOR filter follows with `seed` (u32), `b` (u32, length of each of its 3 blocks), `w` (u8, width in bytes of a
+//! fingerprint: 1, 2 or 4), 3 bytes of 0, and `3 * b` fingerprints of `w` bytes. A word may be present if `b > 0` and
+//! the fingerprints at `hash(word, seed) % b`, `b + hash(word, seed + 1) % b` and `2 * b + hash(word, seed + 2) % b`
+//! xor to the low `8 * w` bits of `hash(word, seed + 3)`.
+//!
+//! `hash` is FNV-1a over the utf-8 bytes of the word, starting from `0x811C9DC5 ^ seed`, followed by the murmur3 finalizer:
+//!
+//! ```js
+//! function hash(bytes, seed) {
+//!   let h = (0x811c9dc5 ^ seed) >>> 0;
+//!   for (const byte of bytes) h = Math.imul(h ^ byte, 0x01000193) >>> 0;
+//!   h = Math.imul(h ^ (h >>> 16), 0x85ebca6b) >>> 0;
+//!   h = Math.imul(h ^ (h >>> 13), 0xc2b2ae35) >>> 0;
+//!   return (h ^ (h >>> 16)) >>> 0;
+//! }
+//! ```
+
+use crate::{dawg::common::{Dawg, Wrapper}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWF";
+const VERSION: u8 = 1;
+/// Size in bytes of the fixed part of a saved filter, before the fields of its kind
+const HEADER_SIZE: usize = 8;
+/// Seed of the second hash of a Bloom filter
+const BLOOM_SEED: u32 = 0x9E37_79B9;
+const LOWERCASE: u8 = 1;
+
+/// Which filter `Dawg::export_filter` builds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum FilterKind {
+    /// about 1.44 * log2(1 / fp_rate) bits per word
+    Bloom,
+    /// XOR filter with 8, 16 or 32 bit fingerprints: 1.23 fingerprints per word, its false-positive rate being at most
+    /// the one asked for (1 / 256, 1 / 65536 or 1 / 2^32)
+    Xor,
+}
+
+/// 32 bit hash of the filters, see the `filter` module
+fn hash(bytes: &[u8], seed: u32) -> u32 {
+    let mut h = 0x811C_9DC5 ^ seed;
+    for byte in bytes {
+        h = (h ^ *byte as u32).wrapping_mul(0x0100_0193);
+    }
+
+    h = (h ^ (h >> 16)).wrapping_mul(0x85EB_CA6B);
+    h = (h ^ (h >> 13)).wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
+
+/// Slots of `word` in an XOR filter whose blocks are `block` long, and its fingerprint
+fn xor_slots(word: &[u8], seed: u32, block: u32) -> ([usize; 3], u32) {
+    let slot = |index: u32| (index * block + hash(word, seed.wrapping_add(index)) % block) as usize;
+    ([slot(0), slot(1), slot(2)], hash(word, seed.wrapping_add(3)))
+}
+
+fn bloom_bits(word: &[u8], bits: u32, hashes: u32) -> impl Iterator<Item = u32> {
+    let (h1, h2) = (hash(word, 0), hash(word, BLOOM_SEED));
+    (0..hashes).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % bits)
+}
+
+/// Fingerprints of an XOR filter over `words`, `None` if the seed can't place every word
+fn build_xor(words: &[Vec<u8>], seed: u32, block: u32) -> Option<Vec<u32>> {
+    let slots = words.iter().map(|word| xor_slots(word, seed, block)).collect::<Vec<_>>();
+    let mut counts = vec![0u32; 3 * block as usize];
+    let mut keys = vec![0usize; 3 * block as usize];
+
+    for (key, (key_slots, _)) in slots.iter().enumerate() {
+        for slot in key_slots {
+            counts[*slot] += 1;
+            keys[*slot] ^= key;
+        }
+    }
+
+    // peels the words off the slots holding a single one, the last peeled being assigned first
+    let mut queue = (0..counts.len()).filter(|slot| counts[*slot] == 1).collect::<Vec<_>>();
+    let mut peeled = Vec::with_capacity(words.len());
+    while let Some(slot) = queue.pop() {
+        if counts[slot] != 1 {
+            continue;
+        }
+
+        let key = keys[slot];
+        peeled.push((key, slot));
+        for other in slots[key].0 {
+            counts[other] -= 1;
+            keys[other] ^= key;
+            if counts[other] == 1 {
+                queue.push(other);
+            }
+        }
+    }
+
+    if peeled.len() != words.len() {
+        return None;
+    }
+
+    let mut fingerprints = vec![0u32; 3 * block as usize];
+    for (key, slot) in peeled.into_iter().rev() {
+        let ([a, b, c], fingerprint) = slots[key];
+        fingerprints[slot] = fingerprint ^ fingerprints[a] ^ fingerprints[b] ^ fingerprints[c];
+    }
+    Some(fingerprints)
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Builds a filter of `kind` over the words of the finished dawg, wrongly accepting about `fp_rate` of other strings,
+    /// and returns it saved (see the `filter` module for the format and `WordFilter` to query it). Queries are lowercased
+    /// first if the dawg folds case
+    pub fn export_filter(&self, kind: FilterKind, fp_rate: f64) -> Vec<u8> {
+        let fp_rate = fp_rate.clamp(f64::MIN_POSITIVE, 0.5);
+        let words = self.words().map(String::into_bytes).collect::<Vec<_>>();
+
+        let mut out = vec![];
+        out.extend_from_slice(MAGIC);
+        out.push(VERSION);
+        out.push(match kind {
+            FilterKind::Bloom => 0,
+            FilterKind::Xor => 1,
+        });
+        out.push(if self.options.case_folding { LOWERCASE } else { 0 });
+        out.push(0);
+
+        match kind {
+            FilterKind::Bloom => {
+                let ln2 = std::f64::consts::LN_2;
+                let bits = (-(words.len().max(1) as f64) * fp_rate.ln() / (ln2 * ln2)).ceil().clamp(8.0, u32::MAX as f64) as u32;
+                let hashes = ((bits as f64 / words.len().max(1) as f64) * ln2).round().clamp(1.0, 32.0) as u32;
+
+                let mut bytes = vec![0u8; bits.div_ceil(8) as usize];
+                for word in &words {
+                    for bit in bloom_bits(word, bits, hashes) {
+                        bytes[(bit >> 3) as usize] |= 1 << (bit & 7);
+                    }
+                }
+
+                out.extend_from_slice(&bits.to_le_bytes());
+                out.extend_from_slice(&hashes.to_le_bytes());
+                out.extend_from_slice(&bytes);
+            }
+            FilterKind::Xor => {
+                let width: u8 = match (1.0 / fp_rate).log2().ceil() as u32 {
+                    0..=8 => 1,
+                    9..=16 => 2,
+                    _ => 4,
+                };
+                let block = match words.len() {
+                    0 => 0,
+                    len => (len as f64 * 1.23 / 3.0).ceil() as u32 + 11,
+                };
+                // a seed that can't place every word is rare, the next one is tried
+                let (seed, fingerprints) = (0u32..).find_map(|seed| build_xor(&words, seed, block).map(|fingerprints| (seed, fingerprints))).unwrap();
+
+                out.extend_from_slice(&seed.to_le_bytes());
+                out.extend_from_slice(&block.to_le_bytes());
+                out.extend_from_slice(&[width, 0, 0, 0]);
+                for fingerprint in fingerprints {
+                    out.extend_from_slice(&fingerprint.to_le_bytes()[..width as usize]);
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Filter {
+    Bloom { bits: u32, hashes: u32, bytes: Vec<u8> },
+    Xor { seed: u32, block: u32, width: usize, fingerprints: Vec<u8> },
+}
+
+/// Filter saved by `Dawg::export_filter`, telling whether a string may be one of the words of the dawg
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordFilter {
+    filter: Filter,
+    lowercase: bool,
+}
+
+impl WordFilter {
+    /// Reads a filter saved by `Dawg::export_filter`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DawgError> {
+        let invalid = |reason: &str| DawgError::InvalidFormat(format!("filter: {reason}"));
+        let u32_at = |at: usize| bytes.get(at..at + 4).map(|field| u32::from_le_bytes(field.try_into().unwrap())).ok_or_else(|| invalid("unexpected end of data"));
+
+        if bytes.len() < HEADER_SIZE || &bytes[..4] != MAGIC {
+            return Err(invalid("missing DAWF magic bytes"));
+        }
+        if bytes[4] != VERSION {
+            return Err(invalid(&format!("unsupported version {}", bytes[4])));
+        }
+
+        let (filter, len) = match bytes[5] {
+            0 => {
+                let (bits, hashes) = (u32_at(HEADER_SIZE)?, u32_at(HEADER_SIZE + 4)?);
+                if bits == 0 || hashes == 0 {
+                    return Err(invalid("a Bloom filter needs bits and hashes"));
+                }
+                let len = bits.div_ceil(8) as usize;
+                let bytes = bytes.get(HEADER_SIZE + 8..HEADER_SIZE + 8 + len).ok_or_else(|| invalid("unexpected end of data"))?.to_vec();
+                (Filter::Bloom { bits, hashes, bytes }, HEADER_SIZE + 8 + len)
+            }
+            1 => {
+                let (seed, block) = (u32_at(HEADER_SIZE)?, u32_at(HEADER_SIZE + 4)?);
+                let width = *bytes.get(HEADER_SIZE + 8).ok_or_else(|| invalid("unexpected end of data"))? as usize;
+                if ![1, 2, 4].contains(&width) {
+                    return Err(invalid(&format!("fingerprints can't be {width} bytes wide")));
+                }
+                let len = 3 * block as usize * width;
+                let fingerprints = bytes.get(HEADER_SIZE + 12..HEADER_SIZE + 12 + len).ok_or_else(|| invalid("unexpected end of data"))?.to_vec();
+                (Filter::Xor { seed, block, width, fingerprints }, HEADER_SIZE + 12 + len)
+            }
+            kind => return Err(invalid(&format!("unknown kind {kind}"))),
+        };
+
+        match len == bytes.len() {
+            true => Ok(Self { filter, lowercase: bytes[6] & LOWERCASE != 0 }),
+            false => Err(invalid("trailing bytes")),
+        }
+    }
+
+    pub fn kind(&self) -> FilterKind {
+        match self.filter {
+            Filter::Bloom { .. } => FilterKind::Bloom,
+            Filter::Xor { .. } => FilterKind::Xor,
+        }
+    }
+
+    /// value is false if `word` is certainly not a word of the dawg, true if it probably is
+    pub fn maybe_contains(&self, word: &str) -> bool {
+        let lowered;
+        let word = match self.lowercase {
+            true => {
+                lowered = word.to_lowercase();
+                lowered.as_bytes()
+            }
+            false => word.as_bytes(),
+        };
+
+        match &self.filter {
+            Filter::Bloom { bits, hashes, bytes } => bloom_bits(word, *bits, *hashes).all(|bit| bytes[(bit >> 3) as usize] >> (bit & 7) & 1 == 1),
+            Filter::Xor { block: 0, .. } => false,
+            Filter::Xor { seed, block, width, fingerprints } => {
+                let fingerprint_at = |slot: usize| {
+                    let mut bytes = [0; 4];
+                    bytes[..*width].copy_from_slice(&fingerprints[slot * width..(slot + 1) * width]);
+                    u32::from_le_bytes(bytes)
+                };
+                let ([a, b, c], fingerprint) = xor_slots(word, *seed, *block);
+                let mask = if *width == 4 { u32::MAX } else { (1 << (8 * width)) - 1 };
+                fingerprint_at(a) ^ fingerprint_at(b) ^ fingerprint_at(c) == fingerprint & mask
+            }
+        }
+    }
+}
@@ -0,0 +1,134 @@
+use std::collections::BTreeSet;
+
+use crate::dawg::{lexicon::{Lexicon, MatchMode}, setops::KMerge};
+
+/// Words laid over the base of a `FilteredDawg`: a set of words that can be changed at any time, along with lexicons
+/// (`&Dawg`, `Arc<Dawg>`, ...) for the larger lists
+#[derive(Default)]
+pub struct Overlay<'a> {
+    words: BTreeSet<String>,
+    lexicons: Vec<Box<dyn Lexicon + 'a>>,
+}
+
+impl<'a> Overlay<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `word` to the set, value is false if it was already there
+    pub fn insert(&mut self, word: &str) -> bool {
+        self.words.insert(word.to_owned())
+    }
+
+    /// Removes `word` from the set, value is false if it wasn't there (the lexicons are left untouched)
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.words.remove(word)
+    }
+
+    pub fn push_lexicon(&mut self, lexicon: impl Lexicon + 'a) {
+        self.lexicons.push(Box::new(lexicon));
+    }
+
+    /// Returns the words of the set in sorted order
+    pub fn words(&self) -> impl Iterator<Item = &str> + '_ {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// value is true if neither the set nor the lexicons hold a word
+    pub fn is_empty(&self) -> bool {
+        self.words.is_empty() && self.lexicons.iter().all(|lexicon| lexicon.word_count() == 0)
+    }
+
+    /// value is true if `word` is in the set or one of the lexicons, exactly
+    fn holds(&self, word: &str) -> bool {
+        self.words.contains(word) || self.lexicons.iter().any(|lexicon| lexicon.contains(word, MatchMode::Exact))
+    }
+
+    /// Returns the words of the set and the lexicons starting with `prefix` in sorted order, without duplicates
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        let words = match mode {
+            MatchMode::Exact => self.words.range(prefix.to_owned()..).take_while(|word| word.starts_with(prefix)).cloned().collect::<Vec<_>>(),
+            mode => self.words.iter().filter(|word| starts_with(word, prefix, mode)).cloned().collect(),
+        };
+
+        let lexicons = self.lexicons.iter().map(|lexicon| lexicon.completions(prefix, mode).into_iter());
+        KMerge::new(std::iter::once(words.into_iter()).chain(lexicons)).collect()
+    }
+}
+
+/// value is true if the first letters of `word` match `prefix` under `mode`
+fn starts_with(word: &str, prefix: &str, mode: MatchMode) -> bool {
+    let mut letters = word.chars();
+    prefix.chars().all(|letter| letters.next().is_some_and(|label| mode.matches(label, letter)))
+}
+
+/// A base lexicon with words added (`allowed`) and taken out (`denied`) without rebuilding it: its words are those of the base
+/// or the allowed overlay, unless they are denied. Denying wins over allowing, and words are allowed and denied exactly as
+/// they are stored (a query under a case-insensitive mode matches the words left once the overlays are applied).
+///
+/// The overlays can be changed at any time, through `allowed_mut` and `denied_mut`. Queries reaching words of the base have
+/// to look them up in the denied overlay: `contains_prefix`, `completions` and `count_completions` go through the matching
+/// words of the base, the more of them the slower
+pub struct FilteredDawg<'a> {
+    base: Box<dyn Lexicon + 'a>,
+    allowed: Overlay<'a>,
+    denied: Overlay<'a>,
+}
+
+impl<'a> FilteredDawg<'a> {
+    /// Filters `base` (`&Dawg`, `Arc<Dawg>`, a MultiDawg, ...), through overlays empty for now
+    pub fn new(base: impl Lexicon + 'a) -> Self {
+        Self { base: Box::new(base), allowed: Overlay::new(), denied: Overlay::new() }
+    }
+
+    pub fn allowed(&self) -> &Overlay<'a> {
+        &self.allowed
+    }
+
+    pub fn allowed_mut(&mut self) -> &mut Overlay<'a> {
+        &mut self.allowed
+    }
+
+    pub fn denied(&self) -> &Overlay<'a> {
+        &self.denied
+    }
+
+    pub fn denied_mut(&mut self) -> &mut Overlay<'a> {
+        &mut self.denied
+    }
+}
+
+impl<'a> Lexicon for FilteredDawg<'a> {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        if mode == MatchMode::Exact {
+            return (self.base.contains(word, mode) || self.allowed.holds(word)) && !self.denied.holds(word);
+        }
+
+        // the stored words matching `word` are its completions of the same length
+        let len = word.chars().count();
+        self.completions(word, mode).iter().any(|completion| completion.chars().count() == len)
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        if self.denied.is_empty() {
+            return self.base.contains_prefix(prefix, mode) || !self.allowed.completions(prefix, mode).is_empty();
+        }
+        !self.completions(prefix, mode).is_empty()
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        let words = [self.base.completions(prefix, mode).into_iter(), self.allowed.completions(prefix, mode).into_iter()];
+        KMerge::new(words).filter(|word| !self.denied.holds(word)).collect()
+    }
+
+    /// Words of the base and the allowed overlay, less the denied words found in either
+    fn word_count(&self) -> usize {
+        let allowed = self.allowed.completions("", MatchMode::Exact);
+        let added = allowed.iter().filter(|word| !self.base.contains(word, MatchMode::Exact)).count();
+        let denied = self.denied.completions("", MatchMode::Exact).iter()
+            .filter(|word| self.base.contains(word, MatchMode::Exact) || allowed.binary_search(word).is_ok())
+            .count();
+
+        self.base.word_count() + added - denied
+    }
+}
@@ -0,0 +1,158 @@
+//! Words tagged with a small set of attributes (proper noun, archaic, regional spelling, ...), one bit each.
+//!
+//! A saved FlaggedDawg is made of the magic bytes `DAWA`, the version of its layout (u16), the length (u64) and the bytes of
+//! the dawg as written by `Dawg::save`, then the number of words (u64) and the flags (u32) of every word in their sorted
+//! order. Numbers are little-endian
+
+use std::io::{Read, Write};
+
+use crate::{dawg::{common::{Dawg, NodeOps, Wrapper}, map::DawgMap, serialize::{write_u32, write_u64, Cursor}}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWA";
+const VERSION: u16 = 1;
+
+/// Map from words to their flags, a `u32` whose bits are attributes chosen by the caller.
+///
+/// The flags are kept aside in the order of the words, like any value of a `DawgMap`: the dawg itself only holds the words,
+/// so it shares as much structure as the dawg of the same words without flags would, whatever their flags.
+///
+/// Queries taking a `mask` and a `require` only see the words whose flags have none of the bits of `mask` and all of
+/// those of `require`, see `FlaggedDawg::completions_flagged`
+pub type FlaggedDawg<T> = DawgMap<T, u32>;
+
+/// value is true if `flags` has none of the bits of `mask` and all of those of `require`
+fn passes(flags: u32, mask: u32, require: u32) -> bool {
+    flags & mask == 0 && flags & require == require
+}
+
+impl<T> FlaggedDawg<T> where T: Wrapper {
+    /// Inserts `word` along with its flags, see `DawgMap::try_insert`
+    pub fn add_flagged(&mut self, word: &str, flags: u32) -> Result<(), DawgError> {
+        self.try_insert(word, flags)
+    }
+
+    /// Returns the flags of `word` (folded by the options), if it is in the dawg
+    pub fn flags_of(&self, word: &str) -> Option<u32> {
+        self.get(word).copied()
+    }
+
+    /// value is true if `word` (folded by the options) is in the dawg with flags passing `mask` and `require`
+    pub fn contains_flagged(&self, word: &str, mask: u32, require: u32) -> bool {
+        self.flags_of(word).is_some_and(|flags| passes(flags, mask, require))
+    }
+
+    /// Returns the words starting with `prefix` (folded by the options) whose flags have none of the bits of `mask` and all
+    /// of those of `require`, in sorted order. The rank of each word is carried down from the prefix, so its flags are read
+    /// without looking the word up again
+    pub fn completions_flagged(&self, prefix: &str, mask: u32, require: u32) -> Vec<String> {
+        let prefix = self.dawg().options.fold_chars(prefix.chars()).collect::<Vec<_>>();
+        let Some((node, rank)) = self.locate(&prefix) else { return vec![] };
+
+        let flags = self.values();
+        let mut words = vec![];
+        let mut stack = vec![(node, prefix, rank)];
+
+        while let Some((node, word, mut rank)) = stack.pop() {
+            if node.is_terminal() {
+                if passes(flags[rank], mask, require) {
+                    words.push(word.iter().collect());
+                }
+                rank += 1;
+            }
+
+            let mut children = vec![];
+            for (label, child) in node.children() {
+                let count = child.reachable();
+                let mut word = word.clone();
+                word.push(label);
+                children.push((child, word, rank));
+                rank += count;
+            }
+            stack.extend(children.into_iter().rev());
+        }
+
+        words
+    }
+
+    /// Returns every word with its flags in sorted order, leaving out the words whose flags don't pass `mask` and `require`
+    pub fn iter_flagged(&self, mask: u32, require: u32) -> impl Iterator<Item = (String, u32)> + '_ {
+        self.iter().map(|(word, flags)| (word, *flags)).filter(move |(_, flags)| passes(*flags, mask, require))
+    }
+
+    /// Returns the node reached by `letters` along with the rank of the first word below it
+    fn locate(&self, letters: &[char]) -> Option<(T::Node, usize)> {
+        let mut node = self.dawg().root.clone();
+        let mut rank = 0;
+
+        for letter in letters {
+            rank += node.is_terminal() as usize;
+
+            let mut next = None;
+            for (label, child) in node.children() {
+                if label == *letter {
+                    next = Some(child);
+                    break;
+                }
+                rank += child.reachable();
+            }
+            node = next?;
+        }
+
+        Some((node, rank))
+    }
+
+    /// Encodes the finished dawg and the flags of its words, see the `flags` module. Fails with `DawgError::NotFinished`
+    /// if the dawg isn't finished
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DawgError> {
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&VERSION.to_le_bytes());
+
+        let dawg = self.dawg().to_bytes()?;
+        write_u64(&mut out, dawg.len() as u64);
+        out.extend_from_slice(&dawg);
+
+        write_u64(&mut out, self.len() as u64);
+        for &flags in self.values() {
+            write_u32(&mut out, flags);
+        }
+        Ok(out)
+    }
+
+    /// Writes the FlaggedDawg to `writer`, see `to_bytes`
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        writer.write_all(&self.to_bytes()?)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Rebuilds a finished FlaggedDawg from the bytes written by `save`
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(DawgError::InvalidFormat(String::from("missing FlaggedDawg magic bytes")));
+        }
+
+        let version = reader.u16()?;
+        if version > VERSION {
+            return Err(DawgError::NewerVersion { version, supported: VERSION });
+        }
+
+        let len = reader.u64()? as usize;
+        let dawg = Dawg::<T>::from_bytes(reader.bytes(len)?)?;
+        Self::check_options(&dawg.options)?;
+
+        let words = reader.u64()? as usize;
+        if words != dawg.root.count() {
+            return Err(DawgError::InvalidFormat(format!("{words} flags saved for {} words", dawg.root.count())));
+        }
+
+        let flags = (0..words).map(|_| reader.u32()).collect::<Result<Vec<_>, _>>()?;
+        Ok(Self::from_parts(dawg, flags))
+    }
+
+    /// Reads a FlaggedDawg written by `save` from `reader`
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+}
@@ -0,0 +1,487 @@
+//! Dictionaries split into one dawg per key (the first letters of their words), loaded one shard at a time.
+//!
+//! A forest saved to a directory holds a file per shard, the dawg of its words as written by `Dawg::save`, named after the
+//! utf-8 bytes of its key in hexadecimal (`shard-61.dawg` for the words starting with "a"), along with an index file
+//! `forest.idx`. A forest packed into a single file holds the same pieces back to back. All numbers are little-endian:
+//!
+//! - index file: magic bytes `DAWT`, version (u16), then the index
+//! - packed file: magic bytes `DAWT`, version (u16), the saved shards one after the other, the index, and the offset of the
+//!   index from the start of the file (u64)
+//!
+//! The index is its length (u32), then the options of the dawgs (as saved along with a dawg, preceded by their length as
+//! a u32), the key length in chars (u32), the number of shards (u32) and for each shard in the order of their keys: the
+//! length of its key (u32), its key, its number of words (u64), and where it starts in the packed file and its length
+//! (u64 each, 0 in an index file). A CRC-32 of the index (from the options on) follows it
+
+use std::{collections::BTreeMap, fmt::Debug, fs::{self, File}, io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write}, path::{Path, PathBuf}, sync::{Arc, Mutex}};
+
+use crate::{dawg::{common::{Dawg, Wrapper}, integrity::{read_section, write_section, Section}, lexicon::{Lexicon, MatchMode}, lru::Lru, options::DawgOptions, serialize::{write_u32, write_u64, Cursor}, setops::KMerge}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWT";
+const VERSION: u16 = 1;
+const INDEX_FILE: &str = "forest.idx";
+
+/// Settings of a `DawgForest`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ForestOptions {
+    options: DawgOptions,
+    key_len: usize,
+    resident_shards: usize,
+}
+
+impl Default for ForestOptions {
+    /// Shards keyed by the first letter of their words, at most 64 of them held in memory
+    fn default() -> Self {
+        Self { options: DawgOptions::default(), key_len: 1, resident_shards: 64 }
+    }
+}
+
+impl ForestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Options of the dawg of every shard, only used by `DawgForest::build` (a saved forest keeps its own)
+    pub fn dawg_options(mut self, options: DawgOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Number of leading letters making the key of a word (a shorter word is its own key), only used by
+    /// `DawgForest::build`. The larger it is, the more shards and the smaller they are
+    pub fn key_len(mut self, key_len: usize) -> Self {
+        self.key_len = key_len;
+        self
+    }
+
+    /// Most shards of a saved forest held in memory at once, the least recently used one being dropped to make room
+    /// for another. A forest built in memory keeps all of its shards
+    pub fn resident_shards(mut self, resident_shards: usize) -> Self {
+        self.resident_shards = resident_shards.max(1);
+        self
+    }
+}
+
+/// How the shards of a `DawgForest` were loaded, see `DawgForest::stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForestStats {
+    /// shards read from their file
+    pub loads: usize,
+    /// shards dropped from memory to make room for others
+    pub evictions: usize,
+    /// shards currently held in memory
+    pub resident: usize,
+    /// most shards held in memory at once
+    pub peak_resident: usize,
+    /// shards that couldn't be loaded, the last error being kept by `DawgForest::last_error`
+    pub failed_loads: usize,
+}
+
+/// Where a shard is in the file of a packed forest, along with its number of words
+#[derive(Debug, Clone, Copy)]
+struct ShardInfo {
+    words: usize,
+    offset: u64,
+    len: u64,
+}
+
+#[derive(Debug)]
+enum Source {
+    /// every shard is held in memory
+    Memory,
+    Directory(PathBuf),
+    Packed(PathBuf),
+}
+
+/// Shards held in memory by their key
+struct ShardCache<T: Wrapper> {
+    shards: Lru<String, Arc<Dawg<T>>>,
+    stats: ForestStats,
+    /// the error of the last shard that couldn't be loaded
+    last_error: Option<String>,
+}
+
+/// Words split by key (their first `key_len` letters, see `ForestOptions`) into shards, each one a dawg of its own.
+///
+/// A query only goes to the shards whose key it can match, and a saved forest only loads those when they are first
+/// queried, keeping at most `resident_shards` of them in memory. Since a key is a prefix of the words of its shard,
+/// going through the shards in the order of their keys lists the words in sorted order. The shards being disjoint,
+/// the number of words is known without loading any of them.
+///
+/// Shards are loaded behind a lock, so a forest of sync dawgs can be shared between threads. Loading can fail: the `try_`
+/// queries return the error, while the `Lexicon` queries answer as if the shard were empty. A shard file that is missing
+/// or corrupt therefore makes `contains` false and `completions` empty for the words it holds, with nothing to tell them
+/// apart from words that aren't there: use the `try_` queries (or check `last_error`) when that matters. Either way the
+/// failure is counted by `stats` and its error kept by `last_error`
+pub struct DawgForest<T: Wrapper> {
+    options: DawgOptions,
+    key_len: usize,
+    resident_shards: usize,
+    index: BTreeMap<String, ShardInfo>,
+    source: Source,
+    cache: Mutex<ShardCache<T>>,
+}
+
+impl<T> Debug for DawgForest<T> where T: Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DawgForest")
+            .field("options", &self.options)
+            .field("key_len", &self.key_len)
+            .field("shards", &self.index.len())
+            .field("source", &self.source)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl<T> DawgForest<T> where T: Wrapper {
+    /// Builds a forest of `words`, in any order, folded by the options and split by key. Every shard stays in memory, and
+    /// a key left without words (all of them skipped by the options) gets no shard
+    pub fn build<I, S>(words: I, options: &ForestOptions) -> Result<Self, DawgError> where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let mut groups = BTreeMap::<String, Vec<String>>::new();
+        for word in words {
+            let word = options.options.fold(word.as_ref());
+            groups.entry(word.chars().take(options.key_len).collect()).or_default().push(word);
+        }
+
+        let mut forest = Self::new(options.options.clone(), options.key_len, options.resident_shards, BTreeMap::new(), Source::Memory);
+        let shards = &mut forest.cache.get_mut().unwrap().shards;
+        for (key, words) in groups {
+            let mut dawg = Dawg::with_options(options.options.clone())?;
+            dawg.add_all(words)?;
+            dawg.finish();
+            if dawg.word_count() == 0 {
+                continue;
+            }
+
+            forest.index.insert(key.clone(), ShardInfo { words: dawg.word_count(), offset: 0, len: 0 });
+            shards.insert(key, Arc::new(dawg));
+        }
+        Ok(forest)
+    }
+
+    fn new(options: DawgOptions, key_len: usize, resident_shards: usize, index: BTreeMap<String, ShardInfo>, source: Source) -> Self {
+        let cache = ShardCache { shards: Lru::new(), stats: ForestStats::default(), last_error: None };
+        Self { options, key_len, resident_shards, index, source, cache: Mutex::new(cache) }
+    }
+
+    /// Opens the forest saved to `dir` by `save_dir`, reading its index only: shards are loaded as queries reach them
+    pub fn load_dir(dir: impl AsRef<Path>, options: &ForestOptions) -> Result<Self, DawgError> {
+        let dir = dir.as_ref();
+        let bytes = fs::read(dir.join(INDEX_FILE))?;
+        let mut reader = Cursor::new(&bytes);
+        check_magic(&mut reader)?;
+
+        let (dawg_options, key_len, index) = read_index(&mut reader)?;
+        Ok(Self::new(dawg_options, key_len, options.resident_shards, index, Source::Directory(dir.to_owned())))
+    }
+
+    /// Opens the forest packed into the file at `path` by `save_packed`, reading its index only: shards are loaded as
+    /// queries reach them
+    pub fn open_packed(path: impl AsRef<Path>, options: &ForestOptions) -> Result<Self, DawgError> {
+        let path = path.as_ref();
+        let mut file = BufReader::new(File::open(path)?);
+        let len = file.seek(SeekFrom::End(0))?;
+        let truncated = DawgError::Truncated { section: Section::Header };
+
+        let mut prefix = [0; 4 + 2];
+        file.seek(SeekFrom::Start(0))?;
+        file.read_exact(&mut prefix).map_err(|_| DawgError::Truncated { section: Section::Header })?;
+        check_magic(&mut Cursor::new(&prefix))?;
+
+        if len < (prefix.len() + 8) as u64 {
+            return Err(truncated);
+        }
+        let mut index_at = [0; 8];
+        file.seek(SeekFrom::End(-8))?;
+        file.read_exact(&mut index_at)?;
+        let index_at = u64::from_le_bytes(index_at);
+        if index_at < prefix.len() as u64 || index_at > len - 8 {
+            return Err(DawgError::InvalidFormat(format!("the index of the forest starts at {index_at}, outside of the file")));
+        }
+
+        let mut bytes = vec![0; (len - 8 - index_at) as usize];
+        file.seek(SeekFrom::Start(index_at))?;
+        file.read_exact(&mut bytes)?;
+        let (dawg_options, key_len, index) = read_index(&mut Cursor::new(&bytes))?;
+
+        if let Some((key, _)) = index.iter().find(|(_, shard)| shard.offset < prefix.len() as u64 || shard.offset.saturating_add(shard.len) > index_at) {
+            return Err(DawgError::InvalidFormat(format!("the shard of key \"{key}\" is outside of the file")));
+        }
+        Ok(Self::new(dawg_options, key_len, options.resident_shards, index, Source::Packed(path.to_owned())))
+    }
+
+    /// Saves every shard to a file of its own in `dir` (created if needed), along with the index (see the `forest` module)
+    pub fn save_dir(&self, dir: impl AsRef<Path>) -> Result<(), DawgError> {
+        let dir = dir.as_ref();
+        fs::create_dir_all(dir)?;
+
+        for key in self.index.keys() {
+            self.shard(key)?.save(BufWriter::new(File::create(dir.join(shard_file(key)))?))?;
+        }
+
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend_from_slice(&VERSION.to_le_bytes());
+        let shards = self.index.iter().map(|(key, shard)| (key.as_str(), ShardInfo { offset: 0, len: 0, ..*shard }));
+        write_index(&mut bytes, &self.options, self.key_len, shards);
+        fs::write(dir.join(INDEX_FILE), bytes)?;
+        Ok(())
+    }
+
+    /// Writes every shard to `writer` back to back, followed by the index (see the `forest` module). Shards are written
+    /// as they are loaded, so a saved forest is repacked within its bound of resident shards
+    pub fn save_packed<W: Write>(&self, writer: W) -> Result<(), DawgError> {
+        let mut writer = BufWriter::new(writer);
+        writer.write_all(MAGIC)?;
+        writer.write_all(&VERSION.to_le_bytes())?;
+
+        let mut offset = (MAGIC.len() + 2) as u64;
+        let mut shards = vec![];
+        for (key, shard) in &self.index {
+            let bytes = self.shard(key)?.to_bytes()?;
+            writer.write_all(&bytes)?;
+            shards.push((key.as_str(), ShardInfo { offset, len: bytes.len() as u64, ..*shard }));
+            offset += bytes.len() as u64;
+        }
+
+        let mut index = vec![];
+        write_index(&mut index, &self.options, self.key_len, shards);
+        write_u64(&mut index, offset);
+        writer.write_all(&index)?;
+        Ok(writer.flush()?)
+    }
+
+    /// the options of the dawgs of the shards
+    pub fn options(&self) -> &DawgOptions {
+        &self.options
+    }
+
+    /// number of leading letters making the key of a word
+    pub fn key_len(&self) -> usize {
+        self.key_len
+    }
+
+    /// Returns the keys of the shards in sorted order
+    pub fn shard_keys(&self) -> impl Iterator<Item = &str> + '_ {
+        self.index.keys().map(String::as_str)
+    }
+
+    pub fn shard_count(&self) -> usize {
+        self.index.len()
+    }
+
+    pub fn stats(&self) -> ForestStats {
+        self.cache.lock().unwrap().stats
+    }
+
+    /// Message of the error of the last shard that couldn't be loaded, by any query
+    pub fn last_error(&self) -> Option<String> {
+        self.cache.lock().unwrap().last_error.clone()
+    }
+
+    /// Returns the dawg of the shard of `key`, loading it if it isn't in memory (and dropping the least recently used shard
+    /// if there is no room left for it). Fails with `DawgError::MissingShard` if no word has this key.
+    /// The lock is released while the shard is read, so queries of other shards aren't held up by it: two threads missing
+    /// the same shard may both read it, the first one to finish being kept
+    pub fn shard(&self, key: &str) -> Result<Arc<Dawg<T>>, DawgError> {
+        let info = self.index.get(key).ok_or_else(|| DawgError::MissingShard(key.to_owned()))?;
+        if let Some(shard) = self.cache.lock().unwrap().shards.get(key) {
+            return Ok(shard.clone());
+        }
+
+        let loaded = self.load(key, info);
+        let mut cache = self.cache.lock().unwrap();
+        let shard = match loaded {
+            Ok(shard) => Arc::new(shard),
+            Err(err) => {
+                cache.stats.failed_loads += 1;
+                cache.last_error = Some(err.to_string());
+                return Err(err);
+            }
+        };
+        cache.stats.loads += 1;
+        if let Some(shard) = cache.shards.get(key) {
+            return Ok(shard.clone());
+        }
+
+        while cache.shards.len() >= self.resident_shards && cache.shards.pop_oldest().is_some() {
+            cache.stats.evictions += 1;
+        }
+        cache.shards.insert(key.to_owned(), shard.clone());
+
+        let resident = cache.shards.len();
+        cache.stats.resident = resident;
+        cache.stats.peak_resident = cache.stats.peak_resident.max(resident);
+        Ok(shard)
+    }
+
+    /// Reads the shard of `key` from its file
+    fn load(&self, key: &str, info: &ShardInfo) -> Result<Dawg<T>, DawgError> {
+        match &self.source {
+            Source::Memory => unreachable!("a forest built in memory holds every shard"),
+            Source::Directory(dir) => Dawg::load(BufReader::new(File::open(dir.join(shard_file(key)))?)),
+            Source::Packed(path) => {
+                let mut file = File::open(path)?;
+                file.seek(SeekFrom::Start(info.offset))?;
+                let mut bytes = vec![0; info.len as usize];
+                file.read_exact(&mut bytes)?;
+                Dawg::from_bytes(&bytes)
+            }
+        }
+    }
+
+    /// Keys of the shards holding the words `query` (folded by the options, as the shards fold it) can match under `mode`,
+    /// or the words it can start if `prefix`
+    fn shards_for(&self, query: &str, prefix: bool, mode: MatchMode) -> Vec<&str> {
+        let letters = self.options.fold_chars(query.chars()).take(self.key_len).collect::<Vec<_>>();
+
+        self.index.keys()
+            .filter(|key| {
+                let mut key = key.chars();
+                letters.iter().all(|letter| key.next().is_some_and(|label| mode.matches(label, *letter))) && (prefix || key.next().is_none())
+            })
+            .map(String::as_str)
+            .collect()
+    }
+
+    /// value is true if `word` is in the shard of its key (in every shard its key can match under `mode`)
+    pub fn try_contains(&self, word: &str, mode: MatchMode) -> Result<bool, DawgError> {
+        for key in self.shards_for(word, false, mode) {
+            if Lexicon::contains(self.shard(key)?.as_ref(), word, mode) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// value is true if at least one word starts with `prefix`, looking in the shards the prefix can reach
+    pub fn try_contains_prefix(&self, prefix: &str, mode: MatchMode) -> Result<bool, DawgError> {
+        for key in self.shards_for(prefix, true, mode) {
+            if Lexicon::contains_prefix(self.shard(key)?.as_ref(), prefix, mode) {
+                return Ok(true);
+            }
+        }
+        Ok(false)
+    }
+
+    /// Returns every word starting with `prefix` in sorted order, from the shards the prefix can reach
+    pub fn try_completions(&self, prefix: &str, mode: MatchMode) -> Result<Vec<String>, DawgError> {
+        let mut completions = vec![];
+        for key in self.shards_for(prefix, true, mode) {
+            completions.push(self.shard(key)?.completions(prefix, mode).into_iter());
+        }
+        Ok(KMerge::new(completions).collect())
+    }
+
+    /// Returns every word in sorted order, going through the shards one at a time in the order of their keys
+    pub fn words(&self) -> ForestWords<'_, T> {
+        ForestWords { forest: self, keys: self.index.keys(), shard: vec![].into_iter() }
+    }
+}
+
+impl<T> Lexicon for DawgForest<T> where T: Wrapper {
+    /// See `try_contains`, false if a shard can't be loaded (see `last_error`)
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        self.try_contains(word, mode).unwrap_or(false)
+    }
+
+    /// See `try_contains_prefix`, false if a shard can't be loaded
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        self.try_contains_prefix(prefix, mode).unwrap_or(false)
+    }
+
+    /// See `try_completions`, empty if a shard can't be loaded
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        self.try_completions(prefix, mode).unwrap_or_default()
+    }
+
+    /// Read from the index, without loading any shard
+    fn word_count(&self) -> usize {
+        self.index.values().map(|shard| shard.words).sum()
+    }
+}
+
+/// Iterator over the words of a DawgForest in sorted order, returned by `DawgForest::words`. The words of a shard are
+/// listed when it is reached, failing if it can't be loaded
+pub struct ForestWords<'a, T: Wrapper> {
+    forest: &'a DawgForest<T>,
+    keys: std::collections::btree_map::Keys<'a, String, ShardInfo>,
+    shard: std::vec::IntoIter<String>,
+}
+
+impl<T> Iterator for ForestWords<'_, T> where T: Wrapper {
+    type Item = Result<String, DawgError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(word) = self.shard.next() {
+                return Some(Ok(word));
+            }
+
+            match self.forest.shard(self.keys.next()?) {
+                Ok(shard) => self.shard = shard.words().collect::<Vec<_>>().into_iter(),
+                Err(err) => return Some(Err(err)),
+            }
+        }
+    }
+}
+
+/// Name of the file of the shard of `key` in a forest saved to a directory
+fn shard_file(key: &str) -> String {
+    let hex = key.bytes().map(|byte| format!("{byte:02x}")).collect::<String>();
+    format!("shard-{hex}.dawg")
+}
+
+fn check_magic(reader: &mut Cursor<'_>) -> Result<(), DawgError> {
+    let truncated = |_| DawgError::Truncated { section: Section::Header };
+    if reader.bytes(MAGIC.len()).map_err(truncated)? != MAGIC {
+        return Err(DawgError::InvalidFormat(String::from("missing forest magic bytes")));
+    }
+
+    let version = reader.u16().map_err(truncated)?;
+    if version > VERSION {
+        return Err(DawgError::NewerVersion { version, supported: VERSION });
+    }
+    Ok(())
+}
+
+fn write_index<'k>(out: &mut Vec<u8>, options: &DawgOptions, key_len: usize, shards: impl IntoIterator<Item = (&'k str, ShardInfo)>) {
+    let mut index = vec![];
+    let mut encoded = vec![];
+    options.encode(&mut encoded);
+    write_u32(&mut index, encoded.len() as u32);
+    index.extend_from_slice(&encoded);
+    write_u32(&mut index, key_len as u32);
+
+    let shards = shards.into_iter().collect::<Vec<_>>();
+    write_u32(&mut index, shards.len() as u32);
+    for (key, shard) in shards {
+        write_u32(&mut index, key.len() as u32);
+        index.extend_from_slice(key.as_bytes());
+        write_u64(&mut index, shard.words as u64);
+        write_u64(&mut index, shard.offset);
+        write_u64(&mut index, shard.len);
+    }
+
+    write_u32(out, index.len() as u32);
+    write_section(out, &index);
+}
+
+fn read_index(reader: &mut Cursor<'_>) -> Result<(DawgOptions, usize, BTreeMap<String, ShardInfo>), DawgError> {
+    let len = reader.u32().map_err(|_| DawgError::Truncated { section: Section::Header })? as usize;
+    let mut index = Cursor::new(read_section(reader, Section::Header, len, true)?);
+
+    let options_len = index.u32()? as usize;
+    let options = DawgOptions::decode(index.bytes(options_len)?)?;
+    let key_len = index.u32()? as usize;
+
+    let mut shards = BTreeMap::new();
+    for _ in 0..index.u32()? {
+        let key_len = index.u32()? as usize;
+        let key = std::str::from_utf8(index.bytes(key_len)?).map_err(|_| DawgError::InvalidFormat(String::from("shard key is not utf-8")))?;
+        let shard = ShardInfo { words: index.u64()? as usize, offset: index.u64()?, len: index.u64()? };
+        shards.insert(key.to_owned(), shard);
+    }
+
+    Ok((options, key_len, shards))
+}
@@ -0,0 +1,120 @@
+//! Front-coded word lists: the words of a dawg in sorted order, each one stored as what it shares with the word before it.
+//!
+//! The format is a sequence of records up to the end of the stream, one per word, without any header:
+//!
+//! - the number of leading bytes the word shares with the previous word (0 for the first word), as a varint
+//! - the number of bytes of the rest of the word, as a varint
+//! - the rest of the word, in utf-8
+//!
+//! Varints are unsigned LEB128: 7 bits per byte, least significant group first, the high bit set on every byte but the last.
+//! Words are written in the byte order of their utf-8 encoding, sharing whole chars only.
+//! For example, "car" then "cart" then "cat" is `00 03 63 61 72` `03 01 74` `02 01 74`
+
+use std::io::{BufReader, BufWriter, ErrorKind, Read, Write};
+
+use crate::{dawg::{common::{Dawg, Wrapper}, options::DawgOptions}, error::DawgError};
+
+/// Longest varint: 10 groups of 7 bits hold a u64
+const MAX_VARINT_BYTES: usize = 10;
+
+fn write_varint(writer: &mut impl Write, mut value: u64) -> std::io::Result<()> {
+    let mut bytes = [0; MAX_VARINT_BYTES];
+    let mut len = 0;
+    loop {
+        bytes[len] = (value & 0x7F) as u8;
+        value >>= 7;
+        len += 1;
+        if value == 0 {
+            break;
+        }
+        bytes[len - 1] |= 0x80;
+    }
+    writer.write_all(&bytes[..len])
+}
+
+/// Reads the next varint, `Ok(None)` if the stream ends right before it
+fn read_varint(reader: &mut impl Read, record: usize) -> Result<Option<u64>, DawgError> {
+    let invalid = |reason: &str| DawgError::InvalidFormat(format!("front-coded record {record}: {reason}"));
+    let mut value = 0u64;
+
+    for group in 0..MAX_VARINT_BYTES {
+        let mut byte = [0];
+        match reader.read_exact(&mut byte) {
+            Ok(()) => {}
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof && group == 0 => return Ok(None),
+            Err(err) if err.kind() == ErrorKind::UnexpectedEof => return Err(invalid("the stream ends inside a varint")),
+            Err(err) => return Err(err.into()),
+        }
+
+        let bits = (byte[0] & 0x7F) as u64;
+        if group == MAX_VARINT_BYTES - 1 && bits > 1 {
+            return Err(invalid("varint overflows 64 bits"));
+        }
+        value |= bits << (7 * group);
+
+        if byte[0] & 0x80 == 0 {
+            return Ok(Some(value));
+        }
+    }
+
+    Err(invalid("varint longer than 10 bytes"))
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Writes the words of the dawg front-coded (see the `frontcoded` module for the format), holding a single word at a time
+    pub fn export_front_coded<W: Write>(&self, writer: W) -> Result<(), DawgError> {
+        let mut writer = BufWriter::new(writer);
+        let mut previous = String::new();
+
+        for word in self.words() {
+            let shared = previous.bytes().zip(word.bytes()).take_while(|(a, b)| a == b).count();
+            // a char shared in part only is written in full with the rest of the word
+            let shared = (0..=shared).rev().find(|end| word.is_char_boundary(*end)).unwrap_or(0);
+            let rest = &word.as_bytes()[shared..];
+
+            write_varint(&mut writer, shared as u64)?;
+            write_varint(&mut writer, rest.len() as u64)?;
+            writer.write_all(rest)?;
+            previous = word;
+        }
+
+        Ok(writer.flush()?)
+    }
+
+    /// Adds the words of a front-coded list (see the `frontcoded` module) through `try_add`, returning how many were read.
+    /// Only the word being decoded is held in memory
+    pub fn read_front_coded<R: Read>(&mut self, reader: R) -> Result<usize, DawgError> {
+        let mut reader = BufReader::new(reader);
+        let mut word = vec![];
+        let mut records = 0;
+
+        while let Some(shared) = read_varint(&mut reader, records)? {
+            let invalid = |reason: &str| DawgError::InvalidFormat(format!("front-coded record {records}: {reason}"));
+            let rest = read_varint(&mut reader, records)?.ok_or_else(|| invalid("the stream ends before the length of the word"))?;
+
+            if shared > word.len() as u64 {
+                return Err(invalid("shares more bytes than the previous word has"));
+            }
+            word.truncate(shared as usize);
+
+            let read = (&mut reader).take(rest).read_to_end(&mut word)?;
+            if read as u64 != rest {
+                return Err(invalid("the stream ends inside the word"));
+            }
+
+            let text = std::str::from_utf8(&word).map_err(|_| invalid("the word is not valid utf-8"))?;
+            self.try_add(text)?;
+            records += 1;
+        }
+
+        Ok(records)
+    }
+
+    /// Builds a finished dawg configured by `options` from a front-coded list (see `read_front_coded`)
+    pub fn from_front_coded<R: Read>(reader: R, options: DawgOptions) -> Result<Self, DawgError> {
+        let mut dawg = Self::with_options(options)?;
+        dawg.read_front_coded(reader)?;
+        dawg.finish();
+        Ok(dawg)
+    }
+}
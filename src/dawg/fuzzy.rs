@@ -0,0 +1,325 @@
+use std::collections::HashMap;
+
+use crate::dawg::{budget::{BudgetMeter, Budgeted, SearchBudget}, common::{Dawg, NodeOps, Wrapper}, lexicon::MatchMode};
+
+/// Deterministic automaton over chars, which `Dawg::search_automaton` runs along every path of a dawg
+pub trait Automaton {
+    type State: Clone;
+
+    /// the state before any letter is read
+    fn start(&self) -> Self::State;
+
+    /// the state reached by reading `letter` in `state`
+    fn step(&self, state: &Self::State, letter: char) -> Self::State;
+
+    /// value is true if the letters read up to `state` are accepted
+    fn is_match(&self, state: &Self::State) -> bool;
+
+    /// value is false if no letters read from `state` on can lead to a match, so the search stops there
+    fn can_match(&self, state: &Self::State) -> bool;
+}
+
+/// Automaton accepting the words within `max_distance` edits (insertions, deletions or substitutions of a letter) of
+/// a query, built once and reusable against any number of dawgs.
+///
+/// It is compiled into a DFA whose states are the rows of the edit distance table, with every distance past
+/// `max_distance` counted as `max_distance + 1`. The letters of the query each get a column of transitions, and every other
+/// letter shares one more, so a step is a binary search among the letters of the query and a table lookup. The number of
+/// states grows with the length of the query and quickly with the distance: it is meant for distances up to 2 or 3
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LevenshteinAutomaton {
+    max_distance: u8,
+    /// distinct letters of the query, sorted
+    letters: Vec<char>,
+    /// `letters.len() + 1` transitions per state, the last one taken on any letter outside of the query
+    transitions: Vec<u32>,
+    /// distance between the query and what was read to reach each state, `max_distance + 1` if further
+    distances: Vec<u8>,
+}
+
+/// The state of a LevenshteinAutomaton from which no match can be reached
+const DEAD: u32 = 0;
+
+impl LevenshteinAutomaton {
+    pub fn new(query: &str, max_distance: u8) -> Self {
+        let query = query.chars().collect::<Vec<_>>();
+        let cap = max_distance.saturating_add(1);
+        let mut letters = query.clone();
+        letters.sort();
+        letters.dedup();
+
+        let dead = vec![cap; query.len() + 1];
+        let start = (0..=query.len()).map(|column| column.min(cap as usize) as u8).collect::<Vec<_>>();
+        let mut rows = vec![dead.clone(), start.clone()];
+        let mut ids = HashMap::from([(dead, DEAD), (start, 1)]);
+        let mut automaton = Self { max_distance, letters, transitions: vec![], distances: vec![] };
+
+        let mut state = 0;
+        while state < rows.len() {
+            // the letters of the query, then a letter found nowhere in it
+            for letter in automaton.letters.iter().copied().map(Some).chain([None]) {
+                let row = &rows[state];
+                let mut next = vec![row[0].saturating_add(1).min(cap)];
+                for column in 1..=query.len() {
+                    let substitution = row[column - 1].saturating_add((Some(query[column - 1]) != letter) as u8);
+                    next.push(substitution.min(row[column].saturating_add(1)).min(next[column - 1].saturating_add(1)).min(cap));
+                }
+
+                if next.iter().all(|distance| *distance == cap) {
+                    next.fill(cap);
+                }
+                let id = *ids.entry(next).or_insert_with_key(|next| {
+                    rows.push(next.clone());
+                    rows.len() as u32 - 1
+                });
+                automaton.transitions.push(id);
+            }
+            state += 1;
+        }
+
+        automaton.distances = rows.iter().map(|row| row[query.len()]).collect();
+        automaton
+    }
+
+    pub fn max_distance(&self) -> u8 {
+        self.max_distance
+    }
+
+    /// number of states of the compiled DFA, the dead state included
+    pub fn state_count(&self) -> usize {
+        self.distances.len()
+    }
+
+    /// Distance between the query and what was read to reach `state`, `None` if it is beyond the maximum distance
+    pub fn distance(&self, state: &u32) -> Option<u8> {
+        Some(self.distances[*state as usize]).filter(|distance| *distance <= self.max_distance)
+    }
+}
+
+impl Automaton for LevenshteinAutomaton {
+    type State = u32;
+
+    fn start(&self) -> u32 {
+        1
+    }
+
+    fn step(&self, state: &u32, letter: char) -> u32 {
+        let column = self.letters.binary_search(&letter).unwrap_or(self.letters.len());
+        self.transitions[*state as usize * (self.letters.len() + 1) + column]
+    }
+
+    fn is_match(&self, state: &u32) -> bool {
+        self.distance(state).is_some()
+    }
+
+    fn can_match(&self, state: &u32) -> bool {
+        *state != DEAD
+    }
+}
+
+/// Automaton accepting the words matching a glob, in which `?` stands for any one letter and `*` for any run of letters,
+/// the empty one included. Every other letter stands for itself.
+///
+/// Its states are the positions in the pattern reached by what was read so far, each `*` also reaching the position after
+/// it. A pattern starting with `*` can match below every node, so searching it visits the whole dawg: bound it with
+/// `Dawg::glob_budgeted`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GlobAutomaton {
+    pattern: Vec<Glob>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Glob {
+    Letter(char),
+    /// `?`
+    Any,
+    /// `*`
+    Run,
+}
+
+impl GlobAutomaton {
+    pub fn new(pattern: &str) -> Self {
+        let pattern = pattern.chars().map(|letter| match letter {
+            '?' => Glob::Any,
+            '*' => Glob::Run,
+            letter => Glob::Letter(letter),
+        });
+        Self { pattern: pattern.collect() }
+    }
+
+    /// Adds to `positions` the ones after every `*` they reach, keeping them sorted
+    fn close(&self, mut positions: Vec<usize>) -> Vec<usize> {
+        let mut index = 0;
+        while index < positions.len() {
+            let position = positions[index];
+            if self.pattern.get(position) == Some(&Glob::Run) && !positions.contains(&(position + 1)) {
+                positions.push(position + 1);
+            }
+            index += 1;
+        }
+        positions.sort_unstable();
+        positions
+    }
+}
+
+impl Automaton for GlobAutomaton {
+    /// positions in the pattern reached, sorted
+    type State = Vec<usize>;
+
+    fn start(&self) -> Vec<usize> {
+        self.close(vec![0])
+    }
+
+    fn step(&self, state: &Vec<usize>, letter: char) -> Vec<usize> {
+        let mut next = vec![];
+        for &position in state {
+            match self.pattern.get(position) {
+                Some(Glob::Run) => next.push(position),
+                Some(Glob::Any) => next.push(position + 1),
+                Some(Glob::Letter(expected)) if *expected == letter => next.push(position + 1),
+                _ => {}
+            }
+        }
+        next.dedup();
+        self.close(next)
+    }
+
+    fn is_match(&self, state: &Vec<usize>) -> bool {
+        state.last() == Some(&self.pattern.len())
+    }
+
+    fn can_match(&self, state: &Vec<usize>) -> bool {
+        !state.is_empty()
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns in sorted order the words of the dawg `automaton` accepts, as spelt in the dawg, leaving every path on which
+    /// it can no longer match
+    pub fn search_automaton<A: Automaton>(&self, automaton: &A) -> Vec<String> {
+        self.search_automaton_budgeted(automaton, SearchBudget::default()).results
+    }
+
+    /// Same as `search_automaton`, stopping once `budget` is spent (every node the automaton steps into counts)
+    pub fn search_automaton_budgeted<A: Automaton>(&self, automaton: &A, budget: SearchBudget) -> Budgeted<String> {
+        let mut meter = BudgetMeter::new(budget);
+        let mut words = vec![];
+        let start = automaton.start();
+        if automaton.can_match(&start) && meter.visit() {
+            self.walk_automaton(automaton, &self.root, start, &mut String::new(), &mut words, &mut meter);
+            meter.visits().report("search_automaton", words.len());
+        }
+        meter.finish(words)
+    }
+
+    fn walk_automaton<A: Automaton>(&self, automaton: &A, node: &T::Node, state: A::State, word: &mut String, words: &mut Vec<String>, meter: &mut BudgetMeter) {
+        if node.is_terminal() && automaton.is_match(&state) {
+            if !meter.accept() {
+                return;
+            }
+            words.push(word.clone());
+        }
+
+        for (letter, child) in node.children() {
+            let next = automaton.step(&state, letter);
+            if automaton.can_match(&next) {
+                if !meter.visit() {
+                    return;
+                }
+                word.push(letter);
+                self.walk_automaton(automaton, &child, next, word, words, meter);
+                word.pop();
+            }
+        }
+    }
+
+    /// Returns in sorted order the words matching the glob `pattern` (folded by the options of the dawg first), see
+    /// `GlobAutomaton`
+    pub fn glob(&self, pattern: &str) -> Vec<String> {
+        self.glob_budgeted(pattern, SearchBudget::default()).results
+    }
+
+    /// Same as `glob`, stopping once `budget` is spent (every node the pattern is matched against counts)
+    pub fn glob_budgeted(&self, pattern: &str, budget: SearchBudget) -> Budgeted<String> {
+        self.search_automaton_budgeted(&GlobAutomaton::new(&self.options.fold(pattern)), budget)
+    }
+
+    /// Returns in sorted order the words within `max_distance` edits of `query` (folded by the options of the dawg first),
+    /// each with its distance, computing the edit distance table along every path. To run the same query more than once,
+    /// compile it with `LevenshteinAutomaton` and use `search_automaton`
+    pub fn fuzzy_search(&self, query: &str, max_distance: u8) -> Vec<(String, u8)> {
+        self.fuzzy_search_budgeted(query, max_distance, SearchBudget::default()).results
+    }
+
+    /// Returns up to `limit` words within `max_distance` edits of `token`, closest first then in sorted order, as
+    /// `Dawg::check_text` suggests them. Unless matched exactly, the token is looked for in lower case as well, so `Teh`
+    /// finds `the`
+    pub fn suggest(&self, token: &str, mode: MatchMode, limit: usize, max_distance: u8) -> Vec<String> {
+        let mut found = self.fuzzy_search(token, max_distance);
+        let lower = token.to_lowercase();
+        if mode != MatchMode::Exact && lower != token {
+            found.extend(self.fuzzy_search(&lower, max_distance));
+        }
+
+        found.sort_by(|(a, a_distance), (b, b_distance)| a_distance.cmp(b_distance).then_with(|| a.cmp(b)));
+        let mut suggestions: Vec<String> = vec![];
+        for (word, _) in found {
+            if suggestions.len() == limit {
+                break;
+            }
+            if !suggestions.contains(&word) {
+                suggestions.push(word);
+            }
+        }
+        suggestions
+    }
+
+    /// Same as `fuzzy_search`, stopping once `budget` is spent (every node a row of the table is computed for counts)
+    pub fn fuzzy_search_budgeted(&self, query: &str, max_distance: u8, budget: SearchBudget) -> Budgeted<(String, u8)> {
+        let query = self.options.fold_chars(query.chars()).collect::<Vec<_>>();
+        let row = (0..=query.len()).collect::<Vec<_>>();
+        let mut walk = DistanceWalk { query: &query, max_distance: max_distance as usize, word: String::new(), words: vec![], meter: BudgetMeter::new(budget) };
+        if walk.meter.visit() {
+            self.walk_distances(&mut walk, &self.root, &row);
+        }
+        walk.meter.visits().report("fuzzy_search", walk.words.len());
+        walk.meter.finish(walk.words)
+    }
+
+    fn walk_distances(&self, walk: &mut DistanceWalk<'_>, node: &T::Node, row: &[usize]) {
+        let distance = row[walk.query.len()];
+        if node.is_terminal() && distance <= walk.max_distance {
+            if !walk.meter.accept() {
+                return;
+            }
+            walk.words.push((walk.word.clone(), distance as u8));
+        }
+
+        for (letter, child) in node.children() {
+            if !walk.meter.visit() {
+                return;
+            }
+
+            let mut next = vec![row[0] + 1];
+            for column in 1..=walk.query.len() {
+                let substitution = row[column - 1] + (walk.query[column - 1] != letter) as usize;
+                next.push(substitution.min(row[column] + 1).min(next[column - 1] + 1));
+            }
+
+            if next.iter().any(|distance| *distance <= walk.max_distance) {
+                walk.word.push(letter);
+                self.walk_distances(walk, &child, &next);
+                walk.word.pop();
+            }
+        }
+    }
+}
+
+/// State of `Dawg::fuzzy_search` along the path being walked
+struct DistanceWalk<'q> {
+    query: &'q [char],
+    max_distance: usize,
+    word: String,
+    words: Vec<(String, u8)>,
+    meter: BudgetMeter,
+}
@@ -0,0 +1,341 @@
+use std::{collections::BTreeMap, fmt::Debug};
+
+use crate::{dawg::{budget::{BudgetMeter, Budgeted, SearchBudget}, common::{Dawg, NodeOps, Wrapper}, crosscheck::{LetterAlphabet, LetterSet}, options::{DawgOptions, SortDirection, SortOrder}}, error::DawgError};
+
+/// Letter of a GADDAG path marking where the reversed prefix of a word ends and the rest of the word starts
+pub const SEPARATOR: char = '\u{0}';
+/// Letter standing for a blank tile in `Rack::new`
+pub const BLANK: char = '?';
+
+/// Dawg of every word `w` split at each of its letters: for `w = xy` with `x` non-empty, the path `reverse(x)`, `SEPARATOR`,
+/// `y`. A word can thus be read from any of its letters, first leftwards then rightwards, which is how move generators
+/// of board games (Gordon's algorithm) extend a word from a square of the board
+pub struct Gaddag<T: Wrapper> {
+    dawg: Dawg<T>,
+}
+
+impl<T> Debug for Gaddag<T> where T: Wrapper, Dawg<T>: Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Gaddag").field("dawg", &self.dawg).finish()
+    }
+}
+
+impl<T> Gaddag<T> where T: Wrapper {
+    /// Builds the GADDAG of `words`, in any order, folded by `options`. Their sort order and direction are not used, and
+    /// `SEPARATOR` is added to their alphabet if they have one. A word containing `SEPARATOR` is rejected
+    pub fn from_words<I, S>(words: I, mut options: DawgOptions) -> Result<Self, DawgError> where I: IntoIterator<Item = S>, S: AsRef<str> {
+        options.sort_order = SortOrder::Chars;
+        options.sort_direction = SortDirection::Ascending;
+        if let Some(alphabet) = &mut options.alphabet {
+            alphabet.push(SEPARATOR);
+            alphabet.sort();
+            alphabet.dedup();
+        }
+
+        let mut paths = vec![];
+        for word in words {
+            let letters = options.fold(word.as_ref()).chars().collect::<Vec<_>>();
+            if letters.contains(&SEPARATOR) {
+                return Err(DawgError::InvalidCharacter { word: word.as_ref().to_owned(), character: SEPARATOR });
+            }
+
+            for split in 1..=letters.len() {
+                let mut path = letters[..split].iter().rev().collect::<String>();
+                path.push(SEPARATOR);
+                path.extend(&letters[split..]);
+                paths.push(path);
+            }
+        }
+        paths.sort();
+        paths.dedup();
+
+        let mut dawg = Dawg::with_options(options)?;
+        for path in &paths {
+            dawg.try_add(path)?;
+        }
+        dawg.finish();
+        Ok(Self { dawg })
+    }
+
+    /// the dawg of the paths of the GADDAG
+    pub fn dawg(&self) -> &Dawg<T> {
+        &self.dawg
+    }
+
+    /// value is true if `word` (folded by the options) is one of the words of the GADDAG
+    pub fn contains(&self, word: &str) -> bool {
+        let letters = self.dawg.options.fold_chars(word.chars()).collect::<Vec<_>>();
+        let end = letters.iter().rev().chain([&SEPARATOR]).try_fold(self.dawg.root.clone(), |node, letter| node.child(letter));
+        end.is_some_and(|node| node.is_terminal())
+    }
+
+    /// Returns every placement of tiles of `rack` on `row` forming a word of the GADDAG over at least one anchor, by
+    /// Gordon's algorithm: from each anchor the word is extended leftwards (without reaching the next anchor on the left,
+    /// which generates its own placements), then past the separator rightwards. A placement must cover every letter
+    /// adjacent to it, and each tile must be allowed by the cross-check of its square. The placements are sorted and a
+    /// blank is only used as a letter it stands for
+    pub fn generate_moves(&self, row: &RowState, rack: &Rack) -> Vec<Placement> {
+        self.generate_moves_budgeted(row, rack, SearchBudget::default()).results
+    }
+
+    /// Same as `generate_moves`, stopping once `budget` is spent (every tile put on a square counts as a node). The anchors
+    /// are tried from left to right and the placements sorted afterwards, so a truncated search returns some of the
+    /// placements, not the first ones in sorted order. Placements found more than once count as as many results
+    pub fn generate_moves_budgeted(&self, row: &RowState, rack: &Rack, budget: SearchBudget) -> Budgeted<Placement> {
+        let meter = BudgetMeter::new(budget);
+        let mut generator = Generator { row, rack: rack.clone(), anchor: 0, left: vec![], right: vec![], tiles: vec![], placements: vec![], meter };
+
+        for anchor in (0..row.len()).filter(|square| row.anchors[*square] && row.letters[*square].is_none()) {
+            generator.anchor = anchor;
+            generator.extend(anchor, &self.dawg.root, true);
+        }
+
+        let mut placements = generator.placements;
+        placements.sort();
+        placements.dedup();
+        generator.meter.finish(placements)
+    }
+}
+
+/// One line of squares of a board, as `Gaddag::generate_moves` sees it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RowState {
+    letters: Vec<Option<char>>,
+    /// empty squares a placement may start from, usually those next to a letter on the board
+    anchors: Vec<bool>,
+    /// letters allowed on each empty square by the words they would form across the row, any letter if `None`
+    cross_checks: Vec<Option<LetterSet>>,
+    alphabet: LetterAlphabet,
+}
+
+impl RowState {
+    /// Row of `len` empty squares, without anchors nor cross-checks
+    pub fn new(len: usize) -> Self {
+        Self { letters: vec![None; len], anchors: vec![false; len], cross_checks: vec![None; len], alphabet: LetterAlphabet::default() }
+    }
+
+    /// Row whose squares are the chars of `row`, `.` and ` ` being empty squares
+    pub fn parse(row: &str) -> Self {
+        let letters = row.chars().map(|square| Some(square).filter(|square| !matches!(square, '.' | ' '))).collect::<Vec<_>>();
+        let mut state = Self::new(letters.len());
+        state.letters = letters;
+        state
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    /// Puts `letter` on the square at `index`, panicking if it is out of the row
+    pub fn letter(mut self, index: usize, letter: char) -> Self {
+        self.letters[index] = Some(letter);
+        self
+    }
+
+    /// Makes the square at `index` an anchor, panicking if it is out of the row
+    pub fn anchor(mut self, index: usize) -> Self {
+        self.anchors[index] = true;
+        self
+    }
+
+    /// Makes every empty square next to a letter of the row an anchor
+    pub fn anchors_next_to_letters(mut self) -> Self {
+        for square in 0..self.len() {
+            let next_to_letter = (square > 0 && self.letters[square - 1].is_some()) || self.letters.get(square + 1).is_some_and(Option::is_some);
+            self.anchors[square] |= self.letters[square].is_none() && next_to_letter;
+        }
+        self
+    }
+
+    /// Restricts the square at `index` to the letters of `allowed`, taken over the alphabet of the row
+    pub fn cross_check(mut self, index: usize, allowed: LetterSet) -> Self {
+        self.cross_checks[index] = Some(allowed);
+        self
+    }
+
+    /// Alphabet the cross-checks are taken over, a to z by default
+    pub fn alphabet(mut self, alphabet: LetterAlphabet) -> Self {
+        self.alphabet = alphabet;
+        self
+    }
+
+    fn allows(&self, square: usize, letter: char) -> bool {
+        self.cross_checks[square].is_none_or(|allowed| allowed.contains(letter, &self.alphabet))
+    }
+
+    /// value is true if `square` is past the end of the row or empty
+    fn is_open(&self, square: usize) -> bool {
+        self.letters.get(square).is_none_or(Option::is_none)
+    }
+}
+
+/// Tiles a player can place: letters, some of them possibly more than once, and blanks standing for any letter
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Rack {
+    letters: BTreeMap<char, usize>,
+    blanks: usize,
+}
+
+impl Rack {
+    /// Rack holding the letters of `tiles`, `BLANK` being a blank
+    pub fn new(tiles: &str) -> Self {
+        let mut rack = Self::default();
+        for tile in tiles.chars() {
+            match tile {
+                BLANK => rack.blanks += 1,
+                letter => *rack.letters.entry(letter).or_default() += 1,
+            }
+        }
+        rack
+    }
+
+    /// number of tiles of the rack, blanks included
+    pub fn len(&self) -> usize {
+        self.letters.values().sum::<usize>() + self.blanks
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn blanks(&self) -> usize {
+        self.blanks
+    }
+
+    /// Removes a tile `letter` from the rack, returning false if there is none
+    fn take(&mut self, letter: char) -> bool {
+        match self.letters.get_mut(&letter) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn put_back(&mut self, letter: char) {
+        *self.letters.entry(letter).or_default() += 1;
+    }
+}
+
+/// Tile of the rack placed on a square by a Placement
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tile {
+    pub index: usize,
+    pub letter: char,
+    /// value is true if a blank stands for the letter
+    pub blank: bool,
+}
+
+/// Word formed on a row by placing tiles of the rack
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Placement {
+    /// index of the first square of the word
+    pub start: usize,
+    /// the whole word, the letters already on the row included
+    pub word: String,
+    /// tiles placed from the rack, from left to right
+    pub tiles: Vec<Tile>,
+}
+
+/// State of `Gaddag::generate_moves` while it extends a word from one anchor
+struct Generator<'r> {
+    row: &'r RowState,
+    rack: Rack,
+    anchor: usize,
+    /// letters from the anchor leftwards
+    left: Vec<char>,
+    /// letters right of the anchor
+    right: Vec<char>,
+    tiles: Vec<Tile>,
+    placements: Vec<Placement>,
+    meter: BudgetMeter,
+}
+
+impl Generator<'_> {
+    /// Puts a letter on `square` (the one already there, or a tile of the rack) following the edges of `node`
+    fn extend<N: NodeOps<Label = char>>(&mut self, square: usize, node: &N, leftwards: bool) {
+        if let Some(letter) = self.row.letters[square] {
+            if let Some(child) = node.child(&letter) {
+                self.go_on(square, letter, None, &child, leftwards);
+            }
+            return;
+        }
+
+        for (letter, child) in node.children() {
+            if letter == SEPARATOR || !self.row.allows(square, letter) {
+                continue;
+            }
+
+            if self.rack.take(letter) {
+                self.go_on(square, letter, Some(false), &child, leftwards);
+                self.rack.put_back(letter);
+            }
+            if self.rack.blanks > 0 {
+                self.rack.blanks -= 1;
+                self.go_on(square, letter, Some(true), &child, leftwards);
+                self.rack.blanks += 1;
+            }
+        }
+    }
+
+    /// Continues the word after `letter` was put on `square`, `blank` telling which tile was placed if one was
+    fn go_on<N: NodeOps<Label = char>>(&mut self, square: usize, letter: char, blank: Option<bool>, node: &N, leftwards: bool) {
+        if !self.meter.visit() {
+            return;
+        }
+        if let Some(blank) = blank {
+            self.tiles.push(Tile { index: square, letter, blank });
+        }
+
+        if leftwards {
+            self.left.push(letter);
+
+            // the left part can only end on the edge of the row or before an empty square
+            if square == 0 || self.row.letters[square - 1].is_none() {
+                if let Some(separator) = node.child(&SEPARATOR) {
+                    if separator.is_terminal() && self.row.is_open(self.anchor + 1) {
+                        self.record(square);
+                    }
+                    if self.anchor + 1 < self.row.len() {
+                        self.extend(self.anchor + 1, &separator, false);
+                    }
+                }
+            }
+            if square > 0 && (self.row.letters[square - 1].is_some() || !self.row.anchors[square - 1]) {
+                self.extend(square - 1, node, true);
+            }
+
+            self.left.pop();
+        } else {
+            self.right.push(letter);
+
+            if node.is_terminal() && self.row.is_open(square + 1) {
+                self.record(self.anchor + 1 - self.left.len());
+            }
+            if square + 1 < self.row.len() {
+                self.extend(square + 1, node, false);
+            }
+
+            self.right.pop();
+        }
+
+        if blank.is_some() {
+            self.tiles.pop();
+        }
+    }
+
+    fn record(&mut self, start: usize) {
+        if !self.meter.accept() {
+            return;
+        }
+        let word = self.left.iter().rev().chain(&self.right).collect();
+        let mut tiles = self.tiles.clone();
+        tiles.sort();
+        self.placements.push(Placement { start, word, tiles });
+    }
+}
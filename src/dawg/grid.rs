@@ -0,0 +1,193 @@
+use std::collections::BTreeMap;
+
+use crate::{dawg::common::{Dawg, NodeRef, Wrapper}, error::DawgError, node::NodeHandle};
+
+/// Rectangular board of cells, each showing one or more letters (such as the "Qu" face of a Boggle die)
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Grid {
+    width: usize,
+    cells: Vec<String>,
+}
+
+impl Grid {
+    /// Grid whose rows are `rows`, one char per cell
+    pub fn new(rows: &[&str]) -> Result<Self, DawgError> {
+        Self::from_cells(rows.iter().map(|row| row.chars().map(String::from).collect()).collect())
+    }
+
+    /// Grid whose rows are `rows`, each cell showing a string of its own
+    pub fn from_cells<S: Into<String>>(rows: Vec<Vec<S>>) -> Result<Self, DawgError> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return Err(DawgError::InvalidValue(String::from("every row of a grid must have the same number of cells")));
+        }
+
+        Ok(Self { width, cells: rows.into_iter().flatten().map(Into::into).collect() })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len().checked_div(self.width).unwrap_or(0)
+    }
+
+    /// Returns the letters of the cell at `row` and `column`, `None` outside of the grid
+    pub fn cell(&self, row: usize, column: usize) -> Option<&str> {
+        match column < self.width {
+            true => self.cells.get(row * self.width + column).map(String::as_str),
+            false => None,
+        }
+    }
+}
+
+/// Cells a path can go to from a cell of a Grid
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Adjacency {
+    /// the cells around it, diagonals included
+    #[default]
+    Eight,
+    /// the cells above, below, left and right of it
+    Four,
+}
+
+/// Settings of `Dawg::find_in_grid`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct GridRules {
+    adjacency: Adjacency,
+    min_len: usize,
+    reuse_cells: bool,
+    all_paths: bool,
+}
+
+impl Default for GridRules {
+    /// Boggle rules: 8 neighbours, words of 3 letters or more, each cell used once per word
+    fn default() -> Self {
+        Self { adjacency: Adjacency::Eight, min_len: 3, reuse_cells: false, all_paths: false }
+    }
+}
+
+impl GridRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn adjacency(mut self, adjacency: Adjacency) -> Self {
+        self.adjacency = adjacency;
+        self
+    }
+
+    /// Fewest letters (chars) a word must have to be found
+    pub fn min_len(mut self, min_len: usize) -> Self {
+        self.min_len = min_len;
+        self
+    }
+
+    /// Whether a path may go through the same cell more than once
+    pub fn reuse_cells(mut self, reuse_cells: bool) -> Self {
+        self.reuse_cells = reuse_cells;
+        self
+    }
+
+    /// Whether every path spelling a word is kept, instead of the first one found
+    pub fn all_paths(mut self, all_paths: bool) -> Self {
+        self.all_paths = all_paths;
+        self
+    }
+}
+
+/// Word found in a Grid by `Dawg::find_in_grid`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GridMatch {
+    pub word: String,
+    /// (row, column) cells spelling the word: every such path if `GridRules::all_paths`, else the first one found
+    pub paths: Vec<Vec<(usize, usize)>>,
+}
+
+impl GridMatch {
+    /// the first path found spelling the word
+    pub fn path(&self) -> &[(usize, usize)] {
+        &self.paths[0]
+    }
+}
+
+/// State of `Dawg::find_in_grid` along the current path
+struct GridSearch<'g> {
+    grid: &'g Grid,
+    rules: GridRules,
+    path: Vec<(usize, usize)>,
+    used: Vec<bool>,
+    word: String,
+    found: BTreeMap<String, Vec<Vec<(usize, usize)>>>,
+}
+
+impl GridSearch<'_> {
+    fn neighbours(&self, (row, column): (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        let (height, width) = (self.grid.height() as isize, self.grid.width() as isize);
+        let diagonals = self.rules.adjacency == Adjacency::Eight;
+
+        (-1..=1).flat_map(move |dr| (-1..=1).map(move |dc| (dr, dc)))
+            .filter(move |(dr, dc): &(isize, isize)| (*dr, *dc) != (0, 0) && (diagonals || dr.abs() + dc.abs() == 1))
+            .map(move |(dr, dc)| (row as isize + dr, column as isize + dc))
+            .filter(move |(r, c)| (0..height).contains(r) && (0..width).contains(c))
+            .map(|(r, c)| (r as usize, c as usize))
+    }
+
+    /// Extends the path onto `cell` from `node`, going on to every neighbour while the letters spell a prefix of a word.
+    /// Empty cells can't be part of a path
+    fn visit<N: NodeRef<Label = char>>(&mut self, cell: (usize, usize), node: &NodeHandle<'_, N>, fold: &impl Fn(&str) -> String) {
+        let index = cell.0 * self.grid.width() + cell.1;
+        if self.used[index] && !self.rules.reuse_cells {
+            return;
+        }
+
+        let letters = fold(self.grid.cells[index].as_str());
+        if letters.is_empty() {
+            return;
+        }
+        let Some(node) = letters.chars().try_fold(node.clone(), |node, letter| node.child(&letter)) else {
+            return;
+        };
+
+        let (word_len, was_used) = (self.word.len(), self.used[index]);
+        self.word.push_str(&letters);
+        self.path.push(cell);
+        self.used[index] = true;
+
+        if node.is_terminal() && self.word.chars().count() >= self.rules.min_len {
+            let paths = self.found.entry(self.word.clone()).or_default();
+            if paths.is_empty() || self.rules.all_paths {
+                paths.push(self.path.clone());
+            }
+        }
+
+        let neighbours = self.neighbours(cell).collect::<Vec<_>>();
+        for neighbour in neighbours {
+            self.visit(neighbour, &node, fold);
+        }
+
+        self.used[index] = was_used;
+        self.path.pop();
+        self.word.truncate(word_len);
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns in sorted order the words spelt by paths of adjacent cells of `grid` under `rules`, the letters of each cell
+    /// being folded by the options of the dawg. The search walks the dawg along with the path and leaves it as soon as the
+    /// letters read aren't the prefix of a word. A word spelt by several paths is returned once
+    pub fn find_in_grid(&self, grid: &Grid, rules: GridRules) -> Vec<GridMatch> {
+        let mut search = GridSearch { grid, rules, path: vec![], used: vec![false; grid.cells.len()], word: String::new(), found: BTreeMap::new() };
+        let fold = |letters: &str| self.options.fold(letters);
+        let root = self.root();
+
+        for row in 0..grid.height() {
+            for column in 0..grid.width() {
+                search.visit((row, column), &root, &fold);
+            }
+        }
+
+        search.found.into_iter().map(|(word, paths)| GridMatch { word, paths }).collect()
+    }
+}
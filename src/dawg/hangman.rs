@@ -0,0 +1,186 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::dawg::{budget::{BudgetMeter, Budgeted, SearchBudget}, common::{Dawg, NodeOps, NodeRef, Wrapper}};
+
+/// Letter standing for an unknown square in `parse_pattern`
+pub const UNKNOWN: char = '_';
+
+/// Pattern of `s`, `UNKNOWN` being an unknown letter: `"_a__e"` is `[None, Some('a'), None, None, Some('e')]`
+pub fn parse_pattern(s: &str) -> Vec<Option<char>> {
+    s.chars().map(|letter| Some(letter).filter(|letter| *letter != UNKNOWN)).collect()
+}
+
+/// How `Dawg::hangman_with` reads a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HangmanRules {
+    revealed_everywhere: bool,
+}
+
+impl Default for HangmanRules {
+    /// Guessing a letter reveals every square holding it
+    fn default() -> Self {
+        Self { revealed_everywhere: true }
+    }
+}
+
+impl HangmanRules {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether a revealed letter shows on every square holding it, so an unknown square can't hold a revealed letter
+    pub fn revealed_everywhere(mut self, revealed_everywhere: bool) -> Self {
+        self.revealed_everywhere = revealed_everywhere;
+        self
+    }
+}
+
+/// Iterator over the words of a Dawg matching a hangman pattern, returned by `Dawg::hangman`
+#[derive(Debug)]
+pub struct PatternMatches<'a, N: NodeRef<Label = char>> {
+    pattern: Vec<Option<char>>,
+    /// letters no unknown square can hold, sorted
+    ruled_out: Vec<char>,
+    /// edges left to follow at each position of the word being spelt
+    stack: Vec<std::vec::IntoIter<(char, N)>>,
+    word: String,
+    /// value is true if the pattern is empty and the empty word is still to be returned
+    empty_word: bool,
+    meter: BudgetMeter,
+    dawg: PhantomData<&'a ()>,
+}
+
+impl<N> PatternMatches<'_, N> where N: NodeRef<Label = char> {
+    /// the edges of `node` that can fill the square at `position`
+    fn candidates(&self, node: &N, position: usize) -> std::vec::IntoIter<(char, N)> {
+        let mut edges = node.children();
+        match self.pattern[position] {
+            Some(known) => edges.retain(|(letter, _)| *letter == known),
+            None => edges.retain(|(letter, _)| self.ruled_out.binary_search(letter).is_err()),
+        }
+        edges.into_iter()
+    }
+}
+
+impl<N> Iterator for PatternMatches<'_, N> where N: NodeRef<Label = char> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.empty_word {
+            self.empty_word = false;
+            return self.meter.accept().then(String::new);
+        }
+
+        loop {
+            let position = self.stack.len();
+            match self.stack.last_mut()?.next() {
+                None => {
+                    self.stack.pop();
+                    self.word.pop();
+                }
+                Some((letter, child)) => {
+                    if !self.meter.visit() {
+                        self.stack.clear();
+                        return None;
+                    }
+
+                    self.word.push(letter);
+                    if position == self.pattern.len() {
+                        let word = child.is_terminal().then(|| self.word.clone());
+                        self.word.pop();
+                        if word.is_some() {
+                            if !self.meter.accept() {
+                                self.stack.clear();
+                                return None;
+                            }
+                            return word;
+                        }
+                    } else {
+                        let next = self.candidates(&child, position);
+                        self.stack.push(next);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// How often each letter shows on the unknown squares of the words matching a hangman pattern, see `Dawg::hangman_tally`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct HangmanTally {
+    /// number of words matching the pattern
+    pub words: usize,
+    /// (words holding the letter on an unknown square, occurrences on unknown squares) of every letter
+    pub letters: BTreeMap<char, (usize, usize)>,
+}
+
+impl HangmanTally {
+    /// Returns the number of matching words holding `letter` on an unknown square
+    pub fn words_with(&self, letter: char) -> usize {
+        self.letters.get(&letter).map_or(0, |(words, _)| *words)
+    }
+
+    /// Returns the letter held on an unknown square by the most matching words (the smallest one on a tie)
+    pub fn best_guess(&self) -> Option<char> {
+        self.letters.iter().max_by(|(a, (a_words, _)), (b, (b_words, _))| a_words.cmp(b_words).then(b.cmp(a))).map(|(letter, _)| *letter)
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns in sorted order the words of the length of `pattern` holding its known letters where it has them, and
+    /// none of `excluded` elsewhere. Under the default `HangmanRules` the unknown squares can't hold a revealed letter
+    /// either, see `hangman_with`
+    pub fn hangman(&self, pattern: &[Option<char>], excluded: &[char]) -> PatternMatches<'_, T::Node> {
+        self.hangman_with(pattern, excluded, HangmanRules::default())
+    }
+
+    /// Same as `hangman` under `rules`. The letters of the pattern and the excluded letters are folded by the options of
+    /// the dawg, and the walk leaves every edge that can't fill the square it leads to
+    pub fn hangman_with(&self, pattern: &[Option<char>], excluded: &[char], rules: HangmanRules) -> PatternMatches<'_, T::Node> {
+        let fold = |letter: char| self.options.fold_chars([letter]).next().unwrap_or(letter);
+        let pattern = pattern.iter().map(|letter| letter.map(fold)).collect::<Vec<_>>();
+
+        let mut ruled_out = excluded.iter().copied().map(fold).collect::<Vec<_>>();
+        if rules.revealed_everywhere {
+            ruled_out.extend(pattern.iter().flatten());
+        }
+        ruled_out.sort();
+        ruled_out.dedup();
+
+        let mut matches = PatternMatches { pattern, ruled_out, stack: vec![], word: String::new(), empty_word: false, meter: BudgetMeter::default(), dawg: PhantomData };
+        match matches.pattern.is_empty() {
+            true => matches.empty_word = self.root.is_terminal(),
+            false => matches.stack.push(matches.candidates(&self.root, 0)),
+        }
+        matches
+    }
+
+    /// Same as `hangman_with`, stopping once `budget` is spent (every edge followed counts as a node)
+    pub fn hangman_budgeted(&self, pattern: &[Option<char>], excluded: &[char], rules: HangmanRules, budget: SearchBudget) -> Budgeted<String> {
+        let mut matches = self.hangman_with(pattern, excluded, rules);
+        matches.meter = BudgetMeter::new(budget);
+        let words = matches.by_ref().collect();
+        matches.meter.finish(words)
+    }
+
+    /// Tallies the letters on the unknown squares of the words `hangman_with` returns, to pick the next guess
+    pub fn hangman_tally(&self, pattern: &[Option<char>], excluded: &[char], rules: HangmanRules) -> HangmanTally {
+        let mut tally = HangmanTally::default();
+        let unknown = pattern.iter().map(Option::is_none).collect::<Vec<_>>();
+
+        for word in self.hangman_with(pattern, excluded, rules) {
+            tally.words += 1;
+            let mut seen = vec![];
+            for (letter, _) in word.chars().zip(&unknown).filter(|(_, unknown)| **unknown) {
+                let (words, occurrences) = tally.letters.entry(letter).or_default();
+                *occurrences += 1;
+                if !seen.contains(&letter) {
+                    seen.push(letter);
+                    *words += 1;
+                }
+            }
+        }
+
+        tally
+    }
+}
@@ -0,0 +1,86 @@
+use crate::dawg::{common::{Dawg, NodeOps, NodeRef, Wrapper}, gaddag::{Gaddag, SEPARATOR}};
+
+/// Letters that make another word when put before (`front`) or after (`back`) a word, in sorted order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Hooks {
+    pub front: Vec<char>,
+    pub back: Vec<char>,
+}
+
+impl Hooks {
+    /// value is true if no letter hooks onto the word
+    pub fn is_empty(&self) -> bool {
+        self.front.is_empty() && self.back.is_empty()
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the letters that make a word of the dawg when put before `word` (folded by the options), such as 'g' for
+    /// "host" ("ghost"). Without a reversed dawg to read them from, the word is walked from every letter leaving the root
+    pub fn front_hooks(&self, word: &str) -> Vec<char> {
+        let letters = self.options.fold_chars(word.chars()).collect::<Vec<_>>();
+
+        self.root.children().into_iter()
+            .filter(|(_, child)| letters.iter().try_fold(child.clone(), |node, letter| node.child(letter)).is_some_and(|node| node.is_terminal()))
+            .map(|(label, _)| label)
+            .collect()
+    }
+
+    /// Returns the letters that make a word of the dawg when put after `word` (folded by the options), such as 's' for
+    /// "host" ("hosts"): the labels of the terminal children of the node the word ends on
+    pub fn back_hooks(&self, word: &str) -> Vec<char> {
+        let end = self.options.fold_chars(word.chars()).try_fold(self.root.clone(), |node, letter| node.child(&letter));
+        end.map(|node| terminal_labels(&node)).unwrap_or_default()
+    }
+
+    /// Returns both the front and the back hooks of `word`
+    pub fn hooks(&self, word: &str) -> Hooks {
+        Hooks { front: self.front_hooks(word), back: self.back_hooks(word) }
+    }
+
+    /// Returns every word of the dawg in sorted order along with its hooks, one word at a time (for printing study sheets)
+    pub fn hook_sheet(&self) -> impl Iterator<Item = (String, Hooks)> + '_ {
+        self.words().map(|word| {
+            let hooks = self.hooks(&word);
+            (word, hooks)
+        })
+    }
+}
+
+impl<T> Gaddag<T> where T: Wrapper {
+    /// Same as `Dawg::front_hooks`, read from the reversed paths of the GADDAG: the node `reverse(word)` leads to has an
+    /// edge for every letter that can come before it, which ends the word if it is followed by a terminal `SEPARATOR`
+    pub fn front_hooks(&self, word: &str) -> Vec<char> {
+        let dawg = self.dawg();
+        let letters = dawg.options.fold_chars(word.chars()).collect::<Vec<_>>();
+        let Some(end) = letters.iter().rev().try_fold(dawg.root.clone(), |node, letter| node.child(letter)) else {
+            return vec![];
+        };
+
+        end.children().into_iter()
+            .filter(|(label, child)| *label != SEPARATOR && child.child(&SEPARATOR).is_some_and(|node| node.is_terminal()))
+            .map(|(label, _)| label)
+            .collect()
+    }
+
+    /// Same as `Dawg::back_hooks`: the terminal children of the node `reverse(word)`, `SEPARATOR` leads to. No path starts
+    /// with the separator, so the back hooks of the empty word are its front hooks, the words of a single letter
+    pub fn back_hooks(&self, word: &str) -> Vec<char> {
+        let dawg = self.dawg();
+        let letters = dawg.options.fold_chars(word.chars()).collect::<Vec<_>>();
+        if letters.is_empty() {
+            return self.front_hooks(word);
+        }
+        let end = letters.iter().rev().chain([&SEPARATOR]).try_fold(dawg.root.clone(), |node, letter| node.child(letter));
+        end.map(|node| terminal_labels(&node)).unwrap_or_default()
+    }
+
+    /// Returns both the front and the back hooks of `word`
+    pub fn hooks(&self, word: &str) -> Hooks {
+        Hooks { front: self.front_hooks(word), back: self.back_hooks(word) }
+    }
+}
+
+fn terminal_labels<N: NodeRef<Label = char>>(node: &N) -> Vec<char> {
+    node.children().into_iter().filter(|(_, child)| child.is_terminal()).map(|(label, _)| label).collect()
+}
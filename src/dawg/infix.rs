@@ -0,0 +1,267 @@
+//! Words containing a given infix, anywhere in them.
+//!
+//! Without an index, `Dawg::words_containing` reads every word. `Dawg::build_infix_index` builds a suffix array of the
+//! words: every position of every word, sorted by the rest of the word from there, so the words containing an infix are
+//! those of a single range of the array, found by binary search.
+//!
+//! A dawg saved with its index is made of the magic bytes `DAWI`, the version of its layout (u16), the length (u64) and
+//! the bytes of the dawg as written by `Dawg::save`, then the number of positions (u64) and the positions themselves (u32,
+//! byte offsets into the words of the dawg put end to end in sorted order). Numbers are little-endian
+
+use std::{io::{Read, Write}, sync::Arc, vec};
+
+use crate::{dawg::{common::{Dawg, NodeRef, Wrapper}, iter::Words, serialize::{write_u32, write_u64, Cursor}}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWI";
+const VERSION: u16 = 1;
+
+/// Suffix array of the words of a dawg, see the `infix` module. It holds the words themselves as well, so it takes about
+/// five bytes per letter of the words (more for letters outside ASCII)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InfixIndex {
+    /// the words in sorted order, put end to end
+    text: String,
+    /// offset of every word in `text`, followed by the length of `text`
+    starts: Vec<u32>,
+    /// offset of every letter of every word in `text`, sorted by the rest of its word from there
+    suffixes: Vec<u32>,
+}
+
+impl InfixIndex {
+    /// Puts the `words` end to end, without sorting their suffixes yet
+    fn with_words(words: impl Iterator<Item = String>) -> Self {
+        let mut text = String::new();
+        let mut starts = vec![];
+
+        for word in words {
+            starts.push(text.len() as u32);
+            text.push_str(&word);
+        }
+        starts.push(text.len() as u32);
+
+        Self { text, starts, suffixes: vec![] }
+    }
+
+    /// Indexes the `words`, given in sorted order
+    fn new(words: impl Iterator<Item = String>) -> Self {
+        let mut index = Self::with_words(words);
+
+        // the end of the word is kept along with every suffix while sorting, rather than looked up on each comparison
+        let mut suffixes = index.starts.windows(2)
+            .flat_map(|bounds| {
+                let (start, end) = (bounds[0] as usize, bounds[1] as usize);
+                index.text[start..end].char_indices().map(move |(offset, _)| ((start + offset) as u32, end as u32))
+            })
+            .collect::<Vec<_>>();
+        suffixes.sort_unstable_by(|(a, a_end), (b, b_end)| index.text[*a as usize..*a_end as usize].cmp(&index.text[*b as usize..*b_end as usize]));
+
+        index.suffixes = suffixes.into_iter().map(|(start, _)| start).collect();
+        index
+    }
+
+    /// Returns the number of words indexed
+    pub fn word_count(&self) -> usize {
+        self.starts.len() - 1
+    }
+
+    /// Returns the rank of the word holding the letter at `offset` in `text`
+    fn rank_at(&self, offset: u32) -> usize {
+        self.starts.partition_point(|&start| start <= offset) - 1
+    }
+
+    /// Returns the rest of the word from `offset`
+    fn suffix(&self, offset: u32) -> &str {
+        let end = self.starts[self.rank_at(offset) + 1];
+        &self.text[offset as usize..end as usize]
+    }
+
+    fn word(&self, rank: usize) -> &str {
+        &self.text[self.starts[rank] as usize..self.starts[rank + 1] as usize]
+    }
+
+    /// Returns the ranks of the words containing `infix` in increasing order, from the range of suffixes starting with it
+    fn ranks(&self, infix: &str) -> Vec<usize> {
+        if infix.is_empty() {
+            return (0..self.word_count()).collect();
+        }
+
+        let lower = self.suffixes.partition_point(|&offset| self.suffix(offset) < infix);
+        let upper = lower + self.suffixes[lower..].partition_point(|&offset| self.suffix(offset).starts_with(infix));
+
+        let mut ranks = self.suffixes[lower..upper].iter().map(|&offset| self.rank_at(offset)).collect::<Vec<_>>();
+        ranks.sort_unstable();
+        ranks.dedup();
+        ranks
+    }
+
+    /// Checks the saved `suffixes` against the words, of which there are as many as letters: every letter once, in sorted order
+    fn check(&self) -> Result<(), DawgError> {
+        let invalid = |reason: &str| Err(DawgError::InvalidFormat(format!("infix index {reason}")));
+
+        let mut seen = vec![false; self.text.len()];
+        for &offset in &self.suffixes {
+            let offset = offset as usize;
+            if offset >= self.text.len() || !self.text.is_char_boundary(offset) || std::mem::replace(&mut seen[offset], true) {
+                return invalid(&format!("has an invalid position {offset}"));
+            }
+        }
+
+        if self.suffixes.windows(2).any(|pair| self.suffix(pair[0]) > self.suffix(pair[1])) {
+            return invalid("is not sorted");
+        }
+        Ok(())
+    }
+}
+
+/// Iterator over the words containing an infix in sorted order, see `Dawg::words_containing`
+#[derive(Debug)]
+pub struct WordsContaining<'a, N: NodeRef<Label = char>> {
+    source: Source<'a, N>,
+    examined: usize,
+}
+
+#[derive(Debug)]
+enum Source<'a, N: NodeRef<Label = char>> {
+    /// ranks of the words containing the infix, found by the index
+    Indexed { index: &'a InfixIndex, ranks: vec::IntoIter<usize> },
+    /// every word of the dawg, each checked for the infix
+    Scan { words: Words<'a, N>, infix: String },
+}
+
+impl<'a, N> WordsContaining<'a, N> where N: NodeRef<Label = char> {
+    /// Returns the number of words read so far to find the matches: only the matching ones when the dawg has an index,
+    /// otherwise every word it went through
+    pub fn examined(&self) -> usize {
+        self.examined
+    }
+
+    /// value is true if the words are found through the index of the dawg rather than by reading them all
+    pub fn is_indexed(&self) -> bool {
+        matches!(self.source, Source::Indexed { .. })
+    }
+}
+
+impl<'a, N> Iterator for WordsContaining<'a, N> where N: NodeRef<Label = char> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        match &mut self.source {
+            Source::Indexed { index, ranks } => {
+                let rank = ranks.next()?;
+                self.examined += 1;
+                Some(index.word(rank).to_owned())
+            }
+            Source::Scan { words, infix } => {
+                for word in words.by_ref() {
+                    self.examined += 1;
+                    if word.contains(infix.as_str()) {
+                        return Some(word);
+                    }
+                }
+                None
+            }
+        }
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns every word containing `infix` (matched exactly) in sorted order.
+    ///
+    /// With an index (see `build_infix_index`), only the words containing the infix are read: they are found by binary
+    /// search in the suffix array. Otherwise every word of the dawg is read and checked, as `words().filter(...)` would
+    pub fn words_containing(&self, infix: &str) -> WordsContaining<'_, T::Node> {
+        let source = match &self.infix_index {
+            Some(index) => Source::Indexed { index, ranks: index.ranks(infix).into_iter() },
+            None => Source::Scan { words: self.words(), infix: infix.to_owned() },
+        };
+        WordsContaining { source, examined: 0 }
+    }
+
+    /// Builds the index `words_containing` goes through, replacing the previous one. Adding or removing words afterwards
+    /// drops it, it has to be built again then. Fails unless the dawg is finished
+    pub fn build_infix_index(&mut self) -> Result<(), DawgError> {
+        if !self.finished {
+            return Err(DawgError::NotFinished);
+        }
+
+        self.infix_index = Some(Arc::new(InfixIndex::new(self.words())));
+        Ok(())
+    }
+
+    /// the index built by `build_infix_index`, if the words haven't changed since
+    pub fn infix_index(&self) -> Option<&InfixIndex> {
+        self.infix_index.as_deref()
+    }
+
+    /// Drops the index, `words_containing` reads every word again
+    pub fn drop_infix_index(&mut self) {
+        self.infix_index = None;
+    }
+
+    /// Encodes the finished dawg along with its index, which is built first if the dawg has none (without keeping it),
+    /// see the `infix` module. Fails like `to_bytes` if the dawg isn't finished
+    pub fn to_bytes_with_infix_index(&self) -> Result<Vec<u8>, DawgError> {
+        let dawg = self.to_bytes()?;
+        let built;
+        let index = match &self.infix_index {
+            Some(index) => index,
+            None => {
+                built = InfixIndex::new(self.words());
+                &built
+            }
+        };
+
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&VERSION.to_le_bytes());
+
+        write_u64(&mut out, dawg.len() as u64);
+        out.extend_from_slice(&dawg);
+
+        write_u64(&mut out, index.suffixes.len() as u64);
+        for &offset in &index.suffixes {
+            write_u32(&mut out, offset);
+        }
+        Ok(out)
+    }
+
+    /// Writes the dawg and its index to `writer`, see `to_bytes_with_infix_index`
+    pub fn save_with_infix_index<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        writer.write_all(&self.to_bytes_with_infix_index()?)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Rebuilds a finished dawg and its index from the bytes written by `save_with_infix_index`. The words are read from the
+    /// dawg, the positions are checked against them
+    pub fn from_bytes_with_infix_index(bytes: &[u8]) -> Result<Self, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(DawgError::InvalidFormat(String::from("missing infix index magic bytes")));
+        }
+
+        let version = reader.u16()?;
+        if version > VERSION {
+            return Err(DawgError::NewerVersion { version, supported: VERSION });
+        }
+
+        let len = reader.u64()? as usize;
+        let mut dawg = Self::from_bytes(reader.bytes(len)?)?;
+
+        let mut index = InfixIndex::with_words(dawg.words());
+        let (positions, letters) = (reader.u64()? as usize, index.text.chars().count());
+        if positions != letters {
+            return Err(DawgError::InvalidFormat(format!("infix index has {positions} positions for {letters} letters")));
+        }
+        index.suffixes = (0..positions).map(|_| reader.u32()).collect::<Result<_, _>>()?;
+        index.check()?;
+
+        dawg.infix_index = Some(Arc::new(index));
+        Ok(dawg)
+    }
+
+    /// Reads a dawg and its index written by `save_with_infix_index` from `reader`
+    pub fn load_with_infix_index<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes_with_infix_index(&bytes)
+    }
+}
@@ -0,0 +1,284 @@
+use std::{fmt::Display, fs::File, io::{self, BufReader, Read, Write}, path::Path};
+
+use crate::{dawg::{compiled, options::DawgOptions, serialize::{self, Cursor, SavedHeader}}, error::DawgError};
+
+/// Lookup table of the CRC-32 (IEEE) of every byte
+const CRC_TABLE: [u32; 256] = {
+    let mut table = [0; 256];
+    let mut byte = 0;
+    while byte < 256 {
+        let mut crc = byte as u32;
+        let mut bit = 0;
+        while bit < 8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+            bit += 1;
+        }
+        table[byte] = crc;
+        byte += 1;
+    }
+    table
+};
+
+/// Running CRC-32 (IEEE, as used by zip and png) of the bytes written to it
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct Crc32(u32);
+
+impl Crc32 {
+    pub(crate) fn new() -> Self {
+        Self(!0)
+    }
+
+    pub(crate) fn update(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 = CRC_TABLE[((self.0 ^ *byte as u32) & 0xFF) as usize] ^ (self.0 >> 8);
+        }
+    }
+
+    pub(crate) fn finish(self) -> u32 {
+        !self.0
+    }
+
+    pub(crate) fn of(bytes: &[u8]) -> u32 {
+        let mut crc = Self::new();
+        crc.update(bytes);
+        crc.finish()
+    }
+}
+
+impl Write for Crc32 {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.update(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+/// Part of a saved dawg covered by a checksum of its own
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Section {
+    /// magic bytes, version, options and the size of the other sections
+    Header,
+    /// terminal flags, reachable counts and edge ranges of the nodes
+    Nodes,
+    Edges,
+    /// the bytes of the labels, referred to by the edges of a saved Dawg
+    Labels,
+    /// the dispatch tables of a saved CompiledDawg
+    Slots,
+}
+
+impl Display for Section {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(match self {
+            Section::Header => "header",
+            Section::Nodes => "node table",
+            Section::Edges => "edge table",
+            Section::Labels => "label table",
+            Section::Slots => "dispatch tables",
+        })
+    }
+}
+
+/// Size of the chunks the sections of a saved Dawg are checksummed by from version 4 of its layout
+pub(crate) const CHUNK_SIZE: usize = 1 << 20;
+
+/// How the sections of a saved Dawg are checked
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Checksums {
+    /// they aren't, before version 3 of the layout
+    None,
+    /// every section is followed by its checksum
+    Section,
+    /// every section is followed by the checksums of its chunks of `CHUNK_SIZE` bytes, which can be checked independently
+    Chunks,
+}
+
+impl Checksums {
+    /// Number of bytes of checksums following a section of `len` bytes
+    pub(crate) fn trailer_len(self, len: u64) -> u64 {
+        match self {
+            Checksums::None => 0,
+            Checksums::Section => 4,
+            Checksums::Chunks => len.div_ceil(CHUNK_SIZE as u64) * 4,
+        }
+    }
+}
+
+/// Appends `section` to `out` followed by its checksum
+pub(crate) fn write_section(out: &mut Vec<u8>, section: &[u8]) {
+    out.extend_from_slice(section);
+    serialize::write_u32(out, Crc32::of(section));
+}
+
+/// Reads the next `len` bytes as `section`, checking them against the checksum following them if `checksummed`
+pub(crate) fn read_section<'a>(reader: &mut Cursor<'a>, section: Section, len: usize, checksummed: bool) -> Result<&'a [u8], DawgError> {
+    let bytes = reader.bytes(len).map_err(|_| DawgError::Truncated { section })?;
+    if checksummed {
+        let expected = reader.u32().map_err(|_| DawgError::Truncated { section })?;
+        check(section, expected, Crc32::of(bytes))?;
+    }
+    Ok(bytes)
+}
+
+pub(crate) fn check(section: Section, expected: u32, found: u32) -> Result<(), DawgError> {
+    match expected == found {
+        true => Ok(()),
+        false => Err(DawgError::Corrupted { section, expected, found }),
+    }
+}
+
+/// Reads `len` bytes of `reader` as `section` without keeping them, then checks them against the checksum following them
+fn stream_section(reader: &mut impl Read, section: Section, len: u64) -> Result<(), DawgError> {
+    let mut crc = Crc32::new();
+    if io::copy(&mut reader.take(len), &mut crc)? != len {
+        return Err(DawgError::Truncated { section });
+    }
+
+    let mut expected = [0; 4];
+    reader.read_exact(&mut expected).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => DawgError::Truncated { section },
+        _ => DawgError::Io(err),
+    })?;
+    check(section, u32::from_le_bytes(expected), crc.finish())
+}
+
+/// Appends `section` to `out` followed by the checksums of its chunks of `CHUNK_SIZE` bytes
+pub(crate) fn write_chunked_section(out: &mut Vec<u8>, section: &[u8]) {
+    out.extend_from_slice(section);
+    for chunk in section.chunks(CHUNK_SIZE) {
+        serialize::write_u32(out, Crc32::of(chunk));
+    }
+}
+
+/// Reads the next `len` bytes as `section` along with the checksums of its chunks following them, without checking them
+pub(crate) fn read_chunked_section<'a>(reader: &mut Cursor<'a>, section: Section, len: usize) -> Result<(&'a [u8], &'a [u8]), DawgError> {
+    let bytes = reader.bytes(len).map_err(|_| DawgError::Truncated { section })?;
+    let checksums = reader.bytes(len.div_ceil(CHUNK_SIZE) * 4).map_err(|_| DawgError::Truncated { section })?;
+    Ok((bytes, checksums))
+}
+
+/// Checks the chunks of `CHUNK_SIZE` bytes of `bytes`, a part of `section` starting on a chunk, against `checksums`
+pub(crate) fn check_chunks(section: Section, bytes: &[u8], checksums: &[u8]) -> Result<(), DawgError> {
+    for (chunk, expected) in bytes.chunks(CHUNK_SIZE).zip(checksums.chunks_exact(4)) {
+        check(section, u32::from_le_bytes(expected.try_into().unwrap()), Crc32::of(chunk))?;
+    }
+    Ok(())
+}
+
+/// Reads `len` bytes of `reader` as `section` without keeping them, then checks their chunks against the checksums following them
+fn stream_chunked_section(reader: &mut impl Read, section: Section, len: u64) -> Result<(), DawgError> {
+    let mut found = vec![];
+    let mut left = len;
+    while left > 0 {
+        let chunk = left.min(CHUNK_SIZE as u64);
+        let mut crc = Crc32::new();
+        if io::copy(&mut reader.take(chunk), &mut crc)? != chunk {
+            return Err(DawgError::Truncated { section });
+        }
+        found.push(crc.finish());
+        left -= chunk;
+    }
+
+    let mut expected = vec![0; found.len() * 4];
+    reader.read_exact(&mut expected).map_err(|err| match err.kind() {
+        io::ErrorKind::UnexpectedEof => DawgError::Truncated { section },
+        _ => DawgError::Io(err),
+    })?;
+    for (expected, found) in expected.chunks_exact(4).zip(found) {
+        check(section, u32::from_le_bytes(expected.try_into().unwrap()), found)?;
+    }
+    Ok(())
+}
+
+/// Checks the checksums of the Dawg or CompiledDawg saved at `path` without building it, reading the file once. The word
+/// counts of a Dawg are checked as well, which only takes its node and edge tables in memory (the labels are streamed).
+/// Files saved before checksums were added to the format only have the size of their sections and the counts checked
+pub fn verify_file(path: impl AsRef<Path>) -> Result<(), DawgError> {
+    verify(BufReader::new(File::open(path)?))
+}
+
+/// Same as `verify_file`, reading the saved dawg from `reader`
+pub fn verify(mut reader: impl Read) -> Result<(), DawgError> {
+    let truncated = |err: io::Error| match err.kind() {
+        io::ErrorKind::UnexpectedEof => DawgError::Truncated { section: Section::Header },
+        _ => DawgError::Io(err),
+    };
+
+    let mut header = vec![0; 4 + 2 + 4];
+    reader.read_exact(&mut header).map_err(truncated)?;
+    let mut prefix = Cursor::new(&header);
+    let (magic, version, options_len) = (prefix.bytes(4)?, prefix.u16()?, prefix.u32()? as u64);
+
+    // the records following the options: the size of every section, then the root for a Dawg
+    let (counts, checksums, record_sizes) = match magic {
+        magic if magic == serialize::MAGIC => (4, match version { ..=2 => Checksums::None, 3 => Checksums::Section, _ => Checksums::Chunks }, [serialize::NODE_SIZE, serialize::EDGE_SIZE, 1]),
+        magic if magic == compiled::MAGIC => (3, if version >= 2 { Checksums::Section } else { Checksums::None }, [compiled::NODE_SIZE, compiled::EDGE_SIZE, compiled::SLOT_SIZE]),
+        _ => return Err(DawgError::InvalidFormat(String::from("missing DAWG magic bytes"))),
+    };
+    let dawg = magic == serialize::MAGIC;
+    let (last, supported) = if dawg { (Section::Labels, serialize::VERSION) } else { (Section::Slots, compiled::VERSION) };
+    if version > supported {
+        return Err(DawgError::NewerVersion { version, supported });
+    }
+
+    let rest = options_len + counts * 4;
+    if reader.by_ref().take(rest).read_to_end(&mut header)? as u64 != rest {
+        return Err(DawgError::Truncated { section: Section::Header });
+    }
+
+    let mut sizes = Cursor::new(&header[header.len() - counts as usize * 4..]);
+    let sizes = [sizes.u32()?, sizes.u32()?, sizes.u32()?];
+    if checksums != Checksums::None {
+        let mut expected = [0; 4];
+        reader.read_exact(&mut expected).map_err(truncated)?;
+        check(Section::Header, u32::from_le_bytes(expected), Crc32::of(&header))?;
+    }
+
+    // the node and edge tables of a Dawg are kept to check its word counts
+    let mut tables = vec![];
+    for ((section, size), record_size) in [Section::Nodes, Section::Edges, last].into_iter().zip(sizes).zip(record_sizes) {
+        let len = size as u64 * record_size as u64;
+        let mut reader = Keep { reader: &mut reader, kept: vec![], keep: dawg && section != last };
+        match checksums {
+            Checksums::Section => stream_section(&mut reader, section, len)?,
+            Checksums::Chunks => stream_chunked_section(&mut reader, section, len)?,
+            Checksums::None if io::copy(&mut reader.by_ref().take(len), &mut io::sink())? != len => return Err(DawgError::Truncated { section }),
+            Checksums::None => {}
+        }
+        reader.kept.truncate(len as usize);
+        tables.push(reader.kept);
+    }
+
+    if dawg {
+        let root = Cursor::new(&header[header.len() - 4..]).u32()?;
+        let header = SavedHeader { options: DawgOptions::default(), node_count: sizes[0], edge_count: sizes[1], label_len: sizes[2], root, checksums };
+        let (nodes, edges) = (header.nodes(0, &tables[0])?, header.edges(0, &tables[1])?);
+        let edges_of = |id: usize| edges[nodes[id].first_edge as usize..(nodes[id].first_edge + nodes[id].edges) as usize].iter().map(|edge| edge.child);
+        let topological = serialize::topological_order(nodes.len(), edges_of)?;
+        serialize::check_counts(&nodes, &edges, &topological)?;
+        serialize::count_prefixes(&nodes, &edges, &topological, root)?;
+    }
+
+    Ok(())
+}
+
+/// Reader keeping a copy of the bytes read through it if `keep`
+struct Keep<'a, R> {
+    reader: &'a mut R,
+    kept: Vec<u8>,
+    keep: bool,
+}
+
+impl<'a, R> Read for Keep<'a, R> where R: Read {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.reader.read(buf)?;
+        if self.keep {
+            self.kept.extend_from_slice(&buf[..read]);
+        }
+        Ok(read)
+    }
+}
@@ -0,0 +1,129 @@
+use std::{collections::{HashMap, HashSet}, error::Error, fmt::Display};
+
+use crate::dawg::common::{Dawg, DawgNode, Label, NodeOps, NodeRef, Wrapper};
+
+/// A rule of the structure of a Dawg found broken by `Dawg::assert_invariants`, naming the id of the node at fault.
+/// Edges are shared pointers, so an edge can't lead to a node that no longer exists and that rule isn't checked
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum InvariantViolation {
+    /// following the edges from `node` leads back to it
+    Cycle { node: usize },
+    /// two different nodes share the id `node`
+    DuplicateId { node: usize },
+    /// the word count stored on a node of a finished dawg isn't the number of words below it
+    WrongCount { node: usize, stored: usize, actual: usize },
+    /// `what` (a collection only used while building) isn't empty although the dawg is finished
+    LeftoverBuildState { what: &'static str },
+    /// the nodes waiting to be minimized don't form a path from the root, the first one off the path is at `depth`
+    BrokenPendingPath { node: usize, depth: usize },
+    /// `node` and `twin` accept the same suffixes (ids as given by `visit_nodes`), so the dawg isn't minimal
+    NotMinimal { node: usize, twin: usize },
+}
+
+impl Display for InvariantViolation {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            InvariantViolation::Cycle { node } => write!(f, "node {node} can be reached from itself"),
+            InvariantViolation::DuplicateId { node } => write!(f, "several nodes have the id {node}"),
+            InvariantViolation::WrongCount { node, stored, actual } => write!(f, "node {node} counts {stored} words but {actual} can be formed from it"),
+            InvariantViolation::LeftoverBuildState { what } => write!(f, "the dawg is finished but its {what} aren't empty"),
+            InvariantViolation::BrokenPendingPath { node, depth } => write!(f, "pending node {node} at depth {depth} isn't on the path of the last word"),
+            InvariantViolation::NotMinimal { node, twin } => write!(f, "nodes {node} and {twin} are equivalent"),
+        }
+    }
+}
+
+impl Error for InvariantViolation {}
+
+/// Address of the node behind `node`, which tells nodes apart whatever their ids
+fn address<N: NodeRef>(node: &N) -> usize {
+    node.read(|inner| inner as *const DawgNode<N> as usize)
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Checks the structure of the dawg, for use after extending the crate or loading untrusted bytes:
+    /// the graph is acyclic, ids are unique, the word counts of a finished dawg are right, a finished dawg holds no
+    /// building state (the last word is kept on purpose) and the nodes of a dawg being built form the path of its last word.
+    /// Minimality is only checked by `assert_minimal`, since editing a finished dawg gives up on it
+    pub fn assert_invariants(&self) -> Result<(), InvariantViolation> {
+        let mut ids: HashMap<usize, usize> = HashMap::new();
+        let mut words: HashMap<usize, usize> = HashMap::new();
+        let mut on_path = HashSet::new();
+        let mut stack: Vec<(T::Node, Vec<_>, usize)> = vec![];
+
+        let mut enter = |node: &T::Node, on_path: &mut HashSet<usize>| {
+            let id = node.id();
+            match *ids.entry(id).or_insert(address(node)) == address(node) {
+                true => Ok(on_path.insert(address(node))),
+                false => Err(InvariantViolation::DuplicateId { node: id }),
+            }
+        };
+
+        enter(&self.root, &mut on_path)?;
+        stack.push((self.root.clone(), self.root.children(), 0));
+
+        while let Some((node, children, next)) = stack.last_mut() {
+            if let Some((_, child)) = children.get(*next) {
+                let child = child.clone();
+                *next += 1;
+
+                if on_path.contains(&address(&child)) {
+                    return Err(InvariantViolation::Cycle { node: child.id() });
+                }
+                if !words.contains_key(&address(&child)) {
+                    enter(&child, &mut on_path)?;
+                    let grand_children = child.children();
+                    stack.push((child, grand_children, 0));
+                }
+                continue;
+            }
+
+            let actual = node.is_terminal() as usize + children.iter().map(|(_, child)| words[&address(child)]).sum::<usize>();
+            if self.finished && node.count() != actual {
+                return Err(InvariantViolation::WrongCount { node: node.id(), stored: node.count(), actual });
+            }
+
+            on_path.remove(&address(node));
+            words.insert(address(node), actual);
+            stack.pop();
+        }
+
+        if self.finished {
+            let leftovers = [
+                ("pending nodes", self.unchecked_nodes.is_empty()),
+                ("minimized nodes", self.minimized_nodes.is_empty()),
+                ("staged words", self.staged.is_empty()),
+            ];
+            if let Some((what, _)) = leftovers.into_iter().find(|(_, empty)| !empty) {
+                return Err(InvariantViolation::LeftoverBuildState { what });
+            }
+        }
+
+        let mut parent = self.root.clone();
+        for (depth, tri) in self.unchecked_nodes.iter().enumerate() {
+            let on_path = address(&tri.parent) == address(&parent) && parent.child(&tri.letter).is_some_and(|child| address(&child) == address(&tri.child));
+            if !on_path {
+                return Err(InvariantViolation::BrokenPendingPath { node: tri.child.id(), depth });
+            }
+            parent = tri.child.clone();
+        }
+
+        Ok(())
+    }
+
+    /// Runs `assert_invariants`, then checks that no two nodes accept the same suffixes (see `verify_minimal`)
+    pub fn assert_minimal(&self) -> Result<(), InvariantViolation> {
+        self.assert_invariants()?;
+
+        let mut seen = HashMap::new();
+        for view in self.visit_nodes() {
+            let signature = (view.is_terminal(), view.edges().map(|(label, id)| (label.clone(), id)).collect::<Vec<_>>());
+            if let Some(twin) = seen.insert(signature, view.id()) {
+                return Err(InvariantViolation::NotMinimal { node: view.id(), twin });
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,178 @@
+use std::marker::PhantomData;
+
+use crate::dawg::common::{Dawg, Label, NodeOps, NodeRef, Wrapper};
+
+/// Iterator over the sequences of a Dawg in sorted order, walking the automaton depth first.
+/// It can also be walked from the back, in which case the two ends stop once they meet
+#[derive(Debug)]
+pub struct Sequences<'a, N: NodeRef> {
+    stack: Vec<(N, Vec<N::Label>)>,
+    /// (node, path to it, whether its children were already pushed) for the walk from the back
+    back_stack: Vec<(N, Vec<N::Label>, bool)>,
+    /// the last sequences yielded from the front and from the back, nothing past them may be yielded again
+    front: Option<Vec<N::Label>>,
+    back: Option<Vec<N::Label>>,
+    /// number of sequences not yielded yet from either end
+    remaining: usize,
+    dawg: PhantomData<&'a ()>,
+}
+
+impl<'a, N> Sequences<'a, N> where N: NodeRef {
+    /// Iterates the sequences below `node`, each prefixed with `prefix`
+    pub(crate) fn new(node: Option<N>, prefix: Vec<N::Label>) -> Self {
+        Self {
+            remaining: node.as_ref().map_or(0, |node| node.reachable()),
+            stack: node.iter().map(|node| (node.clone(), prefix.clone())).collect(),
+            back_stack: node.map(|node| vec![(node, prefix, false)]).unwrap_or_default(),
+            front: None,
+            back: None,
+            dawg: PhantomData,
+        }
+    }
+
+    /// Stops the iteration from both ends
+    fn exhaust(&mut self) {
+        self.stack.clear();
+        self.back_stack.clear();
+    }
+}
+
+impl<'a, N> Iterator for Sequences<'a, N> where N: NodeRef {
+    type Item = Vec<N::Label>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while let Some((node, prefix)) = self.stack.pop() {
+            for (letter, child) in node.children().into_iter().rev() {
+                let mut word = prefix.clone();
+                word.push(letter);
+                self.stack.push((child, word));
+            }
+
+            if node.is_terminal() {
+                if self.back.as_ref().is_some_and(|back| *back <= prefix) {
+                    self.exhaust();
+                    return None;
+                }
+
+                self.front = Some(prefix.clone());
+                self.remaining = self.remaining.saturating_sub(1);
+                return Some(prefix);
+            }
+        }
+
+        None
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+impl<'a, N> ExactSizeIterator for Sequences<'a, N> where N: NodeRef {}
+
+impl<'a, N> DoubleEndedIterator for Sequences<'a, N> where N: NodeRef {
+    /// Sequences are yielded in descending order: a node's own sequence comes after every longer one below it
+    fn next_back(&mut self) -> Option<Self::Item> {
+        while let Some((node, prefix, expanded)) = self.back_stack.pop() {
+            if !expanded {
+                let children = node.children();
+                self.back_stack.push((node, prefix.clone(), true));
+
+                for (letter, child) in children {
+                    let mut word = prefix.clone();
+                    word.push(letter);
+                    self.back_stack.push((child, word, false));
+                }
+                continue;
+            }
+
+            if node.is_terminal() {
+                if self.front.as_ref().is_some_and(|front| *front >= prefix) {
+                    self.exhaust();
+                    return None;
+                }
+
+                self.back = Some(prefix.clone());
+                self.remaining = self.remaining.saturating_sub(1);
+                return Some(prefix);
+            }
+        }
+
+        None
+    }
+}
+
+/// Iterator over the words of a Dawg in sorted order
+#[derive(Debug)]
+pub struct Words<'a, N: NodeRef<Label = char>> {
+    sequences: Sequences<'a, N>,
+}
+
+impl<'a, N> Words<'a, N> where N: NodeRef<Label = char> {
+    pub(crate) fn new(sequences: Sequences<'a, N>) -> Self {
+        Self { sequences }
+    }
+}
+
+impl<'a, N> Iterator for Words<'a, N> where N: NodeRef<Label = char> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.sequences.next().map(|word| word.into_iter().collect())
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.sequences.size_hint()
+    }
+}
+
+impl<'a, N> ExactSizeIterator for Words<'a, N> where N: NodeRef<Label = char> {}
+
+impl<'a, N> DoubleEndedIterator for Words<'a, N> where N: NodeRef<Label = char> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        self.sequences.next_back().map(|word| word.into_iter().collect())
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Returns every sequence in the dawg in sorted order
+    pub fn sequences(&self) -> Sequences<'_, T::Node> {
+        Sequences::new(Some(self.root.clone()), vec![])
+    }
+
+    /// Returns every sequence starting with `prefix` in sorted order
+    pub fn sequences_with_prefix(&self, prefix: &[L]) -> Sequences<'_, T::Node> {
+        Sequences::new(self.find_sequence(prefix), prefix.to_vec())
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns every word in the dawg in sorted order
+    pub fn words(&self) -> Words<'_, T::Node> {
+        Words::new(self.sequences())
+    }
+
+    /// Returns every word starting with `prefix` (matched exactly) in sorted order
+    pub fn words_with_prefix(&self, prefix: &str) -> Words<'_, T::Node> {
+        Words::new(self.sequences_with_prefix(&prefix.chars().collect::<Vec<_>>()))
+    }
+}
+
+#[cfg(all(test, feature = "unsync"))]
+mod tests {
+    use crate::{NodeRef, UnsyncDawg};
+
+    #[test]
+    fn a_wrong_count_can_not_make_the_length_underflow() {
+        let mut dawg = UnsyncDawg::new();
+        dawg.add_all(["a", "b", "c"]).unwrap();
+        dawg.finish();
+        dawg.root.write(|root| root.count = 1);
+
+        let mut words = dawg.words();
+        assert_eq!(words.len(), 1);
+        assert_eq!(words.by_ref().collect::<Vec<_>>(), ["a", "b", "c"]);
+        assert_eq!(words.len(), 0);
+        assert_eq!(dawg.words().rev().count(), 3);
+    }
+}
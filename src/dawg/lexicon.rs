@@ -0,0 +1,167 @@
+use std::collections::HashMap;
+
+use crate::{dawg::{common::{Dawg, NodeOps, Wrapper}, iter::{Sequences, Words}, setops::KMerge}, node::count_reachable};
+
+/// Base letters of U+00C0..U+017F (Latin-1 Supplement and Latin Extended-A) with their accents removed, `.` if there is none
+const LATIN_BASE: &[u8] = b"AAAAAA.CEEEEIIII.NOOOOO.OUUUUY..aaaaaa.ceeeeiiii.nooooo.ouuuuy.yAaAaAaCcCcCcCcDdDdEeEeEeEeEeGgGgGgGgHhHhIiIiIiIiIi..JjKk.LlLlLlLlLlNnNnNn...OoOoOo..RrRrRrSsSsSsSsTtTtTtUuUuUuUuUuUuWwYyYZzZzZz.";
+
+/// How `MatchMode::UnicodeInsensitive` folds letters before comparing them
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum FoldProfile {
+    /// letters match if they are equal once upper-cased
+    #[default]
+    Default,
+    /// letters match if they are equal once upper-cased and stripped of their accents (for the Latin letters of U+00C0..U+017F)
+    IgnoreAccents,
+}
+
+impl FoldProfile {
+    fn strip_accent(letter: char) -> char {
+        match (letter as usize).checked_sub(0xC0).and_then(|index| LATIN_BASE.get(index)) {
+            Some(b'.') | None => letter,
+            Some(base) => *base as char,
+        }
+    }
+
+    fn fold(self, letter: char) -> std::char::ToUppercase {
+        match self {
+            FoldProfile::Default => letter.to_uppercase(),
+            FoldProfile::IgnoreAccents => Self::strip_accent(letter).to_uppercase(),
+        }
+    }
+}
+
+/// How letters are compared while searching the dawg
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum MatchMode {
+    /// letters must match exactly
+    #[default]
+    Exact,
+    /// ASCII letters match regardless of their case, any other letter must match exactly
+    AsciiInsensitive,
+    /// letters match if they are equal once folded by the profile
+    UnicodeInsensitive(FoldProfile),
+}
+
+impl MatchMode {
+    /// value is true if `label` (from the dawg) matches the searched `letter` under this mode
+    pub fn matches(self, label: char, letter: char) -> bool {
+        match self {
+            MatchMode::Exact => label == letter,
+            MatchMode::AsciiInsensitive => label.eq_ignore_ascii_case(&letter),
+            MatchMode::UnicodeInsensitive(profile) => label == letter || profile.fold(label).eq(profile.fold(letter)),
+        }
+    }
+}
+
+impl From<bool> for MatchMode {
+    /// maps the `case_sensitive` flag taken by the older query methods
+    fn from(case_sensitive: bool) -> Self {
+        if case_sensitive { MatchMode::Exact } else { MatchMode::UnicodeInsensitive(FoldProfile::Default) }
+    }
+}
+
+/// Read-only queries shared by every flavour of Dawg, so generic code can accept `impl Lexicon`
+pub trait Lexicon {
+    /// value is true if `word` is one of the words in the lexicon
+    fn contains(&self, word: &str, mode: MatchMode) -> bool;
+
+    /// value is true if at least one word in the lexicon starts with `prefix` (every word starts with the empty prefix)
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool;
+
+    /// Returns every word starting with `prefix` in sorted order
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String>;
+
+    /// Returns the number of words in the lexicon
+    fn word_count(&self) -> usize;
+
+    /// Returns the first `limit` words starting with `prefix` in sorted order. Lists every completion unless overridden
+    fn completions_limit(&self, prefix: &str, mode: MatchMode, limit: usize) -> Vec<String> {
+        let mut words = self.completions(prefix, mode);
+        words.truncate(limit);
+        words
+    }
+
+    /// Returns the number of words starting with `prefix`. Lists every completion unless overridden
+    fn count_completions(&self, prefix: &str, mode: MatchMode) -> usize {
+        self.completions(prefix, mode).len()
+    }
+}
+
+macro_rules! forward_lexicon {
+    ($($pointer:ty),*) => {$(
+        impl<D> Lexicon for $pointer where D: Lexicon + ?Sized {
+            fn contains(&self, word: &str, mode: MatchMode) -> bool {
+                (**self).contains(word, mode)
+            }
+
+            fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+                (**self).contains_prefix(prefix, mode)
+            }
+
+            fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+                (**self).completions(prefix, mode)
+            }
+
+            fn word_count(&self) -> usize {
+                (**self).word_count()
+            }
+
+            fn completions_limit(&self, prefix: &str, mode: MatchMode, limit: usize) -> Vec<String> {
+                (**self).completions_limit(prefix, mode, limit)
+            }
+
+            fn count_completions(&self, prefix: &str, mode: MatchMode) -> usize {
+                (**self).count_completions(prefix, mode)
+            }
+        }
+    )*};
+}
+
+forward_lexicon!(&D, Box<D>, std::rc::Rc<D>, std::sync::Arc<D>);
+
+impl<T> Lexicon for Dawg<T> where T: Wrapper {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        self.is_word_chars(word.chars(), mode)
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        self.contains_prefix_chars(prefix.chars(), mode)
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        let mut words = vec![];
+
+        for res in self.find(prefix, mode) {
+            words.extend(Words::new(Sequences::new(Some(res.node), res.word.chars().collect())));
+        }
+
+        words.sort();
+        words.dedup();
+        words
+    }
+
+    fn word_count(&self) -> usize {
+        self.count_words()
+    }
+
+    /// Stops once `limit` words are listed, merging the words below every node the prefix leads to in sorted order
+    fn completions_limit(&self, prefix: &str, mode: MatchMode, limit: usize) -> Vec<String> {
+        let below = self.find(prefix, mode).into_iter().map(|res| Words::new(Sequences::new(Some(res.node), res.word.chars().collect())));
+        KMerge::new(below).take(limit).collect()
+    }
+
+    /// Read from the word counts of the nodes the prefix leads to (recounted if the dawg isn't finished), without listing
+    /// the words
+    fn count_completions(&self, prefix: &str, mode: MatchMode) -> usize {
+        let mut recounted = HashMap::new();
+        self.find_chars(prefix.chars(), mode, false).iter()
+            .map(|res| match self.finished {
+                true => res.node.count(),
+                false => count_reachable(&res.node, &mut recounted),
+            })
+            .sum()
+    }
+}
@@ -0,0 +1,78 @@
+//! The least recently used cache behind the register spilling to disk, the pages of `PagedDawgReader`, the shards of
+//! `DawgForest` and the results of `CachedDawg`. Each of them bounds it (and counts what it evicts) in its own way
+
+use std::{borrow::Borrow, collections::{BTreeMap, HashMap}, hash::Hash};
+
+/// Map remembering when each of its entries was last used, so the least recently used one can be dropped first
+#[derive(Debug, Clone)]
+pub(crate) struct Lru<K, V> {
+    entries: HashMap<K, (V, u64)>,
+    /// keys by the time they were last used, the least recently used first
+    recency: BTreeMap<u64, K>,
+    clock: u64,
+}
+
+impl<K, V> Default for Lru<K, V> {
+    fn default() -> Self {
+        Self { entries: HashMap::new(), recency: BTreeMap::new(), clock: 0 }
+    }
+}
+
+impl<K, V> Lru<K, V> where K: Hash + Eq + Clone {
+    pub(crate) fn new() -> Self {
+        Self::default()
+    }
+
+    pub(crate) fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    fn tick(&mut self) -> u64 {
+        self.clock += 1;
+        self.clock
+    }
+
+    /// Returns the value of `key`, which becomes the most recently used entry
+    pub(crate) fn get<Q>(&mut self, key: &Q) -> Option<&V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let clock = self.tick();
+        let (value, used) = self.entries.get_mut(key)?;
+        let key = self.recency.remove(used).unwrap();
+        *used = clock;
+        self.recency.insert(clock, key);
+        Some(value)
+    }
+
+    /// Adds `value` as the most recently used entry, replacing the value of `key` if there was one
+    pub(crate) fn insert(&mut self, key: K, value: V) -> &V {
+        let clock = self.tick();
+        if let Some((_, used)) = self.entries.remove(&key) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(clock, key.clone());
+        &self.entries.entry(key).or_insert((value, clock)).0
+    }
+
+    pub(crate) fn remove<Q>(&mut self, key: &Q) -> Option<V> where K: Borrow<Q>, Q: Hash + Eq + ?Sized {
+        let (value, used) = self.entries.remove(key)?;
+        self.recency.remove(&used);
+        Some(value)
+    }
+
+    /// Removes the least recently used entry
+    pub(crate) fn pop_oldest(&mut self) -> Option<(K, V)> {
+        let (_, key) = self.recency.pop_first()?;
+        let (value, _) = self.entries.remove(&key)?;
+        Some((key, value))
+    }
+
+    pub(crate) fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Copy of the cache whose values are made by `copy`, used in the same order
+    pub(crate) fn map<W>(&self, mut copy: impl FnMut(&V) -> W) -> Lru<K, W> {
+        let entries = self.entries.iter().map(|(key, (value, used))| (key.clone(), (copy(value), *used))).collect();
+        Lru { entries, recency: self.recency.clone(), clock: self.clock }
+    }
+}
@@ -0,0 +1,127 @@
+use std::{borrow::Cow, fmt::Debug};
+
+use crate::{dawg::{common::{Dawg, Wrapper}, options::{DawgOptions, SortDirection}, setops::KMerge}, error::{DawgError, OptionsConflict}};
+
+/// Dawg mapping each of its words to a value.
+///
+/// The dawg only stores the words: values are kept in an array in the sorted order of the words, and found through the rank
+/// of their word (see `Dawg::rank`). Words must thus be inserted in the order of their letters, so a map can't use a sort
+/// order other than `SortOrder::Bytes` or `SortOrder::Chars`, nor a `SortDirection` other than ascending. A duplicate word keeps the value it was first inserted with,
+/// unless the duplicate policy rejects it
+pub struct DawgMap<T: Wrapper, V> {
+    dawg: Dawg<T>,
+    values: Vec<V>,
+}
+
+/// Map from words to weights, such as their frequency in a corpus
+pub type WeightedDawg<T> = DawgMap<T, u64>;
+
+impl<T, V> Debug for DawgMap<T, V> where T: Wrapper, Dawg<T>: Debug, V: Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("DawgMap").field("dawg", &self.dawg).field("values", &self.values).finish()
+    }
+}
+
+impl<T, V> Clone for DawgMap<T, V> where T: Wrapper + Clone, V: Clone {
+    fn clone(&self) -> Self {
+        Self { dawg: self.dawg.clone(), values: self.values.clone() }
+    }
+}
+
+impl<T, V> DawgMap<T, V> where T: Wrapper {
+    /// Creates an empty map whose dawg is configured by `options`
+    pub fn with_options(options: DawgOptions) -> Result<Self, DawgError> {
+        Self::check_options(&options)?;
+        Ok(Self { dawg: Dawg::with_options(options)?, values: vec![] })
+    }
+
+    /// Fails unless the words of a dawg configured by `options` are sorted in the order of their letters
+    pub(crate) fn check_options(options: &DawgOptions) -> Result<(), DawgError> {
+        if !options.sort_order.follows_letters() || options.sort_direction != SortDirection::Ascending {
+            return Err(DawgError::InvalidOptions(OptionsConflict::LetterOrder { by: "a DawgMap" }));
+        }
+        Ok(())
+    }
+
+    /// Puts together a finished `dawg` and the values of its words, in the sorted order of the words
+    pub(crate) fn from_parts(dawg: Dawg<T>, values: Vec<V>) -> Self {
+        Self { dawg, values }
+    }
+
+    /// the values of the words, in the sorted order of the words (the value of a word is at its rank)
+    pub(crate) fn values(&self) -> &[V] {
+        &self.values
+    }
+
+    /// the dawg of the words of the map
+    pub fn dawg(&self) -> &Dawg<T> {
+        &self.dawg
+    }
+
+    /// Inserts `word` (going through `Dawg::try_add`) along with its value
+    pub fn try_insert(&mut self, word: &str, value: V) -> Result<(), DawgError> {
+        let letters = self.dawg.options.fold(word).chars().collect::<Vec<_>>();
+        let duplicate = self.dawg.words_added > 0 && self.dawg.previous_word == letters;
+
+        self.dawg.try_add(word)?;
+        if !duplicate {
+            self.values.push(value);
+        }
+        Ok(())
+    }
+
+    pub fn finish(&mut self) {
+        self.dawg.finish();
+    }
+
+    /// Returns the number of words in the map
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Returns the value of `word`, folded by the options of the dawg first
+    pub fn get(&self, word: &str) -> Option<&V> {
+        let letters = self.dawg.options.fold_chars(word.chars()).collect::<Vec<_>>();
+        self.dawg.rank_sequence(&letters).map(|rank| &self.values[rank])
+    }
+
+    /// Returns every word with its value, in sorted order
+    pub fn iter(&self) -> impl Iterator<Item = (String, &V)> + '_ {
+        self.dawg.words().zip(&self.values)
+    }
+}
+
+impl<T, V> DawgMap<T, V> where T: Wrapper, V: Clone {
+    /// Builds one map holding the words of every map of `maps`, merged through a heap like `Dawg::merge_all`. A word of a
+    /// single map keeps its value; a word of several maps gets `resolve(first, second)` of its values, taken in the order of
+    /// `maps` (and folded along them when more than two maps have it), so `resolve` runs only for words found in several maps.
+    /// The merged map takes the options of the first map, and fails like `Dawg::merge_all` if the others don't agree with them
+    pub fn merge_all(maps: &[&Self], mut resolve: impl FnMut(&V, &V) -> V) -> Result<Self, DawgError> {
+        let mut merged = match maps.first() {
+            Some(first) => Self::with_options(first.dawg.options.clone())?,
+            None => Self::from_parts(Dawg::with_wrapper(T::new()), vec![]),
+        };
+        for map in maps.iter().skip(1) {
+            merged.dawg.options.check_mergeable(&map.dawg.options)?;
+        }
+        // the values are borrowed until they are inserted, so only the resolved ones and those inserted are made
+        let mut merge = KMerge::with_payloads(maps.iter().map(|map| map.iter().map(|(word, value)| (word, Cow::Borrowed(value)))));
+        while let Some((word, value)) = merge.next_resolved(|first, second| Cow::Owned(resolve(&first, &second))) {
+            merged.try_insert(&word, value.into_owned()).expect("the merged words are sorted and distinct");
+        }
+
+        merged.finish();
+        Ok(merged)
+    }
+}
+
+impl<T> WeightedDawg<T> where T: Wrapper {
+    /// Returns the weight of `word`, if it is in the map
+    pub fn weight_of(&self, word: &str) -> Option<u64> {
+        self.get(word).copied()
+    }
+}
@@ -0,0 +1,158 @@
+use std::{collections::BTreeMap, fs::File, io::{Read, Write}, path::Path};
+
+use crate::{dawg::{common::{Dawg, NodeOps, Wrapper}, serialize::{write_u32, Cursor, EncodeLabel, SavedHeader}}, error::DawgError};
+
+/// Free-form key/value strings describing a dictionary (where it comes from, what it's for, ...), saved in the header of
+/// the file so `peek_metadata` can read them without loading the dawg.
+///
+/// Metadata never changes the language of a dawg: queries, `diff` and the set operations ignore it, and two dawgs of the
+/// same words are the same language whatever their metadata. Keys are kept in sorted order
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Metadata {
+    entries: BTreeMap<String, String>,
+}
+
+impl Metadata {
+    /// name of the dictionary
+    pub const NAME: &'static str = "name";
+    /// language tag of its words, e.g. `en-GB`
+    pub const LANGUAGE: &'static str = "language";
+    /// checksum of the source the words were read from
+    pub const SOURCE_CHECKSUM: &'static str = "source-checksum";
+    /// version of dawging which saved the dawg, set by `Dawg::save_with_metadata`
+    pub const BUILDER_VERSION: &'static str = "builder-version";
+    /// number of words of the dawg, set by `Dawg::save_with_metadata`
+    pub const WORD_COUNT: &'static str = "word-count";
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets `key` to `value`, replacing its previous value
+    pub fn entry(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.insert(key, value);
+        self
+    }
+
+    pub fn name(self, name: impl Into<String>) -> Self {
+        self.entry(Self::NAME, name)
+    }
+
+    pub fn language(self, language: impl Into<String>) -> Self {
+        self.entry(Self::LANGUAGE, language)
+    }
+
+    pub fn source_checksum(self, checksum: impl Into<String>) -> Self {
+        self.entry(Self::SOURCE_CHECKSUM, checksum)
+    }
+
+    /// Sets `key` to `value`, returning its previous value
+    pub fn insert(&mut self, key: impl Into<String>, value: impl Into<String>) -> Option<String> {
+        self.entries.insert(key.into(), value.into())
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<String> {
+        self.entries.remove(key)
+    }
+
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(String::as_str)
+    }
+
+    pub fn get_name(&self) -> Option<&str> {
+        self.get(Self::NAME)
+    }
+
+    pub fn get_language(&self) -> Option<&str> {
+        self.get(Self::LANGUAGE)
+    }
+
+    pub fn get_source_checksum(&self) -> Option<&str> {
+        self.get(Self::SOURCE_CHECKSUM)
+    }
+
+    pub fn get_builder_version(&self) -> Option<&str> {
+        self.get(Self::BUILDER_VERSION)
+    }
+
+    /// the number of words saved by `Dawg::save_with_metadata`, `None` if missing or not a number
+    pub fn get_word_count(&self) -> Option<usize> {
+        self.get(Self::WORD_COUNT)?.parse().ok()
+    }
+
+    /// Returns the entries in the order of their keys
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &str)> + '_ {
+        self.entries.iter().map(|(key, value)| (key.as_str(), value.as_str()))
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Appends the number of entries then the length and the bytes of every key and value
+    pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+        write_u32(out, self.entries.len() as u32);
+        for (key, value) in &self.entries {
+            for text in [key, value] {
+                write_u32(out, text.len() as u32);
+                out.extend_from_slice(text.as_bytes());
+            }
+        }
+    }
+
+    pub(crate) fn decode(reader: &mut Cursor<'_>) -> Result<Self, DawgError> {
+        let mut metadata = Self::new();
+        for _ in 0..reader.u32()? {
+            let mut text = || {
+                let len = reader.u32()? as usize;
+                String::from_utf8(reader.bytes(len)?.to_vec()).map_err(|_| DawgError::InvalidFormat(String::from("metadata is not utf-8")))
+            };
+            let (key, value) = (text()?, text()?);
+            metadata.insert(key, value);
+        }
+        Ok(metadata)
+    }
+}
+
+impl<'a> FromIterator<(&'a str, &'a str)> for Metadata {
+    fn from_iter<I: IntoIterator<Item = (&'a str, &'a str)>>(entries: I) -> Self {
+        entries.into_iter().fold(Self::new(), |metadata, (key, value)| metadata.entry(key, value))
+    }
+}
+
+/// Reads the metadata of the Dawg saved at `path` from the header of the file, without reading its nodes. A file saved
+/// without metadata (or before it was added to the format) has none
+pub fn peek_metadata(path: impl AsRef<Path>) -> Result<Metadata, DawgError> {
+    read_metadata(File::open(path)?)
+}
+
+/// Same as `peek_metadata`, reading the saved dawg from `reader`: only the header is read, whatever the size of the dawg
+pub fn read_metadata(mut reader: impl Read) -> Result<Metadata, DawgError> {
+    let (header, _) = SavedHeader::read(&mut reader)?;
+    Ok(header.options.metadata)
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: EncodeLabel {
+    /// the metadata set in the options, or read back from the file the dawg was loaded from
+    pub fn metadata(&self) -> &Metadata {
+        &self.options.metadata
+    }
+
+    /// Writes the dawg to `writer` like `save`, along with the metadata of its options updated by `metadata`, the version of
+    /// dawging and the number of words (see `Metadata::BUILDER_VERSION` and `Metadata::WORD_COUNT`)
+    pub fn save_with_metadata<W: Write>(&self, mut writer: W, metadata: &Metadata) -> Result<(), DawgError> {
+        let mut options = self.options.clone();
+        for (key, value) in metadata.iter() {
+            options.metadata.insert(key, value);
+        }
+        options.metadata.insert(Metadata::BUILDER_VERSION, env!("CARGO_PKG_VERSION"));
+        options.metadata.insert(Metadata::WORD_COUNT, self.root.count().to_string());
+
+        writer.write_all(&self.encode_with(&options)?)?;
+        Ok(writer.flush()?)
+    }
+}
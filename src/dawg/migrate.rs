@@ -0,0 +1,18 @@
+use std::{fs, path::Path};
+
+use crate::{dawg::{compiled::{self, CompiledDawg}, serialize::Layout}, error::DawgError};
+
+/// Rewrites a Dawg or CompiledDawg saved by any version of the layout this crate reads (see `Dawg::load`) in the latest
+/// one, without building it. The result is byte for byte what saving the loaded dawg again would write
+pub fn migrate(bytes: &[u8]) -> Result<Vec<u8>, DawgError> {
+    match bytes.get(..4) {
+        Some(magic) if magic == compiled::MAGIC => Ok(CompiledDawg::from_bytes(bytes)?.to_bytes()),
+        _ => Ok(Layout::parse(bytes)?.to_bytes()),
+    }
+}
+
+/// Migrates the dawg saved at `input` (see `migrate`), writing it to `output` which may be `input` itself
+pub fn migrate_file(input: impl AsRef<Path>, output: impl AsRef<Path>) -> Result<(), DawgError> {
+    let migrated = migrate(&fs::read(input)?)?;
+    Ok(fs::write(output, migrated)?)
+}
@@ -0,0 +1,64 @@
+use crate::dawg::{lexicon::{Lexicon, MatchMode}, setops::KMerge};
+
+/// Several lexicons queried as if they were one, without merging them: a word is in the MultiDawg if it is in any layer.
+/// Layers can be anything implementing `Lexicon` (`&Dawg`, `Rc<Dawg>`, `Arc<Dawg>`, another MultiDawg, ...)
+#[derive(Default)]
+pub struct MultiDawg<'a> {
+    layers: Vec<Box<dyn Lexicon + 'a>>,
+}
+
+impl<'a> MultiDawg<'a> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds `layer` on top of the others, returning its index
+    pub fn push_layer(&mut self, layer: impl Lexicon + 'a) -> usize {
+        self.layers.push(Box::new(layer));
+        self.layers.len() - 1
+    }
+
+    /// Adds `layer` at `index`, shifting the layers above it
+    pub fn insert_layer(&mut self, index: usize, layer: impl Lexicon + 'a) {
+        self.layers.insert(index, Box::new(layer));
+    }
+
+    /// Removes the layer at `index`, returning it if there was one
+    pub fn remove_layer(&mut self, index: usize) -> Option<Box<dyn Lexicon + 'a>> {
+        (index < self.layers.len()).then(|| self.layers.remove(index))
+    }
+
+    pub fn layers(&self) -> usize {
+        self.layers.len()
+    }
+
+    /// Sum of the word counts of the layers: exact if no word is in two layers, an upper bound otherwise.
+    /// Unlike `word_count`, it doesn't have to go through the words
+    pub fn word_count_upper_bound(&self) -> usize {
+        self.layers.iter().map(|layer| layer.word_count()).sum()
+    }
+}
+
+impl<'a> Lexicon for MultiDawg<'a> {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        self.layers.iter().any(|layer| layer.contains(word, mode))
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        self.layers.iter().any(|layer| layer.contains_prefix(prefix, mode))
+    }
+
+    /// Merges the sorted completions of every layer, a word found in several layers is returned once
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        KMerge::new(self.layers.iter().map(|layer| layer.completions(prefix, mode).into_iter())).collect()
+    }
+
+    /// Number of distinct words across the layers, found by merging their words (see `word_count_upper_bound` for a cheap bound)
+    fn word_count(&self) -> usize {
+        match self.layers.as_slice() {
+            [] => 0,
+            [layer] => layer.word_count(),
+            _ => KMerge::new(self.layers.iter().map(|layer| layer.completions("", MatchMode::Exact).into_iter())).count(),
+        }
+    }
+}
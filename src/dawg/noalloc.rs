@@ -0,0 +1,340 @@
+//! Queries over the compiled layout that never allocate, for targets that can't afford the heap at query time.
+//!
+//! `Lexicon::contains` and `Lexicon::contains_prefix` under `MatchMode::Exact` follow one edge per letter, and the listings
+//! below write the completions of a prefix into buffers given by the caller. None of them allocates or recurses: the only
+//! state kept while listing is the word being spelled, in the caller's buffer, and the node it leads to. Going back up to
+//! the next sibling walks the word down again from the prefix, so a listing costs the length of a word more per word than
+//! `Lexicon::completions`, in exchange for a bounded stack. A `Normalization` in the options is the exception: it reads
+//! the whole word into a buffer before normalizing it.
+//!
+//! Words are written in utf-8, as they are stored (after the case folding of the options, if any).
+//!
+//! They are built against `std` like the rest of the crate, for a target whose allocator must stay idle while querying:
+//! a `no_std` build for bare metal targets such as `thumbv7em-none-eabihf` isn't provided
+
+use std::{cmp::Ordering, convert::Infallible, ops::{ControlFlow, Range}};
+
+use crate::dawg::{compiled::{CompiledNode, NONE}, lexicon::MatchMode, options::DawgOptions};
+
+/// How far a listing into a buffer went, see `CompiledDawg::completions_into`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Listing {
+    /// words written to the buffer, or given to the visitor
+    pub written: usize,
+    /// words starting with the prefix
+    pub total: usize,
+}
+
+impl Listing {
+    /// value is true if every word starting with the prefix was written
+    pub fn is_complete(&self) -> bool {
+        self.written == self.total
+    }
+}
+
+/// Read access to the nodes and edges of a compiled dawg, decoded (`CompiledDawg`), borrowed (`CompiledView`) or read
+/// from a file as they are reached (`PagedDawgReader`)
+pub(crate) trait CompiledNodes {
+    /// what reading a node or an edge fails with: `Infallible` for the dawgs held in memory, which the listings below are
+    /// limited to
+    type Error;
+
+    fn options(&self) -> &DawgOptions;
+
+    /// the root, `None` if there are no nodes at all
+    fn root(&self) -> Option<CompiledNode>;
+
+    fn node(&self, id: u32) -> Result<CompiledNode, Self::Error>;
+
+    /// label and child of `edge`
+    fn edge(&self, edge: u32) -> Result<(char, u32), Self::Error>;
+
+    /// child held by `slot` of the dispatch tables, `NONE` for an empty slot
+    fn slot(&self, slot: u32) -> Result<u32, Self::Error>;
+
+    /// number of slots of the dispatch tables
+    fn slot_len(&self) -> u32;
+
+    /// Follows the edge of `node` labelled `letter`
+    fn step(&self, node: &CompiledNode, letter: char) -> Result<Option<u32>, Self::Error> {
+        self.step_counting(node, letter, &mut 0)
+    }
+
+    /// Follows the edge of `node` labelled `letter`, through its dispatch table if it has one and by a binary search of its
+    /// labels otherwise, adding the number of label comparisons it took (a table lookup counting as one) to `probes`
+    fn step_counting(&self, node: &CompiledNode, letter: char, probes: &mut usize) -> Result<Option<u32>, Self::Error> {
+        if node.edges == 0 {
+            return Ok(None);
+        }
+
+        if node.table != NONE {
+            *probes += 1;
+            let (low, _) = self.edge(node.first_edge)?;
+            let (high, _) = self.edge(node.first_edge + node.edges - 1)?;
+            if letter < low || letter > high {
+                return Ok(None);
+            }
+            return Ok(Some(self.slot(node.table.saturating_add(letter as u32 - low as u32))?).filter(|child| *child != NONE));
+        }
+
+        let (mut low, mut high) = (node.first_edge, node.first_edge + node.edges);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            let (label, child) = self.edge(middle)?;
+            *probes += 1;
+
+            match label.cmp(&letter) {
+                Ordering::Less => low = middle + 1,
+                Ordering::Greater => high = middle,
+                Ordering::Equal => return Ok(Some(child)),
+            }
+        }
+        Ok(None)
+    }
+
+    /// value is true if the dispatch table of `node` is in bounds and holds exactly the children of its edges, whose labels
+    /// must already be known to be sorted
+    fn table_matches(&self, node: &CompiledNode) -> Result<bool, Self::Error> {
+        if node.edges == 0 {
+            return Ok(false);
+        }
+
+        let (low, _) = self.edge(node.first_edge)?;
+        let (high, _) = self.edge(node.first_edge + node.edges - 1)?;
+        let span = high as u32 - low as u32 + 1;
+        if node.table as u64 + span as u64 > self.slot_len() as u64 {
+            return Ok(false);
+        }
+
+        let mut filled = 0;
+        for slot in node.table..node.table + span {
+            filled += (self.slot(slot)? != NONE) as u32;
+        }
+        if filled != node.edges {
+            return Ok(false);
+        }
+
+        for edge in node.first_edge..node.first_edge + node.edges {
+            let (label, child) = self.edge(edge)?;
+            if self.slot(node.table + label as u32 - low as u32)? != child {
+                return Ok(false);
+            }
+        }
+        Ok(true)
+    }
+
+    /// Returns the node `letters` lead to from `node`
+    fn walk(&self, mut node: CompiledNode, letters: impl IntoIterator<Item = char>) -> Result<Option<CompiledNode>, Self::Error> {
+        for letter in letters {
+            match self.step(&node, letter)? {
+                Some(child) => node = self.node(child)?,
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(node))
+    }
+
+    /// Returns the node `word` (folded by the options) leads to from the root
+    fn find_exact(&self, word: &str) -> Result<Option<CompiledNode>, Self::Error> {
+        match self.root() {
+            Some(root) => self.walk(root, self.options().fold_chars(word.chars())),
+            None => Ok(None),
+        }
+    }
+
+    /// Every node `word` (folded by the options) leads to from the root under `mode`, with the spelling of the path taken
+    /// to it if `spell` is true
+    fn find(&self, word: &str, mode: MatchMode, spell: bool) -> Result<Vec<(CompiledNode, String)>, Self::Error> {
+        let mut frontier = self.root().map(|root| (root, String::new())).into_iter().collect::<Vec<_>>();
+
+        for letter in self.options().fold_chars(word.chars()) {
+            let mut next = vec![];
+
+            for (node, mut spelling) in frontier {
+                match mode {
+                    MatchMode::Exact => {
+                        if let Some(child) = self.step(&node, letter)? {
+                            if spell {
+                                spelling.push(letter);
+                            }
+                            next.push((self.node(child)?, spelling));
+                        }
+                    }
+                    _ => {
+                        for edge in node.first_edge..node.first_edge + node.edges {
+                            let (label, child) = self.edge(edge)?;
+                            if mode.matches(label, letter) {
+                                let spelling = if spell { format!("{spelling}{label}") } else { String::new() };
+                                next.push((self.node(child)?, spelling));
+                            }
+                        }
+                    }
+                }
+            }
+
+            frontier = next;
+        }
+
+        Ok(frontier)
+    }
+
+    /// value is true if `word` is stored, matched exactly
+    fn contains_exact(&self, word: &str) -> bool where Self: CompiledNodes<Error = Infallible> {
+        let Ok(node) = self.find_exact(word);
+        node.is_some_and(|node| node.terminal)
+    }
+
+    /// value is true if a stored word starts with `prefix`, matched exactly
+    fn contains_prefix_exact(&self, prefix: &str) -> bool where Self: CompiledNodes<Error = Infallible> {
+        let Ok(node) = self.find_exact(prefix);
+        node.is_some_and(|node| node.terminal || node.edges > 0)
+    }
+
+    /// Returns the edges of `node` whose label comes after `label`
+    fn edges_after(&self, node: &CompiledNode, label: char) -> Result<Range<u32>, Self::Error> {
+        let (mut low, mut high) = (node.first_edge, node.first_edge + node.edges);
+        while low < high {
+            let middle = low + (high - low) / 2;
+            match self.edge(middle)?.0 <= label {
+                true => low = middle + 1,
+                false => high = middle,
+            }
+        }
+        Ok(low..node.first_edge + node.edges)
+    }
+}
+
+/// Depth first walk below a prefix, spelling the current word in a buffer of the caller: `buffer[base..base + len]`, the
+/// prefix being its first `prefix_len` bytes
+struct Walk {
+    prefix: CompiledNode,
+    node: CompiledNode,
+    base: usize,
+    len: usize,
+    prefix_len: usize,
+    /// end of the last word handed out for good (by `completions_into`): the word being spelled can't be changed in place
+    /// from then on, it is copied past that end first
+    frozen: Option<usize>,
+    started: bool,
+}
+
+impl Walk {
+    /// Starts below the node `prefix` (folded by the options) leads to, spelling it at the start of `buffer`. `None` if
+    /// no word starts with it, or it doesn't fit in `buffer`
+    fn new(nodes: &impl CompiledNodes<Error = Infallible>, prefix: &str, buffer: &mut [u8]) -> Option<(Self, usize)> {
+        let Ok(node) = nodes.find_exact(prefix);
+        let node = node?;
+        let mut walk = Self { prefix: node, node, base: 0, len: 0, prefix_len: 0, frozen: None, started: false };
+
+        for letter in nodes.options().fold_chars(prefix.chars()) {
+            if !walk.push(letter, buffer) {
+                return None;
+            }
+        }
+        walk.prefix_len = walk.len;
+        Some((walk, node.count as usize))
+    }
+
+    /// Spells `letter` after the current word, value is false if there is no room left for it
+    fn push(&mut self, letter: char, buffer: &mut [u8]) -> bool {
+        let base = self.frozen.unwrap_or(self.base);
+        let end = base + self.len + letter.len_utf8();
+        if end > buffer.len() {
+            return false;
+        }
+
+        if let Some(frozen) = self.frozen.take() {
+            buffer.copy_within(self.base..self.base + self.len, frozen);
+            self.base = frozen;
+        }
+        letter.encode_utf8(&mut buffer[end - letter.len_utf8()..end]);
+        self.len += letter.len_utf8();
+        true
+    }
+
+    /// Takes the last letter off the current word
+    fn pop(&mut self, buffer: &[u8]) -> char {
+        let word = &buffer[self.base..self.base + self.len];
+        let start = word.iter().rposition(|byte| byte & 0xC0 != 0x80).unwrap_or(0);
+        let letter = std::str::from_utf8(&word[start..]).ok().and_then(|letter| letter.chars().next()).unwrap_or_default();
+        self.len = start;
+        letter
+    }
+
+    fn word<'b>(&self, buffer: &'b [u8]) -> &'b str {
+        // only whole letters are ever written
+        std::str::from_utf8(&buffer[self.base..self.base + self.len]).unwrap_or_default()
+    }
+
+    /// Moves on to the next word in sorted order, returning where it is spelled in `buffer`. Words too long for the room
+    /// left are skipped
+    fn next(&mut self, nodes: &impl CompiledNodes<Error = Infallible>, buffer: &mut [u8]) -> Option<Range<usize>> {
+        if !self.started {
+            self.started = true;
+            if self.node.terminal {
+                return Some(self.base..self.base + self.len);
+            }
+        }
+
+        let mut edges = self.node.first_edge..self.node.first_edge + self.node.edges;
+        'descend: loop {
+            for edge in edges {
+                let Ok((label, child)) = nodes.edge(edge);
+                if self.push(label, buffer) {
+                    let Ok(child) = nodes.node(child);
+                    self.node = child;
+                    if self.node.terminal {
+                        return Some(self.base..self.base + self.len);
+                    }
+                    edges = self.node.first_edge..self.node.first_edge + self.node.edges;
+                    continue 'descend;
+                }
+            }
+
+            // every edge of the node was followed (or had no room): on to the next sibling of the node, or of its parent
+            if self.len == self.prefix_len {
+                return None;
+            }
+            let label = self.pop(buffer);
+            let below = std::str::from_utf8(&buffer[self.base + self.prefix_len..self.base + self.len]).unwrap_or_default();
+            let Ok(node) = nodes.walk(self.prefix, below.chars());
+            self.node = node?;
+            let Ok(after) = nodes.edges_after(&self.node, label);
+            edges = after;
+        }
+    }
+}
+
+/// Writes the words starting with `prefix` to `out`, see `CompiledDawg::completions_into`
+pub(crate) fn completions_into(nodes: &impl CompiledNodes<Error = Infallible>, prefix: &str, out: &mut [u8], ends: &mut [usize]) -> Listing {
+    let Some((mut walk, total)) = Walk::new(nodes, prefix, out) else {
+        let Ok(node) = nodes.find_exact(prefix);
+        return Listing { written: 0, total: node.map_or(0, |node| node.count as usize) };
+    };
+
+    let mut written = 0;
+    while written < ends.len() {
+        let Some(word) = walk.next(nodes, out) else { break };
+        ends[written] = word.end;
+        walk.frozen = Some(word.end);
+        written += 1;
+    }
+    Listing { written, total }
+}
+
+/// Hands the words starting with `prefix` to `visit`, see `CompiledDawg::for_each_completion`
+pub(crate) fn for_each_completion(nodes: &impl CompiledNodes<Error = Infallible>, prefix: &str, path: &mut [u8], mut visit: impl FnMut(&str) -> ControlFlow<()>) -> Listing {
+    let Some((mut walk, total)) = Walk::new(nodes, prefix, path) else {
+        let Ok(node) = nodes.find_exact(prefix);
+        return Listing { written: 0, total: node.map_or(0, |node| node.count as usize) };
+    };
+
+    let mut written = 0;
+    while walk.next(nodes, path).is_some() {
+        written += 1;
+        if visit(walk.word(path)).is_break() {
+            break;
+        }
+    }
+    Listing { written, total }
+}
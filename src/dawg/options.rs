@@ -0,0 +1,659 @@
+use std::{cmp::Ordering, fmt::Debug, hash::Hash, mem::discriminant, sync::Arc};
+
+use crate::{dawg::{common::{Dawg, Label, Wrapper}, lexicon::MatchMode, metadata::Metadata, register::Register, trace, unicode}, error::{DawgError, OptionsConflict}};
+
+/// What happens when the same word is added twice in a row
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum DuplicatePolicy {
+    /// the duplicate is silently dropped
+    #[default]
+    Ignore,
+    /// adding the duplicate fails with `DawgError::Duplicate`
+    Error,
+}
+
+/// Unit of text the words are split into, each unit being a label of the dawg
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Granularity {
+    /// one label per char, the labels of a `Dawg<T>`
+    #[default]
+    Chars,
+    /// one label per byte of the utf-8 encoding of the word, the labels of a `Dawg<T, u8>`
+    Bytes,
+    /// one label per extended grapheme cluster (UAX #29), so "e" followed by a combining acute accent is a single label.
+    /// The labels of a `Dawg<T, String>`
+    Graphemes,
+}
+
+/// Labels a word is split into under one of the granularities, so a `Dawg<T, L>` of them takes words with `try_add_text`
+pub trait TextLabel: Label {
+    /// the granularity of the options of a dawg of these labels
+    const GRANULARITY: Granularity;
+
+    /// Splits `word` into its labels
+    fn split(word: &str) -> Vec<Self>;
+
+    /// The word spelled by `labels`
+    fn join(labels: &[Self]) -> String;
+}
+
+impl TextLabel for char {
+    const GRANULARITY: Granularity = Granularity::Chars;
+
+    fn split(word: &str) -> Vec<Self> {
+        word.chars().collect()
+    }
+
+    fn join(labels: &[Self]) -> String {
+        labels.iter().collect()
+    }
+}
+
+impl TextLabel for u8 {
+    const GRANULARITY: Granularity = Granularity::Bytes;
+
+    fn split(word: &str) -> Vec<Self> {
+        word.bytes().collect()
+    }
+
+    fn join(labels: &[Self]) -> String {
+        String::from_utf8_lossy(labels).into_owned()
+    }
+}
+
+impl TextLabel for String {
+    const GRANULARITY: Granularity = Granularity::Graphemes;
+
+    fn split(word: &str) -> Vec<Self> {
+        unicode::graphemes(word).map(str::to_owned).collect()
+    }
+
+    fn join(labels: &[Self]) -> String {
+        labels.concat()
+    }
+}
+
+/// Unicode normalization form the words are put in as they are added and queried, so a word typed with a precomposed
+/// letter and the same word typed with a base letter and a combining mark are the same word
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Normalization {
+    /// words are kept as given
+    #[default]
+    None,
+    /// canonical composition, "é" as one char
+    Nfc,
+    /// canonical decomposition, "é" as "e" then a combining acute accent
+    Nfd,
+}
+
+/// What happens to a word that can't be added: one with a letter outside of the alphabet, or out of the sort order
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ValidationPolicy {
+    /// adding the word fails with `DawgError::InvalidCharacter` or `DawgError::Unsorted`
+    #[default]
+    Reject,
+    /// the word is dropped and counted by `words_skipped`, the words after it are still added
+    Skip,
+}
+
+/// Hash function of the minimization register, which only affects how fast the dawg is built, never its words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum RegisterHasher {
+    /// SipHash with keys drawn at random for every dawg, as `HashMap` does
+    #[default]
+    Random,
+    /// SipHash with fixed keys and the seed, so the register probes the same way from one run to the next
+    Seeded(u64),
+}
+
+/// Comparison function of `SortOrder::Custom`
+pub type Collation = Arc<dyn Fn(&str, &str) -> Ordering + Send + Sync>;
+
+/// Order in which `try_add` expects the words, so lists sorted by other tools than Rust can be added as they are.
+///
+/// The automaton itself is built in the order of the letters (`Chars`): with any other order, a word that goes back
+/// on that order (`"Banana"` after `"apple"` under `CaseInsensitiveBytes`) is held aside and only merged in by `finish`,
+/// so queries don't see it until then
+#[derive(Clone, Default)]
+#[non_exhaustive]
+pub enum SortOrder {
+    /// the order of `str`, comparing the utf-8 bytes
+    #[default]
+    Bytes,
+    /// comparing the chars one by one, which orders utf-8 strings exactly like `Bytes`
+    Chars,
+    /// comparing the bytes with ascii letters upper-cased, ties broken by `Bytes`: the order of `sort -f` in the C locale
+    CaseInsensitiveBytes,
+    /// a collation of your own, see `SortOrder::custom`. It isn't saved along with the dawg: a loaded dawg uses `Bytes`
+    Custom(Collation),
+}
+
+impl SortOrder {
+    /// Words must be sorted by `compare`, which should be a total order
+    pub fn custom(compare: impl Fn(&str, &str) -> Ordering + Send + Sync + 'static) -> Self {
+        Self::Custom(Arc::new(compare))
+    }
+
+    pub fn compare(&self, a: &str, b: &str) -> Ordering {
+        match self {
+            SortOrder::Bytes => a.cmp(b),
+            SortOrder::Chars => a.chars().cmp(b.chars()),
+            SortOrder::CaseInsensitiveBytes => {
+                let folded = |word: &str| word.bytes().map(|byte| byte.to_ascii_uppercase()).collect::<Vec<_>>();
+                folded(a).cmp(&folded(b)).then_with(|| a.cmp(b))
+            }
+            SortOrder::Custom(compare) => compare(a, b),
+        }
+    }
+
+    /// name of the order, as given by `DawgError::Unsorted`
+    pub fn name(&self) -> &'static str {
+        match self {
+            SortOrder::Bytes => "bytes",
+            SortOrder::Chars => "chars",
+            SortOrder::CaseInsensitiveBytes => "case-insensitive bytes",
+            SortOrder::Custom(_) => "custom",
+        }
+    }
+
+    /// name of the order read in `direction`, as given by `DawgError::Unsorted` once the direction matters
+    pub(crate) fn directed_name(&self, direction: SortDirection) -> &'static str {
+        match (direction, self) {
+            (SortDirection::Descending, SortOrder::Bytes) => "descending bytes",
+            (SortDirection::Descending, SortOrder::Chars) => "descending chars",
+            (SortDirection::Descending, SortOrder::CaseInsensitiveBytes) => "descending case-insensitive bytes",
+            (SortDirection::Descending, SortOrder::Custom(_)) => "descending custom",
+            (_, SortOrder::Bytes) => "ascending bytes",
+            (_, SortOrder::Chars) => "ascending chars",
+            (_, SortOrder::CaseInsensitiveBytes) => "ascending case-insensitive bytes",
+            (_, SortOrder::Custom(_)) => "ascending custom",
+        }
+    }
+
+    /// value is true if words in this order are also in the order of their letters, the order the automaton is built in
+    pub(crate) fn follows_letters(&self) -> bool {
+        matches!(self, SortOrder::Bytes | SortOrder::Chars)
+    }
+}
+
+/// Whether `try_add` expects the words from the first to the last in their `SortOrder`, or the other way round.
+///
+/// The automaton is always built in ascending order: descending words are held aside (see `SortOrder`) and merged in by
+/// `finish`, which gives the same dawg as adding them in ascending order, at the cost of keeping them in memory until then.
+/// The direction isn't saved along with the dawg, it only matters while adding words
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum SortDirection {
+    #[default]
+    Ascending,
+    /// from the last word to the first, as `sort -r` writes them
+    Descending,
+    /// the direction of the first two distinct words added, see `Dawg::sort_direction`
+    Auto,
+}
+
+impl Debug for SortOrder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SortOrder::Custom(_) => f.write_str("Custom(..)"),
+            _ => f.write_str(match self {
+                SortOrder::Bytes => "Bytes",
+                SortOrder::Chars => "Chars",
+                _ => "CaseInsensitiveBytes",
+            }),
+        }
+    }
+}
+
+impl PartialEq for SortOrder {
+    /// Two custom orders are only equal if they share the same function
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (SortOrder::Custom(a), SortOrder::Custom(b)) => Arc::ptr_eq(a, b),
+            _ => discriminant(self) == discriminant(other),
+        }
+    }
+}
+
+impl Eq for SortOrder {}
+
+impl Hash for SortOrder {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        discriminant(self).hash(state);
+    }
+}
+
+/// Settings of a Dawg, fixed when it is created and saved along with it.
+/// The defaults reproduce the behaviour of `Dawg::new()`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct DawgOptions {
+    pub(crate) case_folding: bool,
+    pub(crate) duplicate_policy: DuplicatePolicy,
+    pub(crate) alphabet: Option<Vec<char>>,
+    pub(crate) capacity: usize,
+    pub(crate) match_mode: MatchMode,
+    pub(crate) sort_order: SortOrder,
+    pub(crate) sort_direction: SortDirection,
+    pub(crate) metadata: Metadata,
+    pub(crate) granularity: Granularity,
+    pub(crate) normalization: Normalization,
+    pub(crate) validation_policy: ValidationPolicy,
+    pub(crate) hasher: RegisterHasher,
+}
+
+impl DawgOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Lower-cases words as they are added and queried, so lookups no longer depend on case.
+    /// Words must then be sorted by their lower-cased form
+    pub fn case_folding(mut self, case_folding: bool) -> Self {
+        self.case_folding = case_folding;
+        self
+    }
+
+    pub fn duplicate_policy(mut self, duplicate_policy: DuplicatePolicy) -> Self {
+        self.duplicate_policy = duplicate_policy;
+        self
+    }
+
+    /// Restricts words to the characters of `alphabet`, words containing anything else are rejected
+    pub fn alphabet(mut self, alphabet: &str) -> Self {
+        let mut alphabet = alphabet.chars().collect::<Vec<_>>();
+        alphabet.sort();
+        alphabet.dedup();
+        self.alphabet = Some(alphabet);
+        self
+    }
+
+    /// Expected number of distinct nodes, used to size the minimization register up front
+    pub fn capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    /// How `contains`, `get` and `starts_with` compare letters, `MatchMode::Exact` unless set
+    pub fn match_mode(mut self, match_mode: MatchMode) -> Self {
+        self.match_mode = match_mode;
+        self
+    }
+
+    /// Order the words must be added in, `SortOrder::Bytes` unless set
+    pub fn sort_order(mut self, sort_order: SortOrder) -> Self {
+        self.sort_order = sort_order;
+        self
+    }
+
+    /// Direction the words are added in, `SortDirection::Ascending` unless set
+    pub fn sort_direction(mut self, sort_direction: SortDirection) -> Self {
+        self.sort_direction = sort_direction;
+        self
+    }
+
+    /// Key/value strings saved in the header of the file along with the dawg, see `Metadata`
+    pub fn metadata(mut self, metadata: Metadata) -> Self {
+        self.metadata = metadata;
+        self
+    }
+
+    /// Unit the words are split into, `Granularity::Chars` unless set. Words are added to dawgs of other units with
+    /// `try_add_text`, in the order of their labels
+    pub fn granularity(mut self, granularity: Granularity) -> Self {
+        self.granularity = granularity;
+        self
+    }
+
+    /// Normalization form words are put in as they are added and queried, after the case folding.
+    /// Words must then be sorted by their normalized form
+    pub fn normalization(mut self, normalization: Normalization) -> Self {
+        self.normalization = normalization;
+        self
+    }
+
+    pub fn validation_policy(mut self, validation_policy: ValidationPolicy) -> Self {
+        self.validation_policy = validation_policy;
+        self
+    }
+
+    pub fn hasher(mut self, hasher: RegisterHasher) -> Self {
+        self.hasher = hasher;
+        self
+    }
+
+    pub fn is_case_folding(&self) -> bool {
+        self.case_folding
+    }
+
+    pub fn get_duplicate_policy(&self) -> DuplicatePolicy {
+        self.duplicate_policy
+    }
+
+    pub fn get_alphabet(&self) -> Option<&[char]> {
+        self.alphabet.as_deref()
+    }
+
+    pub fn get_match_mode(&self) -> MatchMode {
+        self.match_mode
+    }
+
+    pub fn get_sort_order(&self) -> &SortOrder {
+        &self.sort_order
+    }
+
+    pub fn get_sort_direction(&self) -> SortDirection {
+        self.sort_direction
+    }
+
+    pub fn get_metadata(&self) -> &Metadata {
+        &self.metadata
+    }
+
+    pub fn get_granularity(&self) -> Granularity {
+        self.granularity
+    }
+
+    pub fn get_normalization(&self) -> Normalization {
+        self.normalization
+    }
+
+    pub fn get_validation_policy(&self) -> ValidationPolicy {
+        self.validation_policy
+    }
+
+    pub fn get_hasher(&self) -> RegisterHasher {
+        self.hasher
+    }
+
+    /// Checks that the settings can work together
+    pub fn validate(&self) -> Result<(), DawgError> {
+        if let (true, Some(alphabet)) = (self.case_folding, &self.alphabet) {
+            if let Some(letter) = alphabet.iter().find(|letter| letter.to_lowercase().ne([**letter])) {
+                return Err(DawgError::InvalidOptions(OptionsConflict::UnreachableLetter(*letter)));
+            }
+        }
+
+        if self.granularity != Granularity::Chars && (!self.sort_order.follows_letters() || self.sort_direction != SortDirection::Ascending) {
+            return Err(DawgError::InvalidOptions(OptionsConflict::DescendingGranularity(self.granularity)));
+        }
+
+        if self.normalization != Normalization::None {
+            if self.granularity == Granularity::Bytes {
+                return Err(DawgError::InvalidOptions(OptionsConflict::GranularityNormalization(self.normalization)));
+            }
+            if let Some(letter) = self.alphabet.iter().flatten().find(|letter| unicode::normalize([**letter], self.normalization) != [**letter]) {
+                return Err(DawgError::InvalidOptions(OptionsConflict::UnreachableLetter(*letter)));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Fails unless dawgs configured by `self` and `other` store and match their words the same way, so their words can be
+    /// merged into one dawg: the case folding, alphabet, match mode, granularity and normalization must be equal. The other
+    /// settings only matter while building or describe the dawg, the merged dawg takes those of `self`
+    pub(crate) fn check_mergeable(&self, other: &Self) -> Result<(), DawgError> {
+        let stored = |options: &Self| (options.case_folding, options.alphabet.clone(), options.match_mode, options.granularity, options.normalization);
+        if stored(self) != stored(other) {
+            return Err(DawgError::InvalidOptions(OptionsConflict::NotMergeable));
+        }
+        Ok(())
+    }
+
+    /// Fails unless words are split into `labels`, the granularity of the labels of the dawg these options configure
+    pub(crate) fn check_granularity(&self, labels: Granularity) -> Result<(), DawgError> {
+        if self.granularity != labels {
+            return Err(DawgError::InvalidOptions(OptionsConflict::LabelGranularity { labels, options: self.granularity }));
+        }
+        Ok(())
+    }
+
+    /// value is true if `added`, the outcome of adding a word, is a rejection the validation policy says to skip
+    pub(crate) fn skips(&self, added: &Result<(), DawgError>) -> bool {
+        self.validation_policy == ValidationPolicy::Skip && matches!(added, Err(DawgError::InvalidCharacter { .. } | DawgError::Unsorted { .. }))
+    }
+
+    /// Returns `word` as it is stored, folded (see `fold`), failing if words aren't split into `labels` or a letter of the
+    /// folded word is outside of the alphabet
+    pub(crate) fn prepare(&self, word: &str, labels: Granularity) -> Result<String, DawgError> {
+        self.check_granularity(labels)?;
+        let word = self.fold(word);
+
+        if let Some(alphabet) = &self.alphabet {
+            if let Some(character) = word.chars().find(|letter| alphabet.binary_search(letter).is_err()) {
+                return Err(DawgError::InvalidCharacter { word, character });
+            }
+        }
+        Ok(word)
+    }
+
+    /// Applies the case folding then the normalization (if any) to `word`
+    pub(crate) fn fold(&self, word: &str) -> String {
+        let word = match self.case_folding {
+            true => word.to_lowercase(),
+            false => word.to_owned(),
+        };
+        match self.normalization {
+            Normalization::None => word,
+            form => unicode::normalize(word.chars(), form).into_iter().collect(),
+        }
+    }
+
+    /// Applies the case folding (if any) to every letter of `chars` as they are read. A normalization needs the whole word
+    /// (a combining mark changes the letter before it), which is then read and normalized first
+    pub(crate) fn fold_chars(&self, chars: impl IntoIterator<Item = char>) -> impl Iterator<Item = char> {
+        let folded = self.fold_case(chars);
+        match self.normalization {
+            Normalization::None => Folded::Streamed(folded),
+            form => Folded::Normalized(unicode::normalize(folded, form).into_iter()),
+        }
+    }
+
+    /// Same as `fold_chars`, written into `out` whose previous content is replaced, so a caller keeping `out` between
+    /// words doesn't allocate once it is large enough
+    pub(crate) fn fold_into(&self, chars: impl IntoIterator<Item = char>, out: &mut Vec<char>) {
+        out.clear();
+        unicode::normalize_into(self.fold_case(chars), self.normalization, out);
+    }
+
+    /// Lower-cases every letter of `chars` as they are read under case folding
+    fn fold_case(&self, chars: impl IntoIterator<Item = char>) -> impl Iterator<Item = char> {
+        let case_folding = self.case_folding;
+        chars.into_iter().flat_map(move |letter| {
+            let mut lower = letter.to_lowercase();
+            let mut kept = Some(letter);
+            std::iter::from_fn(move || if case_folding { lower.next() } else { kept.take() })
+        })
+    }
+}
+
+/// Letters given by `DawgOptions::fold_chars`: streamed as they are read, or all read and normalized first
+enum Folded<I> {
+    Streamed(I),
+    Normalized(std::vec::IntoIter<char>),
+}
+
+impl<I> Iterator for Folded<I> where I: Iterator<Item = char> {
+    type Item = char;
+
+    fn next(&mut self) -> Option<char> {
+        match self {
+            Folded::Streamed(letters) => letters.next(),
+            Folded::Normalized(letters) => letters.next(),
+        }
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: TextLabel {
+    /// Splits `word` into the labels of this dawg after applying its case folding and normalization, failing if the
+    /// granularity of the options isn't the one of the labels or a letter is outside of the alphabet
+    pub fn text_labels(&self, word: &str) -> Result<Vec<L>, DawgError> {
+        Ok(L::split(&self.options.prepare(word, L::GRANULARITY)?))
+    }
+
+    /// Adds `word` split into the labels of this dawg (see `text_labels`). Words must be added in the order of their labels,
+    /// which is the order of `str` for bytes but not always for graphemes. Once the dawg is finished the word may go anywhere
+    pub fn try_add_text(&mut self, word: &str) -> Result<(), DawgError> {
+        let added = self.add_text(word);
+        if self.options.skips(&added) {
+            self.words_skipped += 1;
+            return Ok(());
+        }
+        added?;
+        self.minimized_nodes.check()
+    }
+
+    fn add_text(&mut self, word: &str) -> Result<(), DawgError> {
+        let labels = self.text_labels(word)?;
+
+        if self.finished {
+            if !self.insert_sequence(&labels) && self.options.duplicate_policy == DuplicatePolicy::Error {
+                return Err(DawgError::Duplicate(L::join(&labels)));
+            }
+            return Ok(());
+        }
+
+        match (self.words_added, self.previous_word.cmp(&labels)) {
+            (0, _) | (_, Ordering::Less) => {}
+            (_, Ordering::Equal) if self.options.duplicate_policy == DuplicatePolicy::Error => return Err(DawgError::Duplicate(L::join(&labels))),
+            (_, Ordering::Equal) => return Ok(()),
+            (_, Ordering::Greater) => {
+                let order = match L::GRANULARITY {
+                    Granularity::Chars => "chars",
+                    Granularity::Bytes => "bytes",
+                    Granularity::Graphemes => "graphemes",
+                };
+                return Err(DawgError::Unsorted { previous: L::join(&self.previous_word), word: L::join(&labels), order });
+            }
+        }
+
+        self.push_sequence(&labels);
+        Ok(())
+    }
+
+    /// value is true if `word`, split into the labels of this dawg, is one of its words
+    pub fn contains_text(&self, word: &str) -> bool {
+        self.text_labels(word).is_ok_and(|labels| self.contains_sequence(&labels))
+    }
+}
+
+/// A dawg is built in place, words are added to it until `finish`: `DawgBuilder::with_options(options)` names the dawg
+/// being built, which records the options and saves them along with its words
+pub type DawgBuilder<T, L = char> = Dawg<T, L>;
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Creates an empty dawg configured by `options`, failing if the register can't be sized for its capacity
+    pub fn with_options(options: DawgOptions) -> Result<Self, DawgError> {
+        options.validate()?;
+
+        let mut dawg = Self::with_wrapper(T::new());
+        dawg.minimized_nodes = Register::with_hasher(options.hasher);
+        if let Err(error) = dawg.minimized_nodes.try_reserve(options.capacity) {
+            return Err(DawgError::InvalidOptions(OptionsConflict::Capacity { nodes: options.capacity, error }));
+        }
+        dawg.options = options;
+        Ok(dawg)
+    }
+
+    /// the options this dawg was created with
+    pub fn options(&self) -> &DawgOptions {
+        &self.options
+    }
+
+    /// Direction the words are being added in: the one of the options, or under `SortDirection::Auto` the one of the
+    /// first two distinct words added (`None` until then). Never `SortDirection::Auto`
+    pub fn sort_direction(&self) -> Option<SortDirection> {
+        match self.options.sort_direction {
+            SortDirection::Auto => self.direction,
+            direction => Some(direction),
+        }
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Adds `word` after applying the options of the dawg, failing instead of panicking if the word is rejected. Once the
+    /// dawg is finished the word may go anywhere, it is inserted with `insert_sequence`. Under `ValidationPolicy::Skip` a
+    /// word with a letter outside of the alphabet or out of order is dropped instead
+    pub fn try_add(&mut self, word: &str) -> Result<(), DawgError> {
+        let added = self.add_word(word);
+        if self.options.skips(&added) {
+            self.words_skipped += 1;
+            return Ok(());
+        }
+        added?;
+        self.minimized_nodes.check()
+    }
+
+    fn add_word(&mut self, word: &str) -> Result<(), DawgError> {
+        let word = self.options.prepare(word, Granularity::Chars)?;
+        let letters = word.chars().collect::<Vec<_>>();
+        // a finished dawg takes the word wherever it goes, through `insert_sequence`
+        if self.finished {
+            if !self.insert_sequence(&letters) && self.options.duplicate_policy == DuplicatePolicy::Error {
+                return Err(DawgError::Duplicate(word));
+            }
+            return Ok(());
+        }
+
+        let order = &self.options.sort_order;
+
+        // where the word goes compared with the previous one
+        let step = match (self.words_added, order.follows_letters()) {
+            (0, _) => Ordering::Equal,
+            (_, true) => self.previous_word.as_slice().cmp(letters.as_slice()),
+            (_, false) => order.compare(&self.previous_word.iter().collect::<String>(), &word),
+        };
+
+        let direction = match (self.sort_direction(), step) {
+            (Some(direction), _) => direction,
+            (None, Ordering::Less) => *self.direction.insert(SortDirection::Ascending),
+            (None, Ordering::Greater) => *self.direction.insert(SortDirection::Descending),
+            (None, Ordering::Equal) => SortDirection::Ascending,
+        };
+
+        let unsorted = match direction {
+            SortDirection::Descending => step == Ordering::Less,
+            _ => step == Ordering::Greater,
+        };
+
+        if unsorted {
+            let previous = self.previous_word.iter().collect();
+            let order = match self.options.sort_direction {
+                SortDirection::Ascending => order.name(),
+                _ => order.directed_name(direction),
+            };
+            return Err(DawgError::Unsorted { previous, word, order });
+        }
+
+        if self.words_added > 0 && self.previous_word == letters && self.options.duplicate_policy == DuplicatePolicy::Error {
+            return Err(DawgError::Duplicate(word));
+        }
+
+        match (order.follows_letters() && direction != SortDirection::Descending) || !self.goes_back(&letters) {
+            true => self.push_sequence(&letters),
+            false => self.stage(letters),
+        }
+        Ok(())
+    }
+
+    /// Adds every word of `words` in any order: they are folded, sorted (in the sort order and direction of the options) and
+    /// deduplicated first, then added with `try_add`. Returns the number of distinct words added
+    pub fn add_all<I, S>(&mut self, words: I) -> Result<usize, DawgError> where I: IntoIterator<Item = S>, S: AsRef<str> {
+        let span = trace::timed!("add_all", words, nodes_created);
+        let (trie_nodes, before) = (self.trie_nodes, self.words_added);
+        let mut words = words.into_iter().map(|word| self.options.fold(word.as_ref())).collect::<Vec<_>>();
+        words.sort_unstable_by(|a, b| self.options.sort_order.compare(a, b));
+        words.dedup();
+        if self.sort_direction() == Some(SortDirection::Descending) {
+            words.reverse();
+        }
+
+        for word in &words {
+            self.try_add(word)?;
+        }
+
+        let added = self.words_added - before;
+        span.record("words", added);
+        span.record("nodes_created", self.trie_nodes.saturating_sub(trie_nodes));
+        Ok(added)
+    }
+}
@@ -0,0 +1,74 @@
+use std::cmp::Ordering;
+
+use crate::dawg::common::{Dawg, NodeOps, Wrapper};
+
+/// Completions returned by `Dawg::completions_page`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct Page {
+    /// at most `limit` completions, in the order of `words_with_prefix`
+    pub words: Vec<String>,
+    /// token to pass as `after` for the next page (the last word of this one), `None` once every completion was returned
+    pub next: Option<String>,
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns up to `limit` of the words starting with `prefix` (matched exactly, as in `words_with_prefix`) that come after
+    /// `after`, or the first of them if `after` is `None`.
+    ///
+    /// `after` doesn't have to be one of the completions: the page starts at the first completion greater than it, so a
+    /// token stays valid when its word is removed from a later version of the dawg. Rather than enumerating the completions
+    /// up to `after`, the walk descends along its letters, only keeping the edges greater than them, so a page costs the
+    /// length of `after` plus the words it returns. A `limit` of 0 returns an empty page without a token
+    pub fn completions_page(&self, prefix: &str, after: Option<&str>, limit: usize) -> Page {
+        let prefix = prefix.chars().collect::<Vec<_>>();
+        let Some(start) = self.find_sequence(&prefix) else {
+            return Page::default();
+        };
+
+        // nodes left to walk with the path to them, the smallest on top
+        let mut stack = vec![];
+        let after = after.map(|after| after.chars().collect::<Vec<_>>());
+        match after {
+            Some(after) if after.starts_with(&prefix) => {
+                let mut node = start;
+                let mut path = prefix;
+                let mut rest = after[path.len()..].iter();
+
+                loop {
+                    let next = rest.next();
+                    let children = node.children();
+                    let greater = children.iter().rev().take_while(|(label, _)| next.is_none_or(|next| label > next));
+                    stack.extend(greater.map(|(label, child)| (child.clone(), [path.as_slice(), &[*label]].concat())));
+
+                    let Some((label, child)) = next.and_then(|next| children.into_iter().find(|(label, _)| label == next)) else {
+                        break;
+                    };
+                    path.push(label);
+                    node = child;
+                }
+            }
+            Some(after) if after.as_slice().cmp(&prefix) == Ordering::Greater => {}
+            _ => stack.push((start, prefix)),
+        }
+
+        let mut page = Page::default();
+        while page.words.len() < limit {
+            let Some((node, path)) = stack.pop() else {
+                break;
+            };
+
+            for (label, child) in node.children().into_iter().rev() {
+                stack.push((child, [path.as_slice(), &[label]].concat()));
+            }
+            if node.is_terminal() {
+                page.words.push(path.into_iter().collect());
+            }
+        }
+
+        // every node left on the stack leads to at least one more completion
+        if !stack.is_empty() {
+            page.next = page.words.last().cloned();
+        }
+        page
+    }
+}
@@ -0,0 +1,300 @@
+use std::{fs::File, io::{Read, Seek, SeekFrom}, path::Path, sync::Mutex};
+
+use crate::{dawg::{compiled::{decode_edge, CompiledNode, Header, EDGE_SIZE, NODE_SIZE, NONE, SLOT_SIZE}, integrity::Section, lexicon::MatchMode, lru::Lru, noalloc::CompiledNodes, options::DawgOptions, serialize::Cursor}, error::DawgError};
+
+/// Settings of `PagedDawgReader`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PagedOptions {
+    page_size: usize,
+    cache_pages: usize,
+}
+
+impl Default for PagedOptions {
+    /// Pages of 4KiB, at most 16384 of them (64MiB) held in memory
+    fn default() -> Self {
+        Self { page_size: 4096, cache_pages: 16384 }
+    }
+}
+
+impl PagedOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Number of bytes read from the file at once
+    pub fn page_size(mut self, page_size: usize) -> Self {
+        self.page_size = page_size.max(1);
+        self
+    }
+
+    /// Most pages held in memory at once, the least recently used one being dropped to make room for another
+    pub fn cache_pages(mut self, cache_pages: usize) -> Self {
+        self.cache_pages = cache_pages.max(1);
+        self
+    }
+}
+
+/// How the page cache of a `PagedDawgReader` was used, see `PagedDawgReader::stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct PageStats {
+    /// pages looked up by queries
+    pub lookups: usize,
+    /// lookups answered from memory
+    pub hits: usize,
+    /// pages read from the file
+    pub reads: usize,
+    /// pages dropped from memory to make room for others
+    pub evictions: usize,
+    /// pages currently held in memory
+    pub resident: usize,
+    /// most pages held in memory at once
+    pub peak_resident: usize,
+}
+
+/// Pages of the file held in memory by their number
+#[derive(Debug)]
+struct PageCache {
+    pages: Lru<u64, Vec<u8>>,
+    stats: PageStats,
+}
+
+/// The file being read along with the pages of it in memory, behind the lock of the reader
+#[derive(Debug)]
+struct Source<R> {
+    reader: R,
+    len: u64,
+    cache: PageCache,
+}
+
+/// Answers queries on a CompiledDawg saved to a file (see `CompiledDawg::save`) without loading it: only its header stays
+/// in memory, nodes and edges are read a page at a time when queries reach them, through a bounded LRU cache of pages.
+///
+/// The reader can be shared between threads, which then share its cache (the lock is held while a page is read from the
+/// file). Opening checks the header against its checksum and the length of the file, but reading every section to check
+/// its checksum would defeat the purpose of the reader: call `integrity::verify_file` first for that. Otherwise records
+/// are checked as they are read, so a damaged section is only reported by the queries reaching the damage
+#[derive(Debug)]
+pub struct PagedDawgReader<R = File> {
+    header: Header,
+    root: Option<CompiledNode>,
+    options: PagedOptions,
+    source: Mutex<Source<R>>,
+}
+
+impl PagedDawgReader<File> {
+    /// Opens the compiled dawg saved at `path`
+    pub fn open(path: impl AsRef<Path>, options: PagedOptions) -> Result<Self, DawgError> {
+        Self::new(File::open(path)?, options)
+    }
+}
+
+impl<R> PagedDawgReader<R> where R: Read + Seek {
+    /// Reads the header of the compiled dawg saved in `reader`, the rest of it being read as queries need it
+    pub fn new(mut reader: R, options: PagedOptions) -> Result<Self, DawgError> {
+        let len = reader.seek(SeekFrom::End(0))?;
+        let truncated = |section| DawgError::Truncated { section };
+
+        reader.seek(SeekFrom::Start(0))?;
+        let mut bytes = vec![0; Header::PREFIX.min(len as usize)];
+        reader.read_exact(&mut bytes)?;
+
+        let header_len = Header::PREFIX + Header::rest_len(&bytes)?;
+        if header_len as u64 > len {
+            return Err(truncated(Section::Header));
+        }
+        bytes.resize(header_len, 0);
+        reader.read_exact(&mut bytes[Header::PREFIX..])?;
+        let header = Header::parse(&bytes)?;
+
+        if header.file_len() > len {
+            let section = match len {
+                len if len < header.edges_at() => Section::Nodes,
+                len if len < header.slots_at() => Section::Edges,
+                _ => Section::Slots,
+            };
+            return Err(truncated(section));
+        }
+
+        let cache = PageCache { pages: Lru::new(), stats: PageStats::default() };
+        let mut paged = Self { header, root: None, options, source: Mutex::new(Source { reader, len, cache }) };
+        if paged.header.node_count > 0 {
+            paged.root = Some(paged.node(0)?);
+        }
+        Ok(paged)
+    }
+
+    pub fn options(&self) -> &DawgOptions {
+        &self.header.options
+    }
+
+    pub fn node_count(&self) -> usize {
+        self.header.node_count as usize
+    }
+
+    /// Returns the number of words in the dawg
+    pub fn word_count(&self) -> usize {
+        self.root.map_or(0, |root| root.count as usize)
+    }
+
+    pub fn stats(&self) -> PageStats {
+        self.source.lock().unwrap().cache.stats
+    }
+
+    /// Copies the `buf.len()` bytes of the file starting at `offset` into `buf`, going through the cache
+    fn read(&self, offset: u64, buf: &mut [u8]) -> Result<(), DawgError> {
+        let page_size = self.options.page_size as u64;
+        let mut source = self.source.lock().unwrap();
+        let Source { reader, len, cache } = &mut *source;
+        let mut copied = 0;
+
+        while copied < buf.len() {
+            let position = offset + copied as u64;
+            let page = position / page_size;
+            cache.stats.lookups += 1;
+
+            let bytes = match cache.pages.get(&page) {
+                Some(bytes) => {
+                    cache.stats.hits += 1;
+                    bytes
+                }
+                None => {
+                    if cache.pages.len() >= self.options.cache_pages && cache.pages.pop_oldest().is_some() {
+                        cache.stats.evictions += 1;
+                    }
+
+                    let start = page * page_size;
+                    let mut bytes = vec![0; page_size.min(len.saturating_sub(start)) as usize];
+                    reader.seek(SeekFrom::Start(start))?;
+                    reader.read_exact(&mut bytes)?;
+                    cache.stats.reads += 1;
+                    cache.stats.resident = cache.pages.len() + 1;
+                    cache.stats.peak_resident = cache.stats.peak_resident.max(cache.stats.resident);
+                    cache.pages.insert(page, bytes)
+                }
+            };
+
+            let from = (position - page * page_size) as usize;
+            let end = bytes.len().min(from + buf.len() - copied);
+            if from >= end {
+                return Err(DawgError::InvalidFormat(String::from("unexpected end of data")));
+            }
+            buf[copied..copied + end - from].copy_from_slice(&bytes[from..end]);
+            copied += end - from;
+        }
+
+        Ok(())
+    }
+
+    fn node(&self, id: u32) -> Result<CompiledNode, DawgError> {
+        let mut bytes = [0; NODE_SIZE];
+        self.read(self.header.nodes_at() + id as u64 * NODE_SIZE as u64, &mut bytes)?;
+        let node = CompiledNode::decode(&mut Cursor::new(&bytes))?;
+
+        match node.first_edge as u64 + node.edges as u64 <= self.header.edge_count as u64 {
+            true => Ok(node),
+            false => Err(DawgError::InvalidFormat(format!("node {id} has edges out of bounds"))),
+        }
+    }
+
+    fn edge(&self, edge: u32) -> Result<(char, u32), DawgError> {
+        let mut bytes = [0; EDGE_SIZE];
+        self.read(self.header.edges_at() + edge as u64 * EDGE_SIZE as u64, &mut bytes)?;
+        decode_edge(&mut Cursor::new(&bytes), edge, self.header.node_count)
+    }
+
+    fn slot(&self, slot: u32) -> Result<u32, DawgError> {
+        if slot >= self.header.slot_count {
+            return Err(DawgError::InvalidFormat(String::from("a dispatch table is out of bounds")));
+        }
+
+        let mut bytes = [0; SLOT_SIZE];
+        self.read(self.header.slots_at() + slot as u64 * SLOT_SIZE as u64, &mut bytes)?;
+        match u32::from_le_bytes(bytes) {
+            child if child == NONE || child < self.header.node_count => Ok(child),
+            _ => Err(DawgError::InvalidFormat(String::from("a dispatch table points at a node out of bounds"))),
+        }
+    }
+
+    /// value is true if `word` is one of the words in the dawg
+    pub fn contains(&self, word: &str, mode: MatchMode) -> Result<bool, DawgError> {
+        Ok(self.find(word, mode, false)?.iter().any(|(node, _)| node.terminal))
+    }
+
+    /// value is true if at least one word in the dawg starts with `prefix`
+    pub fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> Result<bool, DawgError> {
+        Ok(self.find(prefix, mode, false)?.iter().any(|(node, _)| node.terminal || node.edges > 0))
+    }
+
+    /// Returns every word starting with `prefix` in sorted order
+    pub fn completions(&self, prefix: &str, mode: MatchMode) -> Result<Vec<String>, DawgError> {
+        let mut words = vec![];
+
+        for (node, mut word) in self.find(prefix, mode, true)? {
+            if node.terminal {
+                words.push(word.clone());
+            }
+            let mut stack = vec![(node, 0)];
+
+            while let Some((node, next_edge)) = stack.last_mut() {
+                if *next_edge == node.edges {
+                    stack.pop();
+                    if !stack.is_empty() {
+                        word.pop();
+                    }
+                    continue;
+                }
+
+                let (label, child) = self.edge(node.first_edge + *next_edge)?;
+                *next_edge += 1;
+                let child = self.node(child)?;
+
+                word.push(label);
+                if child.terminal {
+                    words.push(word.clone());
+                }
+                // a path longer than the number of nodes goes through one of them twice
+                if stack.len() > self.header.node_count as usize {
+                    return Err(DawgError::InvalidFormat(String::from("the edges form a cycle")));
+                }
+                stack.push((child, 0));
+            }
+        }
+
+        words.sort();
+        words.dedup();
+        Ok(words)
+    }
+
+    /// Returns every word in sorted order
+    pub fn words(&self) -> Result<Vec<String>, DawgError> {
+        self.completions("", MatchMode::Exact)
+    }
+}
+
+impl<R> CompiledNodes for PagedDawgReader<R> where R: Read + Seek {
+    type Error = DawgError;
+
+    fn options(&self) -> &DawgOptions {
+        &self.header.options
+    }
+
+    fn root(&self) -> Option<CompiledNode> {
+        self.root
+    }
+
+    fn node(&self, id: u32) -> Result<CompiledNode, DawgError> {
+        PagedDawgReader::node(self, id)
+    }
+
+    fn edge(&self, edge: u32) -> Result<(char, u32), DawgError> {
+        PagedDawgReader::edge(self, edge)
+    }
+
+    fn slot(&self, slot: u32) -> Result<u32, DawgError> {
+        PagedDawgReader::slot(self, slot)
+    }
+
+    fn slot_len(&self) -> u32 {
+        self.header.slot_count
+    }
+}
@@ -0,0 +1,84 @@
+use std::marker::PhantomData;
+
+use rayon::iter::{plumbing::UnindexedConsumer, IntoParallelIterator, ParallelIterator};
+
+use crate::dawg::{common::{Dawg, NodeOps, NodeRef, Wrapper}, iter::{Sequences, Words}};
+
+/// Words of a Dawg split into subtrees that rayon walks in parallel, as returned by `Dawg::par_words`.
+///
+/// Words are yielded in no particular order, but each word of the dawg is yielded exactly once
+#[derive(Debug)]
+pub struct ParWords<'a, N: NodeRef<Label = char>> {
+    /// subtrees (node, path to it) whose words are yielded together
+    splits: Vec<(N, Vec<char>)>,
+    /// words ending on the nodes that were split into their children
+    words: Vec<String>,
+    dawg: PhantomData<&'a ()>,
+}
+
+impl<'a, N> ParWords<'a, N> where N: NodeRef<Label = char> + Send {
+    /// Splits the words below `node` until there are enough subtrees to keep `threads` busy,
+    /// always splitting the subtree holding the most words
+    fn new(node: Option<N>, prefix: Vec<char>, threads: usize) -> Self {
+        let mut splits = node.map(|node| vec![(node.reachable(), node, prefix)]).unwrap_or_default();
+        let mut words = vec![];
+        let total = splits.first().map_or(0, |(count, _, _)| *count);
+
+        while splits.len() < threads * 4 {
+            let Some(largest) = (0..splits.len()).max_by_key(|index| splits[*index].0) else { break };
+            let (count, node, _) = &splits[largest];
+            let children = node.children();
+
+            if children.is_empty() || *count * threads * 4 <= total {
+                break;
+            }
+
+            let (_, node, prefix) = splits.swap_remove(largest);
+            if node.is_terminal() {
+                words.push(prefix.iter().collect());
+            }
+
+            for (letter, child) in children {
+                let mut path = prefix.clone();
+                path.push(letter);
+                splits.push((child.reachable(), child, path));
+            }
+        }
+
+        Self {
+            splits: splits.into_iter().map(|(_, node, prefix)| (node, prefix)).collect(),
+            words,
+            dawg: PhantomData,
+        }
+    }
+
+    /// number of words in each of the subtrees the work is split into
+    pub fn split_sizes(&self) -> Vec<usize> {
+        self.splits.iter().map(|(node, _)| node.reachable()).collect()
+    }
+}
+
+impl<'a, N> ParallelIterator for ParWords<'a, N> where N: NodeRef<Label = char> + Send {
+    type Item = String;
+
+    /// The words ending where the dawg was split, then the words of each subtree, walked sequentially by one rayon task
+    fn drive_unindexed<C>(self, consumer: C) -> C::Result where C: UnindexedConsumer<String> {
+        self.words.into_par_iter()
+            .chain(self.splits.into_par_iter().flat_map_iter(|(node, prefix)| Words::new(Sequences::new(Some(node), prefix))))
+            .drive_unindexed(consumer)
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper, T::Node: Send {
+    /// Returns every word of the dawg as a rayon parallel iterator, in no particular order. The work is split into subtrees
+    /// of balanced word counts, enough of them to keep every thread of the current rayon pool busy
+    pub fn par_words(&self) -> ParWords<'_, T::Node> {
+        ParWords::new(Some(self.root.clone()), vec![], rayon::current_num_threads())
+    }
+
+    /// Returns every word starting with `prefix` (matched exactly) as a rayon parallel iterator, in no particular order
+    pub fn par_words_with_prefix(&self, prefix: &str) -> ParWords<'_, T::Node> {
+        let prefix = prefix.chars().collect::<Vec<_>>();
+        ParWords::new(self.find_sequence(&prefix), prefix, rayon::current_num_threads())
+    }
+}
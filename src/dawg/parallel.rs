@@ -0,0 +1,189 @@
+//! The parallel counterparts of `finish`, `compile_with` and `from_bytes`, which split their work into contiguous ranges
+//! of nodes (or of chunks of a saved file) run on the threads of the current rayon pool: the global one, unless they are
+//! called from `ThreadPool::install`. Each gives the same result as its sequential counterpart
+
+use std::{collections::HashMap, io::Read, ops::Range};
+
+use rayon::iter::{IntoParallelIterator, ParallelIterator};
+
+use crate::{dawg::{common::{Dawg, Label, NodeOps, NodeRef, Wrapper}, compiled::CompiledDawg, integrity::{check_chunks, CHUNK_SIZE}, serialize::{link, EncodeLabel, Layout, SavedHeader, EDGE_SIZE, NODE_SIZE}, trace, visit::view}, error::DawgError};
+
+/// Fewest items worth handing to a thread of their own
+const MIN_CHUNK: usize = 4096;
+
+/// Runs `f` on every item of `items`, each thread of the pool taking a contiguous share of them along with its offset.
+/// Too few items to keep several threads busy are handled as one share
+fn par_chunks<N, F>(items: &[N], f: F) where N: Sync, F: Fn(usize, &[N]) + Sync {
+    par_ranges(items.len(), MIN_CHUNK, |range| f(range.start, &items[range]));
+}
+
+/// Runs `f` on contiguous ranges covering `0..len`, one per thread of the pool but of at least `min` items each.
+/// Returns what `f` returned for every range, in the order of the ranges
+fn par_ranges<R, F>(len: usize, min: usize, f: F) -> Vec<R> where R: Send, F: Fn(Range<usize>) -> R + Sync {
+    let size = len.div_ceil(rayon::current_num_threads()).max(min).max(1);
+    (0..len.div_ceil(size).max(1)).into_par_iter().map(|range| f(range * size..len.min(range * size + size))).collect()
+}
+
+impl<'a> Layout<'a> {
+    /// Same as `parse`, with the chunks of every section checked and the records decoded in parallel
+    fn parse_parallel(bytes: &'a [u8]) -> Result<Self, DawgError> {
+        let (header, mut reader) = SavedHeader::parse(bytes)?;
+        let sections = header.read_sections(&mut reader)?;
+        for (section, bytes, checksums) in sections {
+            par_ranges(checksums.len() / 4, 1, |chunks| {
+                let end = bytes.len().min(chunks.end * CHUNK_SIZE);
+                check_chunks(section, &bytes[chunks.start * CHUNK_SIZE..end], &checksums[chunks.start * 4..chunks.end * 4])
+            }).into_iter().collect::<Result<(), _>>()?;
+        }
+
+        let [(_, nodes, _), (_, edges, _), (_, labels, _)] = sections;
+        let nodes = par_ranges(header.node_count as usize, MIN_CHUNK, |ids| {
+            header.nodes(ids.start as u32, &nodes[ids.start * NODE_SIZE..ids.end * NODE_SIZE])
+        }).into_iter().collect::<Result<Vec<_>, _>>()?.concat();
+        let edges = par_ranges(header.edge_count as usize, MIN_CHUNK, |ids| {
+            header.edges(ids.start as u32, &edges[ids.start * EDGE_SIZE..ids.end * EDGE_SIZE])
+        }).into_iter().collect::<Result<Vec<_>, _>>()?.concat();
+
+        Self::new(header, nodes, edges, labels)
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, T::Node: Send + Sync, L: EncodeLabel {
+    /// Same as `from_bytes`, in parallel: the chunks of every section are checked against their checksums and the
+    /// records decoded a range at a time, then the saved edges are added to the nodes the same way. The result is the same as
+    /// `from_bytes`. Files saved before version 4 of the layout have their sections checked as a whole, on one thread each
+    pub fn from_bytes_parallel(bytes: &[u8]) -> Result<Self, DawgError> {
+        let layout = Layout::parse_parallel(bytes)?;
+        let (mut dawg, created) = Self::create_nodes(&layout);
+
+        par_ranges(created.len(), MIN_CHUNK, |mut ids| {
+            ids.try_for_each(|id| link(&layout, &created, &created[id], &layout.nodes[id]))
+        }).into_iter().collect::<Result<(), _>>()?;
+
+        dawg.finish_loading(&layout);
+        Ok(dawg)
+    }
+
+    /// Reads a dawg written by `save` from `reader`, then decodes it in parallel, see `from_bytes_parallel`
+    pub fn load_parallel<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes_parallel(&bytes)
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, T::Node: Send + Sync, L: Label {
+    /// Same as `finish`, with the word counts, the completion depths and the canonical ids worked out in parallel.
+    ///
+    /// Nodes are grouped by height (the length of the longest path down to a leaf): the nodes of a height only depend on
+    /// lower ones, so each height is counted in parallel once the one below is done. The result is the same as `finish`
+    pub fn finish_parallel(&mut self) {
+        let span = trace::timed!("finish_parallel", words, nodes, threads);
+        self.reminimize();
+        self.merge_staged();
+        self.minimize(0);
+
+        let nodes = self.postorder();
+        let index = nodes.iter().enumerate().map(|(index, node)| (node.id(), index)).collect::<HashMap<_, _>>();
+        let mut levels: Vec<Vec<T::Node>> = vec![];
+        let mut heights = Vec::with_capacity(nodes.len());
+
+        // children come first in postorder, so their heights are known by the time their parents are reached
+        for node in &nodes {
+            let height = node.children().iter().map(|(_, child)| heights[index[&child.id()]] + 1).max().unwrap_or(0);
+            heights.push(height);
+            if levels.len() <= height {
+                levels.push(vec![]);
+            }
+            levels[height].push(node.clone());
+        }
+
+        for level in &levels {
+            par_chunks(level, |_, chunk| {
+                for node in chunk {
+                    let below = node.children().iter().map(|(_, child)| child.count()).sum::<usize>();
+                    node.write(|node| node.count = node.terminal as usize + below);
+                    // the depths of the children are known by now, so this doesn't walk further down
+                    node.num_depths();
+                }
+            });
+        }
+
+        self.minimized_nodes.release();
+        self.unchecked_nodes = vec![];
+        self.leaf = None;
+        self.unshare();
+        par_chunks(&self.breadth_first(), |offset, chunk| {
+            for (id, node) in chunk.iter().enumerate() {
+                node.write(|node| node.id = offset + id);
+            }
+        });
+        self.checkpoints.clear();
+        self.register_log.clear();
+        self.finished = true;
+        span.record("words", self.root.count());
+        span.record("nodes", nodes.len());
+        span.record("threads", rayon::current_num_threads());
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper, T::Node: Send + Sync {
+    /// Same as `compile_with`, in parallel: every thread compiles a contiguous range of the nodes, in the order of their
+    /// ids, and the ranges are joined one after the other. The result is the same as `compile_with`
+    pub fn compile_parallel(&self, min_fanout: usize) -> CompiledDawg {
+        let nodes = self.breadth_first();
+        let ids = nodes.iter().enumerate().map(|(id, node)| (node.id(), id)).collect::<HashMap<_, _>>();
+        let options = &self.options;
+
+        let mut compiled = CompiledDawg::empty(options.clone());
+        for range in par_ranges(nodes.len(), MIN_CHUNK, |range| {
+            let mut compiled = CompiledDawg::empty(options.clone());
+            for node in &nodes[range] {
+                compiled.push_node(&view(node, &ids), min_fanout);
+            }
+            compiled
+        }) {
+            compiled.append(range);
+        }
+
+        compiled
+    }
+}
+
+#[cfg(all(test, feature = "sync"))]
+mod tests {
+    use crate::{dawg::common::NodeOps, NodeRef, SyncDawg};
+
+    #[test]
+    fn finish_parallel_keeps_the_same_depths_as_finish() {
+        let words = ["a", "ab", "abc", "b", "bcd", "bcde", "cat", "cats", "dog", "dot", "dots"];
+        let (mut sequential, mut parallel) = (SyncDawg::new_sync(), SyncDawg::new_sync());
+        sequential.add_all(words).unwrap();
+        parallel.add_all(words).unwrap();
+        sequential.finish();
+        parallel.finish_parallel();
+
+        let depths = |dawg: &SyncDawg| dawg.breadth_first().iter().map(|node| (node.id(), node.read(|node| node.depths))).collect::<Vec<_>>();
+        assert!(depths(&parallel).iter().all(|(_, depths)| depths.is_some()));
+        assert_eq!(depths(&parallel), depths(&sequential));
+        assert_eq!(parallel.root.read(|root| root.depths), Some((1, 4)));
+    }
+
+    #[test]
+    fn finish_parallel_on_a_fork_leaves_the_original_alone() {
+        let mut dawg = SyncDawg::new_sync();
+        dawg.add_all(["ab", "abc", "b", "bcd", "cde", "def", "xyz"]).unwrap();
+        dawg.finish();
+        let bytes = dawg.to_bytes().unwrap();
+
+        let mut fork = dawg.fork();
+        for word in ["aa", "xyzw", "aaa", "aab", "aac"] {
+            assert!(fork.insert(word).unwrap());
+        }
+        fork.finish_parallel();
+
+        assert_eq!(fork.assert_invariants(), Ok(()));
+        assert_eq!(dawg.assert_invariants(), Ok(()));
+        assert_eq!(dawg.to_bytes().unwrap(), bytes);
+    }
+}
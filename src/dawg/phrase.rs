@@ -0,0 +1,124 @@
+use std::fmt::Debug;
+
+use crate::{dawg::common::{Dawg, Wrapper}, error::DawgError};
+
+/// Dawg of phrases: each edge is labelled by a whole token, so prefixes are made of words rather than letters.
+///
+/// Phrases are split on the delimiter (empty tokens are skipped) and must be added in the order of their token sequences,
+/// which isn't always the order of the joined strings: with a space delimiter, `"a\tb"` sorts before `"a b"` as a string,
+/// but `["a", "b"]` sorts before `["a\tb"]`. Sort the phrases with `PhraseDawg::sort` to get the right order
+pub struct PhraseDawg<T: Wrapper<String>> {
+    dawg: Dawg<T, String>,
+    delimiter: String,
+}
+
+impl<T> Debug for PhraseDawg<T> where T: Wrapper<String>, Dawg<T, String>: Debug {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PhraseDawg").field("dawg", &self.dawg).field("delimiter", &self.delimiter).finish()
+    }
+}
+
+impl<T> Clone for PhraseDawg<T> where T: Wrapper<String> + Clone {
+    fn clone(&self) -> Self {
+        Self { dawg: self.dawg.clone(), delimiter: self.delimiter.clone() }
+    }
+}
+
+impl<T> Default for PhraseDawg<T> where T: Wrapper<String> {
+    /// Phrase dawg splitting on spaces
+    fn default() -> Self {
+        Self::with_delimiter(" ")
+    }
+}
+
+impl<T> PhraseDawg<T> where T: Wrapper<String> {
+    pub fn with_delimiter(delimiter: &str) -> Self {
+        Self { dawg: Dawg::with_wrapper(T::new()), delimiter: delimiter.to_owned() }
+    }
+
+    pub fn delimiter(&self) -> &str {
+        &self.delimiter
+    }
+
+    /// the dawg of token sequences backing this phrase dawg
+    pub fn dawg(&self) -> &Dawg<T, String> {
+        &self.dawg
+    }
+
+    /// Splits `phrase` into its tokens, the way every phrase given to this dawg is split
+    pub fn tokens<'p>(&self, phrase: &'p str) -> Vec<&'p str> {
+        phrase.split(self.delimiter.as_str()).filter(|token| !token.is_empty()).collect()
+    }
+
+    /// Sorts `phrases` by their token sequences, the order in which they must be added
+    pub fn sort(&self, phrases: &mut [&str]) {
+        phrases.sort_by(|a, b| self.tokens(a).cmp(&self.tokens(b)));
+    }
+
+    fn join(&self, tokens: Vec<String>) -> String {
+        tokens.join(&self.delimiter)
+    }
+
+    fn owned(tokens: &[&str]) -> Vec<String> {
+        tokens.iter().map(|token| token.to_string()).collect()
+    }
+
+    /// Adds a phrase already split into tokens, failing if it sorts before the previous one
+    pub fn try_add_tokens(&mut self, tokens: &[&str]) -> Result<(), DawgError> {
+        let tokens = Self::owned(tokens);
+
+        if self.dawg.previous_word > tokens {
+            let previous = self.join(self.dawg.previous_word.clone());
+            return Err(DawgError::Unsorted { previous, word: self.join(tokens), order: "token" });
+        }
+
+        self.dawg.push_sequence(&tokens);
+        Ok(())
+    }
+
+    pub fn try_add(&mut self, phrase: &str) -> Result<(), DawgError> {
+        self.try_add_tokens(&self.tokens(phrase))
+    }
+
+    pub fn add(&mut self, phrase: &str) {
+        if let Err(err) = self.try_add(phrase) {
+            panic!("Error: {err}");
+        }
+    }
+
+    pub fn finish(&mut self) {
+        self.dawg.finish();
+    }
+
+    /// value is true if the tokens make up one of the phrases of the dawg
+    pub fn contains_tokens(&self, tokens: &[&str]) -> bool {
+        self.dawg.contains_sequence(&Self::owned(tokens))
+    }
+
+    pub fn contains(&self, phrase: &str) -> bool {
+        self.contains_tokens(&self.tokens(phrase))
+    }
+
+    /// value is true if at least one phrase starts with these (whole) tokens
+    pub fn starts_with_tokens(&self, tokens: &[&str]) -> bool {
+        self.dawg.contains_prefix_sequence(&Self::owned(tokens))
+    }
+
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        self.starts_with_tokens(&self.tokens(prefix))
+    }
+
+    /// Returns every phrase starting with these tokens, joined with the delimiter, in the order of their token sequences
+    pub fn completions_tokens(&self, tokens: &[&str]) -> Vec<String> {
+        self.dawg.sequences_with_prefix(&Self::owned(tokens)).map(|tokens| self.join(tokens)).collect()
+    }
+
+    pub fn completions(&self, prefix: &str) -> Vec<String> {
+        self.completions_tokens(&self.tokens(prefix))
+    }
+
+    /// Returns every phrase joined with the delimiter, in the order of their token sequences
+    pub fn phrases(&self) -> impl Iterator<Item = String> + '_ {
+        self.dawg.sequences().map(|tokens| self.join(tokens))
+    }
+}
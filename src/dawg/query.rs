@@ -0,0 +1,177 @@
+use crate::{dawg::{common::{Dawg, NodeOps, NodeRef, Wrapper}, lexicon::{FoldProfile, Lexicon, MatchMode}}, node::NodeHandle};
+
+/// A word found in the Dawg, as returned by `Dawg::get`
+#[derive(Debug, Clone)]
+pub struct Match<'a, N: NodeRef> {
+    word: String,
+    node: NodeHandle<'a, N>,
+}
+
+impl<'a, N> Match<'a, N> where N: NodeRef {
+    /// the word as spelt in the dawg
+    pub fn word(&self) -> &str {
+        &self.word
+    }
+
+    /// the (terminal) node the word ends on
+    pub fn node(&self) -> &NodeHandle<'a, N> {
+        &self.node
+    }
+
+    pub fn into_node(self) -> NodeHandle<'a, N> {
+        self.node
+    }
+}
+
+/// What can follow a prefix in the Dawg, as returned by `Dawg::keys_at`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NextLetters {
+    /// number of words the prefix matches (more than one under a case-insensitive mode)
+    prefix_words: usize,
+    /// (letter, number of words continuing with it) pairs, sorted by letter
+    letters: Vec<(char, usize)>,
+}
+
+impl NextLetters {
+    /// value is true if the prefix is itself a word
+    pub fn is_word(&self) -> bool {
+        self.prefix_words > 0
+    }
+
+    /// Returns the letters that can follow the prefix, in sorted order
+    pub fn letters(&self) -> impl Iterator<Item = char> + '_ {
+        self.letters.iter().map(|(letter, _)| *letter)
+    }
+
+    /// Returns every letter that can follow the prefix along with the number of words continuing with it
+    pub fn counts(&self) -> &[(char, usize)] {
+        &self.letters
+    }
+
+    /// Returns the number of words continuing the prefix with `letter` (compared exactly), 0 if it can't follow it
+    pub fn count(&self, letter: char) -> usize {
+        self.letters.binary_search_by(|(label, _)| label.cmp(&letter)).map_or(0, |index| self.letters[index].1)
+    }
+
+    /// Returns the number of words starting with the prefix, the words it matches itself included
+    pub fn word_count(&self) -> usize {
+        self.prefix_words + self.letters.iter().map(|(_, count)| count).sum::<usize>()
+    }
+}
+
+/// Whether a string is a word of the Dawg and whether longer words start with it, as returned by `Dawg::classify`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Containment {
+    /// no word starts with the string
+    Neither,
+    /// the string isn't a word, but longer words start with it
+    Prefix,
+    /// the string is a word and no longer word starts with it
+    Word,
+    /// the string is a word and longer words start with it
+    WordAndPrefix,
+}
+
+impl Containment {
+    fn new(is_word: bool, is_prefix: bool) -> Self {
+        match (is_word, is_prefix) {
+            (false, false) => Containment::Neither,
+            (false, true) => Containment::Prefix,
+            (true, false) => Containment::Word,
+            (true, true) => Containment::WordAndPrefix,
+        }
+    }
+
+    /// value is true if the string is a word
+    pub fn is_word(self) -> bool {
+        matches!(self, Containment::Word | Containment::WordAndPrefix)
+    }
+
+    /// value is true if longer words start with the string
+    pub fn is_prefix_of_other_words(self) -> bool {
+        matches!(self, Containment::Prefix | Containment::WordAndPrefix)
+    }
+
+    /// value is true if at least one word starts with the string, the string itself included (see `Lexicon::contains_prefix`)
+    pub fn is_prefix(self) -> bool {
+        self != Containment::Neither
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// value is true if `word` is one of the words in the dawg, compared with the match mode of its options
+    pub fn contains(&self, word: &str) -> bool {
+        Lexicon::contains(self, word, self.options.match_mode)
+    }
+
+    /// value is true if `word` is one of the words in the dawg once both are upper-cased
+    pub fn contains_case_insensitive(&self, word: &str) -> bool {
+        Lexicon::contains(self, word, MatchMode::UnicodeInsensitive(FoldProfile::Default))
+    }
+
+    /// Returns the word matching `word` under the match mode of the options, along with the node it ends on
+    pub fn get(&self, word: &str) -> Option<Match<'_, T::Node>> {
+        self.get_with(word, self.options.match_mode)
+    }
+
+    /// value is true if at least one word in the dawg starts with `prefix`, compared with the match mode of its options
+    pub fn starts_with(&self, prefix: &str) -> bool {
+        Lexicon::contains_prefix(self, prefix, self.options.match_mode)
+    }
+
+    /// value is true if the letters of `chars` spell one of the words in the dawg under `mode`.
+    /// The letters are matched as they are read, so no string has to be built first (an empty iterator is the empty word)
+    pub fn is_word_chars<I: IntoIterator<Item = char>>(&self, chars: I, mode: MatchMode) -> bool {
+        self.find_chars(chars, mode, false).iter().any(|res| res.node.is_terminal())
+    }
+
+    /// value is true if at least one word in the dawg starts with the letters of `chars` under `mode`
+    pub fn contains_prefix_chars<I: IntoIterator<Item = char>>(&self, chars: I, mode: MatchMode) -> bool {
+        self.find_chars(chars, mode, false).iter().any(|res| res.node.is_terminal() || res.node.read(|node| !node.edges.is_empty()))
+    }
+
+    /// Tells whether `s` is a word under `mode` and whether longer words start with it, from a single walk down the dawg:
+    /// the nodes `s` leads to are terminal for a word, and have edges for longer words
+    pub fn classify(&self, s: &str, mode: MatchMode) -> Containment {
+        let nodes = self.find_chars(s.chars(), mode, false);
+        let is_word = nodes.iter().any(|res| res.node.is_terminal());
+        let is_prefix = nodes.iter().any(|res| res.node.read(|node| !node.edges.is_empty()));
+        Containment::new(is_word, is_prefix)
+    }
+
+    /// Returns the first word (in sorted order of spelling) matching `word` under `mode`
+    pub fn get_with(&self, word: &str, mode: MatchMode) -> Option<Match<'_, T::Node>> {
+        self.find(word, mode).into_iter()
+            .find(|context| context.node.is_terminal())
+            .map(|context| Match { word: context.word, node: NodeHandle::new(context.node, self.dawg_id) })
+    }
+
+    /// Describes the letters that can follow `prefix` under `mode` (the root for the empty prefix), `None` if no word starts
+    /// with it. Only the edges of the nodes the prefix leads to are read, the counts being those kept by the nodes.
+    /// Under a case-insensitive mode, letters that match each other are merged under the smallest of them
+    pub fn keys_at(&self, prefix: &str, mode: MatchMode) -> Option<NextLetters> {
+        let nodes = self.find_chars(prefix.chars(), mode, false);
+        if nodes.is_empty() {
+            return None;
+        }
+
+        let mut next = NextLetters { prefix_words: 0, letters: vec![] };
+        for res in nodes {
+            next.prefix_words += res.node.is_terminal() as usize;
+
+            for (label, child) in res.node.children() {
+                let count = child.reachable();
+                match next.letters.iter_mut().find(|(letter, _)| *letter == label || mode.matches(*letter, label)) {
+                    Some((letter, total)) => {
+                        *letter = label.min(*letter);
+                        *total += count;
+                    }
+                    None => next.letters.push((label, count)),
+                }
+            }
+        }
+
+        next.letters.sort();
+        Some(next)
+    }
+}
@@ -0,0 +1,149 @@
+use crate::dawg::common::{Dawg, Label, NodeOps, NodeRef, Wrapper};
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Index of `sequence` among the sorted sequences of the dawg, if it is one of them.
+    /// Uses the reachable counts of the nodes, so it takes one step per label once the dawg is finished
+    pub fn rank_sequence(&self, sequence: &[L]) -> Option<usize> {
+        let mut node = self.root.clone();
+        let mut rank = 0;
+
+        for label in sequence {
+            rank += node.is_terminal() as usize;
+
+            let mut next = None;
+            for (edge, child) in node.children() {
+                if edge == *label {
+                    next = Some(child);
+                    break;
+                }
+                rank += child.reachable();
+            }
+            node = next?;
+        }
+
+        node.is_terminal().then_some(rank)
+    }
+
+    /// The sequence at `index` among the sorted sequences of the dawg, found by following the reachable counts down
+    pub fn select_sequence(&self, mut index: usize) -> Option<Vec<L>> {
+        let mut node = self.root.clone();
+        let mut sequence = vec![];
+
+        'descend: loop {
+            if node.is_terminal() {
+                if index == 0 {
+                    return Some(sequence);
+                }
+                index -= 1;
+            }
+
+            for (label, child) in node.children() {
+                let count = child.reachable();
+                if index < count {
+                    sequence.push(label);
+                    node = child;
+                    continue 'descend;
+                }
+                index -= count;
+            }
+
+            return None;
+        }
+    }
+
+    /// The first sequence of the dawg sorting strictly after `sequence`, which doesn't have to be one of them. Follows
+    /// `sequence` as far as it goes, then branches off at the deepest node with a larger label and takes the smallest
+    /// labels down to the first terminal node, so it takes about a step per label rather than reading the sequences
+    pub fn next_sequence_after(&self, sequence: &[L]) -> Option<Vec<L>> {
+        let path = self.path_along(sequence);
+
+        // the sequence is all there: the shortest sequence it starts with comes next
+        if path.len() > sequence.len() {
+            if let Some((label, child)) = path[sequence.len()].children().into_iter().next() {
+                return Some(descend_first([sequence, &[label]].concat(), child));
+            }
+        }
+
+        for depth in (0..path.len().min(sequence.len())).rev() {
+            let larger = path[depth].children().into_iter().find(|(label, _)| *label > sequence[depth]);
+            if let Some((label, child)) = larger {
+                return Some(descend_first([&sequence[..depth], &[label]].concat(), child));
+            }
+        }
+        None
+    }
+
+    /// The last sequence of the dawg sorting strictly before `sequence`, which doesn't have to be one of them. Mirrors
+    /// `next_sequence_after`: branches off at the deepest node with a smaller label and takes the largest labels down to
+    /// the end, or stops at the deepest node of the path ending a sequence, whichever comes later
+    pub fn prev_sequence_before(&self, sequence: &[L]) -> Option<Vec<L>> {
+        let path = self.path_along(sequence);
+
+        for depth in (0..path.len().min(sequence.len())).rev() {
+            let smaller = path[depth].children().into_iter().rev().find(|(label, _)| *label < sequence[depth]);
+            if let Some((label, child)) = smaller {
+                return Some(descend_last([&sequence[..depth], &[label]].concat(), child));
+            }
+            if path[depth].is_terminal() {
+                return Some(sequence[..depth].to_vec());
+            }
+        }
+        None
+    }
+
+    /// Returns the nodes `sequence` goes through from the root, as far as it can be followed
+    fn path_along(&self, sequence: &[L]) -> Vec<T::Node> {
+        let mut path = vec![self.root.clone()];
+        for label in sequence {
+            match path.last().unwrap().child(label) {
+                Some(child) => path.push(child),
+                None => break,
+            }
+        }
+        path
+    }
+}
+
+/// Completes `sequence`, which leads to `node`, with the smallest labels down to the first terminal node
+fn descend_first<N: NodeRef>(mut sequence: Vec<N::Label>, mut node: N) -> Vec<N::Label> {
+    while !node.is_terminal() {
+        let Some((label, child)) = node.children().into_iter().next() else { break };
+        sequence.push(label);
+        node = child;
+    }
+    sequence
+}
+
+/// Completes `sequence`, which leads to `node`, with the largest labels down to a node without edges
+fn descend_last<N: NodeRef>(mut sequence: Vec<N::Label>, mut node: N) -> Vec<N::Label> {
+    while let Some((label, child)) = node.children().pop() {
+        sequence.push(label);
+        node = child;
+    }
+    sequence
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Index of `word` among the sorted words of the dawg, if it is one of them (see `rank_sequence`).
+    /// This is the index of a word, not the id of a node
+    pub fn rank(&self, word: &str) -> Option<usize> {
+        self.rank_sequence(&word.chars().collect::<Vec<_>>())
+    }
+
+    /// The word at `index` among the sorted words of the dawg (see `select_sequence`)
+    pub fn select(&self, index: usize) -> Option<String> {
+        self.select_sequence(index).map(|letters| letters.into_iter().collect())
+    }
+
+    /// The first word sorting strictly after `s` (compared letter by letter, exactly), which doesn't have to be a word
+    /// itself (see `next_sequence_after`)
+    pub fn next_word_after(&self, s: &str) -> Option<String> {
+        self.next_sequence_after(&s.chars().collect::<Vec<_>>()).map(|letters| letters.into_iter().collect())
+    }
+
+    /// The last word sorting strictly before `s` (compared letter by letter, exactly), which doesn't have to be a word
+    /// itself (see `prev_sequence_before`)
+    pub fn prev_word_before(&self, s: &str) -> Option<String> {
+        self.prev_sequence_before(&s.chars().collect::<Vec<_>>()).map(|letters| letters.into_iter().collect())
+    }
+}
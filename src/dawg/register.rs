@@ -0,0 +1,416 @@
+use std::{collections::{hash_map::{DefaultHasher, RandomState}, HashMap}, fmt::Debug, fs::{self, File, OpenOptions}, hash::{BuildHasher, Hash, Hasher}, io::{self, Read, Seek, SeekFrom, Write}, path::Path};
+
+use crate::{dawg::{common::{Dawg, NodeOps, NodeRef, Signature, Wrapper}, external::SpillDir, lru::Lru, options::RegisterHasher, serialize::EncodeLabel}, error::{DawgError, OptionsConflict}};
+
+/// Number of files the evicted entries are spread over
+const BUCKETS: usize = 256;
+
+/// How the minimization register was used while building, see `Dawg::register_stats`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RegisterStats {
+    /// signatures looked up while minimizing
+    pub lookups: usize,
+    /// lookups answered from memory
+    pub cache_hits: usize,
+    /// lookups answered from the entries spilled to disk
+    pub disk_hits: usize,
+    /// lookups that found no equivalent node, each adding an entry to the register
+    pub misses: usize,
+    /// entries moved from memory to disk
+    pub evictions: usize,
+    /// most entries held in memory at once
+    pub peak_cached: usize,
+    /// reads or writes of the spilled entries that failed. Each one may leave a node unmerged, so the first one is kept
+    /// and fails the build, see `Dawg::try_finish`
+    pub io_errors: usize,
+}
+
+/// Splits the next length-prefixed part off `records`
+fn next_part<'a>(records: &mut &'a [u8]) -> io::Result<&'a [u8]> {
+    let invalid = || io::Error::new(io::ErrorKind::InvalidData, "truncated register record");
+    let len = u32::from_le_bytes(records.get(..4).ok_or_else(invalid)?.try_into().unwrap()) as usize;
+    let part = records.get(4..4 + len).ok_or_else(invalid)?;
+    *records = &records[4 + len..];
+    Ok(part)
+}
+
+/// Register entry kept in memory while spilling: the node, and the labels leading to it from the root
+type Cached<N> = (N, Vec<<N as NodeRef>::Label>);
+
+/// Entries of the register beyond the budget, written to bucket files as (signature, path from the root) records.
+/// A node is found again by following its path, which stays valid: nodes along it are only ever replaced by equivalent ones.
+///
+/// Where each record starts is kept in memory by the hash of its signature, so a lookup on disk reads that one record
+/// rather than its whole bucket
+struct Spill<N: NodeRef> {
+    budget: usize,
+    dir: SpillDir,
+    cache: Lru<Signature<N::Label>, Cached<N>>,
+    buckets: Vec<Option<File>>,
+    /// (offset, length) of the records in their bucket by the hash of their signature, several if the hashes collide.
+    /// A record brought back into memory is dropped from here, its bytes left in the file unread
+    records: HashMap<u64, Vec<(u64, usize)>>,
+    spilled: usize,
+    encode: fn(&N::Label, &mut Vec<u8>),
+    decode: fn(&[u8]) -> Option<N::Label>,
+}
+
+impl<N> Spill<N> where N: NodeRef {
+    fn new(budget: usize, temp_dir: &Path, encode: fn(&N::Label, &mut Vec<u8>), decode: fn(&[u8]) -> Option<N::Label>) -> io::Result<Self> {
+        Ok(Self {
+            budget,
+            dir: SpillDir::create(temp_dir, "register")?,
+            cache: Lru::new(),
+            buckets: (0..BUCKETS).map(|_| None).collect(),
+            records: HashMap::new(),
+            spilled: 0,
+            encode,
+            decode,
+        })
+    }
+
+    fn hash(signature: &Signature<N::Label>) -> u64 {
+        let mut hasher = DefaultHasher::new();
+        signature.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    fn bucket(hash: u64) -> usize {
+        hash as usize % BUCKETS
+    }
+
+    fn encode_labels(&self, labels: impl Iterator<Item = N::Label>, out: &mut Vec<u8>) {
+        let mut label = vec![];
+        for letter in labels {
+            label.clear();
+            (self.encode)(&letter, &mut label);
+            out.extend_from_slice(&(label.len() as u32).to_le_bytes());
+            out.extend_from_slice(&label);
+        }
+    }
+
+    fn encode_signature(&self, signature: &Signature<N::Label>) -> Vec<u8> {
+        let mut out = vec![signature.terminal as u8];
+        out.extend_from_slice(&(signature.edges.len() as u32).to_le_bytes());
+        self.encode_labels(signature.edges.iter().map(|(letter, _)| letter.clone()), &mut out);
+        for (_, child) in &signature.edges {
+            out.extend_from_slice(&(*child as u64).to_le_bytes());
+        }
+        out
+    }
+
+    /// Appends the record of an evicted entry to its bucket
+    fn write(&mut self, signature: &Signature<N::Label>, path: &[N::Label]) -> io::Result<()> {
+        let key = self.encode_signature(signature);
+        let mut labels = vec![];
+        self.encode_labels(path.iter().cloned(), &mut labels);
+
+        let mut record = Vec::with_capacity(8 + key.len() + labels.len());
+        for part in [&key, &labels] {
+            record.extend_from_slice(&(part.len() as u32).to_le_bytes());
+            record.extend_from_slice(part);
+        }
+
+        let hash = Self::hash(signature);
+        let bucket = Self::bucket(hash);
+        let file = match &mut self.buckets[bucket] {
+            Some(file) => file,
+            None => {
+                let file = OpenOptions::new().create(true).read(true).append(true).open(self.dir.path.join(bucket.to_string()))?;
+                self.buckets[bucket].insert(file)
+            }
+        };
+
+        let offset = file.seek(SeekFrom::End(0))?;
+        file.write_all(&record)?;
+        self.records.entry(hash).or_default().push((offset, record.len()));
+        Ok(())
+    }
+
+    /// Returns the encoded path of the node spilled with `signature`, if any, and forgets its record
+    fn take(&mut self, signature: &Signature<N::Label>) -> io::Result<Option<Vec<u8>>> {
+        let hash = Self::hash(signature);
+        let (Some(candidates), Some(mut file)) = (self.records.get(&hash), self.buckets[Self::bucket(hash)].as_ref()) else {
+            return Ok(None);
+        };
+
+        let key = self.encode_signature(signature);
+        for (at, &(offset, len)) in candidates.iter().enumerate() {
+            let mut bytes = vec![0; len];
+            file.seek(SeekFrom::Start(offset))?;
+            file.read_exact(&mut bytes)?;
+
+            let mut record = bytes.as_slice();
+            let (record_key, path) = (next_part(&mut record)?, next_part(&mut record)?);
+            if record_key == key {
+                let path = path.to_vec();
+                let candidates = self.records.get_mut(&hash).unwrap();
+                candidates.swap_remove(at);
+                if candidates.is_empty() {
+                    self.records.remove(&hash);
+                }
+                return Ok(Some(path));
+            }
+        }
+        Ok(None)
+    }
+
+    /// Moves the least recently used entries to disk until the cache fits the budget, stopping at the first that can't be
+    /// written (and is lost)
+    fn evict(&mut self, stats: &mut RegisterStats) -> io::Result<()> {
+        while self.cache.len() > self.budget {
+            let Some((signature, (_, path))) = self.cache.pop_oldest() else { break };
+            self.write(&signature, &path)?;
+            self.spilled += 1;
+            stats.evictions += 1;
+        }
+        Ok(())
+    }
+
+    /// Drops every entry, in memory and on disk
+    fn release(&mut self) -> io::Result<()> {
+        self.cache = Lru::new();
+        self.records = HashMap::new();
+        self.spilled = 0;
+        for (bucket, file) in self.buckets.iter_mut().enumerate() {
+            if file.take().is_some() {
+                fs::remove_file(self.dir.path.join(bucket.to_string()))?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Copies the spilled entries into a new directory next to this one, and the cache with `copy`
+    fn try_clone(&self, copy: &mut impl FnMut(&N) -> N) -> io::Result<Self> {
+        let temp_dir = self.dir.path.parent().unwrap_or(Path::new("."));
+        let mut clone = Self::new(self.budget, temp_dir, self.encode, self.decode)?;
+
+        for (bucket, file) in self.buckets.iter().enumerate() {
+            if file.is_some() {
+                fs::copy(self.dir.path.join(bucket.to_string()), clone.dir.path.join(bucket.to_string()))?;
+                let file = OpenOptions::new().read(true).append(true).open(clone.dir.path.join(bucket.to_string()))?;
+                clone.buckets[bucket] = Some(file);
+            }
+        }
+
+        clone.cache = self.cache.map(|(node, path)| (copy(node), path.clone()));
+        clone.records = self.records.clone();
+        clone.spilled = self.spilled;
+        Ok(clone)
+    }
+}
+
+/// Hashes the signatures of the register as picked by the `RegisterHasher` of the options
+#[derive(Debug, Clone)]
+pub(crate) enum RegisterState {
+    Random(RandomState),
+    Seeded(u64),
+}
+
+impl From<RegisterHasher> for RegisterState {
+    fn from(hasher: RegisterHasher) -> Self {
+        match hasher {
+            RegisterHasher::Random => RegisterState::Random(RandomState::new()),
+            RegisterHasher::Seeded(seed) => RegisterState::Seeded(seed),
+        }
+    }
+}
+
+impl BuildHasher for RegisterState {
+    type Hasher = DefaultHasher;
+
+    fn build_hasher(&self) -> DefaultHasher {
+        match self {
+            RegisterState::Random(state) => state.build_hasher(),
+            RegisterState::Seeded(seed) => {
+                let mut hasher = DefaultHasher::new();
+                hasher.write_u64(*seed);
+                hasher
+            }
+        }
+    }
+}
+
+/// The register of the nodes minimized so far, by signature: all in memory, unless a budget spills the older entries to disk
+pub(crate) struct Register<N: NodeRef> {
+    nodes: HashMap<Signature<N::Label>, N, RegisterState>,
+    spill: Option<Spill<N>>,
+    stats: RegisterStats,
+    /// first read or write of the spilled entries that failed, kept until the register is dropped
+    error: Option<io::Error>,
+}
+
+impl<N> Debug for Register<N> where N: NodeRef {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Register").field("len", &self.len()).field("budget", &self.spill.as_ref().map(|spill| spill.budget)).field("stats", &self.stats).finish()
+    }
+}
+
+impl<N> Default for Register<N> where N: NodeRef {
+    fn default() -> Self {
+        Self::with_hasher(RegisterHasher::default())
+    }
+}
+
+impl<N> Register<N> where N: NodeRef {
+    pub(crate) fn with_hasher(hasher: RegisterHasher) -> Self {
+        Self { nodes: HashMap::with_hasher(hasher.into()), spill: None, stats: RegisterStats::default(), error: None }
+    }
+
+    /// Counts `err`, keeping it if it is the first
+    fn fail(&mut self, err: io::Error) {
+        self.stats.io_errors += 1;
+        self.error.get_or_insert(err);
+    }
+
+    /// Fails with the first error reading or writing the spilled entries, if any: the entry is lost, so the dawg may not be
+    /// minimal
+    pub(crate) fn check(&self) -> Result<(), DawgError> {
+        match &self.error {
+            Some(err) => Err(DawgError::Io(io::Error::new(err.kind(), err.to_string()))),
+            None => Ok(()),
+        }
+    }
+
+    pub(crate) fn try_reserve(&mut self, additional: usize) -> Result<(), std::collections::TryReserveError> {
+        self.nodes.try_reserve(additional)
+    }
+
+    /// Number of entries, whether in memory or on disk
+    pub(crate) fn len(&self) -> usize {
+        match &self.spill {
+            Some(spill) => spill.cache.len() + spill.spilled,
+            None => self.nodes.len(),
+        }
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub(crate) fn stats(&self) -> RegisterStats {
+        self.stats
+    }
+
+    /// Returns the node registered with `signature`, reaching the spilled ones from `root`
+    pub(crate) fn get(&mut self, signature: &Signature<N::Label>, root: &N) -> Option<N> {
+        self.stats.lookups += 1;
+
+        let Some(spill) = &mut self.spill else {
+            let node = self.nodes.get(signature).cloned();
+            self.stats.cache_hits += node.is_some() as usize;
+            return node;
+        };
+
+        if let Some((node, _)) = spill.cache.get(signature) {
+            self.stats.cache_hits += 1;
+            return Some(node.clone());
+        }
+
+        let path = match spill.take(signature) {
+            Ok(path) => path?,
+            Err(err) => {
+                self.fail(err);
+                return None;
+            }
+        };
+        // the record is forgotten either way: brought back into memory, or lost if its path can't be followed
+        spill.spilled -= 1;
+
+        let mut labels = vec![];
+        let mut bytes = path.as_slice();
+        while !bytes.is_empty() {
+            labels.push((spill.decode)(next_part(&mut bytes).ok()?)?);
+        }
+
+        let node = labels.iter().try_fold(root.clone(), |node, label| node.child(label))?;
+        self.stats.disk_hits += 1;
+        spill.cache.insert(signature.clone(), (node.clone(), labels));
+        if let Err(err) = spill.evict(&mut self.stats) {
+            self.fail(err);
+        }
+        Some(node)
+    }
+
+    /// Registers `node`, whose `path` from the root is only asked for when spilling. Entries aren't spilled while `pinned`
+    pub(crate) fn insert(&mut self, signature: Signature<N::Label>, node: N, path: impl FnOnce() -> Vec<N::Label>, pinned: bool) {
+        self.stats.misses += 1;
+
+        match &mut self.spill {
+            Some(spill) => {
+                spill.cache.insert(signature, (node, path()));
+                let evicted = if pinned { Ok(()) } else { spill.evict(&mut self.stats) };
+                self.stats.peak_cached = self.stats.peak_cached.max(spill.cache.len());
+                if let Err(err) = evicted {
+                    self.fail(err);
+                }
+            }
+            None => {
+                self.nodes.insert(signature, node);
+                self.stats.peak_cached = self.stats.peak_cached.max(self.nodes.len());
+            }
+        }
+    }
+
+    /// Forgets `signature`, which was registered while entries were pinned so it is still in memory
+    pub(crate) fn remove(&mut self, signature: &Signature<N::Label>) {
+        match &mut self.spill {
+            Some(spill) => {
+                spill.cache.remove(signature);
+            }
+            None => {
+                self.nodes.remove(signature);
+            }
+        }
+    }
+
+    /// Drops every entry, keeping the budget, the statistics and the first error
+    pub(crate) fn release(&mut self) {
+        self.nodes = HashMap::with_hasher(self.nodes.hasher().clone());
+        if let Some(Err(err)) = self.spill.as_mut().map(Spill::release) {
+            self.fail(err);
+        }
+    }
+
+    /// Copy of the register whose nodes are copied with `copy`
+    pub(crate) fn deep_clone(&self, mut copy: impl FnMut(&N) -> N) -> Self {
+        let mut nodes = HashMap::with_capacity_and_hasher(self.nodes.len(), self.nodes.hasher().clone());
+        nodes.extend(self.nodes.iter().map(|(signature, node)| (signature.clone(), copy(node))));
+        let error = self.error.as_ref().map(|err| io::Error::new(err.kind(), err.to_string()));
+        let mut clone = Self { nodes, spill: None, stats: self.stats, error };
+
+        if let Some(spill) = &self.spill {
+            match spill.try_clone(&mut copy) {
+                Ok(spill) => clone.spill = Some(spill),
+                Err(err) => clone.fail(err),
+            }
+        }
+        clone
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: EncodeLabel {
+    /// Keeps at most `budget` entries of the minimization register in memory while building, moving the least recently used
+    /// ones to files in a directory of their own inside `temp_dir` (removed once the dawg is dropped).
+    ///
+    /// The register then costs `budget` entries of memory (and the offset of every spilled one) whatever the size of the
+    /// lexicon, at the price of reading a record from disk for every lookup missing the memory. The dawg built is the same as
+    /// without a budget, unless reading or writing the spilled entries fails: the first such error is then returned by
+    /// `try_add`, `try_add_text`, `add_sequence` and `try_finish`. While a checkpoint is active nothing is moved to disk, so the budget may
+    /// be exceeded. Must be set before any word is added
+    pub fn with_register_budget(mut self, budget: usize, temp_dir: impl AsRef<Path>) -> Result<Self, DawgError> {
+        if self.words_added > 0 || self.finished {
+            return Err(DawgError::InvalidOptions(OptionsConflict::LateRegisterBudget));
+        }
+
+        self.minimized_nodes.spill = Some(Spill::new(budget.max(1), temp_dir.as_ref(), L::encode, L::decode)?);
+        Ok(self)
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: crate::dawg::common::Label {
+    /// How the minimization register was used so far, to size the budget given to `with_register_budget`
+    pub fn register_stats(&self) -> RegisterStats {
+        self.minimized_nodes.stats()
+    }
+}
@@ -0,0 +1,64 @@
+use std::collections::HashSet;
+
+use crate::dawg::{common::{Dawg, Wrapper}, lexicon::Lexicon};
+
+/// Source of random numbers for the sampling methods, implement it to plug in the generator of your choice
+pub trait RandomSource {
+    /// Returns the next 64 random bits
+    fn next_u64(&mut self) -> u64;
+
+    /// Returns a number drawn uniformly from `0..bound` (`bound` must not be 0)
+    fn below(&mut self, bound: u64) -> u64 {
+        // rejects the values past the last multiple of bound so every result is equally likely
+        let zone = u64::MAX - u64::MAX % bound;
+        loop {
+            let value = self.next_u64();
+            if value < zone {
+                return value % bound;
+            }
+        }
+    }
+}
+
+/// Small seeded generator (SplitMix64), enough for reproducible samples
+#[derive(Debug, Clone)]
+pub struct SplitMix64 {
+    state: u64,
+}
+
+impl SplitMix64 {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+}
+
+impl RandomSource for SplitMix64 {
+    fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E3779B97F4A7C15);
+        let mut value = self.state;
+        value = (value ^ (value >> 30)).wrapping_mul(0xBF58476D1CE4E5B9);
+        value = (value ^ (value >> 27)).wrapping_mul(0x94D049BB133111EB);
+        value ^ (value >> 31)
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Draws `k` distinct words uniformly (every word if there are no more than `k`), returned in sorted order.
+    /// Only the chosen ranks are kept (Floyd's algorithm) and each is resolved with `select`,
+    /// so the cost is k times the length of a word instead of the size of the lexicon
+    pub fn sample_k<R: RandomSource>(&self, k: usize, rng: &mut R) -> Vec<String> {
+        let total = self.word_count();
+        let mut ranks = HashSet::with_capacity(k.min(total));
+
+        for upper in total.saturating_sub(k)..total {
+            let rank = rng.below(upper as u64 + 1) as usize;
+            if !ranks.insert(rank) {
+                ranks.insert(upper);
+            }
+        }
+
+        let mut ranks = ranks.into_iter().collect::<Vec<_>>();
+        ranks.sort_unstable();
+        ranks.into_iter().filter_map(|rank| self.select(rank)).collect()
+    }
+}
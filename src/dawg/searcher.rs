@@ -0,0 +1,185 @@
+use std::{cell::Cell, marker::PhantomData};
+
+use crate::dawg::{common::{Dawg, NodeOps, NodeRef, Wrapper}, lexicon::MatchMode};
+
+/// Edges of a node entered by the search of `completions`, copied out so the node needn't stay borrowed
+type Edges<N> = Vec<(char, N)>;
+
+/// Runs the queries of `Lexicon` against a dawg while keeping its working buffers between calls,
+/// so once warmed up, repeated queries don't allocate (results are written into buffers owned by the caller).
+///
+/// A searcher is cheap to create, but can't be shared between threads: create one per thread instead
+#[derive(Debug)]
+pub struct DawgSearcher<'a, T: Wrapper> {
+    dawg: &'a Dawg<T>,
+    /// nodes reached so far along with the spelling of the path taken to them
+    frontier: Vec<(T::Node, String)>,
+    next: Vec<(T::Node, String)>,
+    /// spellings no longer in use, recycled by the next step of the search
+    spare: Vec<String>,
+    /// (edges of a node, index of the next one to follow) pairs of the depth first search done by `completions`
+    stack: Vec<(Edges<T::Node>, usize)>,
+    /// edge lists no longer in use, recycled by the next node the search enters
+    spare_edges: Vec<Edges<T::Node>>,
+    word: String,
+    /// letters of the last query once folded and normalized
+    letters: Vec<char>,
+    not_sync: PhantomData<Cell<()>>,
+}
+
+impl<'a, T> DawgSearcher<'a, T> where T: Wrapper {
+    pub fn new(dawg: &'a Dawg<T>) -> Self {
+        Self {
+            dawg,
+            frontier: vec![],
+            next: vec![],
+            spare: vec![],
+            stack: vec![],
+            spare_edges: vec![],
+            word: String::new(),
+            letters: vec![],
+            not_sync: PhantomData,
+        }
+    }
+
+    /// Follows `word` from the root, leaving every node it leads to (and how it is spelt) in the frontier
+    fn find(&mut self, word: &str, mode: MatchMode) {
+        let mut root_spelling = self.spare.pop().unwrap_or_default();
+        root_spelling.clear();
+        self.spare.extend(self.frontier.drain(..).map(|(_, spelling)| spelling));
+        self.frontier.push((self.dawg.root.clone(), root_spelling));
+
+        let mut letters = std::mem::take(&mut self.letters);
+        self.dawg.options.fold_into(word.chars(), &mut letters);
+        for letter in &letters {
+            self.advance(*letter, mode);
+        }
+        self.letters = letters;
+    }
+
+    /// Edges of `node` in a list taken from the spare ones
+    fn edges(spare_edges: &mut Vec<Edges<T::Node>>, node: &T::Node) -> Edges<T::Node> {
+        let mut edges = spare_edges.pop().unwrap_or_default();
+        node.read(|node| edges.extend(node.edges.iter().map(|(label, child)| (*label, child.clone()))));
+        edges
+    }
+
+    /// Moves the frontier along the edges matching `letter`
+    fn advance(&mut self, letter: char, mode: MatchMode) {
+        let Self { frontier, next, spare, .. } = self;
+
+        for (node, spelling) in frontier.iter() {
+            let mut step = |label: char, child: &T::Node| {
+                let mut next_spelling = spare.pop().unwrap_or_default();
+                next_spelling.clear();
+                next_spelling.push_str(spelling);
+                next_spelling.push(label);
+                next.push((child.clone(), next_spelling));
+            };
+
+            match mode {
+                MatchMode::Exact => {
+                    if let Some(child) = node.child(&letter) {
+                        step(letter, &child);
+                    }
+                }
+                _ => node.read(|node| {
+                    for (label, child) in &node.edges {
+                        if mode.matches(*label, letter) {
+                            step(*label, child);
+                        }
+                    }
+                }),
+            }
+        }
+
+        spare.extend(frontier.drain(..).map(|(_, spelling)| spelling));
+        std::mem::swap(frontier, next);
+    }
+
+    /// Same as `Lexicon::contains`
+    pub fn contains(&mut self, word: &str, mode: MatchMode) -> bool {
+        self.find(word, mode);
+        self.frontier.iter().any(|(node, _)| node.is_terminal())
+    }
+
+    /// Same as `Lexicon::contains_prefix`
+    pub fn contains_prefix(&mut self, prefix: &str, mode: MatchMode) -> bool {
+        self.find(prefix, mode);
+        self.frontier.iter().any(|(node, _)| node.is_terminal() || node.read(|node| !node.edges.is_empty()))
+    }
+
+    /// Writes the spelling of the first word matching `word` (in sorted order) into `out`, value is false if there is none
+    pub fn get(&mut self, word: &str, mode: MatchMode, out: &mut String) -> bool {
+        self.find(word, mode);
+        out.clear();
+
+        match self.frontier.iter().find(|(node, _)| node.is_terminal()) {
+            Some((_, spelling)) => {
+                out.push_str(spelling);
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Same as `Lexicon::completions`, written into `out` whose previous content is replaced
+    pub fn completions(&mut self, prefix: &str, mode: MatchMode, out: &mut Vec<String>) {
+        self.find(prefix, mode);
+
+        let Self { frontier, stack, spare_edges, word, .. } = self;
+        let mut found = 0;
+        let mut emit = |word: &str| {
+            match out.get_mut(found) {
+                Some(slot) => {
+                    slot.clear();
+                    slot.push_str(word);
+                }
+                None => out.push(word.to_owned()),
+            }
+            found += 1;
+        };
+
+        for (node, spelling) in frontier.iter() {
+            word.clear();
+            word.push_str(spelling);
+            if node.is_terminal() {
+                emit(word);
+            }
+            stack.push((Self::edges(spare_edges, node), 0));
+
+            while let Some((edges, next_edge)) = stack.last_mut() {
+                match edges.get(*next_edge) {
+                    Some((label, child)) => {
+                        *next_edge += 1;
+                        let child = child.clone();
+                        word.push(*label);
+                        if child.is_terminal() {
+                            emit(word);
+                        }
+                        stack.push((Self::edges(spare_edges, &child), 0));
+                    }
+                    None => {
+                        let (mut edges, _) = stack.pop().unwrap();
+                        edges.clear();
+                        spare_edges.push(edges);
+                        if !stack.is_empty() {
+                            word.pop();
+                        }
+                    }
+                }
+            }
+        }
+
+        out.truncate(found);
+        out.sort_unstable();
+        out.dedup();
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Creates a searcher reusing its buffers across queries on this dawg
+    pub fn searcher(&self) -> DawgSearcher<'_, T> {
+        DawgSearcher::new(self)
+    }
+}
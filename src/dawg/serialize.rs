@@ -0,0 +1,707 @@
+use std::io::{Read, Write};
+
+use crate::{dawg::{common::{Dawg, Label, NodeOps, NodeRef, Wrapper}, integrity::{check, check_chunks, read_chunked_section, read_section, write_chunked_section, Checksums, Crc32, Section}, lexicon::{FoldProfile, MatchMode}, metadata::Metadata, options::{DawgOptions, DuplicatePolicy, Granularity, Normalization, RegisterHasher, SortOrder, ValidationPolicy}, register::Register, trace}, error::DawgError};
+
+/// Magic bytes every saved Dawg starts with
+pub(crate) const MAGIC: &[u8; 4] = b"DAWG";
+/// Version of the layout written by `save`. Version 1 put the children before their parents, which version 2 doesn't require,
+/// version 3 follows every section with its checksum, and version 4 with the checksums of its chunks of 1MiB so they can be
+/// checked in parallel (see `Dawg::load_parallel`)
+pub(crate) const VERSION: u16 = 4;
+
+/// Labels that can be written to and read back from a saved Dawg
+pub trait EncodeLabel: Label {
+    /// Appends the bytes of this label to `out`
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Reads a label back from exactly the bytes `encode` produced
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+impl EncodeLabel for char {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.encode_utf8(&mut [0; 4]).as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        let mut chars = std::str::from_utf8(bytes).ok()?.chars();
+        match (chars.next(), chars.next()) {
+            (Some(letter), None) => Some(letter),
+            _ => None,
+        }
+    }
+}
+
+impl EncodeLabel for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.extend_from_slice(self.as_bytes());
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        String::from_utf8(bytes.to_vec()).ok()
+    }
+}
+
+macro_rules! encode_int {
+    ($($int:ty),*) => {$(
+        impl EncodeLabel for $int {
+            fn encode(&self, out: &mut Vec<u8>) {
+                out.extend_from_slice(&self.to_le_bytes());
+            }
+
+            fn decode(bytes: &[u8]) -> Option<Self> {
+                Some(<$int>::from_le_bytes(bytes.try_into().ok()?))
+            }
+        }
+    )*};
+}
+
+encode_int!(u8, u16, u32, u64, i8, i16, i32, i64);
+
+/// Size in bytes of a node record: terminal flag, reachable count, first edge, number of edges
+pub(crate) const NODE_SIZE: usize = 1 + 8 + 4 + 4;
+/// Size in bytes of an edge record: child id, label offset and label length in the label section
+pub(crate) const EDGE_SIZE: usize = 4 + 4 + 4;
+
+/// A node of a saved Dawg as laid out in the node section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct NodeRecord {
+    pub(crate) terminal: bool,
+    pub(crate) count: u64,
+    pub(crate) first_edge: u32,
+    pub(crate) edges: u32,
+}
+
+/// An edge of a saved Dawg as laid out in the edge section
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct EdgeRecord {
+    pub(crate) child: u32,
+    pub(crate) label_start: u32,
+    pub(crate) label_len: u32,
+}
+
+impl DawgOptions {
+    pub(crate) fn encode(&self, out: &mut Vec<u8>) {
+        out.push(self.case_folding as u8);
+        out.push(match self.duplicate_policy {
+            DuplicatePolicy::Ignore => 0,
+            DuplicatePolicy::Error => 1,
+        });
+        write_u64(out, self.capacity as u64);
+
+        match &self.alphabet {
+            Some(alphabet) => {
+                let alphabet = alphabet.iter().collect::<String>();
+                out.push(1);
+                write_u32(out, alphabet.len() as u32);
+                out.extend_from_slice(alphabet.as_bytes());
+            }
+            None => out.push(0),
+        }
+
+        out.push(match self.match_mode {
+            MatchMode::Exact => 0,
+            MatchMode::AsciiInsensitive => 1,
+            MatchMode::UnicodeInsensitive(FoldProfile::Default) => 2,
+            MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents) => 3,
+        });
+
+        out.push(match self.sort_order {
+            SortOrder::Bytes => 0,
+            SortOrder::Chars => 1,
+            SortOrder::CaseInsensitiveBytes => 2,
+            SortOrder::Custom(_) => 3,
+        });
+
+        // left out when empty, unless the settings below follow it, so dawgs without metadata are saved as they were before
+        // it was added
+        let extended = (self.granularity, self.normalization, self.validation_policy, self.hasher) != Default::default();
+        if !self.metadata.is_empty() || extended {
+            self.metadata.encode(out);
+        }
+
+        if extended {
+            out.push(match self.granularity {
+                Granularity::Chars => 0,
+                Granularity::Bytes => 1,
+                Granularity::Graphemes => 2,
+            });
+            out.push(match self.normalization {
+                Normalization::None => 0,
+                Normalization::Nfc => 1,
+                Normalization::Nfd => 2,
+            });
+            out.push(match self.validation_policy {
+                ValidationPolicy::Reject => 0,
+                ValidationPolicy::Skip => 1,
+            });
+            match self.hasher {
+                RegisterHasher::Random => out.push(0),
+                RegisterHasher::Seeded(seed) => {
+                    out.push(1);
+                    write_u64(out, seed);
+                }
+            }
+        }
+    }
+
+    pub(crate) fn decode(bytes: &[u8]) -> Result<Self, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        let mut options = DawgOptions::new().case_folding(reader.u8()? == 1);
+
+        options.duplicate_policy = match reader.u8()? {
+            0 => DuplicatePolicy::Ignore,
+            1 => DuplicatePolicy::Error,
+            policy => return Err(DawgError::InvalidFormat(format!("unknown duplicate policy {policy}"))),
+        };
+        options.capacity = reader.u64()? as usize;
+
+        if reader.u8()? == 1 {
+            let len = reader.u32()? as usize;
+            let alphabet = std::str::from_utf8(reader.bytes(len)?).map_err(|_| DawgError::InvalidFormat(String::from("alphabet is not utf-8")))?;
+            options = options.alphabet(alphabet);
+        }
+
+        // files saved by the first version of the layout may end before the match mode and the sort order, added since
+        if reader.position == bytes.len() {
+            options.validate()?;
+            return Ok(options);
+        }
+
+        options.match_mode = match reader.u8()? {
+            0 => MatchMode::Exact,
+            1 => MatchMode::AsciiInsensitive,
+            2 => MatchMode::UnicodeInsensitive(FoldProfile::Default),
+            3 => MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+            mode => return Err(DawgError::InvalidFormat(format!("unknown match mode {mode}"))),
+        };
+
+        // a custom order can't be saved, the dawg falls back to the default one
+        options.sort_order = match reader.u8()? {
+            0 | 3 => SortOrder::Bytes,
+            1 => SortOrder::Chars,
+            2 => SortOrder::CaseInsensitiveBytes,
+            order => return Err(DawgError::InvalidFormat(format!("unknown sort order {order}"))),
+        };
+
+        if reader.position < bytes.len() {
+            options.metadata = Metadata::decode(&mut reader)?;
+        }
+
+        if reader.position < bytes.len() {
+            options.granularity = match reader.u8()? {
+                0 => Granularity::Chars,
+                1 => Granularity::Bytes,
+                2 => Granularity::Graphemes,
+                granularity => return Err(DawgError::InvalidFormat(format!("unknown granularity {granularity}"))),
+            };
+            options.normalization = match reader.u8()? {
+                0 => Normalization::None,
+                1 => Normalization::Nfc,
+                2 => Normalization::Nfd,
+                normalization => return Err(DawgError::InvalidFormat(format!("unknown normalization {normalization}"))),
+            };
+            options.validation_policy = match reader.u8()? {
+                0 => ValidationPolicy::Reject,
+                1 => ValidationPolicy::Skip,
+                policy => return Err(DawgError::InvalidFormat(format!("unknown validation policy {policy}"))),
+            };
+            options.hasher = match reader.u8()? {
+                0 => RegisterHasher::Random,
+                1 => RegisterHasher::Seeded(reader.u64()?),
+                hasher => return Err(DawgError::InvalidFormat(format!("unknown hasher {hasher}"))),
+            };
+        }
+
+        options.validate()?;
+        Ok(options)
+    }
+}
+
+pub(crate) fn write_u32(out: &mut Vec<u8>, value: u32) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+pub(crate) fn write_u64(out: &mut Vec<u8>, value: u64) {
+    out.extend_from_slice(&value.to_le_bytes());
+}
+
+/// Bounds checked little-endian reader over a byte slice
+pub(crate) struct Cursor<'a> {
+    bytes: &'a [u8],
+    pub(crate) position: usize,
+}
+
+impl<'a> Cursor<'a> {
+    pub(crate) fn new(bytes: &'a [u8]) -> Self {
+        Self { bytes, position: 0 }
+    }
+
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], DawgError> {
+        let end = self.position.checked_add(len).filter(|end| *end <= self.bytes.len())
+            .ok_or_else(|| DawgError::InvalidFormat(String::from("unexpected end of data")))?;
+        let bytes = &self.bytes[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    pub(crate) fn u8(&mut self) -> Result<u8, DawgError> {
+        Ok(self.bytes(1)?[0])
+    }
+
+    pub(crate) fn u16(&mut self) -> Result<u16, DawgError> {
+        Ok(u16::from_le_bytes(self.bytes(2)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u32(&mut self) -> Result<u32, DawgError> {
+        Ok(u32::from_le_bytes(self.bytes(4)?.try_into().unwrap()))
+    }
+
+    pub(crate) fn u64(&mut self) -> Result<u64, DawgError> {
+        Ok(u64::from_le_bytes(self.bytes(8)?.try_into().unwrap()))
+    }
+}
+
+/// Decodes what follows the version in the header of a saved Dawg, for one version of the layout
+type Decoder = for<'a> fn(&'a [u8], &mut Cursor<'a>) -> Result<SavedHeader, DawgError>;
+
+/// Decoders of every version of the layout `load` can read, keyed by the version in the header. Versions 1 and 2 are laid
+/// out alike (version 2 only stopped requiring the children before their parents, which the loader never relied on)
+const DECODERS: [(u16, Decoder); 4] = [(1, decode_unchecked), (2, decode_unchecked), (3, decode_checksummed), (4, decode_chunked)];
+
+fn decode_unchecked<'a>(bytes: &'a [u8], reader: &mut Cursor<'a>) -> Result<SavedHeader, DawgError> {
+    SavedHeader::decode(bytes, reader, Checksums::None)
+}
+
+fn decode_checksummed<'a>(bytes: &'a [u8], reader: &mut Cursor<'a>) -> Result<SavedHeader, DawgError> {
+    SavedHeader::decode(bytes, reader, Checksums::Section)
+}
+
+fn decode_chunked<'a>(bytes: &'a [u8], reader: &mut Cursor<'a>) -> Result<SavedHeader, DawgError> {
+    SavedHeader::decode(bytes, reader, Checksums::Chunks)
+}
+
+/// A section of a saved Dawg, with the checksums of its chunks if the layout has them
+pub(crate) type RawSection<'a> = (Section, &'a [u8], &'a [u8]);
+
+/// The header of a saved Dawg: its options and the size of the sections following it
+#[derive(Debug)]
+pub(crate) struct SavedHeader {
+    pub(crate) options: DawgOptions,
+    pub(crate) node_count: u32,
+    pub(crate) edge_count: u32,
+    pub(crate) label_len: u32,
+    pub(crate) root: u32,
+    /// how the sections following the header are checked
+    pub(crate) checksums: Checksums,
+}
+
+impl SavedHeader {
+    /// Parses and validates the header at the start of `bytes`, returning it along with a reader of the first section.
+    /// `bytes` only need to hold the header, the sections are left to the caller
+    pub(crate) fn parse(bytes: &[u8]) -> Result<(Self, Cursor<'_>), DawgError> {
+        let mut reader = Cursor::new(bytes);
+        let truncated = |_| DawgError::Truncated { section: Section::Header };
+
+        if reader.bytes(4).map_err(truncated)? != MAGIC {
+            return Err(DawgError::InvalidFormat(String::from("missing DAWG magic bytes")));
+        }
+
+        let version = reader.u16().map_err(truncated)?;
+        if version > VERSION {
+            return Err(DawgError::NewerVersion { version, supported: VERSION });
+        }
+
+        let header = match DECODERS.iter().find(|(decodes, _)| *decodes == version) {
+            Some((_, decode)) => decode(bytes, &mut reader)?,
+            None => return Err(DawgError::InvalidFormat(format!("unsupported version {version}"))),
+        };
+        Ok((header, reader))
+    }
+
+    /// Reads and validates the header at the start of `reader` without reading any further, returning it along with its
+    /// length in bytes. The header of older layouts lacking a checksum is read along with the first bytes of the nodes
+    pub(crate) fn read(reader: &mut impl Read) -> Result<(Self, u64), DawgError> {
+        let mut bytes = vec![0; 4 + 2 + 4];
+        reader.read_exact(&mut bytes).map_err(|err| match err.kind() {
+            std::io::ErrorKind::UnexpectedEof => DawgError::Truncated { section: Section::Header },
+            _ => DawgError::Io(err),
+        })?;
+
+        // the options, the size of every section, the root and the checksum
+        let options_len = u32::from_le_bytes(bytes[6..].try_into().unwrap()) as u64;
+        reader.take(options_len + 4 * 4 + 4).read_to_end(&mut bytes)?;
+        let (header, cursor) = Self::parse(&bytes)?;
+        Ok((header, cursor.position as u64))
+    }
+
+    /// Decodes the header following the version, checking it against the checksum following it unless the layout has none
+    fn decode<'a>(bytes: &'a [u8], reader: &mut Cursor<'a>, checksums: Checksums) -> Result<Self, DawgError> {
+        let truncated = |_| DawgError::Truncated { section: Section::Header };
+        let options_len = reader.u32().map_err(truncated)? as usize;
+        let options = reader.bytes(options_len).map_err(truncated)?;
+        let mut counts = Cursor::new(reader.bytes(4 * 4).map_err(truncated)?);
+        let (node_count, edge_count, label_len, root) = (counts.u32()?, counts.u32()?, counts.u32()?, counts.u32()?);
+
+        if checksums != Checksums::None {
+            let header = &bytes[..reader.position];
+            check(Section::Header, reader.u32().map_err(truncated)?, Crc32::of(header))?;
+        }
+
+        let options = DawgOptions::decode(options)?;
+        if root >= node_count {
+            return Err(DawgError::InvalidFormat(String::from("the root is out of bounds")));
+        }
+        Ok(Self { options, node_count, edge_count, label_len, root, checksums })
+    }
+
+    /// Reads the node, edge and label sections following the header, each along with the checksums of its chunks (left to
+    /// `integrity::check_chunks`). Sections of the layouts checksummed as a whole are checked as they are read
+    pub(crate) fn read_sections<'a>(&self, reader: &mut Cursor<'a>) -> Result<[RawSection<'a>; 3], DawgError> {
+        let lens = [self.node_count as usize * NODE_SIZE, self.edge_count as usize * EDGE_SIZE, self.label_len as usize];
+        let mut sections = [(Section::Nodes, &[][..], &[][..]), (Section::Edges, &[], &[]), (Section::Labels, &[], &[])];
+
+        for ((section, bytes, checksums), len) in sections.iter_mut().zip(lens) {
+            (*bytes, *checksums) = match self.checksums {
+                Checksums::Chunks => read_chunked_section(reader, *section, len)?,
+                checksums => (read_section(reader, *section, len, checksums == Checksums::Section)?, &[][..]),
+            };
+        }
+        Ok(sections)
+    }
+
+    /// Decodes the node records of `bytes`, the first one being the node of `first`
+    pub(crate) fn nodes(&self, first: u32, bytes: &[u8]) -> Result<Vec<NodeRecord>, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        (first..first + (bytes.len() / NODE_SIZE) as u32).map(|id| self.node(id, &mut reader)).collect()
+    }
+
+    /// Decodes the edge records of `bytes`, the first one being the edge of `first`
+    pub(crate) fn edges(&self, first: u32, bytes: &[u8]) -> Result<Vec<EdgeRecord>, DawgError> {
+        let mut reader = Cursor::new(bytes);
+        (first..first + (bytes.len() / EDGE_SIZE) as u32).map(|id| self.edge(id, &mut reader)).collect()
+    }
+
+    /// Decodes a node record from its bytes, checking its edges are within the edge section
+    pub(crate) fn node(&self, id: u32, record: &mut Cursor<'_>) -> Result<NodeRecord, DawgError> {
+        let node = NodeRecord { terminal: record.u8()? == 1, count: record.u64()?, first_edge: record.u32()?, edges: record.u32()? };
+        if node.first_edge as u64 + node.edges as u64 > self.edge_count as u64 {
+            return Err(DawgError::InvalidFormat(format!("node {id} has edges out of bounds")));
+        }
+        Ok(node)
+    }
+
+    /// Decodes an edge record from its bytes, checking it points at an existing node and its label is within the label section
+    pub(crate) fn edge(&self, id: u32, record: &mut Cursor<'_>) -> Result<EdgeRecord, DawgError> {
+        let edge = EdgeRecord { child: record.u32()?, label_start: record.u32()?, label_len: record.u32()? };
+        if edge.label_start as u64 + edge.label_len as u64 > self.label_len as u64 {
+            return Err(DawgError::InvalidFormat(format!("edge {id} has a label out of bounds")));
+        }
+        if edge.child >= self.node_count {
+            return Err(DawgError::InvalidFormat(format!("edge {id} points at a node out of bounds")));
+        }
+        Ok(edge)
+    }
+}
+
+/// The sections of a saved Dawg, decoded but not yet turned into nodes
+#[derive(Debug)]
+pub(crate) struct Layout<'a> {
+    pub(crate) options: DawgOptions,
+    pub(crate) root: u32,
+    pub(crate) nodes: Vec<NodeRecord>,
+    pub(crate) edges: Vec<EdgeRecord>,
+    pub(crate) labels: &'a [u8],
+    /// number of nodes the trie of the saved words would have
+    pub(crate) prefixes: usize,
+}
+
+impl<'a> Layout<'a> {
+    /// Parses and validates the bytes of a saved Dawg: every section must match its checksum, the edges must point at existing
+    /// nodes without forming a cycle, every label must be in bounds and every word count right
+    pub(crate) fn parse(bytes: &'a [u8]) -> Result<Self, DawgError> {
+        let (header, mut reader) = SavedHeader::parse(bytes)?;
+        let sections = header.read_sections(&mut reader)?;
+        for (section, bytes, checksums) in sections {
+            check_chunks(section, bytes, checksums)?;
+        }
+
+        let [(_, nodes, _), (_, edges, _), (_, labels, _)] = sections;
+        let (nodes, edges) = (header.nodes(0, nodes)?, header.edges(0, edges)?);
+        Self::new(header, nodes, edges, labels)
+    }
+
+    /// Puts the decoded sections together, failing if the edges form a cycle or a word count is wrong
+    pub(crate) fn new(header: SavedHeader, nodes: Vec<NodeRecord>, edges: Vec<EdgeRecord>, labels: &'a [u8]) -> Result<Self, DawgError> {
+        let edges_of = |id: usize| edges[nodes[id].first_edge as usize..(nodes[id].first_edge + nodes[id].edges) as usize].iter().map(|edge| edge.child);
+        let topological = topological_order(nodes.len(), edges_of)?;
+        check_counts(&nodes, &edges, &topological)?;
+        let prefixes = count_prefixes(&nodes, &edges, &topological, header.root)?;
+        Ok(Self { options: header.options, root: header.root, nodes, edges, labels, prefixes })
+    }
+
+    /// Encodes the saved dawg again in the latest version of the layout, the way `Dawg::to_bytes` would save it once loaded:
+    /// nodes numbered breadth first from the root (the edges being saved in label order), unreachable ones left out
+    pub(crate) fn to_bytes(&self) -> Vec<u8> {
+        let mut ids = vec![None; self.nodes.len()];
+        ids[self.root as usize] = Some(0);
+        let mut order = vec![self.root];
+        let mut next = 0;
+
+        while let Some(id) = order.get(next).copied() {
+            next += 1;
+            for edge in self.edges_of(&self.nodes[id as usize]) {
+                if ids[edge.child as usize].is_none() {
+                    ids[edge.child as usize] = Some(order.len() as u32);
+                    order.push(edge.child);
+                }
+            }
+        }
+
+        let (mut nodes, mut edges, mut labels) = (vec![], vec![], vec![]);
+        for id in order {
+            let record = &self.nodes[id as usize];
+            nodes.push(record.terminal as u8);
+            write_u64(&mut nodes, record.count);
+            write_u32(&mut nodes, (edges.len() / EDGE_SIZE) as u32);
+            write_u32(&mut nodes, record.edges);
+
+            for edge in self.edges_of(record) {
+                let start = edge.label_start as usize;
+                write_u32(&mut edges, ids[edge.child as usize].unwrap());
+                write_u32(&mut edges, labels.len() as u32);
+                write_u32(&mut edges, edge.label_len);
+                labels.extend_from_slice(&self.labels[start..start + edge.label_len as usize]);
+            }
+        }
+
+        encode(&self.options, &nodes, &edges, &labels)
+    }
+
+    /// the edges leaving `node`
+    pub(crate) fn edges_of(&self, node: &NodeRecord) -> &[EdgeRecord] {
+        &self.edges[node.first_edge as usize..(node.first_edge + node.edges) as usize]
+    }
+
+    pub(crate) fn label<L: EncodeLabel>(&self, edge: &EdgeRecord) -> Result<L, DawgError> {
+        let start = edge.label_start as usize;
+        L::decode(&self.labels[start..start + edge.label_len as usize]).ok_or_else(|| DawgError::InvalidFormat(String::from("label can't be decoded")))
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: EncodeLabel {
+    /// Encodes the finished dawg. Nodes are written in the order of `visit_nodes_bfs`, so a dawg is always saved the same way.
+    /// Fails with `DawgError::NotFinished` if the dawg isn't finished
+    pub fn to_bytes(&self) -> Result<Vec<u8>, DawgError> {
+        self.encode_with(&self.options)
+    }
+
+    /// Encodes the finished dawg along with `options` in place of its own
+    pub(crate) fn encode_with(&self, options: &DawgOptions) -> Result<Vec<u8>, DawgError> {
+        if !self.finished {
+            return Err(DawgError::NotFinished);
+        }
+
+        let mut nodes = vec![];
+        let mut edges = vec![];
+        let mut labels = vec![];
+
+        for view in self.visit_nodes_bfs() {
+            let first_edge = edges.len() as u32 / EDGE_SIZE as u32;
+            let mut edge_count = 0;
+
+            for (label, child) in view.edges() {
+                let label_start = labels.len() as u32;
+                label.encode(&mut labels);
+                write_u32(&mut edges, child as u32);
+                write_u32(&mut edges, label_start);
+                write_u32(&mut edges, labels.len() as u32 - label_start);
+                edge_count += 1;
+            }
+
+            nodes.push(view.is_terminal() as u8);
+            write_u64(&mut nodes, view.count() as u64);
+            write_u32(&mut nodes, first_edge);
+            write_u32(&mut nodes, edge_count);
+        }
+
+        Ok(encode(options, &nodes, &edges, &labels))
+    }
+
+    /// Writes the dawg to `writer`, see `to_bytes`
+    pub fn save<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        let span = trace::timed!("save", bytes);
+        let bytes = self.to_bytes()?;
+        span.record("bytes", bytes.len());
+        writer.write_all(&bytes)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Rebuilds a finished dawg from the bytes written by `save`, its nodes keep the ids they had when it was saved.
+    /// Files saved in earlier versions of the layout are read as well (`migrate::migrate_file` rewrites them in the latest one)
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, DawgError> {
+        let span = trace::timed!("load", bytes, nodes);
+        span.record("bytes", bytes.len());
+        let layout = Layout::parse(bytes)?;
+        span.record("nodes", layout.nodes.len());
+        let (mut dawg, created) = Self::create_nodes(&layout);
+
+        for (node, record) in created.iter().zip(&layout.nodes) {
+            link(&layout, &created, node, record)?;
+        }
+
+        dawg.finish_loading(&layout);
+        Ok(dawg)
+    }
+
+    /// Creates an empty dawg with the options of `layout` and a node for every saved one (their edges being left to `link`)
+    pub(crate) fn create_nodes(layout: &Layout) -> (Self, Vec<T::Node>) {
+        // the options were validated when decoded, and a finished dawg has no register to reserve the capacity for
+        let mut dawg = Self::with_wrapper(T::new());
+        dawg.options = layout.options.clone();
+        dawg.minimized_nodes = Register::with_hasher(dawg.options.hasher);
+        let mut created = Vec::with_capacity(layout.nodes.len());
+
+        for (id, record) in layout.nodes.iter().enumerate() {
+            // the root created along with the dawg becomes the saved root
+            let node = match id as u32 == layout.root {
+                true => dawg.root.clone(),
+                false => dawg.node.create(),
+            };
+
+            node.set_terminal(record.terminal);
+            node.write(|node| node.count = record.count as usize);
+            created.push(node);
+        }
+
+        (dawg, created)
+    }
+
+    /// Marks the dawg whose nodes were all linked as finished, with the ids it would have been saved with
+    pub(crate) fn finish_loading(&mut self, layout: &Layout) {
+        self.trie_nodes = layout.prefixes;
+        self.previous_word = self.sequences().next_back().unwrap_or_default();
+        self.words_added = self.root.count();
+        self.finished = true;
+        self.canonicalize_ids();
+    }
+
+    /// Reads a dawg written by `save` from `reader`
+    pub fn load<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes(&bytes)
+    }
+}
+
+/// Adds the saved edges of `record` to `node`, among the `created` nodes, failing unless their labels strictly increase
+pub(crate) fn link<N: NodeRef>(layout: &Layout, created: &[N], node: &N, record: &NodeRecord) -> Result<(), DawgError> where N::Label: EncodeLabel {
+    let mut previous = None;
+    for edge in layout.edges_of(record) {
+        let label = layout.label::<N::Label>(edge)?;
+        if previous.as_ref().is_some_and(|previous| *previous >= label) {
+            return Err(DawgError::InvalidFormat(format!("the labels of node {} aren't sorted", node.id())));
+        }
+        node.insert_edge(label.clone(), created[edge.child as usize].clone());
+        previous = Some(label);
+    }
+    Ok(())
+}
+
+/// Lays out a saved Dawg in the latest version of the layout, from its encoded sections
+fn encode(options: &DawgOptions, nodes: &[u8], edges: &[u8], labels: &[u8]) -> Vec<u8> {
+    let mut out = encode_header(options, (nodes.len() / NODE_SIZE) as u32, (edges.len() / EDGE_SIZE) as u32, labels.len() as u32, 0);
+    for section in [nodes, edges, labels] {
+        write_chunked_section(&mut out, section);
+    }
+    out
+}
+
+/// Encodes the header of a saved Dawg in the latest version of the layout, up to its checksum. Its length only depends on
+/// the options
+pub(crate) fn encode_header(options: &DawgOptions, node_count: u32, edge_count: u32, label_len: u32, root: u32) -> Vec<u8> {
+    let mut encoded_options = vec![];
+    options.encode(&mut encoded_options);
+
+    let mut out = vec![];
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    write_u32(&mut out, encoded_options.len() as u32);
+    out.extend_from_slice(&encoded_options);
+    for value in [node_count, edge_count, label_len, root] {
+        write_u32(&mut out, value);
+    }
+    let header = Crc32::of(&out);
+    write_u32(&mut out, header);
+    out
+}
+
+/// Checks the saved word count of every node against the words below it, counted children first along `topological`
+pub(crate) fn check_counts(nodes: &[NodeRecord], edges: &[EdgeRecord], topological: &[u32]) -> Result<(), DawgError> {
+    let children_of = |id: usize| {
+        let node = &nodes[id];
+        edges[node.first_edge as usize..(node.first_edge + node.edges) as usize].iter().map(|edge| edge.child)
+    };
+    check_word_counts(topological, |id| (nodes[id].terminal, nodes[id].count), children_of)
+}
+
+/// `check_counts` over any node layout: `node` gives whether a node is terminal and the count saved with it, `children`
+/// the nodes its edges lead to
+pub(crate) fn check_word_counts<I: IntoIterator<Item = u32>>(topological: &[u32], node: impl Fn(usize) -> (bool, u64), children: impl Fn(usize) -> I) -> Result<(), DawgError> {
+    let mut words = vec![0u64; topological.len()];
+    for id in topological.iter().rev() {
+        let (terminal, stored) = node(*id as usize);
+        let actual = children(*id as usize).into_iter().fold(terminal as u64, |sum, child| sum.saturating_add(words[child as usize]));
+
+        if actual != stored {
+            return Err(DawgError::CorruptedCount { node: *id, stored, actual });
+        }
+        words[*id as usize] = actual;
+    }
+    Ok(())
+}
+
+/// Number of nodes the trie of the saved words would have: one per distinct path from `root`, counted parents first along
+/// `topological`. A few nodes with several edges to the same child make more paths than a `usize` can count, which no
+/// saved dawg can have
+pub(crate) fn count_prefixes(nodes: &[NodeRecord], edges: &[EdgeRecord], topological: &[u32], root: u32) -> Result<usize, DawgError> {
+    let too_many = || DawgError::InvalidFormat(String::from("more prefixes than can be counted"));
+    let mut paths = vec![0usize; nodes.len()];
+    paths[root as usize] = 1;
+
+    for id in topological {
+        let node = &nodes[*id as usize];
+        for edge in &edges[node.first_edge as usize..(node.first_edge + node.edges) as usize] {
+            paths[edge.child as usize] = paths[edge.child as usize].checked_add(paths[*id as usize]).ok_or_else(too_many)?;
+        }
+    }
+
+    paths.iter().try_fold(0usize, |sum, paths| sum.checked_add(*paths)).ok_or_else(too_many)
+}
+
+/// Orders the nodes `0..node_count` so every node comes before its children (Kahn's algorithm), failing if the edges form a cycle
+pub(crate) fn topological_order<I: IntoIterator<Item = u32>>(node_count: usize, children: impl Fn(usize) -> I) -> Result<Vec<u32>, DawgError> {
+    let mut parents = vec![0usize; node_count];
+    for id in 0..node_count {
+        for child in children(id) {
+            parents[child as usize] += 1;
+        }
+    }
+
+    let mut order = (0..node_count as u32).filter(|id| parents[*id as usize] == 0).collect::<Vec<_>>();
+    let mut next = 0;
+    while let Some(id) = order.get(next).copied() {
+        next += 1;
+        for child in children(id as usize) {
+            parents[child as usize] -= 1;
+            if parents[child as usize] == 0 {
+                order.push(child);
+            }
+        }
+    }
+
+    match order.len() == node_count {
+        true => Ok(order),
+        false => Err(DawgError::InvalidFormat(String::from("the edges form a cycle"))),
+    }
+}
@@ -0,0 +1,184 @@
+use std::{cmp::{Ordering, Reverse}, collections::BinaryHeap, iter::Peekable};
+
+use crate::{dawg::{common::{Dawg, Label, Wrapper}, iter::{Sequences, Words}}, error::DawgError};
+
+/// Which items of the two inputs a `Merge` yields
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SetOperation {
+    /// items of either input
+    Union,
+    /// items of both inputs
+    Intersection,
+    /// items of the left input missing from the right one
+    Difference,
+}
+
+/// Lazy set operation over two iterators yielding distinct items in sorted order, as `Dawg::words` and `Dawg::sequences` do.
+/// The result is sorted as well, so it can be added to a new dawg as it is yielded
+pub struct Merge<A: Iterator, B: Iterator<Item = A::Item>> {
+    left: Peekable<A>,
+    right: Peekable<B>,
+    operation: SetOperation,
+}
+
+impl<A, B> Merge<A, B> where A: Iterator, B: Iterator<Item = A::Item>, A::Item: Ord {
+    pub fn new(left: A, right: B, operation: SetOperation) -> Self {
+        Self { left: left.peekable(), right: right.peekable(), operation }
+    }
+}
+
+impl<A, B> Iterator for Merge<A, B> where A: Iterator, B: Iterator<Item = A::Item>, A::Item: Ord {
+    type Item = A::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let order = match (self.left.peek(), self.right.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => Ordering::Less,
+                (None, Some(_)) => Ordering::Greater,
+                (Some(left), Some(right)) => left.cmp(right),
+            };
+
+            match (order, self.operation) {
+                (Ordering::Less, SetOperation::Union | SetOperation::Difference) => return self.left.next(),
+                (Ordering::Less, SetOperation::Intersection) if self.right.peek().is_none() => return None,
+                (Ordering::Less, _) => { self.left.next(); }
+                (Ordering::Greater, SetOperation::Union) => return self.right.next(),
+                (Ordering::Greater, _) if self.left.peek().is_none() => return None,
+                (Ordering::Greater, _) => { self.right.next(); }
+                (Ordering::Equal, SetOperation::Difference) => {
+                    self.left.next();
+                    self.right.next();
+                }
+                (Ordering::Equal, _) => {
+                    self.right.next();
+                    return self.left.next();
+                }
+            }
+        }
+    }
+}
+
+/// Lazy union of any number of iterators yielding distinct items in sorted order, each item is yielded once in sorted order.
+/// Built by `with_payloads`, the inputs yield (key, payload) pairs in the order of their keys instead, and `next_resolved`
+/// gives each key once along with the payloads of the inputs having it
+pub struct KMerge<I: Iterator, K = <I as Iterator>::Item, P = ()> {
+    inputs: Vec<I>,
+    /// splits an item of the inputs into its key and its payload
+    split: fn(I::Item) -> (K, P),
+    /// the next key of every input that isn't exhausted, along with the index of that input
+    heap: BinaryHeap<Reverse<(K, usize)>>,
+    /// the payload going with the key of every input in the heap
+    payloads: Vec<Option<P>>,
+}
+
+impl<I> KMerge<I> where I: Iterator, I::Item: Ord {
+    pub fn new(inputs: impl IntoIterator<Item = I>) -> Self {
+        Self::from_parts(inputs, |item| (item, ()))
+    }
+}
+
+impl<I, K, P> KMerge<I, K, P> where I: Iterator<Item = (K, P)>, K: Ord {
+    pub fn with_payloads(inputs: impl IntoIterator<Item = I>) -> Self {
+        Self::from_parts(inputs, |item| item)
+    }
+}
+
+impl<I, K, P> KMerge<I, K, P> where I: Iterator, K: Ord {
+    fn from_parts(inputs: impl IntoIterator<Item = I>, split: fn(I::Item) -> (K, P)) -> Self {
+        let inputs = inputs.into_iter().collect::<Vec<_>>();
+        let payloads = inputs.iter().map(|_| None).collect();
+        let mut merge = Self { inputs, split, heap: BinaryHeap::new(), payloads };
+        for index in 0..merge.inputs.len() {
+            merge.advance(index);
+        }
+        merge
+    }
+
+    fn advance(&mut self, index: usize) {
+        if let Some(item) = self.inputs[index].next() {
+            let (key, payload) = (self.split)(item);
+            self.payloads[index] = Some(payload);
+            self.heap.push(Reverse((key, index)));
+        }
+    }
+
+    /// The next key, with the payloads of the inputs having it folded by `resolve` in the order of the inputs (so `resolve`
+    /// only runs for a key found in several inputs)
+    pub fn next_resolved(&mut self, mut resolve: impl FnMut(P, P) -> P) -> Option<(K, P)> {
+        let Reverse((key, index)) = self.heap.pop()?;
+        let mut payload = self.payloads[index].take().expect("every input in the heap has its next payload");
+        self.advance(index);
+
+        while let Some(Reverse((_, duplicate))) = self.heap.peek().filter(|Reverse((next, _))| *next == key) {
+            let duplicate = *duplicate;
+            self.heap.pop();
+            payload = resolve(payload, self.payloads[duplicate].take().expect("every input in the heap has its next payload"));
+            self.advance(duplicate);
+        }
+
+        Some((key, payload))
+    }
+}
+
+impl<I> Iterator for KMerge<I> where I: Iterator, I::Item: Ord {
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_resolved(|(), ()| ()).map(|(item, ())| item)
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Builds one dawg holding the sequences of every dawg of `dawgs`, with the options of the first one (the default options
+    /// if there is none). Fails if the others store their words differently (see `DawgOptions::check_mergeable`).
+    /// Their sorted sequences are merged through a heap, so each sequence is added once, straight into the new dawg
+    pub fn merge_all(dawgs: &[&Self]) -> Result<Self, DawgError> {
+        let mut merged = match dawgs.first() {
+            Some(first) => Self::with_options(first.options.clone())?,
+            None => Self::with_wrapper(T::new()),
+        };
+        for dawg in dawgs.iter().skip(1) {
+            merged.options.check_mergeable(&dawg.options)?;
+        }
+
+        for sequence in KMerge::new(dawgs.iter().map(|dawg| dawg.sequences())) {
+            merged.push_sequence(&sequence);
+        }
+
+        merged.finish();
+        Ok(merged)
+    }
+
+    /// Returns the sequences of either dawg in sorted order, without building a new dawg
+    pub fn union_sequences<'a, U: Wrapper<L>>(&'a self, other: &'a Dawg<U, L>) -> Merge<Sequences<'a, T::Node>, Sequences<'a, U::Node>> {
+        Merge::new(self.sequences(), other.sequences(), SetOperation::Union)
+    }
+
+    /// Returns the sequences of both dawgs in sorted order, without building a new dawg
+    pub fn intersection_sequences<'a, U: Wrapper<L>>(&'a self, other: &'a Dawg<U, L>) -> Merge<Sequences<'a, T::Node>, Sequences<'a, U::Node>> {
+        Merge::new(self.sequences(), other.sequences(), SetOperation::Intersection)
+    }
+
+    /// Returns the sequences of this dawg missing from `other` in sorted order, without building a new dawg
+    pub fn difference_sequences<'a, U: Wrapper<L>>(&'a self, other: &'a Dawg<U, L>) -> Merge<Sequences<'a, T::Node>, Sequences<'a, U::Node>> {
+        Merge::new(self.sequences(), other.sequences(), SetOperation::Difference)
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the words of either dawg in sorted order, without building a new dawg
+    pub fn union_words<'a, U: Wrapper>(&'a self, other: &'a Dawg<U>) -> Merge<Words<'a, T::Node>, Words<'a, U::Node>> {
+        Merge::new(self.words(), other.words(), SetOperation::Union)
+    }
+
+    /// Returns the words of both dawgs in sorted order, without building a new dawg
+    pub fn intersection_words<'a, U: Wrapper>(&'a self, other: &'a Dawg<U>) -> Merge<Words<'a, T::Node>, Words<'a, U::Node>> {
+        Merge::new(self.words(), other.words(), SetOperation::Intersection)
+    }
+
+    /// Returns the words of this dawg missing from `other` in sorted order, without building a new dawg
+    pub fn difference_words<'a, U: Wrapper>(&'a self, other: &'a Dawg<U>) -> Merge<Words<'a, T::Node>, Words<'a, U::Node>> {
+        Merge::new(self.words(), other.words(), SetOperation::Difference)
+    }
+}
@@ -0,0 +1,223 @@
+//! Spell checking a whole document: `Dawg::check_text` splits the text into words and yields those missing from the dawg.
+//!
+//! The default boundaries are the word boundaries of Unicode (UAX #29), from `unicode-segmentation`. A word keeps its
+//! combining marks and viramas (`नमस्ते`), an apostrophe or a period between two letters (`don't`, `e.g`) and a period or
+//! a comma between two digits (`3.14`, `1,000`). A run of Katakana is one word, while every Han ideograph and Hiragana
+//! letter is a word of its own. Scripts written without spaces, such as Thai, need a dictionary to be split into words,
+//! which the rules leave out: they come one grapheme cluster at a time. Emoji, including their ZWJ sequences, and
+//! punctuation are never words. Offsets are byte indices into the text, so `&text[miss.start..miss.end]` is always the
+//! token
+
+use std::{fmt::Debug, ops::Range};
+
+use unicode_segmentation::UnicodeWordIndices;
+
+use crate::dawg::{common::{Dawg, Wrapper}, lexicon::{Lexicon, MatchMode}, unicode};
+
+/// How `Dawg::check_text` splits the text into words
+#[derive(Debug, Clone, Copy, Default)]
+pub enum Boundaries {
+    /// the word boundaries of Unicode, see the `spell` module
+    #[default]
+    Unicode,
+    /// runs of alphabetic letters, anything else ends a word
+    Alphabetic,
+    /// runs of the letters for which the function returns true
+    Custom(fn(char) -> bool),
+}
+
+/// Settings of `Dawg::check_text`
+#[derive(Debug, Clone)]
+pub struct CheckOptions {
+    boundaries: Boundaries,
+    match_mode: Option<MatchMode>,
+    skip_numbers: bool,
+    skip_all_caps: bool,
+    skip_urls: bool,
+    skip_when: Vec<fn(&str) -> bool>,
+    suggestions: usize,
+    max_distance: u8,
+}
+
+impl Default for CheckOptions {
+    /// Unicode boundaries, the match mode of the dawg, numbers, all-caps words and URLs skipped, no suggestions
+    fn default() -> Self {
+        Self {
+            boundaries: Boundaries::Unicode,
+            match_mode: None,
+            skip_numbers: true,
+            skip_all_caps: true,
+            skip_urls: true,
+            skip_when: vec![],
+            suggestions: 0,
+            max_distance: 2,
+        }
+    }
+}
+
+impl CheckOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn boundaries(mut self, boundaries: Boundaries) -> Self {
+        self.boundaries = boundaries;
+        self
+    }
+
+    /// How the words are looked up, the match mode of the options of the dawg unless set
+    pub fn match_mode(mut self, mode: MatchMode) -> Self {
+        self.match_mode = Some(mode);
+        self
+    }
+
+    /// Whether words holding a digit (`42`, `3.14`, `mp3`) are left out
+    pub fn skip_numbers(mut self, skip: bool) -> Self {
+        self.skip_numbers = skip;
+        self
+    }
+
+    /// Whether words of two letters or more, all of them upper-case (`NASA`, `HTTP`), are left out
+    pub fn skip_all_caps(mut self, skip: bool) -> Self {
+        self.skip_all_caps = skip;
+        self
+    }
+
+    /// Whether the words of URLs and e-mail addresses are left out: those of every run of text between two spaces holding
+    /// `://`, or starting with `www.` or `mailto:`, or with an `@` followed by a period
+    pub fn skip_urls(mut self, skip: bool) -> Self {
+        self.skip_urls = skip;
+        self
+    }
+
+    /// Leaves out the words for which `skip` returns true, on top of the other rules
+    pub fn skip_when(mut self, skip: fn(&str) -> bool) -> Self {
+        self.skip_when.push(skip);
+        self
+    }
+
+    /// Fills `Miss::suggestions` with up to `limit` words within `max_distance` edits of the token (see
+    /// `Dawg::fuzzy_search`), closest first. 0, the default, leaves them empty
+    pub fn suggestions(mut self, limit: usize, max_distance: u8) -> Self {
+        self.suggestions = limit;
+        self.max_distance = max_distance;
+        self
+    }
+
+    pub fn get_boundaries(&self) -> Boundaries {
+        self.boundaries
+    }
+
+    pub fn get_match_mode(&self) -> Option<MatchMode> {
+        self.match_mode
+    }
+
+    /// value is true if `token` is left out by the rules that only look at the token itself
+    fn skips(&self, token: &str) -> bool {
+        let all_caps = || token.chars().filter(|letter| letter.is_uppercase()).nth(1).is_some() && !token.chars().any(char::is_lowercase);
+
+        (self.skip_numbers && token.chars().any(char::is_numeric))
+            || (self.skip_all_caps && all_caps())
+            || self.skip_when.iter().any(|skip| skip(token))
+    }
+}
+
+/// A word of the text missing from the dawg, as yielded by `Dawg::check_text`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Miss<'a> {
+    pub token: &'a str,
+    /// byte offset of the token in the text
+    pub start: usize,
+    /// byte offset of the end of the token in the text
+    pub end: usize,
+    /// the closest words of the dawg, if asked for by `CheckOptions::suggestions`
+    pub suggestions: Vec<String>,
+}
+
+/// Where the tokens of a text come from, as set by `Boundaries`
+enum Tokens<'a> {
+    Unicode(UnicodeWordIndices<'a>),
+    /// runs of the letters for which the function returns true, along with the byte offset the next one is looked for from
+    Runs(fn(char) -> bool, usize),
+}
+
+/// Iterator over the words of a text missing from a dawg, see `Dawg::check_text`
+pub struct Misses<'a, T: Wrapper> {
+    dawg: &'a Dawg<T>,
+    text: &'a str,
+    options: CheckOptions,
+    mode: MatchMode,
+    tokens: Tokens<'a>,
+    /// the last run of text between spaces found to be a URL
+    url: Range<usize>,
+}
+
+impl<'a, T> Debug for Misses<'a, T> where T: Wrapper {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Misses").field("options", &self.options).field("mode", &self.mode).finish_non_exhaustive()
+    }
+}
+
+impl<'a, T> Misses<'a, T> where T: Wrapper {
+    /// Returns the range of the next token
+    fn next_token(&mut self) -> Option<Range<usize>> {
+        match &mut self.tokens {
+            Tokens::Unicode(words) => words.next().map(|(start, word)| start..start + word.len()),
+            Tokens::Runs(is_letter, position) => {
+                let start = *position + self.text[*position..].find(*is_letter)?;
+                let end = self.text[start..].find(|letter| !is_letter(letter)).map_or(self.text.len(), |len| start + len);
+                *position = end;
+                Some(start..end)
+            }
+        }
+    }
+
+    /// value is true if the token at `start` is part of a URL or an e-mail address
+    fn in_url(&mut self, start: usize) -> bool {
+        if !self.url.contains(&start) {
+            let from = self.text[..start].rfind(char::is_whitespace).map_or(0, |space| space + self.text[space..].chars().next().unwrap().len_utf8());
+            let to = self.text[start..].find(char::is_whitespace).map_or(self.text.len(), |space| start + space);
+            let run = self.text[from..to].trim_start_matches(|letter: char| !letter.is_alphanumeric());
+
+            let email = run.find('@').is_some_and(|at| run[at..].contains('.'));
+            let url = run.contains("://") || run.starts_with("www.") || run.starts_with("mailto:") || email;
+            self.url = if url { from..to } else { 0..0 };
+        }
+        !self.url.is_empty()
+    }
+}
+
+impl<'a, T> Iterator for Misses<'a, T> where T: Wrapper {
+    type Item = Miss<'a>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let Range { start, end } = self.next_token()?;
+            let token = &self.text[start..end];
+
+            if self.options.skips(token) || (self.options.skip_urls && self.in_url(start)) || Lexicon::contains(self.dawg, token, self.mode) {
+                continue;
+            }
+
+            let suggestions = match self.options.suggestions {
+                0 => vec![],
+                limit => self.dawg.suggest(token, self.mode, limit, self.options.max_distance),
+            };
+            return Some(Miss { token, start, end, suggestions });
+        }
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Splits `text` into words and yields those missing from the dawg in the order they appear, as configured by
+    /// `options`. Words are looked up as they are reached, and the suggestions are only worked out for the words missing
+    pub fn check_text<'a>(&'a self, text: &'a str, options: CheckOptions) -> Misses<'a, T> {
+        let mode = options.match_mode.unwrap_or(self.options.match_mode);
+        let tokens = match options.boundaries {
+            Boundaries::Unicode => Tokens::Unicode(unicode::words(text)),
+            Boundaries::Alphabetic => Tokens::Runs(char::is_alphabetic, 0),
+            Boundaries::Custom(is_letter) => Tokens::Runs(is_letter, 0),
+        };
+        Misses { dawg: self, text, options, mode, tokens, url: 0..0 }
+    }
+}
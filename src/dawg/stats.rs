@@ -0,0 +1,59 @@
+use std::{collections::HashSet, mem};
+
+use crate::dawg::common::{Dawg, DawgNode, Label, NodeOps, Wrapper};
+
+/// Size of the Dawg compared to the plain (un-minimized) trie holding the same words
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CompressionStats {
+    /// number of distinct nodes reachable from the root of the dawg
+    pub dawg_nodes: usize,
+    /// number of edges between the reachable nodes of the dawg
+    pub dawg_edges: usize,
+    /// number of nodes an equivalent trie would contain (the root included)
+    pub trie_nodes: usize,
+    /// number of edges an equivalent trie would contain
+    pub trie_edges: usize,
+    /// trie_nodes / dawg_nodes, 1.0 means minimization merged nothing
+    pub ratio: f64,
+    /// estimated number of bytes saved by the dawg under the current node representation
+    pub bytes_saved: usize,
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Reports how much smaller the dawg is than the trie of the words added so far.
+    /// Nodes still waiting to be minimized are counted as they currently are, so call this after `finish` for the final figures
+    pub fn compression_stats(&self) -> CompressionStats {
+        let mut visited = HashSet::new();
+        let mut stack = vec![self.root.clone()];
+        let mut dawg_edges = 0;
+
+        while let Some(node) = stack.pop() {
+            if !visited.insert(node.id()) {
+                continue;
+            }
+
+            for (_, child) in node.children() {
+                dawg_edges += 1;
+                stack.push(child);
+            }
+        }
+
+        let dawg_nodes = visited.len();
+        let trie_nodes = self.trie_nodes;
+        let trie_edges = trie_nodes - 1;
+
+        let node_bytes = mem::size_of::<DawgNode<T::Node>>();
+        let edge_bytes = mem::size_of::<L>() + mem::size_of::<T::Node>();
+        let trie_bytes = trie_nodes * node_bytes + trie_edges * edge_bytes;
+        let dawg_bytes = dawg_nodes * node_bytes + dawg_edges * edge_bytes;
+
+        CompressionStats {
+            dawg_nodes,
+            dawg_edges,
+            trie_nodes,
+            trie_edges,
+            ratio: trie_nodes as f64 / dawg_nodes as f64,
+            bytes_saved: trie_bytes.saturating_sub(dawg_bytes),
+        }
+    }
+}
@@ -0,0 +1,283 @@
+//! Building a dawg straight into its saved form, for word lists whose automaton doesn't fit in memory.
+//!
+//! `StreamingWriter` minimizes the words as `Dawg::add_sequence` does, but a node leaving the pending path is final: it is
+//! written out right away and only its signature is kept, to find its twins among the nodes still to come. Nodes are then
+//! numbered children first, the root last, which `load` reads as well as the breadth first order of `Dawg::save` (a loaded
+//! dawg is renumbered either way, so it saves the same bytes as the dawg built in memory).
+//!
+//! The layout puts all the nodes before all the edges, so the node records go to the output as they are written while the
+//! edges and their labels are spilled to a temporary directory, then copied after the nodes by `finish`. The header, whose
+//! counts are only known then, is written last, over the one reserved at the start
+
+use std::{collections::HashMap, fs::File, io::{BufReader, BufWriter, Read, Seek, SeekFrom, Write}, mem::size_of, path::Path};
+
+use crate::{codegen::CompileReport, dawg::{external::SpillDir, integrity::{Crc32, CHUNK_SIZE}, options::{DawgOptions, DuplicatePolicy, Granularity, SortDirection}, serialize::{encode_header, write_u32, write_u64}}, error::{DawgError, OptionsConflict}};
+
+/// Terminal flag and outgoing edges (label, child id) of a written node, which identify it among the written ones
+type Signature = (bool, Box<[(char, u32)]>);
+
+/// A node on the path of the last word, whose edges can still change
+#[derive(Debug)]
+struct Pending {
+    /// label of the edge leading to it from the node above
+    label: char,
+    terminal: bool,
+    /// (label, id, words below) of every child, all of them written
+    edges: Vec<(char, u32, u64)>,
+}
+
+/// Section written a piece at a time, followed by the checksums of its chunks of `CHUNK_SIZE` bytes
+#[derive(Debug, Default)]
+struct Section {
+    len: u64,
+    crc: Option<Crc32>,
+    checksums: Vec<u32>,
+}
+
+impl Section {
+    fn write(&mut self, out: &mut impl Write, mut bytes: &[u8]) -> Result<(), DawgError> {
+        out.write_all(bytes)?;
+        self.len += bytes.len() as u64;
+
+        while !bytes.is_empty() {
+            let filled = (self.len - bytes.len() as u64) as usize % CHUNK_SIZE;
+            let (chunk, rest) = bytes.split_at(bytes.len().min(CHUNK_SIZE - filled));
+            self.crc.get_or_insert_with(Crc32::new).update(chunk);
+            if filled + chunk.len() == CHUNK_SIZE {
+                self.checksums.push(self.crc.take().unwrap().finish());
+            }
+            bytes = rest;
+        }
+        Ok(())
+    }
+
+    /// Writes the checksums of the chunks after the section
+    fn finish(mut self, out: &mut impl Write) -> Result<(), DawgError> {
+        if let Some(crc) = self.crc.take() {
+            self.checksums.push(crc.finish());
+        }
+        for checksum in self.checksums {
+            out.write_all(&checksum.to_le_bytes())?;
+        }
+        Ok(())
+    }
+}
+
+/// Builds a dawg from words in sorted order straight into `writer`, in the layout of `Dawg::save`, see the `streaming`
+/// module. Only the signatures of the nodes written so far and the path of the last word are held in memory
+#[derive(Debug)]
+pub struct StreamingWriter<W: Write + Seek> {
+    writer: W,
+    options: DawgOptions,
+    /// where the header starts in `writer`
+    start: u64,
+    spill: SpillDir,
+    edges: BufWriter<File>,
+    labels: BufWriter<File>,
+    register: HashMap<Signature, (u32, u64)>,
+    /// edges held by the signatures of `register`
+    registered_edges: usize,
+    root: Pending,
+    pending: Vec<Pending>,
+    previous_word: Vec<char>,
+    nodes: Section,
+    node_count: u32,
+    edge_count: u32,
+    label_len: u32,
+    words: usize,
+    lines: usize,
+    peak_memory: usize,
+}
+
+impl<W> StreamingWriter<W> where W: Write + Seek {
+    /// Starts a dawg with `options` at the current position of `writer`, spilling the edges to the temporary directory of
+    /// the system. The words must come in the order of their letters (after the case folding of the options, if any):
+    /// other sort orders and directions are rejected, they would need the words held aside
+    pub fn new(writer: W, options: DawgOptions) -> Result<Self, DawgError> {
+        Self::new_in(writer, options, &std::env::temp_dir())
+    }
+
+    /// Same as `new`, spilling the edges to a directory of its own inside `temp_dir`, removed once the writer is dropped
+    pub fn new_in(mut writer: W, options: DawgOptions, temp_dir: &Path) -> Result<Self, DawgError> {
+        options.validate()?;
+        // the labels written are chars
+        options.check_granularity(Granularity::Chars)?;
+        if !options.sort_order.follows_letters() || options.sort_direction != SortDirection::Ascending {
+            return Err(DawgError::InvalidOptions(OptionsConflict::LetterOrder { by: "a streaming build" }));
+        }
+
+        let start = writer.stream_position()?;
+        writer.write_all(&encode_header(&options, 0, 0, 0, 0))?;
+
+        let spill = SpillDir::create(temp_dir, "stream")?;
+        let edges = BufWriter::new(File::create(spill.path.join("edges"))?);
+        let labels = BufWriter::new(File::create(spill.path.join("labels"))?);
+
+        Ok(Self {
+            writer,
+            options,
+            start,
+            spill,
+            edges,
+            labels,
+            register: HashMap::new(),
+            registered_edges: 0,
+            root: Pending { label: '\0', terminal: false, edges: vec![] },
+            pending: vec![],
+            previous_word: vec![],
+            nodes: Section::default(),
+            node_count: 0,
+            edge_count: 0,
+            label_len: 0,
+            words: 0,
+            lines: 0,
+            peak_memory: 0,
+        })
+    }
+
+    /// Adds `word` after applying the options, the words must be added in sorted order (see `new`). A word equal to the
+    /// previous one is skipped, or rejected under `DuplicatePolicy::Error`. So is a word rejected by the alphabet or the
+    /// order under `ValidationPolicy::Skip`, which is counted by the `skipped` of the report
+    pub fn add(&mut self, word: &str) -> Result<(), DawgError> {
+        let added = self.add_word(word);
+        if self.options.skips(&added) {
+            self.lines += 1;
+            return Ok(());
+        }
+        added
+    }
+
+    fn add_word(&mut self, word: &str) -> Result<(), DawgError> {
+        let word = self.options.prepare(word, Granularity::Chars)?;
+        let letters = word.chars().collect::<Vec<_>>();
+        if self.words > 0 && self.previous_word > letters {
+            let previous = self.previous_word.iter().collect();
+            return Err(DawgError::Unsorted { previous, word, order: self.options.sort_order.name() });
+        }
+
+        self.lines += 1;
+        if self.words > 0 && self.previous_word == letters {
+            return match self.options.duplicate_policy {
+                DuplicatePolicy::Error => Err(DawgError::Duplicate(word)),
+                DuplicatePolicy::Ignore => Ok(()),
+            };
+        }
+
+        let common_prefix = letters.iter().zip(&self.previous_word).take_while(|(letter, previous)| letter == previous).count();
+        self.minimize(common_prefix)?;
+
+        for letter in &letters[common_prefix..] {
+            self.pending.push(Pending { label: *letter, terminal: false, edges: vec![] });
+        }
+        match self.pending.last_mut() {
+            Some(last) => last.terminal = true,
+            None => self.root.terminal = true,
+        }
+
+        self.previous_word = letters;
+        self.words += 1;
+        self.peak_memory = self.peak_memory.max(self.memory_bytes());
+        Ok(())
+    }
+
+    /// Writes the pending nodes below `depth`, deepest first, unless a twin was written before
+    fn minimize(&mut self, depth: usize) -> Result<(), DawgError> {
+        while self.pending.len() > depth {
+            let node = self.pending.pop().unwrap();
+            let (id, count) = self.write_node(node.terminal, node.edges)?;
+
+            let parent = self.pending.last_mut().unwrap_or(&mut self.root);
+            parent.edges.push((node.label, id, count));
+        }
+        Ok(())
+    }
+
+    /// Returns the id and the number of words of the written node with these edges, writing it first if there is none
+    fn write_node(&mut self, terminal: bool, edges: Vec<(char, u32, u64)>) -> Result<(u32, u64), DawgError> {
+        let signature = (terminal, edges.iter().map(|(label, id, _)| (*label, *id)).collect::<Box<[_]>>());
+        if let Some(written) = self.register.get(&signature) {
+            return Ok(*written);
+        }
+
+        let count = terminal as u64 + edges.iter().map(|(_, _, count)| count).sum::<u64>();
+        let mut record = vec![terminal as u8];
+        write_u64(&mut record, count);
+        write_u32(&mut record, self.edge_count);
+        write_u32(&mut record, edges.len() as u32);
+        self.nodes.write(&mut self.writer, &record)?;
+
+        for (label, child, _) in &edges {
+            let mut encoded = [0; 4];
+            let label = label.encode_utf8(&mut encoded).as_bytes();
+            let mut edge = vec![];
+            write_u32(&mut edge, *child);
+            write_u32(&mut edge, self.label_len);
+            write_u32(&mut edge, label.len() as u32);
+            self.edges.write_all(&edge)?;
+            self.labels.write_all(label)?;
+            self.label_len += label.len() as u32;
+        }
+        self.edge_count += edges.len() as u32;
+
+        let written = (self.node_count, count);
+        self.node_count += 1;
+        self.registered_edges += edges.len();
+        self.register.insert(signature, written);
+        Ok(written)
+    }
+
+    /// Returns an estimate of the bytes held in memory: the signatures of the nodes written so far and the pending path
+    pub fn memory_bytes(&self) -> usize {
+        let entry = size_of::<Signature>() + size_of::<(u32, u64)>() + size_of::<u64>();
+        let signatures = self.registered_edges * size_of::<(char, u32)>();
+        let pending = self.pending.iter().map(|node| size_of::<Pending>() + node.edges.capacity() * size_of::<(char, u32, u64)>()).sum::<usize>();
+
+        self.register.capacity() * entry + signatures + pending + self.previous_word.capacity() * size_of::<char>()
+    }
+
+    /// Returns the highest `memory_bytes` seen after a word was added
+    pub fn peak_memory_bytes(&self) -> usize {
+        self.peak_memory
+    }
+
+    /// Writes the nodes still pending, the root last, copies the spilled edges and labels after the nodes and fills in the
+    /// header. The output then loads with `Dawg::load` like any saved dawg
+    pub fn finish(mut self) -> Result<CompileReport, DawgError> {
+        self.minimize(0)?;
+        let root = std::mem::replace(&mut self.root, Pending { label: '\0', terminal: false, edges: vec![] });
+        let (root, _) = self.write_node(root.terminal, root.edges)?;
+        std::mem::take(&mut self.nodes).finish(&mut self.writer)?;
+
+        for (mut spilled, name) in [(self.edges, "edges"), (self.labels, "labels")] {
+            spilled.flush()?;
+            drop(spilled);
+
+            let mut reader = BufReader::new(File::open(self.spill.path.join(name))?);
+            let mut section = Section::default();
+            let mut buffer = vec![0; 64 << 10];
+            loop {
+                match reader.read(&mut buffer)? {
+                    0 => break,
+                    read => section.write(&mut self.writer, &buffer[..read])?,
+                }
+            }
+            section.finish(&mut self.writer)?;
+        }
+
+        let end = self.writer.stream_position()?;
+        self.writer.seek(SeekFrom::Start(self.start))?;
+        self.writer.write_all(&encode_header(&self.options, self.node_count, self.edge_count, self.label_len, root))?;
+        self.writer.seek(SeekFrom::Start(end))?;
+        self.writer.flush()?;
+
+        Ok(CompileReport {
+            lines: self.lines,
+            words: self.words,
+            skipped: self.lines - self.words,
+            nodes: self.node_count as usize,
+            edges: self.edge_count as usize,
+            bytes: (end - self.start) as usize,
+            written: true,
+        })
+    }
+}
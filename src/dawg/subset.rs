@@ -0,0 +1,107 @@
+use std::{fmt::Debug, io::Write, ops::Range};
+
+use crate::{dawg::{common::{Dawg, NodeOps, Wrapper}, map::WeightedDawg, metadata::Metadata}, error::DawgError};
+
+/// The words of a dawg `Dawg::save_subset` keeps
+pub enum SubsetFilter<'a> {
+    /// the words starting with the prefix (matched exactly)
+    Prefix(String),
+    /// the words whose rank (index in sorted order, see `Dawg::rank`) is in the range
+    Ranks(Range<usize>),
+    /// the `n` heaviest words, the weight of a word being at its rank in `weights` (the values of a `WeightedDawg`). Ties
+    /// are broken by rank, the first words winning
+    TopWeighted { weights: &'a [u64], n: usize },
+    /// the words for which the function returns true
+    Predicate(Box<dyn FnMut(&str) -> bool + 'a>),
+}
+
+impl<'a> Debug for SubsetFilter<'a> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Prefix(prefix) => f.debug_tuple("Prefix").field(prefix).finish(),
+            Self::Ranks(ranks) => f.debug_tuple("Ranks").field(ranks).finish(),
+            Self::TopWeighted { weights, n } => f.debug_struct("TopWeighted").field("weights", &weights.len()).field("n", n).finish(),
+            Self::Predicate(_) => f.debug_tuple("Predicate").finish_non_exhaustive(),
+        }
+    }
+}
+
+impl<'a> SubsetFilter<'a> {
+    pub fn predicate(keep: impl FnMut(&str) -> bool + 'a) -> Self {
+        Self::Predicate(Box::new(keep))
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Writes a dawg of the words kept by `filter` to `writer`, as `save` would write the dawg built from them alone (with the
+    /// same options): it loads as any other dawg. Returns the number of words written.
+    ///
+    /// The words are streamed in sorted order into a new dawg, minimized as it is built, so the subset is never held in
+    /// memory as a list. `TopWeighted` marks the chosen ranks first, a bit per word. A `Metadata::WORD_COUNT` entry in the
+    /// options is updated to the size of the subset. Fails if the dawg isn't finished
+    pub fn save_subset<W: Write>(&self, writer: W, filter: SubsetFilter<'_>) -> Result<usize, DawgError> {
+        if !self.finished {
+            return Err(DawgError::NotFinished);
+        }
+
+        let sequences = self.sequences();
+        let words: Box<dyn Iterator<Item = Vec<char>> + '_> = match filter {
+            SubsetFilter::Prefix(prefix) => Box::new(self.sequences_with_prefix(&prefix.chars().collect::<Vec<_>>())),
+            SubsetFilter::Ranks(ranks) => Box::new(sequences.skip(ranks.start).take(ranks.len())),
+            SubsetFilter::TopWeighted { weights, n } => {
+                let chosen = top_ranks(weights, n, self.root.count())?;
+                Box::new(sequences.enumerate().filter(move |(rank, _)| chosen[*rank]).map(|(_, word)| word))
+            }
+            SubsetFilter::Predicate(mut keep) => {
+                let mut word = String::new();
+                Box::new(sequences.filter(move |letters| {
+                    word.clear();
+                    word.extend(letters);
+                    keep(&word)
+                }))
+            }
+        };
+
+        // the words come in the order of their letters whatever the sort order of the options, which are only set once built
+        let mut subset = Self::with_wrapper(T::new());
+        for word in words {
+            subset.push_sequence(&word);
+        }
+        subset.finish();
+
+        let count = subset.root.count();
+        subset.options = self.options.clone();
+        if subset.options.metadata.get(Metadata::WORD_COUNT).is_some() {
+            subset.options.metadata.insert(Metadata::WORD_COUNT, count.to_string());
+        }
+
+        subset.save(writer)?;
+        Ok(count)
+    }
+}
+
+impl<T> WeightedDawg<T> where T: Wrapper {
+    /// Writes the dawg of the `n` heaviest words to `writer`, see `SubsetFilter::TopWeighted`. The weights aren't saved
+    pub fn save_top<W: Write>(&self, writer: W, n: usize) -> Result<usize, DawgError> {
+        self.dawg().save_subset(writer, SubsetFilter::TopWeighted { weights: self.values(), n })
+    }
+}
+
+/// Marks the ranks of the `n` heaviest of the `words`, the first ones winning ties
+fn top_ranks(weights: &[u64], n: usize, words: usize) -> Result<Vec<bool>, DawgError> {
+    if weights.len() != words {
+        return Err(DawgError::InvalidValue(format!("{} weights given for {words} words", weights.len())));
+    }
+
+    let mut ranks = (0..words).collect::<Vec<_>>();
+    if n < words {
+        ranks.select_nth_unstable_by(n, |a, b| weights[*b].cmp(&weights[*a]).then(a.cmp(b)));
+        ranks.truncate(n);
+    }
+
+    let mut chosen = vec![false; words];
+    for rank in ranks {
+        chosen[rank] = true;
+    }
+    Ok(chosen)
+}
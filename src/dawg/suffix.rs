@@ -0,0 +1,316 @@
+//! Words ending with a given suffix, read backwards through the dawg itself rather than from a second, reversed one.
+//!
+//! `Dawg::build_reverse_index` lists the incoming edges of every node. A word ends with a suffix when the suffix leads from
+//! the node its prefix reaches down to a terminal node, so the suffix is read backwards from the terminal nodes up to those
+//! nodes, then every path from the root to them spells a prefix. The index also keeps the number of such paths for every
+//! node, so `count_suffix` doesn't list the words.
+//!
+//! A dawg saved with its index is made of the magic bytes `DAWR`, the version of its layout (u16), the length (u64) and
+//! the bytes of the dawg as written by `Dawg::save`, then the index: the number of nodes (u32), of terminal nodes (u32) and
+//! of edges (u32), the terminal nodes (u32 each), the end of the incoming edges of every node (u32 each), and the label
+//! (u32) and parent (u32) of every incoming edge. Nodes are numbered as `visit_nodes` numbers them. Numbers are
+//! little-endian
+
+use std::{io::{Read, Write}, mem::size_of, sync::Arc};
+
+use crate::{dawg::{common::{Dawg, Label, Wrapper}, serialize::{topological_order, write_u32, write_u64, Cursor}}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWR";
+const VERSION: u16 = 1;
+
+/// Incoming edges of every node of a dawg, see the `suffix` module. Nodes are numbered as `visit_nodes` numbers them, the
+/// root being 0
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReverseIndex<L = char> {
+    terminals: Vec<u32>,
+    /// the incoming edges of node `i` are `ends[i - 1]..ends[i]` (from 0 for the root) of `labels` and `parents`, sorted
+    /// by label
+    ends: Vec<u32>,
+    labels: Vec<L>,
+    parents: Vec<u32>,
+    /// number of paths from the root to every node, that is of prefixes leading to it
+    paths: Vec<u64>,
+}
+
+impl<L> ReverseIndex<L> where L: Label {
+    /// Indexes the edges (parent, label, child) of the `node_count` nodes, the terminal ones being `terminals`. Fails if the
+    /// edges form a cycle
+    fn new(node_count: usize, terminals: Vec<u32>, mut edges: Vec<(u32, L, u32)>) -> Result<Self, DawgError> {
+        edges.sort_unstable_by(|(_, a, a_child), (_, b, b_child)| a_child.cmp(b_child).then_with(|| a.cmp(b)));
+
+        let mut ends = vec![0; node_count];
+        for (_, _, child) in &edges {
+            ends[*child as usize] += 1;
+        }
+        for id in 1..node_count {
+            ends[id] += ends[id - 1];
+        }
+
+        let (parents, labels) = edges.into_iter().map(|(parent, label, _)| (parent, label)).unzip();
+        let mut index = Self { terminals, ends, labels, parents, paths: vec![] };
+        index.count_paths()?;
+        Ok(index)
+    }
+
+    fn incoming(&self, node: u32) -> std::ops::Range<usize> {
+        let start = match node {
+            0 => 0,
+            node => self.ends[node as usize - 1] as usize,
+        };
+        start..self.ends[node as usize] as usize
+    }
+
+    /// Counts the paths from the root to every node, parents first. Fails if the edges form a cycle
+    fn count_paths(&mut self) -> Result<(), DawgError> {
+        // every node comes before its parents in this order, so it is walked backwards
+        let order = topological_order(self.ends.len(), |id| self.parents[self.incoming(id as u32)].iter().copied())?;
+
+        let mut paths = vec![0u64; self.ends.len()];
+        for &node in order.iter().rev() {
+            paths[node as usize] = match node {
+                0 => 1,
+                node => self.parents[self.incoming(node)].iter().map(|parent| paths[*parent as usize]).fold(0, u64::saturating_add),
+            };
+        }
+        self.paths = paths;
+        Ok(())
+    }
+
+    /// Returns the number of nodes indexed
+    pub fn node_count(&self) -> usize {
+        self.ends.len()
+    }
+
+    /// Returns the number of bytes the index takes in memory
+    pub fn memory_bytes(&self) -> usize {
+        size_of::<Self>()
+            + self.terminals.capacity() * size_of::<u32>()
+            + self.ends.capacity() * size_of::<u32>()
+            + self.labels.capacity() * size_of::<L>()
+            + self.parents.capacity() * size_of::<u32>()
+            + self.paths.capacity() * size_of::<u64>()
+    }
+
+    /// Returns the nodes from which `suffix` leads to a terminal node. The automaton is deterministic, so two nodes never
+    /// share a parent through the same label: the nodes found are distinct without being deduplicated
+    fn suffix_nodes(&self, suffix: &[L]) -> Vec<u32> {
+        let mut nodes = self.terminals.clone();
+
+        for label in suffix.iter().rev() {
+            let mut next = vec![];
+            for node in nodes {
+                let incoming = self.incoming(node);
+                let labels = &self.labels[incoming.clone()];
+                let start = incoming.start + labels.partition_point(|edge| edge < label);
+                let end = incoming.start + labels.partition_point(|edge| edge <= label);
+                next.extend_from_slice(&self.parents[start..end]);
+            }
+            nodes = next;
+        }
+
+        nodes
+    }
+
+    /// Returns the number of sequences ending with `suffix`
+    fn count_suffix(&self, suffix: &[L]) -> usize {
+        self.suffix_nodes(suffix).iter().map(|node| self.paths[*node as usize]).sum::<u64>() as usize
+    }
+
+    /// Returns every sequence ending with `suffix` in sorted order: the paths from the root to each node found, walked up
+    /// from the node
+    fn sequences_with_suffix(&self, suffix: &[L]) -> Vec<Vec<L>> {
+        let mut sequences = vec![];
+
+        for node in self.suffix_nodes(suffix) {
+            let mut stack = vec![(node, vec![])];
+            while let Some((node, reversed)) = stack.pop() {
+                if node == 0 {
+                    sequences.push(reversed.iter().rev().chain(suffix).cloned().collect());
+                    continue;
+                }
+
+                for edge in self.incoming(node) {
+                    let mut reversed = reversed.clone();
+                    reversed.push(self.labels[edge].clone());
+                    stack.push((self.parents[edge], reversed));
+                }
+            }
+        }
+
+        sequences.sort_unstable();
+        sequences
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Builds the index `words_with_suffix` and `count_suffix` go through, replacing the previous one: about 8 bytes per
+    /// edge and 12 per node (see `ReverseIndex::memory_bytes`), well below a reversed dawg. Adding or removing words
+    /// afterwards drops it, it has to be built again then. Fails unless the dawg is finished
+    pub fn build_reverse_index(&mut self) -> Result<(), DawgError> {
+        if !self.finished {
+            return Err(DawgError::NotFinished);
+        }
+
+        self.reverse_index = Some(Arc::new(self.index_incoming()));
+        Ok(())
+    }
+
+    /// Indexes the incoming edges of the nodes, numbered by `visit_nodes`
+    fn index_incoming(&self) -> ReverseIndex<L> {
+        let mut terminals = vec![];
+        let mut edges = vec![];
+        let mut node_count = 0;
+        for view in self.visit_nodes_bfs() {
+            node_count += 1;
+            if view.is_terminal() {
+                terminals.push(view.id() as u32);
+            }
+            edges.extend(view.edges().map(|(label, child)| (view.id() as u32, label.clone(), child as u32)));
+        }
+
+        ReverseIndex::new(node_count, terminals, edges).expect("the nodes of a dawg form no cycle")
+    }
+
+    /// the index built by `build_reverse_index`, if the words haven't changed since
+    pub fn reverse_index(&self) -> Option<&ReverseIndex<L>> {
+        self.reverse_index.as_deref()
+    }
+
+    /// Drops the index, `words_with_suffix` and `count_suffix` read every word again
+    pub fn drop_reverse_index(&mut self) {
+        self.reverse_index = None;
+    }
+
+    /// Returns every sequence ending with `suffix` (matched exactly) in sorted order. Without an index (see
+    /// `build_reverse_index`) every sequence is read and checked
+    pub fn sequences_with_suffix(&self, suffix: &[L]) -> Vec<Vec<L>> {
+        match &self.reverse_index {
+            Some(index) => index.sequences_with_suffix(suffix),
+            None => self.sequences().filter(|sequence| sequence.ends_with(suffix)).collect(),
+        }
+    }
+
+    /// Returns the number of sequences ending with `suffix` (matched exactly), from the path counts of the index without
+    /// listing them if there is one
+    pub fn count_sequences_with_suffix(&self, suffix: &[L]) -> usize {
+        match &self.reverse_index {
+            Some(index) => index.count_suffix(suffix),
+            None => self.sequences().filter(|sequence| sequence.ends_with(suffix)).count(),
+        }
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns every word ending with `suffix` (matched exactly) in sorted order, see `sequences_with_suffix`
+    pub fn words_with_suffix(&self, suffix: &str) -> Vec<String> {
+        let suffix = suffix.chars().collect::<Vec<_>>();
+        self.sequences_with_suffix(&suffix).into_iter().map(|letters| letters.into_iter().collect()).collect()
+    }
+
+    /// Returns the number of words ending with `suffix` (matched exactly), see `count_sequences_with_suffix`
+    pub fn count_suffix(&self, suffix: &str) -> usize {
+        self.count_sequences_with_suffix(&suffix.chars().collect::<Vec<_>>())
+    }
+
+    /// Encodes the finished dawg along with its reverse index, which is built first if the dawg has none (without keeping
+    /// it), see the `suffix` module. Fails like `to_bytes` if the dawg isn't finished
+    pub fn to_bytes_with_reverse_index(&self) -> Result<Vec<u8>, DawgError> {
+        let dawg = self.to_bytes()?;
+        let built;
+        let index = match &self.reverse_index {
+            Some(index) => index,
+            None => {
+                built = self.index_incoming();
+                &built
+            }
+        };
+
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&VERSION.to_le_bytes());
+
+        write_u64(&mut out, dawg.len() as u64);
+        out.extend_from_slice(&dawg);
+
+        for count in [index.ends.len(), index.terminals.len(), index.parents.len()] {
+            write_u32(&mut out, count as u32);
+        }
+        for value in index.terminals.iter().chain(&index.ends) {
+            write_u32(&mut out, *value);
+        }
+        for (label, parent) in index.labels.iter().zip(&index.parents) {
+            write_u32(&mut out, *label as u32);
+            write_u32(&mut out, *parent);
+        }
+        Ok(out)
+    }
+
+    /// Writes the dawg and its reverse index to `writer`, see `to_bytes_with_reverse_index`
+    pub fn save_with_reverse_index<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        writer.write_all(&self.to_bytes_with_reverse_index()?)?;
+        Ok(writer.flush()?)
+    }
+
+    /// Rebuilds a finished dawg and its reverse index from the bytes written by `save_with_reverse_index`. The index is
+    /// checked against the dawg: as many nodes and edges, every id in bounds, no cycle
+    pub fn from_bytes_with_reverse_index(bytes: &[u8]) -> Result<Self, DawgError> {
+        let invalid = |reason: &str| DawgError::InvalidFormat(format!("reverse index {reason}"));
+        let mut reader = Cursor::new(bytes);
+        if reader.bytes(MAGIC.len())? != MAGIC {
+            return Err(DawgError::InvalidFormat(String::from("missing reverse index magic bytes")));
+        }
+
+        let version = reader.u16()?;
+        if version > VERSION {
+            return Err(DawgError::NewerVersion { version, supported: VERSION });
+        }
+
+        let len = reader.u64()? as usize;
+        let mut dawg = Self::from_bytes(reader.bytes(len)?)?;
+
+        let (node_count, terminal_count, edge_count) = (reader.u32()? as usize, reader.u32()? as usize, reader.u32()? as usize);
+        let (nodes, edges) = dawg.visit_nodes().fold((0, 0), |(nodes, edges), view| (nodes + 1, edges + view.edges().count()));
+        if node_count != nodes || edge_count != edges || terminal_count > nodes {
+            return Err(invalid(&format!("has {node_count} nodes and {edge_count} edges, the dawg {nodes} and {edges}")));
+        }
+
+        let mut ids = |count: usize, bound: usize| (0..count).map(|_| read_id(&mut reader, bound)).collect::<Result<Vec<_>, _>>();
+        let terminals = ids(terminal_count, node_count)?;
+        let ends = ids(node_count, edge_count + 1)?;
+        if terminals.windows(2).any(|pair| pair[0] >= pair[1]) {
+            return Err(invalid("has terminal nodes out of order"));
+        }
+        if ends.windows(2).any(|pair| pair[0] > pair[1]) || ends.last().is_some_and(|end| *end as usize != edge_count) {
+            return Err(invalid("has edges out of order"));
+        }
+
+        let mut labels = Vec::with_capacity(edge_count);
+        let mut parents = Vec::with_capacity(edge_count);
+        for _ in 0..edge_count {
+            labels.push(char::from_u32(reader.u32()?).ok_or_else(|| invalid("has an invalid label"))?);
+            parents.push(read_id(&mut reader, node_count)?);
+        }
+
+        let mut index = ReverseIndex { terminals, ends, labels, parents, paths: vec![] };
+        if (0..node_count as u32).any(|node| index.labels[index.incoming(node)].windows(2).any(|pair| pair[0] > pair[1])) {
+            return Err(invalid("has incoming edges out of order"));
+        }
+        index.count_paths()?;
+        dawg.reverse_index = Some(Arc::new(index));
+        Ok(dawg)
+    }
+
+    /// Reads a dawg and its reverse index written by `save_with_reverse_index` from `reader`
+    pub fn load_with_reverse_index<R: Read>(mut reader: R) -> Result<Self, DawgError> {
+        let mut bytes = vec![];
+        reader.read_to_end(&mut bytes)?;
+        Self::from_bytes_with_reverse_index(&bytes)
+    }
+}
+
+/// Reads an id, failing unless it is below `bound`
+fn read_id(reader: &mut Cursor<'_>, bound: usize) -> Result<u32, DawgError> {
+    match reader.u32()? {
+        id if (id as usize) < bound => Ok(id),
+        _ => Err(DawgError::InvalidFormat(String::from("reverse index has an id out of bounds"))),
+    }
+}
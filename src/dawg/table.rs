@@ -0,0 +1,245 @@
+//! The automaton of a dawg as a dense transition table, for engines that only know how to follow one: a row per state
+//! and a column per symbol of the alphabet, each cell holding the next state.
+//!
+//! States are numbered as `visit_nodes` numbers the nodes, so the start state (the root) is 0. One more state, the dead
+//! state, comes last: every symbol that can't be followed leads to it, and it leads back to itself on every symbol.
+//! The alphabet is made of the symbols on the edges of the dawg in sorted order, a symbol outside of it leads to the dead
+//! state as well.
+//!
+//! `Dawg::to_transition_table` reads the words a letter at a time. `Dawg::to_byte_transition_table` reads their utf-8
+//! bytes instead, which keeps the table narrow for large alphabets: a letter of several bytes goes through states of its
+//! own, numbered after those of the nodes (node by node, then by label), which are never terminal.
+//!
+//! `write_binary` writes the magic bytes `DAWX`, the version of its layout (u16), the kind of symbols (u8: 0 for letters,
+//! 1 for bytes), the number of states (u32), of columns (u32), the start state (u32) and the dead state (u32), the
+//! alphabet (u32 code points or single bytes), the transitions row by row (u32 each) and the terminal states as a bitset
+//! (u64 words, state `i` being bit `i % 64` of word `i / 64`). Numbers are little-endian
+
+use std::io::Write;
+
+use crate::{dawg::{common::{Dawg, Wrapper}, serialize::{write_u32, write_u64}}, error::DawgError};
+
+const MAGIC: &[u8; 4] = b"DAWX";
+const VERSION: u16 = 1;
+
+/// The symbols of the columns of a `TransitionTable`, in sorted order
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Alphabet {
+    Chars(Vec<char>),
+    Bytes(Vec<u8>),
+}
+
+impl Alphabet {
+    /// Returns the number of symbols, that is of columns
+    pub fn len(&self) -> usize {
+        match self {
+            Alphabet::Chars(letters) => letters.len(),
+            Alphabet::Bytes(bytes) => bytes.len(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the column of `letter` in a table of letters, `None` if it isn't in the alphabet
+    pub fn column_of_char(&self, letter: char) -> Option<usize> {
+        match self {
+            Alphabet::Chars(letters) => letters.binary_search(&letter).ok(),
+            Alphabet::Bytes(_) => None,
+        }
+    }
+
+    /// Returns the column of `byte` in a table of bytes, `None` if it isn't in the alphabet
+    pub fn column_of_byte(&self, byte: u8) -> Option<usize> {
+        match self {
+            Alphabet::Chars(_) => None,
+            Alphabet::Bytes(bytes) => bytes.binary_search(&byte).ok(),
+        }
+    }
+}
+
+/// Dense transition table of the automaton of a dawg, see the `table` module
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TransitionTable {
+    alphabet: Alphabet,
+    /// `alphabet.len()` next states per state, row after row
+    transitions: Vec<u32>,
+    /// bit `i % 64` of word `i / 64` is set if state `i` is terminal
+    terminals: Vec<u64>,
+    dead: u32,
+}
+
+impl TransitionTable {
+    /// Adds the dead state after the `states` rows of `transitions`, the cells left at `u32::MAX` leading to it
+    fn new(alphabet: Alphabet, states: usize, mut transitions: Vec<u32>, terminal: impl Iterator<Item = usize>) -> Self {
+        let columns = alphabet.len();
+        let dead = states as u32;
+
+        transitions.resize(transitions.len() + columns, dead);
+        for next in transitions.iter_mut().filter(|next| **next == u32::MAX) {
+            *next = dead;
+        }
+
+        let mut terminals = vec![0; (dead as usize + 1).div_ceil(64)];
+        for state in terminal {
+            terminals[state / 64] |= 1 << (state % 64);
+        }
+        Self { alphabet, transitions, terminals, dead }
+    }
+
+    pub fn alphabet(&self) -> &Alphabet {
+        &self.alphabet
+    }
+
+    /// Returns the number of states, the dead state included
+    pub fn state_count(&self) -> usize {
+        self.dead as usize + 1
+    }
+
+    /// the state before any symbol is read, always 0
+    pub fn start(&self) -> u32 {
+        0
+    }
+
+    /// the state every symbol that can't be followed leads to, always the last one
+    pub fn dead(&self) -> u32 {
+        self.dead
+    }
+
+    /// Returns the state reached by reading the symbol of `column` in `state`
+    pub fn next(&self, state: u32, column: usize) -> u32 {
+        self.transitions[state as usize * self.alphabet.len() + column]
+    }
+
+    pub fn is_terminal(&self, state: u32) -> bool {
+        self.terminals[state as usize / 64] & (1 << (state % 64)) != 0
+    }
+
+    /// the next states, `alphabet().len()` per state, row after row
+    pub fn transitions(&self) -> &[u32] {
+        &self.transitions
+    }
+
+    /// the terminal states as a bitset, see the `table` module
+    pub fn terminals(&self) -> &[u64] {
+        &self.terminals
+    }
+
+    /// value is true if the table accepts `word`, read a letter or a byte at a time depending on the alphabet
+    pub fn accepts(&self, word: &str) -> bool {
+        let mut state = self.start();
+        let mut read = |column: Option<usize>| {
+            state = column.map_or(self.dead, |column| self.next(state, column));
+        };
+
+        match &self.alphabet {
+            Alphabet::Chars(_) => word.chars().for_each(|letter| read(self.alphabet.column_of_char(letter))),
+            Alphabet::Bytes(_) => word.bytes().for_each(|byte| read(self.alphabet.column_of_byte(byte))),
+        }
+        self.is_terminal(state)
+    }
+
+    /// Writes the table as CSV: a header `state,terminal` followed by a column per symbol (`U+0061` for a letter, `0x61`
+    /// for a byte), then a row per state with its id, 1 if it is terminal (0 otherwise) and its next states. The start
+    /// state is the first row, the dead state the last one
+    pub fn write_csv<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        let symbols: Vec<String> = match &self.alphabet {
+            Alphabet::Chars(letters) => letters.iter().map(|letter| format!("U+{:04X}", *letter as u32)).collect(),
+            Alphabet::Bytes(bytes) => bytes.iter().map(|byte| format!("0x{byte:02X}")).collect(),
+        };
+        let mut line = String::from("state,terminal");
+        symbols.iter().for_each(|symbol| line.extend([",", symbol]));
+        writeln!(writer, "{line}")?;
+
+        for state in 0..self.state_count() as u32 {
+            line = format!("{state},{}", self.is_terminal(state) as u8);
+            for column in 0..self.alphabet.len() {
+                line.push_str(&format!(",{}", self.next(state, column)));
+            }
+            writeln!(writer, "{line}")?;
+        }
+        Ok(writer.flush()?)
+    }
+
+    /// Writes the table in the binary layout described in the `table` module
+    pub fn write_binary<W: Write>(&self, mut writer: W) -> Result<(), DawgError> {
+        let mut out = MAGIC.to_vec();
+        out.extend_from_slice(&VERSION.to_le_bytes());
+        out.push(matches!(self.alphabet, Alphabet::Bytes(_)) as u8);
+        write_u32(&mut out, self.state_count() as u32);
+        write_u32(&mut out, self.alphabet.len() as u32);
+        write_u32(&mut out, self.start());
+        write_u32(&mut out, self.dead);
+
+        match &self.alphabet {
+            Alphabet::Chars(letters) => letters.iter().for_each(|letter| write_u32(&mut out, *letter as u32)),
+            Alphabet::Bytes(bytes) => out.extend_from_slice(bytes),
+        }
+        self.transitions.iter().for_each(|next| write_u32(&mut out, *next));
+        self.terminals.iter().for_each(|word| write_u64(&mut out, *word));
+
+        writer.write_all(&out)?;
+        Ok(writer.flush()?)
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the automaton as a table with a column per letter on the edges of the dawg, see the `table` module. The
+    /// table holds `(node_count + 1) * letters` cells, `to_byte_transition_table` is narrower for large alphabets
+    pub fn to_transition_table(&self) -> TransitionTable {
+        let nodes = self.visit_nodes_bfs().collect::<Vec<_>>();
+        let mut letters = nodes.iter().flat_map(|node| node.edges().map(|(label, _)| *label)).collect::<Vec<_>>();
+        letters.sort_unstable();
+        letters.dedup();
+
+        let alphabet = Alphabet::Chars(letters);
+        let columns = alphabet.len();
+        let mut transitions = vec![u32::MAX; nodes.len() * columns];
+        for node in &nodes {
+            for (label, child) in node.edges() {
+                let column = alphabet.column_of_char(*label).unwrap();
+                transitions[node.id() * columns + column] = child as u32;
+            }
+        }
+
+        let terminal = nodes.iter().filter(|node| node.is_terminal()).map(|node| node.id());
+        TransitionTable::new(alphabet, nodes.len(), transitions, terminal)
+    }
+
+    /// Returns the automaton as a table with a column per byte found in the utf-8 labels of the dawg, see the `table`
+    /// module. A letter of several bytes leads through states of its own, shared by the letters of a node starting with
+    /// the same bytes
+    pub fn to_byte_transition_table(&self) -> TransitionTable {
+        let nodes = self.visit_nodes_bfs().collect::<Vec<_>>();
+        let mut bytes = nodes.iter().flat_map(|node| node.edges().flat_map(|(label, _)| label.to_string().into_bytes())).collect::<Vec<_>>();
+        bytes.sort_unstable();
+        bytes.dedup();
+
+        let alphabet = Alphabet::Bytes(bytes);
+        let columns = alphabet.len();
+        let mut transitions = vec![u32::MAX; nodes.len() * columns];
+        let mut states = nodes.len();
+        for node in &nodes {
+            for (label, child) in node.edges() {
+                let mut encoded = [0; 4];
+                let (last, leading) = label.encode_utf8(&mut encoded).as_bytes().split_last().unwrap();
+
+                let mut state = node.id();
+                for byte in leading {
+                    let cell = state * columns + alphabet.column_of_byte(*byte).unwrap();
+                    if transitions[cell] == u32::MAX {
+                        transitions[cell] = states as u32;
+                        transitions.resize(transitions.len() + columns, u32::MAX);
+                        states += 1;
+                    }
+                    state = transitions[cell] as usize;
+                }
+                transitions[state * columns + alphabet.column_of_byte(*last).unwrap()] = child as u32;
+            }
+        }
+
+        let terminal = nodes.iter().filter(|node| node.is_terminal()).map(|node| node.id());
+        TransitionTable::new(alphabet, states, transitions, terminal)
+    }
+}
@@ -0,0 +1,69 @@
+#[cfg(feature = "tracing")]
+use std::time::Instant;
+
+/// Enters a `Timed` span named `$name`, declaring the fields `$field` (given with `Timed::record`) and `duration_us`.
+/// Builds nothing without the `tracing` feature
+macro_rules! timed {
+    ($name:literal $(, $field:ident)*) => {{
+        #[cfg(feature = "tracing")]
+        let timed = $crate::dawg::trace::Timed::enter(tracing::info_span!($name, $($field = tracing::field::Empty,)* duration_us = tracing::field::Empty));
+        #[cfg(not(feature = "tracing"))]
+        let timed = $crate::dawg::trace::Timed {};
+        timed
+    }};
+}
+
+pub(crate) use timed;
+
+/// Info span around a long-running operation (a build, a save or a load), entered until it's dropped, when it's given the
+/// time it took in microseconds. Empty without the `tracing` feature, every method being a no-op
+pub(crate) struct Timed {
+    #[cfg(feature = "tracing")]
+    span: tracing::span::EnteredSpan,
+    #[cfg(feature = "tracing")]
+    start: Instant,
+}
+
+impl Timed {
+    #[cfg(feature = "tracing")]
+    pub(crate) fn enter(span: tracing::Span) -> Self {
+        Timed { span: span.entered(), start: Instant::now() }
+    }
+
+    /// Gives `value` to `field`, one of the fields declared by `timed!`
+    pub(crate) fn record(&self, _field: &'static str, _value: usize) {
+        #[cfg(feature = "tracing")]
+        self.span.record(_field, _value as u64);
+    }
+}
+
+#[cfg(feature = "tracing")]
+impl Drop for Timed {
+    fn drop(&mut self) {
+        self.span.record("duration_us", self.start.elapsed().as_micros() as u64);
+    }
+}
+
+/// Number of nodes a search went through, counted by its `BudgetMeter` and reported by a debug event once it's done.
+/// Zero sized without the `tracing` feature
+#[derive(Debug, Clone, Copy, Default)]
+pub(crate) struct Visits {
+    #[cfg(feature = "tracing")]
+    nodes: usize,
+}
+
+impl Visits {
+    /// The visit of `_nodes` nodes
+    pub(crate) fn of(_nodes: usize) -> Self {
+        Visits {
+            #[cfg(feature = "tracing")]
+            nodes: _nodes,
+        }
+    }
+
+    /// Emits the debug event of the search `_search`, which found `_matches` words
+    pub(crate) fn report(self, _search: &'static str, _matches: usize) {
+        #[cfg(feature = "tracing")]
+        tracing::debug!(search = _search, nodes_visited = self.nodes, matches = _matches, "search done");
+    }
+}
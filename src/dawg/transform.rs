@@ -0,0 +1,102 @@
+use crate::{dawg::{common::{Dawg, Label, Wrapper}, iter::Sequences, options::SortDirection}, error::DawgError};
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Keeps only the sequences for which `keep` returns true, returning how many were removed.
+    ///
+    /// The sequences are streamed in sorted order from the current automaton into a new one that is minimized as it is built,
+    /// so only the path being visited is held in memory besides the two automata. The new automaton is built in place: its
+    /// nodes come from the same wrapper and go through the same register (and its budget, if any). The nodes are renumbered
+    pub fn retain_sequences(&mut self, keep: impl FnMut(&[L]) -> bool) -> usize {
+        self.merge_staged();
+        let finished = self.finished;
+        let removed = self.rebuild(keep);
+
+        if finished {
+            self.finish();
+        }
+        removed
+    }
+
+    /// Streams the sequences kept by `keep` into a new automaton built in place (see `retain_sequences`), leaving it
+    /// unfinished. Returns how many were dropped
+    pub(crate) fn rebuild(&mut self, mut keep: impl FnMut(&[L]) -> bool) -> usize {
+        let root = std::mem::replace(&mut self.root, self.node.create());
+        let mut removed = 0;
+
+        self.finished = false;
+        self.edited = false;
+        self.minimized_nodes.release();
+        self.unchecked_nodes = vec![];
+        self.leaf = None;
+        self.previous_word = vec![];
+        self.trie_nodes = 1;
+        self.words_added = 0;
+        self.checkpoints.clear();
+        self.register_log.clear();
+        self.infix_index = None;
+        self.reverse_index = None;
+
+        for sequence in Sequences::new(Some(root), vec![]) {
+            match keep(&sequence) {
+                true => self.push_sequence(&sequence),
+                false => removed += 1,
+            }
+        }
+        removed
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Keeps only the words for which `keep` returns true, returning how many were removed (see `retain_sequences`)
+    pub fn retain(&mut self, mut keep: impl FnMut(&str) -> bool) -> usize {
+        let mut word = String::new();
+
+        self.retain_sequences(|letters| {
+            word.clear();
+            word.extend(letters);
+            keep(&word)
+        })
+    }
+
+    /// Builds a new dawg (with the same options) from `f` applied to every word, words mapped to `None` are dropped.
+    ///
+    /// As long as `f` keeps the words in sorted order (lower-casing an already lower-case list, dropping words, ...)
+    /// its results are streamed straight into the new dawg. From the first result out of order, the remaining ones
+    /// are collected and everything is sorted and added again, so only then are the words held in memory.
+    /// Duplicates produced by `f` are handled by the duplicate policy of the options
+    pub fn map_words(&self, mut f: impl FnMut(&str) -> Option<String>) -> Result<Self, DawgError> {
+        let mut mapped = Self::with_options(self.options.clone())?;
+        let mut unsorted = vec![];
+
+        for word in self.words() {
+            let Some(word) = f(&word) else { continue };
+
+            if !unsorted.is_empty() {
+                unsorted.push(word);
+                continue;
+            }
+
+            match mapped.try_add(&word) {
+                Err(DawgError::Unsorted { .. }) => unsorted.push(word),
+                result => result?,
+            }
+        }
+
+        if !unsorted.is_empty() {
+            mapped.finish();
+            let mut words = mapped.words().chain(unsorted.iter().map(|word| self.options.fold(word))).collect::<Vec<_>>();
+            words.sort_by(|a, b| self.options.sort_order.compare(a, b));
+            if mapped.sort_direction() == Some(SortDirection::Descending) {
+                words.reverse();
+            }
+
+            mapped = Self::with_options(self.options.clone())?;
+            for word in words {
+                mapped.try_add(&word)?;
+            }
+        }
+
+        mapped.finish();
+        Ok(mapped)
+    }
+}
@@ -0,0 +1,183 @@
+use std::{fmt::{Debug, Display}, io::BufRead, sync::Arc};
+
+use crate::{dawg::{common::Wrapper, map::DawgMap, options::DawgOptions, wordlist::{decode_line, snippet, trim_line, LoadReport, Utf8Policy}}, error::DawgError};
+
+/// Parser turning the value column of a line into a value, the error being reported in `DawgError::InvalidValue`
+type ValueParser<V> = Arc<dyn Fn(&str) -> Result<V, String> + Send + Sync>;
+
+/// What `DawgMap::from_tsv` does with a line whose value is missing or can't be parsed
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum ValuePolicy {
+    /// reading stops with `DawgError::MissingColumn` or `DawgError::InvalidValue`
+    #[default]
+    Error,
+    /// the line is skipped, and counted by `LoadReport::invalid_values`
+    Skip,
+}
+
+/// How `DawgMap::from_tsv` reads a delimited file of words and values
+pub struct TsvOptions<V> {
+    options: DawgOptions,
+    key_column: usize,
+    value_column: usize,
+    delimiter: char,
+    header: bool,
+    values: ValuePolicy,
+    utf8: Utf8Policy,
+    parse: ValueParser<V>,
+}
+
+impl<V> Clone for TsvOptions<V> {
+    fn clone(&self) -> Self {
+        Self { parse: Arc::clone(&self.parse), options: self.options.clone(), ..*self }
+    }
+}
+
+impl<V> Debug for TsvOptions<V> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TsvOptions")
+            .field("options", &self.options)
+            .field("key_column", &self.key_column)
+            .field("value_column", &self.value_column)
+            .field("delimiter", &self.delimiter)
+            .field("header", &self.header)
+            .field("values", &self.values)
+            .field("utf8", &self.utf8)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<V> TsvOptions<V> {
+    /// Words in the first column and values in the second, separated by tabs and parsed by `parse`, without a header row
+    pub fn new<E, F>(parse: F) -> Self where E: Display, F: Fn(&str) -> Result<V, E> + Send + Sync + 'static {
+        Self {
+            options: DawgOptions::default(),
+            key_column: 0,
+            value_column: 1,
+            delimiter: '\t',
+            header: false,
+            values: ValuePolicy::default(),
+            utf8: Utf8Policy::default(),
+            parse: Arc::new(move |field| parse(field).map_err(|err| err.to_string())),
+        }
+    }
+
+    /// Options of the dawg being built, whose sort order must follow the letters of the words (see `DawgMap`)
+    pub fn dawg_options(mut self, options: DawgOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Column of the words, counted from 0
+    pub fn key_column(mut self, column: usize) -> Self {
+        self.key_column = column;
+        self
+    }
+
+    /// Column of the values, counted from 0
+    pub fn value_column(mut self, column: usize) -> Self {
+        self.value_column = column;
+        self
+    }
+
+    pub fn delimiter(mut self, delimiter: char) -> Self {
+        self.delimiter = delimiter;
+        self
+    }
+
+    /// Whether the first line names the columns, and is skipped
+    pub fn header(mut self, header: bool) -> Self {
+        self.header = header;
+        self
+    }
+
+    pub fn value_policy(mut self, policy: ValuePolicy) -> Self {
+        self.values = policy;
+        self
+    }
+
+    pub fn utf8_policy(mut self, policy: Utf8Policy) -> Self {
+        self.utf8 = policy;
+        self
+    }
+}
+
+impl TsvOptions<u64> {
+    /// Frequency lists: "word\t12345"
+    pub fn frequencies() -> Self {
+        Self::new(|field: &str| field.trim().parse::<u64>())
+    }
+}
+
+impl TsvOptions<String> {
+    /// Values taken as they are, such as the lemma of each word: "word\tlemma"
+    pub fn strings() -> Self {
+        Self::new(|field: &str| Ok::<_, std::convert::Infallible>(field.to_owned()))
+    }
+}
+
+impl<T, V> DawgMap<T, V> where T: Wrapper {
+    /// Builds a finished map from delimited lines of words and values in a single pass, reading them as configured by
+    /// `options`. The words go through `try_insert`, so they must be sorted, and a duplicate keeps its first value.
+    ///
+    /// Lines are read as in `Dawg::read_words`: blank lines and lines starting with `#` are skipped, and errors are wrapped
+    /// in `DawgError::Line` along with the number of the offending line
+    pub fn from_tsv<R: BufRead>(mut reader: R, options: &TsvOptions<V>) -> Result<(Self, LoadReport), DawgError> {
+        let mut map = Self::with_options(options.options.clone())?;
+        let mut report = LoadReport::default();
+        let mut bytes = vec![];
+
+        loop {
+            bytes.clear();
+            if reader.read_until(b'\n', &mut bytes)? == 0 {
+                break;
+            }
+            report.lines += 1;
+
+            let line = trim_line(&bytes, report.lines == 1);
+            let number = report.lines;
+            let at_line = |line: &str, error: DawgError| DawgError::Line { line: number, snippet: snippet(line), error: Box::new(error) };
+
+            if options.header && number == 1 {
+                report.header += 1;
+                continue;
+            }
+
+            let text = match decode_line(line, options.utf8, &mut report) {
+                Ok(Some(text)) => text,
+                Ok(None) => continue,
+                Err(error) => return Err(at_line(&String::from_utf8_lossy(line), error)),
+            };
+
+            if text.trim().is_empty() {
+                report.blank += 1;
+                continue;
+            } else if text.starts_with('#') {
+                report.comments += 1;
+                continue;
+            }
+
+            let fields = text.split(options.delimiter).collect::<Vec<_>>();
+            let field = |column: usize| fields.get(column).copied().ok_or(DawgError::MissingColumn { column, columns: fields.len() });
+            let entry = field(options.key_column).and_then(|word| {
+                let value = (options.parse)(field(options.value_column)?).map_err(DawgError::InvalidValue)?;
+                Ok((word, value))
+            });
+
+            let (word, value) = match (entry, options.values) {
+                (Ok(entry), _) => entry,
+                (Err(_), ValuePolicy::Skip) => {
+                    report.invalid_values += 1;
+                    continue;
+                }
+                (Err(error), ValuePolicy::Error) => return Err(at_line(&text, error)),
+            };
+
+            map.try_insert(word, value).map_err(|error| at_line(&text, error))?;
+            report.words += 1;
+        }
+
+        map.finish();
+        Ok((map, report))
+    }
+}
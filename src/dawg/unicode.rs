@@ -0,0 +1,35 @@
+//! The Unicode algorithms behind `Normalization`, `Granularity::Graphemes` and `Boundaries::Unicode`: canonical
+//! normalization (UAX #15), extended grapheme clusters and word boundaries (UAX #29), from `unicode-normalization` and
+//! `unicode-segmentation`
+
+use unicode_normalization::UnicodeNormalization;
+use unicode_segmentation::{UnicodeSegmentation, UnicodeWordIndices};
+
+use crate::dawg::options::Normalization;
+
+/// `letters` in the normalization form `form` (as they are for `Normalization::None`)
+pub(crate) fn normalize(letters: impl IntoIterator<Item = char>, form: Normalization) -> Vec<char> {
+    let mut normalized = vec![];
+    normalize_into(letters, form, &mut normalized);
+    normalized
+}
+
+/// Same as `normalize`, appending to `out`
+pub(crate) fn normalize_into(letters: impl IntoIterator<Item = char>, form: Normalization, out: &mut Vec<char>) {
+    let letters = letters.into_iter();
+    match form {
+        Normalization::None => out.extend(letters),
+        Normalization::Nfc => out.extend(letters.nfc()),
+        Normalization::Nfd => out.extend(letters.nfd()),
+    }
+}
+
+/// Splits `text` into its extended grapheme clusters
+pub(crate) fn graphemes(text: &str) -> impl Iterator<Item = &str> {
+    text.graphemes(true)
+}
+
+/// Splits `text` into its words along with their byte offsets, leaving out what is between them (spaces, punctuation, emoji)
+pub(crate) fn words(text: &str) -> UnicodeWordIndices<'_> {
+    text.unicode_word_indices()
+}
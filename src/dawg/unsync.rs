@@ -1,171 +1,114 @@
-use std::{collections::HashMap, rc::Rc, cell::RefCell, cmp};
+use std::{rc::Rc, cell::RefCell};
+
+use crate::dawg::common::{NodeRef, Wrapper, DawgNode, Dawg, Label};
+use crate::dawg::lexicon::{MatchMode, Lexicon};
+use crate::dawg::phrase::PhraseDawg;
+use crate::node::NodeHandle;
+/// Pointer to a node of an unsync Dawg
+#[derive(Debug)]
+pub struct UnsyncNode<L: Label>(Rc<RefCell<DawgNode<UnsyncNode<L>>>>);
+
+impl<L> Clone for UnsyncNode<L> where L: Label {
+    fn clone(&self) -> Self {
+        Self(Rc::clone(&self.0))
+    }
+}
+
+impl<L> NodeRef for UnsyncNode<L> where L: Label {
+    type Label = L;
+
+    fn new(node: DawgNode<Self>) -> Self {
+        Self(Rc::new(RefCell::new(node)))
+    }
 
-use crate::utils::Utils;
+    fn read<R>(&self, f: impl FnOnce(&DawgNode<Self>) -> R) -> R {
+        f(&self.0.borrow())
+    }
+
+    fn write<R>(&self, f: impl FnOnce(&mut DawgNode<Self>) -> R) -> R {
+        f(&mut self.0.borrow_mut())
+    }
+}
 
-use crate::dawg::common::{NodeType, Wrapper, DawgNode, Dawg, TriDawg, SearchReq, SearchRes};
 /// Wrapper for DawgNode to persist the next_id of the DawgNode that would be added to the Dawg
 #[derive(Debug, Clone)]
-struct DawgWrapper {
+pub struct DawgWrapper {
     pub(crate) next_id: usize,
 }
 
-impl Wrapper for DawgWrapper {
+impl<L> Wrapper<L> for DawgWrapper where L: Label {
+    type Node = UnsyncNode<L>;
+
     fn new() -> Self {
         Self { next_id: 0 }
     }
 
-    fn create(&mut self) -> NodeType {
+    fn create(&mut self) -> UnsyncNode<L> {
         let node = DawgNode::new(self.next_id);
         self.next_id += 1;
-        NodeType::Unsync(Rc::new(RefCell::new(node)))
+        UnsyncNode::new(node)
     }
 }
 
 
-impl<T> Dawg<T> where T: Wrapper {
-    pub fn new() -> Dawg<impl Wrapper> {
-        let mut d_w = DawgWrapper::new();
-        
-        Dawg { 
-            root: d_w.create(),
-            node: d_w,
-            minimized_nodes: HashMap::new(),
-            unchecked_nodes: vec![],
-            previous_word: String::new(),
-        }
+/// Dawg whose nodes are shared through `Rc<RefCell<_>>`, labelled by `char` unless stated otherwise
+pub type UnsyncDawg<L = char> = Dawg<DawgWrapper, L>;
+
+/// Phrase dawg backed by the unsync nodes
+pub type UnsyncPhraseDawg = PhraseDawg<DawgWrapper>;
+
+impl<L> Default for Dawg<DawgWrapper, L> where L: Label {
+    /// Equivalent to `Dawg::new()`, so a dawg can sit in a `#[derive(Default)]` struct or be swapped out with `mem::take`
+    ///
+    /// ```
+    /// use dawging::dawg::unsync::UnsyncDawg;
+    ///
+    /// #[derive(Default)]
+    /// struct Dictionary {
+    ///     words: UnsyncDawg,
+    /// }
+    ///
+    /// let mut dictionary = Dictionary::default();
+    /// dictionary.words.add(String::from("word"));
+    ///
+    /// let mut old = std::mem::take(&mut dictionary.words);
+    /// old.finish();
+    /// assert!(old.contains("word"));
+    /// assert!(!dictionary.words.contains("word"));
+    /// ```
+    fn default() -> Self {
+        Self::with_wrapper(<DawgWrapper as Wrapper<L>>::new())
     }
+}
 
-    fn minimize(&mut self, down_to: usize) {
-        let mut start = self.unchecked_nodes.len() as i8 - 1;
-        let end = down_to as i8 - 1;
-
-        while start > end {
-            let i = start as usize;
-            let TriDawg { parent, letter, child } = &mut self.unchecked_nodes[i];
-            let parent = parent.get_unsync().unwrap();
-            let child = child.get_unsync().unwrap();
-            let node = child.try_borrow().unwrap().to_string();
-
-            let exists = self.minimized_nodes.contains_key(node.as_str());
-
-            if exists {
-                let minimized_reference = self.minimized_nodes.get(node.as_str()).unwrap().get_unsync().unwrap();
-                parent.as_ref().borrow_mut().edges.insert(letter.to_owned(), NodeType::Unsync(Rc::clone(minimized_reference)));
-            } else {
-                self.minimized_nodes.insert(node, NodeType::Unsync(Rc::clone(child)));
-            }
-
-            self.unchecked_nodes.pop();
-            start -= 1;
-        }
+impl Dawg<DawgWrapper> {
+    pub fn new() -> Self {
+        Self::default()
     }
 
+    /// Adds `word` with `try_add`, for the callers that know their words are valid
+    ///
+    /// # Panics
+    /// If `try_add` fails: the word sorts before the previous one, or the options reject it
     pub fn add(&mut self, word: String) {
-        if self.previous_word > word {
-            panic!("Error: Please ensure all words are sorted bedore adding");
+        if let Err(err) = self.try_add(&word) {
+            panic!("Error: {err}");
         }
-
-        let mut common_prefix = 0;
-        let word_vec = Utils::split_to_vec(word.to_owned());
-        let prev_word_vec = Utils::split_to_vec(self.previous_word.to_owned());
-
-        let min_length = cmp::min(word_vec.len(), prev_word_vec.len());
-
-        for index in 0..min_length {
-            if word_vec[index] != prev_word_vec[index] {
-                break;
-            }
-            common_prefix += 1;
-        }
-
-        self.minimize(common_prefix);
-
-        for index in common_prefix..word_vec.len() {
-            let letter = word_vec[index].to_owned();
-            let mut node = &self.root;
-            
-            if  self.unchecked_nodes.len() != 0 {
-                let last = self.unchecked_nodes.len() -1;
-                node = &self.unchecked_nodes[last].child;
-            }
-            
-            let next_node = self.node.create();
-            node.get_unsync().unwrap().as_ref().borrow_mut().edges.insert(letter.to_owned(), NodeType::Unsync(Rc::clone(&next_node.get_unsync().unwrap())));
-
-            let tridawg = TriDawg::new(NodeType::Unsync(Rc::clone(node.get_unsync().unwrap())), letter, NodeType::Unsync(Rc::clone(next_node.get_unsync().unwrap())));
-            self.unchecked_nodes.push(tridawg);
-        }
-
-        let last_unchecked = self.unchecked_nodes.len() -1;
-        let node = &mut self.unchecked_nodes[last_unchecked].child.get_unsync().unwrap().as_ref().borrow_mut();
-        node.terminal = true;
-        self.previous_word = word;
-    }
-
-    pub fn finish(&mut self) {
-        self.minimize(0);
-        self.root.get_unsync().unwrap().as_ref().borrow_mut().num_reachable();
-        self.minimized_nodes = HashMap::new();
-        self.unchecked_nodes = vec![];
-    }
-
-    fn find(&self, word: &String, return_type: SearchReq, case_sensitive: bool) -> Option<SearchRes<Rc<RefCell<DawgNode>>>> {
-        let mut node = Rc::clone(&self.root.get_unsync().unwrap());
-        let word_vec = Utils::split_to_vec(word.to_owned());
-
-        for i in 0..word.len() {
-            let letter = word_vec[i].to_string();
-            let keys = node.as_ref().borrow().edges.keys().collect::<Vec<_>>().iter().map(|x| x.to_string()).collect::<Vec<_>>();
-
-            match case_sensitive {
-                true => {
-                    if keys.contains(&letter) {
-                        // let nnnode = ;
-                        let next_node = Rc::clone(node.as_ref().borrow().edges[&letter].get_unsync().unwrap());
-                        node = next_node;
-                    } else {
-                        return None;
-                    }
-                }
-                false => {
-                    let modified_keys = keys.iter().map(|x| x.to_uppercase()).collect::<Vec<_>>();
-                    let letter = letter.to_uppercase();
-
-
-                    if let Some(index) = modified_keys.iter().position(|x| x == &letter) {
-                        let actual_key = keys[index].to_owned();
-                        let next_node = Rc::clone(&node.as_ref().borrow().edges[&actual_key].get_unsync().unwrap());
-                        node = next_node;
-                    } else {
-                        return None;
-                    }
-                }
-            }
-        }
-
-        return Some(SearchRes::new(node, word.to_owned()))
     }
 
     /// Given a specific word, check if the word exists in the lexicon (Allowing search to be case sensitive or insensitive)
+    #[deprecated(note = "use `contains` or `contains_case_insensitive`")]
     pub fn is_word(&self, word: String, case_sensitive: bool) -> Option<String> {
-        if let Some(context) = self.find(&word, SearchReq::Word, case_sensitive) {
-            if context.node.as_ref().borrow().terminal {
-                return Some(context.word)
-            }
+        if Lexicon::contains(self, &word, MatchMode::from(case_sensitive)) {
+            return Some(word)
         }
 
         None
     }
 
-    /// find out if word is a prefix of anything in the dictionary
-    pub fn lookup(&self, word: String, case_sensitive: bool) -> Option<Rc<RefCell<DawgNode>>> {
-        if let Some(context) = self.find(&word, SearchReq::Vertex, case_sensitive) {
-            if context.node.as_ref().borrow().terminal {
-                return Some(context.node)
-            }
-        }
-        None
+    /// Returns the node `word` ends on if it is a word of the lexicon
+    #[deprecated(note = "use `get`, which returns the node through `Match::node`")]
+    pub fn lookup(&self, word: String, case_sensitive: bool) -> Option<NodeHandle<'_, UnsyncNode<char>>> {
+        self.get_with(&word, MatchMode::from(case_sensitive)).map(|found| found.into_node())
     }
-
-
 }
@@ -0,0 +1,93 @@
+use crate::dawg::{common::{Dawg, NodeOps, NodeRef, Wrapper}, iter::{Sequences, Words}, lexicon::{Lexicon, MatchMode}, register::Register};
+
+/// Read-only part of a dawg made of the words starting with a prefix, as returned by `Dawg::prefix_view`.
+/// Queries are about what follows the prefix: `contains("happy")` on the view of "un" is `contains("unhappy")` on the dawg
+#[derive(Debug)]
+pub struct DawgView<'a, T: Wrapper> {
+    dawg: &'a Dawg<T>,
+    node: T::Node,
+    prefix: String,
+    /// value is true if words are returned with the prefix in front of them
+    full_words: bool,
+}
+
+impl<'a, T> DawgView<'a, T> where T: Wrapper {
+    /// Makes `words` and `completions` return whole words (prefix included) instead of what follows the prefix
+    pub fn full_words(mut self, full_words: bool) -> Self {
+        self.full_words = full_words;
+        self
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    fn start(&self) -> Vec<char> {
+        match self.full_words {
+            true => self.prefix.chars().collect(),
+            false => vec![],
+        }
+    }
+
+    /// Returns the words of the view in sorted order
+    pub fn words(&self) -> Words<'a, T::Node> {
+        Words::new(Sequences::new(Some(self.node.clone()), self.start()))
+    }
+
+    /// Builds a dawg (with the options of the parent) holding the words of the view, as `words` returns them
+    pub fn to_dawg(&self) -> Dawg<T> {
+        let mut dawg = Dawg::with_wrapper(T::new());
+        dawg.options = self.dawg.options.clone();
+        dawg.minimized_nodes = Register::with_hasher(dawg.options.hasher);
+
+        for word in self.words() {
+            dawg.push_sequence(&word.chars().collect::<Vec<_>>());
+        }
+
+        dawg.finish();
+        dawg
+    }
+}
+
+impl<'a, T> Lexicon for DawgView<'a, T> where T: Wrapper {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        self.dawg.find_chars_from(self.node.clone(), word.chars(), mode, false).iter().any(|res| res.node.is_terminal())
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        self.dawg.find_chars_from(self.node.clone(), prefix.chars(), mode, false).iter()
+            .any(|res| res.node.is_terminal() || res.node.read(|node| !node.edges.is_empty()))
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        let mut words = vec![];
+
+        for res in self.dawg.find_chars_from(self.node.clone(), prefix.chars(), mode, true) {
+            let mut start = self.start();
+            start.extend(res.word.chars());
+            words.extend(Words::new(Sequences::new(Some(res.node), start)));
+        }
+
+        words.sort();
+        words.dedup();
+        words
+    }
+
+    fn word_count(&self) -> usize {
+        self.node.reachable()
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns a view of the words starting with `prefix` (matched exactly), borrowing the nodes of the dawg, if there is any
+    pub fn prefix_view(&self, prefix: &str) -> Option<DawgView<'_, T>> {
+        let prefix = self.options.fold(prefix);
+        let node = self.find_sequence(&prefix.chars().collect::<Vec<_>>())?;
+
+        if !node.is_terminal() && node.read(|node| node.edges.is_empty()) {
+            return None;
+        }
+
+        Some(DawgView { dawg: self, node, prefix, full_words: false })
+    }
+}
@@ -0,0 +1,210 @@
+use std::{collections::{HashMap, HashSet, VecDeque}, marker::PhantomData, ops::ControlFlow};
+
+use crate::dawg::common::{Dawg, Label, NodeOps, NodeRef, Wrapper};
+
+/// What `Dawg::visit_words` tells the visitor about the node the prefix leads to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct NodeInfo {
+    terminal: bool,
+    count: usize,
+    depth: usize,
+}
+
+impl NodeInfo {
+    /// value is true if the prefix is a word
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    /// number of words starting with the prefix, itself included (valid once the dawg is finished)
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// number of letters of the prefix
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+}
+
+/// Whether `Dawg::visit_words` goes on below the node it just visited
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Descend {
+    /// visit the children of the node
+    #[default]
+    Continue,
+    /// leave the words below the node out and move on to its next sibling
+    Skip,
+}
+
+/// Read-only snapshot of a single node of the Dawg, as yielded by `Dawg::visit_nodes`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NodeView<'a, L = char> {
+    id: usize,
+    root: bool,
+    terminal: bool,
+    count: usize,
+    edges: Vec<(L, usize)>,
+    dawg: PhantomData<&'a ()>,
+}
+
+impl<'a, L> NodeView<'a, L> {
+    /// dense id of this node in 0..node_count
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    /// value is true if this is the root of the dawg
+    pub fn is_root(&self) -> bool {
+        self.root
+    }
+
+    /// value is true if this node is the end of a word
+    pub fn is_terminal(&self) -> bool {
+        self.terminal
+    }
+
+    /// number of words that can be formed from this node downwards (valid once the dawg is finished)
+    pub fn count(&self) -> usize {
+        self.count
+    }
+
+    /// (label, child id) pairs leaving this node in sorted label order
+    pub fn edges(&self) -> impl Iterator<Item = (&L, usize)> + '_ {
+        self.edges.iter().map(|(label, id)| (label, *id))
+    }
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Yields every node reachable from the root exactly once, children before their parents (reverse topological order),
+    /// so the root comes last.
+    ///
+    /// Nodes are numbered 0..node_count breadth first from the root: the root is 0, and the edges of each node are followed
+    /// in sorted label order, a node being numbered when it is first reached. Nodes walked through by the same prefixes
+    /// thus get close ids, and repeated traversals of the same dawg produce the same ids and the same order.
+    /// Once the dawg is finished these are also the ids of its nodes (see `NodeHandle::id`). `visit_nodes_bfs` yields the
+    /// nodes in the order of their ids instead
+    pub fn visit_nodes(&self) -> impl Iterator<Item = NodeView<'_, L>> {
+        let ids = self.breadth_first().iter().enumerate().map(|(id, node)| (node.id(), id)).collect::<HashMap<_, _>>();
+        self.postorder().into_iter().map(move |node| view(&node, &ids))
+    }
+
+    /// Same as `visit_nodes`, yielding the nodes in the order of their ids: breadth first from the root, parents before
+    /// their children
+    pub fn visit_nodes_bfs(&self) -> impl Iterator<Item = NodeView<'_, L>> {
+        let nodes = self.breadth_first();
+        let ids = nodes.iter().enumerate().map(|(id, node)| (node.id(), id)).collect::<HashMap<_, _>>();
+        nodes.into_iter().map(move |node| view(&node, &ids))
+    }
+
+    /// value is true if no two nodes of the dawg accept the same suffixes, i.e. the automaton can't be made any smaller.
+    /// Checked bottom up: once the nodes below are distinct, two nodes are equivalent only if they look the same
+    pub fn verify_minimal(&self) -> bool {
+        let mut seen = HashSet::new();
+        self.visit_nodes().all(|view| seen.insert((view.terminal, view.edges)))
+    }
+
+    /// Every node reachable from the root exactly once, in the order `visit_nodes_bfs` yields them
+    pub(crate) fn breadth_first(&self) -> Vec<T::Node> {
+        let mut seen = HashSet::from([self.root.id()]);
+        let mut queue = VecDeque::from([self.root.clone()]);
+        let mut nodes = vec![];
+
+        while let Some(node) = queue.pop_front() {
+            for (_, child) in node.children() {
+                if seen.insert(child.id()) {
+                    queue.push_back(child);
+                }
+            }
+            nodes.push(node);
+        }
+
+        nodes
+    }
+
+    /// Every node reachable from the root exactly once, in the order `visit_nodes` yields them
+    pub(crate) fn postorder(&self) -> Vec<T::Node> {
+        let mut seen = HashSet::new();
+        let mut nodes = vec![];
+        let mut stack: Vec<(T::Node, Vec<_>, usize)> = vec![(self.root.clone(), self.root.children(), 0)];
+
+        while let Some((node, children, next)) = stack.last_mut() {
+            if let Some((_, child)) = children.get(*next) {
+                let child = child.clone();
+                *next += 1;
+
+                if !seen.contains(&child.id()) {
+                    let grand_children = child.children();
+                    stack.push((child, grand_children, 0));
+                }
+                continue;
+            }
+
+            seen.insert(node.id());
+            nodes.push(node.clone());
+            stack.pop();
+        }
+
+        nodes
+    }
+
+    /// Renumbers the nodes with the ids `visit_nodes` gives them, which only depend on the words of the dawg, and returns
+    /// the number of nodes. The ids the wrapper hands out afterwards are never lower than the number of nodes, so they
+    /// can't collide
+    pub(crate) fn canonicalize_ids(&self) -> usize {
+        let nodes = self.breadth_first();
+        let count = nodes.len();
+        for (id, node) in nodes.into_iter().enumerate() {
+            node.write(|node| node.id = id);
+        }
+        count
+    }
+}
+
+/// Snapshot of `node`, whose id and the ids of its children are looked up in `ids`
+pub(crate) fn view<'a, N: NodeRef>(node: &N, ids: &HashMap<usize, usize>) -> NodeView<'a, N::Label> {
+    let id = ids[&node.id()];
+    NodeView {
+        id,
+        root: id == 0,
+        terminal: node.is_terminal(),
+        count: node.count(),
+        edges: node.children().into_iter().map(|(label, child)| (label, ids[&child.id()])).collect(),
+        dawg: PhantomData,
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Calls `f` on every path from the root, depth first in sorted order: the root with the empty prefix, then each child
+    /// with the prefix extended by the label of its edge. `Descend::Skip` leaves out the words below the node just visited,
+    /// `ControlFlow::Break` stops the whole traversal, which then returns it.
+    ///
+    /// The prefix is spelled in a single buffer, shortened and extended as the walk goes up and down, so visiting a node
+    /// allocates nothing beyond the list of its children. Nodes shared by several prefixes are visited once per prefix
+    pub fn visit_words<F>(&self, mut f: F) -> ControlFlow<()> where F: FnMut(&str, &NodeInfo) -> ControlFlow<(), Descend> {
+        let info = |node: &T::Node, depth| NodeInfo { terminal: node.is_terminal(), count: node.count(), depth };
+        let mut prefix = String::new();
+
+        if f(&prefix, &info(&self.root, 0))? == Descend::Skip {
+            return ControlFlow::Continue(());
+        }
+
+        // the children of every node of the path, the next one to visit, and the length of the prefix leading to them
+        let mut stack = vec![(self.root.children(), 0, 0)];
+        while let Some((children, next, len)) = stack.last_mut() {
+            let Some((label, child)) = children.get(*next).cloned() else {
+                stack.pop();
+                continue;
+            };
+            *next += 1;
+            prefix.truncate(*len);
+            prefix.push(label);
+
+            if f(&prefix, &info(&child, stack.len()))? == Descend::Continue {
+                stack.push((child.children(), 0, prefix.len()));
+            }
+        }
+
+        ControlFlow::Continue(())
+    }
+}
@@ -0,0 +1,223 @@
+use std::{collections::BTreeMap, marker::PhantomData};
+
+use crate::{dawg::common::{Dawg, NodeOps, NodeRef, Wrapper}, error::DawgError};
+
+/// Colour of a square of a Wordle guess
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Feedback {
+    /// the letter is in the word at this position
+    Green,
+    /// the letter is in the word, elsewhere
+    Yellow,
+    /// the word holds no more of the letter than the guess has green or yellow squares of it
+    Gray,
+}
+
+impl Feedback {
+    /// Reads `g` as green, `y` as yellow and `.`, `-`, `_`, `b` or `x` as gray, whatever their case
+    pub fn parse(feedback: &str) -> Result<Vec<Feedback>, DawgError> {
+        feedback.chars().map(|square| match square.to_ascii_lowercase() {
+            'g' => Ok(Feedback::Green),
+            'y' => Ok(Feedback::Yellow),
+            '.' | '-' | '_' | 'b' | 'x' => Ok(Feedback::Gray),
+            _ => Err(DawgError::InvalidValue(format!("'{square}' is not a Wordle colour"))),
+        }).collect()
+    }
+}
+
+/// What is known of a word of a given length after Wordle guesses, see `Dawg::wordle_filter`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordleConstraints {
+    greens: Vec<Option<char>>,
+    /// letters known not to be at each position
+    not_at: Vec<Vec<char>>,
+    /// fewest times each letter is known to be in the word
+    min_counts: BTreeMap<char, usize>,
+    /// most times each letter can be in the word, any number if it has no entry
+    max_counts: BTreeMap<char, usize>,
+}
+
+impl WordleConstraints {
+    /// No constraint on words of `len` letters
+    pub fn new(len: usize) -> Self {
+        Self { greens: vec![None; len], not_at: vec![vec![]; len], min_counts: BTreeMap::new(), max_counts: BTreeMap::new() }
+    }
+
+    pub fn len(&self) -> usize {
+        self.greens.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.greens.is_empty()
+    }
+
+    /// The word has `letter` at `position`, which panics if it is out of the word
+    pub fn green(mut self, position: usize, letter: char) -> Self {
+        self.greens[position] = Some(letter);
+        self
+    }
+
+    /// The word doesn't have `letter` at `position`, which panics if it is out of the word
+    pub fn not_at(mut self, position: usize, letter: char) -> Self {
+        self.exclude(position, letter);
+        self
+    }
+
+    /// The word has `letter` at least `count` times
+    pub fn min_count(mut self, letter: char, count: usize) -> Self {
+        self.raise_min(letter, count);
+        self
+    }
+
+    /// The word has `letter` at most `count` times
+    pub fn max_count(mut self, letter: char, count: usize) -> Self {
+        self.lower_max(letter, count);
+        self
+    }
+
+    fn exclude(&mut self, position: usize, letter: char) {
+        if !self.not_at[position].contains(&letter) {
+            self.not_at[position].push(letter);
+        }
+    }
+
+    fn raise_min(&mut self, letter: char, count: usize) {
+        let min = self.min_counts.entry(letter).or_default();
+        *min = count.max(*min);
+    }
+
+    fn lower_max(&mut self, letter: char, count: usize) {
+        let max = self.max_counts.entry(letter).or_insert(count);
+        *max = count.min(*max);
+    }
+
+    /// Adds what `feedback` tells of the word about `guess`, the way Wordle colours duplicate letters: a letter is in the
+    /// word at least as many times as it has green or yellow squares, and exactly that many times if one of its squares
+    /// is gray (so a yellow and a gray of the same letter mean the word has it once, elsewhere than on both squares)
+    pub fn add_guess(&mut self, guess: &str, feedback: &[Feedback]) -> Result<(), DawgError> {
+        let letters = guess.chars().collect::<Vec<_>>();
+        if letters.len() != self.len() || feedback.len() != self.len() {
+            return Err(DawgError::InvalidValue(format!("a guess of {} letters with {} colours for words of {}", letters.len(), feedback.len(), self.len())));
+        }
+
+        let mut found = BTreeMap::<char, usize>::new();
+        let mut grayed = vec![];
+        for (position, (letter, colour)) in letters.iter().zip(feedback).enumerate() {
+            match colour {
+                Feedback::Green => {
+                    self.greens[position] = Some(*letter);
+                    *found.entry(*letter).or_default() += 1;
+                }
+                Feedback::Yellow => {
+                    self.exclude(position, *letter);
+                    *found.entry(*letter).or_default() += 1;
+                }
+                Feedback::Gray => {
+                    self.exclude(position, *letter);
+                    grayed.push(*letter);
+                }
+            }
+        }
+
+        for (letter, count) in &found {
+            self.raise_min(*letter, *count);
+        }
+        for letter in grayed {
+            let count = found.get(&letter).copied().unwrap_or(0);
+            self.lower_max(letter, count);
+        }
+        Ok(())
+    }
+
+    /// value is true if `letter` can be put at `position` after the letters counted by `counts`
+    fn allows(&self, position: usize, letter: char, counts: &BTreeMap<char, usize>) -> bool {
+        let count = counts.get(&letter).copied().unwrap_or(0) + 1;
+        self.greens[position].is_none_or(|green| green == letter)
+            && !self.not_at[position].contains(&letter)
+            && self.max_counts.get(&letter).is_none_or(|max| count <= *max)
+    }
+
+    /// number of letters still needed to reach the minimum counts, given the letters counted by `counts`
+    fn missing(&self, counts: &BTreeMap<char, usize>) -> usize {
+        self.min_counts.iter().map(|(letter, min)| min.saturating_sub(counts.get(letter).copied().unwrap_or(0))).sum()
+    }
+}
+
+/// Iterator over the words of a Dawg meeting WordleConstraints, returned by `Dawg::wordle_filter`
+#[derive(Debug)]
+pub struct WordleMatches<'a, N: NodeRef<Label = char>> {
+    constraints: WordleConstraints,
+    /// edges left to follow at each position of the word being spelt
+    stack: Vec<std::vec::IntoIter<(char, N)>>,
+    word: Vec<char>,
+    /// how many times each letter is in the word being spelt
+    counts: BTreeMap<char, usize>,
+    dawg: PhantomData<&'a ()>,
+}
+
+impl<N> WordleMatches<'_, N> where N: NodeRef<Label = char> {
+    /// the edges of `node` that can be the letter at `position`, leaving enough squares for the letters still needed
+    fn candidates(&self, node: &N, position: usize) -> std::vec::IntoIter<(char, N)> {
+        let mut edges = node.children();
+        let squares_left = self.constraints.len() - position - 1;
+        let missing = self.constraints.missing(&self.counts);
+
+        edges.retain(|(letter, _)| {
+            let needed = self.constraints.min_counts.get(letter).is_some_and(|min| self.counts.get(letter).copied().unwrap_or(0) < *min);
+            self.constraints.allows(position, *letter, &self.counts) && missing - needed as usize <= squares_left
+        });
+        edges.into_iter()
+    }
+
+    fn pop_letter(&mut self) {
+        if let Some(letter) = self.word.pop() {
+            if let Some(count) = self.counts.get_mut(&letter) {
+                *count -= 1;
+            }
+        }
+    }
+}
+
+impl<N> Iterator for WordleMatches<'_, N> where N: NodeRef<Label = char> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        loop {
+            let position = self.stack.len();
+            match self.stack.last_mut()?.next() {
+                None => {
+                    self.stack.pop();
+                    self.pop_letter();
+                }
+                Some((letter, child)) => {
+                    self.word.push(letter);
+                    *self.counts.entry(letter).or_default() += 1;
+
+                    if position == self.constraints.len() {
+                        let word = (child.is_terminal() && self.constraints.missing(&self.counts) == 0).then(|| self.word.iter().collect());
+                        self.pop_letter();
+                        if word.is_some() {
+                            return word;
+                        }
+                    } else {
+                        let next = self.candidates(&child, position);
+                        self.stack.push(next);
+                    }
+                }
+            }
+        }
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns in sorted order the words meeting `constraints`. The walk leaves every edge whose letter can't be at its
+    /// position, would be one too many of its letter, or leaves too few squares for the letters the word still needs
+    pub fn wordle_filter(&self, constraints: &WordleConstraints) -> WordleMatches<'_, T::Node> {
+        let mut matches = WordleMatches { constraints: constraints.clone(), stack: vec![], word: vec![], counts: BTreeMap::new(), dawg: PhantomData };
+        if !constraints.is_empty() {
+            let root = matches.candidates(&self.root, 0);
+            matches.stack.push(root);
+        }
+        matches
+    }
+}
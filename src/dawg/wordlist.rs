@@ -0,0 +1,132 @@
+use std::{borrow::Cow, io::BufRead};
+
+use crate::{dawg::{common::{Dawg, Wrapper}, options::DawgOptions, trace}, error::DawgError};
+
+/// Longest part of a line quoted by the errors of `read_words`
+const SNIPPET_CHARS: usize = 40;
+
+/// What `read_words` does with a line that isn't valid utf-8
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum Utf8Policy {
+    /// reading stops with `DawgError::InvalidUtf8`
+    #[default]
+    Error,
+    /// the line is skipped, and counted by `LoadReport::invalid_utf8`
+    Skip,
+    /// the invalid bytes are replaced with U+FFFD, and the line counted by `LoadReport::lossy`
+    Lossy,
+}
+
+/// What `read_words` made of each line of a word file
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct LoadReport {
+    /// lines read, whatever became of them
+    pub lines: usize,
+    /// lines added as words (duplicates included)
+    pub words: usize,
+    /// lines starting with `#`
+    pub comments: usize,
+    /// empty lines and lines of whitespace only
+    pub blank: usize,
+    /// lines skipped for not being valid utf-8
+    pub invalid_utf8: usize,
+    /// lines added after replacing their invalid utf-8
+    pub lossy: usize,
+    /// header rows skipped (by `DawgMap::from_tsv`)
+    pub header: usize,
+    /// lines skipped for a missing column or a value that can't be parsed (by `DawgMap::from_tsv`)
+    pub invalid_values: usize,
+}
+
+impl LoadReport {
+    /// Number of lines that weren't added as words
+    pub fn skipped(&self) -> usize {
+        self.comments + self.blank + self.invalid_utf8 + self.header + self.invalid_values
+    }
+}
+
+/// `line` without its line ending, nor the BOM that may start the first line of a file
+pub(crate) fn trim_line(line: &[u8], first: bool) -> &[u8] {
+    let mut line = line.strip_suffix(b"\n").unwrap_or(line);
+    line = line.strip_suffix(b"\r").unwrap_or(line);
+    match first {
+        true => line.strip_prefix(b"\xEF\xBB\xBF").unwrap_or(line),
+        false => line,
+    }
+}
+
+/// `line` as a str under `utf8`, `Ok(None)` if the line is skipped. The error isn't wrapped in `DawgError::Line` yet
+pub(crate) fn decode_line<'l>(line: &'l [u8], utf8: Utf8Policy, report: &mut LoadReport) -> Result<Option<Cow<'l, str>>, DawgError> {
+    match std::str::from_utf8(line) {
+        Ok(line) => Ok(Some(line.into())),
+        Err(_) => match utf8 {
+            Utf8Policy::Error => Err(DawgError::InvalidUtf8),
+            Utf8Policy::Skip => {
+                report.invalid_utf8 += 1;
+                Ok(None)
+            }
+            Utf8Policy::Lossy => {
+                report.lossy += 1;
+                Ok(Some(String::from_utf8_lossy(line)))
+            }
+        },
+    }
+}
+
+/// Start of `line`, short enough to be quoted in an error
+pub(crate) fn snippet(line: &str) -> String {
+    match line.char_indices().nth(SNIPPET_CHARS) {
+        Some((end, _)) => format!("{}…", &line[..end]),
+        None => line.to_owned(),
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Adds the words of a word file, one per line, going through `try_add`.
+    ///
+    /// Lines may end with `\n` or `\r\n`, a utf-8 BOM on the first line is dropped, and blank lines and lines starting with `#`
+    /// are skipped (so a word can't start with `#`). Errors are wrapped in `DawgError::Line`, which gives the 1-based number
+    /// of the offending line and its start
+    pub fn read_words<R: BufRead>(&mut self, mut reader: R, utf8: Utf8Policy) -> Result<LoadReport, DawgError> {
+        let mut report = LoadReport::default();
+        let mut bytes = vec![];
+
+        loop {
+            bytes.clear();
+            if reader.read_until(b'\n', &mut bytes)? == 0 {
+                return Ok(report);
+            }
+            report.lines += 1;
+
+            let line = trim_line(&bytes, report.lines == 1);
+            let number = report.lines;
+            let at_line = |line: &str, error: DawgError| DawgError::Line { line: number, snippet: snippet(line), error: Box::new(error) };
+
+            let word = match decode_line(line, utf8, &mut report) {
+                Ok(Some(word)) => word,
+                Ok(None) => continue,
+                Err(error) => return Err(at_line(&String::from_utf8_lossy(line), error)),
+            };
+
+            if word.trim().is_empty() {
+                report.blank += 1;
+            } else if word.starts_with('#') {
+                report.comments += 1;
+            } else {
+                self.try_add(&word).map_err(|error| at_line(&word, error))?;
+                report.words += 1;
+            }
+        }
+    }
+
+    /// Builds a finished dawg configured by `options` from a word file (see `read_words`)
+    pub fn from_reader<R: BufRead>(reader: R, options: DawgOptions, utf8: Utf8Policy) -> Result<(Self, LoadReport), DawgError> {
+        let span = trace::timed!("from_reader", words, nodes_created);
+        let mut dawg = Self::with_options(options)?;
+        let report = dawg.read_words(reader, utf8)?;
+        span.record("words", report.words);
+        span.record("nodes_created", dawg.trie_nodes - 1);
+        dawg.finish();
+        Ok((dawg, report))
+    }
+}
@@ -0,0 +1,122 @@
+use std::{collections::TryReserveError, error::Error, fmt::Display, io};
+
+use crate::dawg::{integrity::Section, options::{Granularity, Normalization}};
+
+/// Everything that can go wrong while building, saving or loading a Dawg
+#[derive(Debug)]
+pub enum DawgError {
+    /// reading or writing the underlying stream failed
+    Io(io::Error),
+    /// the bytes being loaded aren't a Dawg saved by this crate
+    InvalidFormat(String),
+    /// the saved dawg has a `version` of the layout newer than the latest one this version of the crate reads, `supported`
+    NewerVersion { version: u16, supported: u16 },
+    /// the checksum of a section of a saved dawg doesn't match its bytes: `expected` was saved along with the section,
+    /// `found` was computed from what was read
+    Corrupted { section: Section, expected: u32, found: u32 },
+    /// the word count saved for `node` isn't the number of words below it: the checksums match, so the file was written
+    /// that way rather than damaged afterwards
+    CorruptedCount { node: u32, stored: u64, actual: u64 },
+    /// the saved dawg ends before the end of `section`
+    Truncated { section: Section },
+    /// the DawgOptions combine settings that can't work together, or can't be used the way they were asked to
+    InvalidOptions(OptionsConflict),
+    /// the word was added after a word that sorts after it in `order` (the name of the `SortOrder` it was checked against)
+    Unsorted { previous: String, word: String, order: &'static str },
+    /// the word was already added and the duplicate policy rejects duplicates
+    Duplicate(String),
+    /// the word contains a character outside of the configured alphabet
+    InvalidCharacter { word: String, character: char },
+    /// the checkpoint was dropped by `finish` or by rolling back to an earlier one
+    InvalidCheckpoint,
+    /// the operation needs a finished dawg, see `Dawg::finish`
+    NotFinished,
+    /// a line of a word file isn't valid utf-8
+    InvalidUtf8,
+    /// a line of a delimited file has `columns` columns, too few to hold `column` (counted from 0)
+    MissingColumn { column: usize, columns: usize },
+    /// a value can't be used, for the given reason: the value column of a line of a delimited file that can't be parsed,
+    /// a malformed Wordle guess, or the rows, weights or letters given to a query not fitting together
+    InvalidValue(String),
+    /// `error` was caused by the line numbered `line` (from 1) of a word file, which starts with `snippet`
+    Line { line: usize, snippet: String, error: Box<DawgError> },
+    /// no word of the forest has the key of the shard asked for
+    MissingShard(String),
+}
+
+impl Display for DawgError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DawgError::Io(err) => write!(f, "{err}"),
+            DawgError::InvalidFormat(reason) => write!(f, "invalid dawg file: {reason}"),
+            DawgError::NewerVersion { version, supported } => write!(f, "the dawg file was produced by a newer dawging (layout version {version}, this one reads up to {supported})"),
+            DawgError::Corrupted { section, expected, found } => write!(f, "the {section} of the dawg file is corrupted (checksum {found:08x}, expected {expected:08x})"),
+            DawgError::CorruptedCount { node, stored, actual } => write!(f, "node {node} of the dawg file counts {stored} words but {actual} can be formed from it"),
+            DawgError::Truncated { section } => write!(f, "the dawg file ends in the middle of its {section}"),
+            DawgError::InvalidOptions(conflict) => write!(f, "invalid options: {conflict}"),
+            DawgError::Unsorted { previous, word, order } => write!(f, "Please ensure all words are sorted ({order} order) before adding (\"{word}\" was added after \"{previous}\")"),
+            DawgError::Duplicate(word) => write!(f, "\"{word}\" was added more than once"),
+            DawgError::InvalidCharacter { word, character } => write!(f, "\"{word}\" contains '{character}' which is not in the alphabet"),
+            DawgError::InvalidCheckpoint => write!(f, "the checkpoint can no longer be rolled back to"),
+            DawgError::NotFinished => write!(f, "the dawg must be finished first"),
+            DawgError::InvalidUtf8 => write!(f, "the line is not valid utf-8"),
+            DawgError::MissingColumn { column, columns } => write!(f, "the line has no column {column} (it has {columns})"),
+            DawgError::InvalidValue(reason) => write!(f, "invalid value: {reason}"),
+            DawgError::Line { line, snippet, error } => write!(f, "line {line} (\"{snippet}\"): {error}"),
+            DawgError::MissingShard(key) => write!(f, "the forest has no shard of key \"{key}\""),
+        }
+    }
+}
+
+/// Why `DawgOptions` were rejected, see `DawgError::InvalidOptions`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OptionsConflict {
+    /// the letter is in the alphabet, but case folding or normalization means no word can contain it
+    UnreachableLetter(char),
+    /// the granularity only takes words in the ascending order of their labels, which the sort order or direction isn't
+    DescendingGranularity(Granularity),
+    /// byte granularity can't be combined with a normalization, which works on chars
+    GranularityNormalization(Normalization),
+    /// the dawgs store or match their words differently (case folding, alphabet, match mode, granularity or normalization),
+    /// so their words can't be merged
+    NotMergeable,
+    /// the labels of the dawg split words by `labels`, not by the granularity of the options
+    LabelGranularity { labels: Granularity, options: Granularity },
+    /// `by` (a streaming build, a DawgMap) only takes words in the ascending order of their letters
+    LetterOrder { by: &'static str },
+    /// the register can't be sized for a capacity of `nodes` nodes
+    Capacity { nodes: usize, error: TryReserveError },
+    /// the register budget was set once words had been added
+    LateRegisterBudget,
+}
+
+impl Display for OptionsConflict {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            OptionsConflict::UnreachableLetter(letter) => write!(f, "'{letter}' is in the alphabet but case folding or normalization means no word can contain it"),
+            OptionsConflict::DescendingGranularity(granularity) => write!(f, "{granularity:?} granularity only takes words in the ascending order of their labels"),
+            OptionsConflict::GranularityNormalization(normalization) => write!(f, "byte granularity can't be combined with {normalization:?} normalization, which works on chars"),
+            OptionsConflict::NotMergeable => write!(f, "dawgs storing or matching their words differently can't be merged"),
+            OptionsConflict::LabelGranularity { labels, options } => write!(f, "the labels of this dawg split words by {labels:?}, not {options:?}"),
+            OptionsConflict::LetterOrder { by } => write!(f, "{by} only takes words in ascending order of their letters"),
+            OptionsConflict::Capacity { nodes, error } => write!(f, "a capacity of {nodes} nodes can't be reserved: {error}"),
+            OptionsConflict::LateRegisterBudget => write!(f, "the register budget must be set before adding words"),
+        }
+    }
+}
+
+impl Error for DawgError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DawgError::Io(err) => Some(err),
+            DawgError::Line { error, .. } => Some(error.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for DawgError {
+    fn from(err: io::Error) -> Self {
+        DawgError::Io(err)
+    }
+}
@@ -0,0 +1,160 @@
+//! Reading the words of a saved Dawg straight from its file, without building it.
+//!
+//! The nodes, edges and labels of the file are read as the walk reaches them, through a small buffer per section, so the
+//! memory used is bounded by the length of the longest word whatever the size of the dawg
+
+use std::{fs::File, io::{self, BufReader, Read, Seek, SeekFrom}, path::Path};
+
+use crate::{dawg::{integrity::{self, Section}, serialize::{Cursor, EncodeLabel, SavedHeader, EDGE_SIZE, NODE_SIZE}}, error::DawgError};
+
+/// Number of bytes of a section read from the file at once
+const WINDOW_SIZE: usize = 4096;
+
+/// Returns the words of the Dawg saved at `path` (see `Dawg::save`) in sorted order, see `FileWords`
+pub fn words_in(path: impl AsRef<Path>) -> Result<FileWords<BufReader<File>>, DawgError> {
+    FileWords::new(BufReader::new(File::open(path)?))
+}
+
+/// Bytes of a section of the file held in memory, starting at `start` in the file
+#[derive(Debug, Default)]
+struct Window {
+    start: u64,
+    bytes: Vec<u8>,
+}
+
+/// Where the walk is in a node: its edges, and the next one to follow
+#[derive(Debug)]
+struct Frame {
+    first_edge: u32,
+    edges: u32,
+    next: u32,
+}
+
+/// Iterator over the words of a saved Dawg in sorted order, read from the file as they are listed.
+///
+/// Opening reads the file once to check its header and the checksums of its sections (as `integrity::verify` does), then
+/// walks it from the root depth first: only the path to the current word is kept, with a buffer of `WINDOW_SIZE` bytes for
+/// each of the node, edge and label sections. The labels following the nodes in the file, the reader must be able to seek.
+///
+/// Records are checked as they are read like `Dawg::load` does, and a path longer than the number of nodes is reported as
+/// a cycle. The first error ends the iteration. Only dawgs of `char` labels can be read
+pub struct FileWords<R> {
+    reader: R,
+    header: SavedHeader,
+    /// where the node, edge and label sections start in the file
+    sections: [u64; 3],
+    windows: [Window; 3],
+    stack: Vec<Frame>,
+    word: String,
+    /// the root is terminal, and the empty word is yet to be listed
+    empty_word: bool,
+}
+
+impl<R> FileWords<R> where R: Read + Seek {
+    /// Reads the saved dawg from `reader`, from its start
+    pub fn new(mut reader: R) -> Result<Self, DawgError> {
+        reader.seek(SeekFrom::Start(0))?;
+        integrity::verify(&mut reader)?;
+
+        reader.seek(SeekFrom::Start(0))?;
+        let (header, nodes) = SavedHeader::read(&mut reader)?;
+        let (node_len, edge_len) = (header.node_count as u64 * NODE_SIZE as u64, header.edge_count as u64 * EDGE_SIZE as u64);
+        let edges = nodes + node_len + header.checksums.trailer_len(node_len);
+        let labels = edges + edge_len + header.checksums.trailer_len(edge_len);
+
+        let mut words = Self { reader, header, sections: [nodes, edges, labels], windows: Default::default(), stack: vec![], word: String::new(), empty_word: false };
+        let root = words.node(words.header.root)?;
+        words.empty_word = root.0;
+        words.stack.push(root.1);
+        Ok(words)
+    }
+
+    /// Returns the `len` bytes at `offset` in `section` (0 for the nodes, 1 for the edges, 2 for the labels), reading
+    /// the window of the section holding them if they aren't in memory
+    fn read(&mut self, section: usize, offset: u64, len: usize) -> Result<&[u8], DawgError> {
+        let start = self.sections[section] + offset;
+        let window = &mut self.windows[section];
+
+        if start < window.start || start + len as u64 > window.start + window.bytes.len() as u64 {
+            let end = match section {
+                2 => self.sections[2] + self.header.label_len as u64,
+                _ => self.sections[section + 1],
+            };
+            window.start = start;
+            window.bytes.resize(WINDOW_SIZE.max(len).min((end - start) as usize), 0);
+            self.reader.seek(SeekFrom::Start(start))?;
+            self.reader.read_exact(&mut window.bytes).map_err(|err| match err.kind() {
+                io::ErrorKind::UnexpectedEof => DawgError::Truncated { section: [Section::Nodes, Section::Edges, Section::Labels][section] },
+                _ => DawgError::Io(err),
+            })?;
+        }
+
+        let from = (start - window.start) as usize;
+        Ok(&window.bytes[from..from + len])
+    }
+
+    /// Reads whether the node of `id` is terminal, along with where the walk starts in its edges
+    fn node(&mut self, id: u32) -> Result<(bool, Frame), DawgError> {
+        let bytes = self.read(0, id as u64 * NODE_SIZE as u64, NODE_SIZE)?.to_vec();
+        let node = self.header.node(id, &mut Cursor::new(&bytes))?;
+        Ok((node.terminal, Frame { first_edge: node.first_edge, edges: node.edges, next: 0 }))
+    }
+
+    /// Reads the label and the child of the edge of `id`
+    fn edge(&mut self, id: u32) -> Result<(char, u32), DawgError> {
+        let bytes = self.read(1, id as u64 * EDGE_SIZE as u64, EDGE_SIZE)?.to_vec();
+        let edge = self.header.edge(id, &mut Cursor::new(&bytes))?;
+        if edge.label_len > 4 {
+            return Err(DawgError::InvalidFormat(String::from("label can't be decoded")));
+        }
+
+        let label = self.read(2, edge.label_start as u64, edge.label_len as usize)?;
+        let label = char::decode(label).ok_or_else(|| DawgError::InvalidFormat(String::from("label can't be decoded")))?;
+        Ok((label, edge.child))
+    }
+
+    /// Follows the walk to the next terminal node, `None` once every node was left
+    fn advance(&mut self) -> Result<Option<String>, DawgError> {
+        while let Some(frame) = self.stack.last_mut() {
+            if frame.next == frame.edges {
+                self.stack.pop();
+                self.word.pop();
+                continue;
+            }
+
+            let edge = frame.first_edge + frame.next;
+            frame.next += 1;
+            let (label, child) = self.edge(edge)?;
+            let (terminal, frame) = self.node(child)?;
+
+            if self.stack.len() >= self.header.node_count as usize {
+                return Err(DawgError::InvalidFormat(String::from("the edges form a cycle")));
+            }
+            self.stack.push(frame);
+            self.word.push(label);
+
+            if terminal {
+                return Ok(Some(self.word.clone()));
+            }
+        }
+        Ok(None)
+    }
+}
+
+impl<R> Iterator for FileWords<R> where R: Read + Seek {
+    type Item = Result<String, DawgError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if std::mem::take(&mut self.empty_word) {
+            return Some(Ok(String::new()));
+        }
+
+        match self.advance() {
+            Ok(word) => word.map(Ok),
+            Err(err) => {
+                self.stack.clear();
+                Some(Err(err))
+            }
+        }
+    }
+}
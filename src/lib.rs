@@ -1,6 +1,19 @@
+#[cfg(not(any(feature = "sync", feature = "unsync")))]
+compile_error!("at least one of the `sync` and `unsync` features must be enabled");
+
+pub mod codegen;
 pub mod dawg;
+pub mod error;
+pub mod file;
+#[cfg(feature = "testutil")]
+pub mod naive;
 pub mod node;
-mod utils;
+#[cfg(feature = "testutil")]
+pub mod testutil;
 
-// use dawg::unsync::Dawg;
-use dawg::common::Dawg;
\ No newline at end of file
+pub use dawg::common::{Dawg, DawgNode, Label, NodeRef, Wrapper};
+#[cfg(feature = "sync")]
+pub use dawg::sync::{DawgWrapper as SyncWrapper, SyncDawg, SyncNode};
+#[cfg(feature = "unsync")]
+pub use dawg::unsync::{DawgWrapper as UnsyncWrapper, UnsyncDawg, UnsyncNode};
+pub use error::DawgError;
@@ -0,0 +1,107 @@
+//! Reference model of a lexicon used to check the dawgs against, enabled by the `testutil` feature
+
+use std::{collections::BTreeSet, fmt::Display};
+
+use crate::dawg::lexicon::{Lexicon as LexiconQueries, MatchMode};
+
+/// Lexicon keeping its words in a `BTreeSet` and answering every query by going through them,
+/// slow but obviously right
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Lexicon {
+    words: BTreeSet<String>,
+}
+
+impl Lexicon {
+    pub fn new<I: IntoIterator<Item = S>, S: Into<String>>(words: I) -> Self {
+        Self { words: words.into_iter().map(Into::into).collect() }
+    }
+
+    pub fn insert(&mut self, word: &str) -> bool {
+        self.words.insert(word.to_owned())
+    }
+
+    pub fn remove(&mut self, word: &str) -> bool {
+        self.words.remove(word)
+    }
+
+    /// Returns every word in sorted order
+    pub fn words(&self) -> impl Iterator<Item = &str> {
+        self.words.iter().map(String::as_str)
+    }
+
+    /// value is true if `word` starts with letters matching those of `prefix` under `mode`
+    fn matches_prefix(word: &str, prefix: &str, mode: MatchMode) -> bool {
+        let mut letters = word.chars();
+        prefix.chars().all(|letter| letters.next().is_some_and(|label| mode.matches(label, letter)))
+    }
+}
+
+impl LexiconQueries for Lexicon {
+    fn contains(&self, word: &str, mode: MatchMode) -> bool {
+        let len = word.chars().count();
+        self.words.iter().any(|candidate| candidate.chars().count() == len && Self::matches_prefix(candidate, word, mode))
+    }
+
+    fn contains_prefix(&self, prefix: &str, mode: MatchMode) -> bool {
+        self.words.iter().any(|candidate| Self::matches_prefix(candidate, prefix, mode))
+    }
+
+    fn completions(&self, prefix: &str, mode: MatchMode) -> Vec<String> {
+        self.words.iter().filter(|candidate| Self::matches_prefix(candidate, prefix, mode)).cloned().collect()
+    }
+
+    fn word_count(&self) -> usize {
+        self.words.len()
+    }
+}
+
+/// A query of the `Lexicon` trait, run against both sides by `check_equivalence`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Query {
+    Contains(String, MatchMode),
+    ContainsPrefix(String, MatchMode),
+    Completions(String, MatchMode),
+    WordCount,
+    /// every word, in order (the completions of the empty prefix)
+    Words,
+}
+
+/// The first query on which the lexicon and the model disagree
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Divergence {
+    pub query: Query,
+    /// answer of the lexicon under test, formatted
+    pub actual: String,
+    /// answer of the model, formatted
+    pub expected: String,
+}
+
+impl Display for Divergence {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:?} returned {} but the model expected {}", self.query, self.actual, self.expected)
+    }
+}
+
+impl std::error::Error for Divergence {}
+
+/// Runs every query against `lexicon` and `model` (the word count and every word are always compared first),
+/// stopping at the first answer that differs
+pub fn check_equivalence(lexicon: &impl LexiconQueries, model: &Lexicon, queries: &[Query]) -> Result<(), Divergence> {
+    let answer = |query: &Query, lexicon: &dyn LexiconQueries| match query {
+        Query::Contains(word, mode) => format!("{}", lexicon.contains(word, *mode)),
+        Query::ContainsPrefix(prefix, mode) => format!("{}", lexicon.contains_prefix(prefix, *mode)),
+        Query::Completions(prefix, mode) => format!("{:?}", lexicon.completions(prefix, *mode)),
+        Query::WordCount => format!("{}", lexicon.word_count()),
+        Query::Words => format!("{:?}", lexicon.completions("", MatchMode::Exact)),
+    };
+
+    for query in [Query::WordCount, Query::Words].iter().chain(queries) {
+        let (actual, expected) = (answer(query, lexicon), answer(query, model));
+
+        if actual != expected {
+            return Err(Divergence { query: query.clone(), actual, expected });
+        }
+    }
+
+    Ok(())
+}
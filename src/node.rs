@@ -0,0 +1,131 @@
+use std::{collections::HashMap, marker::PhantomData};
+
+use crate::dawg::{common::{Dawg, Label, NodeOps, NodeRef, Wrapper}, iter::Sequences};
+
+/// Read-only handle on a node of a Dawg, borrowed from the Dawg it was found in.
+///
+/// The node behind the handle can't be reached, so it can't be changed through it either:
+///
+/// ```compile_fail
+/// use dawging::dawg::{common::NodeRef, unsync::UnsyncDawg};
+///
+/// let mut dawg = UnsyncDawg::new();
+/// dawg.add(String::from("tap"));
+/// dawg.finish();
+///
+/// let tap = dawg.get("tap").unwrap().into_node();
+/// tap.node.write(|node| node.terminal = false);
+/// ```
+#[derive(Debug, Clone)]
+pub struct NodeHandle<'a, N: NodeRef> {
+    pub(crate) node: N,
+    /// id of the Dawg the node belongs to, so a handle can't be used with another Dawg
+    pub(crate) dawg_id: usize,
+    dawg: PhantomData<&'a ()>,
+}
+
+impl<'a, N> NodeHandle<'a, N> where N: NodeRef {
+    pub(crate) fn new(node: N, dawg_id: usize) -> Self {
+        Self { node, dawg_id, dawg: PhantomData }
+    }
+
+    /// Id of the node, which is canonical once the dawg is finished (see `Dawg::finish`).
+    /// This identifies the node, which is shared by many words, it is not the index of a word
+    pub fn id(&self) -> usize {
+        self.node.id()
+    }
+
+    /// value is true if the path leading to this node is a word
+    pub fn is_terminal(&self) -> bool {
+        self.node.is_terminal()
+    }
+
+    /// number of words that can be formed from this node downwards (this node included when it is terminal)
+    pub fn reachable_words(&self) -> usize {
+        self.node.reachable()
+    }
+
+    /// number of letters of the shortest word that can be formed from this node downwards (0 when it is terminal), `None`
+    /// if no word can
+    pub fn min_completion_depth(&self) -> Option<usize> {
+        self.node.completion_depths().map(|(shortest, _)| shortest)
+    }
+
+    /// number of letters of the longest word that can be formed from this node downwards, `None` if no word can
+    pub fn max_completion_depth(&self) -> Option<usize> {
+        self.node.completion_depths().map(|(_, longest)| longest)
+    }
+
+    /// labels of the edges leaving this node in sorted order
+    pub fn edge_labels(&self) -> impl Iterator<Item = N::Label> {
+        self.node.children().into_iter().map(|(label, _)| label)
+    }
+
+    /// Returns the node reached by following `label` from this node
+    pub fn child(&self, label: &N::Label) -> Option<NodeHandle<'a, N>> {
+        self.node.child(label).map(|node| NodeHandle::new(node, self.dawg_id))
+    }
+}
+
+/// Counts the words below `node` from its edges, for nodes whose count hasn't been computed by `finish` yet
+pub(crate) fn count_reachable<N: NodeRef>(node: &N, seen: &mut HashMap<usize, usize>) -> usize {
+    if let Some(total) = seen.get(&node.id()) {
+        return *total;
+    }
+
+    let total = node.is_terminal() as usize + node.children().iter().map(|(_, child)| count_reachable(child, seen)).sum::<usize>();
+    seen.insert(node.id(), total);
+    total
+}
+
+/// Shortest and longest distance from `node` to the end of a word below it, memoized by node id in `seen`
+pub(crate) fn completion_depths<N: NodeRef>(node: &N, seen: &mut HashMap<usize, Option<(usize, usize)>>) -> Option<(usize, usize)> {
+    if let Some(depths) = node.read(|node| node.depths) {
+        return Some(depths);
+    }
+    if let Some(depths) = seen.get(&node.id()) {
+        return *depths;
+    }
+
+    let mut depths = node.is_terminal().then_some((0, 0));
+    for (_, child) in node.children() {
+        let Some((min, max)) = completion_depths(&child, seen) else { continue };
+        depths = Some(depths.map_or((min + 1, max + 1), |(shortest, longest)| (shortest.min(min + 1), longest.max(max + 1))));
+    }
+    seen.insert(node.id(), depths);
+    depths
+}
+
+impl<T, L> Dawg<T, L> where T: Wrapper<L>, L: Label {
+    /// Returns the root of the dawg
+    pub fn root(&self) -> NodeHandle<'_, T::Node> {
+        NodeHandle::new(self.root.clone(), self.dawg_id)
+    }
+
+    /// Returns the node reached by following `prefix` from the root
+    pub fn descend_sequence(&self, prefix: &[L]) -> Option<NodeHandle<'_, T::Node>> {
+        self.find_sequence(prefix).map(|node| NodeHandle::new(node, self.dawg_id))
+    }
+
+    /// value is true if `node` was handed out by this dawg
+    pub fn owns(&self, node: &NodeHandle<'_, T::Node>) -> bool {
+        node.dawg_id == self.dawg_id
+    }
+
+    /// Returns the sequences that can be formed from `node` downwards, relative to `node`,
+    /// or None if the handle belongs to another dawg
+    pub fn suffixes(&self, node: &NodeHandle<'_, T::Node>) -> Option<Sequences<'_, T::Node>> {
+        if !self.owns(node) {
+            return None;
+        }
+
+        Some(Sequences::new(Some(node.node.clone()), vec![]))
+    }
+}
+
+impl<T> Dawg<T> where T: Wrapper {
+    /// Returns the node reached by following `prefix` (matched exactly) from the root
+    pub fn descend(&self, prefix: &str) -> Option<NodeHandle<'_, T::Node>> {
+        self.descend_sequence(&prefix.chars().collect::<Vec<_>>())
+    }
+}
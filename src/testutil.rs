@@ -0,0 +1,220 @@
+//! Synthetic word lists of any size for tests and benchmarks, enabled by the `testutil` feature.
+//!
+//! Every list is drawn from a generator seeded by `WordListGen::new`, so a seed always gives the same list and a failing
+//! test can be replayed from its seed alone
+
+use std::collections::BTreeSet;
+
+use crate::dawg::{lexicon::{Lexicon, MatchMode}, sample::{RandomSource, SplitMix64}};
+
+/// Mixed into the seed for `near_misses`, so its draws don't follow those of `generate`
+const NEAR_MISS_STREAM: u64 = 0x6E65_6172_6D69_7373;
+
+/// How the letters of the words are drawn
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WordStyle {
+    /// every letter drawn uniformly from the alphabet
+    Uniform,
+    /// every letter drawn after the previous one (or the start of the word) from a table of bigrams: the letters are
+    /// shuffled for each previous letter, and the k-th of them weighted by `1 / k^exponent`, a Zipf law. Some letters then
+    /// follow others far more often, as in natural words
+    Markov { exponent: f64 },
+}
+
+/// How the lengths of the words (in letters) are drawn
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordLengths {
+    /// any length from `min` to `max` (both included), uniformly
+    Uniform { min: usize, max: usize },
+    /// the length `i` weighted by the `i`-th weight
+    Weighted(Vec<u64>),
+}
+
+/// Seeded generator of sorted, duplicate-free word lists, along with words one edit away from those of a lexicon (see
+/// `near_misses`) for the queries that must fail
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordListGen {
+    seed: u64,
+    alphabet: Vec<char>,
+    lengths: WordLengths,
+    style: WordStyle,
+}
+
+impl WordListGen {
+    /// Words of 3 to 10 letters from `a` to `z`, drawn through bigrams weighted by a Zipf law of exponent 1
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            alphabet: ('a'..='z').collect(),
+            lengths: WordLengths::Uniform { min: 3, max: 10 },
+            style: WordStyle::Markov { exponent: 1.0 },
+        }
+    }
+
+    /// Letters of the words, duplicates are dropped
+    pub fn alphabet(mut self, alphabet: impl IntoIterator<Item = char>) -> Self {
+        self.alphabet = alphabet.into_iter().collect::<BTreeSet<_>>().into_iter().collect();
+        self
+    }
+
+    pub fn lengths(mut self, lengths: WordLengths) -> Self {
+        self.lengths = lengths;
+        self
+    }
+
+    pub fn style(mut self, style: WordStyle) -> Self {
+        self.style = style;
+        self
+    }
+
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn get_alphabet(&self) -> &[char] {
+        &self.alphabet
+    }
+
+    pub fn get_lengths(&self) -> &WordLengths {
+        &self.lengths
+    }
+
+    pub fn get_style(&self) -> WordStyle {
+        self.style
+    }
+
+    /// Returns the number of distinct words the alphabet and the lengths allow, `usize::MAX` if there are more
+    pub fn capacity(&self) -> usize {
+        let allowed = |len: usize| match &self.lengths {
+            WordLengths::Uniform { min, max } => (*min..=*max).contains(&len),
+            WordLengths::Weighted(weights) => weights.get(len).is_some_and(|&weight| weight > 0),
+        };
+        let longest = match &self.lengths {
+            WordLengths::Uniform { max, .. } => *max,
+            WordLengths::Weighted(weights) => weights.len().saturating_sub(1),
+        };
+
+        (0..=longest).filter(|&len| allowed(len))
+            .map(|len| u32::try_from(len).ok().and_then(|len| self.alphabet.len().checked_pow(len)).unwrap_or(usize::MAX))
+            .fold(0, usize::saturating_add)
+    }
+
+    /// Returns `count` distinct words in sorted order, always the same ones for the same settings.
+    ///
+    /// Words are drawn until there are `count` distinct ones, so the list is shorter only if `count` goes past `capacity`
+    /// (every possible word is then returned), or if `64 * count` draws didn't give enough distinct words, which only
+    /// happens when `count` is close to `capacity` or the Zipf exponent is very high
+    ///
+    /// # Panics
+    /// If the lengths allow no length at all: a `min` above `max`, no positive weight, or only lengths above 0 along with an
+    /// empty alphabet
+    pub fn generate(&self, count: usize) -> Vec<String> {
+        let mut rng = SplitMix64::new(self.seed);
+        let lengths = self.length_weights();
+        let bigrams = self.bigrams(&mut rng);
+
+        let target = count.min(self.capacity());
+        let mut words = BTreeSet::new();
+        let mut draws = 0;
+
+        while words.len() < target && draws < target.saturating_mul(64).saturating_add(1024) {
+            draws += 1;
+            let len = pick(&lengths, &mut rng);
+            let mut word = String::with_capacity(len);
+            let mut previous = 0;
+
+            for _ in 0..len {
+                let letter = pick(&bigrams[previous], &mut rng);
+                word.push(self.alphabet[letter]);
+                previous = letter + 1;
+            }
+            words.insert(word);
+        }
+
+        words.into_iter().collect()
+    }
+
+    /// Returns up to `count` distinct words one edit away from words of `lexicon` (a letter of the alphabet put in, taken
+    /// out or replaced), none of which is in `lexicon`, in sorted order. Always the same ones for the same seed and lexicon.
+    /// The list is shorter if `64 * count` edits didn't give enough of them, an empty lexicon gives none
+    pub fn near_misses(&self, lexicon: &impl Lexicon, count: usize) -> Vec<String> {
+        let words = lexicon.completions("", MatchMode::Exact);
+        let mut rng = SplitMix64::new(self.seed ^ NEAR_MISS_STREAM);
+        let mut misses = BTreeSet::new();
+        let mut draws = 0;
+
+        while !words.is_empty() && misses.len() < count && draws < count.saturating_mul(64).saturating_add(1024) {
+            draws += 1;
+            let mut letters = words[rng.below(words.len() as u64) as usize].chars().collect::<Vec<_>>();
+            let letter = |rng: &mut SplitMix64| self.alphabet[rng.below(self.alphabet.len() as u64) as usize];
+
+            match rng.below(3) {
+                0 if !self.alphabet.is_empty() => {
+                    let at = rng.below(letters.len() as u64 + 1) as usize;
+                    letters.insert(at, letter(&mut rng));
+                }
+                1 if !self.alphabet.is_empty() && !letters.is_empty() => {
+                    let at = rng.below(letters.len() as u64) as usize;
+                    letters[at] = letter(&mut rng);
+                }
+                2 if !letters.is_empty() => {
+                    letters.remove(rng.below(letters.len() as u64) as usize);
+                }
+                _ => continue,
+            }
+
+            let miss = letters.into_iter().collect::<String>();
+            if !lexicon.contains(&miss, MatchMode::Exact) {
+                misses.insert(miss);
+            }
+        }
+
+        misses.into_iter().collect()
+    }
+
+    /// Returns the weight of every length, from 0 to the longest one
+    fn length_weights(&self) -> Vec<u64> {
+        let mut weights = match &self.lengths {
+            WordLengths::Uniform { min, max } => (0..=*max).map(|len| (len >= *min) as u64).collect(),
+            WordLengths::Weighted(weights) => weights.clone(),
+        };
+        if self.alphabet.is_empty() {
+            weights.truncate(1);
+        }
+
+        assert!(weights.iter().any(|&weight| weight > 0), "no length allowed by {:?}", self.lengths);
+        weights
+    }
+
+    /// Returns the weights of the letters following the start of a word (first row), then following each letter
+    fn bigrams(&self, rng: &mut SplitMix64) -> Vec<Vec<u64>> {
+        let exponent = match self.style {
+            WordStyle::Uniform => return vec![vec![1; self.alphabet.len()]; self.alphabet.len() + 1],
+            WordStyle::Markov { exponent } => exponent,
+        };
+
+        (0..=self.alphabet.len()).map(|_| {
+            // the k-th letter of a shuffled alphabet gets the k-th weight of the Zipf law, at least 1 so it can be drawn
+            let mut order = (0..self.alphabet.len()).collect::<Vec<_>>();
+            for i in (1..order.len()).rev() {
+                order.swap(i, rng.below(i as u64 + 1) as usize);
+            }
+
+            let mut weights = vec![0; self.alphabet.len()];
+            for (rank, letter) in order.into_iter().enumerate() {
+                weights[letter] = ((1 << 20) as f64 / (rank as f64 + 1.0).powf(exponent)).max(1.0) as u64;
+            }
+            weights
+        }).collect()
+    }
+}
+
+/// Draws an index with a probability proportional to its weight, at least one of them being positive
+fn pick(weights: &[u64], rng: &mut impl RandomSource) -> usize {
+    let mut drawn = rng.below(weights.iter().sum());
+    weights.iter().position(|&weight| {
+        let found = drawn < weight;
+        drawn = drawn.saturating_sub(weight);
+        found
+    }).unwrap()
+}
@@ -0,0 +1,69 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{build, build_with};
+use dawging::dawg::options::DawgOptions;
+
+/// The words of the fixture along with known groups of anagrams
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["least", "slate", "stale", "steal", "tales", "enlist", "listen", "silent", "tinsel", "été", "tée"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The groups of at least `min_size` anagrams of `words`, by sorting the letters of every word
+fn brute_force(words: &[String], min_size: usize) -> Vec<Vec<String>> {
+    let mut groups = BTreeMap::<Vec<char>, Vec<String>>::new();
+    for word in words {
+        let mut letters = word.chars().collect::<Vec<_>>();
+        letters.sort();
+        groups.entry(letters).or_default().push(word.clone());
+    }
+    let mut groups = groups.into_values().filter(|group| group.len() >= min_size.max(1)).collect::<Vec<_>>();
+    groups.sort();
+    groups
+}
+
+#[test]
+fn the_known_groups_are_found() {
+    let dawg = build(&words());
+    let groups = dawg.anagram_groups(2).collect::<Vec<_>>();
+    assert!(groups.contains(&["least", "slate", "stale", "steal", "tales"].map(String::from).to_vec()));
+    assert!(groups.contains(&["enlist", "listen", "silent", "tinsel"].map(String::from).to_vec()));
+    // "éte" is one of the words of the fixture
+    assert!(groups.contains(&["tée", "éte"].map(String::from).to_vec()));
+
+    assert_eq!(dawg.anagram_groups(5).collect::<Vec<_>>(), [["least", "slate", "stale", "steal", "tales"].map(String::from)]);
+    assert_eq!(dawg.anagram_groups(6).count(), 0);
+}
+
+#[test]
+fn the_groups_are_those_of_brute_force_at_every_size() {
+    let words = words();
+    let dawg = build(&words);
+    for min_size in [0, 1, 2, 3, 4, 5, 6] {
+        let groups = dawg.anagram_groups(min_size).collect::<Vec<_>>();
+        assert_eq!(groups, brute_force(&words, min_size), "at least {min_size}");
+        assert!(groups.iter().all(|group| group.is_sorted()));
+    }
+    assert_eq!(dawg.anagram_groups(1).map(|group| group.len()).sum::<usize>(), words.len());
+}
+
+#[test]
+fn letters_are_compared_as_the_dawg_stores_them() {
+    let dawg = build_with(&["Least", "sTeal", "tales"], DawgOptions::new().case_folding(true));
+    assert_eq!(dawg.anagram_groups(3).collect::<Vec<_>>(), [["least", "steal", "tales"].map(String::from)]);
+
+    // an accent written as a char of its own is a letter of its own
+    let dawg = build(&["e\u{301}t", "te\u{301}", "té", "t\u{301}e"]);
+    assert_eq!(dawg.anagram_groups(2).collect::<Vec<_>>(), [["e\u{301}t", "te\u{301}", "t\u{301}e"].map(String::from)]);
+
+    let mut empty = dawging::UnsyncDawg::new();
+    empty.finish();
+    assert_eq!(empty.anagram_groups(0).count(), 0);
+}
@@ -0,0 +1,113 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, xorshift};
+use dawging::{dawg::{bidawg::BiDawg, options::DawgOptions}, UnsyncWrapper};
+
+fn reversed(word: &str) -> String {
+    word.chars().rev().collect()
+}
+
+/// Asserts both dawgs are sound, and the reversed one holds the words of the forward one reversed
+fn assert_in_step(bidawg: &BiDawg<UnsyncWrapper>) {
+    checked(bidawg.forward());
+    checked(bidawg.reverse());
+    let mut expected = bidawg.forward().words().map(|word| reversed(&word)).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(bidawg.reverse().words().collect::<Vec<_>>(), expected);
+}
+
+#[test]
+fn suffix_and_affix_queries_are_those_of_brute_force() {
+    let words = common::words();
+    let bidawg = BiDawg::<UnsyncWrapper>::from_words(words.iter().rev(), DawgOptions::new()).unwrap();
+    assert_in_step(&bidawg);
+
+    for suffix in ["", "e", "ing", "ab", "é", "te", "zzzz"] {
+        let expected = words.iter().filter(|word| word.ends_with(suffix)).cloned().collect::<Vec<_>>();
+        assert_eq!(bidawg.words_with_suffix(suffix), expected, "{suffix:?}");
+    }
+    for (prefix, suffix) in [("a", "e"), ("", "s"), ("b", ""), ("ab", "ba"), ("q", "zz"), ("é", "e"), ("ab", "b")] {
+        let expected = words.iter().filter(|word| word.starts_with(prefix) && word.ends_with(suffix)).cloned().collect::<Vec<_>>();
+        assert_eq!(bidawg.words_with_affixes(prefix, suffix), expected, "{prefix:?} {suffix:?}");
+    }
+    // the prefix and the suffix may overlap
+    let small = BiDawg::<UnsyncWrapper>::from_words(["aba", "abba", "ba"], DawgOptions::new()).unwrap();
+    assert_eq!(small.words_with_affixes("ab", "ba"), ["aba", "abba"]);
+    assert_eq!(bidawg.words_with_prefix("ab"), build(&words).words_with_prefix("ab").collect::<Vec<_>>());
+}
+
+#[test]
+fn edits_change_both_dawgs() {
+    let words = common::words();
+    let mut bidawg = BiDawg::<UnsyncWrapper>::from_words(&words, DawgOptions::new()).unwrap();
+    let mut expected = words.clone();
+    let mut state = 17;
+
+    for round in 0..200 {
+        let word = match xorshift(&mut state) % 3 {
+            0 => words[xorshift(&mut state) as usize % words.len()].clone(),
+            _ => format!("{}{}", words[xorshift(&mut state) as usize % words.len()], ["s", "é", "ing"][round % 3]),
+        };
+        if xorshift(&mut state) % 2 == 0 {
+            assert_eq!(bidawg.insert(&word).unwrap(), !expected.contains(&word), "{word}");
+            expected.push(word);
+        } else {
+            assert_eq!(bidawg.remove(&word).unwrap(), expected.contains(&word), "{word}");
+            expected.retain(|other| *other != word);
+        }
+        expected.sort();
+        expected.dedup();
+        if round % 40 == 0 {
+            assert_in_step(&bidawg);
+        }
+    }
+    assert_in_step(&bidawg);
+    assert_eq!(bidawg.forward().words().collect::<Vec<_>>(), expected);
+    assert_eq!(bidawg.words_with_suffix("s"), expected.iter().filter(|word| word.ends_with('s')).cloned().collect::<Vec<_>>());
+}
+
+#[test]
+fn hooks_come_from_both_sides() {
+    let mut bidawg = BiDawg::<UnsyncWrapper>::from_words(["at", "cat", "scat"], DawgOptions::new().case_folding(true)).unwrap();
+    assert!(bidawg.insert("Hat").unwrap() && !bidawg.insert("hat").unwrap());
+    assert!(bidawg.remove("scat").unwrap() && !bidawg.remove("dog").unwrap());
+    assert_in_step(&bidawg);
+
+    assert_eq!(bidawg.words_with_suffix("at"), ["at", "cat", "hat"]);
+    assert_eq!(bidawg.front_hooks("AT"), ['c', 'h']);
+    assert!(bidawg.front_hooks("cat").is_empty() && bidawg.back_hooks("ca") == ['t']);
+    assert_eq!(bidawg.hooks("at"), bidawg.forward().hooks("at"));
+}
+
+#[test]
+fn the_reversed_dawg_is_saved_or_rebuilt() {
+    let bidawg = BiDawg::<UnsyncWrapper>::from_words(["at", "cat", "hat"], DawgOptions::new()).unwrap();
+    let (full, lean) = (bidawg.to_bytes(true), bidawg.to_bytes(false));
+    assert!(lean.len() < full.len());
+
+    for bytes in [&full, &lean] {
+        let mut loaded = BiDawg::<UnsyncWrapper>::from_bytes(bytes).unwrap();
+        assert_eq!(loaded.words_with_suffix("t"), ["at", "cat", "hat"]);
+        assert_in_step(&loaded);
+        loaded.insert("that").unwrap();
+        assert_in_step(&loaded);
+        assert_eq!(loaded.words_with_suffix("hat"), ["hat", "that"]);
+    }
+
+    // a lean file rebuilds the reversed dawg on the first suffix query
+    let mut lean = BiDawg::<UnsyncWrapper>::load(&lean[..]).unwrap();
+    lean.insert("oat").unwrap();
+    assert_eq!(lean.words_with_suffix("oat"), ["oat"]);
+    assert_in_step(&lean);
+
+    let large = BiDawg::<UnsyncWrapper>::from_words(common::words(), DawgOptions::new()).unwrap();
+    assert_in_step(&BiDawg::<UnsyncWrapper>::load(&large.to_bytes(false)[..]).unwrap());
+
+    assert!(BiDawg::<UnsyncWrapper>::from_bytes(b"DAWG").is_err());
+    assert!(BiDawg::<UnsyncWrapper>::from_bytes(&full[..full.len() - 5]).is_err());
+    // the flag says the reversed dawg follows the forward one, but the file ends there
+    let forward = u64::from_le_bytes(full[7..15].try_into().unwrap()) as usize;
+    assert!(BiDawg::<UnsyncWrapper>::from_bytes(&full[..15 + forward]).is_err());
+}
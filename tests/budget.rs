@@ -0,0 +1,108 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::time::{Duration, Instant};
+
+use common::{build, fixture};
+use dawging::{dawg::{budget::{SearchBudget, Truncation}, fuzzy::LevenshteinAutomaton, gaddag::{Gaddag, Rack, RowState}, hangman::{parse_pattern, HangmanRules}, options::DawgOptions}, UnsyncWrapper};
+
+/// Node budgets from nothing to more than any search here visits
+const NODES: [usize; 7] = [0, 1, 10, 100, 1000, 5000, 1_000_000];
+
+#[test]
+fn a_pathological_glob_stops_at_the_node_budget() {
+    // every path can still match `*a*e*i*o*u*`, so the search can leave none of the dawg out
+    let mut words = common::words();
+    words.extend(["abstemious", "arsenious", "facetious"].map(String::from));
+    words.sort();
+    let dawg = build(&words);
+    let glob = "*a*e*i*o*u*";
+    let all = dawg.glob(glob);
+    assert_eq!(all, ["abstemious", "arsenious", "facetious"]);
+
+    let mut previous = 0;
+    for max_nodes in NODES {
+        let bounded = dawg.glob_budgeted(glob, SearchBudget::new().max_nodes(max_nodes));
+        assert_eq!(bounded.results, all[..bounded.results.len()], "{max_nodes} nodes");
+        assert!(bounded.results.len() >= previous);
+        previous = bounded.results.len();
+        if max_nodes < 5000 {
+            assert_eq!(bounded.truncated, Some(Truncation::Nodes), "{max_nodes} nodes");
+        }
+    }
+    assert_eq!(dawg.glob_budgeted(glob, SearchBudget::new().max_nodes(1_000_000)).truncated, None);
+
+    let bounded = dawg.glob_budgeted(glob, SearchBudget::new().max_results(2));
+    assert_eq!((bounded.results, bounded.truncated), (all[..2].to_vec(), Some(Truncation::Results)));
+}
+
+#[test]
+fn bounded_fuzzy_searches_return_the_first_results() {
+    let dawg = build(&common::words());
+    let all = dawg.fuzzy_search("aeiou", 3);
+    let unbounded = dawg.fuzzy_search_budgeted("aeiou", 3, SearchBudget::new());
+    assert_eq!((unbounded.results, unbounded.truncated), (all.clone(), None));
+
+    for max in NODES {
+        let bounded = dawg.fuzzy_search_budgeted("aeiou", 3, SearchBudget::new().max_nodes(max));
+        assert_eq!(bounded.results, all[..bounded.results.len()], "{max} nodes");
+        assert_eq!(bounded.is_truncated(), max < 1_000_000);
+
+        let bounded = dawg.fuzzy_search_budgeted("aeiou", 3, SearchBudget::new().max_results(max));
+        assert_eq!(bounded.results, all[..max.min(all.len())], "{max} results");
+        assert_eq!(bounded.truncated, (max < all.len()).then_some(Truncation::Results));
+    }
+    // exactly as many results as there are isn't a truncation
+    assert!(!dawg.fuzzy_search_budgeted("aeiou", 3, SearchBudget::new().max_results(all.len())).is_truncated());
+
+    let automaton = LevenshteinAutomaton::new("abc", 2);
+    let all = dawg.search_automaton(&automaton);
+    for max_nodes in [3, 50, 400] {
+        let bounded = dawg.search_automaton_budgeted(&automaton, SearchBudget::new().max_nodes(max_nodes));
+        assert!(bounded.is_truncated());
+        assert_eq!(bounded.results, all[..bounded.results.len()]);
+    }
+}
+
+#[test]
+fn a_past_deadline_stops_the_search_at_once() {
+    let dawg = build(&common::words());
+    let past = SearchBudget::new().deadline(Instant::now() - Duration::from_millis(1));
+    let bounded = dawg.fuzzy_search_budgeted("aeiou", 3, past);
+    assert_eq!((bounded.results.len(), bounded.truncated), (0, Some(Truncation::Deadline)));
+    assert_eq!(dawg.glob_budgeted("*a*e*", past).truncated, Some(Truncation::Deadline));
+
+    let later = SearchBudget::new().deadline(Instant::now() + Duration::from_secs(3600));
+    assert_eq!(dawg.fuzzy_search_budgeted("aeiou", 3, later).results, dawg.fuzzy_search("aeiou", 3));
+}
+
+#[test]
+fn bounded_patterns_return_the_first_matches() {
+    let dawg = build(&common::words());
+    let pattern = parse_pattern("____");
+    let all = dawg.hangman_with(&pattern, &[], HangmanRules::default()).collect::<Vec<_>>();
+
+    for max in NODES {
+        let bounded = dawg.hangman_budgeted(&pattern, &[], HangmanRules::default(), SearchBudget::new().max_nodes(max));
+        assert_eq!(bounded.results, all[..bounded.results.len()], "{max} nodes");
+        let bounded = dawg.hangman_budgeted(&pattern, &[], HangmanRules::default(), SearchBudget::new().max_results(max));
+        assert_eq!(bounded.results, all[..max.min(all.len())], "{max} results");
+        assert_eq!(bounded.is_truncated(), max < all.len());
+    }
+}
+
+#[test]
+fn bounded_moves_are_some_of_the_moves() {
+    let words = std::fs::read_to_string(fixture("boggle.txt")).unwrap();
+    let gaddag = Gaddag::<UnsyncWrapper>::from_words(words.lines(), DawgOptions::new()).unwrap();
+    let (row, rack) = (RowState::parse("....a....").anchors_next_to_letters(), Rack::new("etrs?"));
+    let all = gaddag.generate_moves(&row, &rack);
+    assert_eq!(gaddag.generate_moves_budgeted(&row, &rack, SearchBudget::new()).results, all);
+
+    for max_nodes in [0, 10, 50] {
+        let bounded = gaddag.generate_moves_budgeted(&row, &rack, SearchBudget::new().max_nodes(max_nodes));
+        assert_eq!(bounded.truncated, Some(Truncation::Nodes));
+        assert!(bounded.results.is_sorted() && bounded.results.iter().all(|placement| all.contains(placement)));
+    }
+}
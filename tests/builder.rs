@@ -0,0 +1,105 @@
+mod common;
+
+use common::{checked, wrapper_tests};
+use dawging::{Dawg, Wrapper};
+
+wrapper_tests!(
+    the_state_evolves_with_every_add,
+    finish_releases_the_builder_state,
+    clear_resets_everything,
+    inserts_into_a_finished_dawg_are_counted,
+    a_rollback_restores_the_counts,
+);
+
+fn the_state_evolves_with_every_add<T: Wrapper>() {
+    let mut dawg = Dawg::<T>::with_wrapper(T::new());
+    assert!(!dawg.is_finished());
+    assert_eq!((dawg.words_added(), dawg.pending_depth(), dawg.minimized_states()), (0, 0, 0));
+    assert_eq!(dawg.last_word(), None);
+
+    dawg.try_add("cat").unwrap();
+    checked(&dawg);
+    assert_eq!((dawg.words_added(), dawg.pending_depth(), dawg.minimized_states()), (1, 3, 0));
+    assert_eq!(dawg.last_word(), Some(&['c', 'a', 't'][..]));
+
+    // duplicates aren't counted
+    dawg.try_add("cat").unwrap();
+    assert_eq!(dawg.words_added(), 1);
+
+    // the word shares "ca", so only the "t" below it is minimized
+    dawg.try_add("cave").unwrap();
+    checked(&dawg);
+    assert_eq!((dawg.words_added(), dawg.pending_depth(), dawg.minimized_states()), (2, 4, 1));
+
+    // nothing is shared with "dog", so the whole path of "cave" is minimized
+    dawg.try_add("dog").unwrap();
+    checked(&dawg);
+    assert_eq!((dawg.words_added(), dawg.pending_depth()), (3, 3));
+    assert_eq!(dawg.minimized_states(), 4);
+    assert_eq!(dawg.last_word(), Some(&['d', 'o', 'g'][..]));
+}
+
+fn finish_releases_the_builder_state<T: Wrapper>() {
+    let mut dawg = Dawg::<T>::with_wrapper(T::new());
+    dawg.try_add("cat").unwrap();
+    dawg.try_add("dog").unwrap();
+    dawg.finish();
+    checked(&dawg);
+
+    assert!(dawg.is_finished());
+    assert_eq!((dawg.words_added(), dawg.pending_depth(), dawg.minimized_states()), (2, 0, 0));
+    assert_eq!(dawg.last_word(), Some(&['d', 'o', 'g'][..]));
+
+    let loaded = Dawg::<T>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    assert!(loaded.is_finished());
+    assert_eq!((loaded.words_added(), loaded.pending_depth(), loaded.minimized_states()), (2, 0, 0));
+}
+
+fn clear_resets_everything<T: Wrapper>() {
+    let mut dawg = Dawg::<T>::with_wrapper(T::new());
+    dawg.try_add("cat").unwrap();
+    dawg.try_add("dog").unwrap();
+    dawg.finish();
+    dawg.clear();
+    checked(&dawg);
+
+    assert!(!dawg.is_finished());
+    assert_eq!((dawg.words_added(), dawg.pending_depth(), dawg.minimized_states()), (0, 0, 0));
+    assert_eq!(dawg.last_word(), None);
+    assert_eq!(dawg.words().count(), 0);
+
+    // words sorting before the cleared ones are accepted again
+    dawg.try_add("ant").unwrap();
+    checked(&dawg);
+    dawg.finish();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["ant"]);
+    assert_eq!(dawg.words_added(), 1);
+}
+
+fn inserts_into_a_finished_dawg_are_counted<T: Wrapper>() {
+    let mut dawg = Dawg::<T>::with_wrapper(T::new());
+    dawg.try_add("b").unwrap();
+    dawg.try_add("d").unwrap();
+    dawg.finish();
+
+    dawg.insert("a").unwrap();
+    dawg.insert("a").unwrap();
+    dawg.try_add("c").unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.words_added(), 4);
+    assert!(dawg.is_finished());
+    assert_eq!(dawg.last_word(), Some(&['d'][..]));
+}
+
+fn a_rollback_restores_the_counts<T: Wrapper>() {
+    let mut dawg = Dawg::<T>::with_wrapper(T::new());
+    dawg.try_add("cat").unwrap();
+    let checkpoint = dawg.checkpoint();
+    dawg.try_add("cave").unwrap();
+    dawg.try_add("dog").unwrap();
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+
+    assert_eq!((dawg.words_added(), dawg.pending_depth(), dawg.minimized_states()), (1, 3, 0));
+    assert_eq!(dawg.last_word(), Some(&['c', 'a', 't'][..]));
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::rc::Rc;
+
+use common::{build, xorshift};
+use dawging::dawg::{cache::{CacheStats, CachedDawg}, lexicon::{Lexicon, MatchMode}};
+
+#[test]
+fn hits_misses_and_evictions_are_counted() {
+    let dawg = build(&common::words());
+    let cache = CachedDawg::new(&dawg, 3);
+    assert_eq!(cache.stats(), CacheStats::default());
+
+    // a, b, ab, a (hit), a (hit), zz (drops b), b (drops ab), c (drops a), a (drops zz)
+    for prefix in ["a", "b", "ab", "a", "a", "zz", "b", "c", "a"] {
+        assert_eq!(cache.completions(prefix, MatchMode::Exact), Lexicon::completions(&dawg, prefix, MatchMode::Exact));
+    }
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 7, evictions: 4, resident: 3 });
+
+    // contains goes straight to the dawg
+    assert!(cache.contains(&common::words()[10], MatchMode::Exact));
+    assert_eq!((cache.stats().hits, cache.stats().misses), (2, 7));
+
+    cache.invalidate_all();
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 7, evictions: 4, resident: 0 });
+    cache.completions("a", MatchMode::Exact);
+    assert_eq!(cache.stats().misses, 8);
+}
+
+#[test]
+fn cached_answers_are_those_of_the_dawg() {
+    let words = common::words();
+    let dawg = build(&words);
+    let cache = CachedDawg::new(&dawg, 20);
+    let prefixes = ["", "a", "ab", "b", "st", "é", "A", "St", "zz"];
+    let mut state = 5;
+
+    for _ in 0..300 {
+        let prefix = prefixes[xorshift(&mut state) as usize % prefixes.len()];
+        let mode = [MatchMode::Exact, MatchMode::AsciiInsensitive][xorshift(&mut state) as usize % 2];
+        let completions = Lexicon::completions(&dawg, prefix, mode);
+        match xorshift(&mut state) % 3 {
+            0 => assert_eq!(cache.completions(prefix, mode), completions),
+            1 => assert_eq!(cache.count_completions(prefix, mode), completions.len()),
+            _ => {
+                let limit = xorshift(&mut state) as usize % 10;
+                assert_eq!(cache.completions_limit(prefix, mode, limit), completions[..limit.min(completions.len())]);
+            }
+        }
+        assert!(cache.stats().resident <= cache.capacity());
+    }
+    // every query kind is cached for its own arguments
+    let stats = cache.stats();
+    assert!(stats.hits > 0 && stats.evictions > 0, "{stats:?}");
+    assert_eq!(stats.hits + stats.misses, 300);
+    assert_eq!(cache.word_count(), words.len());
+}
+
+#[test]
+fn suggestions_are_cached_for_their_arguments() {
+    let dawg = build(&common::words());
+    let cache = CachedDawg::new(&dawg, 10);
+    for (word, mode, limit, max_distance) in [("teh", MatchMode::Exact, 5, 1), ("Teh", MatchMode::AsciiInsensitive, 5, 1), ("teh", MatchMode::Exact, 2, 2)] {
+        let expected = dawg.suggest(word, mode, limit, max_distance);
+        assert!(!expected.is_empty());
+        assert_eq!(cache.suggest(word, mode, limit, max_distance), expected);
+        assert_eq!(cache.suggest(word, mode, limit, max_distance), expected);
+    }
+    assert_eq!(cache.stats(), CacheStats { hits: 3, misses: 3, evictions: 0, resident: 3 });
+
+    // a completion of the same text is a query of its own
+    cache.completions("teh", MatchMode::Exact);
+    assert_eq!(cache.stats().misses, 4);
+
+    let mut cache = CachedDawg::new(Rc::new(build(&["the"])), 10);
+    assert_eq!(cache.suggest("teh", MatchMode::Exact, 5, 2), ["the"]);
+    cache.replace(Rc::new(build(&["ten"])));
+    assert_eq!(cache.suggest("teh", MatchMode::Exact, 5, 2), ["ten"]);
+}
+
+#[test]
+fn replacing_the_dawg_drops_its_results() {
+    let dawg = Rc::new(build(&common::words()));
+    let mut cache = CachedDawg::new(Rc::clone(&dawg), 10);
+    let count = cache.count_completions("a", MatchMode::Exact);
+    assert_eq!(count, Lexicon::completions(&*dawg, "a", MatchMode::Exact).len());
+
+    let previous = cache.replace(Rc::new(build(&["ax", "ay"])));
+    assert!(Rc::ptr_eq(&previous, &dawg));
+    assert_eq!(cache.stats().resident, 0);
+    assert_eq!(cache.count_completions("a", MatchMode::Exact), 2);
+    assert_eq!(cache.completions("a", MatchMode::Exact), ["ax", "ay"]);
+    assert_eq!(cache.word_count(), 2);
+    assert_eq!(cache.into_inner().word_count(), 2);
+    assert_eq!(CachedDawg::new(&*dawg, 0).capacity(), 1);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_cache_is_shared_between_threads() {
+    use std::{sync::Arc, thread};
+
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(common::words()).unwrap();
+    dawg.finish();
+    let cache = Arc::new(CachedDawg::new(dawg, 50));
+
+    let threads = (0..4).map(|_| {
+        let cache = Arc::clone(&cache);
+        thread::spawn(move || ["a", "b", "q", "mo"].map(|prefix| cache.completions(prefix, MatchMode::Exact)))
+    }).collect::<Vec<_>>();
+    let expected = ["a", "b", "q", "mo"].map(|prefix| Lexicon::completions(cache.get_ref(), prefix, MatchMode::Exact));
+    for thread in threads {
+        assert_eq!(thread.join().unwrap(), expected);
+    }
+
+    let stats = cache.stats();
+    assert_eq!((stats.hits + stats.misses, stats.resident), (16, 4));
+}
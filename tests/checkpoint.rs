@@ -0,0 +1,104 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{DawgError, UnsyncDawg};
+
+const BATCH_A: [&str; 4] = ["apple", "apply", "band", "bandana"];
+const BATCH_B: [&str; 5] = ["bandanas", "bands", "cat", "cats", "dog"];
+const BATCH_C: [&str; 2] = ["zebra", "zebras"];
+
+/// Adds every word of `words` to `dawg`, checking its invariants after each
+fn add(dawg: &mut UnsyncDawg, words: &[&str]) {
+    for word in words {
+        dawg.try_add(word).unwrap();
+        checked(dawg);
+    }
+}
+
+#[test]
+fn rolling_back_a_batch_builds_the_dawg_of_the_others() {
+    let mut dawg = UnsyncDawg::new();
+    add(&mut dawg, &BATCH_A);
+    let checkpoint = dawg.checkpoint();
+    add(&mut dawg, &BATCH_B);
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+
+    dawg.finish();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.to_bytes().unwrap(), build(&BATCH_A).to_bytes().unwrap());
+}
+
+#[test]
+fn nested_checkpoints_roll_back_in_turn() {
+    let mut dawg = UnsyncDawg::new();
+    add(&mut dawg, &BATCH_A);
+    let first = dawg.checkpoint();
+    add(&mut dawg, &BATCH_B);
+    let second = dawg.checkpoint();
+    add(&mut dawg, &BATCH_C);
+
+    dawg.rollback(second).unwrap();
+    checked(&dawg);
+    let mut finished = dawg.clone();
+    finished.finish();
+    assert_eq!(finished.to_bytes().unwrap(), build(&[&BATCH_A[..], &BATCH_B[..]].concat()).to_bytes().unwrap());
+
+    // a checkpoint stays valid after being rolled back to, the ones taken after it don't
+    dawg.rollback(first).unwrap();
+    dawg.rollback(first).unwrap();
+    assert!(matches!(dawg.rollback(second), Err(DawgError::InvalidCheckpoint)));
+
+    add(&mut dawg, &["banner", "cow"]);
+    let third = dawg.checkpoint();
+    add(&mut dawg, &["dz"]);
+    dawg.rollback(third).unwrap();
+    assert!(matches!(dawg.rollback(second), Err(DawgError::InvalidCheckpoint)));
+
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.to_bytes().unwrap(), build(&["apple", "apply", "band", "bandana", "banner", "cow"]).to_bytes().unwrap());
+}
+
+#[test]
+fn rolling_back_after_finish_fails() {
+    let mut dawg = UnsyncDawg::new();
+    add(&mut dawg, &BATCH_A);
+    let checkpoint = dawg.checkpoint();
+    dawg.finish();
+    assert!(matches!(dawg.rollback(checkpoint), Err(DawgError::InvalidCheckpoint)));
+    assert!(dawg.words().eq(BATCH_A));
+}
+
+#[test]
+fn rolling_back_a_finished_dawg_takes_back_its_edits() {
+    let mut dawg = build(&BATCH_A);
+    let checkpoint = dawg.checkpoint();
+    dawg.try_add("c").unwrap();
+    dawg.remove("band").unwrap();
+    assert!(dawg.contains("c") && !dawg.contains("band"));
+
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+    assert!(!dawg.contains("c"));
+    assert!(dawg.words().eq(BATCH_A));
+    dawg.finish();
+    assert_eq!(dawg.to_bytes().unwrap(), build(&BATCH_A).to_bytes().unwrap());
+}
+
+#[test]
+fn rolling_back_to_the_start_empties_the_dawg() {
+    let mut dawg = UnsyncDawg::new();
+    let checkpoint = dawg.checkpoint();
+    add(&mut dawg, &BATCH_C);
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+
+    // words sorted before the rolled back ones can be added again
+    add(&mut dawg, &["a"]);
+    dawg.finish();
+    assert_eq!(dawg.to_bytes().unwrap(), build(&["a"]).to_bytes().unwrap());
+}
@@ -0,0 +1,97 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::{lexicon::{FoldProfile, Lexicon, MatchMode}, query::Containment}, UnsyncDawg};
+
+const MODES: [MatchMode; 4] = [
+    MatchMode::Exact,
+    MatchMode::AsciiInsensitive,
+    MatchMode::UnicodeInsensitive(FoldProfile::Default),
+    MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+];
+
+/// Asserts `classify` agrees with the queries it stands for, for prefixes of every length of some of the words along
+/// with longer and case-changed strings
+fn assert_parity(dawg: &impl Lexicon, classify: impl Fn(&str, MatchMode) -> Containment, words: &[String]) {
+    for word in words.iter().step_by(29) {
+        for len in 0..=word.chars().count() {
+            let prefix = word.chars().take(len).collect::<String>();
+            for probe in [format!("{prefix}q"), prefix.to_uppercase(), prefix] {
+                for mode in MODES {
+                    let containment = classify(&probe, mode);
+                    let longer = dawg.completions(&probe, mode).iter().any(|word| word.chars().count() > probe.chars().count());
+                    assert_eq!(containment.is_word(), dawg.contains(&probe, mode), "{probe:?} {mode:?}");
+                    assert_eq!(containment.is_prefix(), dawg.contains_prefix(&probe, mode), "{probe:?} {mode:?}");
+                    assert_eq!(containment.is_prefix_of_other_words(), longer, "{probe:?} {mode:?}");
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn strings_are_words_prefixes_both_or_neither() {
+    let dawg = build(&["cat", "cats", "dog"]);
+    assert_eq!(dawg.classify("cat", MatchMode::Exact), Containment::WordAndPrefix);
+    assert_eq!(dawg.classify("cats", MatchMode::Exact), Containment::Word);
+    assert_eq!(dawg.classify("ca", MatchMode::Exact), Containment::Prefix);
+    assert_eq!(dawg.classify("cq", MatchMode::Exact), Containment::Neither);
+    assert_eq!(dawg.classify("catsup", MatchMode::Exact), Containment::Neither);
+    assert_eq!(dawg.classify("", MatchMode::Exact), Containment::Prefix);
+    assert_eq!(build(&["", "a"]).classify("", MatchMode::Exact), Containment::WordAndPrefix);
+    assert_eq!(UnsyncDawg::new().classify("", MatchMode::Exact), Containment::Neither);
+
+    for (containment, is_word, longer) in [(Containment::Neither, false, false), (Containment::Prefix, false, true), (Containment::Word, true, false), (Containment::WordAndPrefix, true, true)] {
+        assert_eq!((containment.is_word(), containment.is_prefix_of_other_words()), (is_word, longer));
+        assert_eq!(containment.is_prefix(), is_word || longer);
+    }
+}
+
+#[test]
+fn case_insensitive_modes_classify_every_spelling() {
+    let dawg = build(&["Cat", "café", "cats", "dog"]);
+    assert_eq!(dawg.classify("CAT", MatchMode::Exact), Containment::Neither);
+    assert_eq!(dawg.classify("cat", MatchMode::Exact), Containment::Prefix);
+    // "Cat" is a word and "cats" goes on from "cat"
+    assert_eq!(dawg.classify("cat", MatchMode::AsciiInsensitive), Containment::WordAndPrefix);
+    assert_eq!(dawg.classify("CaTs", MatchMode::UnicodeInsensitive(FoldProfile::Default)), Containment::Word);
+    assert_eq!(dawg.classify("CAFÉ", MatchMode::AsciiInsensitive), Containment::Neither);
+    assert_eq!(dawg.classify("CAFÉ", MatchMode::UnicodeInsensitive(FoldProfile::Default)), Containment::Word);
+    assert_eq!(dawg.classify("cafe", MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents)), Containment::Word);
+}
+
+#[test]
+fn classify_agrees_with_the_separate_queries() {
+    let words = common::words();
+    let mut dawg = build(&words);
+    assert_parity(&dawg, |probe, mode| dawg.classify(probe, mode), &words);
+
+    // a leaf gaining a longer word, and losing it
+    let leaf = words.iter().find(|word| dawg.classify(word, MatchMode::Exact) == Containment::Word).unwrap().clone();
+    dawg.insert(&format!("{leaf}s")).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.classify(&leaf, MatchMode::Exact), Containment::WordAndPrefix);
+    dawg.remove(&leaf).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.classify(&leaf, MatchMode::Exact), Containment::Prefix);
+    dawg.remove(&format!("{leaf}s")).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.classify(&leaf, MatchMode::Exact), Containment::Neither);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn sync_dawgs_classify_as_well() {
+    let words = common::words();
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(&words).unwrap();
+    dawg.finish();
+    checked(&dawg);
+    let unsync = build(&words);
+    assert_parity(&dawg, |probe, mode| dawg.classify(probe, mode), &words[..words.len() / 2]);
+    for word in words.iter().step_by(7) {
+        assert_eq!(dawg.classify(word, MatchMode::Exact), unsync.classify(word, MatchMode::Exact));
+    }
+}
@@ -0,0 +1,92 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, words};
+use dawging::UnsyncDawg;
+
+const WORDS: [&str; 4] = ["bat", "bats", "cat", "cats"];
+
+#[test]
+fn a_clone_holds_the_same_words_in_as_many_nodes() {
+    let dawg = build(&words());
+    let clone = dawg.clone();
+    checked(&clone);
+    clone.assert_minimal().unwrap();
+
+    assert!(clone.words().eq(dawg.words()));
+    assert_eq!(clone.compression_stats(), dawg.compression_stats());
+    assert_eq!(clone.to_bytes().unwrap(), dawg.to_bytes().unwrap());
+}
+
+#[test]
+fn an_unfinished_clone_is_built_on_independently() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.add_all(WORDS).unwrap();
+    let mut clone = dawg.clone();
+    checked(&clone);
+
+    clone.try_add("dog").unwrap();
+    checked(&clone);
+    clone.finish();
+    dawg.add_all(["rat", "rats"]).unwrap();
+    checked(&dawg);
+    dawg.finish();
+
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["bat", "bats", "cat", "cats", "rat", "rats"]);
+    assert_eq!(clone.words().collect::<Vec<_>>(), ["bat", "bats", "cat", "cats", "dog"]);
+    assert_eq!(dawg.to_bytes().unwrap(), build(&["bat", "bats", "cat", "cats", "rat", "rats"]).to_bytes().unwrap());
+    assert_eq!(clone.to_bytes().unwrap(), build(&["bat", "bats", "cat", "cats", "dog"]).to_bytes().unwrap());
+}
+
+#[test]
+fn editing_a_clone_leaves_the_original_alone() {
+    let dawg = build(&WORDS);
+    let before = dawg.to_bytes().unwrap();
+
+    let mut clone = dawg.clone();
+    assert!(clone.remove("bats").unwrap());
+    checked(&clone);
+    assert!(clone.insert("ca").unwrap());
+    checked(&clone);
+
+    assert_eq!(clone.words().collect::<Vec<_>>(), ["bat", "ca", "cat", "cats"]);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), WORDS);
+    assert_eq!(dawg.to_bytes().unwrap(), before);
+    checked(&dawg);
+}
+
+#[test]
+fn the_checkpoints_stay_with_the_original() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("a").unwrap();
+    let checkpoint = dawg.checkpoint();
+    dawg.try_add("b").unwrap();
+
+    let mut clone = dawg.clone();
+    assert!(matches!(clone.rollback(checkpoint), Err(dawging::DawgError::InvalidCheckpoint)));
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+    clone.finish();
+    dawg.finish();
+
+    assert_eq!(clone.words().collect::<Vec<_>>(), ["a", "b"]);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["a"]);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn a_sync_clone_is_as_small_and_independent() {
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+
+    let mut clone = dawg.clone();
+    checked(&clone);
+    assert_eq!(clone.compression_stats(), dawg.compression_stats());
+
+    clone.remove("cat").unwrap();
+    checked(&clone);
+    assert_eq!(clone.words().count(), 3);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), WORDS);
+}
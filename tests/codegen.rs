@@ -0,0 +1,99 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::{build, checked, fixture};
+use dawging::{codegen::{compile_word_list, compile_word_list_with, Artifact, CodegenOptions}, dawg::{embedded::CompiledView, options::DawgOptions, wordlist::Utf8Policy}, DawgError, UnsyncDawg};
+
+/// A directory of its own under the system's temporary one, for the artifacts of a test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dawging-codegen-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn the_fixture_compiles_to_the_same_bytes_every_time() {
+    let dir = temp_dir("fixture");
+    let words = common::words();
+    let (first, second) = (dir.join("first.dawg"), dir.join("second.dawg"));
+
+    let report = compile_word_list(&fixture("words.txt"), &first, &DawgOptions::new()).unwrap();
+    assert_eq!(compile_word_list(&fixture("words.txt"), &second, &DawgOptions::new()).unwrap(), report);
+    let bytes = fs::read(&first).unwrap();
+    assert_eq!(bytes, fs::read(&second).unwrap());
+    assert_eq!(bytes, build(&words).to_bytes().unwrap());
+
+    assert!(report.written);
+    assert_eq!((report.lines, report.words, report.skipped, report.bytes), (words.len(), words.len(), 0, bytes.len()));
+    let dawg = UnsyncDawg::<char>::load(&bytes[..]).unwrap();
+    checked(&dawg);
+    let stats = dawg.compression_stats();
+    assert_eq!((report.nodes, report.edges), (stats.dawg_nodes, stats.dawg_edges));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn an_unchanged_artifact_is_left_untouched() {
+    let dir = temp_dir("unchanged");
+    let output = dir.join("words.dawg");
+    compile_word_list(&fixture("words.txt"), &output, &DawgOptions::new()).unwrap();
+    let modified = fs::metadata(&output).unwrap().modified().unwrap();
+    std::thread::sleep(std::time::Duration::from_millis(20));
+
+    let report = compile_word_list(&fixture("words.txt"), &output, &DawgOptions::new()).unwrap();
+    assert!(!report.written);
+    assert_eq!(fs::metadata(&output).unwrap().modified().unwrap(), modified);
+
+    // other options give other bytes, which replace the old ones
+    assert!(compile_word_list(&fixture("words.txt"), &output, &DawgOptions::new().case_folding(true)).unwrap().written);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn an_unsorted_list_can_be_sorted_and_compiled() {
+    let dir = temp_dir("compiled");
+    let words = common::words();
+    let (input, output) = (dir.join("reversed.txt"), dir.join("words.dawc"));
+    fs::write(&input, words.iter().rev().cloned().collect::<Vec<_>>().join("\n")).unwrap();
+
+    let options = CodegenOptions::new().sort(true).artifact(Artifact::Compiled);
+    let report = compile_word_list_with(&input, &output, &options).unwrap();
+    let bytes = fs::read(&output).unwrap();
+    let view = CompiledView::from_bytes(&bytes).unwrap();
+    assert_eq!(view.words(), words);
+    assert_eq!(bytes, build(&words).compile().to_bytes());
+    assert_eq!((report.lines, report.words, report.nodes, report.edges), (0, words.len(), view.node_count(), view.edge_count()));
+
+    // without sorting, the reversed list stops on its second line
+    let error = compile_word_list(&input, &dir.join("unsorted.dawg"), &DawgOptions::new()).unwrap_err();
+    assert!(matches!(error, DawgError::Line { line: 2, .. }), "{error}");
+    assert!(!dir.join("unsorted.dawg").exists());
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn skipped_lines_are_reported() {
+    let dir = temp_dir("skipped");
+    let input = dir.join("messy.txt");
+    fs::write(&input, b"# words\napple\nb\xffad\n\ncherry\n").unwrap();
+
+    let options = CodegenOptions::new().utf8_policy(Utf8Policy::Skip);
+    let report = compile_word_list_with(&input, &dir.join("words.dawg"), &options).unwrap();
+    assert_eq!((report.lines, report.words, report.skipped), (5, 2, 3));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_missing_input_writes_nothing() {
+    let dir = temp_dir("missing");
+    let output = dir.join("words.dawg");
+    for sort in [false, true] {
+        let error = compile_word_list_with(&dir.join("missing.txt"), &output, &CodegenOptions::new().sort(sort)).unwrap_err();
+        assert!(matches!(&error, DawgError::Io(io) if io.kind() == std::io::ErrorKind::NotFound), "{error}");
+        assert!(!output.exists());
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,35 @@
+//! An allocator counting the allocations of each thread, for the tests checking that queries don't allocate. A test
+//! binary opts in with `#[global_allocator] static ALLOCATOR: Counting = Counting;`
+
+use std::{alloc::{GlobalAlloc, Layout, System}, cell::Cell};
+
+/// The system allocator, counting the allocations (and reallocations) of each thread, so tests running alongside don't
+/// interfere
+pub struct Counting;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for Counting {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.alloc(layout) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { System.dealloc(ptr, layout) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, size: usize) -> *mut u8 {
+        let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+        unsafe { System.realloc(ptr, layout, size) }
+    }
+}
+
+/// Runs `f`, returning its result along with the number of allocations it made on this thread
+pub fn allocations<R>(f: impl FnOnce() -> R) -> (R, usize) {
+    let before = ALLOCATIONS.with(Cell::get);
+    let result = f();
+    (result, ALLOCATIONS.with(Cell::get) - before)
+}
@@ -0,0 +1,167 @@
+//! Helpers shared by the tests: the fixtures, and dawgs built with their invariants checked after every change
+#![allow(dead_code, unused_imports, unused_macros)]
+
+pub mod alloc;
+
+use std::path::PathBuf;
+
+use dawging::{dawg::{common::{Label, Wrapper}, options::DawgOptions}, Dawg};
+#[cfg(feature = "unsync")]
+use dawging::UnsyncDawg;
+
+/// Turns each generic test `name::<T>` into a test per wrapper compiled in, `unsync::name` and `sync::name`
+macro_rules! wrapper_tests {
+    ($($test:ident),* $(,)?) => {
+        #[cfg(feature = "unsync")]
+        mod unsync {
+            $(#[test] fn $test() { super::$test::<dawging::UnsyncWrapper>() })*
+        }
+
+        #[cfg(feature = "sync")]
+        mod sync {
+            $(#[test] fn $test() { super::$test::<dawging::SyncWrapper>() })*
+        }
+    };
+}
+pub(crate) use wrapper_tests;
+
+/// xorshift, to draw the same words, edits and queries on every run from the seed in `state`
+pub fn xorshift(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
+
+/// Returns the path of the fixture `name`
+pub fn fixture(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name)
+}
+
+/// The words of `fixtures/words.txt`, sorted: about 2600 words of a to z, then a few beyond ascii
+pub fn words() -> Vec<String> {
+    std::fs::read_to_string(fixture("words.txt")).unwrap().lines().map(String::from).collect()
+}
+
+/// Panics with the violation if the structure of `dawg` is broken
+pub fn checked<T, L>(dawg: &Dawg<T, L>) where T: Wrapper<L>, L: Label {
+    if let Err(violation) = dawg.assert_invariants() {
+        panic!("broken invariant: {violation}");
+    }
+}
+
+/// Builds a finished dawg of `words`, given in sorted order, checking the invariants after every word (every 100th word
+/// of the longer lists, which would take a walk of the whole dawg per word otherwise)
+#[cfg(feature = "unsync")]
+pub fn build<S: AsRef<str>>(words: &[S]) -> UnsyncDawg {
+    build_with(words, DawgOptions::new())
+}
+
+/// Same as `build`, with `options`
+#[cfg(feature = "unsync")]
+pub fn build_with<S: AsRef<str>>(words: &[S], options: DawgOptions) -> UnsyncDawg {
+    build_in(words, options)
+}
+
+/// Same as `build_with`, the nodes being allocated by the wrapper `T`
+pub fn build_in<T: Wrapper, S: AsRef<str>>(words: &[S], options: DawgOptions) -> Dawg<T> {
+    let mut dawg = Dawg::<T>::with_options(options).unwrap();
+    for (index, word) in words.iter().enumerate() {
+        dawg.try_add(word.as_ref()).unwrap();
+        if words.len() <= 200 || index % 100 == 0 {
+            checked(&dawg);
+        }
+    }
+    dawg.finish();
+    if let Err(violation) = dawg.assert_minimal() {
+        panic!("broken invariant: {violation}");
+    }
+    dawg
+}
+
+/// CRC-32 (IEEE) of `bytes`, to forge saved dawgs whose checksums still match
+pub fn crc32(bytes: &[u8]) -> u32 {
+    let mut crc = !0u32;
+    for byte in bytes {
+        crc ^= *byte as u32;
+        for _ in 0..8 {
+            crc = if crc & 1 == 1 { (crc >> 1) ^ 0xEDB8_8320 } else { crc >> 1 };
+        }
+    }
+    !crc
+}
+
+/// Returns the offset of the node section of a saved dawg and its number of nodes
+pub fn node_section(bytes: &[u8]) -> (usize, usize) {
+    let options_len = u32::from_le_bytes(bytes[6..10].try_into().unwrap()) as usize;
+    let counts = 10 + options_len;
+    let node_count = u32::from_le_bytes(bytes[counts..counts + 4].try_into().unwrap()) as usize;
+    (counts + 4 * 4 + 4, node_count)
+}
+
+/// Rewrites the word count of the saved node `node` (ids as saved), then the checksum of the node section (of less than
+/// a chunk) so that it still matches
+pub fn forge_count(bytes: &[u8], node: usize, count: u64) -> Vec<u8> {
+    let (start, node_count) = node_section(bytes);
+    let mut forged = bytes.to_vec();
+    let record = start + node * 17;
+    forged[record + 1..record + 9].copy_from_slice(&count.to_le_bytes());
+
+    let end = start + node_count * 17;
+    let checksum = crc32(&forged[start..end]);
+    forged[end..end + 4].copy_from_slice(&checksum.to_le_bytes());
+    forged
+}
+
+/// `forge_count` for a saved CompiledDawg, whose header has one count less and whose node records are 21 bytes long
+pub fn forge_compiled_count(bytes: &[u8], node: usize, count: u64) -> Vec<u8> {
+    let options_len = u32::from_le_bytes(bytes[6..10].try_into().unwrap()) as usize;
+    let counts = 10 + options_len;
+    let node_count = u32::from_le_bytes(bytes[counts..counts + 4].try_into().unwrap()) as usize;
+    let start = counts + 3 * 4 + 4;
+    let mut forged = bytes.to_vec();
+    let record = start + node * 21;
+    forged[record + 1..record + 9].copy_from_slice(&count.to_le_bytes());
+
+    let end = start + node_count * 21;
+    let checksum = crc32(&forged[start..end]);
+    forged[end..end + 4].copy_from_slice(&checksum.to_le_bytes());
+    forged
+}
+
+/// A saved dawg of `len` nodes in a chain, each but the last one having edges 'a' and 'b' to the next: its checksums and
+/// word counts (saturated at `u64::MAX`, like the loader counts them) all match, but it has 2^(len - 1) words
+pub fn forge_chain<T: Wrapper>(len: u32) -> Vec<u8> {
+    let mut empty = Dawg::with_wrapper(T::new());
+    empty.finish();
+    let empty = empty.to_bytes().unwrap();
+    let options_len = u32::from_le_bytes(empty[6..10].try_into().unwrap()) as usize;
+
+    let (mut nodes, mut edges) = (vec![], vec![]);
+    for id in 0..len {
+        let last = id + 1 == len;
+        let count = 1u64.checked_shl(len - 1 - id).unwrap_or(u64::MAX);
+        nodes.push(last as u8);
+        nodes.extend_from_slice(&count.to_le_bytes());
+        nodes.extend_from_slice(&(edges.len() as u32 / 12).to_le_bytes());
+        nodes.extend_from_slice(&(if last { 0u32 } else { 2 }).to_le_bytes());
+
+        for label in (0..2u32).filter(|_| !last) {
+            for value in [id + 1, label, 1] {
+                edges.extend_from_slice(&value.to_le_bytes());
+            }
+        }
+    }
+
+    let mut forged = empty[..10 + options_len].to_vec();
+    for value in [len, 2 * (len - 1), 2, 0] {
+        forged.extend_from_slice(&value.to_le_bytes());
+    }
+    let header = crc32(&forged);
+    forged.extend_from_slice(&header.to_le_bytes());
+    for section in [&nodes[..], &edges[..], b"ab"] {
+        forged.extend_from_slice(section);
+        forged.extend_from_slice(&crc32(section).to_le_bytes());
+    }
+    forged
+}
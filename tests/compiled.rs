@@ -0,0 +1,147 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, build_with, forge_compiled_count};
+use dawging::{dawg::{compiled::CompiledDawg, lexicon::{FoldProfile, Lexicon, MatchMode}, options::DawgOptions}, DawgError, UnsyncDawg};
+
+const MODES: [MatchMode; 4] = [
+    MatchMode::Exact,
+    MatchMode::AsciiInsensitive,
+    MatchMode::UnicodeInsensitive(FoldProfile::Default),
+    MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+];
+
+/// The words of the fixture along with upper-cased and accented variants, so that the root has a wide fanout
+fn mixed_words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(common::words().iter().step_by(5).map(|word| word.to_uppercase()));
+    words.extend(["é", "éte", "Été", "ß", "über", "Über", "字"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Every answer of `lexicon` to queries built from `words`, to compare layouts through the trait alone
+fn answers(lexicon: &impl Lexicon, words: &[String]) -> Vec<String> {
+    let mut answers = vec![lexicon.word_count().to_string()];
+    let extra = ["", "zz", "Q", "é", "E", "\u{10FFFF}"].map(String::from);
+    for word in words.iter().step_by(17).chain(&extra) {
+        let prefix = word.chars().take(2).collect::<String>();
+        for mode in MODES {
+            answers.push(format!("{}", lexicon.contains(word, mode)));
+            answers.push(format!("{}", lexicon.contains(&format!("{word}!"), mode)));
+            answers.push(format!("{}", lexicon.contains_prefix(&prefix, mode)));
+            answers.push(format!("{:?}", lexicon.completions(&prefix, mode)));
+        }
+    }
+    answers
+}
+
+#[test]
+fn every_layout_answers_as_the_dawg() {
+    let words = mixed_words();
+    let dawg = build(&words);
+    let expected = answers(&dawg, &words);
+
+    let (tables, simple, all) = (dawg.compile(), dawg.compile_with(usize::MAX), dawg.compile_with(1));
+    assert!(tables.dispatch_tables() > 0);
+    assert_eq!(simple.dispatch_tables(), 0);
+    assert!(all.dispatch_tables() > tables.dispatch_tables());
+
+    for compiled in [&tables, &simple, &all] {
+        assert_eq!(compiled.node_count(), dawg.visit_nodes().count());
+        assert_eq!(compiled.words(), words);
+        assert_eq!(answers(compiled, &words), expected);
+    }
+}
+
+#[test]
+fn the_tables_round_trip() {
+    let words = mixed_words();
+    let dawg = build(&words);
+
+    for compiled in [dawg.compile(), dawg.compile_with(usize::MAX)] {
+        let loaded = CompiledDawg::from_bytes(&compiled.to_bytes()).unwrap();
+        assert_eq!(loaded, compiled);
+        assert_eq!(loaded.dispatch_tables(), compiled.dispatch_tables());
+
+        let mut saved = vec![];
+        compiled.save(&mut saved).unwrap();
+        assert_eq!(CompiledDawg::load(&saved[..]).unwrap(), compiled);
+    }
+}
+
+#[test]
+fn a_fat_root_takes_fewer_probes() {
+    let mut words = vec![];
+    for first in ('a'..='z').chain('A'..='Z') {
+        for vowel in ['a', 'e', 'i', 'o', 'u'] {
+            words.push(format!("{first}{vowel}"));
+        }
+    }
+    words.sort();
+    let dawg = build(&words);
+    let (tables, simple) = (dawg.compile(), dawg.compile_with(usize::MAX));
+
+    let probes = |compiled: &CompiledDawg| words.iter().map(|word| compiled.probes(word)).sum::<usize>();
+    assert!(probes(&tables) * 2 < probes(&simple), "{} vs {}", probes(&tables), probes(&simple));
+    assert!(words.iter().all(|word| tables.contains(word, MatchMode::Exact)));
+    assert_eq!(tables.probes("!"), 1);
+}
+
+#[test]
+fn the_options_are_compiled_along() {
+    let dawg = build_with(&["apple", "banana", "cherry"], DawgOptions::new().case_folding(true));
+    let compiled = dawg.compile();
+    assert!(compiled.options().is_case_folding());
+    assert!(compiled.contains("APPLE", MatchMode::Exact));
+    assert!(CompiledDawg::from_bytes(&compiled.to_bytes()).unwrap().contains("Cherry", MatchMode::Exact));
+}
+
+#[test]
+fn an_empty_dawg_compiles() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.finish();
+    let compiled = dawg.compile();
+    assert_eq!(compiled.word_count(), 0);
+    assert!(!compiled.contains("", MatchMode::Exact));
+    assert!(compiled.words().is_empty());
+    assert_eq!(CompiledDawg::from_bytes(&compiled.to_bytes()).unwrap(), compiled);
+}
+
+#[test]
+fn broken_tables_are_refused() {
+    let dawg = build(&["a", "b", "c", "d"]);
+    let compiled = dawg.compile_with(2);
+    assert_eq!(compiled.dispatch_tables(), 1);
+    let bytes = compiled.to_bytes();
+
+    for len in 0..bytes.len() {
+        assert!(CompiledDawg::from_bytes(&bytes[..len]).is_err(), "cut at {len}");
+    }
+    assert!(CompiledDawg::from_bytes(&dawg.to_bytes().unwrap()).is_err());
+
+    // every byte changed either fails to load or gives a dawg that can be queried without panicking
+    for index in 0..bytes.len() {
+        for value in [0, 1, 0xFF] {
+            let mut changed = bytes.clone();
+            changed[index] = value;
+            if let Ok(compiled) = CompiledDawg::from_bytes(&changed) {
+                compiled.words();
+                compiled.contains("a", MatchMode::AsciiInsensitive);
+                compiled.probes("abc");
+            }
+        }
+    }
+}
+
+#[test]
+fn forged_counts_are_refused() {
+    let compiled = build(&["a", "ab", "b"]).compile();
+    let bytes = compiled.to_bytes();
+    assert_eq!(CompiledDawg::from_bytes(&forge_compiled_count(&bytes, 0, 3)).unwrap(), compiled);
+
+    let forged = forge_compiled_count(&bytes, 0, 2);
+    assert!(matches!(CompiledDawg::from_bytes(&forged), Err(DawgError::CorruptedCount { node: 0, stored: 2, actual: 3 })));
+}
@@ -0,0 +1,96 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::complete::Completion, UnsyncDawg};
+
+const WORDS: [&str; 7] = ["car", "cart", "carton", "internationalisation", "internationalization", "internet", "zebra"];
+
+/// The completion of `prefix` from the words starting with it: the longest prefix they share, cut at the first of them
+/// ending there
+fn brute_force(words: &[String], prefix: &str) -> Completion {
+    let completions = words.iter().filter(|word| word.starts_with(prefix)).collect::<Vec<_>>();
+    match completions.as_slice() {
+        [] => Completion::None,
+        [word] => Completion::Unique(word.to_string()),
+        [first, rest @ ..] => {
+            let mut shared = first.chars().collect::<Vec<_>>();
+            for word in rest {
+                let len = shared.iter().zip(word.chars()).take_while(|(a, b)| **a == *b).count();
+                shared.truncate(len);
+            }
+            let len = prefix.chars().count().max(completions.iter().map(|word| word.chars().count()).min().unwrap().min(shared.len()));
+            Completion::CommonPrefix(shared[..len].iter().collect())
+        }
+    }
+}
+
+#[test]
+fn prefixes_complete_as_far_as_they_are_unambiguous() {
+    let dawg = build(&WORDS);
+    let common = |prefix: &str| Completion::CommonPrefix(prefix.to_string());
+
+    assert_eq!(dawg.complete_unique("ze"), Completion::Unique("zebra".into()));
+    assert_eq!(dawg.complete_unique("carto"), Completion::Unique("carton".into()));
+    assert_eq!(dawg.complete_unique("internationalis"), Completion::Unique("internationalisation".into()));
+    // a full word alone is its own completion
+    assert_eq!(dawg.complete_unique("zebra"), Completion::Unique("zebra".into()));
+
+    // a long run shared before the words part
+    assert_eq!(dawg.complete_unique("i"), common("intern"));
+    assert_eq!(dawg.complete_unique("interna"), common("internationali"));
+    assert_eq!(dawg.complete_unique("intern"), common("intern"));
+    // stopping at the first word ending, however the others go on
+    assert_eq!(dawg.complete_unique("ca"), common("car"));
+    assert_eq!(dawg.complete_unique("cart"), common("cart"));
+    assert_eq!(dawg.complete_unique(""), common(""));
+
+    assert_eq!(dawg.complete_unique("x"), Completion::None);
+    assert_eq!(dawg.complete_unique("zebras"), Completion::None);
+}
+
+#[test]
+fn completions_are_those_of_brute_force() {
+    let mut words = common::words();
+    words.extend(WORDS.map(String::from));
+    words.sort();
+    words.dedup();
+    let dawg = build(&words);
+
+    for word in words.iter().step_by(3) {
+        let letters = word.chars().collect::<Vec<_>>();
+        for len in 0..=letters.len() {
+            let prefix = letters[..len].iter().collect::<String>();
+            assert_eq!(dawg.complete_unique(&prefix), brute_force(&words, &prefix), "{prefix:?}");
+        }
+    }
+}
+
+#[test]
+fn thousands_of_completions_give_the_shared_extension() {
+    let words = common::words().into_iter().filter(|word| word.is_ascii()).map(|word| format!("qqq{word}")).collect::<Vec<_>>();
+    assert!(words.len() > 2000);
+    let mut dawg = build(&words);
+    assert_eq!(dawg.complete_unique("q"), Completion::CommonPrefix("qqq".into()));
+
+    // the counts kept up by edits tell the completion as well
+    let only = dawg.words_with_prefix("qqqa").collect::<Vec<_>>();
+    for word in &only[1..] {
+        assert!(dawg.remove_sequence(&word.chars().collect::<Vec<_>>()));
+    }
+    checked(&dawg);
+    assert_eq!(dawg.complete_unique("qqqa"), Completion::Unique(only[0].clone()));
+}
+
+#[test]
+fn empty_and_unfinished_dawgs() {
+    assert_eq!(UnsyncDawg::new().complete_unique(""), Completion::None);
+    assert_eq!(build::<&str>(&[]).complete_unique(""), Completion::None);
+    assert_eq!(build(&[""]).complete_unique(""), Completion::Unique("".into()));
+
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("solo").unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.complete_unique("s"), Completion::Unique("solo".into()));
+}
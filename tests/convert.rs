@@ -0,0 +1,73 @@
+#![cfg(all(feature = "sync", feature = "unsync"))]
+
+mod common;
+
+use std::{sync::Arc, thread};
+
+use common::{build, build_with, checked};
+use dawging::{dawg::{options::DawgOptions, visit::NodeView}, DawgError, SyncDawg, UnsyncDawg};
+
+/// The edges of `node`, each with the id of the node it leads to
+fn edges(node: &NodeView<'_>) -> Vec<(char, usize)> {
+    node.edges().map(|(label, id)| (*label, id)).collect()
+}
+
+#[test]
+fn the_converted_dawg_holds_the_same_nodes() {
+    let words = common::words();
+    let dawg = build(&words);
+    let bytes = dawg.to_bytes().unwrap();
+    let nodes = dawg.visit_nodes().map(|node| (node.id(), node.is_terminal(), node.count(), edges(&node))).collect::<Vec<_>>();
+
+    let sync = dawg.to_sync().unwrap();
+    checked(&sync);
+    assert_eq!(sync.to_bytes().unwrap(), bytes);
+    assert_eq!(sync.visit_nodes().map(|node| (node.id(), node.is_terminal(), node.count(), edges(&node))).collect::<Vec<_>>(), nodes);
+    assert_eq!(sync.words().collect::<Vec<_>>(), words);
+    assert_eq!(sync.longest_completion("a"), build(&words).longest_completion("a"));
+
+    let unsync = sync.to_unsync().unwrap();
+    checked(&unsync);
+    assert_eq!(unsync.to_bytes().unwrap(), bytes);
+    assert_eq!(unsync.visit_nodes().count(), nodes.len());
+}
+
+#[test]
+fn the_options_go_along() {
+    let dawg = build_with(&["Cat", "dog"], DawgOptions::new().case_folding(true));
+    let sync = dawg.to_sync().unwrap();
+    assert!(sync.contains("CAT") && sync.contains("Dog"));
+    assert!(sync.to_unsync().unwrap().contains("cAt"));
+}
+
+#[test]
+fn the_sync_dawg_is_shared_between_threads_and_edited_afterwards() {
+    let words = Arc::new(common::words());
+    let dawg = Arc::new(build(&words).to_sync().unwrap());
+
+    let readers = (0..4).map(|reader| {
+        let (dawg, words) = (Arc::clone(&dawg), Arc::clone(&words));
+        thread::spawn(move || words.iter().skip(reader).step_by(4).all(|word| dawg.contains(word)) && !dawg.contains("zzzz"))
+    }).collect::<Vec<_>>();
+    for reader in readers {
+        assert!(reader.join().unwrap());
+    }
+
+    let mut dawg = Arc::try_unwrap(dawg).unwrap();
+    assert!(dawg.insert_sequence(&['z', 'z', 'z', 'z']));
+    checked(&dawg);
+    let dawg = dawg.to_unsync().unwrap();
+    assert!(dawg.contains("zzzz"));
+    assert_eq!(dawg.words().count(), words.len() + 1);
+}
+
+#[test]
+fn unfinished_dawgs_are_refused() {
+    let mut unsync = UnsyncDawg::new();
+    unsync.try_add("a").unwrap();
+    assert!(matches!(unsync.to_sync(), Err(DawgError::NotFinished)));
+
+    let mut sync = SyncDawg::new_sync();
+    sync.try_add("a").unwrap();
+    assert!(matches!(sync.to_unsync(), Err(DawgError::NotFinished)));
+}
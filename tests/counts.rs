@@ -0,0 +1,93 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, xorshift};
+use dawging::{dawg::lexicon::{Lexicon, MatchMode}, UnsyncDawg};
+
+/// A word of 1 to 6 letters out of "abcdé", so that edits share most of their paths
+fn draw_word(state: &mut u64) -> String {
+    (0..1 + xorshift(state) % 6).map(|_| ['a', 'b', 'c', 'd', 'é'][(xorshift(state) % 5) as usize]).collect()
+}
+
+/// Asserts every query going through the word counts agrees with `model`
+fn assert_counts(dawg: &UnsyncDawg, model: &BTreeSet<String>, state: &mut u64) {
+    let sorted = model.iter().collect::<Vec<_>>();
+    assert_eq!(Lexicon::word_count(dawg), sorted.len());
+
+    for _ in 0..5 {
+        let probe = draw_word(state);
+        assert_eq!(dawg.rank(&probe), sorted.binary_search(&&probe).ok(), "{probe}");
+        let index = xorshift(state) as usize % (sorted.len() + 2);
+        assert_eq!(dawg.select(index).as_ref(), sorted.get(index).copied());
+
+        let prefix = probe.chars().take(1 + xorshift(state) as usize % 3).collect::<String>();
+        let count = sorted.iter().filter(|word| word.starts_with(&prefix)).count();
+        assert_eq!(dawg.keys_at(&prefix, MatchMode::Exact).map_or(0, |keys| keys.word_count()), count, "{prefix}");
+        let page = dawg.completions_page(&prefix, None, 4);
+        assert_eq!(page.words, sorted.iter().filter(|word| word.starts_with(&prefix)).take(4).cloned().cloned().collect::<Vec<_>>());
+    }
+}
+
+#[test]
+fn counts_follow_thousands_of_edits() {
+    // debug builds also check every edit against a recount of the whole dawg
+    let mut state = 0x1234_5678;
+    let mut model = (0..600).map(|_| draw_word(&mut state)).collect::<BTreeSet<_>>();
+    let mut dawg = build(&model.iter().collect::<Vec<_>>());
+
+    for round in 0..4000 {
+        let word = draw_word(&mut state);
+        if xorshift(&mut state) % 2 == 0 {
+            assert_eq!(dawg.insert(&word).unwrap(), model.insert(word.clone()), "{word}");
+        } else {
+            assert_eq!(dawg.remove(&word).unwrap(), model.remove(&word), "{word}");
+        }
+        if round % 7 == 0 {
+            assert_counts(&dawg, &model, &mut state);
+        }
+    }
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), model.iter().cloned().collect::<Vec<_>>());
+}
+
+#[test]
+fn counts_of_forks_are_their_own() {
+    let mut state = 99;
+    let model = (0..400).map(|_| draw_word(&mut state)).collect::<BTreeSet<_>>();
+    let dawg = build(&model.iter().collect::<Vec<_>>());
+    let mut fork = dawg.fork();
+    let mut forked = model.clone();
+
+    for _ in 0..500 {
+        let word = draw_word(&mut state);
+        match forked.contains(&word) {
+            true => assert!(fork.remove(&word).unwrap()),
+            false => assert!(fork.insert(&word).unwrap()),
+        }
+        if !forked.remove(&word) {
+            forked.insert(word);
+        }
+    }
+    checked(&fork);
+    checked(&dawg);
+    assert_counts(&fork, &forked, &mut state);
+    assert_counts(&dawg, &model, &mut state);
+}
+
+#[test]
+fn counts_of_words_added_unsorted() {
+    // edits on a dawg still being built minimize it first
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("b").unwrap();
+    dawg.try_add("bd").unwrap();
+    assert!(dawg.insert("a").unwrap() && dawg.insert("abc").unwrap());
+    assert!(dawg.remove("bd").unwrap() && !dawg.remove("bd").unwrap());
+    checked(&dawg);
+
+    let model = ["a", "abc", "b"].map(String::from).into_iter().collect::<BTreeSet<_>>();
+    assert_counts(&dawg, &model, &mut 7);
+    assert_eq!((dawg.rank("abc"), dawg.select(2).as_deref()), (Some(1), Some("b")));
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::HashSet;
+
+use common::{build, build_with};
+use dawging::dawg::{crosscheck::{LetterAlphabet, LetterSet, MAX_LETTERS}, options::DawgOptions};
+
+/// (before, after) pairs around each letter of every 3rd word, along with a few around nothing in the dawg
+fn squares(words: &[String]) -> Vec<(String, String)> {
+    let mut squares = vec![(String::new(), String::new())];
+    for word in words.iter().step_by(3) {
+        let letters = word.chars().collect::<Vec<_>>();
+        for index in 0..letters.len() {
+            squares.push((letters[..index].iter().collect(), letters[index + 1..].iter().collect()));
+        }
+    }
+    squares.extend([("zq", ""), ("", "zzzzzz"), ("x", "y"), ("é", ""), ("", "é")].map(|(before, after)| (before.to_string(), after.to_string())));
+    squares
+}
+
+/// The letters of `alphabet` that make a word of `words` between `before` and `after`
+fn brute_force(words: &HashSet<&str>, alphabet: &LetterAlphabet, before: &str, after: &str) -> Vec<char> {
+    alphabet.letters().iter().copied().filter(|letter| words.contains(format!("{before}{letter}{after}").as_str())).collect()
+}
+
+#[test]
+fn cross_checks_find_the_letters_that_make_words() {
+    let words = common::words();
+    let lookup = words.iter().map(String::as_str).collect::<HashSet<_>>();
+    let dawg = build(&words);
+    let mixed = LetterAlphabet::new("abcé字xyz").unwrap();
+
+    for (before, after) in squares(&words) {
+        let latin = dawg.cross_checks(&before, &after);
+        assert_eq!(latin.letters(&LetterAlphabet::LATIN).collect::<Vec<_>>(), brute_force(&lookup, &LetterAlphabet::LATIN, &before, &after), "{before}_{after}");
+        assert_eq!(dawg.cross_checks_in(&before, &after, &LetterAlphabet::default()), latin);
+
+        let letters = dawg.cross_checks_in(&before, &after, &mixed);
+        assert_eq!(letters.letters(&mixed).collect::<Vec<_>>(), brute_force(&lookup, &mixed, &before, &after), "{before}_{after}");
+    }
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn sync_dawgs_give_the_same_cross_checks() {
+    let words = common::words();
+    let unsync = build(&words);
+    let mut sync = dawging::SyncDawg::new_sync();
+    for word in &words {
+        sync.try_add(word).unwrap();
+    }
+    sync.finish();
+    common::checked(&sync);
+
+    for (before, after) in squares(&words).into_iter().step_by(11) {
+        assert_eq!(sync.cross_checks(&before, &after), unsync.cross_checks(&before, &after), "{before}_{after}");
+    }
+}
+
+#[test]
+fn the_squares_are_folded_as_the_words() {
+    let dawg = build_with(&["cat", "cot"], DawgOptions::new().case_folding(true));
+    assert_eq!(dawg.cross_checks("C", "T").letters(&LetterAlphabet::LATIN).collect::<String>(), "ao");
+    assert_eq!(dawg.cross_checks("", "at").letters(&LetterAlphabet::LATIN).collect::<String>(), "c");
+    assert_eq!(dawg.cross_checks("ca", "").letters(&LetterAlphabet::LATIN).collect::<String>(), "t");
+    assert!(dawg.cross_checks("cat", "").is_empty());
+}
+
+#[test]
+fn letter_sets_hold_up_to_sixty_four_letters() {
+    assert!(LetterAlphabet::new(&('\u{100}'..'\u{200}').collect::<String>()).is_err());
+    let wide = LetterAlphabet::new(&('\u{100}'..'\u{140}').collect::<String>()).unwrap();
+    assert_eq!(wide.letters().len(), MAX_LETTERS);
+    assert_eq!(LetterSet::full(&wide).len(), MAX_LETTERS);
+    assert_eq!(LetterSet::full(&LetterAlphabet::LATIN).len(), 26);
+    assert!(LetterSet::full(&LetterAlphabet::new("").unwrap()).is_empty());
+
+    let alphabet = LetterAlphabet::new("cabba").unwrap();
+    assert_eq!(alphabet.letters(), ['a', 'b', 'c']);
+    let mut set = LetterSet::default();
+    assert!(set.insert('c', &alphabet) && set.insert('a', &alphabet));
+    assert!(!set.insert('z', &alphabet));
+    assert_eq!(set.letters(&alphabet).collect::<String>(), "ac");
+    assert_eq!(set.bits(), 0b101);
+
+    let mut other = LetterSet::default();
+    other.insert('b', &alphabet);
+    other.insert('c', &alphabet);
+    assert_eq!(set.intersection(other).letters(&alphabet).collect::<String>(), "c");
+    assert_eq!(set.union(other), LetterSet::full(&alphabet));
+    assert!(!set.contains('b', &alphabet) && !set.contains('z', &alphabet));
+}
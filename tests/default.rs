@@ -0,0 +1,89 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::mem;
+
+use common::checked;
+use dawging::{dawg::options::DawgOptions, Dawg, DawgError, UnsyncDawg, UnsyncWrapper};
+
+const WORDS: [&str; 5] = ["a", "ab", "abc", "b", "bc"];
+
+#[derive(Default)]
+struct Dictionary {
+    words: UnsyncDawg,
+    rebuilt: usize,
+}
+
+impl Dictionary {
+    /// Swaps the words out for an empty dawg, then adds them back along with `extra`
+    fn rebuild(&mut self, extra: &str) {
+        let old = mem::take(&mut self.words);
+        checked(&self.words);
+
+        let mut words = old.words().chain([extra.to_string()]).collect::<Vec<_>>();
+        words.sort();
+        for word in &words {
+            self.words.try_add(word).unwrap();
+            checked(&self.words);
+        }
+        self.words.finish();
+        self.rebuilt += 1;
+    }
+}
+
+#[test]
+fn a_default_dawg_behaves_like_a_new_one() {
+    let (mut defaulted, mut new) = (UnsyncDawg::default(), UnsyncDawg::new());
+    for word in WORDS {
+        defaulted.try_add(word).unwrap();
+        new.try_add(word).unwrap();
+        checked(&defaulted);
+    }
+    defaulted.finish();
+    new.finish();
+
+    assert_eq!(defaulted.to_bytes().unwrap(), new.to_bytes().unwrap());
+    assert_eq!(defaulted.options(), &DawgOptions::default());
+    assert!(!UnsyncDawg::<char>::default().is_finished());
+}
+
+#[test]
+fn a_taken_dawg_leaves_an_empty_one_behind() {
+    let mut dictionary = Dictionary::default();
+    dictionary.rebuild("b");
+    dictionary.rebuild("a");
+    assert_eq!(dictionary.rebuilt, 2);
+    assert_eq!(dictionary.words.words().collect::<Vec<_>>(), ["a", "b"]);
+
+    let taken = mem::take(&mut dictionary.words);
+    assert_eq!(taken.words().count(), 2);
+    assert_eq!(dictionary.words.words().count(), 0);
+    // a new dawg, still to be finished before it can be saved
+    assert!(!dictionary.words.is_finished());
+    assert!(matches!(dictionary.words.to_bytes(), Err(DawgError::NotFinished)));
+}
+
+#[test]
+fn other_labels_have_a_default_dawg_too() {
+    let mut dawg = Dawg::<UnsyncWrapper, u8>::default();
+    dawg.add_sequence(b"ab").unwrap();
+    dawg.add_sequence(b"b").unwrap();
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.sequences().collect::<Vec<_>>(), [b"ab".to_vec(), b"b".to_vec()]);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_default_behaves_like_new_sync() {
+    let (mut defaulted, mut new) = (dawging::SyncDawg::default(), dawging::SyncDawg::new_sync());
+    for word in WORDS {
+        defaulted.try_add(word).unwrap();
+        new.try_add(word).unwrap();
+    }
+    defaulted.finish();
+    new.finish();
+    checked(&defaulted);
+    assert_eq!(defaulted.to_bytes().unwrap(), new.to_bytes().unwrap());
+}
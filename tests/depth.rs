@@ -0,0 +1,97 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::UnsyncDawg;
+
+/// The words of the fixture along with a family of "ta" words
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["ta", "tab", "tabby", "tabs", "tac", "tacky"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The shortest (or longest) completion of `prefix`, the smallest in sorted order among those as short (or long)
+fn brute_force(words: &[String], prefix: &str, longest: bool) -> Option<String> {
+    words.iter().filter(|word| word.starts_with(prefix)).min_by(|a, b| {
+        let (a_len, b_len) = (a.chars().count(), b.chars().count());
+        let by_len = if longest { b_len.cmp(&a_len) } else { a_len.cmp(&b_len) };
+        by_len.then(a.cmp(b))
+    }).cloned()
+}
+
+/// Compares the completions and the depths of the nodes with brute force, for the first letters of every 13th word
+fn compare(dawg: &UnsyncDawg, words: &[String]) {
+    let mut prefixes = ["", "zzzq", "é", "字"].map(String::from).to_vec();
+    for word in words.iter().step_by(13) {
+        let letters = word.chars().collect::<Vec<_>>();
+        prefixes.extend((0..=letters.len().min(4)).map(|len| letters[..len].iter().collect::<String>()));
+    }
+
+    for prefix in &prefixes {
+        let (shortest, longest) = (brute_force(words, prefix, false), brute_force(words, prefix, true));
+        assert_eq!(dawg.shortest_completion(prefix), shortest, "{prefix:?}");
+        assert_eq!(dawg.longest_completion(prefix), longest, "{prefix:?}");
+
+        let depth = |word: Option<String>| word.map(|word| word.chars().count() - prefix.chars().count());
+        if let Some(node) = dawg.descend_sequence(&prefix.chars().collect::<Vec<_>>()) {
+            assert_eq!((node.min_completion_depth(), node.max_completion_depth()), (depth(shortest), depth(longest)), "{prefix:?}");
+        }
+    }
+}
+
+#[test]
+fn completions_are_those_of_brute_force() {
+    let words = words();
+    let dawg = build(&words);
+    compare(&dawg, &words);
+
+    assert_eq!(dawg.shortest_completion("ta").as_deref(), Some("ta"));
+    assert_eq!(dawg.shortest_completion("tab").as_deref(), Some("tab"));
+    assert_eq!(dawg.longest_completion("tab").as_deref(), Some("tabby"));
+    assert_eq!((dawg.shortest_completion("qqqqq"), dawg.longest_completion("qqqqq")), (None, None));
+}
+
+#[test]
+fn ties_go_to_the_smallest_word() {
+    let dawg = build(&["cab", "cad", "car", "cards", "carts", "cat"]);
+    for _ in 0..3 {
+        assert_eq!(dawg.shortest_completion("ca").as_deref(), Some("cab"));
+        assert_eq!(dawg.longest_completion("ca").as_deref(), Some("cards"));
+        assert_eq!(dawg.longest_completion("cart").as_deref(), Some("carts"));
+    }
+}
+
+#[test]
+fn depths_follow_edits_and_saving() {
+    let mut words = words();
+    let mut dawg = build(&words);
+    for word in ["aaaaaaaaaaaaaaaaaaaaa", "tabbbbbbbbbbbby", "zz"] {
+        assert!(dawg.insert_sequence(&word.chars().collect::<Vec<_>>()));
+        checked(&dawg);
+        words.push(word.into());
+    }
+    assert!(dawg.remove_sequence(&['t', 'a', 'c', 'k', 'y']));
+    checked(&dawg);
+    words.retain(|word| word != "tacky");
+    words.sort();
+
+    compare(&dawg, &words);
+    assert_eq!(dawg.longest_completion("tac").as_deref(), Some("tac"));
+    compare(&UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap(), &words);
+}
+
+#[test]
+fn an_empty_dawg_has_no_completion() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.finish();
+    assert_eq!((dawg.shortest_completion(""), dawg.longest_completion("")), (None, None));
+    assert_eq!((dawg.root().min_completion_depth(), dawg.root().max_completion_depth()), (None, None));
+
+    let dawg = build(&[""]);
+    assert_eq!(dawg.longest_completion("").as_deref(), Some(""));
+    assert_eq!(dawg.root().max_completion_depth(), Some(0));
+}
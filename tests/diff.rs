@@ -0,0 +1,69 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::{BTreeSet, HashSet};
+
+use common::{build, xorshift};
+use dawging::{dawg::options::DawgOptions, DawgError};
+
+/// Up to `count` distinct words of 1 to 5 letters out of "abcd"
+fn draw(state: &mut u64, count: u64) -> BTreeSet<String> {
+    (0..count).map(|_| (0..1 + xorshift(state) % 5).map(|_| b"abcd"[(xorshift(state) % 4) as usize] as char).collect()).collect()
+}
+
+/// Owned copies of `words`
+fn strings<const N: usize>(words: [&str; N]) -> std::array::IntoIter<String, N> {
+    words.map(String::from).into_iter()
+}
+
+#[test]
+fn additions_removals_both_and_neither() {
+    let dawg = build(&["a", "b", "c"]);
+
+    assert!(dawg.diff_with_sorted(strings(["a", "b", "c"])).unwrap().is_empty());
+
+    let report = dawg.diff_with_sorted(strings(["a", "aa", "b", "c", "d"])).unwrap();
+    assert_eq!((report.removed, report.added), (vec![], vec!["aa".to_string(), "d".to_string()]));
+
+    let report = dawg.diff_with_sorted(strings(["b"])).unwrap();
+    assert_eq!((report.removed, report.added), (vec!["a".to_string(), "c".to_string()], vec![]));
+
+    let report = dawg.diff_with_sorted(strings(["a", "a", "bb", "c", "d"])).unwrap();
+    assert_eq!(report.removed, ["b"]);
+    assert_eq!(report.added, ["bb", "d"]);
+
+    let report = dawg.diff_with_sorted(strings([])).unwrap();
+    assert_eq!(report.removed, ["a", "b", "c"]);
+    assert!(report.added.is_empty());
+}
+
+#[test]
+fn unsorted_words_are_reported() {
+    let dawg = build(&["a", "b", "c"]);
+    assert!(matches!(dawg.diff_with_sorted(strings(["b", "a"])), Err(DawgError::Unsorted { .. })));
+    assert!(matches!(dawg.diff_with_sorted(strings(["a", "c", "bb"])), Err(DawgError::Unsorted { .. })));
+}
+
+#[test]
+fn the_new_words_are_folded_like_added_words() {
+    let dawg = common::build_with(&["apple", "pear"], DawgOptions::new().case_folding(true));
+    let report = dawg.diff_with_sorted(strings(["APPLE", "Plum"])).unwrap();
+    assert_eq!(report.removed, ["pear"]);
+    assert_eq!(report.added, ["plum"]);
+}
+
+#[test]
+fn the_differences_match_the_sets() {
+    let mut state = 1;
+    for _ in 0..30 {
+        let (old, new) = (draw(&mut state, 300), draw(&mut state, 300));
+        let dawg = build(&old.iter().collect::<Vec<_>>());
+        let report = dawg.diff_with_sorted(new.iter().cloned()).unwrap();
+
+        let (old, new) = (old.into_iter().collect::<HashSet<_>>(), new.into_iter().collect::<HashSet<_>>());
+        assert!(report.removed.is_sorted() && report.added.is_sorted());
+        assert_eq!(report.removed.into_iter().collect::<HashSet<_>>(), &old - &new);
+        assert_eq!(report.added.into_iter().collect::<HashSet<_>>(), &new - &old);
+    }
+}
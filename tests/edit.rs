@@ -0,0 +1,209 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::{BTreeSet, HashSet};
+
+use common::{build, checked, xorshift};
+use dawging::{dawg::{lexicon::Lexicon, options::{DawgOptions, SortDirection, ValidationPolicy}}, UnsyncDawg};
+
+/// A word of 1 to 7 letters out of "abcde"
+fn draw_word(state: &mut u64) -> String {
+    (0..1 + xorshift(state) % 7).map(|_| b"abcde"[(xorshift(state) % 5) as usize] as char).collect()
+}
+
+/// Up to 3000 distinct words of `draw_word`
+fn draw(state: &mut u64) -> BTreeSet<String> {
+    (0..3000).map(|_| draw_word(state)).collect()
+}
+
+/// Ids of the nodes reachable from the root of `dawg`
+fn node_ids(dawg: &UnsyncDawg) -> HashSet<usize> {
+    let mut ids = HashSet::new();
+    let mut stack = vec![dawg.root()];
+    while let Some(node) = stack.pop() {
+        if ids.insert(node.id()) {
+            stack.extend(node.edge_labels().map(|label| node.child(&label).unwrap()));
+        }
+    }
+    ids
+}
+
+#[test]
+fn editing_a_fork_leaves_the_original_alone() {
+    let mut state = 21;
+    let words = draw(&mut state);
+    let dawg = build(&words.iter().collect::<Vec<_>>());
+    let (bytes, ids) = (dawg.to_bytes().unwrap(), node_ids(&dawg));
+
+    let mut fork = dawg.fork();
+    let mut expected = words.clone();
+    for _ in 0..200 {
+        let word = draw_word(&mut state);
+        match expected.contains(&word) {
+            true => assert!(fork.remove(&word).unwrap()),
+            false => assert!(fork.insert(&word).unwrap()),
+        }
+        checked(&fork);
+        if !expected.remove(&word) {
+            expected.insert(word);
+        }
+    }
+
+    assert!(fork.words().eq(expected.iter().cloned()));
+    assert_eq!(fork.word_count(), expected.len());
+    assert!(expected.iter().enumerate().all(|(rank, word)| fork.rank(word) == Some(rank)));
+    assert_eq!(dawg.to_bytes().unwrap(), bytes);
+    assert_eq!(node_ids(&dawg), ids);
+    checked(&dawg);
+}
+
+#[test]
+fn a_fork_shares_the_nodes_its_edits_leave_alone() {
+    let mut state = 5;
+    let words = draw(&mut state);
+    let dawg = build(&words.iter().collect::<Vec<_>>());
+    let original = node_ids(&dawg);
+
+    let mut fork = dawg.fork();
+    assert_eq!(node_ids(&fork), original);
+    for _ in 0..20 {
+        let word = draw_word(&mut state);
+        if !fork.remove(&word).unwrap() {
+            fork.insert(&word).unwrap();
+        }
+    }
+    checked(&fork);
+
+    // every edit copies at most one node per letter, and the root
+    let copies = node_ids(&fork).difference(&original).count();
+    assert!(copies <= 20 * 8, "{copies} of {} nodes copied", original.len());
+}
+
+#[test]
+fn finishing_a_fork_leaves_the_ids_of_the_original_alone() {
+    let dawg = build(&["ab", "abc", "b", "bcd", "cde", "def", "xyz"]);
+    let (bytes, ids) = (dawg.to_bytes().unwrap(), node_ids(&dawg));
+
+    let mut fork = dawg.fork();
+    for word in ["aa", "xyzw", "aaa", "aab", "aac"] {
+        assert!(fork.insert(word).unwrap());
+    }
+    fork.finish();
+    checked(&fork);
+
+    checked(&dawg);
+    assert_eq!(node_ids(&dawg), ids);
+    assert_eq!(dawg.to_bytes().unwrap(), bytes);
+    assert!(dawg.clone().words().eq(["ab", "abc", "b", "bcd", "cde", "def", "xyz"]));
+    assert!(UnsyncDawg::from_bytes(&dawg.to_bytes().unwrap()).unwrap().words().eq(dawg.words()));
+}
+
+#[test]
+fn finishing_the_original_leaves_the_ids_of_its_fork_alone() {
+    let mut dawg = build(&["ab", "abc", "b", "bcd", "cde", "def", "xyz"]);
+    let fork = dawg.fork();
+    let (bytes, ids) = (fork.to_bytes().unwrap(), node_ids(&fork));
+
+    for word in ["aa", "xyzw", "aaa", "aab", "aac"] {
+        assert!(dawg.insert(word).unwrap());
+    }
+    dawg.finish();
+    checked(&dawg);
+
+    checked(&fork);
+    assert_eq!(node_ids(&fork), ids);
+    assert_eq!(fork.to_bytes().unwrap(), bytes);
+    assert!(dawg.words().eq(["aa", "aaa", "aab", "aac", "ab", "abc", "b", "bcd", "cde", "def", "xyz", "xyzw"]));
+}
+
+#[test]
+fn edits_report_whether_they_changed_anything() {
+    let mut dawg = build(&["bat", "bats", "cat"]);
+    assert!(!dawg.insert("bat").unwrap());
+    assert!(!dawg.remove("ba").unwrap());
+    assert!(!dawg.remove("zzz").unwrap());
+    assert!(dawg.insert("").unwrap());
+    assert!(dawg.remove("bats").unwrap());
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["", "bat", "cat"]);
+}
+
+#[test]
+fn finishing_after_edits_gives_the_dawg_of_a_fresh_build() {
+    let mut dawg = build(&["cat", "cats", "dog", "dogs"]);
+    dawg.insert("cow").unwrap();
+    dawg.finish();
+    checked(&dawg);
+    assert!(dawg.verify_minimal());
+    assert_eq!(dawg.to_bytes().unwrap(), build(&["cat", "cats", "cow", "dog", "dogs"]).to_bytes().unwrap());
+
+    let mut state = 13;
+    let mut words = draw(&mut state);
+    let mut dawg = build(&words.iter().collect::<Vec<_>>());
+    for _ in 0..50 {
+        let word = draw_word(&mut state);
+        match words.remove(&word) {
+            true => assert!(dawg.remove(&word).unwrap()),
+            false => assert!(dawg.try_add(&word).is_ok() && words.insert(word)),
+        }
+    }
+    dawg.finish();
+    checked(&dawg);
+    assert!(dawg.verify_minimal());
+    assert_eq!(dawg.to_bytes().unwrap(), build(&words.iter().collect::<Vec<_>>()).to_bytes().unwrap());
+}
+
+#[test]
+fn removing_every_word_empties_the_fork() {
+    let mut state = 9;
+    let words = draw(&mut state).into_iter().take(300).collect::<Vec<_>>();
+    let dawg = build(&words);
+    let mut fork = dawg.fork();
+    for word in dawg.words() {
+        assert!(fork.remove(&word).unwrap());
+    }
+    checked(&fork);
+    assert_eq!(fork.words().next(), None);
+    assert_eq!(fork.word_count(), 0);
+    assert_eq!(fork.root().edge_labels().count(), 0);
+    assert!(dawg.words().eq(words));
+}
+
+#[test]
+fn words_added_to_the_original_after_a_fork_stay_out_of_it() {
+    let mut dawg = build(&["bat", "cat"]);
+    let fork = dawg.fork();
+    dawg.try_add("dog").unwrap();
+    checked(&dawg);
+    assert!(dawg.contains("dog"));
+    assert!(!fork.contains("dog"));
+}
+
+#[test]
+fn forking_an_unfinished_dawg_clones_it() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.add_all(["bat", "cat"]).unwrap();
+    let mut fork = dawg.fork();
+    fork.try_add("dog").unwrap();
+    dawg.try_add("rat").unwrap();
+    fork.finish();
+    dawg.finish();
+    checked(&fork);
+    checked(&dawg);
+    assert_eq!(fork.words().collect::<Vec<_>>(), ["bat", "cat", "dog"]);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["bat", "cat", "rat"]);
+}
+
+#[test]
+fn a_fork_keeps_what_the_build_recorded() {
+    let options = DawgOptions::new().alphabet("abc").validation_policy(ValidationPolicy::Skip).sort_direction(SortDirection::Auto);
+    let mut dawg = UnsyncDawg::with_options(options).unwrap();
+    for word in ["cb", "x", "ba", "a"] {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.finish();
+
+    let fork = dawg.fork();
+    assert_eq!((fork.words_skipped(), fork.sort_direction()), (1, Some(SortDirection::Descending)));
+}
@@ -0,0 +1,95 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, fixture, forge_compiled_count};
+use dawging::{dawg::{compiled::CompiledDawg, embedded::{CompiledView, EmbeddedDawg}, lexicon::{FoldProfile, Lexicon, MatchMode}}, DawgError};
+
+/// `fixtures/words.dawc`: the words of `fixtures/words.txt`, compiled
+static DICT: EmbeddedDawg = EmbeddedDawg::new(include_bytes!("fixtures/words.dawc"));
+
+static BROKEN: EmbeddedDawg = EmbeddedDawg::new(b"DAWCnope");
+
+const MODES: [MatchMode; 3] = [MatchMode::Exact, MatchMode::AsciiInsensitive, MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents)];
+
+/// Checks that `view` answers as `compiled` does on queries built from `words`
+fn assert_same_answers(view: &CompiledView, compiled: &CompiledDawg, words: &[String]) {
+    assert_eq!(view.word_count(), compiled.word_count());
+    assert_eq!(view.node_count(), compiled.node_count());
+    assert_eq!(view.words(), compiled.words());
+
+    let extra = ["", "zz", "Q", "é", "字", "xyzzy"].map(String::from);
+    for word in words.iter().step_by(23).chain(&extra) {
+        let prefix = word.chars().take(2).collect::<String>();
+        for mode in MODES {
+            assert_eq!(view.contains(word, mode), compiled.contains(word, mode), "{word}");
+            assert_eq!(view.contains(&format!("{word}q"), mode), compiled.contains(&format!("{word}q"), mode));
+            assert_eq!(view.contains_prefix(&prefix, mode), compiled.contains_prefix(&prefix, mode));
+            assert_eq!(view.completions(&prefix, mode), compiled.completions(&prefix, mode));
+        }
+    }
+}
+
+#[test]
+fn the_embedded_fixture_answers_as_the_file_loaded_at_runtime() {
+    let words = common::words();
+    let loaded = CompiledDawg::load(std::fs::File::open(fixture("words.dawc")).unwrap()).unwrap();
+    let view = DICT.view().unwrap();
+
+    assert_eq!(view.words(), words);
+    assert!(words.iter().all(|word| view.contains(word, MatchMode::Exact)));
+    assert_same_answers(view, &loaded, &words);
+    assert!(std::ptr::eq(DICT.view().unwrap(), view));
+
+    // the fixture is what compiling the word list gives today
+    assert_eq!(DICT.bytes(), build(&words).compile().to_bytes());
+}
+
+#[test]
+fn the_bytes_need_no_alignment() {
+    let words = common::words();
+    let loaded = CompiledDawg::from_bytes(DICT.bytes()).unwrap();
+
+    for offset in 1..8 {
+        let mut shifted = vec![0; offset];
+        shifted.extend_from_slice(DICT.bytes());
+        let view = CompiledView::from_bytes(&shifted[offset..]).unwrap();
+        assert_same_answers(&view, &loaded, &words);
+    }
+}
+
+#[test]
+fn broken_bytes_are_refused() {
+    assert!(BROKEN.view().is_err());
+    assert!(BROKEN.view().is_err());
+
+    let bytes = DICT.bytes();
+    let mut changed = bytes.to_vec();
+    changed[bytes.len() - 10] ^= 1;
+    assert!(matches!(CompiledView::from_bytes(&changed), Err(DawgError::Corrupted { .. })));
+    assert!(matches!(CompiledView::from_bytes(&bytes[..bytes.len() - 3]), Err(DawgError::Truncated { .. })));
+    assert!(CompiledView::from_bytes(&[]).is_err());
+
+    // every byte of a small dawg changed either fails the check or gives a view that can be queried without panicking
+    let small = build(&["ab", "ac", "b", "bc"]).compile_with(2).to_bytes();
+    for index in 0..small.len() {
+        for value in [0, 1, 0xFF] {
+            let mut changed = small.clone();
+            changed[index] = value;
+            if let Ok(view) = CompiledView::from_bytes(&changed) {
+                view.words();
+                view.contains("ab", MatchMode::AsciiInsensitive);
+                view.completions("a", MatchMode::Exact);
+            }
+        }
+    }
+}
+
+#[test]
+fn forged_counts_are_refused() {
+    let bytes = build(&["a", "ab", "b"]).compile().to_bytes();
+    assert!(CompiledView::from_bytes(&forge_compiled_count(&bytes, 0, 3)).is_ok());
+
+    let forged = forge_compiled_count(&bytes, 0, 4);
+    assert!(matches!(CompiledView::from_bytes(&forged), Err(DawgError::CorruptedCount { node: 0, stored: 4, actual: 3 })));
+}
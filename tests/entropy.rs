@@ -0,0 +1,77 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::dawg::entropy::{letter_entropy, END_OF_WORD};
+
+/// `-p * log2(p)`, worked out separately from the crate
+fn share(p: f64) -> f64 {
+    -p * p.log2()
+}
+
+/// Asserts `found` holds the outcomes of `expected` in the same order, with the same entropies to rounding
+fn assert_close(found: &[(char, f64)], expected: &[(char, f64)]) {
+    assert_eq!(found.iter().map(|(letter, _)| *letter).collect::<Vec<_>>(), expected.iter().map(|(letter, _)| *letter).collect::<Vec<_>>());
+    for ((letter, found), (_, expected)) in found.iter().zip(expected) {
+        assert!((found - expected).abs() < 1e-12, "{letter:?}: {found} instead of {expected}");
+    }
+}
+
+#[test]
+fn entropies_of_a_tiny_dawg_by_hand() {
+    let dawg = build(&["a", "ab", "abc", "abd", "ac", "b"]);
+
+    // "a" itself ends a word: 1 of the 5 completions ends there, 3 go on with "b" and 1 with "c"
+    assert_close(&dawg.letter_entropy_at("a"), &[(END_OF_WORD, share(0.2)), ('c', share(0.2)), ('b', share(0.6))]);
+    assert_close(&dawg.letter_entropy_at("ab"), &[(END_OF_WORD, share(1.0 / 3.0)), ('c', share(1.0 / 3.0)), ('d', share(1.0 / 3.0))]);
+    assert_close(&dawg.letter_entropy_at(""), &[('b', share(1.0 / 6.0)), ('a', share(5.0 / 6.0))]);
+    // a single outcome carries no information
+    assert_eq!(dawg.letter_entropy_at("abc"), [(END_OF_WORD, 0.0)]);
+    assert!(dawg.letter_entropy_at("z").is_empty());
+}
+
+#[test]
+fn ties_are_sorted_by_letter_on_every_call() {
+    let dawg = build(&["ab", "ad", "af", "ah", "ahh"]);
+    for _ in 0..3 {
+        assert_close(&dawg.letter_entropy_at("a"), &[('h', share(0.4)), ('b', share(0.2)), ('d', share(0.2)), ('f', share(0.2))]);
+    }
+    assert_close(&letter_entropy(["ba", "ca", "aa", "b"], 1), &[(END_OF_WORD, share(0.25)), ('a', share(0.75))]);
+    assert_close(&letter_entropy(["z", "y", "x"], 0), &[('x', share(1.0 / 3.0)), ('y', share(1.0 / 3.0)), ('z', share(1.0 / 3.0))]);
+}
+
+#[test]
+fn the_counts_of_the_dawg_agree_with_its_completions() {
+    let mut dawg = build(&common::words());
+    let compare = |dawg: &dawging::UnsyncDawg| {
+        for prefix in ["", "a", "ab", "b", "st", "é"] {
+            let found = dawg.letter_entropy_at(prefix);
+            assert_close(&found, &letter_entropy(dawg.words_with_prefix(prefix), prefix.chars().count()));
+            let information = found.iter().map(|(_, share)| share).sum::<f64>();
+            assert!(information >= 0.0 && information <= (found.len() as f64).log2() + 1e-12);
+        }
+    };
+    compare(&dawg);
+
+    // the reachable counts stay right once words are added and removed
+    for word in ["ab", "abz", "stzz"] {
+        dawg.insert_sequence(&word.chars().collect::<Vec<_>>());
+        checked(&dawg);
+    }
+    let removed = dawg.words_with_prefix("a").step_by(2).collect::<Vec<_>>();
+    for word in removed {
+        assert!(dawg.remove_sequence(&word.chars().collect::<Vec<_>>()));
+    }
+    checked(&dawg);
+    compare(&dawg);
+}
+
+#[test]
+fn candidates_of_any_search() {
+    let dawg = build(&["bake", "cake", "came", "lake", "make"]);
+    let candidates = dawg.hangman(&dawging::dawg::hangman::parse_pattern("_a_e"), &['l']);
+    // "b" and "m" once each, "c" twice: the three of them split the candidates as well, a half and a quarter weighing the same
+    assert_close(&letter_entropy(candidates, 0), &[('b', share(0.25)), ('c', share(0.5)), ('m', share(0.25))]);
+    assert!(letter_entropy(Vec::<String>::new(), 0).is_empty());
+}
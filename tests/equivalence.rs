@@ -0,0 +1,113 @@
+#![cfg(all(feature = "unsync", feature = "testutil"))]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, xorshift};
+use dawging::{dawg::lexicon::{FoldProfile, MatchMode}, naive::{self, check_equivalence, Query}, UnsyncDawg};
+
+const LETTERS: [char; 7] = ['a', 'b', 'A', 'é', 'E', 'e', 'z'];
+
+const MODES: [MatchMode; 4] = [
+    MatchMode::Exact,
+    MatchMode::AsciiInsensitive,
+    MatchMode::UnicodeInsensitive(FoldProfile::Default),
+    MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+];
+
+/// Up to 200 distinct words of 1 to 6 letters out of the letters but 'z', which only the queries use
+fn draw_words(state: &mut u64) -> Vec<String> {
+    let words: BTreeSet<String> = (0..xorshift(state) % 200)
+        .map(|_| (0..1 + xorshift(state) % 6).map(|_| LETTERS[(xorshift(state) % 6) as usize]).collect())
+        .collect();
+    words.into_iter().collect()
+}
+
+/// 300 queries of every kind, of up to 4 letters under any mode
+fn draw_queries(state: &mut u64) -> Vec<Query> {
+    (0..300).map(|_| {
+        let query = (0..xorshift(state) % 5).map(|_| LETTERS[(xorshift(state) % 7) as usize]).collect::<String>();
+        let mode = MODES[(xorshift(state) % 4) as usize];
+        match xorshift(state) % 3 {
+            0 => Query::Contains(query, mode),
+            1 => Query::ContainsPrefix(query, mode),
+            _ => Query::Completions(query, mode),
+        }
+    }).collect()
+}
+
+#[test]
+fn the_dawgs_answer_as_the_model() {
+    let mut state = 77;
+    for _ in 0..20 {
+        let words = draw_words(&mut state);
+        let queries = draw_queries(&mut state);
+        let model = naive::Lexicon::new(words.iter().cloned());
+
+        let dawg = build(&words);
+        check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("{divergence}"));
+
+        let mut unfinished = UnsyncDawg::new();
+        unfinished.add_all(&words).unwrap();
+        check_equivalence(&unfinished, &model, &queries).unwrap_or_else(|divergence| panic!("unfinished: {divergence}"));
+
+        let compiled = dawg.compile();
+        check_equivalence(&compiled, &model, &queries).unwrap_or_else(|divergence| panic!("compiled: {divergence}"));
+    }
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_answers_as_the_model() {
+    let mut state = 31;
+    for _ in 0..20 {
+        let words = draw_words(&mut state);
+        let queries = draw_queries(&mut state);
+        let mut dawg = dawging::SyncDawg::new_sync();
+        dawg.add_all(&words).unwrap();
+        dawg.finish();
+        common::checked(&dawg);
+
+        let model = naive::Lexicon::new(words.iter().cloned());
+        check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("sync: {divergence}"));
+    }
+}
+
+#[test]
+fn edits_keep_the_dawg_in_line_with_the_model() {
+    let mut state = 12;
+    let words = draw_words(&mut state);
+    let queries = draw_queries(&mut state);
+    let mut dawg = build(&words);
+    let mut model = naive::Lexicon::new(words.iter().cloned());
+
+    for _ in 0..50 {
+        let word = (0..1 + xorshift(&mut state) % 4).map(|_| LETTERS[(xorshift(&mut state) % 7) as usize]).collect::<String>();
+        match model.remove(&word) {
+            true => assert!(dawg.remove(&word).unwrap()),
+            false => {
+                model.insert(&word);
+                assert!(dawg.insert(&word).unwrap());
+            }
+        }
+        common::checked(&dawg);
+    }
+    check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("edited: {divergence}"));
+}
+
+#[test]
+fn the_first_divergence_is_reported() {
+    let dawg = build(&["b"]);
+    let divergence = check_equivalence(&dawg, &naive::Lexicon::new(["b"]), &[
+        Query::Contains("b".into(), MatchMode::Exact),
+        Query::Contains("a".into(), MatchMode::Exact),
+    ]);
+    assert!(divergence.is_ok());
+
+    // the words are compared before the queries
+    let divergence = check_equivalence(&dawg, &naive::Lexicon::new(["a"]), &[]).unwrap_err();
+    assert_eq!(divergence.query, Query::Words);
+    assert_eq!((divergence.actual.as_str(), divergence.expected.as_str()), (r#"["b"]"#, r#"["a"]"#));
+    assert!(divergence.to_string().contains("Words"));
+}
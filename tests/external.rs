@@ -0,0 +1,109 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs, io::{self, BufReader, Read}, path::PathBuf};
+
+use common::checked;
+use dawging::{dawg::{external::ExternalSortOptions, options::DawgOptions}, DawgError, UnsyncDawg};
+
+/// A directory of its own under the system's temporary one, for the spilled chunks of a test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dawging-external-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Reader handing out its bytes 7 at a time, then failing instead of reaching the end
+struct Failing {
+    bytes: Vec<u8>,
+    read: usize,
+}
+
+impl Read for Failing {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.read == self.bytes.len() {
+            return Err(io::Error::other("disk gone"));
+        }
+        let len = buf.len().min(7).min(self.bytes.len() - self.read);
+        buf[..len].copy_from_slice(&self.bytes[self.read..self.read + len]);
+        self.read += len;
+        Ok(len)
+    }
+}
+
+/// The words of the fixture shuffled (by a fixed permutation), repeated in part, with CRLF endings and blank lines
+fn unsorted_text(words: &[String]) -> String {
+    let mut lines = (0..words.len()).map(|index| words[index * 7919 % words.len()].as_str()).collect::<Vec<_>>();
+    lines.extend(words.iter().step_by(10).map(String::as_str));
+    lines.insert(50, "");
+    lines.join("\r\n")
+}
+
+#[test]
+fn a_build_over_many_chunks_equals_the_build_in_memory() {
+    let dir = temp_dir("chunks");
+    let words = common::words();
+    let file = dir.join("input.txt");
+    fs::write(&file, unsorted_text(&words)).unwrap();
+    let spill = dir.join("spill");
+    fs::create_dir_all(&spill).unwrap();
+    let expected = common::build(&words).to_bytes().unwrap();
+
+    for chunk_size in [1, 100, 10_000, usize::MAX] {
+        let options = ExternalSortOptions::new().chunk_size(chunk_size).temp_dir(&spill);
+        let dawg = UnsyncDawg::from_unsorted_file(&file, &options).unwrap();
+        checked(&dawg);
+        dawg.assert_minimal().unwrap();
+        assert_eq!(dawg.to_bytes().unwrap(), expected, "chunks of {chunk_size} bytes");
+        assert_eq!(fs::read_dir(&spill).unwrap().count(), 0);
+    }
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn the_words_are_folded_by_the_options() {
+    let dir = temp_dir("fold");
+    let options = ExternalSortOptions::new().chunk_size(4).temp_dir(&dir).dawg_options(DawgOptions::new().case_folding(true));
+    let dawg = UnsyncDawg::from_unsorted_reader(&b"Tap\ntap\nAPE\nzoo\n"[..], &options).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["ape", "tap", "zoo"]);
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn errors_leave_no_chunk_behind() {
+    let dir = temp_dir("errors");
+    let options = ExternalSortOptions::new().chunk_size(100).temp_dir(&dir);
+    let text = unsorted_text(&common::words()).into_bytes();
+
+    // the reader fails once several chunks are on disk
+    let error = UnsyncDawg::from_unsorted_reader(BufReader::new(Failing { bytes: text, read: 0 }), &options).unwrap_err();
+    assert!(matches!(error, DawgError::Io(_)), "{error}");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+    let error = UnsyncDawg::from_unsorted_reader(&b"a\nb\xff\n"[..], &options).unwrap_err();
+    assert!(matches!(error, DawgError::Line { line: 2, .. }), "{error}");
+
+    // the invalid word is only found while merging the chunks
+    let options = ExternalSortOptions::new().chunk_size(2).temp_dir(&dir).dawg_options(DawgOptions::new().alphabet("ab"));
+    let error = UnsyncDawg::from_unsorted_reader(&b"ab\nzz\nb\n"[..], &options).unwrap_err();
+    assert!(matches!(error, DawgError::InvalidCharacter { character: 'z', .. }), "{error}");
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+
+    let options = ExternalSortOptions::new().chunk_size(2).temp_dir(dir.join("missing"));
+    assert!(matches!(UnsyncDawg::from_unsorted_reader(&b"ab\nzz\nb\n"[..], &options), Err(DawgError::Io(_))));
+    assert!(matches!(UnsyncDawg::from_unsorted_file(dir.join("missing.txt"), &options), Err(DawgError::Io(_))));
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn an_empty_input_builds_an_empty_dawg() {
+    let dir = temp_dir("empty");
+    let dawg = UnsyncDawg::from_unsorted_reader(&b"\n\r\n"[..], &ExternalSortOptions::new().temp_dir(&dir)).unwrap();
+    checked(&dawg);
+    assert!(dawg.is_finished());
+    assert_eq!(dawg.words().next(), None);
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,62 @@
+//! Runs under every feature set: each flavour of dawg compiled in goes through the same queries
+
+use dawging::{dawg::lexicon::{Lexicon, MatchMode}, Dawg, Wrapper};
+
+const WORDS: [&str; 6] = ["ab", "abc", "b", "bat", "bats", "cat"];
+
+/// Builds `dawg` from the words, checking it after every one, then asks it every kind of query
+fn query_suite<T: Wrapper + Clone>(mut dawg: Dawg<T>) {
+    for word in WORDS {
+        dawg.try_add(word).unwrap();
+        dawg.assert_invariants().unwrap();
+    }
+    dawg.finish();
+    dawg.assert_minimal().unwrap();
+
+    assert_eq!(dawg.words().collect::<Vec<_>>(), WORDS);
+    assert_eq!(dawg.words().rev().count(), WORDS.len());
+    assert!(dawg.contains("bat") && !dawg.contains("ba"));
+    assert!(dawg.starts_with("ba"));
+    assert_eq!(Lexicon::completions(&dawg, "BA", MatchMode::AsciiInsensitive), ["bat", "bats"]);
+    assert_eq!(dawg.word_count(), 6);
+    assert_eq!(dawg.descend("ab").unwrap().reachable_words(), 2);
+
+    let loaded = Dawg::<T>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    assert!(loaded.words().eq(dawg.words()));
+
+    let mut fork = dawg.fork();
+    fork.remove("ab").unwrap();
+    fork.assert_invariants().unwrap();
+    assert_eq!(fork.word_count(), 5);
+    assert_eq!(dawg.word_count(), 6);
+}
+
+#[test]
+#[cfg(feature = "unsync")]
+fn the_unsync_dawg_passes_the_query_suite() {
+    query_suite(dawging::UnsyncDawg::new());
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_passes_the_query_suite() {
+    query_suite(dawging::SyncDawg::new_sync());
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_is_shared_between_threads() {
+    fn is_send_sync<T: Send + Sync>(_: &T) {}
+
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+    is_send_sync(&dawg);
+
+    let dawg = std::sync::Arc::new(dawg);
+    let threads = WORDS.map(|word| {
+        let dawg = dawg.clone();
+        std::thread::spawn(move || (*dawg).contains(word))
+    });
+    assert!(threads.into_iter().all(|thread| thread.join().unwrap()));
+}
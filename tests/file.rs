@@ -0,0 +1,86 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs, io::{self, Cursor, Read, Seek, SeekFrom}};
+
+use common::build;
+use dawging::{file::{words_in, FileWords}, DawgError, UnsyncDawg};
+
+/// Reader handing out at most `max` bytes per read, keeping the largest read it was asked for
+struct Limited<R> {
+    inner: R,
+    max: usize,
+    largest: usize,
+}
+
+impl<R: Read> Read for Limited<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = buf.len().min(self.max);
+        self.largest = self.largest.max(len);
+        self.inner.read(&mut buf[..len])
+    }
+}
+
+impl<R: Seek> Seek for Limited<R> {
+    fn seek(&mut self, position: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(position)
+    }
+}
+
+/// The fixture, along with the empty word, saved
+fn saved() -> (Vec<String>, Vec<u8>) {
+    let mut words = common::words();
+    words.insert(0, String::new());
+    let bytes = build(&words).to_bytes().unwrap();
+    (words, bytes)
+}
+
+#[test]
+fn the_words_of_a_saved_file_are_those_of_the_loaded_dawg() {
+    let (words, bytes) = saved();
+    let path = std::env::temp_dir().join(format!("dawging-file-{}.dawg", std::process::id()));
+    fs::write(&path, &bytes).unwrap();
+
+    let streamed = words_in(&path).unwrap().map(Result::unwrap).collect::<Vec<_>>();
+    let loaded = UnsyncDawg::<char>::load(fs::File::open(&path).unwrap()).unwrap();
+    assert_eq!(streamed, loaded.words().collect::<Vec<_>>());
+    assert_eq!(streamed, words);
+    fs::remove_file(&path).unwrap();
+
+    let empty = UnsyncDawg::<char>::from_bytes(&build::<&str>(&[]).to_bytes().unwrap()).unwrap().to_bytes().unwrap();
+    assert_eq!(FileWords::new(Cursor::new(empty)).unwrap().count(), 0);
+    assert!(words_in(std::env::temp_dir().join("dawging-file-missing.dawg")).is_err());
+}
+
+#[test]
+fn small_reads_are_enough() {
+    let (words, bytes) = saved();
+    let mut reader = Limited { inner: Cursor::new(bytes), max: 64, largest: 0 };
+    let streamed = FileWords::new(&mut reader).unwrap().map(Result::unwrap).collect::<Vec<_>>();
+    assert_eq!(streamed, words);
+    assert!(reader.largest <= 64);
+}
+
+#[test]
+fn damaged_files_are_refused() {
+    let (_, bytes) = saved();
+    for at in [bytes.len() / 3, bytes.len() / 2, bytes.len() - 5] {
+        let mut damaged = bytes.clone();
+        damaged[at] ^= 0x40;
+        assert!(FileWords::new(Cursor::new(damaged)).is_err(), "byte {at}");
+    }
+    for len in [0, 4, 20, bytes.len() / 2, bytes.len() - 1] {
+        assert!(FileWords::new(Cursor::new(bytes[..len].to_vec())).is_err(), "{len} bytes");
+    }
+    assert!(FileWords::new(Cursor::new(b"nope".to_vec())).is_err());
+}
+
+#[test]
+fn the_checks_of_load_apply() {
+    let saved = build(&["bat", "bats", "cat", "cats"]).to_bytes().unwrap();
+    // a count that doesn't match the words below its node, with a checksum that still matches
+    let forged = common::forge_count(&saved, 0, 1);
+    assert!(matches!(UnsyncDawg::<char>::from_bytes(&forged), Err(DawgError::CorruptedCount { node: 0, .. })));
+    assert!(matches!(FileWords::new(Cursor::new(forged)), Err(DawgError::CorruptedCount { node: 0, .. })));
+}
@@ -0,0 +1,151 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::HashSet;
+
+use common::{build, build_with, xorshift};
+use dawging::{dawg::{filter::{FilterKind, WordFilter}, options::DawgOptions}, DawgError, UnsyncDawg};
+
+const FILTERS: [(FilterKind, f64); 5] = [(FilterKind::Bloom, 0.01), (FilterKind::Bloom, 0.001), (FilterKind::Xor, 0.01), (FilterKind::Xor, 0.0001), (FilterKind::Xor, 1e-9)];
+
+/// `count` distinct strings of 3 to 10 lowercase letters, none of them in `words`
+fn non_words(words: &[String], count: usize) -> Vec<String> {
+    let words = words.iter().collect::<HashSet<_>>();
+    let (mut state, mut drawn) = (77, HashSet::new());
+    while drawn.len() < count {
+        let value = xorshift(&mut state);
+        let word = (0..3 + value % 8).map(|index| (b'a' + (value >> (index * 5) & 31) as u8 % 26) as char).collect::<String>();
+        if !words.contains(&word) {
+            drawn.insert(word);
+        }
+    }
+    drawn.into_iter().collect()
+}
+
+/// The hash of the filters, written from the documentation of the `filter` module alone
+fn hash(bytes: &[u8], seed: u32) -> u32 {
+    let mut h = 0x811C_9DC5 ^ seed;
+    for byte in bytes {
+        h = (h ^ *byte as u32).wrapping_mul(0x0100_0193);
+    }
+    h = (h ^ (h >> 16)).wrapping_mul(0x85EB_CA6B);
+    h = (h ^ (h >> 13)).wrapping_mul(0xC2B2_AE35);
+    h ^ (h >> 16)
+}
+
+/// Queries a saved filter as a reader in another language would, following the documented format
+fn documented_lookup(filter: &[u8], word: &str) -> bool {
+    let u32_at = |at: usize| u32::from_le_bytes(filter[at..at + 4].try_into().unwrap());
+    assert_eq!((&filter[..4], filter[4], filter[7]), (&b"DAWF"[..], 1, 0));
+    let word = match filter[6] & 1 {
+        1 => word.to_lowercase(),
+        _ => word.to_string(),
+    };
+    let word = word.as_bytes();
+
+    match filter[5] {
+        0 => {
+            let (bits, hashes) = (u32_at(8), u32_at(12));
+            let (h1, h2) = (hash(word, 0), hash(word, 0x9E37_79B9));
+            (0..hashes).all(|i| {
+                let bit = h1.wrapping_add(i.wrapping_mul(h2)) % bits;
+                filter[16 + (bit >> 3) as usize] >> (bit & 7) & 1 == 1
+            })
+        }
+        1 => {
+            let (seed, block, width) = (u32_at(8), u32_at(12), filter[16] as usize);
+            if block == 0 {
+                return false;
+            }
+            let fingerprint = |slot: u32| {
+                let at = 20 + slot as usize * width;
+                let mut bytes = [0; 4];
+                bytes[..width].copy_from_slice(&filter[at..at + width]);
+                u32::from_le_bytes(bytes)
+            };
+            let slots = (0..3).map(|index| index * block + hash(word, seed + index) % block);
+            let mask = if width == 4 { u32::MAX } else { (1 << (8 * width)) - 1 };
+            slots.map(fingerprint).fold(0, |xor, fingerprint| xor ^ fingerprint) == hash(word, seed + 3) & mask
+        }
+        kind => panic!("unknown kind {kind}"),
+    }
+}
+
+#[test]
+fn no_word_is_rejected_and_few_others_are_accepted() {
+    let words = common::words();
+    let dawg = build(&words);
+    let non_words = non_words(&words, 200_000);
+
+    for (kind, fp_rate) in FILTERS {
+        let bytes = dawg.export_filter(kind, fp_rate);
+        let filter = WordFilter::from_bytes(&bytes).unwrap();
+        assert_eq!(filter.kind(), kind);
+        assert!(words.iter().all(|word| filter.maybe_contains(word)), "{kind:?}");
+
+        let accepted = non_words.iter().filter(|word| filter.maybe_contains(word)).count();
+        assert!(accepted as f64 / non_words.len() as f64 <= 2.0 * fp_rate, "{kind:?} at {fp_rate}: {accepted} accepted");
+    }
+}
+
+#[test]
+fn saved_filters_round_trip_and_follow_their_documentation() {
+    let words = common::words();
+    let dawg = build(&words);
+    let queries = words.iter().step_by(7).cloned().chain(non_words(&words, 2_000)).collect::<Vec<_>>();
+
+    for (kind, fp_rate) in FILTERS {
+        let bytes = dawg.export_filter(kind, fp_rate);
+        assert_eq!(dawg.export_filter(kind, fp_rate), bytes);
+        let filter = WordFilter::from_bytes(&bytes).unwrap();
+        assert_eq!(WordFilter::from_bytes(&bytes).unwrap(), filter.clone());
+        assert!(queries.iter().all(|word| documented_lookup(&bytes, word) == filter.maybe_contains(word)), "{kind:?}");
+    }
+}
+
+#[test]
+fn folded_dawgs_give_filters_that_fold_queries() {
+    let dawg = build_with(&["apple", "Banana"], DawgOptions::new().case_folding(true));
+    for kind in [FilterKind::Bloom, FilterKind::Xor] {
+        let bytes = dawg.export_filter(kind, 0.001);
+        assert_eq!(bytes[6] & 1, 1);
+        let filter = WordFilter::from_bytes(&bytes).unwrap();
+        assert!(["APPLE", "banana", "BaNaNa"].iter().all(|word| filter.maybe_contains(word) && documented_lookup(&bytes, word)));
+    }
+}
+
+#[test]
+fn an_empty_dawg_accepts_nothing() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.finish();
+    for kind in [FilterKind::Bloom, FilterKind::Xor] {
+        let filter = WordFilter::from_bytes(&dawg.export_filter(kind, 0.01)).unwrap();
+        assert!(!filter.maybe_contains("x"));
+        assert!(!filter.maybe_contains(""));
+    }
+}
+
+#[test]
+fn broken_filters_are_refused() {
+    let dawg = build(&["ab", "b", "cd"]);
+    for kind in [FilterKind::Bloom, FilterKind::Xor] {
+        let bytes = dawg.export_filter(kind, 0.01);
+        for len in 0..bytes.len() {
+            assert!(matches!(WordFilter::from_bytes(&bytes[..len]), Err(DawgError::InvalidFormat(_))), "{kind:?} cut at {len}");
+        }
+
+        let mut longer = bytes.clone();
+        longer.push(0);
+        assert!(WordFilter::from_bytes(&longer).is_err());
+        for (index, value) in [(0, b'X'), (4, 2), (5, 2)] {
+            let mut changed = bytes.clone();
+            changed[index] = value;
+            assert!(WordFilter::from_bytes(&changed).is_err(), "{kind:?} byte {index}");
+        }
+    }
+
+    let mut xor = dawg.export_filter(FilterKind::Xor, 0.01);
+    xor[16] = 3;
+    assert!(WordFilter::from_bytes(&xor).is_err());
+}
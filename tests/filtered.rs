@@ -0,0 +1,116 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{collections::BTreeSet, rc::Rc};
+
+use common::{build, xorshift};
+use dawging::dawg::{filtered::FilteredDawg, lexicon::{Lexicon, MatchMode}};
+
+#[test]
+fn a_word_is_in_the_base_or_allowed_and_not_denied() {
+    // one word for each of the eight cases of (in the base, allowed, denied)
+    let base = build(&["bad", "ball", "base", "bath"]);
+    let mut filtered = FilteredDawg::new(&base);
+    for word in ["ball", "bath", "bond", "bump"] {
+        filtered.allowed_mut().insert(word);
+    }
+    for word in ["base", "bath", "bump", "buzz"] {
+        filtered.denied_mut().insert(word);
+    }
+
+    for (word, expected) in [("bad", true), ("ball", true), ("base", false), ("bath", false), ("bond", true), ("bump", false), ("buzz", false), ("bell", false)] {
+        assert_eq!(filtered.contains(word, MatchMode::Exact), expected, "{word}");
+    }
+    assert_eq!(filtered.completions("b", MatchMode::Exact), ["bad", "ball", "bond"]);
+    assert_eq!((filtered.count_completions("b", MatchMode::Exact), filtered.word_count()), (3, 3));
+    assert!(filtered.contains_prefix("bo", MatchMode::Exact) && !filtered.contains_prefix("bu", MatchMode::Exact));
+}
+
+#[test]
+fn overlay_edits_show_at_once() {
+    let base = build(&["Dog", "apple", "apply", "bad", "badly", "cat"]);
+    let mut filtered = FilteredDawg::new(&base);
+    assert_eq!(filtered.word_count(), 6);
+
+    filtered.denied_mut().insert("bad");
+    filtered.allowed_mut().insert("bat");
+    assert_eq!(filtered.completions("ba", MatchMode::Exact), ["badly", "bat"]);
+    assert!(filtered.contains("DOG", MatchMode::AsciiInsensitive) && !filtered.contains("BAD", MatchMode::AsciiInsensitive));
+    assert!(filtered.contains("BAT", MatchMode::AsciiInsensitive));
+
+    filtered.denied_mut().insert("badly");
+    filtered.denied_mut().insert("bat");
+    assert!(!filtered.contains_prefix("ba", MatchMode::Exact));
+    assert_eq!((filtered.count_completions("b", MatchMode::Exact), filtered.word_count()), (0, 4));
+
+    assert!(filtered.denied_mut().remove("bat"));
+    assert!(!filtered.denied_mut().remove("bat"));
+    assert!(filtered.contains("bat", MatchMode::Exact));
+    assert_eq!(filtered.word_count(), 5);
+    assert_eq!(filtered.denied().words().collect::<Vec<_>>(), ["bad", "badly"]);
+}
+
+#[test]
+fn random_edits_follow_the_sets() {
+    let words = common::words();
+    let base = build(&words);
+    let mut filtered = FilteredDawg::new(&base);
+    let (mut allowed, mut denied) = (BTreeSet::new(), BTreeSet::new());
+    let mut state = 23;
+
+    for round in 0..300 {
+        let word = match xorshift(&mut state) % 2 {
+            0 => words[xorshift(&mut state) as usize % words.len()].clone(),
+            _ => format!("{}x", words[xorshift(&mut state) as usize % words.len()]),
+        };
+        let (overlay, set) = match xorshift(&mut state) % 2 {
+            0 => (filtered.allowed_mut(), &mut allowed),
+            _ => (filtered.denied_mut(), &mut denied),
+        };
+        if xorshift(&mut state) % 3 == 0 {
+            assert_eq!(overlay.remove(&word), set.remove(&word));
+        } else {
+            assert_eq!(overlay.insert(&word), set.insert(word));
+        }
+
+        if round % 50 == 49 {
+            let expected = words.iter().chain(&allowed).filter(|word| !denied.contains(*word)).cloned().collect::<BTreeSet<_>>();
+            assert_eq!(filtered.completions("", MatchMode::Exact), expected.iter().cloned().collect::<Vec<_>>());
+            assert_eq!(filtered.word_count(), expected.len());
+            for prefix in ["a", "ab", "b", "st", "é"] {
+                let count = expected.iter().filter(|word| word.starts_with(prefix)).count();
+                assert_eq!(filtered.count_completions(prefix, MatchMode::Exact), count, "{prefix:?}");
+                assert_eq!(filtered.contains_prefix(prefix, MatchMode::Exact), count > 0);
+            }
+            for word in words.iter().step_by(11).chain(&allowed).chain(&denied) {
+                assert_eq!(filtered.contains(word, MatchMode::Exact), expected.contains(word), "{word}");
+            }
+        }
+    }
+}
+
+#[test]
+fn dawgs_make_the_larger_overlays() {
+    let words = common::words();
+    let denied = words.iter().step_by(3).cloned().collect::<Vec<_>>();
+    let allowed = ["zzallowed", "zzextra"];
+    let mut filtered = FilteredDawg::new(Rc::new(build(&words)));
+    filtered.denied_mut().push_lexicon(build(&denied));
+    filtered.allowed_mut().push_lexicon(Rc::new(build(&allowed)));
+
+    let mut expected = words.iter().filter(|word| !denied.contains(word)).cloned().collect::<Vec<_>>();
+    expected.extend(allowed.map(String::from));
+    expected.sort();
+    assert_eq!(filtered.completions("", MatchMode::Exact), expected);
+    assert_eq!(filtered.word_count(), expected.len());
+    for prefix in ["a", "b", "ab", "q", "zz"] {
+        assert_eq!(filtered.count_completions(prefix, MatchMode::Exact), expected.iter().filter(|word| word.starts_with(prefix)).count());
+    }
+    for word in words.iter().take(100) {
+        assert_eq!(filtered.contains(word, MatchMode::Exact), !denied.contains(word));
+    }
+    // the set of an overlay goes along with its lexicons
+    filtered.denied_mut().insert("zzextra");
+    assert!(!filtered.contains("zzextra", MatchMode::Exact) && !filtered.denied().is_empty());
+}
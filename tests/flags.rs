@@ -0,0 +1,93 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::{flags::FlaggedDawg, options::DawgOptions}, UnsyncWrapper};
+
+/// Two bits from the length of the word, and a third one if it holds an "e"
+fn flags(word: &str) -> u32 {
+    (word.len() as u32 % 4) | if word.contains('e') { 8 } else { 0 }
+}
+
+/// (mask, require) pairs
+const FILTERS: [(u32, u32); 7] = [(0, 0), (8, 0), (0, 8), (1, 2), (3, 0), (0, 3), (8, 8)];
+
+fn flagged(words: &[String]) -> FlaggedDawg<UnsyncWrapper> {
+    let mut dawg = FlaggedDawg::<UnsyncWrapper>::with_options(DawgOptions::new()).unwrap();
+    for word in words {
+        dawg.add_flagged(word, flags(word)).unwrap();
+    }
+    dawg.finish();
+    checked(dawg.dawg());
+    dawg
+}
+
+#[test]
+fn flags_are_read_back() {
+    let words = common::words();
+    let dawg = flagged(&words);
+    for word in &words {
+        assert_eq!(dawg.flags_of(word), Some(flags(word)), "{word}");
+    }
+    assert_eq!(dawg.flags_of("zzzzqq"), None);
+    assert_eq!(dawg.iter_flagged(0, 0).collect::<Vec<_>>(), words.iter().map(|word| (word.clone(), flags(word))).collect::<Vec<_>>());
+}
+
+#[test]
+fn filtered_queries_are_those_of_brute_force() {
+    let words = common::words();
+    let dawg = flagged(&words);
+    let passes = |word: &str, mask: u32, require: u32| flags(word) & mask == 0 && flags(word) & require == require;
+
+    for (mask, require) in FILTERS {
+        for prefix in ["", "a", "co", "st", "q", "é"] {
+            let expected = words.iter().filter(|word| word.starts_with(prefix) && passes(word, mask, require)).cloned().collect::<Vec<_>>();
+            assert_eq!(dawg.completions_flagged(prefix, mask, require), expected, "{prefix:?} {mask} {require}");
+        }
+        let expected = words.iter().filter(|word| passes(word, mask, require)).cloned().collect::<Vec<_>>();
+        assert_eq!(dawg.iter_flagged(mask, require).map(|(word, _)| word).collect::<Vec<_>>(), expected);
+        for word in words.iter().step_by(13) {
+            assert_eq!(dawg.contains_flagged(word, mask, require), passes(word, mask, require), "{word}");
+        }
+        assert!(!dawg.contains_flagged("zzzzqq", mask, require));
+    }
+}
+
+#[test]
+fn flags_survive_saving() {
+    let words = common::words();
+    let dawg = flagged(&words);
+    let mut saved = vec![];
+    dawg.save(&mut saved).unwrap();
+    assert_eq!(saved, dawg.to_bytes().unwrap());
+    assert_eq!(&saved[..4], b"DAWA");
+
+    let loaded = FlaggedDawg::<UnsyncWrapper>::load(&saved[..]).unwrap();
+    checked(loaded.dawg());
+    assert!(loaded.iter_flagged(0, 0).eq(dawg.iter_flagged(0, 0)));
+    assert_eq!(loaded.completions_flagged("a", 8, 1), dawg.completions_flagged("a", 8, 1));
+
+    for len in [0, 6, saved.len() / 2, saved.len() - 2] {
+        assert!(FlaggedDawg::<UnsyncWrapper>::from_bytes(&saved[..len]).is_err(), "{len} bytes");
+    }
+}
+
+#[test]
+fn flags_leave_the_structure_shared() {
+    let words = common::words();
+    assert_eq!(flagged(&words).dawg().compression_stats(), build(&words).compression_stats());
+
+    // "aking" is shared by the five words, whatever their flags
+    let family = ["baking", "caking", "making", "raking", "taking"];
+    for flags in [[4; 5], [1, 2, 4, 8, 16]] {
+        let mut dawg = FlaggedDawg::<UnsyncWrapper>::with_options(DawgOptions::new()).unwrap();
+        for (word, flags) in family.iter().zip(flags) {
+            dawg.add_flagged(word, flags).unwrap();
+        }
+        dawg.finish();
+        assert_eq!(dawg.dawg().compression_stats().dawg_nodes, 7);
+        let expected = family.iter().zip(flags).filter(|(_, flags)| flags & 4 == 4).map(|(word, _)| *word).collect::<Vec<_>>();
+        assert_eq!(dawg.completions_flagged("", 0, 4), expected);
+    }
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use common::{build, checked};
+use dawging::{dawg::{forest::{DawgForest, ForestOptions, ForestStats}, lexicon::{Lexicon, MatchMode}, options::{DawgOptions, ValidationPolicy}}, DawgError, UnsyncWrapper};
+
+const PREFIXES: [&str; 9] = ["", "a", "ab", "Ab", "zz", "é", "q", "xyz", "mo"];
+
+/// A directory of its own under the system's temporary one, for the files of a test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dawging-forest-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Every word of `forest`, in the order it lists them
+fn words_of(forest: &DawgForest<UnsyncWrapper>) -> Vec<String> {
+    forest.words().map(Result::unwrap).collect()
+}
+
+/// Asserts `forest` answers the queries of every mode as `words` in a single dawg do
+fn assert_same_answers(forest: &DawgForest<UnsyncWrapper>, words: &[String]) {
+    let dawg = build(words);
+    assert_eq!(forest.word_count(), dawg.word_count());
+    for mode in [MatchMode::Exact, MatchMode::AsciiInsensitive] {
+        for prefix in PREFIXES {
+            assert_eq!(forest.completions(prefix, mode), dawg.completions(prefix, mode), "{prefix:?} {mode:?}");
+            assert_eq!(forest.contains_prefix(prefix, mode), dawg.contains_prefix(prefix, mode), "{prefix:?} {mode:?}");
+        }
+        for word in words.iter().step_by(7) {
+            assert!(forest.contains(word, mode));
+            let upper = word.to_uppercase();
+            assert_eq!(forest.contains(&upper, mode), Lexicon::contains(&dawg, &upper, mode), "{upper} {mode:?}");
+        }
+        assert!(!forest.contains("notaword", mode));
+    }
+}
+
+#[test]
+fn a_forest_answers_as_a_single_dawg() {
+    let words = common::words();
+    let forest = DawgForest::<UnsyncWrapper>::build(words.iter().rev(), &ForestOptions::new()).unwrap();
+    assert_same_answers(&forest, &words);
+    assert_eq!(words_of(&forest), words);
+    assert!(forest.shard_count() > 26);
+    for key in forest.shard_keys() {
+        checked(&forest.shard(key).unwrap());
+    }
+    assert!(forest.shard("0").is_err());
+}
+
+#[test]
+fn words_are_listed_in_order_across_the_shards() {
+    let words = ["", "a", "Ab", "abc", "b", "ba", "bad", "c"];
+    let options = ForestOptions::new().key_len(2).dawg_options(DawgOptions::new().case_folding(true));
+    let forest = DawgForest::<UnsyncWrapper>::build(words, &options).unwrap();
+
+    // a word shorter than the key is a key of its own
+    assert_eq!(forest.shard_keys().collect::<Vec<_>>(), ["", "a", "ab", "b", "ba", "c"]);
+    assert_eq!(words_of(&forest), ["", "a", "ab", "abc", "b", "ba", "bad", "c"]);
+    assert!(forest.contains("", MatchMode::Exact) && forest.contains("ABC", MatchMode::Exact));
+    assert!(!forest.contains("ac", MatchMode::Exact));
+    assert_eq!(forest.completions("a", MatchMode::Exact), ["a", "ab", "abc"]);
+    assert_eq!(forest.completions("", MatchMode::Exact).len(), 8);
+}
+
+#[test]
+fn keys_whose_words_are_all_skipped_get_no_shard() {
+    let options = DawgOptions::new().alphabet("abc").validation_policy(ValidationPolicy::Skip);
+    let forest = DawgForest::<UnsyncWrapper>::build(["ab", "ax", "bx", "ca"], &ForestOptions::new().dawg_options(options)).unwrap();
+    assert_eq!(forest.shard_keys().collect::<Vec<_>>(), ["a", "c"]);
+    assert_eq!(forest.word_count(), 2);
+    assert_eq!(words_of(&forest), ["ab", "ca"]);
+    assert!(matches!(forest.shard("b"), Err(DawgError::MissingShard(_))));
+}
+
+#[test]
+fn saved_shards_are_loaded_as_queries_reach_them() {
+    let words = common::words();
+    let dir = temp_dir("lazy");
+    DawgForest::<UnsyncWrapper>::build(&words, &ForestOptions::new()).unwrap().save_dir(&dir).unwrap();
+
+    let forest = DawgForest::<UnsyncWrapper>::load_dir(&dir, &ForestOptions::new().resident_shards(3)).unwrap();
+    assert_eq!(forest.stats(), ForestStats::default());
+    assert_eq!(forest.word_count(), words.len());
+    assert_eq!(forest.stats().loads, 0);
+
+    assert!(forest.contains("é", MatchMode::Exact));
+    assert_eq!(forest.stats().loads, 1);
+    forest.completions("ab", MatchMode::Exact);
+    forest.contains_prefix("a", MatchMode::Exact);
+    assert_eq!((forest.stats().loads, forest.stats().resident), (2, 2));
+
+    assert_eq!(words_of(&forest), words);
+    let stats = forest.stats();
+    assert!(stats.resident <= 3 && stats.peak_resident == 3 && stats.evictions == stats.loads - 3, "{stats:?}");
+    assert_same_answers(&forest, &words);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn threads_share_the_shards_of_a_saved_forest() {
+    let words = common::words();
+    let dir = temp_dir("threads");
+    DawgForest::<dawging::SyncWrapper>::build(&words, &ForestOptions::new()).unwrap().save_dir(&dir).unwrap();
+
+    // shards are read outside the lock, a shard read by two threads at once being kept once
+    let forest = DawgForest::<dawging::SyncWrapper>::load_dir(&dir, &ForestOptions::new().resident_shards(4)).unwrap();
+    std::thread::scope(|scope| {
+        for thread in 0..4 {
+            let (forest, words) = (&forest, &words);
+            scope.spawn(move || {
+                for word in words.iter().skip(thread) {
+                    assert!(forest.contains(word, MatchMode::Exact), "{word}");
+                }
+            });
+        }
+    });
+    let stats = forest.stats();
+    assert!(stats.failed_loads == 0 && stats.peak_resident <= 4 && stats.resident <= stats.loads - stats.evictions, "{stats:?}");
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_packed_forest_answers_as_the_directory() {
+    let words = common::words();
+    let dir = temp_dir("packed");
+    let options = ForestOptions::new().resident_shards(3);
+    DawgForest::<UnsyncWrapper>::build(&words, &ForestOptions::new()).unwrap().save_dir(&dir).unwrap();
+
+    // repacking a saved forest stays within its bound of resident shards
+    let saved = DawgForest::<UnsyncWrapper>::load_dir(&dir, &options).unwrap();
+    let packed = dir.join("packed.bin");
+    saved.save_packed(fs::File::create(&packed).unwrap()).unwrap();
+    assert!(saved.stats().peak_resident <= 3);
+
+    let forest = DawgForest::<UnsyncWrapper>::open_packed(&packed, &options).unwrap();
+    assert_eq!(forest.stats().loads, 0);
+    assert_eq!(words_of(&forest), words);
+    assert_same_answers(&forest, &words);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn damaged_forests_are_reported() {
+    let dir = temp_dir("damaged");
+    DawgForest::<UnsyncWrapper>::build(["apple", "berry", "cherry"], &ForestOptions::new()).unwrap().save_dir(&dir).unwrap();
+
+    // a missing shard only fails the queries reaching it
+    fs::remove_file(dir.join("shard-62.dawg")).unwrap();
+    let forest = DawgForest::<UnsyncWrapper>::load_dir(&dir, &ForestOptions::new()).unwrap();
+    assert!(forest.try_contains("apple", MatchMode::Exact).unwrap());
+    assert_eq!(forest.last_error(), None);
+    assert!(forest.try_contains("berry", MatchMode::Exact).is_err());
+    assert!(forest.words().any(|word| word.is_err()));
+    assert_eq!(forest.stats().failed_loads, 2);
+    // which `Lexicon` answers as if the shard were empty, counting the failures all the same
+    assert!(Lexicon::contains(&forest, "apple", MatchMode::Exact) && !Lexicon::contains(&forest, "berry", MatchMode::Exact));
+    assert!(!Lexicon::contains_prefix(&forest, "be", MatchMode::Exact));
+    assert!(Lexicon::completions(&forest, "b", MatchMode::Exact).is_empty());
+    assert_eq!(forest.stats().failed_loads, 5);
+    assert!(forest.last_error().is_some());
+    assert!(matches!(forest.shard("z"), Err(DawgError::MissingShard(key)) if key == "z"));
+
+    let index = dir.join("forest.idx");
+    let mut bytes = fs::read(&index).unwrap();
+    let len = bytes.len();
+    bytes[len - 6] ^= 1;
+    fs::write(&index, bytes).unwrap();
+    assert!(DawgForest::<UnsyncWrapper>::load_dir(&dir, &ForestOptions::new()).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,90 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::options::DawgOptions, DawgError, UnsyncDawg};
+
+fn export(dawg: &UnsyncDawg) -> Vec<u8> {
+    let mut bytes = vec![];
+    dawg.export_front_coded(&mut bytes).unwrap();
+    bytes
+}
+
+fn import(bytes: &[u8]) -> Result<UnsyncDawg, DawgError> {
+    UnsyncDawg::from_front_coded(bytes, DawgOptions::new())
+}
+
+#[test]
+fn a_big_list_round_trips_smaller_than_its_text() {
+    let words = common::words();
+    let dawg = build(&words);
+    let bytes = export(&dawg);
+    assert!(bytes.len() < words.iter().map(|word| word.len() + 1).sum::<usize>());
+
+    let back = import(&bytes).unwrap();
+    checked(&back);
+    back.assert_minimal().unwrap();
+    assert_eq!(back.to_bytes().unwrap(), dawg.to_bytes().unwrap());
+}
+
+#[test]
+fn the_documented_example_decodes() {
+    let example = [0, 3, b'c', b'a', b'r', 3, 1, b't', 2, 1, b't'];
+    assert_eq!(export(&build(&["car", "cart", "cat"])), example);
+    assert_eq!(import(&example).unwrap().words().collect::<Vec<_>>(), ["car", "cart", "cat"]);
+}
+
+#[test]
+fn long_words_take_longer_varints_and_chars_are_shared_whole() {
+    let long = "x".repeat(300);
+    let bytes = export(&build(&[long.as_str(), "è", "é"]));
+    // 300 is 0b10_0101100: 0xAC then 0x02
+    assert_eq!(bytes[..3], [0, 0xAC, 0x02]);
+    // "è" and "é" share their first byte, which isn't a char of its own
+    assert_eq!(bytes[303..], [0, 2, 0xC3, 0xA8, 0, 2, 0xC3, 0xA9]);
+    assert_eq!(import(&bytes).unwrap().words().collect::<Vec<_>>(), [long.as_str(), "è", "é"]);
+}
+
+#[test]
+fn empty_words_and_lists_round_trip() {
+    let bytes = export(&build(&["", "a"]));
+    assert_eq!(bytes, [0, 0, 0, 1, b'a']);
+    assert_eq!(import(&bytes).unwrap().words().collect::<Vec<_>>(), ["", "a"]);
+
+    let empty = import(&[]).unwrap();
+    assert!(empty.is_finished());
+    assert_eq!(empty.words().next(), None);
+    assert!(export(&empty).is_empty());
+}
+
+#[test]
+fn malformed_lists_are_refused() {
+    let broken: [&[u8]; 8] = [
+        &[0x80],
+        &[0, 0x80],
+        &[0xFF; 11],
+        &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02],
+        &[0],
+        &[0, 3, b'a'],
+        &[5, 1, b'a'],
+        &[0, 1, 0xFF],
+    ];
+    for bytes in broken {
+        let error = import(bytes).unwrap_err();
+        assert!(matches!(error, DawgError::InvalidFormat(_)), "{bytes:?}: {error}");
+    }
+    // a varint of u64::MAX is well formed, but then the word it announces is missing
+    assert!(import(&[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01, 0]).is_err());
+    assert!(matches!(import(&[0, 1, b'b', 0, 1, b'a']), Err(DawgError::Unsorted { .. })));
+}
+
+#[test]
+fn a_list_can_be_read_into_a_dawg_being_built() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("a").unwrap();
+    assert_eq!(dawg.read_front_coded(&export(&build(&["b", "ba"]))[..]).unwrap(), 2);
+    checked(&dawg);
+    dawg.finish();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["a", "b", "ba"]);
+}
@@ -0,0 +1,155 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, build_with};
+use dawging::dawg::{fuzzy::{Automaton, LevenshteinAutomaton}, options::DawgOptions};
+
+/// value is true if `word` matches the glob `pattern`, trying every split of the letters between the `*`s
+fn globs(pattern: &[char], word: &[char]) -> bool {
+    match (pattern, word) {
+        ([], []) => true,
+        (['*', rest @ ..], _) => (0..=word.len()).any(|skip| globs(rest, &word[skip..])),
+        ([expected, rest @ ..], [letter, tail @ ..]) => (*expected == '?' || expected == letter) && globs(rest, tail),
+        _ => false,
+    }
+}
+
+/// Edit distance between `a` and `b`, by the full table
+fn distance(a: &str, b: &str) -> usize {
+    let b = b.chars().collect::<Vec<_>>();
+    let mut row = (0..=b.len()).collect::<Vec<_>>();
+    for (index, letter) in a.chars().enumerate() {
+        let mut next = vec![index + 1];
+        for column in 1..=b.len() {
+            next.push((row[column - 1] + (b[column - 1] != letter) as usize).min(row[column] + 1).min(next[column - 1] + 1));
+        }
+        row = next;
+    }
+    row[b.len()]
+}
+
+/// Queries drawn from `words`, along with a few found nowhere in them
+fn queries(words: &[String]) -> Vec<String> {
+    words.iter().step_by(97).cloned().chain(["", "x", "qqqq", "éte", "abcdefghij", "字"].map(String::from)).collect()
+}
+
+/// Automaton accepting the words that start with its letter, to run a search through the trait alone
+struct StartsWith(char);
+
+impl Automaton for StartsWith {
+    /// None before any letter, then whether the first letter was the right one
+    type State = Option<bool>;
+
+    fn start(&self) -> Self::State {
+        None
+    }
+
+    fn step(&self, state: &Self::State, letter: char) -> Self::State {
+        Some(state.unwrap_or(letter == self.0))
+    }
+
+    fn is_match(&self, state: &Self::State) -> bool {
+        *state == Some(true)
+    }
+
+    fn can_match(&self, state: &Self::State) -> bool {
+        *state != Some(false)
+    }
+}
+
+#[test]
+fn both_searches_find_the_words_within_the_distance() {
+    let words = common::words();
+    let dawg = build(&words);
+
+    for query in queries(&words) {
+        for max_distance in 0..=3 {
+            let expected = words.iter().filter_map(|word| {
+                let distance = distance(&query, word);
+                (distance <= max_distance as usize).then(|| (word.clone(), distance as u8))
+            }).collect::<Vec<_>>();
+
+            assert_eq!(dawg.fuzzy_search(&query, max_distance), expected, "{query} within {max_distance}");
+            let automaton = LevenshteinAutomaton::new(&query, max_distance);
+            assert_eq!(automaton.max_distance(), max_distance);
+            assert_eq!(dawg.search_automaton(&automaton), expected.iter().map(|(word, _)| word.clone()).collect::<Vec<_>>());
+        }
+    }
+}
+
+#[test]
+fn the_automaton_tracks_the_distance_of_what_it_read() {
+    let words = common::words().into_iter().step_by(3).collect::<Vec<_>>();
+    for query in ["", "able", "anchor", "zzzzzz", "éte"] {
+        let automaton = LevenshteinAutomaton::new(query, 2);
+        assert!(automaton.state_count() > 1);
+        for word in words.iter().map(String::as_str).chain(["", "abel", "ankhor"]) {
+            let state = word.chars().fold(automaton.start(), |state, letter| automaton.step(&state, letter));
+            let expected = distance(query, word);
+            assert_eq!(automaton.distance(&state), (expected <= 2).then_some(expected as u8), "{query} to {word}");
+            assert_eq!(automaton.is_match(&state), expected <= 2);
+        }
+    }
+}
+
+#[test]
+fn one_automaton_serves_several_dawgs() {
+    let words = common::words();
+    let dawgs = [build(&words), build(&words.iter().step_by(2).collect::<Vec<_>>()), build(&words.iter().skip(1).step_by(5).collect::<Vec<_>>())];
+
+    for query in queries(&words) {
+        let automaton = LevenshteinAutomaton::new(&query, 2);
+        for dawg in &dawgs {
+            let expected = dawg.fuzzy_search(&query, 2).into_iter().map(|(word, _)| word).collect::<Vec<_>>();
+            assert_eq!(dawg.search_automaton(&automaton), expected, "{query}");
+        }
+    }
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn one_automaton_serves_sync_and_unsync_dawgs() {
+    let words = common::words();
+    let unsync = build(&words);
+    let mut sync = dawging::SyncDawg::new_sync();
+    for word in &words {
+        sync.try_add(word).unwrap();
+    }
+    sync.finish();
+
+    let automaton = LevenshteinAutomaton::new(&format!("{}x", words[500]), 2);
+    assert!(!unsync.search_automaton(&automaton).is_empty());
+    assert_eq!(sync.search_automaton(&automaton), unsync.search_automaton(&automaton));
+}
+
+#[test]
+fn any_automaton_can_be_searched() {
+    let words = common::words();
+    let dawg = build(&words);
+    for letter in ['a', 'q', 'é', '!'] {
+        let expected = words.iter().filter(|word| word.starts_with(letter)).cloned().collect::<Vec<_>>();
+        assert_eq!(dawg.search_automaton(&StartsWith(letter)), expected);
+    }
+}
+
+#[test]
+fn queries_are_folded_as_the_words() {
+    let dawg = build_with(&["cat", "dog"], DawgOptions::new().case_folding(true));
+    assert_eq!(dawg.fuzzy_search("CAR", 1), [("cat".to_string(), 1)]);
+    assert_eq!(dawg.fuzzy_search("DOG", 0), [("dog".to_string(), 0)]);
+}
+
+#[test]
+fn globs_match_what_they_spell() {
+    let words = common::words();
+    let dawg = build(&words);
+    for pattern in ["*", "", "a*", "*ing", "?a?", "c*t", "*a*e*i*", "**e?", "*?*?*?*?*?*?*?*?*?*?*?*?", "b?*?d", "x*y*z"] {
+        let letters = pattern.chars().collect::<Vec<_>>();
+        let expected = words.iter().filter(|word| globs(&letters, &word.chars().collect::<Vec<_>>())).cloned().collect::<Vec<_>>();
+        assert_eq!(dawg.glob(pattern), expected, "{pattern}");
+    }
+
+    let dawg = build_with(&["Cat", "cot", "dog"], DawgOptions::new().case_folding(true));
+    assert_eq!(dawg.glob("C?T"), ["cat", "cot"]);
+}
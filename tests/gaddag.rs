@@ -0,0 +1,181 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::{BTreeSet, HashSet};
+
+use common::{checked, xorshift};
+use dawging::{dawg::{crosscheck::{LetterAlphabet, LetterSet}, gaddag::{Gaddag, Placement, Rack, RowState, Tile, BLANK, SEPARATOR}, options::DawgOptions}, DawgError, UnsyncWrapper};
+
+const WORDS: [&str; 21] = ["a", "ab", "abba", "abs", "ad", "as", "ba", "bad", "bads", "bass", "cab", "cabs", "cad", "cads", "dab", "dabs", "dada", "sad", "sass", "scab", "scad"];
+
+/// Letters drawn for the random rows and racks
+const LETTERS: [char; 5] = ['a', 'b', 'c', 'd', 's'];
+
+fn gaddag() -> Gaddag<UnsyncWrapper> {
+    let gaddag = Gaddag::<UnsyncWrapper>::from_words(WORDS.iter().rev(), DawgOptions::new()).unwrap();
+    checked(gaddag.dawg());
+    gaddag
+}
+
+/// A row of squares, as the brute force enumerator sees it
+struct Row {
+    letters: Vec<Option<char>>,
+    anchors: Vec<bool>,
+    allowed: Vec<Option<Vec<char>>>,
+}
+
+impl Row {
+    fn state(&self) -> RowState {
+        let mut state = RowState::new(self.letters.len());
+        for square in 0..self.letters.len() {
+            if let Some(letter) = self.letters[square] {
+                state = state.letter(square, letter);
+            }
+            if self.anchors[square] {
+                state = state.anchor(square);
+            }
+            if let Some(allowed) = &self.allowed[square] {
+                let mut set = LetterSet::default();
+                allowed.iter().for_each(|letter| assert!(set.insert(*letter, &LetterAlphabet::LATIN)));
+                state = state.cross_check(square, set);
+            }
+        }
+        state
+    }
+}
+
+/// Calls `found` with every way of putting tiles of `rack` on the squares of `empty`, a blank standing for any letter of a to z
+fn fill(empty: &[usize], rack: &mut Vec<char>, tiles: &mut Vec<Tile>, found: &mut dyn FnMut(&[Tile])) {
+    let Some((square, rest)) = empty.split_first() else { return found(tiles) };
+    for tile in rack.iter().copied().collect::<BTreeSet<_>>() {
+        let index = rack.iter().position(|other| *other == tile).unwrap();
+        rack.remove(index);
+        let letters = match tile == BLANK {
+            true => ('a'..='z').collect::<Vec<_>>(),
+            false => vec![tile],
+        };
+        for letter in letters {
+            tiles.push(Tile { index: *square, letter, blank: tile == BLANK });
+            fill(rest, rack, tiles, found);
+            tiles.pop();
+        }
+        rack.insert(index, tile);
+    }
+}
+
+/// Every placement on `row` by trying every span of squares and every way of filling its empty squares
+fn brute_force(row: &Row, rack: &str, words: &HashSet<&str>) -> BTreeSet<Placement> {
+    let len = row.letters.len();
+    let mut placements = BTreeSet::new();
+    for start in 0..len {
+        for end in start..len {
+            let covers_neighbours = (start == 0 || row.letters[start - 1].is_none()) && (end + 1 == len || row.letters[end + 1].is_none());
+            if !covers_neighbours || !(start..=end).any(|square| row.anchors[square] && row.letters[square].is_none()) {
+                continue;
+            }
+
+            let empty = (start..=end).filter(|square| row.letters[*square].is_none()).collect::<Vec<_>>();
+            fill(&empty, &mut rack.chars().collect(), &mut vec![], &mut |tiles| {
+                let word = (start..=end).map(|square| row.letters[square].unwrap_or_else(|| tiles.iter().find(|tile| tile.index == square).unwrap().letter)).collect::<String>();
+                let allowed = tiles.iter().all(|tile| row.allowed[tile.index].as_ref().is_none_or(|allowed| allowed.contains(&tile.letter)));
+                if allowed && words.contains(word.as_str()) {
+                    placements.insert(Placement { start, word, tiles: tiles.to_vec() });
+                }
+            });
+        }
+    }
+    placements
+}
+
+/// (start, word) of each placement
+fn spans(placements: &[Placement]) -> BTreeSet<(usize, &str)> {
+    placements.iter().map(|placement| (placement.start, placement.word.as_str())).collect()
+}
+
+#[test]
+fn the_gaddag_holds_the_words_read_from_any_letter() {
+    let gaddag = gaddag();
+    assert!(WORDS.iter().all(|word| gaddag.contains(word)));
+    assert!(!gaddag.contains("bd") && !gaddag.contains("") && !gaddag.contains("abb"));
+
+    // "cab" is read from each of its letters
+    let paths = gaddag.dawg().words().collect::<HashSet<_>>();
+    for path in ["c\u{0}ab", "ac\u{0}b", "bac\u{0}"] {
+        assert!(paths.contains(path), "{path}");
+    }
+    assert_eq!(paths.len(), WORDS.iter().map(|word| word.len()).sum::<usize>());
+
+    let error = Gaddag::<UnsyncWrapper>::from_words([format!("a{SEPARATOR}b")], DawgOptions::new()).unwrap_err();
+    assert!(matches!(error, DawgError::InvalidCharacter { character: SEPARATOR, .. }));
+
+    let folded = Gaddag::<UnsyncWrapper>::from_words(["Cab", "ab"], DawgOptions::new().case_folding(true).alphabet("abc")).unwrap();
+    assert!(folded.contains("CAB") && folded.contains("cab") && !folded.contains("ba"));
+}
+
+#[test]
+fn hand_checked_rows() {
+    let gaddag = gaddag();
+
+    // tiles "a" and "s" around a "b"
+    let moves = gaddag.generate_moves(&RowState::parse("..b..").anchors_next_to_letters(), &Rack::new("as"));
+    let expected = [(1, "ab"), (1, "abs"), (2, "ba")];
+    assert_eq!(spans(&moves), expected.into_iter().collect());
+    let abs = moves.iter().find(|placement| placement.word == "abs").unwrap();
+    assert_eq!(abs.tiles, [Tile { index: 1, letter: 'a', blank: false }, Tile { index: 3, letter: 's', blank: false }]);
+
+    let moves = gaddag.generate_moves(&RowState::parse(".a.").anchor(0).anchor(2), &Rack::new("bs"));
+    assert_eq!(spans(&moves), [(0, "ba"), (1, "ab"), (1, "as")].into_iter().collect());
+
+    // a placement must take in the letter right after it: "ca" would be followed by "b", so "cab" is formed instead
+    let moves = gaddag.generate_moves(&RowState::parse("..b").anchor(0), &Rack::new("ca"));
+    assert_eq!(spans(&moves), [(0, "a"), (0, "cab")].into_iter().collect());
+
+    // the cross-check of square 1 rules the "a" out
+    let row = RowState::parse("..b").anchor(1).cross_check(1, LetterSet::default());
+    assert!(gaddag.generate_moves(&row, &Rack::new("ca")).is_empty());
+
+    assert!(gaddag.generate_moves(&RowState::parse("...").anchor(1), &Rack::new("")).is_empty());
+    assert!(gaddag.generate_moves(&RowState::parse("ab."), &Rack::new("s")).is_empty());
+}
+
+#[test]
+fn blanks_give_each_placement_once() {
+    let gaddag = gaddag();
+    let moves = gaddag.generate_moves(&RowState::parse("..").anchor(0), &Rack::new("a?"));
+
+    let ab = moves.iter().filter(|placement| placement.word == "ab").collect::<Vec<_>>();
+    assert_eq!(ab.len(), 1);
+    assert!(!ab[0].tiles[0].blank && ab[0].tiles[1].blank);
+    // "a" is placed either with the tile "a" or with the blank
+    assert_eq!(moves.iter().filter(|placement| placement.word == "a").count(), 2);
+
+    let blanks = gaddag.generate_moves(&RowState::parse("...").anchor(0), &Rack::new("??"));
+    assert_eq!(blanks.iter().collect::<HashSet<_>>().len(), blanks.len());
+    assert!(blanks.iter().all(|placement| placement.tiles.iter().all(|tile| tile.blank)));
+    assert_eq!(Rack::new("a??b").blanks(), 2);
+    assert_eq!(Rack::new("a??b").len(), 4);
+}
+
+#[test]
+fn random_rows_give_every_placement_of_brute_force() {
+    let gaddag = gaddag();
+    let words = WORDS.into_iter().collect::<HashSet<_>>();
+    let (mut state, mut found) = (3, 0);
+
+    for _ in 0..400 {
+        let mut below = |bound: u64| xorshift(&mut state) % bound;
+        let len = 1 + below(7) as usize;
+        let letters = (0..len).map(|_| (below(3) == 0).then(|| LETTERS[below(5) as usize])).collect::<Vec<_>>();
+        let anchors = (0..len).map(|_| below(2) == 0).collect::<Vec<_>>();
+        let allowed = (0..len).map(|_| (below(4) == 0).then(|| ['a', 'b', 's'].into_iter().filter(|_| below(2) == 0).collect())).collect::<Vec<_>>();
+        let rack = (0..below(4)).map(|_| ['a', 'b', 's', 'd', 'c', BLANK, 'a'][below(7) as usize]).collect::<String>();
+        let row = Row { letters, anchors, allowed };
+
+        let moves = gaddag.generate_moves(&row.state(), &Rack::new(&rack));
+        assert!(moves.windows(2).all(|pair| pair[0] < pair[1]));
+        found += moves.len();
+        assert_eq!(moves.into_iter().collect::<BTreeSet<_>>(), brute_force(&row, &rack, &words), "{:?} {:?} {:?} {rack}", row.letters, row.anchors, row.allowed);
+    }
+    assert!(found > 400, "{found}");
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, build_with, fixture};
+use dawging::dawg::{grid::{Adjacency, Grid, GridRules}, options::DawgOptions};
+
+type Path = Vec<(usize, usize)>;
+
+/// A Boggle board, with a "Qu" die on the last row
+const BOARD: [&str; 4] = ["cats", "area", "tent", "sbqe"];
+
+/// `fixtures/boggle.txt`: short words, several of which are on the board
+fn boggle_words() -> Vec<String> {
+    std::fs::read_to_string(fixture("boggle.txt")).unwrap().lines().map(String::from).collect()
+}
+
+/// The cells of the board, "q" showing "qu"
+fn cells() -> Vec<Vec<String>> {
+    BOARD.iter().map(|row| row.chars().map(|letter| if letter == 'q' { String::from("qu") } else { letter.to_string() }).collect()).collect()
+}
+
+/// Every path of `cells` spelling `word`, found by trying each cell as a start
+fn paths(cells: &[Vec<String>], word: &str, diagonals: bool, reuse: bool) -> Vec<Path> {
+    fn walk(cells: &[Vec<String>], rest: &str, cell: (usize, usize), path: &mut Path, rules: (bool, bool), paths: &mut Vec<Path>) {
+        let letters = &cells[cell.0][cell.1];
+        if (!rules.1 && path.contains(&cell)) || letters.is_empty() || !rest.starts_with(letters.as_str()) {
+            return;
+        }
+
+        path.push(cell);
+        let rest = &rest[letters.len()..];
+        if rest.is_empty() {
+            paths.push(path.clone());
+        }
+        for (dr, dc) in (-1..=1).flat_map(|dr| (-1..=1).map(move |dc| (dr, dc))) {
+            let (row, column) = (cell.0 as isize + dr, cell.1 as isize + dc);
+            let adjacent = (dr, dc) != (0, 0) && (rules.0 || dr.abs() + dc.abs() == 1);
+            if adjacent && (0..cells.len() as isize).contains(&row) && (0..cells[0].len() as isize).contains(&column) && !rest.is_empty() {
+                walk(cells, rest, (row as usize, column as usize), path, rules, paths);
+            }
+        }
+        path.pop();
+    }
+
+    let mut paths = vec![];
+    for row in 0..cells.len() {
+        for column in 0..cells[0].len() {
+            walk(cells, word, (row, column), &mut vec![], (diagonals, reuse), &mut paths);
+        }
+    }
+    paths
+}
+
+#[test]
+fn the_classic_board_gives_its_known_words() {
+    let dawg = build(&boggle_words());
+    let grid = Grid::from_cells(cells()).unwrap();
+    assert_eq!((grid.width(), grid.height()), (4, 4));
+    assert_eq!((grid.cell(3, 2), grid.cell(0, 4), grid.cell(4, 0)), (Some("qu"), None, None));
+
+    let found = dawg.find_in_grid(&grid, GridRules::new()).into_iter().map(|found| found.word).collect::<Vec<_>>();
+    let expected = [
+        "art", "arts", "car", "care", "cart", "cat", "eat", "neat", "net", "queen", "rat", "rate", "sat", "seat", "set", "star", "stare", "tar",
+        "tare", "tea", "tear", "ten", "tent", "test",
+    ];
+    assert_eq!(found, expected);
+    // no "i" for "quit" nor "quite", and no "a" next to the "b" for "bat"
+    assert!(boggle_words().iter().filter(|word| !found.contains(word)).eq(["bat", "bats", "quit", "quite"].iter()));
+}
+
+#[test]
+fn every_rule_gives_the_words_and_paths_of_brute_force() {
+    let mut words = boggle_words();
+    words.extend(common::words());
+    words.sort();
+    words.dedup();
+    let dawg = build(&words);
+    let cells = cells();
+    let grid = Grid::from_cells(cells.clone()).unwrap();
+
+    for (adjacency, diagonals) in [(Adjacency::Eight, true), (Adjacency::Four, false)] {
+        for reuse in [false, true] {
+            for min_len in [1, 3, 5] {
+                let expected = words.iter()
+                    .filter(|word| word.chars().count() >= min_len)
+                    .map(|word| (word, paths(&cells, word, diagonals, reuse)))
+                    .filter(|(_, paths)| !paths.is_empty())
+                    .collect::<Vec<_>>();
+
+                let rules = GridRules::new().adjacency(adjacency).reuse_cells(reuse).min_len(min_len);
+                let first = dawg.find_in_grid(&grid, rules);
+                let all = dawg.find_in_grid(&grid, rules.all_paths(true));
+                assert_eq!(first.iter().map(|found| &found.word).collect::<Vec<_>>(), expected.iter().map(|(word, _)| *word).collect::<Vec<_>>(), "{adjacency:?}, reuse {reuse}");
+                assert_eq!(all.len(), first.len());
+
+                for ((first, all), (_, paths)) in first.iter().zip(&all).zip(&expected) {
+                    assert_eq!(first.paths.len(), 1);
+                    assert!(paths.contains(&first.path().to_vec()), "{}", first.word);
+                    let mut found = all.paths.clone();
+                    found.sort();
+                    let mut paths = paths.clone();
+                    paths.sort();
+                    assert_eq!(found, paths, "{}", all.word);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn cells_are_used_once_unless_the_rules_allow_more() {
+    let dawg = build(&["aaa", "aba", "abab"]);
+    let grid = Grid::new(&["ab"]).unwrap();
+    let words = |rules: GridRules| dawg.find_in_grid(&grid, rules).into_iter().map(|found| found.word).collect::<Vec<_>>();
+
+    assert!(words(GridRules::new()).is_empty());
+    assert_eq!(words(GridRules::new().reuse_cells(true)), ["aba", "abab"]);
+    let abab = &dawg.find_in_grid(&grid, GridRules::new().reuse_cells(true))[1];
+    assert_eq!(abab.path(), [(0, 0), (0, 1), (0, 0), (0, 1)]);
+
+    // diagonal neighbours only meet under 8-way adjacency
+    let grid = Grid::new(&["a.", ".b"]).unwrap();
+    let dawg = build(&["ab"]);
+    assert_eq!(dawg.find_in_grid(&grid, GridRules::new().min_len(2)).len(), 1);
+    assert!(dawg.find_in_grid(&grid, GridRules::new().min_len(2).adjacency(Adjacency::Four)).is_empty());
+}
+
+#[test]
+fn ragged_and_empty_grids() {
+    assert!(Grid::new(&["ab", "c"]).is_err());
+    let empty = Grid::new(&[]).unwrap();
+    assert_eq!((empty.width(), empty.height()), (0, 0));
+    assert!(build(&["a"]).find_in_grid(&empty, GridRules::new().min_len(1)).is_empty());
+
+    // a cell showing nothing can't be part of a path
+    let grid = Grid::from_cells(vec![vec!["a", "", "b"]]).unwrap();
+    assert!(build(&["ab"]).find_in_grid(&grid, GridRules::new().min_len(2).adjacency(Adjacency::Four)).is_empty());
+}
+
+#[test]
+fn the_cells_are_folded_as_the_words() {
+    let dawg = build_with(&["ab", "qua"], DawgOptions::new().case_folding(true));
+    let grid = Grid::from_cells(vec![vec!["A", "B"], vec!["Qu", "X"]]).unwrap();
+    let found = dawg.find_in_grid(&grid, GridRules::new().min_len(2));
+    assert_eq!(found.iter().map(|found| found.word.as_str()).collect::<Vec<_>>(), ["ab", "qua"]);
+    assert_eq!(found[1].path(), [(1, 0), (0, 0)]);
+}
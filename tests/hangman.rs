@@ -0,0 +1,115 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeMap;
+
+use common::{build, build_with, fixture};
+use dawging::dawg::{hangman::{parse_pattern, HangmanRules, HangmanTally}, options::DawgOptions};
+
+/// (pattern, wrong guesses) pairs
+const GAMES: [(&str, &str); 12] = [
+    ("_a__e", ""), ("_a__e", "st"), ("___", ""), ("___", "aeiou"), ("t__t", "e"), ("_", ""), ("", ""), ("c_t", "a"), ("_____", "xyz"),
+    ("zzzz", ""), ("a___", "bcd"), ("é__", ""),
+];
+
+/// The words of both fixtures
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(std::fs::read_to_string(fixture("boggle.txt")).unwrap().lines().map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The words matching `pattern` by checking every word of `words`
+fn brute_force(words: &[String], pattern: &[Option<char>], excluded: &[char], revealed_everywhere: bool) -> Vec<String> {
+    let revealed = pattern.iter().flatten().copied().collect::<Vec<_>>();
+    words.iter().filter(|word| {
+        let letters = word.chars().collect::<Vec<_>>();
+        letters.len() == pattern.len() && letters.iter().zip(pattern).all(|(letter, square)| match square {
+            Some(known) => letter == known,
+            None => !(excluded.contains(letter) || (revealed_everywhere && revealed.contains(letter))),
+        })
+    }).cloned().collect()
+}
+
+/// The tally of the unknown squares of `words`, counted by hand
+fn count(words: &[String], pattern: &[Option<char>]) -> HangmanTally {
+    let mut letters = BTreeMap::<char, (usize, usize)>::new();
+    for word in words {
+        let mut seen = vec![];
+        for (letter, _) in word.chars().zip(pattern).filter(|(_, square)| square.is_none()) {
+            let (holding, occurrences) = letters.entry(letter).or_default();
+            *occurrences += 1;
+            if !seen.contains(&letter) {
+                seen.push(letter);
+                *holding += 1;
+            }
+        }
+    }
+    HangmanTally { words: words.len(), letters }
+}
+
+#[test]
+fn the_matches_are_those_of_brute_force() {
+    let words = words();
+    let dawg = build(&words);
+
+    for (pattern, excluded) in GAMES {
+        let (pattern, excluded) = (parse_pattern(pattern), excluded.chars().collect::<Vec<_>>());
+        for revealed_everywhere in [true, false] {
+            let expected = brute_force(&words, &pattern, &excluded, revealed_everywhere);
+            let rules = HangmanRules::new().revealed_everywhere(revealed_everywhere);
+            assert_eq!(dawg.hangman_with(&pattern, &excluded, rules).collect::<Vec<_>>(), expected, "{pattern:?} without {excluded:?}, {rules:?}");
+        }
+        assert_eq!(dawg.hangman(&pattern, &excluded).collect::<Vec<_>>(), brute_force(&words, &pattern, &excluded, true));
+    }
+}
+
+#[test]
+fn the_tally_counts_the_unknown_squares() {
+    let words = words();
+    let dawg = build(&words);
+
+    for (pattern, excluded) in GAMES {
+        let (pattern, excluded) = (parse_pattern(pattern), excluded.chars().collect::<Vec<_>>());
+        for revealed_everywhere in [true, false] {
+            let rules = HangmanRules::new().revealed_everywhere(revealed_everywhere);
+            let tally = dawg.hangman_tally(&pattern, &excluded, rules);
+            let expected = count(&brute_force(&words, &pattern, &excluded, revealed_everywhere), &pattern);
+            assert_eq!(tally, expected, "{pattern:?} without {excluded:?}");
+
+            let most = tally.letters.values().map(|(holding, _)| *holding).max();
+            assert_eq!(tally.best_guess().map(|letter| tally.words_with(letter)), most);
+            assert_eq!(tally.best_guess(), tally.letters.iter().find(|(_, (holding, _))| Some(*holding) == most).map(|(letter, _)| *letter));
+        }
+    }
+}
+
+#[test]
+fn a_small_game_by_hand() {
+    let dawg = build(&["bake", "cake", "came", "lake", "make", "male", "mole", "sale"]);
+    let pattern = parse_pattern("_a_e");
+
+    assert_eq!(dawg.hangman(&pattern, &['l']).collect::<Vec<_>>(), ["bake", "cake", "came", "make"]);
+    let tally = dawg.hangman_tally(&pattern, &['l'], HangmanRules::new());
+    assert_eq!(tally.words, 4);
+    assert_eq!((tally.words_with('k'), tally.words_with('c'), tally.words_with('m'), tally.words_with('z')), (3, 2, 2, 0));
+    assert_eq!(tally.best_guess(), Some('k'));
+
+    assert_eq!(dawg.hangman(&parse_pattern("_o_e"), &[]).collect::<Vec<_>>(), ["mole"]);
+    // by default the "a" revealed on the second square would show on every square holding one
+    let dawg = build(&["aaaa", "baaa", "bacd"]);
+    assert_eq!(dawg.hangman(&parse_pattern("_a__"), &[]).collect::<Vec<_>>(), ["bacd"]);
+    assert_eq!(dawg.hangman_with(&parse_pattern("_a__"), &[], HangmanRules::new().revealed_everywhere(false)).count(), 3);
+    assert_eq!(dawg.hangman_tally(&parse_pattern("zz"), &[], HangmanRules::new()), HangmanTally::default());
+    assert_eq!(HangmanTally::default().best_guess(), None);
+}
+
+#[test]
+fn the_pattern_and_the_guesses_are_folded_as_the_words() {
+    let dawg = build_with(&["Cake", "make"], DawgOptions::new().case_folding(true));
+    assert_eq!(dawg.hangman(&parse_pattern("_AKE"), &[]).collect::<Vec<_>>(), ["cake", "make"]);
+    assert_eq!(dawg.hangman(&parse_pattern("_ake"), &['M']).collect::<Vec<_>>(), ["cake"]);
+}
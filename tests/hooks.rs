@@ -0,0 +1,92 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, build_with, checked, fixture};
+use dawging::{dawg::{bidawg::BiDawg, gaddag::Gaddag, hooks::Hooks, options::DawgOptions}, UnsyncWrapper};
+
+/// The words of both fixtures, along with a few words of one or two letters
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(std::fs::read_to_string(fixture("boggle.txt")).unwrap().lines().map(String::from));
+    words.extend(["host", "ghost", "hosts", "a", "i", "ai", "ta"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The hooks of `word` by trying every letter of `words` on both of its sides
+fn brute_force(words: &BTreeSet<&str>, letters: &BTreeSet<char>, word: &str) -> Hooks {
+    Hooks {
+        front: letters.iter().copied().filter(|letter| words.contains(format!("{letter}{word}").as_str())).collect(),
+        back: letters.iter().copied().filter(|letter| words.contains(format!("{word}{letter}").as_str())).collect(),
+    }
+}
+
+#[test]
+fn every_structure_finds_the_hooks_of_brute_force() {
+    let words = words();
+    let lookup = words.iter().map(String::as_str).collect::<BTreeSet<_>>();
+    let letters = words.iter().flat_map(|word| word.chars()).collect::<BTreeSet<_>>();
+    let dawg = build(&words);
+    let gaddag = Gaddag::<UnsyncWrapper>::from_words(&words, DawgOptions::new()).unwrap();
+    let bidawg = BiDawg::<UnsyncWrapper>::from_words(&words, DawgOptions::new()).unwrap();
+    checked(gaddag.dawg());
+    checked(bidawg.reverse());
+
+    let mut queries = words.iter().step_by(3).cloned().collect::<Vec<_>>();
+    queries.extend(["zzqx", "hos", "x", "é"].map(String::from));
+    let mut without_hooks = 0;
+    for word in &queries {
+        let hooks = brute_force(&lookup, &letters, word);
+        assert_eq!(dawg.hooks(word), hooks, "{word}");
+        assert_eq!(gaddag.hooks(word), hooks, "{word}");
+        assert_eq!(bidawg.hooks(word), hooks, "{word}");
+        assert_eq!((dawg.front_hooks(word), dawg.back_hooks(word)), (hooks.front.clone(), hooks.back.clone()));
+        without_hooks += hooks.is_empty() as usize;
+    }
+    assert!(without_hooks > 10, "{without_hooks}");
+}
+
+#[test]
+fn known_hooks() {
+    let dawg = build(&words());
+    let gaddag = Gaddag::<UnsyncWrapper>::from_words(words(), DawgOptions::new()).unwrap();
+
+    assert!(dawg.front_hooks("host").contains(&'g') && dawg.back_hooks("host").contains(&'s'));
+    assert!(gaddag.front_hooks("host").contains(&'g') && gaddag.back_hooks("host").contains(&'s'));
+    assert!(dawg.hooks("zzqx").is_empty() && gaddag.hooks("zzqx").is_empty());
+
+    // one-letter words hook onto each other
+    assert!(dawg.back_hooks("a").contains(&'i') && gaddag.front_hooks("a").contains(&'t'));
+    assert!(dawg.front_hooks("i").contains(&'a'));
+
+    // the hooks of the empty word are the words of a single letter
+    let single = words().into_iter().filter(|word| word.chars().count() == 1).flat_map(|word| word.chars().collect::<Vec<_>>()).collect::<Vec<_>>();
+    assert_eq!(dawg.hooks(""), Hooks { front: single.clone(), back: single.clone() });
+    assert_eq!(gaddag.hooks(""), Hooks { front: single.clone(), back: single });
+}
+
+#[test]
+fn the_sheet_lists_every_word_with_its_hooks() {
+    let words = words();
+    let dawg = build(&words);
+    let gaddag = Gaddag::<UnsyncWrapper>::from_words(&words, DawgOptions::new()).unwrap();
+
+    let sheet = dawg.hook_sheet().collect::<Vec<_>>();
+    assert_eq!(sheet.iter().map(|(word, _)| word).collect::<Vec<_>>(), words.iter().collect::<Vec<_>>());
+    for (word, hooks) in sheet.iter().step_by(37) {
+        assert_eq!(*hooks, gaddag.hooks(word), "{word}");
+    }
+}
+
+#[test]
+fn words_are_folded_by_the_options() {
+    let dawg = build_with(&["ghost", "Host", "hosts"], DawgOptions::new().case_folding(true));
+    assert_eq!(dawg.hooks("HOST"), Hooks { front: vec!['g'], back: vec!['s'] });
+
+    let bidawg = BiDawg::<UnsyncWrapper>::from_words(["ghost", "Host", "hosts"], DawgOptions::new().case_folding(true)).unwrap();
+    assert_eq!(bidawg.hooks("HOST"), Hooks { front: vec!['g'], back: vec!['s'] });
+}
@@ -0,0 +1,107 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, words};
+use dawging::{Dawg, UnsyncDawg, Wrapper};
+
+const WORDS: [&str; 6] = ["a", "ab", "abc", "b", "bc", "zz"];
+
+/// Every prefix of `words` with the id of the node it leads to
+fn ids<T: Wrapper>(dawg: &Dawg<T>, words: &[&str]) -> Vec<(String, usize)> {
+    let prefixes = words.iter().flat_map(|word| (0..=word.len()).map(|end| word[..end].to_string())).collect::<BTreeSet<_>>();
+    prefixes.into_iter().map(|prefix| (prefix.clone(), dawg.descend(&prefix).unwrap().id())).collect()
+}
+
+#[test]
+fn ids_are_dense_and_start_at_the_root() {
+    let dawg = build(&words());
+    let node_count = dawg.compression_stats().dawg_nodes;
+    let mut seen = dawg.visit_nodes().map(|view| view.id()).collect::<Vec<_>>();
+    seen.sort();
+
+    assert_eq!(dawg.root().id(), 0);
+    assert_eq!(seen, (0..node_count).collect::<Vec<_>>());
+}
+
+#[test]
+fn independent_builds_give_the_same_ids() {
+    let dawg = build(&WORDS);
+
+    // a build that went through other nodes first
+    let mut other = UnsyncDawg::new();
+    other.add_all(["junk", "more"]).unwrap();
+    other.clear();
+    let checkpoint = other.checkpoint();
+    other.add_all(["a", "x"]).unwrap();
+    other.rollback(checkpoint).unwrap();
+    other.add_all(WORDS).unwrap();
+    other.finish();
+    checked(&other);
+
+    assert_eq!(ids(&other, &WORDS), ids(&dawg, &WORDS));
+    assert_eq!(ids(&dawg.clone(), &WORDS), ids(&dawg, &WORDS));
+}
+
+#[test]
+fn ids_survive_a_save_and_load() {
+    let dawg = build(&WORDS);
+    let mut saved = vec![];
+    dawg.save(&mut saved).unwrap();
+    let loaded = UnsyncDawg::<char>::load(saved.as_slice()).unwrap();
+    checked(&loaded);
+
+    assert_eq!(ids(&loaded, &WORDS), ids(&dawg, &WORDS));
+}
+
+#[test]
+fn shared_nodes_have_one_id() {
+    let dawg = build(&WORDS);
+    // "b" and "zz" end on the same leaf as "abc"
+    assert_eq!(dawg.descend("abc").unwrap().id(), dawg.descend("zz").unwrap().id());
+    assert_eq!(dawg.descend("a").unwrap().id(), dawg.descend("a").unwrap().id());
+    assert_ne!(dawg.descend("a").unwrap().id(), dawg.descend("b").unwrap().id());
+}
+
+#[test]
+fn finishing_again_after_an_edit_renumbers_densely() {
+    let mut dawg = build(&WORDS);
+    dawg.insert("ac").unwrap();
+    checked(&dawg);
+    // the copies made by the insert get new ids, past the dense ones
+    assert!(dawg.root().id() != 0);
+
+    // saving numbers them densely again, though the edit left the dawg larger than a fresh build
+    let loaded = UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    let node_count = loaded.compression_stats().dawg_nodes;
+    let mut seen = loaded.visit_nodes().map(|view| view.id()).collect::<Vec<_>>();
+    seen.sort();
+    assert_eq!(seen, (0..node_count).collect::<Vec<_>>());
+    assert_eq!(loaded.root().id(), 0);
+    assert!(node_count > build(&["a", "ab", "abc", "ac", "b", "bc", "zz"]).compression_stats().dawg_nodes);
+    assert_eq!(UnsyncDawg::<char>::from_bytes(&loaded.to_bytes().unwrap()).unwrap().to_bytes().unwrap(), loaded.to_bytes().unwrap());
+}
+
+#[test]
+fn word_ranks_are_not_node_ids() {
+    let dawg = build(&WORDS);
+    for (index, word) in WORDS.iter().enumerate() {
+        assert_eq!(dawg.rank(word), Some(index));
+        assert_eq!(dawg.select(index).as_deref(), Some(*word));
+    }
+    // "b" and "zz" end on the same leaf as "abc", though they have different ranks
+    assert_eq!(dawg.descend("abc").unwrap().id(), dawg.descend("zz").unwrap().id());
+    assert_ne!(dawg.rank("abc"), dawg.rank("zz"));
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_gives_the_same_ids() {
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(ids(&dawg, &WORDS), ids(&build(&WORDS), &WORDS));
+}
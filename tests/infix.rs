@@ -0,0 +1,139 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{DawgError, UnsyncDawg};
+
+/// The words of the fixture along with the empty word and words of several scripts
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["", "aé字b", "ééé", "zzzle"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Infixes found anywhere in words, at their start, at their end, whole words, and infixes found nowhere
+fn infixes(words: &[String]) -> Vec<String> {
+    let mut infixes = ["", "zzle", "e", "é", "字", "é字", "éé", "qqqq", "ing", "ab", "s"].map(String::from).to_vec();
+    for word in words.iter().step_by(97) {
+        let letters = word.chars().collect::<Vec<_>>();
+        if letters.len() >= 3 {
+            infixes.push(letters[..2].iter().collect());
+            infixes.push(letters[letters.len() - 2..].iter().collect());
+            infixes.push(letters[1..letters.len() - 1].iter().collect());
+            infixes.push(word.clone());
+        }
+    }
+    infixes
+}
+
+fn brute_force(words: &[String], infix: &str) -> Vec<String> {
+    words.iter().filter(|word| word.contains(infix)).cloned().collect()
+}
+
+#[test]
+fn without_an_index_every_word_is_read() {
+    let words = words();
+    let dawg = build(&words);
+    for infix in infixes(&words) {
+        let mut found = dawg.words_containing(&infix);
+        assert!(!found.is_indexed());
+        assert_eq!(found.by_ref().collect::<Vec<_>>(), brute_force(&words, &infix), "{infix:?}");
+        assert_eq!(found.examined(), words.len());
+    }
+}
+
+#[test]
+fn with_an_index_only_the_matches_are_read() {
+    let words = words();
+    let mut dawg = build(&words);
+    dawg.build_infix_index().unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.infix_index().unwrap().word_count(), words.len());
+
+    for infix in infixes(&words) {
+        let mut found = dawg.words_containing(&infix);
+        assert!(found.is_indexed());
+        let matches = found.by_ref().collect::<Vec<_>>();
+        assert_eq!(matches, brute_force(&words, &infix), "{infix:?}");
+        assert_eq!(found.examined(), matches.len());
+        if !infix.is_empty() {
+            assert!(found.examined() < words.len() / 2, "{infix:?}");
+        }
+    }
+}
+
+#[test]
+fn the_index_follows_the_words() {
+    let mut unfinished = UnsyncDawg::new();
+    unfinished.try_add("a").unwrap();
+    assert!(matches!(unfinished.build_infix_index(), Err(DawgError::NotFinished)));
+
+    let mut dawg = build(&words());
+    dawg.build_infix_index().unwrap();
+    assert!(dawg.fork().infix_index().is_some() && dawg.clone().infix_index().is_some());
+
+    // an edit drops the index, queries read every word again
+    let mut edited = dawg.clone();
+    edited.insert("zzzzle").unwrap();
+    checked(&edited);
+    assert!(edited.infix_index().is_none());
+    let mut found = edited.words_containing("zzle");
+    assert_eq!(found.by_ref().collect::<Vec<_>>(), ["zzzle", "zzzzle"]);
+    assert!(!found.is_indexed());
+
+    dawg.drop_infix_index();
+    assert!(!dawg.words_containing("e").is_indexed());
+}
+
+#[test]
+fn the_index_is_saved_along_with_the_dawg() {
+    let words = words();
+    let mut dawg = build(&words);
+    dawg.build_infix_index().unwrap();
+    let bytes = dawg.to_bytes_with_infix_index().unwrap();
+    let mut saved = vec![];
+    dawg.save_with_infix_index(&mut saved).unwrap();
+    assert_eq!(saved, bytes);
+
+    let loaded = UnsyncDawg::load_with_infix_index(&bytes[..]).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.infix_index(), dawg.infix_index());
+    for infix in infixes(&words).iter().step_by(3) {
+        assert_eq!(loaded.words_containing(infix).collect::<Vec<_>>(), brute_force(&words, infix));
+    }
+
+    // a dawg without an index is saved with one
+    dawg.drop_infix_index();
+    assert_eq!(dawg.to_bytes_with_infix_index().unwrap(), bytes);
+    assert!(dawg.infix_index().is_none());
+    assert!(UnsyncDawg::from_bytes_with_infix_index(&bytes).unwrap().words_containing("zzl").is_indexed());
+}
+
+#[test]
+fn damaged_indexes_are_refused() {
+    let words = words();
+    let bytes = build(&words).to_bytes_with_infix_index().unwrap();
+    let len = bytes.len();
+    let positions = len - 4 * words.iter().map(|word| word.chars().count()).sum::<usize>();
+    let refused = |bytes: &[u8]| matches!(UnsyncDawg::from_bytes_with_infix_index(bytes), Err(DawgError::InvalidFormat(_)) | Err(DawgError::Truncated { .. }));
+
+    // the first and the last suffixes swapped
+    let mut unsorted = bytes.clone();
+    for byte in 0..4 {
+        unsorted.swap(positions + byte, len - 4 + byte);
+    }
+    assert!(refused(&unsorted));
+    // a position past the words
+    let mut outside = bytes.clone();
+    outside[len - 1] = 0xff;
+    assert!(refused(&outside));
+    // a position used twice
+    let mut twice = bytes.clone();
+    twice.copy_within(positions..positions + 4, positions + 4);
+    assert!(refused(&twice));
+    assert!(UnsyncDawg::from_bytes_with_infix_index(&bytes[..len - 3]).is_err());
+    assert!(UnsyncDawg::from_bytes_with_infix_index(&build(&words).to_bytes().unwrap()).is_err());
+}
@@ -0,0 +1,181 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, forge_count};
+use dawging::{dawg::{compiled::CompiledDawg, embedded::CompiledView, integrity::{verify, verify_file, Section}, paged::{PagedDawgReader, PagedOptions}}, DawgError, UnsyncDawg, UnsyncWrapper};
+
+fn saved() -> Vec<u8> {
+    build(&["able", "bake", "baker", "cake", "caked", "zebra", "über"]).to_bytes().unwrap()
+}
+
+fn u32_at(bytes: &[u8], at: usize) -> usize {
+    u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap()) as usize
+}
+
+/// Offsets of a saved dawg: the end of its header (before its checksum), then the start of its nodes, edges and labels
+fn offsets(bytes: &[u8]) -> [usize; 4] {
+    let options_len = u32_at(bytes, 6);
+    let (nodes, edges, labels) = (u32_at(bytes, 10 + options_len), u32_at(bytes, 14 + options_len), u32_at(bytes, 18 + options_len));
+    let header = 10 + options_len + 16;
+    let node_start = header + 4;
+    let edge_start = node_start + nodes * 17 + 4;
+    let label_start = edge_start + edges * 12 + 4;
+    assert_eq!(label_start + labels + 4, bytes.len());
+    [header, node_start, edge_start, label_start]
+}
+
+fn load(bytes: &[u8]) -> Result<UnsyncDawg, DawgError> {
+    UnsyncDawg::from_bytes(bytes)
+}
+
+#[test]
+fn the_round_trip_still_works() {
+    let bytes = saved();
+    verify(&bytes[..]).unwrap();
+    assert_eq!(load(&bytes).unwrap().to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn a_flipped_byte_names_its_section() {
+    let bytes = saved();
+    let [header, nodes, edges, labels] = offsets(&bytes);
+    let options_len = u32_at(&bytes, 6);
+    let label_len = bytes.len() - 4 - labels;
+
+    let flips = [
+        (11 + options_len, Section::Header),
+        (header + 1, Section::Header),
+        (nodes + 3, Section::Nodes),
+        (edges - 5, Section::Nodes),
+        (edges, Section::Edges),
+        (labels + label_len - 1, Section::Labels),
+        (bytes.len() - 1, Section::Labels),
+    ];
+    for (at, expected) in flips {
+        let mut corrupted = bytes.clone();
+        corrupted[at] ^= 0x10;
+        assert!(matches!(load(&corrupted), Err(DawgError::Corrupted { section, .. }) if section == expected), "flip at {at}");
+        assert!(matches!(verify(&corrupted[..]), Err(DawgError::Corrupted { section, .. }) if section == expected), "flip at {at}");
+    }
+}
+
+#[test]
+fn a_truncation_names_its_section() {
+    let bytes = saved();
+    let [header, nodes, edges, labels] = offsets(&bytes);
+
+    let cuts = [
+        (3, Section::Header),
+        (8, Section::Header),
+        (header + 2, Section::Header),
+        (nodes + 5, Section::Nodes),
+        (edges - 2, Section::Nodes),
+        (edges + 1, Section::Edges),
+        (labels + 1, Section::Labels),
+        (bytes.len() - 1, Section::Labels),
+    ];
+    for (cut, expected) in cuts {
+        assert!(matches!(load(&bytes[..cut]), Err(DawgError::Truncated { section }) if section == expected), "cut at {cut}");
+        assert!(matches!(verify(&bytes[..cut]), Err(DawgError::Truncated { section }) if section == expected), "cut at {cut}");
+    }
+}
+
+#[test]
+fn no_corruption_panics() {
+    let bytes = saved();
+    for at in 0..bytes.len() {
+        for value in [0, 0xFF] {
+            let mut corrupted = bytes.clone();
+            if corrupted[at] == value {
+                continue;
+            }
+            corrupted[at] = value;
+            // the version may be lowered to one reading the same bytes
+            assert!(load(&corrupted).is_err() || at < 6, "byte {at} set to {value}");
+            let _ = verify(&corrupted[..]);
+        }
+    }
+
+    for cut in 0..bytes.len() {
+        assert!(load(&bytes[..cut]).is_err());
+        assert!(verify(&bytes[..cut]).is_err());
+    }
+}
+
+#[test]
+fn a_forged_count_is_caught_by_verify() {
+    let forged = forge_count(&saved(), 0, 1);
+    assert!(matches!(verify(&forged[..]), Err(DawgError::CorruptedCount { node: 0, stored: 1, actual: 7 })));
+    assert!(matches!(load(&forged), Err(DawgError::CorruptedCount { node: 0, .. })));
+}
+
+#[test]
+fn more_prefixes_than_can_be_counted_are_caught_by_verify() {
+    verify(&common::forge_chain::<UnsyncWrapper>(6)[..]).unwrap();
+    assert!(matches!(verify(&common::forge_chain::<UnsyncWrapper>(70)[..]), Err(DawgError::InvalidFormat(_))));
+}
+
+#[test]
+fn files_without_checksums_are_still_read() {
+    let bytes = saved();
+    let [header, nodes, edges, labels] = offsets(&bytes);
+
+    let mut unchecked = bytes[..header].to_vec();
+    unchecked[4] = 2;
+    unchecked.extend_from_slice(&bytes[nodes..edges - 4]);
+    unchecked.extend_from_slice(&bytes[edges..labels - 4]);
+    unchecked.extend_from_slice(&bytes[labels..bytes.len() - 4]);
+
+    assert_eq!(load(&unchecked).unwrap().to_bytes().unwrap(), bytes);
+    verify(&unchecked[..]).unwrap();
+    assert!(matches!(verify(&unchecked[..unchecked.len() - 1]), Err(DawgError::Truncated { section: Section::Labels })));
+}
+
+#[test]
+fn verify_file_reads_the_file() {
+    let bytes = saved();
+    let path = std::env::temp_dir().join(format!("dawging-integrity-{}", std::process::id()));
+    std::fs::write(&path, &bytes).unwrap();
+    verify_file(&path).unwrap();
+
+    let mut corrupted = bytes.clone();
+    corrupted[offsets(&bytes)[2]] ^= 1;
+    std::fs::write(&path, &corrupted).unwrap();
+    let error = verify_file(&path).unwrap_err();
+    std::fs::remove_file(&path).unwrap();
+    assert!(error.to_string().contains("edge table"), "{error}");
+
+    assert!(matches!(verify(&b"not a dawg file at all, really"[..]), Err(DawgError::InvalidFormat(_))));
+}
+
+#[test]
+fn compiled_dawgs_are_checked_up_front() {
+    let compiled = build(&["able", "bake", "baker", "cake", "caked", "zebra", "über"]).compile_with(2);
+    let bytes = compiled.to_bytes();
+    assert_eq!(CompiledDawg::from_bytes(&bytes).unwrap(), compiled);
+    verify(&bytes[..]).unwrap();
+
+    let options_len = u32_at(&bytes, 6);
+    let (nodes, edges) = (u32_at(&bytes, 10 + options_len), u32_at(&bytes, 14 + options_len));
+    let node_start = 10 + options_len + 12 + 4;
+    let edge_start = node_start + nodes * 21 + 4;
+    let slot_start = edge_start + edges * 8 + 4;
+
+    for (at, expected) in [(12, Section::Header), (node_start, Section::Nodes), (edge_start + 4, Section::Edges), (slot_start, Section::Slots)] {
+        let mut corrupted = bytes.clone();
+        corrupted[at] ^= 0x40;
+        assert!(matches!(CompiledDawg::from_bytes(&corrupted), Err(DawgError::Corrupted { section, .. }) if section == expected), "flip at {at}");
+        assert!(matches!(CompiledView::from_bytes(&corrupted), Err(DawgError::Corrupted { section, .. }) if section == expected), "flip at {at}");
+        assert!(matches!(verify(&corrupted[..]), Err(DawgError::Corrupted { section, .. }) if section == expected), "flip at {at}");
+    }
+
+    for cut in [5, node_start + 1, slot_start - 1, bytes.len() - 3] {
+        let paged = PagedDawgReader::new(std::io::Cursor::new(bytes[..cut].to_vec()), PagedOptions::new());
+        assert!(matches!(paged, Err(DawgError::Truncated { .. })), "cut at {cut}");
+    }
+    for cut in 0..bytes.len() {
+        assert!(CompiledDawg::from_bytes(&bytes[..cut]).is_err());
+        assert!(CompiledView::from_bytes(&bytes[..cut]).is_err());
+    }
+}
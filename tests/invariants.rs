@@ -0,0 +1,94 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, words};
+use dawging::{dawg::{common::{DawgNode, Wrapper}, invariants::InvariantViolation, options::{DawgOptions, SortOrder}, unsync::UnsyncNode}, Dawg, NodeRef, UnsyncDawg};
+
+/// Hands out nodes that all share the id 0
+struct SameId;
+
+impl Wrapper for SameId {
+    type Node = UnsyncNode<char>;
+
+    fn new() -> Self {
+        SameId
+    }
+
+    fn create(&mut self) -> Self::Node {
+        UnsyncNode::new(DawgNode::new(0))
+    }
+}
+
+#[test]
+fn hold_after_every_mutating_operation() {
+    let mut dawg = UnsyncDawg::new();
+    for word in ["tap", "taps", "top", "tops"] {
+        dawg.try_add(word).unwrap();
+        checked(&dawg);
+    }
+
+    let checkpoint = dawg.checkpoint();
+    dawg.try_add("zz").unwrap();
+    checked(&dawg);
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+
+    dawg.finish();
+    dawg.assert_minimal().unwrap();
+
+    let mut fork = dawg.fork();
+    fork.insert("ta").unwrap();
+    checked(&fork);
+    fork.remove("tops").unwrap();
+    checked(&fork);
+    fork.try_add("a").unwrap();
+    checked(&fork);
+    assert_eq!(fork.words().collect::<Vec<_>>(), ["a", "ta", "tap", "taps", "top"]);
+
+    let mut kept = dawg.clone();
+    kept.retain(|word| word.len() == 3);
+    kept.assert_minimal().unwrap();
+
+    kept.clear();
+    checked(&kept);
+    dawg.assert_minimal().unwrap();
+}
+
+#[test]
+fn hold_under_a_sort_order_that_stages_words() {
+    let mut dawg = UnsyncDawg::with_options(DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes)).unwrap();
+    for word in ["a", "B", "c", "Cb", "cc"] {
+        dawg.try_add(word).unwrap();
+        checked(&dawg);
+    }
+    dawg.finish();
+    dawg.assert_minimal().unwrap();
+}
+
+#[test]
+fn hold_for_the_fixture() {
+    let dawg = build(&words());
+    assert_eq!(dawg.words().count(), words().len());
+}
+
+#[test]
+fn an_insert_can_leave_the_dawg_non_minimal() {
+    let mut dawg = build(&["ab", "b", "cb"]);
+    dawg.insert("db").unwrap();
+    checked(&dawg);
+    assert!(matches!(dawg.assert_minimal(), Err(InvariantViolation::NotMinimal { .. })));
+}
+
+#[test]
+fn duplicate_ids_are_found() {
+    let mut dawg = Dawg::with_wrapper(SameId);
+    dawg.try_add("ab").unwrap();
+    assert_eq!(dawg.assert_invariants(), Err(InvariantViolation::DuplicateId { node: 0 }));
+}
+
+#[test]
+fn violations_name_the_node() {
+    let violation = InvariantViolation::WrongCount { node: 1, stored: 2, actual: 3 };
+    assert_eq!(violation.to_string(), "node 1 counts 2 words but 3 can be formed from it");
+}
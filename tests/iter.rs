@@ -0,0 +1,111 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, words, xorshift};
+
+/// Up to 60 distinct words of 1 to 5 letters out of "abc", sorted
+fn draw(state: &mut u64) -> Vec<String> {
+    let words: BTreeSet<String> = (0..xorshift(state) % 60)
+        .map(|_| (0..1 + xorshift(state) % 5).map(|_| b"abc"[(xorshift(state) % 3) as usize] as char).collect())
+        .collect();
+    words.into_iter().collect()
+}
+
+#[test]
+fn reversed_words_come_in_descending_order() {
+    let words = words();
+    let dawg = build(&words);
+    assert!(dawg.words().rev().eq(words.iter().rev().cloned()));
+    assert!(dawg.words_with_prefix("ab").rev().eq(words.iter().filter(|word| word.starts_with("ab")).rev().cloned()));
+    assert_eq!(dawg.words_with_prefix("zzz").next_back(), None);
+}
+
+#[test]
+fn interleaved_ends_yield_every_word_once() {
+    let mut state = 99;
+    for round in 0..50 {
+        let words = draw(&mut state);
+        let mut dawg = dawging::UnsyncDawg::new();
+        dawg.add_all(&words).unwrap();
+        checked(&dawg);
+        if round % 2 == 0 {
+            dawg.finish();
+        }
+
+        let mut iter = dawg.words();
+        let (mut front, mut back) = (vec![], vec![]);
+        loop {
+            let word = match xorshift(&mut state) % 2 {
+                0 => iter.next().map(|word| front.push(word)),
+                _ => iter.next_back().map(|word| back.push(word)),
+            };
+            if word.is_none() {
+                break;
+            }
+        }
+        assert!(iter.next().is_none() && iter.next_back().is_none());
+
+        // the front yields ascend, the back ones descend, and they meet without overlapping
+        back.reverse();
+        front.extend(back);
+        assert_eq!(front, words);
+    }
+}
+
+#[test]
+fn the_empty_word_comes_first_from_the_front_and_last_from_the_back() {
+    let dawg = build(&["", "a", "b"]);
+    assert_eq!(dawg.words().rev().collect::<Vec<_>>(), ["b", "a", ""]);
+
+    let mut iter = dawg.words();
+    assert_eq!(iter.next_back().as_deref(), Some("b"));
+    assert_eq!(iter.next().as_deref(), Some(""));
+    assert_eq!(iter.next_back().as_deref(), Some("a"));
+    assert_eq!((iter.next(), iter.next_back()), (None, None));
+}
+
+#[test]
+fn sequences_run_backwards_too() {
+    let mut dawg = dawging::Dawg::<dawging::UnsyncWrapper, u16>::default();
+    for sequence in [vec![1, 2], vec![1, 3], vec![4]] {
+        dawg.add_sequence(&sequence).unwrap();
+    }
+    dawg.finish();
+    assert_eq!(dawg.sequences().rev().collect::<Vec<_>>(), [vec![4], vec![1, 3], vec![1, 2]]);
+    assert_eq!(dawg.sequences_with_prefix(&[1]).next_back(), Some(vec![1, 3]));
+}
+
+#[test]
+fn the_length_is_exact_from_both_ends() {
+    let mut state = 5;
+    for round in 0..40 {
+        let words = draw(&mut state);
+
+        let mut dawg = dawging::UnsyncDawg::new();
+        dawg.add_all(&words).unwrap();
+        let dawg = match round % 2 {
+            0 => build(&words),
+            _ => dawg,
+        };
+
+        for prefix in ["", "a", "ab", "c", "zz"] {
+            let mut iter = dawg.words_with_prefix(prefix);
+            let mut left = words.iter().filter(|word| word.starts_with(prefix)).count();
+            loop {
+                assert_eq!(iter.len(), left);
+                let word = match xorshift(&mut state) % 2 {
+                    0 => iter.next(),
+                    _ => iter.next_back(),
+                };
+                if word.is_none() {
+                    break;
+                }
+                left -= 1;
+            }
+            assert_eq!(iter.len(), 0);
+        }
+    }
+}
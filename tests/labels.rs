@@ -0,0 +1,103 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{Dawg, UnsyncWrapper};
+
+/// Phonemes, an enum standing for any token type that is `Ord + Hash + Clone`
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+enum Phoneme {
+    K,
+    Ae,
+    T,
+    S,
+}
+
+/// Builds a finished dawg of `sequences`, given in sorted order, checking the invariants after every one
+fn build_sequences<L: dawging::Label>(sequences: &[Vec<L>]) -> Dawg<UnsyncWrapper, L> {
+    let mut dawg = Dawg::<UnsyncWrapper, L>::default();
+    for sequence in sequences {
+        dawg.add_sequence(sequence).unwrap();
+        checked(&dawg);
+    }
+    dawg.finish();
+    dawg.assert_minimal().unwrap();
+    dawg
+}
+
+#[test]
+fn token_sequences_are_added_and_queried() {
+    let sequences = vec![vec![1u16, 2], vec![1, 2, 3], vec![1, 2, 3, 4], vec![5, 2, 3], vec![7]];
+    let dawg = build_sequences(&sequences);
+
+    assert!(dawg.contains_sequence(&[1, 2]));
+    assert!(!dawg.contains_sequence(&[1]));
+    assert!(!dawg.contains_sequence(&[]));
+    assert!(dawg.contains_prefix_sequence(&[1]));
+    assert!(dawg.contains_prefix_sequence(&[5, 2]));
+    assert!(!dawg.contains_prefix_sequence(&[2]));
+
+    assert_eq!(dawg.sequences().collect::<Vec<_>>(), sequences);
+    assert_eq!(dawg.sequences().rev().collect::<Vec<_>>(), sequences.iter().rev().cloned().collect::<Vec<_>>());
+    assert_eq!(dawg.sequences_with_prefix(&[1, 2, 3]).collect::<Vec<_>>(), [vec![1, 2, 3], vec![1, 2, 3, 4]]);
+    assert_eq!(dawg.sequences_with_prefix(&[9]).count(), 0);
+}
+
+#[test]
+fn shared_suffixes_of_tokens_are_merged() {
+    let sequences = vec![vec![1u16, 2, 3], vec![4, 2, 3], vec![5, 2, 3]];
+    let stats = build_sequences(&sequences).compression_stats();
+    // the three first tokens lead to the same node
+    assert_eq!((stats.dawg_nodes, stats.trie_nodes), (4, 10));
+}
+
+#[test]
+fn token_dawgs_round_trip() {
+    let sequences = vec![vec![1u16, 2], vec![1, 300], vec![65535]];
+    let dawg = build_sequences(&sequences);
+    let loaded = Dawg::<UnsyncWrapper, u16>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.sequences().collect::<Vec<_>>(), sequences);
+
+    let words = vec![vec![String::from("kick"), String::from("off")], vec![String::from("kick"), String::from("the"), String::from("bucket")]];
+    let dawg = build_sequences(&words);
+    let loaded = Dawg::<UnsyncWrapper, String>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    assert_eq!(loaded.sequences().collect::<Vec<_>>(), words);
+}
+
+#[test]
+fn any_ordered_token_can_label_the_edges() {
+    use Phoneme::*;
+    let sequences = vec![vec![K, Ae, T], vec![K, Ae, T, S], vec![T, Ae, K, S]];
+    let mut dawg = build_sequences(&sequences);
+
+    assert!(dawg.contains_sequence(&[K, Ae, T, S]));
+    assert_eq!(dawg.sequences_with_prefix(&[K]).count(), 2);
+    assert!(dawg.insert_sequence(&[S, Ae, T]));
+    checked(&dawg);
+    assert!(dawg.remove_sequence(&[K, Ae, T]));
+    checked(&dawg);
+    // in the order the variants are declared
+    assert_eq!(dawg.sequences().collect::<Vec<_>>(), [vec![K, Ae, T, S], vec![T, Ae, K, S], vec![S, Ae, T]]);
+}
+
+#[test]
+fn the_string_api_is_unchanged() {
+    let dawg = build(&["a", "ab", "héllo"]);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["a", "ab", "héllo"]);
+    assert_eq!(dawg.words_with_prefix("h").collect::<Vec<_>>(), ["héllo"]);
+    assert!(dawg.contains("héllo"));
+    assert_eq!(dawg.sequences_with_prefix(&['h']).next(), Some("héllo".chars().collect()));
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_takes_tokens_too() {
+    let mut dawg = dawging::SyncDawg::<String>::default();
+    dawg.add_sequence(&[String::from("kick"), String::from("the"), String::from("bucket")]).unwrap();
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.sequences().count(), 1);
+    assert!(dawg.contains_prefix_sequence(&[String::from("kick")]));
+}
@@ -0,0 +1,99 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::rc::Rc;
+
+use common::{build, checked};
+use dawging::{dawg::{compiled::CompiledDawg, embedded::CompiledView, lexicon::{FoldProfile, Lexicon, MatchMode}}, UnsyncDawg};
+
+const WORDS: [&str; 10] = ["Apple", "Cats", "ape", "apple", "apply", "bat", "bats", "cat", "éte", "été"];
+
+const MODES: [MatchMode; 4] = [
+    MatchMode::Exact,
+    MatchMode::AsciiInsensitive,
+    MatchMode::UnicodeInsensitive(FoldProfile::Default),
+    MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+];
+
+/// Every answer of `lexicon` to the same queries, to compare implementors through the trait alone
+fn answers(lexicon: &impl Lexicon) -> Vec<String> {
+    let mut answers = vec![lexicon.word_count().to_string()];
+    for query in ["apple", "APPLE", "ap", "b", "cats", "Cat", "", "z", "ETE", "ete", "bats!"] {
+        for mode in MODES {
+            let (word, prefix, completions) = (lexicon.contains(query, mode), lexicon.contains_prefix(query, mode), lexicon.completions(query, mode));
+            // the counts and limits of an implementor overriding them agree with its own completions
+            assert_eq!(lexicon.count_completions(query, mode), completions.len(), "{query} {mode:?}");
+            for limit in [0, 1, 2, usize::MAX] {
+                assert_eq!(lexicon.completions_limit(query, mode, limit), completions[..limit.min(completions.len())], "{query} {mode:?} {limit}");
+            }
+            answers.push(format!("{query} {mode:?} {word} {prefix} {completions:?}"));
+        }
+    }
+    answers
+}
+
+#[test]
+fn every_implementor_answers_the_same() {
+    let dawg = build(&WORDS);
+    let expected = answers(&dawg);
+
+    let mut unfinished = UnsyncDawg::new();
+    unfinished.add_all(WORDS).unwrap();
+    checked(&unfinished);
+    assert_eq!(answers(&unfinished), expected);
+
+    let compiled = dawg.compile();
+    assert_eq!(answers(&compiled), expected);
+    assert_eq!(answers(&dawg.compile_with(usize::MAX)), expected);
+    assert_eq!(answers(&CompiledDawg::from_bytes(&compiled.to_bytes()).unwrap()), expected);
+
+    let bytes = compiled.to_bytes();
+    assert_eq!(answers(&CompiledView::from_bytes(&bytes).unwrap()), expected);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_answers_the_same() {
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(answers(&dawg), answers(&build(&WORDS)));
+}
+
+#[test]
+fn pointers_forward_to_the_lexicon() {
+    let dawg = build(&WORDS);
+    let expected = answers(&dawg);
+
+    assert_eq!(answers(&&dawg), expected);
+    assert_eq!(answers(&Rc::new(dawg.compile())), expected);
+    let boxed: Box<dyn Lexicon> = Box::new(dawg.compile());
+    assert_eq!(answers(&boxed), expected);
+}
+
+#[test]
+fn the_answers_follow_the_match_mode() {
+    let dawg = build(&WORDS);
+
+    assert!(!Lexicon::contains(&dawg, "APPLE", MatchMode::Exact));
+    assert!(Lexicon::contains(&dawg, "APPLE", MatchMode::AsciiInsensitive));
+    assert!(!Lexicon::contains(&dawg, "ETE", MatchMode::UnicodeInsensitive(FoldProfile::Default)));
+    assert!(Lexicon::contains(&dawg, "ETE", MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents)));
+    assert_eq!(Lexicon::completions(&dawg, "CAT", MatchMode::AsciiInsensitive), ["Cats", "cat"]);
+    assert!(Lexicon::contains_prefix(&dawg, "", MatchMode::Exact));
+    assert!(!Lexicon::contains_prefix(&build::<&str>(&[]), "", MatchMode::Exact));
+}
+
+#[test]
+fn the_inherent_queries_wrap_the_lexicon() {
+    let dawg = build(&WORDS);
+    for query in ["apple", "Apple", "APPLE", "ap", "été", "x"] {
+        assert_eq!(dawg.contains(query), Lexicon::contains(&dawg, query, MatchMode::Exact));
+        assert_eq!(dawg.starts_with(query), Lexicon::contains_prefix(&dawg, query, MatchMode::Exact));
+        assert_eq!(dawg.contains_case_insensitive(query), Lexicon::contains(&dawg, query, MatchMode::UnicodeInsensitive(FoldProfile::Default)));
+        assert_eq!(dawg.is_word_chars(query.chars(), MatchMode::Exact), Lexicon::contains(&dawg, query, MatchMode::Exact));
+    }
+}
+
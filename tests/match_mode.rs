@@ -0,0 +1,127 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, build_with};
+use dawging::{dawg::{lexicon::{FoldProfile, Lexicon, MatchMode}, options::DawgOptions}, UnsyncDawg};
+
+/// Ascii, accented and case-variant words, sorted
+const WORDS: [&str; 7] = ["Apple", "apple", "café", "naïve", "straße", "Éclair", "ÉCLAIR"];
+
+const EXACT: MatchMode = MatchMode::Exact;
+const ASCII: MatchMode = MatchMode::AsciiInsensitive;
+const UNICODE: MatchMode = MatchMode::UnicodeInsensitive(FoldProfile::Default);
+const ACCENTS: MatchMode = MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents);
+
+fn fixture() -> UnsyncDawg {
+    let mut words = WORDS.to_vec();
+    words.sort();
+    build(&words)
+}
+
+#[test]
+fn exact_matches_the_letters_as_they_are() {
+    let dawg = fixture();
+    assert!(Lexicon::contains(&dawg, "Apple", EXACT) && !Lexicon::contains(&dawg, "APPLE", EXACT));
+    assert!(Lexicon::contains(&dawg, "café", EXACT) && !Lexicon::contains(&dawg, "cafe", EXACT));
+    assert_eq!(Lexicon::completions(&dawg, "É", EXACT), ["ÉCLAIR", "Éclair"]);
+}
+
+#[test]
+fn ascii_insensitive_folds_ascii_letters_only() {
+    let dawg = fixture();
+    assert!(Lexicon::contains(&dawg, "aPPLE", ASCII));
+    assert!(Lexicon::contains(&dawg, "CAFé", ASCII) && !Lexicon::contains(&dawg, "CAFÉ", ASCII));
+    assert!(!Lexicon::contains(&dawg, "éclair", ASCII));
+    assert_eq!(Lexicon::completions(&dawg, "APP", ASCII), ["Apple", "apple"]);
+}
+
+#[test]
+fn unicode_insensitive_folds_every_letter() {
+    let dawg = fixture();
+    assert!(Lexicon::contains(&dawg, "CAFÉ", UNICODE) && !Lexicon::contains(&dawg, "cafe", UNICODE));
+    assert!(Lexicon::contains(&dawg, "éclair", UNICODE));
+    assert!(Lexicon::contains(&dawg, "NAÏVE", UNICODE));
+    assert_eq!(Lexicon::completions(&dawg, "écl", UNICODE), ["ÉCLAIR", "Éclair"]);
+}
+
+#[test]
+fn ignoring_accents_strips_them_before_folding() {
+    let dawg = fixture();
+    assert!(Lexicon::contains(&dawg, "CAFE", ACCENTS));
+    assert!(Lexicon::contains(&dawg, "naive", ACCENTS));
+    assert!(Lexicon::contains(&dawg, "eclair", ACCENTS));
+    assert!(Lexicon::contains_prefix(&dawg, "STRA", ACCENTS));
+    assert_eq!(Lexicon::completions(&dawg, "e", ACCENTS), ["ÉCLAIR", "Éclair"]);
+}
+
+#[test]
+fn matches_compares_a_label_with_a_letter() {
+    assert!(EXACT.matches('a', 'a') && !EXACT.matches('a', 'A'));
+    assert!(ASCII.matches('a', 'A') && !ASCII.matches('é', 'É'));
+    assert!(UNICODE.matches('é', 'É') && !UNICODE.matches('é', 'e'));
+    assert!(ACCENTS.matches('é', 'E') && ACCENTS.matches('ï', 'i'));
+    assert_eq!(MatchMode::default(), EXACT);
+}
+
+#[test]
+fn the_flag_maps_to_a_mode() {
+    assert_eq!(MatchMode::from(true), EXACT);
+    assert_eq!(MatchMode::from(false), UNICODE);
+}
+
+#[test]
+fn the_options_store_a_default_mode() {
+    let dawg = build_with(&["café"], DawgOptions::new().match_mode(ACCENTS));
+    assert!(dawg.contains("CAFE") && dawg.starts_with("CA"));
+    assert_eq!(dawg.get("Cafe").unwrap().word(), "café");
+    // an explicit mode still wins
+    assert!(!Lexicon::contains(&dawg, "CAFE", EXACT));
+
+    let loaded = UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    assert_eq!(loaded.options().get_match_mode(), ACCENTS);
+    assert!(loaded.contains("cafe"));
+}
+
+#[test]
+#[allow(deprecated)]
+fn the_deprecated_flags_forward_to_the_modes() {
+    let dawg = fixture();
+    for word in ["APPLE", "apple", "éclair", "cafe", "x"] {
+        assert_eq!(dawg.is_word(word.to_string(), true).is_some(), Lexicon::contains(&dawg, word, EXACT));
+        assert_eq!(dawg.is_word(word.to_string(), false).is_some(), Lexicon::contains(&dawg, word, UNICODE));
+        assert_eq!(dawg.lookup(word.to_string(), false).is_some(), Lexicon::contains(&dawg, word, UNICODE));
+    }
+}
+
+#[test]
+fn char_iterators_answer_like_strings() {
+    let dawg = build(&["Abc", "a", "ab", "bé", "x"]);
+    for query in ["", "a", "A", "ab", "aB", "abc", "ABC", "be", "BÉ", "x", "xy", "zz"] {
+        for mode in [EXACT, ASCII, UNICODE, ACCENTS] {
+            assert_eq!(dawg.is_word_chars(query.chars(), mode), Lexicon::contains(&dawg, query, mode), "{query} {mode:?}");
+            assert_eq!(dawg.contains_prefix_chars(query.chars(), mode), Lexicon::contains_prefix(&dawg, query, mode), "{query} {mode:?}");
+        }
+    }
+}
+
+#[test]
+fn char_iterators_compose_with_a_pipeline() {
+    let dawg = fixture();
+    let folded = "ÇAFÉ".chars().map(|letter| if letter == 'Ç' { 'c' } else { letter.to_lowercase().next().unwrap() });
+    assert!(dawg.is_word_chars(folded, EXACT));
+    assert!(dawg.contains_prefix_chars("STRA".chars().map(|letter| letter.to_ascii_lowercase()), EXACT));
+    // a word long enough to be read lazily, stopping at the first letter that can't be followed
+    assert!(!dawg.is_word_chars("x".chars().chain(std::iter::repeat('a')), EXACT));
+}
+
+#[test]
+fn an_empty_iterator_is_the_empty_word() {
+    let dawg = fixture();
+    assert!(!dawg.is_word_chars(std::iter::empty(), EXACT));
+    assert!(dawg.contains_prefix_chars(std::iter::empty(), EXACT));
+
+    let with_empty = build(&["", "a"]);
+    assert!(with_empty.is_word_chars(std::iter::empty(), EXACT));
+    assert!(!UnsyncDawg::<char>::new().contains_prefix_chars(std::iter::empty(), EXACT));
+}
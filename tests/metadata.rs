@@ -0,0 +1,101 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::io::{self, Cursor, Read};
+
+use common::{build, build_with, checked, fixture};
+use dawging::{dawg::{metadata::{peek_metadata, read_metadata, Metadata}, options::DawgOptions}, file::FileWords, UnsyncDawg};
+
+/// Reader counting the bytes read through it
+struct Counting<R> {
+    inner: R,
+    read: usize,
+}
+
+impl<R: Read> Read for Counting<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        self.read += read;
+        Ok(read)
+    }
+}
+
+fn metadata() -> Metadata {
+    Metadata::new().name("test words").language("en-GB").entry("ünïcode key", "välue\nwith a newline").entry("", "")
+}
+
+#[test]
+fn metadata_is_saved_and_read_back() {
+    let words = common::words();
+    let dawg = build_with(&words, DawgOptions::new().metadata(metadata()));
+    assert_eq!(dawg.metadata(), &metadata());
+    assert_eq!((dawg.metadata().get_name(), dawg.metadata().get_language()), (Some("test words"), Some("en-GB")));
+    assert_eq!(dawg.metadata().iter().next(), Some(("", "")));
+
+    let bytes = dawg.to_bytes().unwrap();
+    let loaded = UnsyncDawg::<char>::from_bytes(&bytes).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.metadata(), &metadata());
+    assert_eq!(read_metadata(Cursor::new(&bytes)).unwrap(), metadata());
+    assert_eq!(FileWords::new(Cursor::new(&bytes)).unwrap().count(), words.len());
+}
+
+#[test]
+fn saving_with_metadata_adds_the_build_info() {
+    let words = common::words();
+    let dawg = build(&words);
+    let mut saved = vec![];
+    dawg.save_with_metadata(&mut saved, &Metadata::new().source_checksum("abc")).unwrap();
+
+    let metadata = read_metadata(Cursor::new(&saved)).unwrap();
+    assert_eq!(metadata.get_source_checksum(), Some("abc"));
+    assert_eq!(metadata.get_word_count(), Some(words.len()));
+    assert_eq!(metadata.get_builder_version(), Some(env!("CARGO_PKG_VERSION")));
+    // the dawg saved keeps its own metadata
+    assert!(dawg.metadata().is_empty());
+    assert!(UnsyncDawg::<char>::from_bytes(&saved).unwrap().words().eq(dawg.words()));
+}
+
+#[test]
+fn metadata_leaves_the_language_alone() {
+    let words = common::words();
+    let plain = build(&words);
+    let described = build_with(&words, DawgOptions::new().metadata(metadata()));
+
+    assert_eq!(plain.difference_words(&described).count() + described.difference_words(&plain).count(), 0);
+    assert!(plain.diff_with_sorted(described.words()).unwrap().is_empty());
+    assert_eq!(plain.visit_nodes().count(), described.visit_nodes().count());
+}
+
+#[test]
+fn only_the_header_is_read() {
+    let mut words = (0..60_000u32).map(|n| format!("{:x}{n}", n.wrapping_mul(2_654_435_761))).collect::<Vec<_>>();
+    words.sort();
+    let mut dawg = UnsyncDawg::with_options(DawgOptions::new().metadata(metadata())).unwrap();
+    dawg.add_all(&words).unwrap();
+    dawg.finish();
+    let bytes = dawg.to_bytes().unwrap();
+    assert!(bytes.len() > 1_000_000);
+
+    let mut reader = Counting { inner: Cursor::new(&bytes), read: 0 };
+    assert_eq!(read_metadata(&mut reader).unwrap(), metadata());
+    assert!(reader.read < 4096, "{} bytes read", reader.read);
+
+    let path = std::env::temp_dir().join(format!("dawging-metadata-{}.dawg", std::process::id()));
+    std::fs::write(&path, &bytes).unwrap();
+    assert_eq!(peek_metadata(&path).unwrap(), metadata());
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn files_without_metadata_have_none() {
+    let plain = build(&common::words()).to_bytes().unwrap();
+    assert!(read_metadata(Cursor::new(&plain)).unwrap().is_empty());
+    assert!(UnsyncDawg::<char>::from_bytes(&plain).unwrap().metadata().is_empty());
+    // saved before metadata was part of the format
+    assert!(peek_metadata(fixture("v1.dawg")).unwrap().is_empty());
+
+    assert!(read_metadata(Cursor::new(b"DAWG")).is_err());
+    assert!(peek_metadata(fixture("missing.dawg")).is_err());
+}
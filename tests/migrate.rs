@@ -0,0 +1,91 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build_with, checked, fixture};
+use dawging::{dawg::{compiled::CompiledDawg, integrity::verify, lexicon::{Lexicon, MatchMode}, migrate::{migrate, migrate_file}, options::DawgOptions}, DawgError, UnsyncDawg};
+
+/// The words of `fixtures/v1.dawg`, saved case folded by the first layout of the save format
+const V1_WORDS: [&str; 10] = ["able", "bake", "baker", "baking", "cake", "caked", "cakes", "make", "maker", "über"];
+
+fn v1() -> Vec<u8> {
+    std::fs::read(fixture("v1.dawg")).unwrap()
+}
+
+/// `bytes` with the version of their layout set to `version`
+fn with_version(bytes: &[u8], version: u16) -> Vec<u8> {
+    let mut bytes = bytes.to_vec();
+    bytes[4..6].copy_from_slice(&version.to_le_bytes());
+    bytes
+}
+
+#[test]
+fn a_v1_file_loads_and_answers_queries() {
+    let v1 = v1();
+    assert_eq!(u16::from_le_bytes([v1[4], v1[5]]), 1);
+
+    let dawg = UnsyncDawg::<char>::from_bytes(&v1).unwrap();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), V1_WORDS);
+    assert!(dawg.options().is_case_folding());
+    assert!(dawg.contains("BAKER"));
+    assert!(Lexicon::contains_prefix(&dawg, "bak", MatchMode::Exact));
+    assert_eq!(Lexicon::word_count(&dawg), 10);
+    assert_eq!(dawg.rank("make"), Some(7));
+    verify(&v1[..]).unwrap();
+}
+
+#[test]
+fn migrating_writes_what_a_fresh_build_saves() {
+    let migrated = migrate(&v1()).unwrap();
+    let fresh = build_with(&V1_WORDS, DawgOptions::new().case_folding(true));
+    assert_eq!(migrated, fresh.to_bytes().unwrap());
+    assert_eq!(migrated, UnsyncDawg::<char>::from_bytes(&v1()).unwrap().to_bytes().unwrap());
+    verify(&migrated[..]).unwrap();
+    assert_eq!(migrate(&migrated).unwrap(), migrated);
+
+    let compiled = fresh.compile().to_bytes();
+    assert_eq!(migrate(&compiled).unwrap(), compiled);
+}
+
+#[test]
+fn a_file_can_be_migrated_in_place() {
+    let dir = std::env::temp_dir().join(format!("dawging-migrate-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join("words.dawg");
+    std::fs::write(&path, v1()).unwrap();
+
+    migrate_file(&path, &path).unwrap();
+    assert_eq!(std::fs::read(&path).unwrap(), migrate(&v1()).unwrap());
+    assert!(matches!(migrate_file(dir.join("missing.dawg"), &path), Err(DawgError::Io(_))));
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn newer_versions_are_reported_as_such() {
+    let current = migrate(&v1()).unwrap();
+    let supported = u16::from_le_bytes([current[4], current[5]]);
+    let newer = with_version(&current, supported + 1);
+
+    let error = UnsyncDawg::<char>::from_bytes(&newer).unwrap_err();
+    assert!(matches!(error, DawgError::NewerVersion { version, supported: reads } if version == supported + 1 && reads == supported), "{error}");
+    assert!(error.to_string().contains("newer dawging"));
+    assert!(matches!(verify(&newer[..]), Err(DawgError::NewerVersion { .. })));
+    assert!(matches!(migrate(&newer), Err(DawgError::NewerVersion { .. })));
+    assert!(matches!(UnsyncDawg::<char>::from_bytes(&with_version(&current, 0)), Err(DawgError::InvalidFormat(_))));
+
+    let compiled = build_with(&V1_WORDS, DawgOptions::new()).compile().to_bytes();
+    let newer = with_version(&compiled, 9);
+    assert!(matches!(CompiledDawg::from_bytes(&newer), Err(DawgError::NewerVersion { version: 9, .. })));
+    assert!(matches!(verify(&newer[..]), Err(DawgError::NewerVersion { .. })));
+}
+
+#[test]
+fn a_cut_v1_file_is_refused() {
+    let v1 = v1();
+    for len in 0..v1.len() {
+        assert!(UnsyncDawg::<char>::from_bytes(&v1[..len]).is_err(), "cut at {len}");
+        assert!(migrate(&v1[..len]).is_err(), "cut at {len}");
+    }
+}
@@ -0,0 +1,115 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{collections::BTreeSet, rc::Rc};
+
+use common::{build, xorshift};
+use dawging::{dawg::{lexicon::{Lexicon, MatchMode}, multi::MultiDawg}, UnsyncDawg};
+
+/// Up to `count` distinct words of 1 to 5 letters out of "abcd", so that two draws share words
+fn draw(state: &mut u64, count: u64) -> BTreeSet<String> {
+    (0..count).map(|_| (0..1 + xorshift(state) % 5).map(|_| b"abcd"[(xorshift(state) % 4) as usize] as char).collect()).collect()
+}
+
+/// Builds a dawg of `words`
+fn dawg_of(words: &BTreeSet<String>) -> UnsyncDawg {
+    build(&words.iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn the_layers_answer_as_their_union() {
+    let mut state = 4;
+    let (a, b) = (draw(&mut state, 150), draw(&mut state, 150));
+    let (x, y) = (dawg_of(&a), dawg_of(&b));
+    let mut multi = MultiDawg::new();
+    multi.push_layer(&x);
+    multi.push_layer(Rc::new(y));
+    multi.push_layer(UnsyncDawg::new());
+
+    let union = a.union(&b).cloned().collect::<BTreeSet<_>>();
+    assert_eq!(multi.word_count(), union.len());
+    assert_eq!(multi.word_count_upper_bound(), a.len() + b.len());
+    assert!(union.iter().all(|word| multi.contains(word, MatchMode::Exact)));
+    assert!(!multi.contains("abcde", MatchMode::Exact));
+    assert!(!multi.contains("", MatchMode::Exact));
+
+    for prefix in ["", "a", "ab", "dd", "x"] {
+        let expected = union.iter().filter(|word| word.starts_with(prefix)).cloned().collect::<Vec<_>>();
+        assert_eq!(multi.completions(prefix, MatchMode::Exact), expected);
+        assert_eq!(multi.contains_prefix(prefix, MatchMode::Exact), !expected.is_empty());
+    }
+}
+
+#[test]
+fn a_word_in_several_layers_is_returned_once() {
+    let (x, y) = (build(&["apple", "apply"]), build(&["apple", "pear"]));
+    let mut multi = MultiDawg::new();
+    multi.push_layer(&x);
+    multi.push_layer(&y);
+    multi.push_layer(&x);
+
+    assert_eq!(multi.completions("", MatchMode::Exact), ["apple", "apply", "pear"]);
+    assert_eq!(multi.word_count(), 3);
+    assert_eq!(multi.word_count_upper_bound(), 6);
+}
+
+#[test]
+fn layers_come_and_go() {
+    let (base, user) = (build(&["apple", "pear"]), build(&["kiwi"]));
+    let mut multi = MultiDawg::new();
+    assert_eq!(multi.word_count(), 0);
+    assert_eq!(multi.push_layer(&base), 0);
+    assert!(!multi.contains("kiwi", MatchMode::Exact));
+
+    multi.insert_layer(0, &user);
+    assert_eq!(multi.layers(), 2);
+    assert!(multi.contains("kiwi", MatchMode::Exact));
+    assert_eq!(multi.completions("", MatchMode::Exact), ["apple", "kiwi", "pear"]);
+
+    assert!(multi.remove_layer(0).unwrap().contains("kiwi", MatchMode::Exact));
+    assert!(multi.remove_layer(9).is_none());
+    assert_eq!(multi.layers(), 1);
+    assert!(!multi.contains("kiwi", MatchMode::Exact));
+    assert_eq!(multi.word_count(), 2);
+}
+
+#[test]
+fn the_match_mode_goes_through_to_every_layer() {
+    let (x, y) = (build(&["Apple"]), build(&["apple", "pear"]));
+    let mut multi = MultiDawg::new();
+    multi.push_layer(&x);
+    multi.push_layer(&y);
+
+    assert!(multi.contains("PEAR", MatchMode::AsciiInsensitive));
+    assert!(!multi.contains("PEAR", MatchMode::Exact));
+    assert_eq!(multi.completions("APP", MatchMode::AsciiInsensitive), ["Apple", "apple"]);
+}
+
+#[test]
+fn a_multi_dawg_can_be_a_layer() {
+    let (x, y, z) = (build(&["a"]), build(&["b"]), build(&["c"]));
+    let mut inner = MultiDawg::new();
+    inner.push_layer(&x);
+    inner.push_layer(&y);
+    let mut outer = MultiDawg::new();
+    outer.push_layer(inner);
+    outer.push_layer(&z);
+    assert_eq!(outer.completions("", MatchMode::Exact), ["a", "b", "c"]);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn shared_sync_dawgs_can_be_layers() {
+    let mut dawg = dawging::SyncDawg::new_sync();
+    for word in ["b", "c"] {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.finish();
+    let x = build(&["a", "b"]);
+
+    let mut multi = MultiDawg::new();
+    multi.push_layer(&x);
+    multi.push_layer(std::sync::Arc::new(dawg));
+    assert_eq!(multi.completions("", MatchMode::Exact), ["a", "b", "c"]);
+}
@@ -0,0 +1,106 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, xorshift};
+use dawging::{Dawg, UnsyncDawg, UnsyncWrapper};
+
+/// The words of the fixture along with words of several scripts
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["aé字b", "é字", "ab"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// The words themselves, their prefixes, and prefixes followed by a letter that may or may not go on
+fn probes(words: &[String], state: &mut u64) -> Vec<String> {
+    let mut probes = ["", "\u{0}", "a", "zzzzzzzz", "\u{10FFFF}", "é", "é\u{10FFFF}", "字", "aé", "aé字", "aé字bz"].map(String::from).to_vec();
+    for word in words {
+        probes.push(word.clone());
+        let letters = word.chars().collect::<Vec<_>>();
+        let mut probe = letters[..xorshift(state) as usize % (letters.len() + 1)].iter().collect::<String>();
+        probes.push(probe.clone());
+        probe.push(['a', 'm', 'z', '{', 'é', '字'][xorshift(state) as usize % 6]);
+        probes.push(probe);
+    }
+    probes
+}
+
+/// Asserts the neighbours of every probe are those found in the sorted `words`
+fn assert_neighbours(dawg: &UnsyncDawg, words: &[String], probes: &[String]) {
+    for probe in probes {
+        let after = words.partition_point(|word| word <= probe);
+        let before = words.partition_point(|word| word < probe);
+        assert_eq!(dawg.next_word_after(probe).as_ref(), words.get(after), "after {probe:?}");
+        assert_eq!(dawg.prev_word_before(probe).as_ref(), before.checked_sub(1).map(|before| &words[before]), "before {probe:?}");
+    }
+}
+
+#[test]
+fn neighbours_are_those_of_the_sorted_list() {
+    let words = words();
+    let dawg = build(&words);
+    let probes = probes(&words, &mut 7);
+    assert!(probes.len() > 5000);
+    assert_neighbours(&dawg, &words, &probes);
+}
+
+#[test]
+fn neighbours_at_the_ends_of_the_list() {
+    let words = words();
+    let dawg = build(&words);
+    assert_eq!(dawg.next_word_after(""), words.first().cloned());
+    assert_eq!(dawg.prev_word_before(""), None);
+    assert_eq!(dawg.prev_word_before(&words[0]), None);
+    assert_eq!(dawg.next_word_after(words.last().unwrap()), None);
+    assert_eq!(dawg.prev_word_before("\u{10FFFF}"), words.last().cloned());
+
+    // the empty word comes before all others
+    let dawg = build(&["", "b"]);
+    assert_eq!((dawg.prev_word_before(""), dawg.next_word_after("")), (None, Some("b".into())));
+    assert_eq!((dawg.prev_word_before("a"), dawg.next_word_after("b")), (Some("".into()), None));
+
+    let empty = build::<&str>(&[]);
+    assert_eq!((empty.next_word_after(""), empty.prev_word_before("z")), (None, None));
+}
+
+#[test]
+fn neighbours_follow_edits() {
+    let words = words();
+    let mut dawg = build(&words);
+    let mut model = words.iter().cloned().collect::<BTreeSet<_>>();
+    let mut state = 31;
+
+    for _ in 0..300 {
+        let word = format!("{}{}", words[xorshift(&mut state) as usize % words.len()], ["", "s", "é"][xorshift(&mut state) as usize % 3]);
+        match model.contains(&word) {
+            true => assert!(dawg.remove(&word).unwrap()),
+            false => assert!(dawg.insert(&word).unwrap()),
+        }
+        if !model.remove(&word) {
+            model.insert(word);
+        }
+    }
+    checked(&dawg);
+    let words = model.into_iter().collect::<Vec<_>>();
+    let probes = probes(&words, &mut 5);
+    assert_neighbours(&dawg, &words, &probes[..probes.len() / 3]);
+}
+
+#[test]
+fn any_labels_have_neighbours() {
+    let mut dawg = Dawg::<UnsyncWrapper, u8>::default();
+    dawg.add_sequence(&[1, 2]).unwrap();
+    dawg.add_sequence(&[3]).unwrap();
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.next_sequence_after(&[1, 2]), Some(vec![3]));
+    assert_eq!(dawg.next_sequence_after(&[1]), Some(vec![1, 2]));
+    assert_eq!(dawg.prev_sequence_before(&[2]), Some(vec![1, 2]));
+    assert_eq!(dawg.prev_sequence_before(&[1, 2]), None);
+    assert_eq!(dawg.next_sequence_after(&[3, 0]), None);
+}
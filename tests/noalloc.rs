@@ -0,0 +1,162 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::ops::ControlFlow;
+
+use common::{alloc::{allocations, Counting}, build_with};
+use dawging::dawg::{compiled::CompiledDawg, embedded::CompiledView, lexicon::{Lexicon, MatchMode}, noalloc::Listing, options::DawgOptions};
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+/// The queries guaranteed not to allocate, on both the compiled dawg and its view
+trait NoAlloc: Lexicon {
+    fn list_into(&self, prefix: &str, out: &mut [u8], ends: &mut [usize]) -> Listing;
+    fn visit(&self, prefix: &str, path: &mut [u8], visit: &mut dyn FnMut(&str) -> ControlFlow<()>) -> Listing;
+}
+
+impl NoAlloc for CompiledDawg {
+    fn list_into(&self, prefix: &str, out: &mut [u8], ends: &mut [usize]) -> Listing {
+        self.completions_into(prefix, out, ends)
+    }
+
+    fn visit(&self, prefix: &str, path: &mut [u8], visit: &mut dyn FnMut(&str) -> ControlFlow<()>) -> Listing {
+        self.for_each_completion(prefix, path, visit)
+    }
+}
+
+impl NoAlloc for CompiledView<'_> {
+    fn list_into(&self, prefix: &str, out: &mut [u8], ends: &mut [usize]) -> Listing {
+        self.completions_into(prefix, out, ends)
+    }
+
+    fn visit(&self, prefix: &str, path: &mut [u8], visit: &mut dyn FnMut(&str) -> ControlFlow<()>) -> Listing {
+        self.for_each_completion(prefix, path, visit)
+    }
+}
+
+/// The words written to `out`, split at `ends`
+fn written<'a>(out: &'a [u8], ends: &[usize]) -> Vec<&'a str> {
+    let mut start = 0;
+    ends.iter().map(|&end| {
+        let word = std::str::from_utf8(&out[start..end]).unwrap();
+        start = end;
+        word
+    }).collect()
+}
+
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["aé字b", "aéé"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Asserts the queries over `dawg` give the words of `words` without allocating
+fn assert_no_allocations(dawg: &impl NoAlloc, words: &[String]) {
+    let (mut out, mut ends, mut path) = (vec![0; 1 << 16], vec![0; 8000], [0; 64]);
+    let probes = words.iter().step_by(7).map(String::as_str).chain(["", "zzzzq", "ab", "aé", "Ab"]);
+
+    for probe in probes {
+        let (found, count) = allocations(|| (dawg.contains(probe, MatchMode::Exact), dawg.contains_prefix(probe, MatchMode::Exact)));
+        assert_eq!(count, 0, "{probe:?}");
+        assert_eq!(found, (words.iter().any(|word| word == probe), words.iter().any(|word| word.starts_with(probe))));
+
+        if probe.chars().count() > 2 && !probe.starts_with('a') {
+            continue;
+        }
+        let expected = words.iter().filter(|word| word.starts_with(probe)).map(String::as_str).collect::<Vec<_>>();
+        let (listing, count) = allocations(|| dawg.list_into(probe, &mut out, &mut ends));
+        assert_eq!(count, 0, "{probe:?}");
+        assert_eq!(listing, Listing { written: expected.len(), total: expected.len() });
+        assert_eq!(written(&out, &ends[..listing.written]), expected);
+
+        // the visitor only pushes to a vector with room enough
+        let mut lengths = Vec::with_capacity(expected.len());
+        let (listing, count) = allocations(|| dawg.visit(probe, &mut path, &mut |word| {
+            lengths.push(word.len());
+            ControlFlow::Continue(())
+        }));
+        assert_eq!(count, 0, "{probe:?}");
+        assert_eq!(listing.written, expected.len());
+        assert_eq!(lengths, expected.iter().map(|word| word.len()).collect::<Vec<_>>());
+    }
+}
+
+/// Asserts listings into buffers too small keep whole words, in order, and tell how many were left out
+fn assert_truncated(dawg: &impl NoAlloc, words: &[String]) {
+    let expected = words.iter().filter(|word| word.starts_with('a')).map(String::as_str).collect::<Vec<_>>();
+    let mut out = vec![0; 1 << 16];
+
+    // `ends` full
+    let mut ends = [0; 5];
+    let listing = dawg.list_into("a", &mut out, &mut ends);
+    assert_eq!(listing, Listing { written: 5, total: expected.len() });
+    assert!(!listing.is_complete());
+    assert_eq!(written(&out, &ends), expected[..5]);
+
+    // `out` full, the words that no longer fit are skipped
+    let mut ends = vec![0; 8000];
+    for size in [0, 1, 3, 7, 20, 100, 1000] {
+        let mut out = vec![0; size];
+        let listing = dawg.list_into("a", &mut out, &mut ends);
+        assert_eq!(listing.total, expected.len());
+        assert_eq!(listing.is_complete(), expected.iter().map(|word| word.len()).sum::<usize>() <= size);
+        let listed = written(&out, &ends[..listing.written]);
+        let mut rest = expected.iter();
+        assert!(listed.iter().all(|word| rest.any(|other| other == word)), "{size}");
+
+        // words longer than `path` are skipped
+        let mut path = vec![0; size];
+        let mut visited = vec![];
+        let listing = dawg.visit("a", &mut path, &mut |word| {
+            visited.push(word.to_string());
+            ControlFlow::Continue(())
+        });
+        assert_eq!(visited, expected.iter().filter(|word| word.len() <= size).copied().collect::<Vec<_>>());
+        assert_eq!(listing.written, visited.len());
+    }
+
+    let mut visited = 0;
+    let listing = dawg.visit("", &mut [0; 64], &mut |_| {
+        visited += 1;
+        if visited == 3 { ControlFlow::Break(()) } else { ControlFlow::Continue(()) }
+    });
+    assert_eq!(listing.written, 3);
+}
+
+#[test]
+fn queries_over_compiled_dawgs_never_allocate() {
+    let words = words();
+    let dawg = build_with(&words, DawgOptions::new());
+    for fanout in [usize::MAX, 4, 16] {
+        let compiled = dawg.compile_with(fanout);
+        assert_no_allocations(&compiled, &words);
+        let bytes = compiled.to_bytes();
+        assert_no_allocations(&CompiledView::from_bytes(&bytes).unwrap(), &words);
+    }
+}
+
+#[test]
+fn small_buffers_truncate_listings() {
+    let words = words();
+    let compiled = build_with(&words, DawgOptions::new()).compile_with(4);
+    assert_truncated(&compiled, &words);
+    let bytes = compiled.to_bytes();
+    assert_truncated(&CompiledView::from_bytes(&bytes).unwrap(), &words);
+}
+
+#[test]
+fn listings_fold_the_prefix() {
+    let compiled = build_with(&["Apple", "apply", "Bat"], DawgOptions::new().case_folding(true)).compile();
+    let (mut out, mut ends) = ([0; 32], [0; 4]);
+    let (listing, count) = allocations(|| compiled.completions_into("APP", &mut out, &mut ends));
+    assert_eq!(count, 0);
+    assert_eq!(written(&out, &ends[..listing.written]), ["apple", "apply"]);
+
+    let empty = build_with::<&str>(&[], DawgOptions::new()).compile();
+    assert_eq!(empty.completions_into("", &mut out, &mut ends), Listing::default());
+    assert!(!empty.contains("", MatchMode::Exact));
+}
@@ -0,0 +1,124 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, words};
+use dawging::{dawg::{common::NodeRef, unsync::UnsyncDawg}, node::NodeHandle};
+
+const WORDS: [&str; 5] = ["bat", "bats", "cat", "cats", "do"];
+
+/// Spells every word below `node` after `prefix` through the accessors of the handles alone
+fn spell<N: NodeRef<Label = char>>(node: &NodeHandle<N>, prefix: String, words: &mut Vec<String>) {
+    if node.is_terminal() {
+        words.push(prefix.clone());
+    }
+    for label in node.edge_labels() {
+        spell(&node.child(&label).unwrap(), format!("{prefix}{label}"), words);
+    }
+}
+
+#[test]
+fn the_words_are_rebuilt_through_the_handles() {
+    let dawg = build(&words());
+    let mut spelled = vec![];
+    spell(&dawg.root(), String::new(), &mut spelled);
+    assert_eq!(spelled, dawg.words().collect::<Vec<_>>());
+}
+
+#[test]
+fn an_unfinished_dawg_is_walked_the_same() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.add_all(WORDS).unwrap();
+    checked(&dawg);
+
+    let mut spelled = vec![];
+    spell(&dawg.root(), String::new(), &mut spelled);
+    assert_eq!(spelled, WORDS);
+    assert_eq!(dawg.root().reachable_words(), 5);
+}
+
+#[test]
+fn the_accessors_describe_the_node() {
+    let dawg = build(&WORDS);
+    let root = dawg.root();
+    assert!(!root.is_terminal());
+    assert_eq!(root.edge_labels().collect::<String>(), "bcd");
+    assert_eq!(root.reachable_words(), 5);
+    assert!(root.child(&'x').is_none());
+
+    let bat = dawg.descend("bat").unwrap();
+    assert!(bat.is_terminal());
+    assert_eq!(bat.reachable_words(), 2);
+    assert_eq!(bat.edge_labels().collect::<Vec<_>>(), ['s']);
+    assert_eq!((bat.min_completion_depth(), bat.max_completion_depth()), (Some(0), Some(1)));
+
+    // both words end on the same node once minimized
+    assert_eq!(dawg.descend("bat").unwrap().id(), dawg.descend("cat").unwrap().id());
+    assert_eq!(dawg.descend("ba").unwrap().reachable_words(), 2);
+    assert!(dawg.descend("x").is_none());
+    assert_eq!(dawg.descend("").unwrap().id(), root.id());
+}
+
+#[test]
+fn descending_follows_the_child_accessors() {
+    let dawg = build(&WORDS);
+    let mut node = dawg.root();
+    for letter in "cats".chars() {
+        node = node.child(&letter).unwrap();
+    }
+    assert_eq!(node.id(), dawg.descend_sequence(&['c', 'a', 't', 's']).unwrap().id());
+    assert!(node.is_terminal());
+    assert_eq!(node.edge_labels().count(), 0);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_handles_have_the_same_accessors() {
+    let mut dawg = dawging::dawg::sync::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+    checked(&dawg);
+
+    let mut spelled = vec![];
+    spell(&dawg.root(), String::new(), &mut spelled);
+    assert_eq!(spelled, WORDS);
+    assert_eq!(dawg.descend("ca").unwrap().reachable_words(), 2);
+}
+
+#[test]
+fn a_handle_stays_valid_across_other_queries() {
+    let dawg = build(&WORDS);
+    let bat = dawg.get("bat").unwrap().into_node();
+
+    assert!(dawg.contains("bats"));
+    assert_eq!(dawg.words().count(), 5);
+    assert!(dawg.descend("do").is_some());
+
+    assert!(bat.is_terminal());
+    assert!(dawg.owns(&bat));
+    assert_eq!(dawg.suffixes(&bat).unwrap().collect::<Vec<_>>(), [vec![], vec!['s']]);
+}
+
+#[test]
+fn a_handle_is_rejected_by_another_dawg() {
+    let (dawg, other) = (build(&WORDS), build(&WORDS));
+    let bat = dawg.get("bat").unwrap().into_node();
+
+    assert!(!other.owns(&bat));
+    assert!(other.suffixes(&bat).is_none());
+    // even a copy of the dawg it came from
+    let copy = dawg.clone();
+    assert!(!copy.owns(&bat));
+    assert!(copy.suffixes(&bat).is_none());
+    assert!(dawg.owns(&dawg.root()) && !dawg.owns(&other.root()));
+}
+
+#[test]
+#[allow(deprecated)]
+fn the_deprecated_lookup_returns_a_handle() {
+    let dawg = build(&WORDS);
+    let bat = dawg.lookup(String::from("BAT"), false).unwrap();
+    assert!(dawg.owns(&bat));
+    assert_eq!(bat.id(), dawg.get("bat").unwrap().node().id());
+    assert!(dawg.lookup(String::from("ba"), true).is_none());
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::build;
+use dawging::dawg::page::Page;
+
+/// Prefixes with many, few and no completions
+const PREFIXES: [&str; 8] = ["", "a", "b", "ab", "st", "é", "zzzz", "qx"];
+
+/// Every completion of `prefix`, paged `limit` at a time, along with the number of pages
+fn page_through(dawg: &dawging::UnsyncDawg, prefix: &str, limit: usize) -> (Vec<String>, usize) {
+    let (mut words, mut pages, mut after) = (vec![], 0, None::<String>);
+    loop {
+        let page = dawg.completions_page(prefix, after.as_deref(), limit);
+        assert_eq!(page, dawg.completions_page(prefix, after.as_deref(), limit));
+        assert!(page.words.len() <= limit);
+        pages += 1;
+        words.extend(page.words.iter().cloned());
+        match page.next {
+            Some(next) => {
+                assert_eq!(Some(&next), page.words.last());
+                after = Some(next);
+            }
+            None => return (words, pages),
+        }
+    }
+}
+
+#[test]
+fn paging_through_a_prefix_gives_every_completion_once() {
+    let dawg = build(&common::words());
+    for prefix in PREFIXES {
+        let all = dawg.words_with_prefix(prefix).collect::<Vec<_>>();
+        for limit in [1, 7, 50, 5000] {
+            let (words, pages) = page_through(&dawg, prefix, limit);
+            assert_eq!(words, all, "{prefix:?} {limit} at a time");
+            assert_eq!(pages, all.len().div_ceil(limit).max(1), "{prefix:?} {limit} at a time");
+        }
+    }
+}
+
+#[test]
+fn pages_start_after_tokens_that_are_not_completions() {
+    let dawg = build(&common::words());
+    for prefix in PREFIXES {
+        let all = dawg.words_with_prefix(prefix).collect::<Vec<_>>();
+        for after in ["", "a", "aa", "ab", "abzz", "ac\u{0}", "b", "bq", "st", "stzz", "z", "zzzzzz", "é"] {
+            let page = dawg.completions_page(prefix, Some(after), usize::MAX);
+            let expected = all.iter().filter(|word| word.as_str() > after).cloned().collect::<Vec<_>>();
+            assert_eq!(page, Page { words: expected, next: None }, "{prefix:?} after {after:?}");
+        }
+    }
+}
+
+#[test]
+fn a_token_stays_valid_once_its_word_is_gone() {
+    let words = ["car", "card", "care", "cart", "cat", "dog"];
+    let before = build(&words);
+    let page = before.completions_page("ca", None, 2);
+    assert_eq!(page, Page { words: vec!["car".into(), "card".into()], next: Some("card".into()) });
+
+    let after = build(&["car", "care", "cart", "cat", "dog"]);
+    let page = after.completions_page("ca", page.next.as_deref(), 2);
+    assert_eq!(page, Page { words: vec!["care".into(), "cart".into()], next: Some("cart".into()) });
+    // the last page holds exactly the words left, so it has no token
+    assert_eq!(after.completions_page("ca", Some("cart"), 1), Page { words: vec!["cat".into()], next: None });
+}
+
+#[test]
+fn empty_pages() {
+    let dawg = build(&["car", "cat"]);
+    assert_eq!(dawg.completions_page("ca", None, 0), Page::default());
+    assert_eq!(dawg.completions_page("x", None, 10), Page::default());
+    assert_eq!(dawg.completions_page("ca", Some("cat"), 10), Page::default());
+    assert_eq!(dawg.completions_page("ca", Some("d"), 10), Page::default());
+    // a token before the prefix starts from its first completion
+    assert_eq!(dawg.completions_page("ca", Some("b"), 10).words, ["car", "cat"]);
+    assert_eq!(dawg.completions_page("car", Some("ca"), 10).words, ["car"]);
+}
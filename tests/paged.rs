@@ -0,0 +1,121 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{io::Cursor, sync::Arc};
+
+use common::build;
+use dawging::{dawg::{compiled::CompiledDawg, lexicon::{FoldProfile, Lexicon, MatchMode}, paged::{PagedDawgReader, PagedOptions}}, UnsyncDawg};
+
+const MODES: [MatchMode; 3] = [MatchMode::Exact, MatchMode::AsciiInsensitive, MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents)];
+
+/// Reader of `bytes` through pages of `page_size` bytes, `cache_pages` of them at most in memory
+fn reader(bytes: &[u8], page_size: usize, cache_pages: usize) -> PagedDawgReader<Cursor<Vec<u8>>> {
+    PagedDawgReader::new(Cursor::new(bytes.to_vec()), PagedOptions::new().page_size(page_size).cache_pages(cache_pages)).unwrap()
+}
+
+/// Checks that `paged` answers as `compiled` does on queries built from `words`
+fn assert_same_answers(compiled: &CompiledDawg, paged: &PagedDawgReader<Cursor<Vec<u8>>>, words: &[String]) {
+    assert_eq!(paged.word_count(), compiled.word_count());
+    assert_eq!(paged.node_count(), compiled.node_count());
+    assert_eq!(paged.words().unwrap(), compiled.words());
+
+    for word in words.iter().step_by(29) {
+        let prefix = word.chars().take(2).collect::<String>();
+        for mode in MODES {
+            assert_eq!(paged.contains(word, mode).unwrap(), compiled.contains(word, mode));
+            assert_eq!(paged.contains(&format!("{word}q"), mode).unwrap(), compiled.contains(&format!("{word}q"), mode));
+            assert_eq!(paged.contains_prefix(&prefix, mode).unwrap(), compiled.contains_prefix(&prefix, mode));
+            assert_eq!(paged.completions(&prefix, mode).unwrap(), compiled.completions(&prefix, mode));
+        }
+    }
+}
+
+#[test]
+fn pages_answer_as_the_loaded_dawg() {
+    let words = common::words();
+    let dawg = build(&words);
+
+    for compiled in [dawg.compile(), dawg.compile_with(usize::MAX)] {
+        let bytes = compiled.to_bytes();
+        for (page_size, cache_pages) in [(4096, 1024), (64, 8), (100, 1)] {
+            let paged = reader(&bytes, page_size, cache_pages);
+            assert_same_answers(&compiled, &paged, &words);
+
+            let stats = paged.stats();
+            assert!(stats.peak_resident <= cache_pages, "{stats:?}");
+            assert!(stats.resident <= cache_pages, "{stats:?}");
+            assert_eq!(stats.lookups, stats.hits + stats.reads, "{stats:?}");
+            if cache_pages < bytes.len() / page_size {
+                assert!(stats.evictions > 0, "{stats:?}");
+            }
+        }
+    }
+}
+
+#[test]
+fn a_single_tiny_page_is_enough() {
+    let words = common::words().into_iter().step_by(10).collect::<Vec<_>>();
+    let compiled = build(&words).compile();
+    let bytes = compiled.to_bytes();
+
+    for page_size in [1, 7] {
+        let paged = reader(&bytes, page_size, 1);
+        assert_same_answers(&compiled, &paged, &words);
+        assert_eq!(paged.stats().peak_resident, 1);
+    }
+}
+
+#[test]
+fn threads_share_the_cache() {
+    let words = common::words();
+    let dawg = build(&words);
+    let path = std::env::temp_dir().join(format!("dawging-paged-{}.dawc", std::process::id()));
+    dawg.compile().save(std::fs::File::create(&path).unwrap()).unwrap();
+
+    let paged = Arc::new(PagedDawgReader::open(&path, PagedOptions::new().page_size(256).cache_pages(4)).unwrap());
+    let threads = (0..4).map(|thread| {
+        let (paged, words) = (paged.clone(), words.clone());
+        std::thread::spawn(move || words.iter().skip(thread).step_by(4).all(|word| paged.contains(word, MatchMode::Exact).unwrap()))
+    }).collect::<Vec<_>>();
+
+    assert!(threads.into_iter().all(|thread| thread.join().unwrap()));
+    assert!(paged.stats().peak_resident <= 4);
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn broken_files_are_refused_or_fail_their_queries() {
+    let dawg = build(&common::words());
+    let bytes = dawg.compile().to_bytes();
+    let open = |bytes: &[u8]| PagedDawgReader::new(Cursor::new(bytes.to_vec()), PagedOptions::new());
+
+    assert!(open(&bytes[..bytes.len() - 1]).is_err());
+    assert!(open(&dawg.to_bytes().unwrap()).is_err());
+    assert!(open(&[]).is_err());
+    assert!((0..60).all(|len| open(&bytes[..len]).is_err()));
+
+    // every byte changed either fails to open or gives a reader that can be queried without panicking
+    let small = build(&["ab", "ac", "b", "bc"]).compile_with(2).to_bytes();
+    for index in 0..small.len() {
+        for value in [0, 1, 0xFF] {
+            let mut changed = small.clone();
+            changed[index] = value;
+            if let Ok(paged) = PagedDawgReader::new(Cursor::new(changed), PagedOptions::new().page_size(3).cache_pages(1)) {
+                let _ = paged.words();
+                let _ = paged.contains("ab", MatchMode::Exact);
+                let _ = paged.completions("a", MatchMode::AsciiInsensitive);
+            }
+        }
+    }
+}
+
+#[test]
+fn an_empty_dawg_can_be_paged() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.finish();
+    let paged = reader(&dawg.compile().to_bytes(), 4096, 1);
+    assert_eq!(paged.word_count(), 0);
+    assert!(paged.words().unwrap().is_empty());
+    assert!(!paged.contains("", MatchMode::Exact).unwrap());
+}
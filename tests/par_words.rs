@@ -0,0 +1,80 @@
+#![cfg(all(feature = "rayon", feature = "sync"))]
+
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use common::{checked, words};
+use dawging::SyncDawg;
+use rayon::iter::ParallelIterator;
+
+/// Builds a finished sync dawg of `words`, given in sorted order
+fn build_sync<S: AsRef<str>>(words: &[S]) -> SyncDawg {
+    let mut dawg = SyncDawg::new_sync();
+    for word in words {
+        dawg.try_add(word.as_ref()).unwrap();
+    }
+    dawg.finish();
+    checked(&dawg);
+    dawg
+}
+
+#[test]
+fn the_parallel_words_are_the_words() {
+    let dawg = build_sync(&words());
+    let mut words = dawg.par_words().collect::<Vec<_>>();
+    words.sort();
+    assert_eq!(words, dawg.words().collect::<Vec<_>>());
+}
+
+#[test]
+fn no_split_holds_most_of_the_words() {
+    let words = words();
+    let dawg = build_sync(&words);
+    let splits = dawg.par_words();
+    let sizes = splits.split_sizes();
+
+    assert!(sizes.len() > 1);
+    assert!(sizes.iter().all(|size| size * 10 < words.len() * 9), "{sizes:?}");
+    // the words ending where the dawg was split are yielded apart, so every word is counted once
+    assert!(sizes.iter().sum::<usize>() <= words.len());
+}
+
+#[test]
+fn the_prefixed_words_are_split_too() {
+    let dawg = build_sync(&words());
+    let mut lengths = dawg.par_words_with_prefix("ab").map(|word| word.len()).collect::<Vec<_>>();
+    lengths.sort();
+    let mut expected = dawg.words_with_prefix("ab").map(|word| word.len()).collect::<Vec<_>>();
+    expected.sort();
+    assert_eq!(lengths, expected);
+
+    assert_eq!(dawg.par_words_with_prefix("zzzzzz").count(), 0);
+    assert_eq!(build_sync::<&str>(&[]).par_words().count(), 0);
+}
+
+#[test]
+fn for_each_sees_every_word_once() {
+    let words = words();
+    let dawg = build_sync(&words);
+    let (seen, letters) = (AtomicUsize::new(0), AtomicUsize::new(0));
+    dawg.par_words().for_each(|word| {
+        seen.fetch_add(1, Ordering::Relaxed);
+        letters.fetch_add(word.chars().count(), Ordering::Relaxed);
+    });
+
+    assert_eq!(seen.into_inner(), words.len());
+    assert_eq!(letters.into_inner(), words.iter().map(|word| word.chars().count()).sum::<usize>());
+}
+
+#[test]
+fn the_words_compose_with_rayon_adapters() {
+    let words = words();
+    let dawg = build_sync(&words);
+    let letters = dawg.par_words().filter(|word| word.starts_with('a')).map(|word| word.chars().count()).sum::<usize>();
+    assert_eq!(letters, words.iter().filter(|word| word.starts_with('a')).map(|word| word.chars().count()).sum::<usize>());
+
+    // in a pool of its own, the work is split for its threads
+    let pool = rayon::ThreadPoolBuilder::new().num_threads(2).build().unwrap();
+    assert_eq!(pool.install(|| dawg.par_words().count()), words.len());
+}
@@ -0,0 +1,132 @@
+#![cfg(all(feature = "rayon", feature = "sync"))]
+
+mod common;
+
+use common::{checked, forge_chain, node_section, xorshift};
+use dawging::{dawg::integrity::{verify, Section}, DawgError, SyncDawg, SyncWrapper};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+
+/// A rayon pool of `threads` threads, for the parallel calls to run on
+fn pool(threads: usize) -> ThreadPool {
+    ThreadPoolBuilder::new().num_threads(threads).build().unwrap()
+}
+
+/// Builds a finished sync dawg of `words`, given in sorted order
+fn build_sync<S: AsRef<str>>(words: &[S]) -> SyncDawg {
+    let mut dawg = SyncDawg::new_sync();
+    for word in words {
+        dawg.try_add(word.as_ref()).unwrap();
+    }
+    dawg.finish();
+    checked(&dawg);
+    dawg
+}
+
+/// A saved dawg of more than 2MiB of nodes, so its node section has several chunks to check apart, along with its words
+fn large() -> (Vec<String>, Vec<u8>) {
+    let mut words = (0..40_000u32).map(|n| format!("{:x}{}", n.wrapping_mul(2_654_435_761), n % 977)).collect::<Vec<_>>();
+    words.extend(common::words());
+    words.sort();
+    words.dedup();
+
+    let mut dawg = SyncDawg::new_sync();
+    dawg.add_all(&words).unwrap();
+    dawg.finish();
+    let bytes = dawg.to_bytes().unwrap();
+    assert!(node_section(&bytes).1 * 17 > 2 << 20, "{} nodes", node_section(&bytes).1);
+    (words, bytes)
+}
+
+/// Every node as the walk of `visit_nodes` sees it
+fn nodes<T: dawging::dawg::common::Wrapper>(dawg: &dawging::Dawg<T>) -> Vec<(usize, bool, usize)> {
+    dawg.visit_nodes().map(|node| (node.id(), node.is_terminal(), node.count())).collect()
+}
+
+#[test]
+fn finish_parallel_gives_the_dawg_of_finish() {
+    // enough words for the lower heights to be split between the threads
+    let mut state = 17u64;
+    let mut words = (0..40_000)
+        .map(|_| {
+            let len = 1 + state % 10;
+            (0..len).map(|_| b"abcdefgh"[(xorshift(&mut state) % 8) as usize] as char).collect::<String>()
+        })
+        .collect::<Vec<_>>();
+    words.sort();
+    words.dedup();
+
+    for (threads, words) in [(4, &words[..]), (1, &words[..]), (3, &words[..10]), (2, &[][..])] {
+        let mut dawg = SyncDawg::new_sync();
+        dawg.add_all(words).unwrap();
+        pool(threads).install(|| dawg.finish_parallel());
+        checked(&dawg);
+        assert!(dawg.is_finished());
+        assert_eq!(dawg.to_bytes().unwrap(), build_sync(words).to_bytes().unwrap());
+        assert!(dawg.visit_nodes().eq(build_sync(words).visit_nodes()));
+    }
+}
+
+#[test]
+fn a_parallel_load_is_the_sequential_load() {
+    let (words, bytes) = large();
+    verify(&bytes[..]).unwrap();
+    let sequential = SyncDawg::<char>::from_bytes(&bytes).unwrap();
+    let expected = nodes(&sequential);
+
+    for threads in [1, 4] {
+        let parallel = pool(threads).install(|| SyncDawg::<char>::from_bytes_parallel(&bytes)).unwrap();
+        assert_eq!(nodes(&parallel), expected);
+        assert!(parallel.words().eq(sequential.words()));
+        for word in words.iter().step_by(997) {
+            assert!(parallel.contains(word) && !parallel.contains(&format!("{word}#")));
+        }
+    }
+
+    let parallel = pool(3).install(|| SyncDawg::<char>::load_parallel(&bytes[..])).unwrap();
+    checked(&parallel);
+    assert_eq!(parallel.to_bytes().unwrap(), bytes);
+    // the file still loads sequentially, into either kind of dawg
+    #[cfg(feature = "unsync")]
+    assert!(dawging::UnsyncDawg::<char>::load(&bytes[..]).unwrap().words().eq(sequential.words()));
+}
+
+#[test]
+fn a_damaged_chunk_fails_every_load() {
+    let (_, bytes) = large();
+    let (start, _) = node_section(&bytes);
+    let mut damaged = bytes.clone();
+    damaged[start + (1 << 20) + 12_345] ^= 1;
+
+    for threads in [1, 4] {
+        assert!(matches!(pool(threads).install(|| SyncDawg::<char>::from_bytes_parallel(&damaged)), Err(DawgError::Corrupted { section: Section::Nodes, .. })));
+    }
+    assert!(matches!(SyncDawg::<char>::from_bytes(&damaged), Err(DawgError::Corrupted { section: Section::Nodes, .. })));
+    assert!(matches!(verify(&damaged[..]), Err(DawgError::Corrupted { section: Section::Nodes, .. })));
+
+    let truncated = &bytes[..bytes.len() - 3];
+    assert!(matches!(SyncDawg::<char>::from_bytes_parallel(truncated), Err(DawgError::Truncated { .. })));
+    assert!(matches!(SyncDawg::<char>::load_parallel(truncated), Err(DawgError::Truncated { .. })));
+}
+
+#[test]
+fn more_prefixes_than_can_be_counted_fail_every_load() {
+    let forged = forge_chain::<SyncWrapper>(70);
+    for threads in [1, 4] {
+        assert!(matches!(pool(threads).install(|| SyncDawg::<char>::from_bytes_parallel(&forged)), Err(DawgError::InvalidFormat(_))));
+    }
+    assert!(matches!(SyncDawg::<char>::from_bytes(&forged), Err(DawgError::InvalidFormat(_))));
+}
+
+#[test]
+fn a_parallel_compile_is_the_sequential_compile() {
+    let (words, _) = large();
+    let dawg = build_sync(&words);
+    for min_fanout in [2, usize::MAX] {
+        let expected = dawg.compile_with(min_fanout);
+        for threads in [1, 4] {
+            let compiled = pool(threads).install(|| dawg.compile_parallel(min_fanout));
+            assert_eq!(compiled, expected);
+        }
+    }
+    assert_eq!(build_sync::<&str>(&[]).compile_parallel(2), build_sync::<&str>(&[]).compile_with(2));
+}
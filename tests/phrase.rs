@@ -0,0 +1,93 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::checked;
+use dawging::{dawg::unsync::UnsyncPhraseDawg, DawgError};
+
+/// Builds a finished phrase dawg of `phrases` in any order, checking the invariants after every phrase
+fn build(mut phrases: Vec<&str>) -> UnsyncPhraseDawg {
+    let mut dawg = UnsyncPhraseDawg::default();
+    dawg.sort(&mut phrases);
+    for phrase in phrases {
+        dawg.try_add(phrase).unwrap();
+        checked(dawg.dawg());
+    }
+    dawg.finish();
+    dawg.dawg().assert_minimal().unwrap();
+    dawg
+}
+
+fn cities() -> UnsyncPhraseDawg {
+    build(vec!["new york city", "new york", "kick the bucket", "new  jersey", "old york city", "big york city"])
+}
+
+#[test]
+fn phrases_are_looked_up_by_token() {
+    let dawg = cities();
+    assert!(dawg.contains("new york"));
+    assert!(dawg.contains_tokens(&["new", "jersey"]));
+    // empty tokens are skipped, so the double space doesn't count
+    assert!(dawg.contains("new jersey") && dawg.contains(" new   jersey "));
+    assert!(!dawg.contains("new"));
+    assert!(!dawg.contains("new yorkcity"));
+    assert_eq!(dawg.phrases().count(), 6);
+}
+
+#[test]
+fn prefixes_are_made_of_whole_tokens() {
+    let dawg = cities();
+    assert!(dawg.starts_with("new") && dawg.starts_with_tokens(&["kick", "the"]));
+    assert!(!dawg.starts_with("ne"));
+    assert_eq!(dawg.completions("new york"), ["new york", "new york city"]);
+    assert_eq!(dawg.completions_tokens(&["new"]), ["new jersey", "new york", "new york city"]);
+    assert!(dawg.completions("york").is_empty());
+}
+
+#[test]
+fn shared_runs_of_tokens_are_merged() {
+    let dawg = cities();
+    let node = |tokens: &[&str]| dawg.dawg().descend_sequence(&tokens.iter().map(|token| token.to_string()).collect::<Vec<_>>()).unwrap().id();
+    assert_eq!(node(&["big", "york"]), node(&["old", "york"]));
+    assert_eq!(node(&["big", "york", "city"]), node(&["kick", "the", "bucket"]));
+
+    let stats = dawg.dawg().compression_stats();
+    assert!(stats.dawg_nodes < stats.trie_nodes, "{stats:?}");
+}
+
+#[test]
+fn phrases_sort_by_their_tokens() {
+    let dawg = UnsyncPhraseDawg::default();
+    let mut phrases = vec!["a\tb", "a b", "a c"];
+    dawg.sort(&mut phrases);
+    // "a\tb" sorts first as a string, but ["a", "b"] comes before ["a\tb"]
+    assert_eq!(phrases, ["a b", "a c", "a\tb"]);
+
+    let mut dawg = UnsyncPhraseDawg::default();
+    dawg.try_add("a c").unwrap();
+    assert!(matches!(dawg.try_add("a b"), Err(DawgError::Unsorted { .. })));
+}
+
+#[test]
+fn the_delimiter_is_configurable() {
+    let mut dawg = UnsyncPhraseDawg::with_delimiter("/");
+    assert_eq!(dawg.delimiter(), "/");
+    assert_eq!(dawg.tokens("usr//local/bin"), ["usr", "local", "bin"]);
+
+    dawg.try_add("usr/bin").unwrap();
+    dawg.try_add("usr/local/bin").unwrap();
+    dawg.finish();
+    assert!(dawg.contains("usr//bin"));
+    assert!(!dawg.contains("usr bin"));
+    assert_eq!(dawg.completions("usr"), ["usr/bin", "usr/local/bin"]);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_phrase_dawg_splits_the_same() {
+    let mut dawg = dawging::dawg::sync::SyncPhraseDawg::with_delimiter("/");
+    dawg.add("a/b");
+    dawg.finish();
+    checked(dawg.dawg());
+    assert!(dawg.contains("a//b"));
+}
@@ -0,0 +1,178 @@
+mod common;
+
+use common::{build_in, wrapper_tests};
+use dawging::{dawg::{lexicon::{FoldProfile, Lexicon, MatchMode}, options::DawgOptions}, Dawg, Wrapper};
+
+const WORDS: [&str; 4] = ["Bat", "bats", "cat", "été"];
+
+wrapper_tests!(
+    the_queries_read_as_std_collections,
+    get_with_spells_the_word_as_it_is_in_the_dawg,
+    the_queries_follow_the_match_mode_of_the_options,
+    keys_at_gives_the_letters_after_a_prefix,
+    keys_at_merges_the_letters_that_fold_together,
+);
+
+const QUERIES: [&str; 9] = ["Bat", "bat", "BAT", "bats", "ba", "ÉTÉ", "été", "", "x"];
+
+fn the_queries_read_as_std_collections<T: Wrapper>() {
+    let dawg = build_in::<T, _>(&WORDS, DawgOptions::new());
+
+    assert!(dawg.contains("Bat") && !dawg.contains("bat"));
+    assert!(dawg.contains_case_insensitive("bat"));
+    assert!(dawg.contains_case_insensitive("ÉTÉ"));
+    assert!(dawg.starts_with("ba") && dawg.starts_with("") && !dawg.starts_with("bx"));
+
+    let found = dawg.get("bats").unwrap();
+    assert_eq!(found.word(), "bats");
+    assert!(found.node().is_terminal());
+    assert!(dawg.get("ba").is_none());
+}
+
+fn get_with_spells_the_word_as_it_is_in_the_dawg<T: Wrapper>() {
+    let dawg = build_in::<T, _>(&WORDS, DawgOptions::new());
+    assert_eq!(dawg.get_with("bAT", MatchMode::AsciiInsensitive).unwrap().word(), "Bat");
+    assert_eq!(dawg.get_with("ÉTÉ", MatchMode::from(false)).unwrap().word(), "été");
+    assert!(dawg.get_with("bat", MatchMode::Exact).is_none());
+}
+
+fn the_queries_follow_the_match_mode_of_the_options<T: Wrapper>() {
+    let dawg = build_in::<T, _>(&WORDS, DawgOptions::new().match_mode(MatchMode::AsciiInsensitive));
+    assert!(dawg.contains("BAT") && dawg.starts_with("CA"));
+    assert_eq!(dawg.get("BATS").unwrap().word(), "bats");
+    assert!(!dawg.contains("ÉTÉ"));
+}
+
+/// The words of the fixture along with case and accent variants, so that letters fold together
+fn mixed_words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["Apple", "Abc", "ÉTÉ", "Été", "été", "ab", "AB"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Prefixes of every 29th word, whole words included, along with a few that no word starts with
+fn prefixes(words: &[String]) -> Vec<String> {
+    let mut prefixes = vec![String::new()];
+    for word in words.iter().step_by(29) {
+        prefixes.extend(word.char_indices().map(|(end, _)| word[..end].to_string()));
+        prefixes.push(word.clone());
+    }
+    prefixes.extend(["zzzz", "é", "É", "A", "ét", "ÉT"].map(String::from));
+    prefixes
+}
+
+/// What `keys_at` should find after `prefix`, derived from the completions of the prefix: whether it is a word, and the
+/// letters after it with their counts, letters matching each other under `mode` merged under the smallest
+fn next_letters<T: Wrapper>(dawg: &Dawg<T>, prefix: &str, mode: MatchMode) -> Option<(bool, Vec<(char, usize)>)> {
+    let completions = Lexicon::completions(dawg, prefix, mode);
+    if completions.is_empty() {
+        return None;
+    }
+
+    let length = prefix.chars().count();
+    let mut letters: Vec<(char, usize)> = vec![];
+    for word in &completions {
+        let Some(next) = word.chars().nth(length) else { continue };
+        match letters.iter_mut().find(|(letter, _)| mode.matches(*letter, next)) {
+            Some((letter, count)) => {
+                *letter = next.min(*letter);
+                *count += 1;
+            }
+            None => letters.push((next, 1)),
+        }
+    }
+    letters.sort();
+    Some((completions.iter().any(|word| word.chars().count() == length), letters))
+}
+
+fn keys_at_gives_the_letters_after_a_prefix<T: Wrapper>() {
+    let words = mixed_words();
+    let dawg = build_in::<T, _>(&words, DawgOptions::new());
+
+    for prefix in prefixes(&words) {
+        let next = dawg.keys_at(&prefix, MatchMode::Exact);
+        let completions = dawg.words_with_prefix(&prefix).collect::<Vec<_>>();
+        let Some(next) = next else {
+            assert!(completions.is_empty(), "{prefix}");
+            continue;
+        };
+
+        let mut letters = std::collections::BTreeMap::new();
+        for word in &completions {
+            if let Some(letter) = word[prefix.len()..].chars().next() {
+                *letters.entry(letter).or_insert(0) += 1;
+            }
+        }
+        assert_eq!(next.counts(), letters.into_iter().collect::<Vec<_>>(), "{prefix}");
+        assert_eq!(next.letters().collect::<Vec<_>>(), next.counts().iter().map(|(letter, _)| *letter).collect::<Vec<_>>());
+        assert_eq!(next.is_word(), completions.contains(&prefix));
+        assert_eq!(next.word_count(), completions.len());
+    }
+
+    let root = dawg.keys_at("", MatchMode::Exact).unwrap();
+    assert_eq!(root.word_count(), words.len());
+    assert!(root.count('A') > 0 && root.count('a') > 0 && root.count('!') == 0);
+    assert!(dawg.keys_at("qqqqqq", MatchMode::Exact).is_none());
+}
+
+fn keys_at_merges_the_letters_that_fold_together<T: Wrapper>() {
+    let words = mixed_words();
+    let dawg = build_in::<T, _>(&words, DawgOptions::new());
+
+    for mode in [MatchMode::AsciiInsensitive, MatchMode::UnicodeInsensitive(FoldProfile::Default), MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents)] {
+        for prefix in prefixes(&words) {
+            let next = dawg.keys_at(&prefix, mode);
+            let expected = next_letters(&dawg, &prefix, mode);
+            assert_eq!(next.as_ref().map(|next| (next.is_word(), next.counts().to_vec())), expected, "{prefix} under {mode:?}");
+            if let Some(next) = next {
+                assert_eq!(next.word_count(), Lexicon::completions(&dawg, &prefix, mode).len());
+            }
+        }
+    }
+
+    let (exact, folded) = (dawg.keys_at("", MatchMode::Exact).unwrap(), dawg.keys_at("", MatchMode::AsciiInsensitive).unwrap());
+    assert_eq!(folded.count('A'), exact.count('A') + exact.count('a'));
+    assert_eq!(folded.count('a'), 0);
+    assert_eq!(folded.word_count(), exact.word_count());
+
+    // "ÉTÉ", "Été" and "été" all go on with a letter folding to 'é'
+    let accents = dawg.keys_at("ÉT", MatchMode::UnicodeInsensitive(FoldProfile::Default)).unwrap();
+    assert_eq!(accents.count('É'), 3);
+    assert!(!accents.is_word());
+}
+
+#[test]
+#[cfg(feature = "unsync")]
+#[allow(deprecated)]
+fn the_deprecated_names_forward_to_the_new_ones() {
+    let dawg = common::build(&WORDS);
+    for query in QUERIES {
+        let word = Some(query.to_string());
+        assert_eq!(dawg.is_word(query.to_string(), true), word.clone().filter(|_| dawg.contains(query)));
+        assert_eq!(dawg.is_word(query.to_string(), false), word.filter(|_| dawg.contains_case_insensitive(query)));
+
+        let sensitive = dawg.lookup(query.to_string(), true);
+        assert_eq!(sensitive.map(|node| node.id()), dawg.get(query).map(|found| found.node().id()));
+        let insensitive = dawg.lookup(query.to_string(), false);
+        assert_eq!(insensitive.map(|node| node.id()), dawg.get_with(query, MatchMode::from(false)).map(|found| found.node().id()));
+    }
+}
+
+#[test]
+#[cfg(feature = "sync")]
+#[allow(deprecated)]
+fn the_deprecated_sync_names_forward_to_the_new_ones() {
+    let mut dawg = dawging::dawg::sync::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+    common::checked(&dawg);
+
+    for query in QUERIES {
+        assert_eq!(dawg.is_word_sync(query.to_string(), true).is_some(), dawg.contains(query));
+        assert_eq!(dawg.is_word_sync(query.to_string(), false).is_some(), dawg.contains_case_insensitive(query));
+        assert_eq!(dawg.lookup_sync(query.to_string(), true).map(|node| node.id()), dawg.get(query).map(|found| found.node().id()));
+    }
+    assert_eq!(dawg.is_word_sync(String::from("BATS"), false), Some(String::from("BATS")));
+}
@@ -0,0 +1,176 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs, path::PathBuf};
+
+use dawging::{Dawg, DawgError, UnsyncDawg, UnsyncWrapper};
+
+/// A directory of its own under the system's temporary one, for the spilled entries of a test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dawging-register-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+#[test]
+fn a_tiny_budget_builds_the_same_dawg() {
+    let dir = temp_dir("tiny");
+    let words = common::words();
+    let expected = common::build(&words).to_bytes().unwrap();
+
+    for budget in [1, 2, 8, 64] {
+        let mut dawg = UnsyncDawg::<char>::new().with_register_budget(budget, &dir).unwrap();
+        for (index, word) in words.iter().enumerate() {
+            dawg.try_add(word).unwrap();
+            if index % 100 == 0 {
+                common::checked(&dawg);
+            }
+        }
+        let stats = dawg.register_stats();
+        dawg.finish();
+        common::checked(&dawg);
+        dawg.assert_minimal().unwrap();
+
+        assert_eq!(dawg.to_bytes().unwrap(), expected, "budget {budget}");
+        assert_eq!(stats.io_errors, 0);
+        assert!(stats.peak_cached <= budget, "{stats:?}");
+        assert!(stats.evictions > 0 && stats.disk_hits > 0, "{stats:?}");
+        assert_eq!(stats.lookups, stats.cache_hits + stats.disk_hits + stats.misses, "{stats:?}");
+    }
+
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_spill_that_cant_be_written_fails_the_build() {
+    let dir = temp_dir("unwritable");
+    let words = common::words();
+    let mut dawg = UnsyncDawg::<char>::new().with_register_budget(1, &dir).unwrap();
+    // the tests may run as root, which writes through any permission, so the spill directory is taken away instead
+    fs::remove_dir_all(&dir).unwrap();
+
+    let added = words.iter().try_for_each(|word| dawg.try_add(word));
+    assert!(matches!(added, Err(DawgError::Io(_))), "{added:?}");
+    assert!(matches!(dawg.try_finish(), Err(DawgError::Io(_))));
+    assert!(dawg.register_stats().io_errors > 0);
+}
+
+#[test]
+fn without_a_budget_nothing_goes_to_disk() {
+    let words = common::words();
+    let mut dawg = UnsyncDawg::<char>::new();
+    for word in &words {
+        dawg.try_add(word).unwrap();
+    }
+    let stats = dawg.register_stats();
+
+    assert_eq!((stats.evictions, stats.disk_hits, stats.io_errors), (0, 0, 0));
+    assert_eq!(stats.lookups, stats.cache_hits + stats.misses);
+}
+
+#[test]
+fn retain_keeps_the_budget() {
+    let dir = temp_dir("retain");
+    let words = common::words();
+    let kept = words.iter().filter(|word| !word.contains('e')).cloned().collect::<Vec<_>>();
+
+    let mut dawg = UnsyncDawg::<char>::new().with_register_budget(4, &dir).unwrap();
+    dawg.add_all(&words).unwrap();
+    dawg.finish();
+    let before = dawg.register_stats();
+
+    dawg.retain(|word| !word.contains('e'));
+    common::checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.to_bytes().unwrap(), common::build(&kept).to_bytes().unwrap());
+
+    // the rebuild went through the same register, spilling past the same budget
+    let stats = dawg.register_stats();
+    assert_eq!(stats.io_errors, 0);
+    assert!(stats.peak_cached <= 4, "{stats:?}");
+    assert!(stats.evictions > before.evictions && stats.disk_hits > before.disk_hits, "{stats:?}");
+
+    drop(dawg);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn a_clone_keeps_its_own_spilled_entries() {
+    let dir = temp_dir("clone");
+    let words = common::words();
+    let expected = common::build(&words).to_bytes().unwrap();
+    let half = words.len() / 2;
+
+    let mut dawg = UnsyncDawg::<char>::new().with_register_budget(3, &dir).unwrap();
+    for word in &words[..half] {
+        dawg.try_add(word).unwrap();
+    }
+    let mut clone = dawg.clone();
+    for dawg in [&mut dawg, &mut clone] {
+        for word in &words[half..] {
+            dawg.try_add(word).unwrap();
+        }
+        dawg.finish();
+        common::checked(dawg);
+        assert_eq!(dawg.to_bytes().unwrap(), expected);
+    }
+
+    drop((dawg, clone));
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn a_rollback_with_a_budget_builds_the_same_dawg() {
+    let dir = temp_dir("rollback");
+    let words = common::words();
+    let expected = common::build(&words).to_bytes().unwrap();
+
+    let mut dawg = dawging::SyncDawg::new_sync().with_register_budget(4, &dir).unwrap();
+    let checkpoint = dawg.checkpoint();
+    for word in &words[..words.len() / 3] {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.rollback(checkpoint).unwrap();
+    common::checked(&dawg);
+    for word in &words {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.finish();
+    common::checked(&dawg);
+
+    assert_eq!(dawg.to_bytes().unwrap(), expected);
+    drop(dawg);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sequences_of_other_labels_spill_too() {
+    let dir = temp_dir("labels");
+    let words = common::words();
+    let mut dawg = Dawg::<UnsyncWrapper, u32>::default().with_register_budget(2, &dir).unwrap();
+    for word in &words {
+        dawg.add_sequence(&word.chars().map(|letter| letter as u32).collect::<Vec<_>>()).unwrap();
+    }
+    dawg.finish();
+    common::checked(&dawg);
+
+    assert!(dawg.register_stats().disk_hits > 0);
+    assert_eq!(dawg.sequences().count(), words.len());
+    dawg.assert_minimal().unwrap();
+    drop(dawg);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn the_budget_must_be_set_before_adding_words() {
+    let dir = temp_dir("late");
+    let mut dawg = UnsyncDawg::<char>::new();
+    dawg.try_add("a").unwrap();
+
+    assert!(dawg.with_register_budget(3, &dir).is_err());
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::{lexicon::{FoldProfile, Lexicon, MatchMode}, options::{DawgOptions, ValidationPolicy}}, DawgError, UnsyncDawg};
+
+#[test]
+fn the_empty_word_marks_the_root_terminal() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("").unwrap();
+    checked(&dawg);
+    dawg.try_add("ab").unwrap();
+    checked(&dawg);
+    assert!(dawg.contains(""));
+    assert!(dawg.root().is_terminal());
+
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["", "ab"]);
+    assert_eq!(dawg.rank(""), Some(0));
+    assert_eq!(dawg.select(0).as_deref(), Some(""));
+}
+
+#[test]
+fn an_unfinished_dawg_of_the_empty_word_counts_it() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("").unwrap();
+    assert_eq!(Lexicon::word_count(&dawg), 1);
+    assert_eq!(Lexicon::completions(&dawg, "", MatchMode::Exact), [""]);
+    assert!(!Lexicon::contains(&dawg, "a", MatchMode::Exact));
+}
+
+#[test]
+fn unsorted_and_repeated_words_are_added_once() {
+    let mut dawg = UnsyncDawg::new();
+    assert_eq!(dawg.add_all(["b", "a", "b", "b", ""]).unwrap(), 3);
+    checked(&dawg);
+    dawg.finish();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["", "a", "b"]);
+}
+
+#[test]
+fn skipped_words_are_not_counted_as_added() {
+    let options = DawgOptions::new().alphabet("abc").validation_policy(ValidationPolicy::Skip);
+    let mut dawg = UnsyncDawg::with_options(options).unwrap();
+    assert_eq!(dawg.add_all(["ab", "ax", "bx", "ca"]).unwrap(), 2);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["ab", "ca"]);
+}
+
+#[test]
+fn an_unsorted_sequence_is_an_error() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.add_sequence(&['b']).unwrap();
+    let err = dawg.add_sequence(&['a']).unwrap_err();
+    assert!(matches!(&err, DawgError::Unsorted { order: "labels", .. }), "{err:?}");
+    assert_eq!(err.to_string(), "Please ensure all words are sorted (labels order) before adding (\"sequence 2\" was added after \"sequence 1\")");
+
+    // the dawg is left as it was
+    checked(&dawg);
+    dawg.finish();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["b"]);
+}
+
+#[test]
+fn long_words_differing_by_their_last_letter() {
+    let (long, longer) = ("a".repeat(300), format!("{}b", "a".repeat(300)));
+    let dawg = build(&[long.as_str(), longer.as_str()]);
+    assert!(dawg.contains(&long));
+    assert!(dawg.starts_with(&"a".repeat(150)));
+    assert_eq!(dawg.words().rev().collect::<Vec<_>>(), [longer.clone(), long.clone()]);
+
+    let loaded = UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    checked(&loaded);
+    assert!(loaded.words().eq([long, longer]));
+}
+
+#[test]
+fn non_ascii_queries_are_read_by_letter() {
+    let dawg = build(&["Étude", "ß", "é"]);
+    let accents = MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents);
+    assert!(Lexicon::contains(&dawg, "E", accents));
+    assert!(Lexicon::contains_prefix(&dawg, "étu", accents));
+    assert!(!Lexicon::contains(&dawg, "É", MatchMode::AsciiInsensitive));
+    assert_eq!(Lexicon::completions(&dawg, "É", MatchMode::UnicodeInsensitive(FoldProfile::Default)), ["Étude", "é"]);
+    assert_eq!(dawg.rank("é"), Some(2));
+}
+
+/// Replays every input of the fuzz corpus with the checks of the fuzz target
+#[test]
+#[cfg(feature = "testutil")]
+fn the_fuzz_corpus_replays_cleanly() {
+    use dawging::naive::{self, check_equivalence, Query};
+
+    const MODES: [MatchMode; 4] = [
+        MatchMode::Exact,
+        MatchMode::AsciiInsensitive,
+        MatchMode::UnicodeInsensitive(FoldProfile::Default),
+        MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents),
+    ];
+
+    let corpus = std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("fuzz/corpus/build_query");
+    for entry in std::fs::read_dir(corpus).unwrap() {
+        let path = entry.unwrap().path();
+        let text = String::from_utf8_lossy(&std::fs::read(&path).unwrap()).into_owned();
+        let (queries, words): (Vec<&str>, Vec<&str>) = text.split('\n').partition(|line| line.starts_with('?'));
+
+        let mut dawg = UnsyncDawg::new();
+        dawg.add_all(&words).unwrap();
+        checked(&dawg);
+        let model = naive::Lexicon::new(words.iter().copied());
+        let queries = queries.iter().map(|query| &query[1..]).chain(words.iter().copied())
+            .flat_map(|query| MODES.map(|mode| [
+                Query::Contains(query.into(), mode),
+                Query::ContainsPrefix(query.into(), mode),
+                Query::Completions(query.into(), mode),
+            ]))
+            .flatten()
+            .collect::<Vec<_>>();
+
+        check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("{}: {divergence}", path.display()));
+        dawg.finish();
+        checked(&dawg);
+        dawg.assert_minimal().unwrap();
+        check_equivalence(&dawg, &model, &queries).unwrap_or_else(|divergence| panic!("{}: {divergence}", path.display()));
+        assert!(model.words().enumerate().all(|(rank, word)| dawg.rank(word) == Some(rank)), "{}", path.display());
+    }
+}
@@ -0,0 +1,72 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::{BTreeSet, HashMap};
+
+use common::{build, words};
+use dawging::dawg::sample::{RandomSource, SplitMix64};
+
+/// Generator handing out the same value forever, to check what `below` does with it
+struct Constant(u64);
+
+impl RandomSource for Constant {
+    fn next_u64(&mut self) -> u64 {
+        self.0
+    }
+}
+
+#[test]
+fn samples_are_distinct_words_in_sorted_order() {
+    let words = words();
+    let dawg = build(&words);
+    let mut rng = SplitMix64::new(3);
+
+    for k in [0, 1, 50, 500] {
+        let sample = dawg.sample_k(k, &mut rng);
+        assert_eq!(sample.len(), k);
+        assert!(sample.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(sample.iter().all(|word| dawg.contains(word)));
+    }
+}
+
+#[test]
+fn a_seed_always_draws_the_same_sample() {
+    let dawg = build(&words());
+    assert_eq!(dawg.sample_k(50, &mut SplitMix64::new(9)), dawg.sample_k(50, &mut SplitMix64::new(9)));
+    assert_ne!(dawg.sample_k(50, &mut SplitMix64::new(9)), dawg.sample_k(50, &mut SplitMix64::new(10)));
+}
+
+#[test]
+fn asking_for_more_than_every_word_returns_them_all() {
+    let words = (0..500).map(|index| format!("w{index}")).collect::<BTreeSet<_>>().into_iter().collect::<Vec<_>>();
+    let dawg = build(&words);
+    let mut rng = SplitMix64::new(1);
+    assert_eq!(dawg.sample_k(500, &mut rng), words);
+    assert_eq!(dawg.sample_k(1000, &mut rng), words);
+    assert!(build::<&str>(&[]).sample_k(3, &mut rng).is_empty());
+}
+
+#[test]
+fn every_word_is_drawn_about_as_often() {
+    let dawg = build(&["a", "ab", "b", "ba"]);
+    let mut rng = SplitMix64::new(5);
+    let mut drawn = HashMap::<String, usize>::new();
+
+    for _ in 0..20000 {
+        for word in dawg.sample_k(2, &mut rng) {
+            *drawn.entry(word).or_default() += 1;
+        }
+    }
+    // 10000 draws expected for each word, off by less than 10%
+    assert_eq!(drawn.len(), 4);
+    assert!(drawn.values().all(|count| (9000..11000).contains(count)), "{drawn:?}");
+}
+
+#[test]
+fn below_stays_under_the_bound() {
+    assert_eq!(Constant(7).below(5), 2);
+    assert_eq!(Constant(0).below(1), 0);
+    let mut rng = SplitMix64::new(0);
+    assert!((0..1000).all(|_| rng.below(3) < 3));
+}
@@ -0,0 +1,94 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{alloc::{allocations, Counting}, build, build_with, xorshift};
+use dawging::dawg::{lexicon::{FoldProfile, Lexicon, MatchMode}, options::{DawgOptions, Normalization}};
+
+#[global_allocator]
+static ALLOCATOR: Counting = Counting;
+
+fn draw(state: &mut u64, letters: &[char], max: u64) -> String {
+    (0..xorshift(state) % max).map(|_| letters[(xorshift(state) % letters.len() as u64) as usize]).collect()
+}
+
+const MODES: [MatchMode; 3] = [MatchMode::Exact, MatchMode::AsciiInsensitive, MatchMode::UnicodeInsensitive(FoldProfile::IgnoreAccents)];
+
+#[test]
+fn the_searcher_answers_like_the_one_shot_queries() {
+    let mut state = 7;
+    let mut words = (0..2000).map(|_| draw(&mut state, &['a', 'b', 'A', 'c', 'B', 'é'], 7)).collect::<Vec<_>>();
+    words.sort();
+    words.dedup();
+    let dawg = build(&words);
+
+    let mut searcher = dawg.searcher();
+    let (mut completions, mut found) = (vec![], String::new());
+    for _ in 0..3000 {
+        let query = draw(&mut state, &['a', 'b', 'A', 'c', 'B', 'E', 'x'], 5);
+        for mode in MODES {
+            assert_eq!(searcher.contains(&query, mode), Lexicon::contains(&dawg, &query, mode));
+            assert_eq!(searcher.contains_prefix(&query, mode), Lexicon::contains_prefix(&dawg, &query, mode));
+            searcher.completions(&query, mode, &mut completions);
+            assert_eq!(completions, Lexicon::completions(&dawg, &query, mode), "{query} {mode:?}");
+
+            let expected = dawg.get_with(&query, mode).map(|found| found.word().to_string());
+            assert_eq!(searcher.get(&query, mode, &mut found).then(|| found.clone()), expected);
+        }
+    }
+}
+
+#[test]
+fn steady_queries_allocate_nothing() {
+    let dawg = build(&common::words());
+    let mut searcher = dawg.searcher();
+    let insensitive = MatchMode::UnicodeInsensitive(FoldProfile::Default);
+    let mut completions = vec![];
+    let mut query = |searcher: &mut dawging::dawg::searcher::DawgSearcher<_>| {
+        searcher.contains("ABACK", insensitive);
+        searcher.contains_prefix("ab", MatchMode::Exact);
+        searcher.contains("zzz", MatchMode::Exact);
+        searcher.completions("abd", MatchMode::Exact, &mut completions);
+    };
+
+    // the buffers grow during the first queries
+    for _ in 0..3 {
+        query(&mut searcher);
+    }
+    assert_eq!(allocations(|| (0..100).for_each(|_| query(&mut searcher))).1, 0);
+    // while the one-shot queries allocate every time
+    assert!(allocations(|| Lexicon::completions(&dawg, "abd", MatchMode::Exact)).1 > 0);
+}
+
+#[test]
+fn normalized_queries_allocate_nothing_either() {
+    let dawg = build_with(&["cafe", "café", "cafés"], DawgOptions::new().case_folding(true).normalization(Normalization::Nfc));
+    let mut searcher = dawg.searcher();
+    let mut completions = vec![];
+    let mut query = |searcher: &mut dawging::dawg::searcher::DawgSearcher<_>| {
+        assert!(searcher.contains("CAFE\u{301}", MatchMode::Exact));
+        searcher.completions("cafe\u{301}", MatchMode::Exact, &mut completions);
+    };
+
+    for _ in 0..3 {
+        query(&mut searcher);
+    }
+    assert_eq!(allocations(|| (0..100).for_each(|_| query(&mut searcher))).1, 0);
+    assert_eq!(completions, ["café", "cafés"]);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_has_a_searcher_too() {
+    let mut dawg = dawging::SyncDawg::new_sync();
+    for word in ["ab", "abc", "b"] {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.finish();
+
+    let mut searcher = dawg.searcher();
+    let mut completions = vec![];
+    searcher.completions("AB", MatchMode::AsciiInsensitive, &mut completions);
+    assert_eq!(completions, ["ab", "abc"]);
+    assert!(searcher.contains_prefix("", MatchMode::Exact));
+}
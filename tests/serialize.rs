@@ -0,0 +1,273 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, forge_chain, forge_count, words};
+use dawging::{dawg::{lexicon::{Lexicon, MatchMode}, options::{DawgBuilder, DawgOptions, DuplicatePolicy, Granularity, Normalization, RegisterHasher, SortOrder, ValidationPolicy}}, error::OptionsConflict, Dawg, DawgError, UnsyncDawg, UnsyncWrapper};
+
+#[test]
+fn options_round_trip_and_change_behaviour() {
+    let options = DawgOptions::new().case_folding(true).duplicate_policy(DuplicatePolicy::Error).alphabet("abcdefghijklmnopqrstuvwxyz").capacity(10);
+    let mut dawg = UnsyncDawg::with_options(options.clone()).unwrap();
+    for word in ["Apple", "apply", "BANANA", "band"] {
+        dawg.try_add(word).unwrap();
+    }
+    assert!(matches!(dawg.try_add("band"), Err(DawgError::Duplicate(_))));
+    assert!(matches!(dawg.try_add("c4t"), Err(DawgError::InvalidCharacter { character: '4', .. })));
+    assert!(matches!(dawg.try_add("aaa"), Err(DawgError::Unsorted { .. })));
+    dawg.finish();
+    assert!(dawg.contains("APPLE"));
+
+    let mut saved = vec![];
+    dawg.save(&mut saved).unwrap();
+    let loaded = UnsyncDawg::<char>::load(&saved[..]).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.options(), &options);
+    assert_eq!(loaded.words().collect::<Vec<_>>(), dawg.words().collect::<Vec<_>>());
+    assert!(loaded.contains("BaNd"));
+    assert!(Lexicon::contains(&loaded, "band", MatchMode::Exact));
+    assert_eq!(loaded.word_count(), 4);
+    assert_eq!(loaded.to_bytes().unwrap(), saved);
+}
+
+#[test]
+fn incompatible_options_are_rejected() {
+    let options = DawgOptions::new().case_folding(true).alphabet("aB");
+    assert!(matches!(UnsyncDawg::<char>::with_options(options), Err(DawgError::InvalidOptions(OptionsConflict::UnreachableLetter('B')))));
+}
+
+#[test]
+fn a_capacity_that_cant_be_reserved_is_an_error() {
+    let options = DawgOptions::new().capacity(usize::MAX);
+    assert!(matches!(UnsyncDawg::<char>::with_options(options), Err(DawgError::InvalidOptions(OptionsConflict::Capacity { nodes: usize::MAX, .. }))));
+}
+
+#[test]
+fn normalization_round_trips_and_merges_spellings() {
+    let options = DawgOptions::new().normalization(Normalization::Nfc);
+    let mut dawg = UnsyncDawg::with_options(options.clone()).unwrap();
+    dawg.try_add("cafe\u{301}").unwrap();
+    dawg.try_add("caf\u{e9}").unwrap();
+    dawg.finish();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["caf\u{e9}"]);
+
+    let loaded = UnsyncDawg::<char>::load(&dawg.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(loaded.options(), &options);
+    assert!(loaded.contains("cafe\u{301}"));
+    assert!(loaded.contains("caf\u{e9}"));
+
+    let mut decomposed = UnsyncDawg::with_options(DawgOptions::new().normalization(Normalization::Nfd)).unwrap();
+    decomposed.try_add("caf\u{e9}").unwrap();
+    decomposed.finish();
+    assert_eq!(decomposed.words().collect::<Vec<_>>(), ["cafe\u{301}"]);
+}
+
+#[test]
+fn granularities_round_trip_and_split_words() {
+    let options = DawgOptions::new().granularity(Granularity::Graphemes).case_folding(true);
+    let mut graphemes = UnsyncDawg::<String>::with_options(options.clone()).unwrap();
+    for word in ["cafes", "Cafe\u{301}"] {
+        graphemes.try_add_text(word).unwrap();
+    }
+    assert!(matches!(graphemes.try_add_text("cafe"), Err(DawgError::Unsorted { order: "graphemes", .. })));
+    graphemes.finish();
+    assert_eq!(graphemes.text_labels("cafe\u{301}").unwrap(), ["c", "a", "f", "e\u{301}"]);
+    assert!(graphemes.contains_text("CAFE\u{301}"));
+    assert!(!graphemes.contains_text("cafe"));
+
+    let loaded = UnsyncDawg::<String>::load(&graphemes.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(loaded.options(), &options);
+    assert_eq!(loaded.sequences().collect::<Vec<_>>(), graphemes.sequences().collect::<Vec<_>>());
+
+    let mut bytes = UnsyncDawg::<u8>::with_options(DawgOptions::new().granularity(Granularity::Bytes)).unwrap();
+    bytes.try_add_text("\u{e9}").unwrap();
+    bytes.finish();
+    assert_eq!(bytes.sequences().collect::<Vec<_>>(), [vec![0xc3, 0xa9]]);
+    assert!(bytes.contains_text("\u{e9}"));
+
+    // the granularity must be the one of the labels
+    let mut chars = UnsyncDawg::with_options(DawgOptions::new().granularity(Granularity::Bytes)).unwrap();
+    let conflict = OptionsConflict::LabelGranularity { labels: Granularity::Chars, options: Granularity::Bytes };
+    assert!(matches!(chars.try_add("cafe"), Err(DawgError::InvalidOptions(found)) if found == conflict));
+    chars.finish();
+    assert!(matches!(chars.insert("\u{e9}"), Err(DawgError::InvalidOptions(_))));
+    assert!(matches!(chars.remove("\u{e9}"), Err(DawgError::InvalidOptions(_))));
+    assert_eq!(chars.word_count(), 0);
+    assert!(matches!(UnsyncDawg::<u8>::default().try_add_text("cafe"), Err(DawgError::InvalidOptions(_))));
+}
+
+#[test]
+fn incompatible_text_options_are_rejected() {
+    let invalid = [
+        (DawgOptions::new().granularity(Granularity::Bytes).normalization(Normalization::Nfc), OptionsConflict::GranularityNormalization(Normalization::Nfc)),
+        (DawgOptions::new().normalization(Normalization::Nfd).alphabet("\u{e9}"), OptionsConflict::UnreachableLetter('\u{e9}')),
+        (DawgOptions::new().granularity(Granularity::Graphemes).sort_order(SortOrder::CaseInsensitiveBytes), OptionsConflict::DescendingGranularity(Granularity::Graphemes)),
+    ];
+    for (options, conflict) in invalid {
+        assert!(matches!(UnsyncDawg::<String>::with_options(options), Err(DawgError::InvalidOptions(found)) if found == conflict));
+    }
+    assert!(DawgOptions::new().granularity(Granularity::Graphemes).normalization(Normalization::Nfc).validate().is_ok());
+}
+
+#[test]
+fn the_validation_policy_and_hasher_round_trip_and_change_behaviour() {
+    let options = DawgOptions::new().alphabet("abcdefghijklmnopqrstuvwxyz").validation_policy(ValidationPolicy::Skip).hasher(RegisterHasher::Seeded(7));
+    let mut dawg = DawgBuilder::<UnsyncWrapper>::with_options(options.clone()).unwrap();
+    for word in ["apple", "c4t", "band", "aaa", "cat"] {
+        dawg.try_add(word).unwrap();
+    }
+    assert_eq!((dawg.words_added(), dawg.words_skipped()), (3, 2));
+    // duplicates are still up to the duplicate policy
+    assert!(dawg.try_add("cat").is_ok());
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["apple", "band", "cat"]);
+
+    let mut saved = vec![];
+    dawg.save(&mut saved).unwrap();
+    let mut loaded = UnsyncDawg::<char>::load(&saved[..]).unwrap();
+    assert_eq!(loaded.options(), &options);
+    assert_eq!(loaded.to_bytes().unwrap(), saved);
+
+    // a finished dawg skips the word just the same, and the seeded register minimizes the edits like any other
+    loaded.try_add("d0g").unwrap();
+    loaded.try_add("bat").unwrap();
+    loaded.finish();
+    checked(&loaded);
+    assert_eq!(loaded.words().collect::<Vec<_>>(), ["apple", "band", "bat", "cat"]);
+    assert_eq!(loaded.words_skipped(), 1);
+
+    let mut rejecting = UnsyncDawg::with_options(DawgOptions::new().alphabet("abc")).unwrap();
+    assert!(matches!(rejecting.try_add("abd"), Err(DawgError::InvalidCharacter { .. })));
+    assert_eq!(rejecting.words_skipped(), 0);
+}
+
+#[test]
+fn text_words_are_skipped_too() {
+    let options = DawgOptions::new().granularity(Granularity::Bytes).validation_policy(ValidationPolicy::Skip);
+    let mut dawg = UnsyncDawg::<u8>::with_options(options).unwrap();
+    for word in ["b", "a", "c"] {
+        dawg.try_add_text(word).unwrap();
+    }
+    assert_eq!((dawg.words_added(), dawg.words_skipped()), (2, 1));
+}
+
+#[test]
+fn the_defaults_are_unchanged() {
+    let mut dawg = UnsyncDawg::new();
+    for word in ["Apple", "Apple", "apple"] {
+        dawg.add(word.to_string());
+    }
+    dawg.finish();
+    assert!(!dawg.contains("APPLE"));
+    assert_eq!(dawg.word_count(), 2);
+}
+
+#[test]
+fn other_labels_round_trip() {
+    let mut dawg = Dawg::<UnsyncWrapper, u8>::default();
+    dawg.add_sequence(&[1, 2]).unwrap();
+    dawg.add_sequence(&[3]).unwrap();
+    dawg.finish();
+
+    let loaded = Dawg::<UnsyncWrapper, u8>::load(&dawg.to_bytes().unwrap()[..]).unwrap();
+    assert_eq!(loaded.sequences().collect::<Vec<_>>(), [vec![1, 2], vec![3]]);
+}
+
+#[test]
+fn the_fixture_round_trips() {
+    let dawg = build(&words());
+    let loaded = UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.to_bytes().unwrap(), dawg.to_bytes().unwrap());
+    assert!(loaded.words().eq(dawg.words()));
+}
+
+#[test]
+fn every_truncation_is_an_error() {
+    let saved = build(&["apple", "apply", "band"]).to_bytes().unwrap();
+    for len in 0..saved.len() {
+        assert!(UnsyncDawg::<char>::from_bytes(&saved[..len]).is_err(), "{len} bytes loaded");
+    }
+}
+
+#[test]
+fn more_prefixes_than_can_be_counted_are_rejected() {
+    // small enough for the prefixes to be counted
+    let chain = UnsyncDawg::<char>::from_bytes(&forge_chain::<UnsyncWrapper>(6)).unwrap();
+    checked(&chain);
+    assert_eq!(chain.words().count(), 32);
+
+    let forged = forge_chain::<UnsyncWrapper>(70);
+    assert!(matches!(UnsyncDawg::<char>::from_bytes(&forged), Err(DawgError::InvalidFormat(_))));
+}
+
+#[test]
+fn a_wrong_count_behind_matching_checksums_is_rejected() {
+    let saved = build(&["a", "b", "c"]).to_bytes().unwrap();
+    let forged = forge_count(&saved, 0, 1);
+
+    match UnsyncDawg::<char>::from_bytes(&forged) {
+        Err(DawgError::CorruptedCount { node: 0, stored: 1, actual: 3 }) => {}
+        other => panic!("{other:?}"),
+    }
+
+    // a count below the root
+    let saved = build(&["ab", "ac", "b"]).to_bytes().unwrap();
+    let forged = forge_count(&saved, 1, 7);
+    assert!(matches!(UnsyncDawg::<char>::from_bytes(&forged), Err(DawgError::CorruptedCount { node: 1, stored: 7, actual: 2 })));
+}
+
+#[test]
+fn an_unfinished_dawg_is_not_saved() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.add_all(["a", "b"]).unwrap();
+    let mut saved = vec![];
+    assert!(matches!(dawg.to_bytes(), Err(DawgError::NotFinished)));
+    assert!(matches!(dawg.save(&mut saved), Err(DawgError::NotFinished)));
+    assert!(saved.is_empty());
+
+    dawg.finish();
+    dawg.save(&mut saved).unwrap();
+    assert_eq!(saved, dawg.to_bytes().unwrap());
+}
+
+/// A dawg saved in version 2 of the layout (without checksums), whose root has an edge to a terminal leaf for every byte
+/// of `labels`, in that order
+fn v2_with_root_labels(labels: &[u8]) -> Vec<u8> {
+    let current = build::<&str>(&[]).to_bytes().unwrap();
+    let options_len = u32::from_le_bytes(current[6..10].try_into().unwrap()) as usize;
+
+    let mut bytes = b"DAWG".to_vec();
+    bytes.extend_from_slice(&2u16.to_le_bytes());
+    bytes.extend_from_slice(&current[6..10 + options_len]);
+    for value in [2, labels.len() as u32, labels.len() as u32, 0] {
+        bytes.extend_from_slice(&value.to_le_bytes());
+    }
+    // the root, then the leaf
+    for (terminal, count, first_edge, edges) in [(0, labels.len() as u64, 0, labels.len() as u32), (1, 1, labels.len() as u32, 0)] {
+        bytes.push(terminal);
+        bytes.extend_from_slice(&count.to_le_bytes());
+        bytes.extend_from_slice(&first_edge.to_le_bytes());
+        bytes.extend_from_slice(&edges.to_le_bytes());
+    }
+    for label in 0..labels.len() as u32 {
+        for value in [1, label, 1] {
+            bytes.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+    bytes.extend_from_slice(labels);
+    bytes
+}
+
+#[test]
+fn labels_out_of_order_are_rejected() {
+    let loaded = UnsyncDawg::<char>::from_bytes(&v2_with_root_labels(b"ab")).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.words().collect::<Vec<_>>(), ["a", "b"]);
+
+    for labels in [b"aa", b"ba"] {
+        let error = UnsyncDawg::<char>::from_bytes(&v2_with_root_labels(labels)).unwrap_err();
+        assert!(matches!(error, DawgError::InvalidFormat(ref reason) if reason.contains("sorted")), "{error}");
+    }
+}
@@ -0,0 +1,210 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use common::{build, build_with, checked, xorshift};
+use dawging::{dawg::{map::DawgMap, metadata::Metadata, options::DawgOptions, setops::KMerge}, error::OptionsConflict, DawgError, UnsyncDawg, UnsyncWrapper};
+
+/// Up to `count` distinct words of 1 to 4 letters out of "abc", the alphabet being small enough for two draws to overlap
+fn draw(state: &mut u64, count: u64) -> BTreeSet<String> {
+    (0..count).map(|_| (0..1 + xorshift(state) % 4).map(|_| b"abc"[(xorshift(state) % 3) as usize] as char).collect()).collect()
+}
+
+/// Builds a map of `words`, each to itself along with `tag`
+fn map_of(words: &BTreeSet<String>, tag: usize) -> DawgMap<UnsyncWrapper, Vec<(String, usize)>> {
+    let mut map = DawgMap::with_options(DawgOptions::default()).unwrap();
+    for word in words {
+        map.try_insert(word, vec![(word.clone(), tag)]).unwrap();
+    }
+    map.finish();
+    map
+}
+
+/// Builds a dawg of `words`
+fn dawg_of(words: &BTreeSet<String>) -> UnsyncDawg {
+    build(&words.iter().collect::<Vec<_>>())
+}
+
+#[test]
+fn set_operations_match_the_sets() {
+    let mut state = 17;
+    for left in [0, 1, 5, 30, 80] {
+        for right in [0, 1, 5, 30, 80] {
+            let a = draw(&mut state, left);
+            let b = draw(&mut state, right);
+            let (x, y) = (dawg_of(&a), dawg_of(&b));
+
+            assert!(x.union_words(&y).eq(a.union(&b).cloned()));
+            assert!(x.intersection_words(&y).eq(a.intersection(&b).cloned()));
+            assert!(x.difference_words(&y).eq(a.difference(&b).cloned()));
+            assert!(y.difference_words(&x).eq(b.difference(&a).cloned()));
+        }
+    }
+}
+
+#[test]
+fn identical_and_empty_sides() {
+    let mut state = 5;
+    let words = draw(&mut state, 60);
+    let (x, y, empty) = (dawg_of(&words), dawg_of(&words), UnsyncDawg::new());
+
+    assert!(x.union_words(&y).eq(words.iter().cloned()));
+    assert!(x.intersection_words(&y).eq(words.iter().cloned()));
+    assert_eq!(x.difference_words(&y).next(), None);
+
+    assert!(x.union_words(&empty).eq(words.iter().cloned()));
+    assert!(empty.union_words(&x).eq(words.iter().cloned()));
+    assert_eq!(x.intersection_words(&empty).next(), None);
+    assert!(x.difference_words(&empty).eq(words.iter().cloned()));
+    assert_eq!(empty.difference_words(&x).next(), None);
+}
+
+#[test]
+fn a_union_feeds_the_builder() {
+    let mut state = 41;
+    let (a, b) = (draw(&mut state, 80), draw(&mut state, 80));
+    let (x, y) = (dawg_of(&a), dawg_of(&b));
+
+    let mut union = UnsyncDawg::new();
+    for word in x.union_words(&y) {
+        union.try_add(&word).unwrap();
+    }
+    union.finish();
+    checked(&union);
+    union.assert_minimal().unwrap();
+    assert!(union.words().eq(a.union(&b).cloned()));
+    assert_eq!(union.to_bytes().unwrap(), UnsyncDawg::merge_all(&[&x, &y]).unwrap().to_bytes().unwrap());
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn both_kinds_of_dawgs_can_be_merged() {
+    let mut state = 3;
+    let (a, b) = (draw(&mut state, 50), draw(&mut state, 50));
+    let x = dawg_of(&a);
+    let mut y = dawging::SyncDawg::new_sync();
+    y.add_all(&b).unwrap();
+    y.finish();
+    checked(&y);
+
+    assert!(x.union_words(&y).eq(a.union(&b).cloned()));
+    assert!(y.intersection_words(&x).eq(b.intersection(&a).cloned()));
+    assert!(y.difference_words(&x).eq(b.difference(&a).cloned()));
+}
+
+#[test]
+fn set_operations_over_sequences() {
+    let mut x = dawging::Dawg::<dawging::UnsyncWrapper, u16>::default();
+    let mut y = dawging::Dawg::<dawging::UnsyncWrapper, u16>::default();
+    for sequence in [vec![1u16], vec![1, 2], vec![300]] {
+        x.add_sequence(&sequence).unwrap();
+    }
+    for sequence in [vec![1u16, 2], vec![2], vec![300, 1]] {
+        y.add_sequence(&sequence).unwrap();
+    }
+    x.finish();
+    y.finish();
+
+    assert_eq!(x.union_sequences(&y).collect::<Vec<_>>(), [vec![1], vec![1, 2], vec![2], vec![300], vec![300, 1]]);
+    assert_eq!(x.intersection_sequences(&y).collect::<Vec<_>>(), [vec![1, 2]]);
+    assert_eq!(x.difference_sequences(&y).collect::<Vec<_>>(), [vec![1], vec![300]]);
+}
+
+#[test]
+fn merging_many_dawgs_builds_their_union() {
+    let mut state = 8;
+    let (a, b, c) = (draw(&mut state, 100), draw(&mut state, 100), draw(&mut state, 100));
+    let (x, y, z) = (dawg_of(&a), dawg_of(&b), dawg_of(&c));
+
+    let merged = UnsyncDawg::merge_all(&[&x, &y, &z]).unwrap();
+    checked(&merged);
+    merged.assert_minimal().unwrap();
+    let union = a.iter().chain(&b).chain(&c).cloned().collect::<BTreeSet<_>>();
+    assert_eq!(merged.to_bytes().unwrap(), dawg_of(&union).to_bytes().unwrap());
+    assert_eq!(UnsyncDawg::merge_all(&[&x, &x]).unwrap().to_bytes().unwrap(), x.to_bytes().unwrap());
+    assert_eq!(UnsyncDawg::merge_all(&[&x]).unwrap().to_bytes().unwrap(), x.to_bytes().unwrap());
+}
+
+#[test]
+fn merging_no_dawg_builds_an_empty_one() {
+    let merged = UnsyncDawg::<char>::merge_all(&[]).unwrap();
+    checked(&merged);
+    assert!(merged.is_finished());
+    assert_eq!(merged.words().next(), None);
+    assert_eq!(merged.to_bytes().unwrap(), build::<&str>(&[]).to_bytes().unwrap());
+}
+
+#[test]
+fn merging_keeps_the_options_of_the_inputs() {
+    let options = DawgOptions::new().case_folding(true);
+    let (x, y) = (build_with(&["Apple", "band"], options.clone()), build_with(&["BANANA"], options.clone()));
+
+    let merged = UnsyncDawg::merge_all(&[&x, &y]).unwrap();
+    checked(&merged);
+    assert_eq!(merged.options(), &options);
+    assert!(merged.contains("APPLE") && merged.contains("Banana") && merged.contains("bAnD"));
+    assert_eq!(merged.to_bytes().unwrap(), build_with(&["apple", "banana", "band"], options.clone()).to_bytes().unwrap());
+
+    // settings that only describe the dawg come from the first one
+    let named = build_with(&["cherry"], options.clone().metadata(Metadata::new().name("fruit")));
+    assert_eq!(UnsyncDawg::merge_all(&[&named, &x]).unwrap().options().get_metadata().get_name(), Some("fruit"));
+
+    let plain = build(&["Apple"]);
+    assert!(matches!(UnsyncDawg::merge_all(&[&x, &plain]), Err(DawgError::InvalidOptions(OptionsConflict::NotMergeable))));
+}
+
+#[test]
+fn merging_maps_resolves_only_the_shared_words() {
+    let words = |list: &[&str]| list.iter().map(|word| word.to_string()).collect::<BTreeSet<_>>();
+    let (x, y, z) = (map_of(&words(&["a", "ab", "b"]), 0), map_of(&words(&["ab", "c"]), 1), map_of(&words(&["b", "ca"]), 2));
+
+    let mut resolved = vec![];
+    let merged = DawgMap::merge_all(&[&x, &y, &z], |first, second| {
+        resolved.push(first[0].0.clone());
+        [&first[..], &second[..]].concat()
+    }).unwrap();
+    checked(merged.dawg());
+    assert_eq!(resolved, ["ab", "b"]);
+    assert_eq!(merged.get("ab"), Some(&vec![(String::from("ab"), 0), (String::from("ab"), 1)]));
+    assert_eq!(merged.get("b"), Some(&vec![(String::from("b"), 0), (String::from("b"), 2)]));
+    assert_eq!(merged.get("ca"), Some(&vec![(String::from("ca"), 2)]));
+    assert_eq!(merged.iter().map(|(word, _)| word).collect::<Vec<_>>(), ["a", "ab", "b", "c", "ca"]);
+
+    let mut state = 5;
+    let drawn = [draw(&mut state, 60), draw(&mut state, 60), draw(&mut state, 60)];
+    let maps = drawn.iter().enumerate().map(|(tag, words)| map_of(words, tag)).collect::<Vec<_>>();
+    let mut calls = BTreeMap::<String, usize>::new();
+    let merged = DawgMap::merge_all(&maps.iter().collect::<Vec<_>>(), |first, second| {
+        *calls.entry(first[0].0.clone()).or_default() += 1;
+        [&first[..], &second[..]].concat()
+    }).unwrap();
+
+    for (word, value) in merged.iter() {
+        let tags = (0..3).filter(|tag| drawn[*tag].contains(&word)).collect::<Vec<_>>();
+        assert_eq!(value.iter().map(|(_, tag)| *tag).collect::<Vec<_>>(), tags);
+        assert_eq!(calls.get(&word).copied().unwrap_or(0), tags.len() - 1, "{word}");
+    }
+    assert_eq!(merged.len(), drawn.iter().flatten().collect::<BTreeSet<_>>().len());
+    assert!(DawgMap::<UnsyncWrapper, usize>::merge_all(&[], |_, _| unreachable!()).unwrap().is_empty());
+}
+
+#[test]
+fn k_merge_yields_each_item_once() {
+    let inputs = [vec![1, 3, 5, 7], vec![], vec![2, 3, 7, 8], vec![7]];
+    let merged = KMerge::new(inputs.iter().map(|input| input.iter().copied())).collect::<Vec<_>>();
+    assert_eq!(merged, [1, 2, 3, 5, 7, 8]);
+    assert_eq!(KMerge::new(Vec::<std::vec::IntoIter<u8>>::new()).next(), None);
+}
+
+#[test]
+fn k_merge_folds_the_payloads_of_a_key_in_the_order_of_the_inputs() {
+    let inputs = [vec![(1, "a"), (4, "b")], vec![(1, "c"), (2, "d")], vec![(1, "e"), (4, "f")]];
+    let mut merge = KMerge::with_payloads(inputs.iter().map(|input| input.iter().map(|(key, payload)| (*key, payload.to_string()))));
+    let mut merged = vec![];
+    while let Some(entry) = merge.next_resolved(|first, second| first + &second) {
+        merged.push(entry);
+    }
+    assert_eq!(merged, [(1, String::from("ace")), (2, String::from("d")), (4, String::from("bf"))]);
+}
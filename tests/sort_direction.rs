@@ -0,0 +1,141 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs::File, io::BufReader};
+
+use common::{build, build_with, checked, fixture, words};
+use dawging::{dawg::{options::{DawgOptions, DuplicatePolicy, SortDirection, SortOrder}, wordlist::Utf8Policy}, DawgError, UnsyncDawg};
+
+fn load(name: &str, options: DawgOptions) -> UnsyncDawg {
+    let (dawg, _) = UnsyncDawg::from_reader(BufReader::new(File::open(fixture(name)).unwrap()), options, Utf8Policy::Error).unwrap();
+    dawg
+}
+
+fn try_build(words: &[&str], options: DawgOptions) -> Result<UnsyncDawg, DawgError> {
+    let mut dawg = UnsyncDawg::with_options(options)?;
+    for word in words {
+        dawg.try_add(word)?;
+    }
+    dawg.finish();
+    Ok(dawg)
+}
+
+#[test]
+fn ascending_and_descending_files_give_the_same_bytes() {
+    let ascending = load("words.txt", DawgOptions::new());
+    for direction in [SortDirection::Descending, SortDirection::Auto] {
+        let descending = load("words_descending.txt", DawgOptions::new().sort_direction(direction));
+        checked(&descending);
+        assert_eq!(descending.sort_direction(), Some(SortDirection::Descending));
+        assert_eq!(descending.to_bytes().unwrap(), ascending.to_bytes().unwrap());
+    }
+
+    let auto = load("words.txt", DawgOptions::new().sort_direction(SortDirection::Auto));
+    assert_eq!(auto.sort_direction(), Some(SortDirection::Ascending));
+    assert_eq!(auto.to_bytes().unwrap(), ascending.to_bytes().unwrap());
+}
+
+#[test]
+fn descending_input_may_repeat_words() {
+    let mut descending = words();
+    descending.reverse();
+    descending.insert(10, descending[10].clone());
+    descending.insert(0, descending[0].clone());
+
+    let dawg = build_with(&descending, DawgOptions::new().sort_direction(SortDirection::Descending));
+    assert_eq!(dawg.to_bytes().unwrap(), build(&words()).to_bytes().unwrap());
+}
+
+#[test]
+fn add_all_takes_any_direction() {
+    for direction in [SortDirection::Ascending, SortDirection::Descending, SortDirection::Auto] {
+        let mut dawg = UnsyncDawg::with_options(DawgOptions::new().sort_direction(direction)).unwrap();
+        dawg.add_all(words()).unwrap();
+        dawg.finish();
+        assert_eq!(dawg.to_bytes().unwrap(), build(&words()).to_bytes().unwrap());
+    }
+}
+
+#[test]
+fn flipping_direction_midway_is_rejected_with_the_detected_direction() {
+    let auto = DawgOptions::new().sort_direction(SortDirection::Auto);
+
+    let error = try_build(&["d", "c", "b", "c"], auto.clone()).unwrap_err();
+    assert!(matches!(error, DawgError::Unsorted { .. }));
+    assert!(error.to_string().contains("descending bytes order"), "{error}");
+
+    let error = try_build(&["a", "a", "b", "a"], auto).unwrap_err();
+    assert!(error.to_string().contains("ascending bytes order"), "{error}");
+
+    let error = try_build(&["a", "b"], DawgOptions::new().sort_direction(SortDirection::Descending)).unwrap_err();
+    assert!(error.to_string().contains("descending bytes order"), "{error}");
+
+    // the default ascending order keeps its message
+    let error = try_build(&["b", "a"], DawgOptions::new()).unwrap_err();
+    assert!(error.to_string().contains("(bytes order)"), "{error}");
+}
+
+#[test]
+fn a_file_flipping_direction_errors_at_its_line() {
+    let lines = "c\nb\na\nb\n";
+    let error = UnsyncDawg::from_reader(lines.as_bytes(), DawgOptions::new().sort_direction(SortDirection::Auto), Utf8Policy::Error).unwrap_err();
+    assert!(matches!(error, DawgError::Line { line: 4, .. }), "{error}");
+    assert!(error.to_string().contains("descending bytes order"), "{error}");
+}
+
+#[test]
+fn descending_under_a_case_insensitive_order() {
+    let ascending = ["apple", "Avocado", "banana", "Berry", "cherry"];
+    let mut descending = ascending;
+    descending.reverse();
+
+    let up = try_build(&ascending, DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes)).unwrap();
+    let down = try_build(&descending, DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes).sort_direction(SortDirection::Auto)).unwrap();
+    checked(&down);
+    assert_eq!(up.words().collect::<Vec<_>>(), down.words().collect::<Vec<_>>());
+}
+
+#[test]
+fn the_empty_word_comes_last_when_descending() {
+    let dawg = try_build(&["b", "a", ""], DawgOptions::new().sort_direction(SortDirection::Descending)).unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["", "a", "b"]);
+}
+
+#[test]
+fn a_single_word_leaves_the_direction_undetected() {
+    let mut dawg = UnsyncDawg::with_options(DawgOptions::new().sort_direction(SortDirection::Auto)).unwrap();
+    dawg.try_add("x").unwrap();
+    assert_eq!(dawg.sort_direction(), None);
+}
+
+#[test]
+fn a_finished_dawg_takes_words_going_back() {
+    for options in [DawgOptions::new().sort_direction(SortDirection::Descending), DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes), DawgOptions::new()] {
+        let mut dawg = try_build(&["b", "a"], options.sort_direction(SortDirection::Auto)).unwrap();
+        dawg.try_add("0").unwrap();
+        dawg.try_add("B").unwrap();
+        checked(&dawg);
+        assert!(dawg.contains("0") && dawg.contains("B") && dawg.contains("a"));
+        assert_eq!(dawg.words().count(), 4);
+        assert_eq!(dawg.last_word(), Some(&['a'][..]));
+    }
+
+    let mut dawg = try_build(&["a", "c"], DawgOptions::new()).unwrap();
+    dawg.add_sequence(&['b']).unwrap();
+    dawg.add_sequence(&['0']).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["0", "a", "b", "c"]);
+    assert_eq!(dawg.last_word(), Some(&['c'][..]));
+}
+
+#[test]
+fn a_finished_dawg_rejects_duplicates_under_the_error_policy() {
+    let mut dawg = try_build(&["a", "b"], DawgOptions::new()).unwrap();
+    dawg.try_add("a").unwrap();
+
+    let options = DawgOptions::new().duplicate_policy(DuplicatePolicy::Error);
+    let mut dawg = try_build(&["a", "b"], options).unwrap();
+    assert!(matches!(dawg.try_add("a"), Err(DawgError::Duplicate(_))));
+    assert_eq!(dawg.words().count(), 2);
+}
@@ -0,0 +1,110 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::cmp::Ordering;
+
+use common::{build, build_with, checked, words};
+use dawging::{dawg::options::{DawgOptions, SortOrder, ValidationPolicy}, DawgError, UnsyncDawg};
+
+/// Sorted as `sort -f` in the C locale would
+const CASE_INSENSITIVE: [&str; 7] = ["apple", "Avocado", "banana", "Berry", "berry", "cherry", "Zebra"];
+
+fn try_build(words: &[&str], options: DawgOptions) -> Result<UnsyncDawg, DawgError> {
+    let mut dawg = UnsyncDawg::with_options(options)?;
+    for word in words {
+        dawg.try_add(word)?;
+        checked(&dawg);
+    }
+    dawg.finish();
+    Ok(dawg)
+}
+
+/// The words of `words` in the order of `str`
+fn sorted(words: &[&str]) -> Vec<String> {
+    let mut sorted = words.iter().map(|word| word.to_string()).collect::<Vec<_>>();
+    sorted.sort();
+    sorted
+}
+
+#[test]
+fn a_case_insensitive_list_builds_under_the_matching_order() {
+    let dawg = try_build(&CASE_INSENSITIVE, DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes)).unwrap();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), sorted(&CASE_INSENSITIVE));
+    // the order is saved with the options, so only the automata are compared
+    assert_eq!(dawg.visit_nodes().collect::<Vec<_>>(), build(&sorted(&CASE_INSENSITIVE)).visit_nodes().collect::<Vec<_>>());
+}
+
+#[test]
+fn a_word_going_back_on_the_letters_is_held_aside_until_finish() {
+    let mut dawg = UnsyncDawg::with_options(DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes)).unwrap();
+    dawg.try_add("apple").unwrap();
+    dawg.try_add("Banana").unwrap();
+    assert!(dawg.contains("apple"));
+    assert!(!dawg.contains("Banana"));
+
+    dawg.finish();
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["Banana", "apple"]);
+}
+
+#[test]
+fn merging_the_words_held_aside_keeps_the_skipped_count() {
+    let options = DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes).alphabet("aelpABn").validation_policy(ValidationPolicy::Skip);
+    let mut dawg = UnsyncDawg::with_options(options).unwrap();
+    for word in ["apple", "axe", "Banana"] {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.finish();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["Banana", "apple"]);
+    assert_eq!(dawg.words_skipped(), 1);
+}
+
+#[test]
+fn a_case_insensitive_list_is_rejected_under_bytes() {
+    let error = try_build(&CASE_INSENSITIVE, DawgOptions::new()).unwrap_err();
+    assert!(matches!(&error, DawgError::Unsorted { previous, word, order: "bytes" } if previous == "apple" && word == "Avocado"), "{error:?}");
+    assert!(error.to_string().contains("bytes order"));
+
+    let error = try_build(&["b", "a"], DawgOptions::new().sort_order(SortOrder::CaseInsensitiveBytes)).unwrap_err();
+    assert!(matches!(error, DawgError::Unsorted { order: "case-insensitive bytes", .. }));
+    let error = try_build(&["b", "a"], DawgOptions::new().sort_order(SortOrder::Chars)).unwrap_err();
+    assert!(matches!(error, DawgError::Unsorted { order: "chars", .. }));
+}
+
+#[test]
+fn chars_and_bytes_build_the_same_dawg() {
+    let words = words();
+    let chars = build_with(&words, DawgOptions::new().sort_order(SortOrder::Chars));
+    assert!(chars.visit_nodes().eq(build(&words).visit_nodes()));
+}
+
+#[test]
+fn a_custom_collation_round_trips() {
+    // longest words first, then in the order of str
+    let by_length = SortOrder::custom(|a: &str, b: &str| b.len().cmp(&a.len()).then_with(|| a.cmp(b)));
+    let words = ["banana", "apple", "berry", "kiwi", "fig", "a"];
+    let dawg = try_build(&words, DawgOptions::new().sort_order(by_length.clone())).unwrap();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), sorted(&words));
+
+    let error = try_build(&["fig", "kiwi"], DawgOptions::new().sort_order(by_length)).unwrap_err();
+    assert!(matches!(error, DawgError::Unsorted { order: "custom", .. }));
+
+    // the collation isn't saved, the loaded dawg checks the bytes again
+    let loaded = UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    assert_eq!(loaded.options().get_sort_order(), &SortOrder::Bytes);
+    assert!(loaded.words().eq(dawg.words()));
+}
+
+#[test]
+fn compare_follows_each_order() {
+    assert_eq!(SortOrder::Bytes.compare("Zebra", "apple"), Ordering::Less);
+    assert_eq!(SortOrder::CaseInsensitiveBytes.compare("Zebra", "apple"), Ordering::Greater);
+    assert_eq!(SortOrder::CaseInsensitiveBytes.compare("Berry", "berry"), Ordering::Less);
+    assert_eq!(SortOrder::Chars.compare("é", "z"), Ordering::Greater);
+    assert_eq!(SortOrder::custom(|a: &str, b: &str| b.cmp(a)).compare("a", "b"), Ordering::Greater);
+}
@@ -0,0 +1,129 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, build_with, fixture};
+use dawging::dawg::{lexicon::{FoldProfile, MatchMode}, options::DawgOptions, spell::{Boundaries, CheckOptions}};
+
+/// The words of `fixtures/document.txt`, spelled right
+const VOCABULARY: [&str; 32] = [
+    "and", "at", "brown", "café", "counted", "dog", "don't", "e.g", "fox", "foxes", "hello", "hours", "in", "jumps", "lazy",
+    "mail", "me", "naïve", "of", "or", "over", "panic", "quick", "so", "straße", "the", "them", "world", "über", "字", "東",
+    "京都",
+];
+
+/// Misspellings planted in `fixtures/document.txt`, in order
+const PLANTED: [&str; 7] = ["quikc", "jumsp", "lazzy", "naïev", "strasse", "京", "wrld"];
+
+fn document() -> String {
+    std::fs::read_to_string(fixture("document.txt")).unwrap()
+}
+
+fn vocabulary() -> Vec<&'static str> {
+    let mut words = VOCABULARY.to_vec();
+    words.sort();
+    words
+}
+
+fn insensitive() -> CheckOptions {
+    CheckOptions::new().match_mode(MatchMode::UnicodeInsensitive(FoldProfile::Default))
+}
+
+#[test]
+fn planted_misspellings_are_found() {
+    let (dawg, document) = (build(&vocabulary()), document());
+    let misses = dawg.check_text(&document, insensitive()).collect::<Vec<_>>();
+    assert_eq!(misses.iter().map(|miss| miss.token).collect::<Vec<_>>(), PLANTED);
+    assert!(misses.iter().all(|miss| miss.suggestions.is_empty()));
+
+    // the mode of the check, not that of the dawg, decides for the capitalized words
+    let exact = dawg.check_text(&document, CheckOptions::new()).map(|miss| miss.token).collect::<Vec<_>>();
+    for token in ["The", "Don't", "Café"] {
+        assert!(exact.contains(&token), "{token} in {exact:?}");
+    }
+    let folded = build_with(&vocabulary(), DawgOptions::new().case_folding(true));
+    assert_eq!(folded.check_text(&document, CheckOptions::new()).map(|miss| miss.token).collect::<Vec<_>>(), PLANTED);
+}
+
+#[test]
+fn offsets_slice_the_text() {
+    let (dawg, document) = (build(&vocabulary()), document());
+    let mut from = 0;
+    for miss in dawg.check_text(&document, insensitive()) {
+        assert_eq!(&document[miss.start..miss.end], miss.token);
+        assert_eq!(miss.start, from + document[from..].find(miss.token).unwrap(), "{}", miss.token);
+        from = miss.end;
+    }
+
+    // multi-byte letters before, inside and after the tokens
+    let text = "ééé «naïev» 字京 x";
+    let misses = dawg.check_text(text, insensitive()).collect::<Vec<_>>();
+    assert_eq!(misses.iter().map(|miss| (miss.token, miss.start, miss.end)).collect::<Vec<_>>(), [("ééé", 0, 6), ("naïev", 9, 15), ("京", 21, 24), ("x", 25, 26)]);
+    assert!(misses.iter().all(|miss| text.is_char_boundary(miss.start) && text.is_char_boundary(miss.end)));
+}
+
+#[test]
+fn skipped_tokens_come_back_when_asked() {
+    let (dawg, document) = (build(&vocabulary()), document());
+    let checked = |options: CheckOptions| dawg.check_text(&document, options).map(|miss| miss.token).collect::<Vec<_>>();
+    let skipped = ["NASA", "42", "3.14", "1,000", "https", "exmple.com", "pth", "www.foo.bar", "jon", "exmple.org"];
+    let default = checked(insensitive());
+    assert!(skipped.iter().all(|token| !default.contains(token)));
+
+    let none = checked(insensitive().skip_numbers(false).skip_all_caps(false).skip_urls(false));
+    for token in skipped {
+        assert!(none.contains(&token), "{token} in {none:?}");
+    }
+    assert_eq!(checked(insensitive().skip_numbers(false)).iter().filter(|token| token.starts_with(|letter: char| letter.is_ascii_digit())).count(), 3);
+    assert!(checked(insensitive().skip_all_caps(false)).contains(&"NASA"));
+    assert!(checked(insensitive().skip_urls(false)).contains(&"www.foo.bar"));
+    assert_eq!(checked(insensitive().skip_when(|token| token.ends_with('z') || token.starts_with('w'))), ["quikc", "jumsp", "lazzy", "naïev", "strasse", "京"]);
+}
+
+#[test]
+fn boundaries_split_the_words() {
+    let dawg = build(&vocabulary());
+    let tokens = |text: &'static str, boundaries: Boundaries| dawg.check_text(text, CheckOptions::new().boundaries(boundaries)).map(|miss| miss.token).collect::<Vec<_>>();
+    assert_eq!(tokens("don't 3.14 x1y", Boundaries::Unicode), Vec::<&str>::new());
+    assert_eq!(tokens("don't 3.14 x1y", Boundaries::Alphabetic), ["don", "t", "x", "y"]);
+    assert_eq!(tokens("ab-cd ef", Boundaries::Custom(|letter| letter != ' ')), ["ab-cd", "ef"]);
+    assert_eq!(tokens("", Boundaries::Unicode), Vec::<&str>::new());
+}
+
+#[test]
+fn unicode_boundaries_follow_the_word_rules() {
+    let dawg = build(&vocabulary());
+    let tokens = |text: &'static str| dawg.check_text(text, CheckOptions::new()).map(|miss| miss.token).collect::<Vec<_>>();
+    // a virama and a dependent vowel sign stay inside the word
+    assert_eq!(tokens("नमस्ते दुनिया"), ["नमस्ते", "दुनिया"]);
+    // a run of Katakana is one word, Han ideographs and Hiragana one word each
+    assert_eq!(tokens("カタカナ 漢語ひら"), ["カタカナ", "漢", "語", "ひ", "ら"]);
+    // Thai needs a dictionary to be split into words, the rules keep each letter with its vowel signs
+    assert_eq!(tokens("สวัสดี"), ["ส", "วั", "ส", "ดี"]);
+    // an emoji ZWJ sequence is no word, and doesn't split those around it
+    assert_eq!(tokens("ab👩\u{200D}👩\u{200D}👧cd ef 👍🏽"), ["ab", "cd", "ef"]);
+}
+
+#[test]
+fn suggestions_come_from_the_fuzzy_search() {
+    let (dawg, document) = (build(&vocabulary()), document());
+    let misses = dawg.check_text(&document, insensitive().suggestions(3, 2)).collect::<Vec<_>>();
+    let suggested = |token: &str| misses.iter().find(|miss| miss.token == token).unwrap().suggestions.clone();
+    assert_eq!(suggested("lazzy"), ["lazy"]);
+    assert_eq!(suggested("wrld")[0], "world");
+    assert_eq!(suggested("quikc")[0], "quick");
+    assert_eq!(suggested("naïev")[0], "naïve");
+
+    for miss in &misses {
+        assert!(miss.suggestions.len() <= 3);
+        let fuzzy = dawg.fuzzy_search(miss.token, 2);
+        assert!(miss.suggestions.iter().all(|word| fuzzy.iter().any(|(found, _)| found == word)), "{}", miss.token);
+        // closest first
+        let distances = miss.suggestions.iter().map(|word| fuzzy.iter().find(|(found, _)| found == word).unwrap().1).collect::<Vec<_>>();
+        assert!(distances.windows(2).all(|pair| pair[0] <= pair[1]));
+    }
+
+    let folded = build_with(&["hello"], DawgOptions::new().case_folding(true));
+    let misses = folded.check_text("HeLLo Helo", CheckOptions::new().suggestions(1, 1)).map(|miss| (miss.token, miss.suggestions)).collect::<Vec<_>>();
+    assert_eq!(misses, [("Helo", vec![String::from("hello")])]);
+}
@@ -0,0 +1,85 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, words};
+use dawging::UnsyncDawg;
+
+/// Nodes of the plain trie of `words`: one per distinct prefix, the empty one (the root) included
+fn trie_nodes(words: &[&str]) -> usize {
+    let prefixes = words.iter().flat_map(|word| word.char_indices().map(|(at, _)| &word[..at]).chain([*word])).collect::<BTreeSet<_>>();
+    prefixes.len()
+}
+
+#[test]
+fn shared_suffixes_compress_well_above_one() {
+    let words = ["bat", "bats", "cat", "cats", "hat", "hats", "tap", "taps", "top", "tops"];
+    let stats = build(&words).compression_stats();
+
+    assert_eq!(stats.trie_nodes, trie_nodes(&words));
+    assert_eq!(stats.trie_edges, stats.trie_nodes - 1);
+    assert!(stats.ratio > 2.0, "{stats:?}");
+    assert!(stats.bytes_saved > 0);
+}
+
+#[test]
+fn words_without_shared_suffixes_compress_to_about_one() {
+    let words = ["abc", "def", "ghi"];
+    let stats = build(&words).compression_stats();
+
+    assert_eq!(stats.trie_nodes, 10);
+    // only the three last letters end on the same node
+    assert_eq!(stats.dawg_nodes, 8);
+    assert!(stats.ratio < 1.3, "{stats:?}");
+}
+
+#[test]
+fn a_single_word_is_not_compressed() {
+    let stats = build(&["abc"]).compression_stats();
+    assert_eq!((stats.dawg_nodes, stats.trie_nodes), (4, 4));
+    assert_eq!((stats.dawg_edges, stats.trie_edges), (3, 3));
+    assert_eq!((stats.ratio, stats.bytes_saved), (1.0, 0));
+}
+
+#[test]
+fn the_empty_dawg_holds_only_its_root() {
+    let stats = build::<&str>(&[]).compression_stats();
+    assert_eq!((stats.dawg_nodes, stats.dawg_edges, stats.trie_nodes, stats.trie_edges), (1, 0, 1, 0));
+}
+
+#[test]
+fn the_trie_is_counted_while_building() {
+    let mut dawg = UnsyncDawg::new();
+    for (added, word) in ["ab", "abc", "b"].into_iter().enumerate() {
+        dawg.try_add(word).unwrap();
+        checked(&dawg);
+        assert_eq!(dawg.compression_stats().trie_nodes, [3, 4, 5][added]);
+    }
+}
+
+#[test]
+fn the_fixture_compresses() {
+    let words = words();
+    let stats = build(&words).compression_stats();
+
+    assert_eq!(stats.trie_nodes, trie_nodes(&words.iter().map(String::as_str).collect::<Vec<_>>()));
+    assert!(stats.dawg_nodes < stats.trie_nodes);
+    assert!(stats.ratio > 1.5, "{stats:?}");
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_is_minimized_as_much() {
+    let words = words();
+    let mut dawg = dawging::SyncDawg::new_sync();
+    for word in &words {
+        dawg.try_add(word).unwrap();
+    }
+    dawg.finish();
+    checked(&dawg);
+
+    let (sync, unsync) = (dawg.compression_stats(), build(&words).compression_stats());
+    assert_eq!((sync.dawg_nodes, sync.dawg_edges, sync.trie_nodes), (unsync.dawg_nodes, unsync.dawg_edges, unsync.trie_nodes));
+}
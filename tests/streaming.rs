@@ -0,0 +1,120 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{fs, io::{Cursor, Seek, Write}, path::{Path, PathBuf}};
+
+use common::{build_with, checked};
+use dawging::{codegen::CompileReport, dawg::{options::{DawgOptions, DuplicatePolicy, Granularity, SortDirection}, streaming::StreamingWriter}, error::OptionsConflict, file::FileWords, DawgError, UnsyncDawg};
+
+/// A directory of its own under the system's temporary one, for the spilled edges of a test
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("dawging-streaming-{name}-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// The fixture along with 40k words sharing few suffixes
+fn large() -> Vec<String> {
+    let mut words = (0..40_000u32).map(|n| format!("{:x}{}", n.wrapping_mul(2_654_435_761), n % 977)).collect::<Vec<_>>();
+    words.extend(common::words());
+    words.push(String::new());
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Streams `words` after a few bytes of something else, returning the bytes of the dawg, the report and the peak memory
+fn stream<S: AsRef<str>>(words: &[S], options: DawgOptions, dir: &Path) -> (Vec<u8>, CompileReport, usize) {
+    let mut out = Cursor::new(b"xyz".to_vec());
+    out.seek(std::io::SeekFrom::End(0)).unwrap();
+    let mut writer = StreamingWriter::new_in(&mut out, options, dir).unwrap();
+    for word in words {
+        writer.add(word.as_ref()).unwrap();
+    }
+    let peak = writer.peak_memory_bytes();
+    let report = writer.finish().unwrap();
+    assert_eq!(out.stream_position().unwrap() as usize, 3 + report.bytes);
+
+    let bytes = out.into_inner();
+    assert_eq!(&bytes[..3], b"xyz");
+    (bytes[3..].to_vec(), report, peak)
+}
+
+#[test]
+fn streamed_dawgs_load_as_the_dawgs_built_in_memory() {
+    let dir = temp_dir("equal");
+    let large = large();
+    let lists = [vec![], vec![String::new()], vec![String::from("a")], common::words(), large];
+
+    for words in &lists {
+        let (bytes, report, _) = stream(words, DawgOptions::new(), &dir);
+        // `build` would check the large list every 100 words
+        let mut built = UnsyncDawg::new();
+        built.add_all(words).unwrap();
+        built.finish();
+        let loaded = UnsyncDawg::<char>::from_bytes(&bytes).unwrap();
+        checked(&loaded);
+        assert_eq!(loaded.to_bytes().unwrap(), built.to_bytes().unwrap(), "{} words", words.len());
+        assert_eq!(FileWords::new(Cursor::new(&bytes)).unwrap().map(Result::unwrap).collect::<Vec<_>>(), *words);
+
+        assert_eq!((report.lines, report.words, report.skipped), (words.len(), words.len(), 0));
+        assert_eq!(report.nodes, built.visit_nodes().count());
+        assert_eq!(report.edges, built.visit_nodes().map(|view| view.edges().count()).sum::<usize>());
+        assert_eq!(report.bytes, bytes.len());
+    }
+    // the spilled edges are gone once the writers are done
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn peak_memory_is_that_of_the_register() {
+    let dir = temp_dir("memory");
+    let words = large();
+    let (_, report, peak) = stream(&words, DawgOptions::new(), &dir);
+
+    // a few words per node and edge written, far below the automaton held whole
+    assert!(peak <= report.nodes * 80 + report.edges * 16 + 4096, "{peak} bytes for {} nodes", report.nodes);
+    let (_, half, half_peak) = stream(&words[..words.len() / 2], DawgOptions::new(), &dir);
+    assert!(half_peak < peak && half.nodes < report.nodes);
+
+    // a long word only adds its path while pending
+    let long = ["a".repeat(500)];
+    let (bytes, report, peak) = stream(&long, DawgOptions::new(), &dir);
+    assert_eq!(report.nodes, 501);
+    assert!(peak >= 500 * std::mem::size_of::<char>() && peak < 500 * 200, "{peak}");
+    assert_eq!(UnsyncDawg::<char>::from_bytes(&bytes).unwrap().words().collect::<Vec<_>>(), long);
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn words_go_through_the_options() {
+    let dir = temp_dir("options");
+    let options = DawgOptions::new().case_folding(true);
+    let (bytes, report, _) = stream(&["Ab", "ab", "AC"], options.clone(), &dir);
+    assert_eq!((report.lines, report.words, report.skipped), (3, 2, 1));
+    assert_eq!(UnsyncDawg::<char>::from_bytes(&bytes).unwrap().to_bytes().unwrap(), build_with(&["ab", "ac"], options).to_bytes().unwrap());
+
+    let mut out = Cursor::new(vec![]);
+    let mut writer = StreamingWriter::new_in(&mut out, DawgOptions::new().duplicate_policy(DuplicatePolicy::Error), &dir).unwrap();
+    writer.add("b").unwrap();
+    assert!(matches!(writer.add("b"), Err(DawgError::Duplicate(_))));
+    assert!(matches!(writer.add("a"), Err(DawgError::Unsorted { .. })));
+    writer.add("c").unwrap();
+    writer.finish().unwrap();
+    assert_eq!(UnsyncDawg::<char>::from_bytes(out.get_ref()).unwrap().words().collect::<Vec<_>>(), ["b", "c"]);
+
+    let mut writer = StreamingWriter::new_in(Cursor::new(vec![]), DawgOptions::new().alphabet("ab"), &dir).unwrap();
+    assert!(matches!(writer.add("abc"), Err(DawgError::InvalidCharacter { .. })));
+    drop(writer);
+    let descending = DawgOptions::new().sort_direction(SortDirection::Descending);
+    assert!(matches!(StreamingWriter::new_in(Cursor::new(vec![]), descending, &dir), Err(DawgError::InvalidOptions(OptionsConflict::LetterOrder { .. }))));
+    // the labels written are chars
+    let bytes = DawgOptions::new().granularity(Granularity::Bytes);
+    assert!(matches!(StreamingWriter::new_in(Cursor::new(vec![]), bytes, &dir), Err(DawgError::InvalidOptions(OptionsConflict::LabelGranularity { .. }))));
+
+    assert_eq!(fs::read_dir(&dir).unwrap().count(), 0);
+    fs::remove_dir_all(&dir).unwrap();
+    out.flush().unwrap();
+}
@@ -0,0 +1,121 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build_with, checked};
+use dawging::{dawg::{map::WeightedDawg, metadata::Metadata, options::DawgOptions, subset::SubsetFilter, tsv::TsvOptions}, DawgError, UnsyncDawg, UnsyncWrapper};
+
+/// The fixture with its name, as a shipped dictionary would be
+fn options() -> DawgOptions {
+    DawgOptions::new().metadata(Metadata::new().name("fixture"))
+}
+
+/// The dawg built from `words` alone, saved
+fn rebuilt<S: AsRef<str>>(words: &[S], options: DawgOptions) -> Vec<u8> {
+    build_with(words, options).to_bytes().unwrap()
+}
+
+fn subset(dawg: &UnsyncDawg, filter: SubsetFilter<'_>) -> (usize, Vec<u8>) {
+    let mut bytes = vec![];
+    let count = dawg.save_subset(&mut bytes, filter).unwrap();
+    (count, bytes)
+}
+
+/// A weight for every word, with many ties
+fn weight(word: &str) -> u64 {
+    word.bytes().fold(7u64, |weight, byte| weight.wrapping_mul(31).wrapping_add(byte as u64)) % 50
+}
+
+#[test]
+fn prefix_subsets_are_the_dawgs_of_their_words() {
+    let words = common::words();
+    let dawg = build_with(&words, options());
+    for prefix in ["", "a", "ab", "st", "é", "zzzz"] {
+        let expected = words.iter().filter(|word| word.starts_with(prefix)).cloned().collect::<Vec<_>>();
+        let (count, bytes) = subset(&dawg, SubsetFilter::Prefix(prefix.into()));
+        assert_eq!(count, expected.len());
+        assert_eq!(bytes, rebuilt(&expected, options()), "{prefix:?}");
+
+        let loaded = UnsyncDawg::<char>::load(&bytes[..]).unwrap();
+        checked(&loaded);
+        assert_eq!(loaded.words().collect::<Vec<_>>(), expected);
+        assert_eq!(loaded.metadata().get_name(), Some("fixture"));
+    }
+    assert_eq!(subset(&dawg, SubsetFilter::Prefix(String::new())).1, dawg.to_bytes().unwrap());
+}
+
+#[test]
+fn rank_and_predicate_subsets_are_those_of_brute_force() {
+    let words = common::words();
+    let dawg = build_with(&words, options());
+    for ranks in [0..0, 0..1, 100..600, 2000..words.len(), 0..words.len() + 10] {
+        let expected = &words[ranks.start.min(words.len())..ranks.end.min(words.len())];
+        let (count, bytes) = subset(&dawg, SubsetFilter::Ranks(ranks.clone()));
+        assert_eq!((count, bytes), (expected.len(), rebuilt(expected, options())), "{ranks:?}");
+    }
+
+    let keep = |word: &str| word.len() % 3 == 0 || word.contains('é');
+    let expected = words.iter().filter(|word| keep(word)).cloned().collect::<Vec<_>>();
+    let (count, bytes) = subset(&dawg, SubsetFilter::predicate(keep));
+    assert_eq!((count, bytes), (expected.len(), rebuilt(&expected, options())));
+    checked(&UnsyncDawg::<char>::load(&rebuilt(&expected, options())[..]).unwrap());
+}
+
+#[test]
+fn top_weighted_subsets_break_ties_by_rank() {
+    let words = common::words();
+    let text = words.iter().map(|word| format!("{word}\t{}\n", weight(word))).collect::<String>();
+    let (map, _) = WeightedDawg::<UnsyncWrapper>::from_tsv(text.as_bytes(), &TsvOptions::frequencies()).unwrap();
+    checked(map.dawg());
+    let weights = map.iter().map(|(_, weight)| *weight).collect::<Vec<_>>();
+
+    for n in [0, 1, 50, 1000, words.len(), 100_000] {
+        let mut ranks = (0..words.len()).collect::<Vec<_>>();
+        ranks.sort_by_key(|&rank| (std::cmp::Reverse(weight(&words[rank])), rank));
+        ranks.truncate(n);
+        ranks.sort();
+        let expected = ranks.iter().map(|&rank| words[rank].clone()).collect::<Vec<_>>();
+
+        let mut bytes = vec![];
+        assert_eq!(map.save_top(&mut bytes, n).unwrap(), expected.len());
+        assert_eq!(bytes, rebuilt(&expected, DawgOptions::new()), "{n}");
+        let (count, direct) = subset(map.dawg(), SubsetFilter::TopWeighted { weights: &weights, n });
+        assert_eq!((count, direct), (expected.len(), bytes));
+    }
+
+    let error = map.dawg().save_subset(vec![], SubsetFilter::TopWeighted { weights: &[1, 2], n: 1 }).unwrap_err();
+    assert!(matches!(error, DawgError::InvalidValue(_)));
+}
+
+#[test]
+fn subsets_are_saved_the_same_every_time() {
+    let words = common::words();
+    let dawg = build_with(&words, options());
+    let again = UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    let filters = || [SubsetFilter::Prefix("co".into()), SubsetFilter::Ranks(10..900), SubsetFilter::predicate(|word: &str| word.ends_with('s'))];
+    for (first, second) in filters().into_iter().zip(filters()) {
+        let first = subset(&dawg, first);
+        assert_eq!(first, subset(&dawg, second));
+    }
+    for (first, second) in filters().into_iter().zip(filters()) {
+        assert_eq!(subset(&dawg, first), subset(&again, second));
+    }
+
+    // the word count written along with the dawg is that of the subset
+    let mut counted = vec![];
+    dawg.save_with_metadata(&mut counted, &Metadata::new()).unwrap();
+    let counted = UnsyncDawg::<char>::load(&counted[..]).unwrap();
+    let (count, bytes) = subset(&counted, SubsetFilter::Prefix("b".into()));
+    assert_eq!(UnsyncDawg::<char>::load(&bytes[..]).unwrap().metadata().get_word_count(), Some(count));
+}
+
+#[test]
+fn unfinished_dawgs_have_no_subsets() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("a").unwrap();
+    assert!(matches!(dawg.save_subset(vec![], SubsetFilter::Prefix("a".into())), Err(DawgError::NotFinished)));
+    dawg.finish();
+    let (count, bytes) = subset(&dawg, SubsetFilter::Prefix("b".into()));
+    assert_eq!(count, 0);
+    assert_eq!(UnsyncDawg::<char>::from_bytes(&bytes).unwrap().words().count(), 0);
+}
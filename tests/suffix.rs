@@ -0,0 +1,159 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::{bidawg::BiDawg, options::DawgOptions}, Dawg, DawgError, UnsyncDawg, UnsyncWrapper};
+
+/// The words of the fixture along with the empty word and words of several scripts
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["", "aé字b", "ééé"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Suffixes of every length from some of the words, whole words, and suffixes found nowhere
+fn suffixes(words: &[String]) -> Vec<String> {
+    let mut suffixes = ["", "ing", "e", "é", "字b", "éé", "qqqq", "s", "ed"].map(String::from).to_vec();
+    for word in words.iter().step_by(53) {
+        let letters = word.chars().collect::<Vec<_>>();
+        for len in 1..=letters.len().min(4) {
+            suffixes.push(letters[letters.len() - len..].iter().collect());
+        }
+        suffixes.push(word.clone());
+    }
+    suffixes
+}
+
+fn brute_force(words: &[String], suffix: &str) -> Vec<String> {
+    words.iter().filter(|word| word.ends_with(suffix)).cloned().collect()
+}
+
+/// Asserts the suffix queries of `dawg` are those of brute force over `words`
+fn assert_suffixes(dawg: &UnsyncDawg, words: &[String]) {
+    for suffix in suffixes(words) {
+        let expected = brute_force(words, &suffix);
+        assert_eq!(dawg.words_with_suffix(&suffix), expected, "{suffix:?}");
+        assert_eq!(dawg.count_suffix(&suffix), expected.len(), "{suffix:?}");
+    }
+}
+
+#[test]
+fn suffix_queries_are_those_of_brute_force() {
+    let words = words();
+    let mut dawg = build(&words);
+    assert_suffixes(&dawg, &words);
+
+    dawg.build_reverse_index().unwrap();
+    checked(&dawg);
+    assert_suffixes(&dawg, &words);
+
+    // as a reversed dawg would give them
+    let bidawg = BiDawg::<UnsyncWrapper>::from_words(&words, DawgOptions::new()).unwrap();
+    for suffix in suffixes(&words) {
+        assert_eq!(dawg.words_with_suffix(&suffix), bidawg.words_with_suffix(&suffix), "{suffix:?}");
+    }
+}
+
+#[test]
+fn the_index_reports_its_memory() {
+    let mut dawg = build(&words());
+    let mut unfinished = UnsyncDawg::new();
+    unfinished.try_add("a").unwrap();
+    assert!(matches!(unfinished.build_reverse_index(), Err(DawgError::NotFinished)));
+    assert!(dawg.reverse_index().is_none());
+
+    dawg.build_reverse_index().unwrap();
+    let (nodes, edges) = dawg.visit_nodes().fold((0, 0), |(nodes, edges), view| (nodes + 1, edges + view.edges().count()));
+    let index = dawg.reverse_index().unwrap();
+    assert_eq!(index.node_count(), nodes);
+    assert!(index.memory_bytes() >= 8 * edges + 12 * nodes);
+    assert!(index.memory_bytes() < 2 * (8 * edges + 16 * nodes) + 1024, "{}", index.memory_bytes());
+}
+
+#[test]
+fn edits_drop_the_index() {
+    let words = words();
+    let mut dawg = build(&words);
+    dawg.build_reverse_index().unwrap();
+    assert!(dawg.fork().reverse_index().is_some() && dawg.clone().reverse_index().is_some());
+
+    let mut removed = dawg.clone();
+    assert!(removed.remove("ééé").unwrap());
+    checked(&removed);
+    assert!(removed.reverse_index().is_none());
+    let rest = words.iter().filter(|word| *word != "ééé").cloned().collect::<Vec<_>>();
+    assert_suffixes(&removed, &rest);
+    removed.build_reverse_index().unwrap();
+    assert_suffixes(&removed, &rest);
+
+    let mut inserted = dawg.clone();
+    inserted.insert("zzzzing").unwrap();
+    checked(&inserted);
+    assert!(inserted.reverse_index().is_none());
+    assert_eq!(inserted.count_suffix("zzing"), 1);
+
+    dawg.drop_reverse_index();
+    assert!(dawg.reverse_index().is_none());
+    assert_suffixes(&dawg, &words);
+}
+
+#[test]
+fn the_index_is_saved_along_with_the_dawg() {
+    let words = words();
+    let mut dawg = build(&words);
+    dawg.build_reverse_index().unwrap();
+    let bytes = dawg.to_bytes_with_reverse_index().unwrap();
+    let mut saved = vec![];
+    dawg.save_with_reverse_index(&mut saved).unwrap();
+    assert_eq!(saved, bytes);
+
+    let loaded = UnsyncDawg::load_with_reverse_index(&bytes[..]).unwrap();
+    checked(&loaded);
+    assert_eq!(loaded.reverse_index(), dawg.reverse_index());
+    assert_suffixes(&loaded, &words);
+
+    // an index is built for the dawgs saved without one
+    let mut edited = dawg.clone();
+    edited.insert("zzzzing").unwrap();
+    let loaded = UnsyncDawg::from_bytes_with_reverse_index(&edited.to_bytes_with_reverse_index().unwrap()).unwrap();
+    assert!(loaded.reverse_index().is_some());
+    assert_eq!(loaded.words_with_suffix("zzzing"), ["zzzzing"]);
+}
+
+#[test]
+fn damaged_indexes_are_refused() {
+    let bytes = build(&words()).to_bytes_with_reverse_index().unwrap();
+    let len = bytes.len();
+    let refused = |bytes: &[u8]| UnsyncDawg::from_bytes_with_reverse_index(bytes).is_err();
+
+    // the parent of the last edge past the nodes
+    let mut parent = bytes.clone();
+    parent[len - 1] = 0xff;
+    assert!(refused(&parent));
+    // the label of the last edge not a char
+    let mut label = bytes.clone();
+    label[len - 8..len - 4].copy_from_slice(&0xd800u32.to_le_bytes());
+    assert!(refused(&label));
+    let mut magic = bytes.clone();
+    magic[0] = b'X';
+    assert!(refused(&magic));
+    assert!(refused(&bytes[..len - 3]));
+    assert!(refused(&build(&words()).to_bytes().unwrap()));
+}
+
+#[test]
+fn any_labels_have_suffixes() {
+    let mut dawg = Dawg::<UnsyncWrapper, u8>::default();
+    for sequence in [&[1, 2, 3][..], &[2, 3], &[4]] {
+        dawg.add_sequence(sequence).unwrap();
+    }
+    dawg.finish();
+    dawg.build_reverse_index().unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.sequences_with_suffix(&[2, 3]), [vec![1, 2, 3], vec![2, 3]]);
+    assert_eq!(dawg.count_sequences_with_suffix(&[3]), 2);
+    assert_eq!(dawg.count_sequences_with_suffix(&[]), 3);
+}
@@ -0,0 +1,188 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, xorshift};
+use dawging::{dawg::table::{Alphabet, TransitionTable}, UnsyncDawg};
+
+/// A table read back from an emitter: the symbols of the columns as code points (or bytes), the transitions row by row,
+/// whether each state is terminal, the start state and the dead state
+struct Plain {
+    symbols: Vec<u32>,
+    bytes: bool,
+    transitions: Vec<u32>,
+    terminal: Vec<bool>,
+    start: usize,
+}
+
+impl Plain {
+    /// Runs the table over `word` with array lookups alone
+    fn accepts(&self, word: &str) -> bool {
+        let symbols = match self.bytes {
+            true => word.bytes().map(u32::from).collect::<Vec<_>>(),
+            false => word.chars().map(u32::from).collect(),
+        };
+        let dead = self.terminal.len() - 1;
+        let mut state = self.start;
+        for symbol in symbols {
+            state = match self.symbols.binary_search(&symbol) {
+                Ok(column) => self.transitions[state * self.symbols.len() + column] as usize,
+                Err(_) => dead,
+            };
+        }
+        self.terminal[state]
+    }
+}
+
+/// The table as it is kept, read through its slices
+fn from_table(table: &TransitionTable) -> Plain {
+    let (symbols, bytes) = match table.alphabet() {
+        Alphabet::Chars(letters) => (letters.iter().map(|letter| *letter as u32).collect(), false),
+        Alphabet::Bytes(bytes) => (bytes.iter().map(|byte| *byte as u32).collect(), true),
+    };
+    let terminal = (0..table.state_count()).map(|state| table.terminals()[state / 64] >> (state % 64) & 1 == 1).collect();
+    Plain { symbols, bytes, transitions: table.transitions().to_vec(), terminal, start: table.start() as usize }
+}
+
+/// The table parsed back from `write_binary`
+fn from_binary(table: &TransitionTable) -> Plain {
+    let mut bytes = vec![];
+    table.write_binary(&mut bytes).unwrap();
+    assert_eq!((&bytes[..4], u16::from_le_bytes([bytes[4], bytes[5]])), (&b"DAWX"[..], 1));
+    let u32_at = |at: usize| u32::from_le_bytes(bytes[at..at + 4].try_into().unwrap());
+    let byte_symbols = bytes[6] == 1;
+    let (states, columns, start, dead) = (u32_at(7) as usize, u32_at(11) as usize, u32_at(15) as usize, u32_at(19) as usize);
+    assert_eq!(dead, states - 1);
+
+    let mut at = 23;
+    let symbols = match byte_symbols {
+        true => bytes[at..at + columns].iter().map(|byte| *byte as u32).collect(),
+        false => (0..columns).map(|column| u32_at(at + 4 * column)).collect(),
+    };
+    at += if byte_symbols { columns } else { 4 * columns };
+    let transitions = (0..states * columns).map(|cell| u32_at(at + 4 * cell)).collect();
+    at += 4 * states * columns;
+    let words = bytes[at..].chunks(8).map(|word| u64::from_le_bytes(word.try_into().unwrap())).collect::<Vec<_>>();
+    assert_eq!(words.len(), states.div_ceil(64));
+    let terminal = (0..states).map(|state| words[state / 64] >> (state % 64) & 1 == 1).collect();
+    Plain { symbols, bytes: byte_symbols, transitions, terminal, start }
+}
+
+/// The table parsed back from `write_csv`
+fn from_csv(table: &TransitionTable) -> Plain {
+    let mut text = vec![];
+    table.write_csv(&mut text).unwrap();
+    let text = String::from_utf8(text).unwrap();
+    let mut lines = text.lines();
+    let header = lines.next().unwrap().split(',').collect::<Vec<_>>();
+    assert_eq!(header[..2], ["state", "terminal"]);
+    let bytes = header.get(2).is_some_and(|symbol| symbol.starts_with("0x"));
+    let symbols = header[2..].iter().map(|symbol| u32::from_str_radix(&symbol[2..], 16).unwrap()).collect::<Vec<_>>();
+
+    let (mut transitions, mut terminal) = (vec![], vec![]);
+    for (state, line) in lines.enumerate() {
+        let cells = line.split(',').map(|cell| cell.parse::<u32>().unwrap()).collect::<Vec<_>>();
+        assert_eq!((cells[0] as usize, cells.len()), (state, symbols.len() + 2));
+        terminal.push(cells[1] == 1);
+        transitions.extend(&cells[2..]);
+    }
+    Plain { symbols, bytes, transitions, terminal, start: 0 }
+}
+
+fn words() -> Vec<String> {
+    let mut words = common::words();
+    words.extend(["é", "éa", "èb", "ë", "字字", "字"].map(String::from));
+    words.sort();
+    words.dedup();
+    words
+}
+
+/// Asserts every form of both tables of `dawg` accepts its words and nothing else
+fn assert_simulated(dawg: &UnsyncDawg, words: &[String], state: &mut u64) {
+    let tables = [dawg.to_transition_table(), dawg.to_byte_transition_table()];
+    let plains = tables.iter().flat_map(|table| [from_table(table), from_binary(table), from_csv(table)]).collect::<Vec<_>>();
+
+    let letters = "abcdefghijklmnopqrstuvwxyzéè字ë!".chars().collect::<Vec<_>>();
+    let mut probes = words.to_vec();
+    for word in words.iter().step_by(5) {
+        probes.extend(word.char_indices().map(|(at, _)| word[..at].to_string()));
+        probes.push(format!("{word}s"));
+    }
+    probes.extend((0..5000).map(|_| (0..xorshift(state) % 9).map(|_| letters[(xorshift(state) % letters.len() as u64) as usize]).collect::<String>()));
+
+    for probe in &probes {
+        let expected = dawg.contains(probe);
+        assert!(tables.iter().all(|table| table.accepts(probe) == expected), "{probe:?}");
+        assert!(plains.iter().all(|plain| plain.accepts(probe) == expected), "{probe:?}");
+    }
+    assert!(probes.iter().filter(|probe| !dawg.contains(probe)).count() > 4000);
+}
+
+#[test]
+fn simulated_tables_accept_the_words_alone() {
+    let words = words();
+    let dawg = build(&words);
+    assert_simulated(&dawg, &words, &mut 12345);
+}
+
+#[test]
+fn states_and_the_dead_state() {
+    let dawg = build(&words());
+    let (letters, bytes) = (dawg.to_transition_table(), dawg.to_byte_transition_table());
+    assert_eq!(letters.state_count(), dawg.visit_nodes().count() + 1);
+    assert!(bytes.state_count() > letters.state_count());
+    assert!(matches!(bytes.alphabet(), Alphabet::Bytes(symbols) if symbols.len() <= 256));
+
+    for table in [&letters, &bytes] {
+        let dead = table.dead();
+        assert_eq!((table.start(), dead as usize), (0, table.state_count() - 1));
+        assert!(!table.is_terminal(dead));
+        assert!((0..table.alphabet().len()).all(|column| table.next(dead, column) == dead));
+        assert_eq!(table.transitions().len(), table.state_count() * table.alphabet().len());
+    }
+    assert_eq!(letters.alphabet().column_of_char('a'), Some(0));
+    assert_eq!(letters.alphabet().column_of_char('!'), None);
+    assert_eq!(bytes.alphabet().column_of_byte(0xff), None);
+    // the states of the letters of several bytes are never terminal
+    let node_states = letters.state_count() - 1;
+    assert!((node_states..bytes.state_count()).all(|state| !bytes.is_terminal(state as u32)));
+}
+
+#[test]
+fn bytes_keep_large_alphabets_narrow() {
+    let words = ('一'..='\u{9fff}').step_by(7).take(500).map(String::from).collect::<Vec<_>>();
+    let dawg = build(&words);
+    let (letters, bytes) = (dawg.to_transition_table(), dawg.to_byte_transition_table());
+    assert_eq!(letters.alphabet().len(), 500);
+    assert!(bytes.alphabet().len() * 3 < letters.alphabet().len(), "{} bytes", bytes.alphabet().len());
+    assert_simulated(&dawg, &words, &mut 3);
+}
+
+#[test]
+fn tables_follow_edits() {
+    let mut words = words();
+    let mut dawg = build(&words);
+    for word in ["zzz字", "éé", "q"] {
+        dawg.insert(word).unwrap();
+        words.push(word.to_string());
+    }
+    dawg.remove("字字").unwrap();
+    words.retain(|word| word != "字字");
+    words.sort();
+    checked(&dawg);
+    assert_simulated(&dawg, &words, &mut 99);
+}
+
+#[test]
+fn empty_dawgs_have_tables() {
+    let empty = build::<&str>(&[]);
+    for table in [empty.to_transition_table(), empty.to_byte_transition_table()] {
+        assert!(!table.accepts("") && !table.accepts("a"));
+        assert_ne!(table.start(), table.dead());
+        assert!(table.alphabet().is_empty());
+    }
+    let only_empty = build(&[""]);
+    let table = only_empty.to_byte_transition_table();
+    assert!(table.accepts("") && !table.accepts("x"));
+    assert!(from_binary(&table).accepts("") && from_csv(&table).accepts(""));
+}
@@ -0,0 +1,97 @@
+#![cfg(all(feature = "unsync", feature = "testutil"))]
+
+mod common;
+
+use common::{build, checked};
+use dawging::{dawg::lexicon::{Lexicon, MatchMode}, testutil::{WordLengths, WordListGen, WordStyle}};
+
+const STYLES: [WordStyle; 3] = [WordStyle::Uniform, WordStyle::Markov { exponent: 1.0 }, WordStyle::Markov { exponent: 2.5 }];
+
+/// Whether `a` and `b` are one letter put in, taken out or replaced apart
+fn one_edit_apart(a: &str, b: &str) -> bool {
+    let (a, b) = (a.chars().collect::<Vec<_>>(), b.chars().collect::<Vec<_>>());
+    let (short, long) = if a.len() <= b.len() { (&a, &b) } else { (&b, &a) };
+    match long.len() - short.len() {
+        0 => short.iter().zip(long).filter(|(a, b)| a != b).count() == 1,
+        1 => (0..long.len()).any(|at| long[..at] == short[..at] && long[at + 1..] == short[at..]),
+        _ => false,
+    }
+}
+
+#[test]
+fn a_seed_gives_the_same_list() {
+    for style in STYLES {
+        let gen = WordListGen::new(42).style(style);
+        let words = gen.generate(3000);
+        assert_eq!(words, gen.clone().generate(3000));
+        assert_eq!(words, WordListGen::new(42).style(style).generate(3000));
+        assert_ne!(words, WordListGen::new(43).style(style).generate(3000), "{style:?}");
+        // a shorter list is not a prefix of the longer one, but drawn the same way
+        assert_eq!(gen.generate(100), gen.generate(100));
+    }
+}
+
+#[test]
+fn lists_are_sorted_distinct_and_of_the_requested_size() {
+    for style in STYLES {
+        let words = WordListGen::new(5).style(style).generate(5000);
+        assert_eq!(words.len(), 5000, "{style:?}");
+        assert!(words.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(words.iter().all(|word| (3..=10).contains(&word.chars().count())));
+        checked(&build(&words));
+    }
+
+    let weighted = WordListGen::new(7).alphabet("xyz".chars()).lengths(WordLengths::Weighted(vec![0, 0, 1, 0, 5])).generate(50);
+    assert_eq!(weighted.len(), 50);
+    assert!(weighted.iter().all(|word| word.len() == 2 || word.len() == 4));
+    assert!(weighted.iter().all(|word| word.chars().all(|letter| "xyz".contains(letter))));
+    assert_eq!(WordListGen::new(9).generate(100_000).len(), 100_000);
+}
+
+#[test]
+fn short_lists_when_there_are_not_enough_words() {
+    // 2 + 4 + 8 words of 1 to 3 letters
+    let small = WordListGen::new(7).alphabet("abba".chars()).lengths(WordLengths::Uniform { min: 1, max: 3 });
+    assert_eq!(small.get_alphabet(), ['a', 'b']);
+    assert_eq!(small.capacity(), 14);
+    assert_eq!(small.generate(10).len(), 10);
+    let all = small.generate(100);
+    assert_eq!(all.len(), 14);
+    assert_eq!(all[..4], ["a", "aa", "aaa", "aab"]);
+
+    assert_eq!(WordListGen::new(0).alphabet([]).lengths(WordLengths::Uniform { min: 0, max: 4 }).generate(5), [""]);
+    assert_eq!(WordListGen::new(0).capacity(), (3..=10).map(|len| 26usize.pow(len)).sum());
+    assert_eq!(WordListGen::new(0).lengths(WordLengths::Uniform { min: 1, max: 20 }).capacity(), usize::MAX);
+}
+
+#[test]
+fn a_high_exponent_skews_the_letters() {
+    let share = |exponent: f64| {
+        let words = WordListGen::new(1).style(WordStyle::Markov { exponent }).generate(2000);
+        let mut firsts = [0; 26];
+        for word in &words {
+            firsts[(word.as_bytes()[0] - b'a') as usize] += 1;
+        }
+        *firsts.iter().max().unwrap() as f64 / words.len() as f64
+    };
+    assert!(share(3.0) > 2.0 * share(0.0));
+}
+
+#[test]
+fn near_misses_are_one_edit_from_a_word_and_not_in_the_lexicon() {
+    for style in STYLES {
+        let gen = WordListGen::new(11).style(style);
+        let words = gen.generate(2000);
+        let dawg = build(&words);
+        let misses = gen.near_misses(&dawg, 500);
+        assert_eq!(misses.len(), 500);
+        assert_eq!(misses, gen.near_misses(&dawg, 500));
+        assert!(misses.windows(2).all(|pair| pair[0] < pair[1]));
+
+        for miss in &misses {
+            assert!(!Lexicon::contains(&dawg, miss, MatchMode::Exact) && !dawg.contains(miss) && words.binary_search(miss).is_err(), "{miss}");
+            assert!(words.iter().any(|word| one_edit_apart(word, miss)), "{miss}");
+        }
+    }
+    assert!(WordListGen::new(11).near_misses(&build::<&str>(&[]), 10).is_empty());
+}
@@ -0,0 +1,176 @@
+#![cfg(all(feature = "tracing", feature = "unsync"))]
+
+mod common;
+
+use std::{collections::HashMap, fmt::Debug, sync::{Arc, Mutex}};
+
+use common::{build, words};
+use dawging::{dawg::{compiled::CompiledDawg, options::DawgOptions, wordlist::Utf8Policy}, UnsyncDawg};
+use tracing::{field::{Field, Visit}, span::{Attributes, Id, Record}, Event, Level, Metadata, Subscriber};
+
+/// A span or an event seen by `Capture`: its name, level, the span it happened in and the fields it was given
+#[derive(Debug, Clone)]
+struct Seen {
+    name: String,
+    level: Level,
+    parent: Option<String>,
+    fields: HashMap<String, String>,
+}
+
+impl Seen {
+    fn u64(&self, field: &str) -> u64 {
+        self.fields.get(field).unwrap_or_else(|| panic!("{} has no {field}: {:?}", self.name, self.fields)).parse().unwrap()
+    }
+}
+
+impl Visit for Seen {
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.fields.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name().to_string(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        self.fields.insert(field.name().to_string(), format!("{value:?}"));
+    }
+}
+
+/// Subscriber keeping every span and event, the span `n` being at `spans[n - 1]`
+#[derive(Clone, Default)]
+struct Capture {
+    spans: Arc<Mutex<Vec<Seen>>>,
+    events: Arc<Mutex<Vec<Seen>>>,
+    entered: Arc<Mutex<Vec<u64>>>,
+}
+
+impl Capture {
+    fn seen(&self, metadata: &Metadata<'_>) -> Seen {
+        let spans = self.spans.lock().unwrap();
+        let parent = self.entered.lock().unwrap().last().map(|id| spans[*id as usize - 1].name.clone());
+        Seen { name: metadata.name().to_string(), level: *metadata.level(), parent, fields: HashMap::new() }
+    }
+
+    /// Runs `f` with this subscriber, returning what it saw
+    fn run<R>(f: impl FnOnce() -> R) -> (R, Vec<Seen>, Vec<Seen>) {
+        let capture = Capture::default();
+        let value = tracing::subscriber::with_default(capture.clone(), f);
+        let spans = capture.spans.lock().unwrap().clone();
+        let events = capture.events.lock().unwrap().clone();
+        (value, spans, events)
+    }
+}
+
+impl Subscriber for Capture {
+    fn enabled(&self, _: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes<'_>) -> Id {
+        let mut seen = self.seen(span.metadata());
+        span.record(&mut seen);
+        let mut spans = self.spans.lock().unwrap();
+        spans.push(seen);
+        Id::from_u64(spans.len() as u64)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        values.record(&mut self.spans.lock().unwrap()[span.into_u64() as usize - 1]);
+    }
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event<'_>) {
+        let mut seen = self.seen(event.metadata());
+        event.record(&mut seen);
+        self.events.lock().unwrap().push(seen);
+    }
+
+    fn enter(&self, span: &Id) {
+        self.entered.lock().unwrap().push(span.into_u64());
+    }
+
+    fn exit(&self, _: &Id) {
+        self.entered.lock().unwrap().pop();
+    }
+}
+
+fn span<'a>(spans: &'a [Seen], name: &str) -> &'a Seen {
+    spans.iter().find(|span| span.name == name).unwrap_or_else(|| panic!("no {name} span in {spans:?}"))
+}
+
+#[test]
+fn a_build_from_a_reader_is_one_span_around_the_finish() {
+    let words = words();
+    let text = words.join("\n");
+    let ((dawg, report), spans, _) = Capture::run(|| UnsyncDawg::from_reader(text.as_bytes(), DawgOptions::new(), Utf8Policy::Error).unwrap());
+
+    // coarse: nothing per word or per letter
+    assert_eq!(spans.iter().map(|span| span.name.as_str()).collect::<Vec<_>>(), ["from_reader", "finish"]);
+    let (from_reader, finish) = (span(&spans, "from_reader"), span(&spans, "finish"));
+    assert!(spans.iter().all(|span| span.level == Level::INFO && span.fields.contains_key("duration_us")));
+
+    assert_eq!(from_reader.u64("words"), report.words as u64);
+    assert_eq!(from_reader.u64("nodes_created") + 1, dawg.compression_stats().trie_nodes as u64);
+    assert_eq!(finish.parent.as_deref(), Some("from_reader"));
+    assert_eq!(finish.u64("words"), words.len() as u64);
+    assert_eq!(finish.u64("nodes"), dawg.visit_nodes().count() as u64);
+    assert!(finish.u64("nodes") < from_reader.u64("nodes_created"));
+}
+
+#[test]
+fn add_all_counts_the_distinct_words_and_the_nodes_it_created() {
+    let mut dawg = UnsyncDawg::new();
+    let (added, spans, _) = Capture::run(|| dawg.add_all(["cats", "cat", "dog", "cat", "dot"]).unwrap());
+
+    assert_eq!(spans.len(), 1);
+    let add_all = span(&spans, "add_all");
+    assert_eq!(add_all.u64("words"), added as u64);
+    assert_eq!(added, 4);
+    // c-a-t-s, d-o-g and the t of dot
+    assert_eq!(add_all.u64("nodes_created"), 8);
+    assert!(add_all.fields.contains_key("duration_us"));
+}
+
+#[test]
+fn saves_and_loads_give_their_byte_counts() {
+    let dawg = build(&words());
+    let (bytes, spans, _) = Capture::run(|| {
+        let mut bytes = vec![];
+        dawg.save(&mut bytes).unwrap();
+        UnsyncDawg::<char>::load(bytes.as_slice()).unwrap();
+        bytes
+    });
+    assert_eq!(span(&spans, "save").u64("bytes"), bytes.len() as u64);
+    let load = span(&spans, "load");
+    assert_eq!(load.u64("bytes"), bytes.len() as u64);
+    assert_eq!(load.u64("nodes"), dawg.visit_nodes().count() as u64);
+
+    let compiled = dawg.compile();
+    let (bytes, spans, _) = Capture::run(|| {
+        let mut bytes = vec![];
+        compiled.save(&mut bytes).unwrap();
+        CompiledDawg::load(bytes.as_slice()).unwrap();
+        bytes
+    });
+    assert_eq!(span(&spans, "save").u64("bytes"), bytes.len() as u64);
+    assert_eq!(span(&spans, "load").u64("bytes"), bytes.len() as u64);
+}
+
+#[test]
+fn a_fuzzy_search_reports_the_nodes_it_visited() {
+    let dawg = build(&words());
+    let (found, spans, events) = Capture::run(|| dawg.fuzzy_search("hose", 1));
+
+    assert!(spans.is_empty());
+    assert_eq!(events.len(), 1);
+    let event = &events[0];
+    assert_eq!(event.level, Level::DEBUG);
+    assert_eq!(event.fields["search"], "fuzzy_search");
+    assert_eq!(event.u64("matches"), found.len() as u64);
+    // the search leaves most of the dawg alone, and never goes through a node of the trie twice
+    let visited = event.u64("nodes_visited");
+    assert!(visited > found.len() as u64, "{visited}");
+    assert!(visited < dawg.compression_stats().trie_nodes as u64 / 4, "{visited}");
+}
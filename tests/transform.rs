@@ -0,0 +1,140 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, words, xorshift};
+use dawging::{dawg::options::{DawgOptions, DuplicatePolicy}, DawgError};
+
+/// Up to 3000 distinct words of 1 to 12 letters out of "abcde'", sorted
+fn draw(state: &mut u64) -> Vec<String> {
+    let words: BTreeSet<String> = (0..3000)
+        .map(|_| (0..1 + xorshift(state) % 12).map(|_| b"abcde'"[(xorshift(state) % 6) as usize] as char).collect())
+        .collect();
+    words.into_iter().collect()
+}
+
+/// Keeps the words of `words` for which `keep` returns true in a dawg of them, checking the dawg against a plain filter
+fn retain_and_compare(words: &[String], keep: impl Fn(&str) -> bool) {
+    let mut dawg = build(words);
+    let nodes = dawg.visit_nodes().count();
+    let expected = words.iter().filter(|word| keep(word)).cloned().collect::<Vec<_>>();
+
+    assert_eq!(dawg.retain(|word| keep(word)), words.len() - expected.len());
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert!(dawg.verify_minimal());
+    assert!(dawg.is_finished());
+    assert_eq!(dawg.words().collect::<Vec<_>>(), expected);
+    assert_eq!(dawging::dawg::lexicon::Lexicon::word_count(&dawg), expected.len());
+    if expected.len() < words.len() {
+        assert!(dawg.visit_nodes().count() < nodes);
+    }
+}
+
+#[test]
+fn retain_by_length_keeps_the_filtered_words() {
+    let mut state = 7;
+    let words = draw(&mut state);
+    retain_and_compare(&words, |word| word.len() <= 6);
+    retain_and_compare(&words, |word| word.len() % 2 == 0);
+    retain_and_compare(&common::words(), |word| word.chars().count() > 4);
+}
+
+#[test]
+fn retain_by_letters_keeps_the_filtered_words() {
+    let mut state = 23;
+    let words = draw(&mut state);
+    retain_and_compare(&words, |word| !word.contains('\''));
+    retain_and_compare(&words, |word| word.starts_with('a') || word.ends_with('e'));
+    retain_and_compare(&words, |_| true);
+    retain_and_compare(&words, |_| false);
+}
+
+#[test]
+fn retain_on_a_dawg_being_built_lets_it_go_on() {
+    let mut dawg = dawging::UnsyncDawg::new();
+    dawg.try_add("ab").unwrap();
+    dawg.try_add("b").unwrap();
+    assert_eq!(dawg.retain(|word| word != "b"), 1);
+    assert!(!dawg.is_finished());
+    checked(&dawg);
+
+    dawg.try_add("c").unwrap();
+    dawg.finish();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["ab", "c"]);
+}
+
+#[test]
+fn retain_sequences_filters_other_labels() {
+    let mut dawg = dawging::Dawg::<dawging::UnsyncWrapper, u16>::default();
+    let sequences = [vec![1u16, 2], vec![1, 2, 3], vec![4, 2, 3], vec![500]];
+    for sequence in &sequences {
+        dawg.add_sequence(sequence).unwrap();
+    }
+    dawg.finish();
+
+    assert_eq!(dawg.retain_sequences(|sequence| sequence.len() > 2), 2);
+    checked(&dawg);
+    assert!(dawg.verify_minimal());
+    assert_eq!(dawg.sequences().collect::<Vec<_>>(), [vec![1, 2, 3], vec![4, 2, 3]]);
+}
+
+#[test]
+fn the_words_fixture_survives_a_retain_of_everything() {
+    let words = words();
+    let mut dawg = build(&words);
+    let bytes = dawg.to_bytes().unwrap();
+    assert_eq!(dawg.retain(|_| true), 0);
+    assert_eq!(dawg.to_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn mapping_every_word_to_itself_gives_the_same_dawg() {
+    let dawg = build(&words());
+    let mapped = dawg.map_words(|word| Some(word.to_string())).unwrap();
+    checked(&mapped);
+    assert_eq!(mapped.to_bytes().unwrap(), dawg.to_bytes().unwrap());
+}
+
+#[test]
+fn lower_casing_merges_the_case_variants() {
+    let dawg = build(&["Apple", "Banana", "apple", "banana", "cherry"]);
+    let lowered = dawg.map_words(|word| Some(word.to_lowercase())).unwrap();
+    checked(&lowered);
+    lowered.assert_minimal().unwrap();
+    assert_eq!(lowered.words().collect::<Vec<_>>(), ["apple", "banana", "cherry"]);
+
+    let options = DawgOptions::new().duplicate_policy(DuplicatePolicy::Error);
+    let dawg = common::build_with(&["Apple", "apple"], options);
+    assert!(matches!(dawg.map_words(|word| Some(word.to_lowercase())), Err(DawgError::Duplicate(_))));
+    assert!(matches!(dawg.map_words(|word| Some(word.to_uppercase())), Err(DawgError::Duplicate(_))));
+}
+
+#[test]
+fn dropping_words_shrinks_the_language() {
+    let words = words();
+    let dawg = build(&words);
+    let mapped = dawg.map_words(|word| (word.len() > 5).then(|| word.to_string())).unwrap();
+    checked(&mapped);
+    mapped.assert_minimal().unwrap();
+    assert!(mapped.words().eq(words.iter().filter(|word| word.len() > 5).cloned()));
+    assert!(mapped.visit_nodes().count() < dawg.visit_nodes().count());
+}
+
+#[test]
+fn words_mapped_out_of_order_are_sorted_again() {
+    let words = words();
+    let dawg = build(&words);
+    let reversed = dawg.map_words(|word| Some(word.chars().rev().collect())).unwrap();
+    checked(&reversed);
+    reversed.assert_minimal().unwrap();
+
+    let mut expected = words.iter().map(|word| word.chars().rev().collect::<String>()).collect::<Vec<_>>();
+    expected.sort();
+    expected.dedup();
+    assert_eq!(reversed.words().collect::<Vec<_>>(), expected);
+}
@@ -0,0 +1,80 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::checked;
+use dawging::{dawg::{map::{DawgMap, WeightedDawg}, options::DawgOptions, tsv::{TsvOptions, ValuePolicy}}, DawgError, UnsyncWrapper};
+
+type Frequencies = WeightedDawg<UnsyncWrapper>;
+
+#[test]
+fn a_frequency_list_gives_the_weight_of_each_word() {
+    let words = common::words();
+    let text = words.iter().enumerate().map(|(index, word)| format!("{word}\t{}\n", index * 31)).collect::<String>();
+    let (map, report) = Frequencies::from_tsv(text.as_bytes(), &TsvOptions::frequencies()).unwrap();
+    checked(map.dawg());
+    assert_eq!(report.words, words.len());
+    assert_eq!(map.len(), words.len());
+    assert!(words.iter().enumerate().all(|(index, word)| map.weight_of(word) == Some(index as u64 * 31)));
+    assert_eq!(map.weight_of("zzzz"), None);
+}
+
+#[test]
+fn the_header_and_the_columns_are_configurable() {
+    let text = "word,freq,lemma\nran,3,run\nrun,5,run\n";
+    let options = TsvOptions::frequencies().delimiter(',').header(true);
+    let (map, report) = Frequencies::from_tsv(text.as_bytes(), &options).unwrap();
+    assert_eq!((report.header, report.words), (1, 2));
+    assert_eq!(map.weight_of("run"), Some(5));
+
+    let options = TsvOptions::strings().delimiter(',').header(true).value_column(2);
+    let (lemmas, _) = DawgMap::<UnsyncWrapper, String>::from_tsv(text.as_bytes(), &options).unwrap();
+    assert_eq!(lemmas.get("ran").map(String::as_str), Some("run"));
+
+    let options = TsvOptions::strings().key_column(1).value_column(0);
+    let (reversed, _) = DawgMap::<UnsyncWrapper, String>::from_tsv("x\ta\ny\tb\n".as_bytes(), &options).unwrap();
+    assert_eq!(reversed.iter().collect::<Vec<_>>(), [("a".to_string(), &"x".to_string()), ("b".to_string(), &"y".to_string())]);
+
+    // without skipping it, the header is a word whose value doesn't parse
+    let error = Frequencies::from_tsv(text.as_bytes(), &TsvOptions::frequencies().delimiter(',')).unwrap_err();
+    assert!(matches!(error, DawgError::Line { line: 1, .. }), "{error}");
+}
+
+#[test]
+fn bad_values_stop_or_are_skipped_by_the_policy() {
+    let text = "a\t1\nb\tx\nc\t3\nd\n";
+    let error = Frequencies::from_tsv(text.as_bytes(), &TsvOptions::frequencies()).unwrap_err();
+    assert!(matches!(&error, DawgError::Line { line: 2, error, .. } if matches!(**error, DawgError::InvalidValue(_))), "{error}");
+
+    let (map, report) = Frequencies::from_tsv(text.as_bytes(), &TsvOptions::frequencies().value_policy(ValuePolicy::Skip)).unwrap();
+    checked(map.dawg());
+    assert_eq!((report.words, report.invalid_values), (2, 2));
+    assert_eq!(map.iter().map(|(word, weight)| (word, *weight)).collect::<Vec<_>>(), [("a".into(), 1), ("c".into(), 3)]);
+
+    let error = Frequencies::from_tsv("a\t1\nd\n".as_bytes(), &TsvOptions::frequencies()).unwrap_err();
+    assert!(matches!(&error, DawgError::Line { line: 2, error, .. } if matches!(**error, DawgError::MissingColumn { column: 1, columns: 1 })), "{error}");
+}
+
+#[test]
+fn a_closure_parses_the_values() {
+    let options = TsvOptions::new(|field: &str| if field.len() < 3 { Ok(field.len()) } else { Err("too long") });
+    let (map, _) = DawgMap::<UnsyncWrapper, usize>::from_tsv("a\tok\nb\t\n".as_bytes(), &options).unwrap();
+    assert_eq!((map.get("a"), map.get("b")), (Some(&2), Some(&0)));
+
+    let error = DawgMap::<UnsyncWrapper, usize>::from_tsv("a\tok\nb\tlong\n".as_bytes(), &options).unwrap_err();
+    assert!(error.to_string().starts_with("line 2") && error.to_string().contains("too long"), "{error}");
+}
+
+#[test]
+fn lines_go_through_the_checks_of_plain_loading() {
+    let (map, report) = Frequencies::from_tsv("# counts\na\t1\n\na\t2\nb\t3\n".as_bytes(), &TsvOptions::frequencies()).unwrap();
+    assert_eq!((report.comments, report.blank), (1, 1));
+    assert_eq!(map.iter().map(|(word, weight)| (word, *weight)).collect::<Vec<_>>(), [("a".into(), 1), ("b".into(), 3)]);
+
+    let error = Frequencies::from_tsv("b\t1\na\t2\n".as_bytes(), &TsvOptions::frequencies()).unwrap_err();
+    assert!(matches!(&error, DawgError::Line { line: 2, error, .. } if matches!(**error, DawgError::Unsorted { .. })), "{error}");
+
+    let options = TsvOptions::frequencies().dawg_options(DawgOptions::new().case_folding(true));
+    let (map, _) = Frequencies::from_tsv("Apple\t4\napple\t9\n".as_bytes(), &options).unwrap();
+    assert_eq!((map.len(), map.weight_of("APPLE")), (1, Some(4)));
+}
@@ -0,0 +1,8 @@
+/// Normalization and grapheme clusters come from two crates, whose tables must be of the same Unicode version for a
+/// word to be normalized and split consistently
+#[test]
+fn normalization_and_segmentation_share_a_unicode_version() {
+    let (major, minor, update) = unicode_normalization::UNICODE_VERSION;
+    let normalization = (major as u64, minor as u64, update as u64);
+    assert_eq!(normalization, unicode_segmentation::UNICODE_VERSION);
+}
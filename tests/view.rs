@@ -0,0 +1,80 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::collections::BTreeSet;
+
+use common::{build, checked, xorshift};
+use dawging::dawg::lexicon::{FoldProfile, Lexicon, MatchMode};
+
+/// 500 draws of words of 1 to 6 letters out of "abcA", sorted and without duplicates
+fn draw(state: &mut u64) -> Vec<String> {
+    let words: BTreeSet<String> = (0..500)
+        .map(|_| (0..1 + xorshift(state) % 6).map(|_| b"abcA"[(xorshift(state) % 4) as usize] as char).collect())
+        .collect();
+    words.into_iter().collect()
+}
+
+const QUERIES: [&str; 8] = ["", "a", "b", "A", "ab", "AB", "cc", "abc"];
+
+const MODES: [MatchMode; 3] = [MatchMode::Exact, MatchMode::AsciiInsensitive, MatchMode::UnicodeInsensitive(FoldProfile::Default)];
+
+#[test]
+fn the_view_answers_as_the_dawg_does_after_the_prefix() {
+    let mut state = 2;
+    let words = draw(&mut state);
+    let dawg = build(&words);
+
+    for prefix in ["a", "ab", "Ab", "cA", "abca"] {
+        let view = dawg.prefix_view(prefix).unwrap();
+        assert_eq!(view.prefix(), prefix);
+        let suffixes = words.iter().filter_map(|word| word.strip_prefix(prefix)).map(String::from).collect::<Vec<_>>();
+        assert_eq!(view.words().collect::<Vec<_>>(), suffixes);
+        assert_eq!(view.word_count(), suffixes.len());
+
+        for query in QUERIES {
+            let full = format!("{prefix}{query}");
+            assert_eq!(view.contains(query, MatchMode::Exact), dawg.contains(&full), "{prefix} {query}");
+            assert_eq!(view.contains_prefix(query, MatchMode::Exact), dawg.starts_with(&full), "{prefix} {query}");
+
+            for mode in MODES {
+                // only what follows the prefix is compared with the mode, the prefix itself is matched exactly
+                let expected = suffixes.iter().filter(|suffix| suffix.chars().count() == query.chars().count()
+                    && suffix.chars().zip(query.chars()).all(|(a, b)| a == b || mode.matches(a, b)));
+                assert_eq!(view.contains(query, mode), expected.count() > 0, "{prefix} {query} {mode:?}");
+            }
+
+            let completions = Lexicon::completions(&dawg, &full, MatchMode::Exact);
+            let suffixes = completions.iter().map(|word| word[prefix.len()..].to_string()).collect::<Vec<_>>();
+            assert_eq!(view.completions(query, MatchMode::Exact), suffixes);
+        }
+    }
+}
+
+#[test]
+fn a_view_of_full_words_keeps_the_prefix() {
+    let mut state = 8;
+    let dawg = build(&draw(&mut state));
+
+    for prefix in ["a", "bA", "c"] {
+        let view = dawg.prefix_view(prefix).unwrap().full_words(true);
+        let expected = dawg.words_with_prefix(prefix).collect::<Vec<_>>();
+        assert_eq!(view.words().collect::<Vec<_>>(), expected);
+        assert_eq!(view.completions("", MatchMode::Exact), expected);
+
+        let copy = view.to_dawg();
+        checked(&copy);
+        copy.assert_minimal().unwrap();
+        assert_eq!(copy.words().collect::<Vec<_>>(), expected);
+    }
+}
+
+#[test]
+fn a_prefix_no_word_starts_with_has_no_view() {
+    let dawg = build(&["bat", "bats", "cat"]);
+    assert!(dawg.prefix_view("z").is_none());
+    assert!(dawg.prefix_view("batsy").is_none());
+    assert!(dawg.prefix_view("BAT").is_none());
+    assert!(dawg.prefix_view("bats").unwrap().contains("", MatchMode::Exact));
+    assert_eq!(dawg.prefix_view("").unwrap().word_count(), 3);
+}
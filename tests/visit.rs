@@ -0,0 +1,261 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{collections::HashSet, ops::ControlFlow};
+
+use common::{build, words};
+use dawging::{dawg::visit::{Descend, NodeView}, UnsyncDawg};
+
+const WORDS: [&str; 10] = ["bat", "bats", "cat", "cats", "hat", "hats", "tap", "taps", "top", "tops"];
+
+/// Spells every word below `id` after `prefix`, walking the views by id
+fn spell(views: &[NodeView], id: usize, prefix: String, words: &mut Vec<String>) {
+    if views[id].is_terminal() {
+        words.push(prefix.clone());
+    }
+    for (label, child) in views[id].edges() {
+        spell(views, child, format!("{prefix}{label}"), words);
+    }
+}
+
+/// `words_with_prefix` through the visitor: down along the prefix, then every word below it
+fn with_prefix(dawg: &UnsyncDawg, prefix: &str) -> Vec<String> {
+    let len = prefix.chars().count();
+    let mut found = vec![];
+    let _ = dawg.visit_words(|spelled, node| {
+        if node.depth() < len {
+            return ControlFlow::Continue(if prefix.starts_with(spelled) { Descend::Continue } else { Descend::Skip });
+        }
+        if !spelled.starts_with(prefix) {
+            return ControlFlow::Continue(Descend::Skip);
+        }
+        if node.is_terminal() {
+            found.push(spelled.to_string());
+        }
+        ControlFlow::Continue(Descend::Continue)
+    });
+    found
+}
+
+/// The words of `len` letters through the visitor, along with the number of nodes visited
+fn of_length(dawg: &UnsyncDawg, len: usize) -> (Vec<String>, usize) {
+    let (mut found, mut visited) = (vec![], 0);
+    let _ = dawg.visit_words(|spelled, node| {
+        visited += 1;
+        assert_eq!(node.depth(), spelled.chars().count());
+        if node.depth() < len {
+            return ControlFlow::Continue(Descend::Continue);
+        }
+        if node.is_terminal() {
+            found.push(spelled.to_string());
+        }
+        ControlFlow::Continue(Descend::Skip)
+    });
+    (found, visited)
+}
+
+#[test]
+fn children_come_before_their_parents() {
+    let dawg = build(&words());
+    let mut yielded = HashSet::new();
+    let views = dawg.visit_nodes().collect::<Vec<_>>();
+
+    for view in &views {
+        assert!(view.edges().all(|(_, child)| yielded.contains(&child)), "node {} before its children", view.id());
+        yielded.insert(view.id());
+    }
+    assert!(views.last().unwrap().is_root());
+    assert_eq!(views.last().unwrap().id(), 0);
+    assert_eq!(yielded.len(), views.len());
+}
+
+#[test]
+fn the_language_is_rebuilt_from_the_views() {
+    let dawg = build(&WORDS);
+    let mut views = dawg.visit_nodes().collect::<Vec<_>>();
+    views.sort_by_key(|view| view.id());
+    assert!(views.iter().enumerate().all(|(id, view)| view.id() == id));
+    assert_eq!(views[0].count(), WORDS.len());
+
+    let mut spelled = vec![];
+    spell(&views, 0, String::new(), &mut spelled);
+    assert_eq!(spelled, WORDS);
+}
+
+#[test]
+fn repeated_traversals_agree() {
+    let dawg = build(&WORDS);
+    assert_eq!(dawg.visit_nodes().collect::<Vec<_>>(), dawg.visit_nodes().collect::<Vec<_>>());
+    assert_eq!(dawg.visit_nodes().collect::<Vec<_>>(), build(&WORDS).visit_nodes().collect::<Vec<_>>());
+}
+
+#[test]
+fn ids_are_numbered_breadth_first() {
+    let dawg = build(&words());
+    let views = dawg.visit_nodes_bfs().collect::<Vec<_>>();
+    assert!(views[0].is_root());
+    assert!(views.iter().enumerate().all(|(id, view)| view.id() == id));
+
+    // every node is numbered when first reached from the nodes before it
+    let mut next = 1;
+    let mut numbered = HashSet::from([0]);
+    for view in &views {
+        for (_, child) in view.edges() {
+            if numbered.insert(child) {
+                assert_eq!(child, next);
+                next += 1;
+            }
+        }
+    }
+    assert_eq!(next, views.len());
+
+    // the ids of the views are those of the nodes, edge by edge along the words
+    for word in words().iter().step_by(37) {
+        let mut node = dawg.root();
+        for letter in word.chars() {
+            let child = node.child(&letter).unwrap();
+            assert!(views[node.id()].edges().any(|(label, id)| *label == letter && id == child.id()));
+            node = child;
+        }
+        assert_eq!(views[node.id()].count(), node.reachable_words());
+    }
+}
+
+#[test]
+fn the_ids_survive_a_round_trip() {
+    let dawg = build(&words());
+    let loaded = dawging::UnsyncDawg::<char>::from_bytes(&dawg.to_bytes().unwrap()).unwrap();
+    assert_eq!(loaded.to_bytes().unwrap(), dawg.to_bytes().unwrap());
+    assert!(loaded.visit_nodes().eq(dawg.visit_nodes()));
+}
+
+#[test]
+fn minimality_is_checked_bottom_up() {
+    let dawg = build(&WORDS);
+    assert!(dawg.verify_minimal());
+
+    let mut edited = dawg.fork();
+    edited.insert("dats").unwrap();
+    assert!(!edited.verify_minimal());
+}
+
+#[test]
+fn every_view_counts_the_words_below_it() {
+    let dawg = build(&WORDS);
+    let mut views = dawg.visit_nodes().collect::<Vec<_>>();
+    views.sort_by_key(|view| view.id());
+
+    for view in &views {
+        let mut spelled = vec![];
+        spell(&views, view.id(), String::new(), &mut spelled);
+        assert_eq!(view.count(), spelled.len());
+    }
+    assert_eq!(views.len(), dawg.compression_stats().dawg_nodes);
+    assert_eq!(views.iter().map(|view| view.edges().count()).sum::<usize>(), dawg.compression_stats().dawg_edges);
+}
+
+#[test]
+fn an_empty_dawg_yields_its_root_alone() {
+    let dawg = build::<&str>(&[]);
+    let views = dawg.visit_nodes().collect::<Vec<_>>();
+    assert_eq!(views.len(), 1);
+    assert!(views[0].is_root() && !views[0].is_terminal());
+    assert_eq!((views[0].count(), views[0].edges().count()), (0, 0));
+}
+
+#[test]
+fn an_unfinished_dawg_is_visited_as_it_is() {
+    let mut dawg = dawging::UnsyncDawg::new();
+    dawg.add_all(["ab", "ac"]).unwrap();
+    common::checked(&dawg);
+
+    let mut views = dawg.visit_nodes().collect::<Vec<_>>();
+    views.sort_by_key(|view| view.id());
+    let mut spelled = vec![];
+    spell(&views, 0, String::new(), &mut spelled);
+    assert_eq!(spelled, ["ab", "ac"]);
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn the_sync_dawg_is_visited_the_same() {
+    let mut dawg = dawging::SyncDawg::new_sync();
+    dawg.add_all(WORDS).unwrap();
+    dawg.finish();
+    common::checked(&dawg);
+    assert!(dawg.visit_nodes().eq(build(&WORDS).visit_nodes()));
+}
+
+#[test]
+fn canned_queries_are_rebuilt_from_the_visitor() {
+    let mut words = words();
+    words.push(String::new());
+    words.sort();
+    let dawg = build(&words);
+
+    for prefix in ["", "a", "ab", "zz", "qqq", "é", "st"] {
+        assert_eq!(with_prefix(&dawg, prefix), dawg.words_with_prefix(prefix).collect::<Vec<_>>(), "{prefix:?}");
+    }
+    for len in 0..8 {
+        assert_eq!(of_length(&dawg, len).0, words.iter().filter(|word| word.chars().count() == len).cloned().collect::<Vec<_>>());
+    }
+
+    // every node tells the words below its prefix
+    let _ = dawg.visit_words(|spelled, node| {
+        assert_eq!(node.count(), dawg.words_with_prefix(spelled).count(), "{spelled:?}");
+        assert_eq!(node.is_terminal(), words.binary_search(&spelled.to_string()).is_ok());
+        ControlFlow::Continue(if node.depth() < 2 { Descend::Continue } else { Descend::Skip })
+    });
+}
+
+#[test]
+fn skipped_nodes_are_not_visited() {
+    let dawg = build(&words());
+    let mut total = 0;
+    assert_eq!(dawg.visit_words(|_, _| {
+        total += 1;
+        ControlFlow::Continue(Descend::Continue)
+    }), ControlFlow::Continue(()));
+
+    // a node per prefix of every word, and no other
+    let prefixes = words().iter().flat_map(|word| (0..=word.chars().count()).map(|len| word.chars().take(len).collect::<String>()).collect::<Vec<_>>()).collect::<HashSet<_>>();
+    assert_eq!(total, prefixes.len());
+    let (_, visited) = of_length(&dawg, 2);
+    assert_eq!(visited, prefixes.iter().filter(|prefix| prefix.chars().count() <= 2).count());
+    assert!(visited * 10 < total, "{visited} of {total}");
+
+    let mut visited = 0;
+    let _ = dawg.visit_words(|_, _| {
+        visited += 1;
+        ControlFlow::Continue(Descend::Skip)
+    });
+    assert_eq!(visited, 1);
+}
+
+#[test]
+fn breaking_stops_the_traversal_at_once() {
+    let mut dawg = build(&words());
+    for stop in [1, 2, 50] {
+        let mut visited = 0;
+        let flow = dawg.visit_words(|_, _| {
+            visited += 1;
+            if visited == stop { ControlFlow::Break(()) } else { ControlFlow::Continue(Descend::Continue) }
+        });
+        assert_eq!((flow, visited), (ControlFlow::Break(()), stop));
+    }
+
+    // edits show in the next traversal
+    dawg.insert("zzzzz").unwrap();
+    dawg.remove(&words()[0]).unwrap();
+    common::checked(&dawg);
+    let mut last = String::new();
+    let _ = dawg.visit_words(|spelled, node| {
+        if node.is_terminal() {
+            last = spelled.to_string();
+        }
+        ControlFlow::Continue(Descend::Continue)
+    });
+    assert_eq!(last, dawg.words().last().unwrap());
+    assert_eq!(with_prefix(&dawg, &words()[0]), dawg.words_with_prefix(&words()[0]).collect::<Vec<_>>());
+}
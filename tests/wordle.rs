@@ -0,0 +1,122 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, fixture, xorshift};
+use dawging::{dawg::wordle::{Feedback, WordleConstraints}, DawgError};
+
+/// `fixtures/five.txt`: about 1500 words of 5 letters, sorted
+fn five() -> Vec<String> {
+    std::fs::read_to_string(fixture("five.txt")).unwrap().lines().map(String::from).collect()
+}
+
+/// The colours Wordle gives `guess` when the word is `answer`: greens first, then yellows from left to right while the
+/// answer has letters left that weren't matched
+fn score(guess: &str, answer: &str) -> Vec<Feedback> {
+    let guess = guess.chars().collect::<Vec<_>>();
+    let mut left = answer.chars().map(Some).collect::<Vec<_>>();
+    let mut colours = vec![Feedback::Gray; guess.len()];
+
+    for (position, letter) in guess.iter().enumerate() {
+        if left[position] == Some(*letter) {
+            colours[position] = Feedback::Green;
+            left[position] = None;
+        }
+    }
+    for (position, letter) in guess.iter().enumerate() {
+        if colours[position] == Feedback::Gray {
+            if let Some(unmatched) = left.iter().position(|other| *other == Some(*letter)) {
+                colours[position] = Feedback::Yellow;
+                left[unmatched] = None;
+            }
+        }
+    }
+    colours
+}
+
+/// The words of `words` that would give every guess the colours it got
+fn brute_force<'w>(words: &'w [String], guesses: &[(&str, Vec<Feedback>)]) -> Vec<&'w String> {
+    words.iter().filter(|word| guesses.iter().all(|(guess, colours)| score(guess, word) == *colours)).collect()
+}
+
+#[test]
+fn random_games_leave_the_words_of_brute_force() {
+    let words = five();
+    let dawg = build(&words);
+    let mut state = 11;
+
+    for _ in 0..300 {
+        let answer = &words[xorshift(&mut state) as usize % words.len()];
+        let guesses = (0..1 + xorshift(&mut state) % 3).map(|_| {
+            let guess = words[xorshift(&mut state) as usize % words.len()].as_str();
+            (guess, score(guess, answer))
+        }).collect::<Vec<_>>();
+
+        let mut constraints = WordleConstraints::new(5);
+        for (guess, colours) in &guesses {
+            constraints.add_guess(guess, colours).unwrap();
+        }
+        let found = dawg.wordle_filter(&constraints).collect::<Vec<_>>();
+        assert!(found.contains(answer));
+        assert_eq!(found.iter().collect::<Vec<_>>(), brute_force(&words, &guesses), "{answer}: {guesses:?}");
+    }
+}
+
+#[test]
+fn duplicate_letters_follow_wordle() {
+    let words = five();
+    let dawg = build(&words);
+
+    // a yellow and a gray "e": the word has exactly one, neither first nor last
+    let guesses = [("eerie", Feedback::parse("y.gg.").unwrap())];
+    let mut constraints = WordleConstraints::new(5);
+    constraints.add_guess("eerie", &guesses[0].1).unwrap();
+    let found = dawg.wordle_filter(&constraints).collect::<Vec<_>>();
+    assert_eq!(found.iter().collect::<Vec<_>>(), brute_force(&words, &guesses));
+    assert!(found.iter().all(|word| word.matches('e').count() == 1 && !word.starts_with('e') && !word.ends_with('e') && &word[2..4] == "ri"));
+
+    // a green and a gray "s": the word has exactly one, where the green is
+    let small = build(&["sassy", "saucy", "sissy", "spicy", "truss"]);
+    let mut constraints = WordleConstraints::new(5);
+    constraints.add_guess("sissy", &Feedback::parse("g..._").unwrap()).unwrap();
+    assert!(small.wordle_filter(&constraints).next().is_none());
+    let mut constraints = WordleConstraints::new(5);
+    constraints.add_guess("sassy", &Feedback::parse("gg..g").unwrap()).unwrap();
+    assert_eq!(small.wordle_filter(&constraints).collect::<Vec<_>>(), ["saucy"]);
+    assert_eq!(score("sassy", "saucy"), Feedback::parse("gg..g").unwrap());
+
+    // two yellows: at least two of the letter
+    let mut constraints = WordleConstraints::new(5);
+    constraints.add_guess("ssabc", &Feedback::parse("yy...").unwrap()).unwrap();
+    assert_eq!(small.wordle_filter(&constraints).collect::<Vec<_>>(), ["truss"]);
+}
+
+#[test]
+fn constraints_can_be_set_one_by_one() {
+    let words = five();
+    let dawg = build(&words);
+    let matching = |constraints: &WordleConstraints, check: &dyn Fn(&str) -> bool| {
+        let expected = words.iter().filter(|word| check(word)).cloned().collect::<Vec<_>>();
+        assert_eq!(dawg.wordle_filter(constraints).collect::<Vec<_>>(), expected);
+    };
+
+    matching(&WordleConstraints::new(5).max_count('e', 0).green(0, 's'), &|word| !word.contains('e') && word.starts_with('s'));
+    matching(&WordleConstraints::new(5).min_count('a', 2), &|word| word.matches('a').count() >= 2);
+    matching(&WordleConstraints::new(5).min_count('a', 1).max_count('a', 1).not_at(0, 'a'), &|word| word.matches('a').count() == 1 && !word.starts_with('a'));
+    matching(&WordleConstraints::new(5), &|_| true);
+    assert!(dawg.wordle_filter(&WordleConstraints::new(4)).next().is_none());
+    assert!(dawg.wordle_filter(&WordleConstraints::new(5).min_count('a', 6)).next().is_none());
+    assert_eq!(WordleConstraints::new(5).len(), 5);
+    assert!(WordleConstraints::new(0).is_empty());
+}
+
+#[test]
+fn malformed_guesses_are_refused() {
+    assert_eq!(Feedback::parse("GY.-_bx").unwrap(), [Feedback::Green, Feedback::Yellow, Feedback::Gray, Feedback::Gray, Feedback::Gray, Feedback::Gray, Feedback::Gray]);
+    assert!(matches!(Feedback::parse("gyq"), Err(DawgError::InvalidValue(_))));
+
+    let mut constraints = WordleConstraints::new(5);
+    assert!(matches!(constraints.add_guess("abc", &Feedback::parse("...").unwrap()), Err(DawgError::InvalidValue(_))));
+    assert!(matches!(constraints.add_guess("abcde", &[]), Err(DawgError::InvalidValue(_))));
+    assert_eq!(constraints, WordleConstraints::new(5));
+}
@@ -0,0 +1,83 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use common::{build, checked, fixture};
+use dawging::{dawg::{options::DawgOptions, wordlist::{LoadReport, Utf8Policy}}, DawgError, UnsyncDawg};
+
+/// `fixtures/messy.txt`: a BOM, CRLF and LF endings, comments, blank lines, a line of invalid utf-8 (line 7, "blue"
+/// followed by a stray byte) and no line ending after the last word
+fn messy() -> Vec<u8> {
+    std::fs::read(fixture("messy.txt")).unwrap()
+}
+
+fn load(bytes: &[u8], options: DawgOptions, utf8: Utf8Policy) -> Result<(UnsyncDawg, LoadReport), DawgError> {
+    UnsyncDawg::from_reader(bytes, options, utf8)
+}
+
+#[test]
+fn skipping_bad_lines_builds_the_clean_words() {
+    let (dawg, report) = load(&messy(), DawgOptions::new(), Utf8Policy::Skip).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.to_bytes().unwrap(), build(&["apple", "banana", "cherry", "date"]).to_bytes().unwrap());
+    assert_eq!(report, LoadReport { lines: 9, words: 4, comments: 2, blank: 2, invalid_utf8: 1, ..LoadReport::default() });
+    assert_eq!(report.skipped(), 5);
+}
+
+#[test]
+fn invalid_utf8_stops_on_its_line() {
+    let error = load(&messy(), DawgOptions::new(), Utf8Policy::Error).unwrap_err();
+    assert!(matches!(&error, DawgError::Line { line: 7, snippet, error } if snippet == "blue\u{FFFD}" && matches!(**error, DawgError::InvalidUtf8)), "{error:?}");
+    assert!(error.to_string().starts_with("line 7 (\"blue\u{FFFD}\")"));
+}
+
+#[test]
+fn lossy_lines_are_added_with_replacement_characters() {
+    let (dawg, report) = load(&messy(), DawgOptions::new(), Utf8Policy::Lossy).unwrap();
+    checked(&dawg);
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["apple", "banana", "blue\u{FFFD}", "cherry", "date"]);
+    assert_eq!((report.words, report.lossy, report.invalid_utf8), (5, 1, 0));
+}
+
+#[test]
+fn errors_give_the_number_of_their_line() {
+    let error = load(b"# header\napple\n\ncherry\nbanana\n", DawgOptions::new(), Utf8Policy::Error).unwrap_err();
+    assert!(matches!(&error, DawgError::Line { line: 5, snippet, error } if snippet == "banana" && matches!(**error, DawgError::Unsorted { .. })), "{error:?}");
+
+    let options = DawgOptions::new().alphabet("abc");
+    let error = load(b"ab\r\nabd\r\n", options, Utf8Policy::Error).unwrap_err();
+    assert!(matches!(&error, DawgError::Line { line: 2, error, .. } if matches!(**error, DawgError::InvalidCharacter { character: 'd', .. })), "{error:?}");
+}
+
+#[test]
+fn long_lines_are_quoted_by_their_start() {
+    let long = format!("b\n{}\n", "a".repeat(100));
+    let error = load(long.as_bytes(), DawgOptions::new(), Utf8Policy::Error).unwrap_err();
+    let DawgError::Line { line: 2, snippet, .. } = error else { panic!("{error:?}") };
+    assert_eq!(snippet, format!("{}…", "a".repeat(40)));
+}
+
+#[test]
+fn a_bom_is_only_dropped_from_the_first_line() {
+    let (dawg, _) = load(b"\xef\xbb\xbfa\nb\n", DawgOptions::new(), Utf8Policy::Error).unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["a", "b"]);
+
+    let (dawg, _) = load(b"a\n\xef\xbb\xbfb\n", DawgOptions::new(), Utf8Policy::Error).unwrap();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["a", "\u{FEFF}b"]);
+}
+
+#[test]
+fn words_can_be_read_into_a_dawg_being_built() {
+    let mut dawg = UnsyncDawg::new();
+    dawg.try_add("a").unwrap();
+    let report = dawg.read_words(&b"b\nc\n"[..], Utf8Policy::Error).unwrap();
+    checked(&dawg);
+    assert_eq!((report.lines, report.words), (2, 2));
+    dawg.try_add("d").unwrap();
+    dawg.finish();
+    assert_eq!(dawg.words().collect::<Vec<_>>(), ["a", "b", "c", "d"]);
+
+    let (empty, report) = load(b"", DawgOptions::new(), Utf8Policy::Error).unwrap();
+    assert_eq!(report, LoadReport::default());
+    assert_eq!(empty.words().next(), None);
+}
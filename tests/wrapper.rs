@@ -0,0 +1,181 @@
+#![cfg(feature = "unsync")]
+
+mod common;
+
+use std::{cell::RefCell, rc::Rc};
+
+use common::{build, checked, words};
+use dawging::{Dawg, DawgNode, NodeRef, UnsyncNode, Wrapper};
+
+/// Keeps every node it creates in a vector the caller holds on to, as an arena would
+#[derive(Clone)]
+struct Arena {
+    nodes: Rc<RefCell<Vec<UnsyncNode<char>>>>,
+}
+
+impl Wrapper for Arena {
+    type Node = UnsyncNode<char>;
+
+    fn new() -> Self {
+        Arena { nodes: Rc::default() }
+    }
+
+    fn create(&mut self) -> Self::Node {
+        let node = UnsyncNode::new(DawgNode::new(self.nodes.borrow().len()));
+        self.nodes.borrow_mut().push(node.clone());
+        node
+    }
+}
+
+/// Counts the nodes it creates, numbering them from 1
+#[derive(Clone)]
+struct Counting(usize);
+
+impl Wrapper for Counting {
+    type Node = UnsyncNode<char>;
+
+    fn new() -> Self {
+        Counting(0)
+    }
+
+    fn create(&mut self) -> Self::Node {
+        self.0 += 1;
+        UnsyncNode::new(DawgNode::new(self.0))
+    }
+}
+
+/// Builds a finished dawg of `words` allocated by `wrapper`, checking the invariants after every word
+fn build_in<T: Wrapper>(wrapper: T, words: &[String]) -> Dawg<T> {
+    let mut dawg = Dawg::with_wrapper(wrapper);
+    for (index, word) in words.iter().enumerate() {
+        dawg.try_add(word).unwrap();
+        if index % 100 == 0 {
+            checked(&dawg);
+        }
+    }
+    dawg.finish();
+    dawg.assert_minimal().unwrap();
+    dawg
+}
+
+#[test]
+fn an_arena_builds_the_same_dawg() {
+    let words = words();
+    let arena = Arena::new();
+    let nodes = arena.nodes.clone();
+    let dawg = build_in(arena, &words);
+
+    assert_eq!(dawg.to_bytes().unwrap(), build(&words).to_bytes().unwrap());
+    // the arena keeps the nodes that were merged away as well
+    assert!(nodes.borrow().len() >= dawg.compression_stats().dawg_nodes);
+    assert!(Rc::ptr_eq(&dawg.wrapper().nodes, &nodes));
+}
+
+#[test]
+fn a_counting_wrapper_sees_every_node_created() {
+    let words = words();
+    let dawg = build_in(Counting::new(), &words);
+    let stats = dawg.compression_stats();
+
+    assert_eq!(dawg.to_bytes().unwrap(), build(&words).to_bytes().unwrap());
+    // a node per letter added along a new path, the root included
+    assert!(dawg.wrapper().0 >= stats.dawg_nodes);
+    assert!(dawg.wrapper().0 <= stats.trie_nodes + 1);
+}
+
+#[test]
+fn a_custom_wrapper_is_cloned_and_edited_like_the_default_one() {
+    let dawg = build_in(Counting::new(), &["bat", "bats", "cat"].map(String::from));
+    let mut fork = dawg.fork();
+    fork.insert("ca").unwrap();
+    checked(&fork);
+    fork.remove("bats").unwrap();
+    checked(&fork);
+
+    assert_eq!(fork.words().collect::<Vec<_>>(), ["bat", "ca", "cat"]);
+    assert_eq!(dawg.clone().to_bytes().unwrap(), build(&["bat", "bats", "cat"]).to_bytes().unwrap());
+}
+
+#[test]
+fn retain_keeps_allocating_from_the_same_wrapper() {
+    let words = words();
+    let arena = Arena::new();
+    let nodes = arena.nodes.clone();
+    let mut dawg = build_in(arena, &words);
+    let (created, counting) = (nodes.borrow().len(), build_in(Counting::new(), &words).wrapper().0);
+
+    let kept = words.iter().filter(|word| word.len() % 2 == 0).cloned().collect::<Vec<_>>();
+    dawg.retain(|word| word.len() % 2 == 0);
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+
+    assert_eq!(dawg.to_bytes().unwrap(), build(&kept).to_bytes().unwrap());
+    // the rebuilt dawg went on allocating from the arena the caller holds
+    assert!(Rc::ptr_eq(&dawg.wrapper().nodes, &nodes));
+    assert!(nodes.borrow().len() > created);
+
+    let mut counted = build_in(Counting::new(), &words);
+    counted.retain(|word| word.len() % 2 == 0);
+    assert!(counted.wrapper().0 > counting, "the count started over");
+}
+
+#[test]
+#[cfg(feature = "sync")]
+fn a_custom_wrapper_can_make_sync_nodes() {
+    #[derive(Clone)]
+    struct SyncCounting(usize);
+
+    impl Wrapper for SyncCounting {
+        type Node = dawging::SyncNode<char>;
+
+        fn new() -> Self {
+            SyncCounting(0)
+        }
+
+        fn create(&mut self) -> Self::Node {
+            self.0 += 1;
+            dawging::SyncNode::new(DawgNode::new(self.0))
+        }
+    }
+
+    let words = words();
+    let dawg = build_in(SyncCounting::new(), &words);
+    assert_eq!(dawg.to_bytes().unwrap(), build(&words).to_bytes().unwrap());
+    assert!(dawg.wrapper().0 > 0);
+}
+
+#[test]
+fn the_words_being_added_share_a_single_leaf() {
+    let words = words();
+    let dawg = build_in(Counting::new(), &words);
+
+    // letters added along a new path, of which the last of each word ends on the shared leaf rather than a node of its own
+    let mut letters = 0;
+    for pair in std::iter::once(["", &words[0]]).chain(words.windows(2).map(|pair| [&pair[0][..], &pair[1][..]])) {
+        let shared = pair[0].chars().zip(pair[1].chars()).take_while(|(a, b)| a == b).count();
+        letters += pair[1].chars().count() - shared;
+    }
+    // a word the next one goes on from gives its leaf back and ends on a node of its own
+    let extended = words.windows(2).filter(|pair| pair[1].starts_with(&pair[0])).count();
+
+    assert_eq!(dawg.wrapper().0, 1 + (letters - words.len()) + 1 + extended);
+    assert_eq!(dawg.to_bytes().unwrap(), build(&words).to_bytes().unwrap());
+}
+
+#[test]
+fn a_rollback_keeps_the_shared_leaf_apart() {
+    let mut dawg = Dawg::with_wrapper(Counting::new());
+    dawg.add_all(["bat", "bats", "cat"]).unwrap();
+    let checkpoint = dawg.checkpoint();
+    dawg.try_add("cats").unwrap();
+    dawg.try_add("dog").unwrap();
+    dawg.rollback(checkpoint).unwrap();
+    checked(&dawg);
+
+    dawg.try_add("catsup").unwrap();
+    checked(&dawg);
+    dawg.finish();
+    checked(&dawg);
+    dawg.assert_minimal().unwrap();
+    assert_eq!(dawg.to_bytes().unwrap(), build(&["bat", "bats", "cat", "catsup"]).to_bytes().unwrap());
+}